@@ -55,6 +55,40 @@ export interface SavingsAccountZone {
     createdAt: number;
 }
 
+/** Interest accrued on a savings account or term deposit since the last capitalization */
+export interface InterestAccrual {
+    accountId: string;
+    accountName: string;
+    currency: string;
+    effectiveRate: number;
+    /** Gross interest; the withholding tax and net interest follow */
+    accruedInterest: string;
+    accruedTax: string;
+    accruedNet: string;
+    lastCapitalization: number;
+    nextCapitalization: number | null;
+    /** End of the current term of a term deposit (rolled over when it renews) */
+    maturityDate: number | null;
+    /** Principal and net interest paid out or renewed at maturity */
+    maturityPayout: string | null;
+    projectedYearlyInterest: string;
+}
+
+/** A projected interest credit or term deposit payout */
+export interface InterestPayment {
+    accountId: string;
+    accountName: string;
+    date: number;
+    kind: 'interest' | 'maturity';
+    grossInterest: string;
+    tax: string;
+    netInterest: string;
+    /** Net interest, or principal and net interest at maturity */
+    amount: string;
+    currency: string;
+    amountCzk: number;
+}
+
 // Stock Investments
 export interface StockInvestment {
     id: string;
@@ -77,6 +111,8 @@ export interface InvestmentTransaction {
     currency: string;
     transactionDate: number;
     createdAt: number;
+    /** Brokerage account holding the position (null = unassigned) */
+    accountId?: string | null;
 }
 
 // Crypto
@@ -99,9 +135,32 @@ export interface CryptoTransaction {
     pricePerUnit: string;
     currency: string;
     transactionDate: number;
+    /** Wallet or exchange holding the coins */
+    wallet?: string | null;
     createdAt: number;
 }
 
+/** Crypto transaction types; staking/airdrop/interest/mining are income */
+export type CryptoTransactionType =
+    | 'buy'
+    | 'sell'
+    | 'staking'
+    | 'airdrop'
+    | 'interest'
+    | 'mining'
+    | 'gift'
+    | 'transfer_in'
+    | 'transfer_out';
+
+export interface CryptoWalletHolding {
+    ticker: string;
+    wallet: string | null;
+    quantity: number;
+}
+
+/** How crypto income enters the cost basis */
+export type CryptoIncomeCostBasis = 'fair_value' | 'zero';
+
 // Bonds
 export interface Bond {
     id: string;
@@ -112,10 +171,64 @@ export interface Bond {
     currency: string;
     interestRate: string;
     maturityDate: number | null;
+    couponFrequency: BondCouponFrequency;
+    dayCount: BondDayCount;
+    issueDate: number | null;
+    firstCouponDate: number | null;
+    /** Clean prices in percent of face value */
+    purchasePrice: string | null;
+    purchaseDate: number | null;
+    marketPrice: string | null;
+    /** 'manual' or the price provider that reported the market price */
+    marketPriceSource: string | null;
+    marketPriceUpdatedAt: number | null;
+    /** Ticker or ISIN the price providers quote the bond under */
+    priceTicker: string | null;
     createdAt: number;
     updatedAt: number;
 }
 
+export type BondCouponFrequency = 'annual' | 'semiannual' | 'quarterly' | 'monthly';
+export type BondDayCount = '30/360' | 'act/360' | 'act/365' | 'act/act';
+
+/** A scheduled coupon or principal repayment (amount for the whole holding) */
+export interface BondCashFlow {
+    bondId: string;
+    bondName: string;
+    date: number;
+    kind: 'coupon' | 'principal';
+    amount: string;
+    currency: string;
+    amountCzk: number;
+}
+
+/** Price, yield and duration of a bond holding */
+export interface BondAnalytics {
+    bondId: string;
+    /** Percent of face value */
+    cleanPrice: string;
+    dirtyPrice: string;
+    accruedInterest: string;
+    /** Dirty value of the holding in the bond currency */
+    marketValue: string;
+    marketValueCzk: number;
+    purchaseCost: string | null;
+    /** Percent */
+    currentYield: number;
+    /** Effective annual yield to maturity in percent, comparable to a savings rate */
+    yieldToMaturity: number | null;
+    macaulayDuration: number | null;
+    modifiedDuration: number | null;
+}
+
+export interface BondSchedule {
+    bondId: string;
+    /** Interest accrued since the last coupon date, in the bond currency */
+    accruedInterest: string;
+    nextCouponDate: number | null;
+    cashFlows: BondCashFlow[];
+}
+
 // Loans
 export interface Loan {
     id: string;
@@ -127,10 +240,77 @@ export interface Loan {
     monthlyPayment: string;
     startDate: number;
     endDate: number | null;
+    repaymentType: LoanRepaymentType;
+    /** Counterparty IBAN of installments in bank transactions */
+    paymentIban: string | null;
+    /** Variable symbol of installments in bank transactions */
+    paymentVariableSymbol: string | null;
     createdAt: number;
     updatedAt: number;
 }
 
+export type LoanRepaymentType = 'annuity' | 'linear' | 'interest_only';
+
+/** One monthly installment (amounts in the loan currency) */
+export interface LoanInstallment {
+    date: number;
+    payment: string;
+    interest: string;
+    principal: string;
+    /** Outstanding principal after the installment */
+    balance: string;
+    /** 'paid' = matched in bank transactions, 'assumed' = past, paid as scheduled */
+    status: 'paid' | 'assumed' | 'upcoming';
+}
+
+export interface LoanSchedule {
+    loanId: string;
+    outstandingPrincipal: string;
+    outstandingPrincipalCzk: number;
+    paidPrincipal: string;
+    paidInterest: string;
+    remainingInterest: string;
+    payoffDate: number | null;
+    matchedPayments: number;
+    installments: LoanInstallment[];
+}
+
+export type LoanPrepaymentStrategy = 'shorten_term' | 'lower_payment';
+
+/** What-if scenario: prepayments and/or a new rate after the fixation ends */
+export interface LoanScenario {
+    name: string;
+    oneOffPrepayments: { date: number; amount: string }[];
+    monthlyPrepayment?: string | null;
+    strategy?: LoanPrepaymentStrategy | null;
+    /** Annual rate in percent from the end of the current fixation */
+    refixationRate?: string | null;
+    /** Percent of the outstanding principal that can be prepaid yearly without a fee */
+    feeFreePercent?: string | null;
+    prepaymentFeePercent?: string | null;
+}
+
+export interface LoanScenarioResult {
+    name: string;
+    monthlyPayment: string;
+    paymentAfterRefixation: string | null;
+    totalInterest: string;
+    interestSaved: string;
+    totalPrepaid: string;
+    fees: string;
+    payoffDate: number | null;
+    installments: number;
+}
+
+export interface LoanSimulation {
+    loanId: string;
+    currency: string;
+    outstandingPrincipal: string;
+    refixationDate: number | null;
+    baseline: LoanScenarioResult;
+    scenarios: LoanScenarioResult[];
+}
+
 // Real Estate
 export interface RecurringCost {
     name: string;
@@ -146,6 +326,7 @@ export interface RealEstate {
     type: string;
     purchasePrice: string;
     purchasePriceCurrency: string;
+    purchaseDate: number | null;
     marketPrice: string;
     marketPriceCurrency: string;
     monthlyRent: string | null;
@@ -173,6 +354,75 @@ export interface RealEstateOneTimeCost {
     createdAt: number;
 }
 
+// Real Estate Valuations
+export type ValuationSource = 'appraisal' | 'index' | 'listing' | 'manual';
+
+export interface RealEstateValuation {
+    id: string;
+    realEstateId: string;
+    valuationDate: number;
+    value: string;
+    currency: string;
+    source: ValuationSource;
+    notes: string | null;
+    createdAt: number;
+}
+
+export interface RealEstateValuePoint {
+    date: number;
+    value: number;
+    loanBalance: number;
+    equity: number;
+}
+
+export interface RealEstateValueHistory {
+    realEstateId: string;
+    currency: string;
+    purchasePrice: string;
+    purchaseDate: number | null;
+    currentValue: string;
+    loanBalance: string;
+    equity: string;
+    appreciation: string;
+    appreciationPercent: number | null;
+    annualizedAppreciationPercent: number | null;
+    points: RealEstateValuePoint[];
+}
+
+// Rental Performance
+/** Linked transactions, property terms, or linked transactions with terms filling the gaps */
+export type PerformanceSource = 'actual' | 'estimated' | 'mixed';
+
+export interface RentalPerformance {
+    realEstateId: string;
+    name: string;
+    currency: string;
+    annualRent: string;
+    rentSource: PerformanceSource;
+    /** Recurring costs and insurance premiums, plus linked debits */
+    operatingCosts: string;
+    costsSource: PerformanceSource;
+    netOperatingIncome: string;
+    debtService: string;
+    debtSource: PerformanceSource;
+    annualCashFlow: string;
+    totalCost: string;
+    cashInvested: string;
+    currentValue: string;
+    equity: string;
+    /** Over the holding period: linked transactions where there are any, the terms for the rest */
+    cumulativeCashFlow: string;
+    yearsHeld: number;
+    grossYieldPercent: number | null;
+    netYieldPercent: number | null;
+    capRatePercent: number | null;
+    cashOnCashPercent: number | null;
+    totalRoiPercent: number | null;
+    breakEvenMonthlyRent: string;
+    breakEvenOccupancyPercent: number | null;
+    paybackYears: number | null;
+}
+
 // Real Estate Photos
 export interface RealEstatePhotoBatch {
     id: string;
@@ -280,8 +530,12 @@ export interface PortfolioMetrics {
     totalLiabilities: number;
     totalAssets: number;
     netWorth: number;
+    /** Stock holdings split by brokerage account */
+    accountValues: AccountValue[];
 }
 
+// Amounts are stored in CZK; get_portfolio_history returns them in the
+// reporting currency at the rate of recordedAt.
 export interface PortfolioMetricsHistory {
     id: string;
     totalSavings: string;
@@ -295,6 +549,40 @@ export interface PortfolioMetricsHistory {
     recordedAt: number;
 }
 
+/** Grouping of an exposure breakdown */
+export type ExposureDimension = 'sector' | 'industry' | 'country' | 'currency';
+
+export interface ExposureItem {
+    /** Sector/industry name, ISO country code, currency code or 'unknown' */
+    key: string;
+    /** Set for buckets holding a whole non-stock asset class */
+    assetClass: string | null;
+    value: number;
+    percent: number;
+    holdingsCount: number;
+}
+
+export interface ExposureReport {
+    dimension: ExposureDimension;
+    stocksOnly: boolean;
+    totalValue: number;
+    items: ExposureItem[];
+}
+
+/** Currency a ticker's holdings are denominated in, e.g. USD for a US equity ETF listed in EUR */
+export interface UnderlyingCurrency {
+    ticker: string;
+    currency: string;
+    updatedAt: number;
+}
+
+/** Stock exposure on one day of the per-ticker value history */
+export interface ExposureHistoryPoint {
+    date: number;
+    totalValue: number;
+    items: ExposureItem[];
+}
+
 // Zod Schemas for form validation
 export const setupSchema = z.object({
     name: z.string().min(1, "validation.nameRequired"),
@@ -336,6 +624,14 @@ export const insertBondSchema = z.object({
     quantity: z.string().optional(),
     interestRate: z.string().optional(),
     maturityDate: z.date().or(z.number()).optional(),
+    couponFrequency: z.enum(['annual', 'semiannual', 'quarterly', 'monthly']).optional(),
+    dayCount: z.enum(['30/360', 'act/360', 'act/365', 'act/act']).optional(),
+    issueDate: z.number().nullish(),
+    firstCouponDate: z.number().nullish(),
+    purchasePrice: z.string().nullish(),
+    purchaseDate: z.number().nullish(),
+    marketPrice: z.string().nullish(),
+    priceTicker: z.string().nullish(),
 });
 export type InsertBond = z.infer<typeof insertBondSchema>;
 
@@ -348,6 +644,9 @@ export const insertLoanSchema = z.object({
     monthlyPayment: z.string().optional(),
     startDate: z.date().or(z.number()).optional(),
     endDate: z.date().or(z.number()).optional(),
+    repaymentType: z.enum(['annuity', 'linear', 'interest_only']).optional(),
+    paymentIban: z.string().nullish(),
+    paymentVariableSymbol: z.string().nullish(),
 });
 
 // Type aliases for Insert types
@@ -384,6 +683,7 @@ export const insertRealEstateSchema = z.object({
     type: z.string().min(1),
     purchasePrice: z.string().optional(),
     purchasePriceCurrency: z.string().optional(),
+    purchaseDate: z.number().optional().nullable(),
     marketPrice: z.string().optional(),
     marketPriceCurrency: z.string().optional(),
     monthlyRent: z.string().optional().nullable(),
@@ -409,6 +709,16 @@ export const insertRealEstateOneTimeCostSchema = z.object({
 });
 export type InsertRealEstateOneTimeCost = z.infer<typeof insertRealEstateOneTimeCostSchema>;
 
+export const insertRealEstateValuationSchema = z.object({
+    realEstateId: z.string(),
+    valuationDate: z.date().or(z.number()),
+    value: z.string().min(1),
+    currency: z.string().optional(),
+    source: z.enum(['appraisal', 'index', 'listing', 'manual']).optional(),
+    notes: z.string().optional(),
+});
+export type InsertRealEstateValuation = z.infer<typeof insertRealEstateValuationSchema>;
+
 // Other Assets schemas
 export const insertOtherAssetSchema = z.object({
     name: z.string().min(1),
@@ -544,6 +854,7 @@ export interface CashflowReport {
     viewType: 'monthly' | 'yearly';
     personal: CashflowSection;
     investments: CashflowSection;
+    termDepositMaturities: CashflowCategory; // principal returned, not counted as income
     totalIncome: number;
     totalExpenses: number;
     netCashflow: number;
@@ -591,7 +902,8 @@ export interface CalculatedDefaults {
 }
 
 // Bank Accounts
-export type AccountType = 'checking' | 'savings' | 'credit_card' | 'investment';
+export type AccountType = 'checking' | 'savings' | 'credit_card' | 'investment' | 'term_deposit';
+export type InterestCapitalization = 'monthly' | 'annual' | 'maturity';
 export type DataSource = 'manual' | 'csv_import' | 'api_sync';
 export type TransactionType = 'credit' | 'debit';
 export type TransactionStatus = 'booked' | 'pending';
@@ -619,7 +931,14 @@ export interface BankAccount {
     lastSyncedAt: number | null;
     interestRate: string | null;
     hasZoneDesignation: boolean;
+    /** Maturity of a term deposit */
     terminationDate: number | null;
+    /** Interest accrues from this date (defaults to when the account was added) */
+    depositStartDate: number | null;
+    autoRenew: boolean;
+    capitalization: InterestCapitalization;
+    /** Withholding tax on interest in percent */
+    interestTaxRate: string;
     /** Exclude from portfolio balance (for operational/checking accounts) */
     excludeFromBalance: boolean;
     createdAt: number;
@@ -643,6 +962,10 @@ export interface InsertBankAccount {
     interestRate?: string | null;
     hasZoneDesignation?: boolean;
     terminationDate?: number | null;
+    depositStartDate?: number | null;
+    autoRenew?: boolean;
+    capitalization?: InterestCapitalization;
+    interestTaxRate?: string;
     /** Exclude from portfolio balance (for operational/checking accounts) */
     excludeFromBalance?: boolean;
 }
@@ -876,6 +1199,59 @@ export interface TwrSeries {
     data: TwrDataPoint[];
 }
 
+/** Grouping level of a money-weighted return calculation */
+export type XirrScope = 'holding' | 'tag' | 'asset_class' | 'portfolio';
+
+/** Money-weighted return (XIRR) of one holding, tag, asset class or the portfolio */
+export interface XirrResult {
+    scope: XirrScope;
+    /** Reporting currency all amounts are in */
+    currency: string;
+    /** Ticker, tag ID, asset class ("stocks" / "crypto") or "portfolio" */
+    id: string;
+    name: string;
+    /** Asset class of a holding, null for other scopes */
+    assetClass: string | null;
+    /** Annualized return in percent, null when it cannot be solved */
+    xirr: number | null;
+    startValue: number;
+    endValue: number;
+    invested: number;
+    withdrawn: number;
+    gain: number;
+    cashFlowCount: number;
+}
+
+export type RiskScope = 'holding' | 'tag' | 'asset_class' | 'portfolio';
+
+/** Risk metrics from flow-adjusted daily returns in CZK */
+export interface RiskMetrics {
+    scope: RiskScope;
+    /** Ticker, tag ID, asset class ("stocks" / "crypto") or "portfolio" */
+    id: string;
+    name: string;
+    assetClass: string | null;
+    observations: number;
+    startDate: number | null;
+    endDate: number | null;
+    /** Annualized return in percent */
+    annualizedReturn: number | null;
+    /** Annualized volatility in percent */
+    volatility: number | null;
+    /** Largest peak-to-trough decline in percent (negative) */
+    maxDrawdown: number | null;
+    drawdownPeakDate: number | null;
+    drawdownTroughDate: number | null;
+    /** null while the drawdown has not recovered */
+    drawdownRecoveryDate: number | null;
+    sharpeRatio: number | null;
+    sortinoRatio: number | null;
+    beta: number | null;
+    benchmark: string | null;
+    /** Annual risk-free rate in percent */
+    riskFreeRate: number;
+}
+
 export interface StockInvestmentWithTags {
     id: string;
     ticker: string;
@@ -900,3 +1276,391 @@ export interface TagMetrics {
     portfolioPercent: number;
     holdingsCount: number;
 }
+
+// ============================================================================
+// Benchmark Types
+// ============================================================================
+
+export interface Benchmark {
+    /** Yahoo ticker, e.g. "VWCE.DE", "^GSPC" */
+    ticker: string;
+    name: string;
+    currency: string;
+    createdAt: number;
+    priceCount: number;
+    firstDate: number | null;
+    lastDate: number | null;
+}
+
+export interface InsertBenchmark {
+    ticker: string;
+    name: string;
+    currency?: string | null;
+}
+
+export interface BenchmarkPoint {
+    /** "YYYY-MM-DD" */
+    date: string;
+    /** Cumulative TWR in percent */
+    portfolioTwr: number;
+    /** Cumulative benchmark price return in percent (reporting currency) */
+    benchmarkReturn: number | null;
+}
+
+/** "What if every contribution had gone into the benchmark" (reporting currency) */
+export interface BenchmarkSimulation {
+    startValue: number;
+    invested: number;
+    withdrawn: number;
+    actualValue: number;
+    benchmarkValue: number;
+    /** actualValue - benchmarkValue */
+    difference: number;
+    cashFlowCount: number;
+}
+
+export interface BenchmarkComparison {
+    benchmark: string;
+    /** Reporting currency returns and simulation amounts are measured in */
+    currency: string;
+    /** null = whole stock portfolio */
+    tagId: string | null;
+    fromTs: number;
+    toTs: number;
+    portfolioReturn: number;
+    benchmarkReturn: number | null;
+    excessReturn: number | null;
+    series: BenchmarkPoint[];
+    simulation: BenchmarkSimulation | null;
+}
+
+// ============================================================================
+// Price Provider Types
+// ============================================================================
+
+export type PriceProviderId = 'yahoo' | 'coingecko' | 'stooq' | 'alpha_vantage' | 'local_file';
+
+export type PriceAssetKind = 'stock' | 'crypto';
+
+export interface PriceProviderSettings {
+    /** Providers tried in order for stocks */
+    stockOrder: PriceProviderId[];
+    /** Providers tried in order for crypto */
+    cryptoOrder: PriceProviderId[];
+    /** CSV/JSON file read by the local file provider */
+    localFilePath: string | null;
+}
+
+/** Provider tried first for one ticker, before the fallback order */
+export interface PriceProviderPreference {
+    ticker: string;
+    assetKind: PriceAssetKind;
+    provider: PriceProviderId;
+    updatedAt: number;
+}
+
+export interface InsertPriceProviderPreference {
+    ticker: string;
+    assetKind: PriceAssetKind;
+    provider: PriceProviderId;
+}
+
+export type TickerCurrencySource = 'provider' | 'manual';
+
+/** Stored quote currency of a stock ticker, trusted over the ticker-suffix guess */
+export interface TickerCurrency {
+    ticker: string;
+    currency: string;
+    source: TickerCurrencySource;
+    updatedAt: number;
+}
+
+// ============================================================================
+// Allocation Types
+// ============================================================================
+
+/** 'tag' = percent of the stock portfolio, 'asset_class' = percent of total assets */
+export type AllocationKind = 'tag' | 'asset_class';
+
+export type AllocationAssetClass =
+    | 'savings'
+    | 'stocks'
+    | 'bonds'
+    | 'crypto'
+    | 'real_estate'
+    | 'other_assets';
+
+export interface AllocationTarget {
+    id: string;
+    kind: AllocationKind;
+    /** Tag ID or asset class name */
+    targetKey: string;
+    targetPercent: string;
+    /** Allowed deviation in percentage points */
+    tolerancePercent: string;
+    updatedAt: number;
+}
+
+export interface InsertAllocationTarget {
+    kind: AllocationKind;
+    targetKey: string;
+    targetPercent: string;
+    tolerancePercent?: string | null;
+}
+
+export type DriftStatus = 'no_target' | 'within' | 'over' | 'under';
+
+export interface AllocationDrift {
+    key: string;
+    name: string;
+    currentValue: number;
+    currentPercent: number;
+    targetPercent: number | null;
+    tolerancePercent: number | null;
+    /** Current minus target, in percentage points */
+    driftPercent: number | null;
+    /** Value needed to reach the target (positive = buy) */
+    driftValue: number | null;
+    status: DriftStatus;
+}
+
+export interface AllocationReport {
+    kind: AllocationKind;
+    totalValue: number;
+    targetsTotalPercent: number;
+    needsRebalancing: boolean;
+    items: AllocationDrift[];
+}
+
+export interface RebalanceTrade {
+    key: string;
+    name: string;
+    currentValue: number;
+    targetValue: number;
+    /** Positive = buy, negative = sell */
+    amount: number;
+    resultingPercent: number;
+}
+
+export interface RebalancePlan {
+    kind: AllocationKind;
+    cashOnly: boolean;
+    contribution: number;
+    totalValueAfter: number;
+    unallocatedCash: number;
+    trades: RebalanceTrade[];
+}
+
+// ============================================================================
+// Savings Goal Types
+// ============================================================================
+
+/** 'bank_account' covers checking and savings accounts and term deposits */
+export type GoalLinkKind = 'bank_account' | 'bond' | 'tag';
+
+export interface GoalLink {
+    kind: GoalLinkKind;
+    /** Bank account, bond or stock tag ID */
+    linkId: string;
+}
+
+export interface SavingsGoal {
+    id: string;
+    name: string;
+    targetAmount: string;
+    currency: string;
+    targetDate: number;
+    /** Expected yearly return of the linked holdings, percent */
+    expectedReturn: string;
+    /** Planned monthly contribution in the goal currency */
+    monthlyContribution: string;
+    links: GoalLink[];
+    createdAt: number;
+    updatedAt: number;
+}
+
+export interface InsertSavingsGoal {
+    name: string;
+    targetAmount: string;
+    currency?: string | null;
+    targetDate: number;
+    expectedReturn?: string | null;
+    monthlyContribution?: string | null;
+    links?: GoalLink[];
+}
+
+export type GoalStatus = 'achieved' | 'on_track' | 'behind' | 'overdue';
+
+export interface GoalLinkValue {
+    kind: GoalLinkKind;
+    linkId: string;
+    name: string;
+    /** Current value in the goal currency */
+    value: string;
+}
+
+/** Amounts are in the goal currency */
+export interface SavingsGoalProgress {
+    goal: SavingsGoal;
+    currentValue: string;
+    progressPercent: number;
+    remaining: string;
+    monthsLeft: number;
+    /** Monthly contribution reaching the target by the date at the expected return */
+    requiredMonthly: string;
+    /** Value at the target date with the planned contribution */
+    projectedValue: string;
+    status: GoalStatus;
+    links: GoalLinkValue[];
+}
+
+// ============================================================================
+// Dividend Types
+// ============================================================================
+
+export interface ReceivedDividend {
+    id: string;
+    ticker: string;
+    paymentDate: number;
+    grossAmount: string;
+    withholdingTax: string;
+    currency: string;
+    /** ISO 3166-1 alpha-2 code of the source country */
+    sourceCountry: string;
+    createdAt: number;
+}
+
+export interface InsertReceivedDividend {
+    ticker: string;
+    paymentDate: number;
+    grossAmount: string;
+    withholdingTax?: string | null;
+    currency: string;
+    sourceCountry: string;
+}
+
+export interface DividendTaxCountry {
+    country: string;
+    treatyRate: number;
+    dividendCount: number;
+    grossCzk: number;
+    withheldCzk: number;
+    creditableCzk: number;
+    nonCreditableCzk: number;
+}
+
+export interface DividendTaxReport {
+    year: number;
+    totalGrossCzk: number;
+    totalWithheldCzk: number;
+    totalCreditableCzk: number;
+    totalNonCreditableCzk: number;
+    countries: DividendTaxCountry[];
+}
+
+// ============================================================================
+// Brokerage Account Types
+// ============================================================================
+
+export type BrokerageAccountType = 'taxable' | 'pension' | 'other';
+
+export interface BrokerageAccount {
+    id: string;
+    name: string;
+    broker: string | null;
+    accountType: BrokerageAccountType;
+    currency: string;
+    createdAt: number;
+}
+
+export interface InsertBrokerageAccount {
+    name: string;
+    broker?: string | null;
+    accountType?: BrokerageAccountType;
+    currency?: string;
+}
+
+/** Position of one ticker within one brokerage account */
+export interface AccountPosition {
+    /** null = transactions not assigned to any account */
+    accountId: string | null;
+    investmentId: string;
+    ticker: string;
+    companyName: string;
+    quantity: string;
+    averagePrice: string;
+    currency: string;
+    currentValueCzk: number;
+    costBasisCzk: number;
+}
+
+export interface AccountValue {
+    /** null = transactions not assigned to any account */
+    accountId: string | null;
+    name: string | null;
+    accountType: BrokerageAccountType | null;
+    totalValue: number;
+    totalCost: number;
+    positionsCount: number;
+}
+
+// ============================================================================
+// Broker Import Types
+// ============================================================================
+
+export type Broker = 'degiro' | 'trading212' | 'ibkr' | 'xtb';
+
+export interface BrokerImportResult {
+    tradesImported: number;
+    dividendsImported: number;
+    feesImported: number;
+    fxConversionsImported: number;
+    /** Rows already imported earlier (matched by broker order/transaction ID) */
+    duplicatesSkipped: number;
+    imported: string[];
+    errors: string[];
+}
+
+// ============================================================================
+// Crypto Exchange Import Types
+// ============================================================================
+
+export type Exchange = 'binance' | 'coinbase' | 'kraken';
+
+export interface CryptoImportResult {
+    tradesImported: number;
+    incomeImported: number;
+    feesImported: number;
+    /** Crypto deposits/withdrawals, stored as transfer_in/transfer_out */
+    transfersImported: number;
+    duplicatesSkipped: number;
+    imported: string[];
+    errors: string[];
+}
+
+// ============================================================================
+// Exchange Rate Types
+// ============================================================================
+
+/** 'cnb_annual' = uniform annual rate (jednotný kurz), for tax reports only */
+export type FxSource = 'ecb' | 'cnb_daily' | 'cnb_annual';
+
+export interface FxSettings {
+    /** Rates for portfolio values and history */
+    valuationSource: FxSource;
+    /** Rates for tax reports */
+    taxSource: FxSource;
+}
+
+/** CZK rate for a currency neither the ECB nor the ČNB publishes */
+export interface ManualExchangeRate {
+    currency: string;
+    /** CZK per unit */
+    rate: number;
+    updatedAt: number;
+}
+
+export interface InsertManualExchangeRate {
+    currency: string;
+    rate: number;
+}
@@ -19,6 +19,11 @@ pub fn collect_types() -> TypeCollection {
     types.register::<crate::models::UpdateUserProfile>();
     types.register::<crate::models::InsertUserProfile>();
     types.register::<crate::models::PortfolioMetricsHistory>();
+    types.register::<crate::models::ExposureDimension>();
+    types.register::<crate::models::ExposureItem>();
+    types.register::<crate::models::ExposureReport>();
+    types.register::<crate::models::ExposureHistoryPoint>();
+    types.register::<crate::models::UnderlyingCurrency>();
 
     // Investment models
     types.register::<crate::models::StockInvestment>();
@@ -31,6 +36,20 @@ pub fn collect_types() -> TypeCollection {
     types.register::<crate::models::TickerValueHistory>();
     types.register::<crate::models::TwrDataPoint>();
     types.register::<crate::models::TwrSeries>();
+    types.register::<crate::models::XirrScope>();
+    types.register::<crate::models::XirrResult>();
+    types.register::<crate::models::RiskScope>();
+    types.register::<crate::models::RiskMetrics>();
+
+    // Dividend models
+    types.register::<crate::models::ReceivedDividend>();
+    types.register::<crate::models::InsertReceivedDividend>();
+    types.register::<crate::models::DividendTaxCountry>();
+    types.register::<crate::models::DividendTaxReport>();
+
+    // Broker import types
+    types.register::<crate::services::broker_import::Broker>();
+    types.register::<crate::services::broker_import::BrokerImportResult>();
 
     // Crypto models
     types.register::<crate::models::CryptoInvestment>();
@@ -38,19 +57,36 @@ pub fn collect_types() -> TypeCollection {
     types.register::<crate::models::InsertCryptoInvestment>();
     types.register::<crate::models::CryptoTransaction>();
     types.register::<crate::models::InsertCryptoTransaction>();
+    types.register::<crate::models::CryptoWalletHolding>();
+    types.register::<crate::models::CryptoIncomeCostBasis>();
+
+    // Crypto exchange import types
+    types.register::<crate::services::crypto_exchange_import::Exchange>();
+    types.register::<crate::services::crypto_exchange_import::CryptoImportResult>();
 
     // Savings models
     types.register::<crate::models::SavingsAccount>();
     types.register::<crate::models::InsertSavingsAccount>();
     types.register::<crate::models::SavingsAccountZone>();
+    types.register::<crate::models::InterestAccrual>();
+    types.register::<crate::models::InterestPayment>();
 
     // Bond models
     types.register::<crate::models::Bond>();
     types.register::<crate::models::InsertBond>();
+    types.register::<crate::models::BondCashFlow>();
+    types.register::<crate::models::BondSchedule>();
+    types.register::<crate::models::BondAnalytics>();
 
     // Loan models
     types.register::<crate::models::Loan>();
     types.register::<crate::models::InsertLoan>();
+    types.register::<crate::models::LoanInstallment>();
+    types.register::<crate::models::LoanSchedule>();
+    types.register::<crate::models::LoanPrepayment>();
+    types.register::<crate::models::LoanScenario>();
+    types.register::<crate::models::LoanScenarioResult>();
+    types.register::<crate::models::LoanSimulation>();
 
     // Real estate models
     types.register::<crate::models::RealEstate>();
@@ -60,6 +96,12 @@ pub fn collect_types() -> TypeCollection {
     types.register::<crate::models::RealEstatePhotoBatch>();
     types.register::<crate::models::RealEstatePhoto>();
     types.register::<crate::models::RealEstateDocument>();
+    types.register::<crate::models::RealEstateValuation>();
+    types.register::<crate::models::InsertRealEstateValuation>();
+    types.register::<crate::models::RealEstateValuePoint>();
+    types.register::<crate::models::RealEstateValueHistory>();
+    types.register::<crate::models::PerformanceSource>();
+    types.register::<crate::models::RentalPerformance>();
 
     // Insurance models
     types.register::<crate::models::InsurancePolicy>();
@@ -91,12 +133,50 @@ pub fn collect_types() -> TypeCollection {
     types.register::<crate::models::TransactionFilters>();
     types.register::<crate::models::TransactionQueryResult>();
 
+    // Brokerage account models
+    types.register::<crate::models::BrokerageAccount>();
+    types.register::<crate::models::InsertBrokerageAccount>();
+    types.register::<crate::models::AccountPosition>();
+    types.register::<crate::models::AccountValue>();
+
     // Stock tags models
     types.register::<crate::models::StockTag>();
     types.register::<crate::models::InsertStockTag>();
     types.register::<crate::models::StockTagGroup>();
     types.register::<crate::models::InsertStockTagGroup>();
 
+    // Benchmark models
+    types.register::<crate::models::Benchmark>();
+    types.register::<crate::models::InsertBenchmark>();
+    types.register::<crate::models::BenchmarkPoint>();
+    types.register::<crate::models::BenchmarkSimulation>();
+    types.register::<crate::models::BenchmarkComparison>();
+
+    // Price provider models
+    types.register::<crate::models::PriceProviderId>();
+    types.register::<crate::models::PriceAssetKind>();
+    types.register::<crate::models::PriceProviderSettings>();
+    types.register::<crate::models::PriceProviderPreference>();
+    types.register::<crate::models::InsertPriceProviderPreference>();
+
+    // Allocation models
+    types.register::<crate::models::AllocationKind>();
+    types.register::<crate::models::AllocationTarget>();
+    types.register::<crate::models::InsertAllocationTarget>();
+    types.register::<crate::models::DriftStatus>();
+    types.register::<crate::models::AllocationDrift>();
+    types.register::<crate::models::AllocationReport>();
+    types.register::<crate::models::RebalanceTrade>();
+    types.register::<crate::models::RebalancePlan>();
+
+    // Savings goal models
+    types.register::<crate::models::GoalLink>();
+    types.register::<crate::models::SavingsGoal>();
+    types.register::<crate::models::InsertSavingsGoal>();
+    types.register::<crate::models::GoalStatus>();
+    types.register::<crate::models::GoalLinkValue>();
+    types.register::<crate::models::SavingsGoalProgress>();
+
     // Cashflow models
     types.register::<crate::models::CashflowItem>();
     types.register::<crate::models::InsertCashflowItem>();
@@ -0,0 +1,108 @@
+//! Target allocation and rebalancing commands
+
+use crate::commands::portfolio::calculate_portfolio_metrics;
+use crate::commands::stock_tags::{get_stocks_analysis, get_tag_metrics};
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{
+    AllocationKind, AllocationReport, AllocationTarget, InsertAllocationTarget, RebalancePlan,
+};
+use crate::services::allocation::{self as allocation_service, AllocationGroup};
+use tauri::State;
+
+/// Get allocation targets, optionally only of one kind
+#[tauri::command]
+pub async fn get_allocation_targets(
+    db: State<'_, Database>,
+    kind: Option<AllocationKind>,
+) -> Result<Vec<AllocationTarget>> {
+    db.with_conn(|conn| allocation_service::get_targets(conn, kind))
+}
+
+/// Set the target weight of a tag or asset class
+#[tauri::command]
+pub async fn set_allocation_target(
+    db: State<'_, Database>,
+    data: InsertAllocationTarget,
+) -> Result<AllocationTarget> {
+    data.validate()?;
+    db.with_conn(|conn| allocation_service::set_target(conn, &data))
+}
+
+/// Delete an allocation target
+#[tauri::command]
+pub async fn delete_allocation_target(db: State<'_, Database>, id: String) -> Result<()> {
+    db.with_conn(|conn| allocation_service::delete_target(conn, &id))
+}
+
+/// Get current vs. target weights per tag or asset class
+#[tauri::command]
+pub async fn get_allocation_drift(
+    db: State<'_, Database>,
+    kind: AllocationKind,
+    exclude_personal_real_estate: bool,
+) -> Result<AllocationReport> {
+    build_allocation_report(&db, kind, exclude_personal_real_estate).await
+}
+
+/// Propose buy/sell amounts restoring target weights.
+/// With `cash_only` only the planned contribution is invested and nothing is sold.
+#[tauri::command]
+pub async fn get_rebalancing_plan(
+    db: State<'_, Database>,
+    kind: AllocationKind,
+    contribution: Option<f64>,
+    cash_only: bool,
+    exclude_personal_real_estate: bool,
+) -> Result<RebalancePlan> {
+    let report = build_allocation_report(&db, kind, exclude_personal_real_estate).await?;
+    Ok(allocation_service::plan_rebalance(
+        &report,
+        contribution.unwrap_or(0.0),
+        cash_only,
+    ))
+}
+
+async fn build_allocation_report(
+    db: &State<'_, Database>,
+    kind: AllocationKind,
+    exclude_personal_real_estate: bool,
+) -> Result<AllocationReport> {
+    let (groups, total) = match kind {
+        AllocationKind::Tag => {
+            let stocks = get_stocks_analysis(db.clone()).await?;
+            let total: f64 = stocks.iter().map(|s| s.current_value).sum();
+            let groups: Vec<AllocationGroup> = get_tag_metrics(db.clone(), Vec::new())
+                .await?
+                .into_iter()
+                .map(|m| AllocationGroup {
+                    key: m.tag.id,
+                    name: m.tag.name,
+                    value: m.total_value,
+                })
+                .collect();
+            (groups, total)
+        }
+        AllocationKind::AssetClass => {
+            let metrics = calculate_portfolio_metrics(db, exclude_personal_real_estate)?;
+            let groups: Vec<AllocationGroup> = [
+                ("savings", metrics.total_savings),
+                ("stocks", metrics.total_investments),
+                ("bonds", metrics.total_bonds),
+                ("crypto", metrics.total_crypto),
+                ("real_estate", metrics.total_real_estate),
+                ("other_assets", metrics.total_other_assets),
+            ]
+            .into_iter()
+            .map(|(key, value)| AllocationGroup {
+                key: key.to_string(),
+                name: key.to_string(),
+                value,
+            })
+            .collect();
+            (groups, metrics.total_assets)
+        }
+    };
+
+    db.with_conn(|conn| allocation_service::build_report(conn, kind, &groups, total))
+}
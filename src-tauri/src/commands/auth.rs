@@ -319,7 +319,12 @@ pub async fn update_user_profile(
     db: State<'_, Database>,
     updates: UpdateUserProfile,
 ) -> Result<UserProfile> {
-    auth::update_user_profile(&db, updates)
+    let currency_changed = updates.currency.is_some();
+    let profile = auth::update_user_profile(&db, updates)?;
+    if currency_changed {
+        crate::services::reporting::ensure_reporting_history(&db).await?;
+    }
+    Ok(profile)
 }
 
 /// Phase 1: Prepare password change - verifies current password, generates new recovery key
@@ -396,7 +401,12 @@ pub async fn set_mcp_server_enabled(
         coingecko_modal_dismissed: None,
         mcp_server_enabled: Some(enabled),
     };
-    auth::update_user_profile(&db, updates)
+    let currency_changed = updates.currency.is_some();
+    let profile = auth::update_user_profile(&db, updates)?;
+    if currency_changed {
+        crate::services::reporting::ensure_reporting_history(&db).await?;
+    }
+    Ok(profile)
 }
 
 /// Get current MCP server status (running, port, data_dir)
@@ -30,7 +30,8 @@ pub async fn get_all_bank_accounts(
                 ba.institution_id, ba.external_account_id, ba.data_source, ba.last_synced_at,
                 ba.interest_rate, ba.has_zone_designation, ba.termination_date,
                 ba.created_at, ba.updated_at,
-                i.id, i.name, i.bic, i.country, i.logo_url, i.created_at
+                i.id, i.name, i.bic, i.country, i.logo_url, i.created_at,
+                ba.deposit_start_date, ba.auto_renew, ba.capitalization, ba.interest_tax_rate
             FROM bank_accounts ba
             LEFT JOIN institutions i ON ba.institution_id = i.id
             ORDER BY ba.name ASC",
@@ -53,6 +54,10 @@ pub async fn get_all_bank_accounts(
                     interest_rate: row.get(11)?,
                     has_zone_designation: row.get::<_, i32>(12)? != 0,
                     termination_date: row.get(13)?,
+                    deposit_start_date: row.get(22)?,
+                    auto_renew: row.get::<_, i32>(23)? != 0,
+                    capitalization: row.get(24)?,
+                    interest_tax_rate: row.get(25)?,
                     created_at: row.get(14)?,
                     updated_at: row.get(15)?,
                 };
@@ -98,7 +103,8 @@ pub async fn get_bank_account(
                 ba.institution_id, ba.external_account_id, ba.data_source, ba.last_synced_at,
                 ba.interest_rate, ba.has_zone_designation, ba.termination_date,
                 ba.created_at, ba.updated_at,
-                i.id, i.name, i.bic, i.country, i.logo_url, i.created_at
+                i.id, i.name, i.bic, i.country, i.logo_url, i.created_at,
+                ba.deposit_start_date, ba.auto_renew, ba.capitalization, ba.interest_tax_rate
             FROM bank_accounts ba
             LEFT JOIN institutions i ON ba.institution_id = i.id
             WHERE ba.id = ?1",
@@ -120,6 +126,10 @@ pub async fn get_bank_account(
                 interest_rate: row.get(11)?,
                 has_zone_designation: row.get::<_, i32>(12)? != 0,
                 termination_date: row.get(13)?,
+                deposit_start_date: row.get(22)?,
+                auto_renew: row.get::<_, i32>(23)? != 0,
+                capitalization: row.get(24)?,
+                interest_tax_rate: row.get(25)?,
                 created_at: row.get(14)?,
                 updated_at: row.get(15)?,
             };
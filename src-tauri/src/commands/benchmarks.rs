@@ -0,0 +1,90 @@
+//! Benchmark commands
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{Benchmark, BenchmarkComparison, InsertBenchmark};
+use crate::services::benchmarks as benchmark_service;
+use tauri::State;
+
+/// Default backfill range when there are no transactions yet
+const DEFAULT_BACKFILL_DAYS: i64 = 5 * 365;
+
+/// Get all benchmarks
+#[tauri::command]
+pub async fn get_benchmarks(db: State<'_, Database>) -> Result<Vec<Benchmark>> {
+    db.with_conn(benchmark_service::get_benchmarks)
+}
+
+/// Add a benchmark ticker (prices are fetched by `backfill_benchmark_history`)
+#[tauri::command]
+pub async fn add_benchmark(db: State<'_, Database>, data: InsertBenchmark) -> Result<Benchmark> {
+    data.validate()?;
+    db.with_conn(|conn| benchmark_service::add_benchmark(conn, &data))
+}
+
+/// Delete a benchmark and its price history
+#[tauri::command]
+pub async fn delete_benchmark(db: State<'_, Database>, ticker: String) -> Result<()> {
+    db.with_conn(|conn| benchmark_service::delete_benchmark(conn, &ticker))
+}
+
+/// Fetch daily benchmark prices through the price provider chain.
+/// Without `from_ts` the range starts at the first stock transaction.
+/// Returns the number of stored prices.
+#[tauri::command]
+pub async fn backfill_benchmark_history(
+    db: State<'_, Database>,
+    ticker: String,
+    from_ts: Option<i64>,
+) -> Result<i32> {
+    let now = chrono::Utc::now().timestamp();
+    let benchmark = db.with_conn(|conn| benchmark_service::get_benchmark(conn, &ticker))?;
+    let start = match from_ts {
+        Some(ts) => ts,
+        None => db
+            .with_conn(|conn| {
+                Ok(conn
+                    .query_row(
+                        "SELECT MIN(transaction_date) FROM investment_transactions",
+                        [],
+                        |row| row.get::<_, Option<i64>>(0),
+                    )
+                    .ok()
+                    .flatten())
+            })?
+            .unwrap_or(now - DEFAULT_BACKFILL_DAYS * 86400),
+    };
+
+    let history = crate::services::price_api::get_historical_stock_prices(
+        &db,
+        std::slice::from_ref(&benchmark.ticker),
+        start,
+        now,
+    )
+    .await?;
+    let prices: Vec<(i64, f64)> = history
+        .get(&benchmark.ticker)
+        .map(|prices| prices.iter().map(|p| (p.timestamp, p.price)).collect())
+        .unwrap_or_default();
+
+    let stored =
+        db.with_conn(|conn| benchmark_service::store_prices(conn, &benchmark.ticker, &prices))?;
+    Ok(stored as i32)
+}
+
+/// Compare the stock portfolio, or one tag, with a benchmark.
+///
+/// `from_ts` / `to_ts`: Unix timestamps (seconds, midnight UTC) for the date range,
+/// as for `get_stock_twr`.
+#[tauri::command]
+pub async fn compare_with_benchmark(
+    db: State<'_, Database>,
+    benchmark: String,
+    tag_id: Option<String>,
+    from_ts: i64,
+    to_ts: i64,
+) -> Result<BenchmarkComparison> {
+    db.with_conn(|conn| {
+        benchmark_service::compare(conn, &benchmark, tag_id.as_deref(), from_ts, to_ts)
+    })
+}
@@ -2,37 +2,27 @@
 
 use crate::db::Database;
 use crate::error::{AppError, Result};
-use crate::models::{Bond, InsertBond};
+use crate::models::{Bond, BondAnalytics, BondCashFlow, BondSchedule, InsertBond};
+use crate::services::bonds as bond_service;
 use tauri::State;
 use uuid::Uuid;
 
-/// Get all bonds
+/// Default calendar range when `to_ts` is not given
+const DEFAULT_CALENDAR_DAYS: i64 = 365;
+
+/// `market_price_source` of prices entered by the user
+const MANUAL_SOURCE: &str = "manual";
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
 /// Get all bonds
 #[tauri::command]
 pub async fn get_all_bonds(db: State<'_, Database>) -> Result<Vec<Bond>> {
-    db.with_conn(|conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, name, isin, coupon_value, quantity, currency, interest_rate, maturity_date, created_at, updated_at
-             FROM bonds ORDER BY name"
-        )?;
-
-        let bonds = stmt.query_map([], |row| {
-            Ok(Bond {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                isin: row.get(2)?,
-                coupon_value: row.get(3)?,
-                quantity: row.get(4)?,
-                currency: row.get(5)?,
-                interest_rate: row.get(6)?,
-                maturity_date: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        })?.filter_map(|r| r.ok()).collect();
-
-        Ok(bonds)
-    })
+    db.with_conn(bond_service::get_bonds)
 }
 
 /// Create bond
@@ -46,12 +36,29 @@ pub async fn create_bond(db: State<'_, Database>, data: InsertBond) -> Result<Bo
     let interest_rate = data.interest_rate.unwrap_or_else(|| "0".to_string());
     let currency = data.currency.unwrap_or_else(|| "CZK".to_string());
     let quantity = data.quantity.unwrap_or_else(|| "1".to_string());
+    let coupon_frequency = data
+        .coupon_frequency
+        .unwrap_or_else(|| "annual".to_string());
+    let day_count = data.day_count.unwrap_or_else(|| "30/360".to_string());
+    let purchase_price = non_empty(data.purchase_price);
+    let market_price = non_empty(data.market_price);
+    let market_price_source = market_price.as_ref().map(|_| MANUAL_SOURCE.to_string());
+    let market_price_updated_at = market_price.as_ref().map(|_| now);
+    let price_ticker = non_empty(data.price_ticker);
 
     db.with_conn(|conn| {
         conn.execute(
-            "INSERT INTO bonds (id, name, isin, coupon_value, quantity, currency, interest_rate, maturity_date, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)",
-            rusqlite::params![id, data.name, data.isin.as_deref(), data.coupon_value, quantity, currency, interest_rate, data.maturity_date, now],
+            "INSERT INTO bonds (id, name, isin, coupon_value, quantity, currency, interest_rate, maturity_date,
+                                coupon_frequency, day_count, issue_date, first_coupon_date, purchase_price, purchase_date,
+                                market_price, market_price_source, market_price_updated_at, price_ticker,
+                                created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?19)",
+            rusqlite::params![
+                id, data.name, data.isin.as_deref(), data.coupon_value, quantity, currency, interest_rate,
+                data.maturity_date, coupon_frequency, day_count, data.issue_date, data.first_coupon_date,
+                purchase_price, data.purchase_date, market_price, market_price_source, market_price_updated_at,
+                price_ticker, now
+            ],
         )?;
 
         Ok(Bond {
@@ -63,6 +70,16 @@ pub async fn create_bond(db: State<'_, Database>, data: InsertBond) -> Result<Bo
             currency,
             interest_rate,
             maturity_date: data.maturity_date,
+            coupon_frequency,
+            day_count,
+            issue_date: data.issue_date,
+            first_coupon_date: data.first_coupon_date,
+            purchase_price,
+            purchase_date: data.purchase_date,
+            market_price,
+            market_price_source,
+            market_price_updated_at,
+            price_ticker,
             created_at: now,
             updated_at: now,
         })
@@ -78,34 +95,45 @@ pub async fn update_bond(db: State<'_, Database>, id: String, data: InsertBond)
     let now = chrono::Utc::now().timestamp();
 
     db.with_conn(|conn| {
-        conn.execute(
+        let changes = conn.execute(
             "UPDATE bonds SET name = ?1, isin = ?2, coupon_value = ?3,
              quantity = COALESCE(?4, quantity), currency = COALESCE(?5, currency),
-             interest_rate = COALESCE(?6, interest_rate), maturity_date = ?7, updated_at = ?8
-             WHERE id = ?9",
+             interest_rate = COALESCE(?6, interest_rate), maturity_date = ?7,
+             coupon_frequency = COALESCE(?8, coupon_frequency), day_count = COALESCE(?9, day_count),
+             issue_date = ?10, first_coupon_date = ?11, updated_at = ?12,
+             purchase_price = ?14, purchase_date = ?15, price_ticker = ?16,
+             market_price = CASE WHEN ?17 IS NOT NULL THEN ?17
+                 WHEN market_price_source = 'manual' THEN NULL ELSE market_price END,
+             market_price_source = CASE WHEN ?17 IS NOT NULL THEN 'manual'
+                 WHEN market_price_source = 'manual' THEN NULL ELSE market_price_source END,
+             market_price_updated_at = CASE WHEN ?17 IS NOT NULL THEN ?12
+                 WHEN market_price_source = 'manual' THEN NULL ELSE market_price_updated_at END
+             WHERE id = ?13",
             rusqlite::params![
-                data.name, data.isin.as_deref(), data.coupon_value, data.quantity,
-                data.currency, data.interest_rate, data.maturity_date, now, id
+                data.name,
+                data.isin.as_deref(),
+                data.coupon_value,
+                data.quantity,
+                data.currency,
+                data.interest_rate,
+                data.maturity_date,
+                data.coupon_frequency,
+                data.day_count,
+                data.issue_date,
+                data.first_coupon_date,
+                now,
+                id,
+                non_empty(data.purchase_price),
+                data.purchase_date,
+                non_empty(data.price_ticker),
+                non_empty(data.market_price)
             ],
         )?;
+        if changes == 0 {
+            return Err(AppError::NotFound("Bond not found".into()));
+        }
 
-        conn.query_row(
-            "SELECT id, name, isin, coupon_value, quantity, currency, interest_rate, maturity_date, created_at, updated_at
-             FROM bonds WHERE id = ?1",
-            [&id],
-            |row| Ok(Bond {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                isin: row.get(2)?,
-                coupon_value: row.get(3)?,
-                quantity: row.get(4)?,
-                currency: row.get(5)?,
-                interest_rate: row.get(6)?,
-                maturity_date: row.get(7)?,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            }),
-        ).map_err(|_| AppError::NotFound("Bond not found".into()))
+        bond_service::get_bond(conn, &id)
     })
 }
 
@@ -120,3 +148,44 @@ pub async fn delete_bond(db: State<'_, Database>, id: String) -> Result<()> {
         Ok(())
     })
 }
+
+/// Coupon and principal schedule of a bond with interest accrued as of today
+#[tauri::command]
+pub async fn get_bond_schedule(db: State<'_, Database>, id: String) -> Result<BondSchedule> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        let bond = bond_service::get_bond(conn, &id)?;
+        Ok(bond_service::schedule(&bond, now))
+    })
+}
+
+/// Coupon and principal payments of all bonds between `from_ts` (default now)
+/// and `to_ts` (default a year later)
+#[tauri::command]
+pub async fn get_bond_cash_flow_calendar(
+    db: State<'_, Database>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<BondCashFlow>> {
+    let from = from_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let to = to_ts.unwrap_or(from + DEFAULT_CALENDAR_DAYS * 86400);
+    db.with_conn(|conn| bond_service::cash_flow_calendar(conn, from, to))
+}
+
+/// Price, yield to maturity and duration of every bond
+#[tauri::command]
+pub async fn get_bond_analytics(db: State<'_, Database>) -> Result<Vec<BondAnalytics>> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        Ok(bond_service::get_bonds(conn)?
+            .iter()
+            .map(|bond| bond_service::analytics(bond, now))
+            .collect())
+    })
+}
+
+/// Fetch market prices for bonds with a price ticker; returns the number updated
+#[tauri::command]
+pub async fn refresh_bond_prices(db: State<'_, Database>) -> Result<i32> {
+    bond_service::refresh_market_prices(&db).await
+}
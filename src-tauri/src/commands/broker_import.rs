@@ -0,0 +1,96 @@
+//! Broker statement import commands (Degiro, Trading212, IBKR, XTB)
+
+use crate::commands::portfolio;
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::broker_import::{self, Broker, BrokerImportResult};
+use crate::services::price_api;
+use std::collections::HashMap;
+use std::fs;
+use tauri::{AppHandle, Emitter, State};
+
+/// Import a broker statement file
+/// `account_currency` is the cash currency of the account (used by XTB, defaults to CZK)
+/// `account_id` assigns the imported trades to a brokerage account
+#[tauri::command]
+pub async fn import_broker_statement(
+    db: State<'_, Database>,
+    app: AppHandle,
+    file_path: String,
+    broker: Broker,
+    account_currency: Option<String>,
+    account_id: Option<String>,
+) -> Result<BrokerImportResult> {
+    let bytes =
+        fs::read(&file_path).map_err(|e| AppError::Internal(format!("Cannot open file: {}", e)))?;
+    let account_currency = account_currency
+        .map(|c| c.to_uppercase())
+        .unwrap_or_else(|| "CZK".to_string());
+
+    let statement = broker_import::parse_broker_file(broker, &bytes, &account_currency)?;
+
+    // Resolve ISINs: cached mappings first, then Yahoo search
+    let mut isins: Vec<String> = statement
+        .records
+        .iter()
+        .filter_map(|r| r.isin.clone())
+        .filter(|i| broker_import::is_valid_isin(i))
+        .collect();
+    isins.sort();
+    isins.dedup();
+
+    let mut isin_tickers: HashMap<String, (String, Option<String>)> = HashMap::new();
+    let mut isins_needing_lookup = Vec::new();
+    db.with_conn(|conn| {
+        for isin in &isins {
+            match broker_import::get_cached_isin_ticker(conn, isin) {
+                Some(mapping) => {
+                    isin_tickers.insert(isin.clone(), mapping);
+                }
+                None => isins_needing_lookup.push(isin.clone()),
+            }
+        }
+        Ok(())
+    })?;
+
+    for isin in isins_needing_lookup {
+        // Small delay between lookups to avoid rate limiting
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+        match price_api::search_stock_tickers(&isin).await {
+            Ok(results) => {
+                if let Some(found) = results.first() {
+                    log::info!("[BROKER IMPORT] Resolved {} to {}", isin, found.symbol);
+                    let name = Some(found.shortname.clone()).filter(|n| !n.is_empty());
+                    db.with_conn(|conn| {
+                        broker_import::save_isin_ticker(conn, &isin, &found.symbol, name.as_deref())
+                    })?;
+                    isin_tickers.insert(isin, (found.symbol.to_uppercase(), name));
+                }
+            }
+            Err(e) => log::warn!("[BROKER IMPORT] Failed to resolve {}: {}", isin, e),
+        }
+    }
+
+    let (result, ticker_earliest_dates) = db.with_conn(|conn| {
+        broker_import::import_broker_records(
+            conn,
+            broker,
+            statement,
+            &isin_tickers,
+            account_id.as_deref(),
+        )
+    })?;
+
+    // Update portfolio snapshot and history for the touched tickers
+    portfolio::update_todays_snapshot(&db).await.ok();
+    for (ticker, earliest_date) in ticker_earliest_dates.iter() {
+        portfolio::trigger_historical_recalculation_for_stock_ticker(&db, *earliest_date, ticker)
+            .await
+            .ok();
+    }
+
+    app.emit("recalculation-complete", ()).ok();
+
+    Ok(result)
+}
@@ -0,0 +1,68 @@
+//! Brokerage account commands
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{AccountPosition, AccountValue, BrokerageAccount, InsertBrokerageAccount};
+use crate::services::brokerage_accounts as account_service;
+use tauri::State;
+
+/// Get all brokerage accounts
+#[tauri::command]
+pub async fn get_brokerage_accounts(db: State<'_, Database>) -> Result<Vec<BrokerageAccount>> {
+    db.with_conn(account_service::get_all_accounts)
+}
+
+/// Create a brokerage account
+#[tauri::command]
+pub async fn create_brokerage_account(
+    db: State<'_, Database>,
+    data: InsertBrokerageAccount,
+) -> Result<BrokerageAccount> {
+    data.validate()?;
+    db.with_conn(|conn| account_service::create_account(conn, &data))
+}
+
+/// Update a brokerage account
+#[tauri::command]
+pub async fn update_brokerage_account(
+    db: State<'_, Database>,
+    id: String,
+    data: InsertBrokerageAccount,
+) -> Result<BrokerageAccount> {
+    data.validate()?;
+    db.with_conn(|conn| account_service::update_account(conn, &id, &data))
+}
+
+/// Delete a brokerage account (its transactions become unassigned)
+#[tauri::command]
+pub async fn delete_brokerage_account(db: State<'_, Database>, id: String) -> Result<()> {
+    db.with_conn(|conn| account_service::delete_account(conn, &id))
+}
+
+/// Get per-account positions, optionally for a single account
+#[tauri::command]
+pub async fn get_account_positions(
+    db: State<'_, Database>,
+    account_id: Option<String>,
+) -> Result<Vec<AccountPosition>> {
+    db.with_conn(|conn| account_service::get_account_positions(conn, account_id.as_deref()))
+}
+
+/// Get total value and cost basis per brokerage account
+#[tauri::command]
+pub async fn get_account_values(db: State<'_, Database>) -> Result<Vec<AccountValue>> {
+    db.with_conn(account_service::get_account_values)
+}
+
+/// Move a stock transaction to another account (None = unassigned)
+/// Totals per ticker do not change, so no history recalculation is needed
+#[tauri::command]
+pub async fn set_transaction_account(
+    db: State<'_, Database>,
+    tx_id: String,
+    account_id: Option<String>,
+) -> Result<()> {
+    db.with_conn(|conn| {
+        account_service::set_transaction_account(conn, &tx_id, account_id.as_deref())
+    })
+}
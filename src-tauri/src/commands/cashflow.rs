@@ -3,7 +3,10 @@
 use crate::db::Database;
 use crate::error::Result;
 use crate::models::{CashflowItem, InsertCashflowItem};
+use crate::services::bonds as bond_service;
 use crate::services::currency::convert_to_czk;
+use crate::services::interest as interest_service;
+use crate::services::money::{self, Decimal};
 use serde::Serialize;
 use std::collections::HashMap;
 use tauri::State;
@@ -56,6 +59,9 @@ pub struct CashflowReport {
     pub view_type: String, // "monthly" or "yearly"
     pub personal: CashflowSection,
     pub investments: CashflowSection,
+    /// Principal of term deposits maturing in the period, not part of the totals
+    #[serde(rename = "termDepositMaturities")]
+    pub term_deposit_maturities: CashflowCategory,
     #[serde(rename = "totalIncome")]
     pub total_income: f64,
     #[serde(rename = "totalExpenses")]
@@ -401,73 +407,24 @@ pub async fn get_cashflow_report(
         let mut investment_income_categories: Vec<CashflowCategory> = Vec::new();
         let mut investment_expense_categories: Vec<CashflowCategory> = Vec::new();
 
-        // 1. Interest Income (Savings)
+        // 1. Interest Income (savings and term deposits: net interest credited
+        // in the next year after withholding tax)
         let mut savings_items: Vec<CashflowReportItem> = Vec::new();
-        let mut savings_stmt = conn.prepare(
-            "SELECT id, name, balance, currency, interest_rate, has_zone_designation FROM bank_accounts WHERE account_type = 'savings'"
-        )?;
-        let savings_rows = savings_stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, i32>(5)?,
-            ))
-        })?;
-
-        for row in savings_rows.filter_map(|r| r.ok()) {
-            let balance: f64 = row.2.parse().unwrap_or(0.0);
-            let base_interest_rate: f64 = row.4.parse().unwrap_or(0.0);
-            let has_zones = row.5 != 0;
-
-            let effective_rate = if has_zones {
-                let zones: Vec<(f64, Option<f64>, f64)> = {
-                    let mut zone_stmt = conn.prepare(
-                        "SELECT from_amount, to_amount, interest_rate FROM bank_account_zones
-                         WHERE bank_account_id = ?1 ORDER BY from_amount ASC"
-                    )?;
-                    let rows = zone_stmt.query_map([&row.0], |zrow| {
-                        let from: f64 = zrow.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                        let to: Option<f64> = zrow.get::<_, Option<String>>(1)?.map(|s| s.parse().unwrap_or(0.0));
-                        let rate: f64 = zrow.get::<_, String>(2)?.parse().unwrap_or(0.0);
-                        Ok((from, to, rate))
-                    })?;
-                    rows.filter_map(|r| r.ok()).collect()
-                };
-
-                if zones.is_empty() {
-                    base_interest_rate
-                } else {
-                    let mut weighted_interest = 0.0;
-                    let mut remaining = balance;
-
-                    for (from_amt, to_amt, rate) in zones {
-                        if remaining <= 0.0 { break; }
-                        let zone_max = to_amt.unwrap_or(f64::MAX);
-                        let zone_size = zone_max - from_amt;
-                        let amount_in_zone = remaining.min(zone_size);
-                        if amount_in_zone > 0.0 {
-                            weighted_interest += amount_in_zone * rate / 100.0;
-                            remaining -= amount_in_zone;
-                        }
-                    }
-
-                    if balance > 0.0 { (weighted_interest / balance) * 100.0 } else { 0.0 }
-                }
-            } else {
-                base_interest_rate
-            };
-
-            let yearly_interest = balance * effective_rate / 100.0;
-            let yearly_interest_czk = convert_to_czk(yearly_interest, &row.3);
+        let now = chrono::Utc::now().timestamp();
+        let year_ahead = now + 365 * 86400;
+        for (account, zones) in interest_service::interest_accounts(conn)? {
+            let yearly_interest: Decimal = interest_service::payments(&account, &zones, now, year_ahead)
+                .iter()
+                .map(|p| money::parse_or_zero(&p.net_interest))
+                .sum();
+            let yearly_interest_czk = money::to_f64(money::to_czk(yearly_interest, &account.currency));
+            let yearly_interest = money::to_f64(yearly_interest);
             let normalized = normalize_to_period(yearly_interest_czk, "yearly", target_period);
 
             if normalized > 0.0 {
                 savings_items.push(CashflowReportItem {
-                    id: row.0, name: row.1, amount: normalized,
-                    original_amount: yearly_interest, original_currency: row.3,
+                    id: account.id, name: account.name, amount: normalized,
+                    original_amount: yearly_interest, original_currency: account.currency,
                     original_frequency: "yearly".to_string(), is_user_defined: false,
                 });
             }
@@ -482,6 +439,35 @@ pub async fn get_cashflow_report(
             total: savings_total, items: savings_items, is_user_editable: true,
         });
 
+        // Term Deposit Maturities (principal paid out by deposits maturing in the
+        // next year without renewal). Savings only change form, so they are listed
+        // apart from income; their net interest is counted above.
+        let mut maturity_items: Vec<CashflowReportItem> = Vec::new();
+        for payment in interest_service::calendar(conn, now, year_ahead)? {
+            if payment.kind != "maturity" {
+                continue;
+            }
+            let principal = money::parse_or_zero(&payment.amount)
+                - money::parse_or_zero(&payment.net_interest);
+            let principal_czk = money::to_f64(money::to_czk(principal, &payment.currency));
+            let principal = money::to_f64(principal);
+            let normalized = normalize_to_period(principal_czk, "yearly", target_period);
+
+            if normalized > 0.0 {
+                maturity_items.push(CashflowReportItem {
+                    id: payment.account_id, name: payment.account_name, amount: normalized,
+                    original_amount: principal, original_currency: payment.currency,
+                    original_frequency: "yearly".to_string(), is_user_defined: false,
+                });
+            }
+        }
+        sort_items_alphabetically(&mut maturity_items);
+        let maturity_total: f64 = maturity_items.iter().map(|i| i.amount).sum();
+        let term_deposit_maturities = CashflowCategory {
+            key: "termDepositMaturities".to_string(), name: "Term Deposit Maturities".to_string(),
+            total: maturity_total, items: maturity_items, is_user_editable: false,
+        };
+
         // 2. Stock Dividends
         let mut dividend_items: Vec<CashflowReportItem> = Vec::new();
         let mut inv_stmt = conn.prepare("SELECT ticker, company_name, quantity FROM stock_investments")?;
@@ -527,26 +513,31 @@ pub async fn get_cashflow_report(
             total: dividend_total, items: dividend_items, is_user_editable: true,
         });
 
-        // 3. Bonds Interest
+        // 3. Bonds Interest (coupons due in the next year; bonds without a
+        // maturity date at their nominal yearly rate)
         let mut bond_items: Vec<CashflowReportItem> = Vec::new();
-        let mut bond_stmt = conn.prepare("SELECT id, name, coupon_value, quantity, interest_rate, currency FROM bonds")?;
-        let bonds = bond_stmt.query_map([], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?, row.get::<_, String>(4)?, row.get::<_, String>(5)?))
-        })?;
-
-        for bond in bonds.filter_map(|r| r.ok()) {
-            let coupon_value: f64 = bond.2.parse().unwrap_or(0.0);
-            let quantity: f64 = bond.3.parse().unwrap_or(1.0);
-            let interest_rate: f64 = bond.4.parse().unwrap_or(0.0);
-            let yearly_interest = coupon_value * quantity * interest_rate / 100.0;
-            let yearly_interest_czk = convert_to_czk(yearly_interest, &bond.5);
+        for bond in bond_service::get_bonds(conn)? {
+            let yearly_interest = if bond.maturity_date.is_some() {
+                bond_service::schedule(&bond, now)
+                    .cash_flows
+                    .iter()
+                    .filter(|f| f.kind == "coupon" && f.date >= now && f.date < year_ahead)
+                    .map(|f| money::parse_or_zero(&f.amount))
+                    .sum::<Decimal>()
+            } else {
+                let coupon_value = money::parse_or_zero(&bond.coupon_value);
+                let quantity = money::parse(&bond.quantity).unwrap_or(Decimal::ONE);
+                let interest_rate = money::parse_or_zero(&bond.interest_rate);
+                coupon_value * quantity * interest_rate / Decimal::ONE_HUNDRED
+            };
+            let yearly_interest_czk = money::to_f64(money::to_czk(yearly_interest, &bond.currency));
+            let yearly_interest = money::to_f64(yearly_interest);
             let normalized = normalize_to_period(yearly_interest_czk, "yearly", target_period);
 
             if normalized > 0.0 {
                 bond_items.push(CashflowReportItem {
-                    id: bond.0, name: bond.1, amount: normalized,
-                    original_amount: yearly_interest, original_currency: bond.5,
+                    id: bond.id, name: bond.name, amount: normalized,
+                    original_amount: yearly_interest, original_currency: bond.currency,
                     original_frequency: "yearly".to_string(), is_user_defined: false,
                 });
             }
@@ -761,6 +752,7 @@ pub async fn get_cashflow_report(
             view_type: target_period.to_string(),
             personal: personal_section,
             investments: investments_section,
+            term_deposit_maturities,
             total_income,
             total_expenses,
             net_cashflow,
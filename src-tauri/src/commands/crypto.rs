@@ -3,10 +3,11 @@
 use crate::db::Database;
 use crate::error::{AppError, Result};
 use crate::models::{
-    CryptoInvestment, CryptoTransaction, EnrichedCryptoInvestment, InsertCryptoInvestment,
-    InsertCryptoTransaction,
+    CryptoIncomeCostBasis, CryptoInvestment, CryptoTransaction, CryptoWalletHolding,
+    EnrichedCryptoInvestment, InsertCryptoInvestment, InsertCryptoTransaction,
 };
 use crate::services::crypto_investments as crypto_service;
+use crate::services::reporting::Reporting;
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
@@ -199,7 +200,7 @@ pub async fn get_crypto_transactions(
     db.with_conn(|conn| {
         let mut stmt = conn.prepare(
             "SELECT id, investment_id, type, ticker, name, quantity, price_per_unit,
-                    currency, transaction_date, created_at
+                    currency, transaction_date, wallet, created_at
              FROM crypto_transactions WHERE investment_id = ?1
              ORDER BY transaction_date DESC",
         )?;
@@ -216,7 +217,8 @@ pub async fn get_crypto_transactions(
                     price_per_unit: row.get(6)?,
                     currency: row.get(7)?,
                     transaction_date: row.get(8)?,
-                    created_at: row.get(9)?,
+                    wallet: row.get(9)?,
+                    created_at: row.get(10)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -234,7 +236,7 @@ pub async fn get_all_crypto_transactions(
     db.with_conn(|conn| {
         let mut stmt = conn.prepare(
             "SELECT id, investment_id, type, ticker, name, quantity, price_per_unit,
-                    currency, transaction_date, created_at
+                    currency, transaction_date, wallet, created_at
              FROM crypto_transactions
              ORDER BY transaction_date DESC",
         )?;
@@ -251,7 +253,8 @@ pub async fn get_all_crypto_transactions(
                     price_per_unit: row.get(6)?,
                     currency: row.get(7)?,
                     transaction_date: row.get(8)?,
-                    created_at: row.get(9)?,
+                    wallet: row.get(9)?,
+                    created_at: row.get(10)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -456,5 +459,45 @@ pub async fn get_crypto_value_history(
     start_date: Option<i64>,
     end_date: Option<i64>,
 ) -> Result<Vec<crate::models::TickerValueHistory>> {
-    db.with_conn(|conn| crypto_service::get_value_history(conn, &ticker, start_date, end_date))
+    db.with_conn(|conn| {
+        let mut history = crypto_service::get_value_history(conn, &ticker, start_date, end_date)?;
+        let reporting = Reporting::load(conn)?;
+        for entry in &mut history {
+            reporting.convert_ticker_history(entry);
+        }
+        Ok(history)
+    })
+}
+
+/// Get crypto holdings per wallet/exchange, optionally for one ticker
+#[tauri::command]
+pub async fn get_crypto_wallet_holdings(
+    db: State<'_, Database>,
+    ticker: Option<String>,
+) -> Result<Vec<CryptoWalletHolding>> {
+    db.with_conn(|conn| crypto_service::get_wallet_holdings(conn, ticker.as_deref()))
+}
+
+/// Get how crypto income (staking, airdrops, interest, mining) enters the cost basis
+#[tauri::command]
+pub async fn get_crypto_income_cost_basis(
+    db: State<'_, Database>,
+) -> Result<CryptoIncomeCostBasis> {
+    db.with_conn(|conn| Ok(crypto_service::get_income_cost_basis(conn)))
+}
+
+/// Set the crypto income cost basis treatment and recalculate average prices
+#[tauri::command]
+pub async fn set_crypto_income_cost_basis(
+    db: State<'_, Database>,
+    basis: CryptoIncomeCostBasis,
+) -> Result<()> {
+    db.with_conn(|conn| crypto_service::set_income_cost_basis(conn, basis))?;
+
+    // Update portfolio snapshot
+    crate::commands::portfolio::update_todays_snapshot(&db)
+        .await
+        .ok();
+
+    Ok(())
 }
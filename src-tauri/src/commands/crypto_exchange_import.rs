@@ -0,0 +1,101 @@
+//! Crypto exchange import commands (Binance, Coinbase, Kraken)
+
+use crate::commands::portfolio;
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::services::crypto_exchange_import::{self, CryptoImportResult, Exchange};
+use crate::services::price_api;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use tauri::{AppHandle, Emitter, State};
+
+/// Import a crypto exchange export file
+#[tauri::command]
+pub async fn import_crypto_exchange(
+    db: State<'_, Database>,
+    app: AppHandle,
+    file_path: String,
+    exchange: Exchange,
+) -> Result<CryptoImportResult> {
+    let bytes =
+        fs::read(&file_path).map_err(|e| AppError::Internal(format!("Cannot open file: {}", e)))?;
+    let export = crypto_exchange_import::parse_exchange_file(exchange, &bytes)?;
+
+    // Coins that will be created need a CoinGecko ID for pricing
+    let assets: BTreeSet<String> = export
+        .entries
+        .iter()
+        .flat_map(|e| {
+            e.received
+                .iter()
+                .chain(e.spent.iter())
+                .chain(e.fees.iter())
+                .map(|(asset, _)| asset.clone())
+        })
+        .filter(|a| crypto_exchange_import::fiat_of(a).is_none())
+        .collect();
+
+    let new_assets: Vec<String> = db.with_conn(|conn| {
+        Ok(assets
+            .into_iter()
+            .filter(|asset| {
+                conn.query_row(
+                    "SELECT 1 FROM crypto_investments WHERE ticker = ?1",
+                    [asset],
+                    |_| Ok(()),
+                )
+                .is_err()
+            })
+            .collect())
+    })?;
+
+    let keys = price_api::get_api_keys(&db)?;
+    let mut coins: HashMap<String, (String, String)> = HashMap::new();
+    for asset in new_assets {
+        // Small delay between lookups to avoid rate limiting
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+        match price_api::search_crypto(keys.coingecko.as_deref(), &asset).await {
+            // Results are sorted by market cap rank, so the first exact match is the main coin
+            Ok(results) => {
+                if let Some(found) = results.iter().find(|r| r.symbol == asset) {
+                    coins.insert(asset, (found.id.clone(), found.name.clone()));
+                }
+            }
+            Err(e) => log::warn!("[CRYPTO IMPORT] Failed to look up {}: {}", asset, e),
+        }
+    }
+
+    let (result, ticker_earliest_dates) = db.with_conn(|conn| {
+        crypto_exchange_import::import_exchange_entries(conn, exchange, export, &coins)
+    })?;
+
+    // Update portfolio snapshot and history for the touched coins
+    portfolio::update_todays_snapshot(&db).await.ok();
+    for (ticker, earliest_date) in ticker_earliest_dates.iter() {
+        let coingecko_id: Option<String> = db.with_conn(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT coingecko_id FROM crypto_investments WHERE ticker = ?1",
+                    [ticker],
+                    |row| row.get(0),
+                )
+                .ok()
+                .flatten())
+        })?;
+        if let Some(cg_id) = coingecko_id.filter(|id| !id.is_empty()) {
+            portfolio::trigger_historical_recalculation_for_crypto_ticker(
+                &db,
+                *earliest_date,
+                ticker,
+                &cg_id,
+            )
+            .await
+            .ok();
+        }
+    }
+
+    app.emit("recalculation-complete", ()).ok();
+
+    Ok(result)
+}
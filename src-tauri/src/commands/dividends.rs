@@ -0,0 +1,143 @@
+//! Received dividend commands and foreign dividend tax report
+
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::{DividendTaxReport, FxSource, InsertReceivedDividend, ReceivedDividend};
+use crate::services::dividend_tax;
+use crate::services::exchange_rate_history::historical_rates_from;
+use crate::services::exchange_rates;
+use std::collections::HashMap;
+use tauri::State;
+use uuid::Uuid;
+
+/// Get received dividends, optionally only for one ticker
+#[tauri::command]
+pub async fn get_received_dividends(
+    db: State<'_, Database>,
+    ticker: Option<String>,
+) -> Result<Vec<ReceivedDividend>> {
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, ticker, payment_date, gross_amount, withholding_tax, currency, source_country, created_at
+             FROM stock_dividends
+             WHERE ?1 IS NULL OR ticker = ?1
+             ORDER BY payment_date DESC",
+        )?;
+
+        let dividends = stmt
+            .query_map([ticker.map(|t| t.to_uppercase())], |row| {
+                Ok(ReceivedDividend {
+                    id: row.get(0)?,
+                    ticker: row.get(1)?,
+                    payment_date: row.get(2)?,
+                    gross_amount: row.get(3)?,
+                    withholding_tax: row.get(4)?,
+                    currency: row.get(5)?,
+                    source_country: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(dividends)
+    })
+}
+
+/// Record a received dividend
+#[tauri::command]
+pub async fn create_received_dividend(
+    db: State<'_, Database>,
+    data: InsertReceivedDividend,
+) -> Result<ReceivedDividend> {
+    // Validate inputs at the trust boundary
+    data.validate()?;
+
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp();
+    let ticker = data.ticker.trim().to_uppercase();
+    let withholding_tax = data
+        .withholding_tax
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "0".to_string());
+    let currency = data.currency.to_uppercase();
+    let source_country = data.source_country.to_uppercase();
+
+    db.with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO stock_dividends (id, ticker, payment_date, gross_amount, withholding_tax, currency, source_country, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                id,
+                ticker,
+                data.payment_date,
+                data.gross_amount,
+                withholding_tax,
+                currency,
+                source_country,
+                now
+            ],
+        )?;
+
+        Ok(ReceivedDividend {
+            id,
+            ticker,
+            payment_date: data.payment_date,
+            gross_amount: data.gross_amount,
+            withholding_tax,
+            currency,
+            source_country,
+            created_at: now,
+        })
+    })
+}
+
+/// Delete a received dividend
+#[tauri::command]
+pub async fn delete_received_dividend(db: State<'_, Database>, id: String) -> Result<()> {
+    db.with_conn(|conn| {
+        let changes = conn.execute("DELETE FROM stock_dividends WHERE id = ?1", [&id])?;
+        if changes == 0 {
+            return Err(AppError::NotFound("Dividend not found".into()));
+        }
+        Ok(())
+    })
+}
+
+/// Get the foreign dividend tax credit report for a tax year
+/// `annual_rates` optionally overrides the CZK rate per currency (e.g., uniform annual rate);
+/// otherwise the tax rate source from the exchange rate settings is used
+#[tauri::command]
+pub async fn get_dividend_tax_report(
+    db: State<'_, Database>,
+    year: i32,
+    annual_rates: Option<HashMap<String, f64>>,
+) -> Result<DividendTaxReport> {
+    let source = db.with_conn(exchange_rates::get_settings)?.tax_source;
+
+    let annual_rates = match (annual_rates, source) {
+        (Some(rates), _) => Some(rates),
+        (None, FxSource::CnbAnnual) => match exchange_rates::fetch_cnb_annual_rates(year).await {
+            Ok(rates) => Some(rates),
+            Err(e) => {
+                log::warn!("[DIVIDEND TAX] Annual rates for {} failed: {}", year, e);
+                None
+            }
+        },
+        (None, _) => None,
+    };
+
+    // Daily rates of the payment dates, for currencies without an annual rate
+    let (start, end) = dividend_tax::year_bounds(year);
+    let end = end.min(chrono::Utc::now().timestamp());
+    let daily_rates = historical_rates_from(&db, start, end, source).await?;
+
+    db.with_conn(|conn| {
+        dividend_tax::build_dividend_tax_report(
+            conn,
+            year,
+            annual_rates.as_ref(),
+            Some(&daily_rates),
+        )
+    })
+}
@@ -0,0 +1,49 @@
+//! Savings goal commands
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{InsertSavingsGoal, SavingsGoal, SavingsGoalProgress};
+use crate::services::goals as goal_service;
+use tauri::State;
+
+/// Get all savings goals
+#[tauri::command]
+pub async fn get_savings_goals(db: State<'_, Database>) -> Result<Vec<SavingsGoal>> {
+    db.with_conn(goal_service::get_goals)
+}
+
+/// Create a savings goal
+#[tauri::command]
+pub async fn create_savings_goal(
+    db: State<'_, Database>,
+    data: InsertSavingsGoal,
+) -> Result<SavingsGoal> {
+    data.validate()?;
+    db.with_conn(|conn| goal_service::create_goal(conn, &data))
+}
+
+/// Update a savings goal and replace its linked holdings
+#[tauri::command]
+pub async fn update_savings_goal(
+    db: State<'_, Database>,
+    id: String,
+    data: InsertSavingsGoal,
+) -> Result<SavingsGoal> {
+    data.validate()?;
+    db.with_conn(|conn| goal_service::update_goal(conn, &id, &data))
+}
+
+/// Delete a savings goal
+#[tauri::command]
+pub async fn delete_savings_goal(db: State<'_, Database>, id: String) -> Result<()> {
+    db.with_conn(|conn| goal_service::delete_goal(conn, &id))
+}
+
+/// Progress, required monthly contribution and status of every savings goal
+#[tauri::command]
+pub async fn get_savings_goal_progress(
+    db: State<'_, Database>,
+) -> Result<Vec<SavingsGoalProgress>> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| goal_service::get_progress(conn, now))
+}
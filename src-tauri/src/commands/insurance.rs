@@ -5,6 +5,7 @@ use crate::error::{AppError, Result};
 use crate::models::{
     InsertInsuranceDocument, InsertInsurancePolicy, InsuranceDocument, InsurancePolicy, RealEstate,
 };
+use crate::services::real_estate as real_estate_service;
 use tauri::{Manager, State};
 use uuid::Uuid;
 
@@ -424,35 +425,13 @@ pub async fn get_insurance_real_estate(
 ) -> Result<Option<RealEstate>> {
     db.with_conn(|conn| {
         let result = conn.query_row(
-            "SELECT r.id, r.name, r.address, r.type, r.purchase_price, r.purchase_price_currency,
-                    r.market_price, r.market_price_currency, r.monthly_rent, r.monthly_rent_currency,
-                    r.recurring_costs, r.photos, r.notes, r.created_at, r.updated_at
-             FROM real_estate r
-             INNER JOIN real_estate_insurances rei ON r.id = rei.real_estate_id
-             WHERE rei.insurance_id = ?1",
+            &format!(
+                "SELECT {} FROM real_estate
+                 WHERE id IN (SELECT real_estate_id FROM real_estate_insurances WHERE insurance_id = ?1)",
+                real_estate_service::REAL_ESTATE_COLUMNS
+            ),
             [&insurance_id],
-            |row| {
-                let rc_json: String = row.get(10)?;
-                let photos_json: String = row.get(11)?;
-
-                Ok(RealEstate {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    address: row.get(2)?,
-                    property_type: row.get(3)?,
-                    purchase_price: row.get(4)?,
-                    purchase_price_currency: row.get(5)?,
-                    market_price: row.get(6)?,
-                    market_price_currency: row.get(7)?,
-                    monthly_rent: row.get(8)?,
-                    monthly_rent_currency: row.get(9)?,
-                    recurring_costs: serde_json::from_str(&rc_json).unwrap_or_default(),
-                    photos: serde_json::from_str(&photos_json).unwrap_or_default(),
-                    notes: row.get(12)?,
-                    created_at: row.get(13)?,
-                    updated_at: row.get(14)?,
-                })
-            },
+            real_estate_service::real_estate_from_row,
         );
 
         match result {
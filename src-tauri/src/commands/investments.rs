@@ -5,10 +5,15 @@ use crate::db::Database;
 use crate::error::{AppError, Result};
 use crate::models::{
     DividendOverride, EnrichedStockInvestment, InsertInvestmentTransaction, InsertStockInvestment,
-    InvestmentTransaction, StockInvestment, StockPriceOverride, StockTag, TwrSeries,
+    InvestmentTransaction, RiskMetrics, RiskScope, StockInvestment, StockPriceOverride, StockTag,
+    TwrSeries, XirrResult, XirrScope,
 };
 use crate::services::currency::convert_to_czk;
 use crate::services::investments as investment_service;
+use crate::services::money::{self, Decimal};
+use crate::services::reporting::Reporting;
+use crate::services::risk as risk_service;
+use crate::services::xirr as xirr_service;
 use rusqlite::OptionalExtension;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, State};
@@ -109,7 +114,7 @@ pub async fn get_investment_with_details(
         // Get transactions
         let mut stmt = conn.prepare(
             "SELECT id, investment_id, type, ticker, company_name, quantity, price_per_unit,
-                    currency, transaction_date, created_at
+                    currency, transaction_date, created_at, account_id
              FROM investment_transactions WHERE investment_id = ?1
              ORDER BY transaction_date DESC",
         )?;
@@ -127,6 +132,7 @@ pub async fn get_investment_with_details(
                     currency: row.get(7)?,
                     transaction_date: row.get(8)?,
                     created_at: row.get(9)?,
+                    account_id: row.get(10)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -397,7 +403,7 @@ pub async fn get_investment_transactions(
     db.with_conn(|conn| {
         let mut stmt = conn.prepare(
             "SELECT id, investment_id, type, ticker, company_name, quantity, price_per_unit,
-                    currency, transaction_date, created_at
+                    currency, transaction_date, created_at, account_id
              FROM investment_transactions WHERE investment_id = ?1
              ORDER BY transaction_date DESC",
         )?;
@@ -415,6 +421,7 @@ pub async fn get_investment_transactions(
                     currency: row.get(7)?,
                     transaction_date: row.get(8)?,
                     created_at: row.get(9)?,
+                    account_id: row.get(10)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -432,7 +439,7 @@ pub async fn get_all_stock_transactions(
     db.with_conn(|conn| {
         let mut stmt = conn.prepare(
             "SELECT id, investment_id, type, ticker, company_name, quantity, price_per_unit,
-                    currency, transaction_date, created_at
+                    currency, transaction_date, created_at, account_id
              FROM investment_transactions
              ORDER BY transaction_date DESC",
         )?;
@@ -450,6 +457,7 @@ pub async fn get_all_stock_transactions(
                     currency: row.get(7)?,
                     transaction_date: row.get(8)?,
                     created_at: row.get(9)?,
+                    account_id: row.get(10)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -580,7 +588,8 @@ pub async fn update_investment_transaction(
 
         conn.execute(
             "UPDATE investment_transactions
-             SET type = ?2, quantity = ?3, price_per_unit = ?4, currency = ?5, transaction_date = ?6
+             SET type = ?2, quantity = ?3, price_per_unit = ?4, currency = ?5, transaction_date = ?6,
+                 account_id = ?7
              WHERE id = ?1",
             rusqlite::params![
                 tx_id,
@@ -589,6 +598,7 @@ pub async fn update_investment_transaction(
                 data.price_per_unit,
                 data.currency,
                 data.transaction_date,
+                data.account_id,
             ],
         )?;
 
@@ -597,7 +607,7 @@ pub async fn update_investment_transaction(
         // Fetch updated transaction
         let tx = conn.query_row(
             "SELECT id, investment_id, type, ticker, company_name, quantity, price_per_unit,
-                    currency, transaction_date, created_at
+                    currency, transaction_date, created_at, account_id
              FROM investment_transactions WHERE id = ?1",
             [&tx_id],
             |row| {
@@ -612,6 +622,7 @@ pub async fn update_investment_transaction(
                     currency: row.get(7)?,
                     transaction_date: row.get(8)?,
                     created_at: row.get(9)?,
+                    account_id: row.get(10)?,
                 })
             },
         )?;
@@ -643,6 +654,7 @@ pub async fn import_investment_transactions(
     app: AppHandle,
     transactions: Vec<serde_json::Value>,
     default_currency: String,
+    account_id: Option<String>,
 ) -> Result<serde_json::Value> {
     use crate::services::price_api;
     use std::collections::HashMap;
@@ -878,6 +890,8 @@ pub async fn import_investment_transactions(
                 &price,
                 currency,
                 transaction_date,
+                None,
+                account_id.as_deref(),
             )
         });
 
@@ -1028,7 +1042,15 @@ pub async fn get_stock_value_history(
     start_date: Option<i64>,
     end_date: Option<i64>,
 ) -> Result<Vec<crate::models::TickerValueHistory>> {
-    db.with_conn(|conn| investment_service::get_value_history(conn, &ticker, start_date, end_date))
+    db.with_conn(|conn| {
+        let mut history =
+            investment_service::get_value_history(conn, &ticker, start_date, end_date)?;
+        let reporting = Reporting::load(conn)?;
+        for entry in &mut history {
+            reporting.convert_ticker_history(entry);
+        }
+        Ok(history)
+    })
 }
 
 /// Get time-weighted return series for a set of stock tags and/or the whole portfolio.
@@ -1036,6 +1058,23 @@ pub async fn get_stock_value_history(
 /// `tag_ids`: IDs of tags to compute separate series for.
 /// `include_portfolio`: if true, also include a whole-portfolio series.
 /// `include_untagged`: if true, include a series for stocks with no tags assigned.
+/// Tickers of the positions selected by `sql` (ticker, quantity rows) that hold units
+fn held_tickers(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<String>> {
+    let rows: Vec<(String, String)> = conn
+        .prepare(sql)?
+        .query_map(params, |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows
+        .into_iter()
+        .filter(|(_, quantity)| money::parse_or_zero(quantity) > Decimal::ZERO)
+        .map(|(ticker, _)| ticker)
+        .collect())
+}
+
 /// `from_ts` / `to_ts`: Unix timestamps (seconds, midnight UTC) for the date range.
 ///
 /// When `tag_ids` is empty and `include_untagged` is false, only the portfolio series is returned.
@@ -1055,10 +1094,11 @@ pub async fn get_stock_twr(
 
         // Whole portfolio series (always when no filter, optional when filters selected)
         if !any_filter || include_portfolio {
-            let all_tickers: Vec<String> = conn
-                .prepare("SELECT ticker FROM stock_investments WHERE CAST(quantity AS REAL) > 0 ORDER BY ticker")?
-                .query_map([], |row| row.get(0))?
-                .collect::<rusqlite::Result<_>>()?;
+            let all_tickers = held_tickers(
+                conn,
+                "SELECT ticker, quantity FROM stock_investments ORDER BY ticker",
+                [],
+            )?;
             let data =
                 investment_service::compute_twr_for_tickers(conn, &all_tickers, from_ts, to_ts)?;
             series.push(TwrSeries { tag: None, is_untagged: false, data });
@@ -1084,14 +1124,13 @@ pub async fn get_stock_twr(
                 .optional()?;
 
             if let Some(tag) = tag {
-                let tickers: Vec<String> = conn
-                    .prepare(
-                        "SELECT si.ticker FROM stock_investments si \
-                         JOIN stock_investment_tags sit ON sit.investment_id = si.id \
-                         WHERE sit.tag_id = ?1 AND CAST(si.quantity AS REAL) > 0 ORDER BY si.ticker",
-                    )?
-                    .query_map([tag_id], |row| row.get(0))?
-                    .collect::<rusqlite::Result<_>>()?;
+                let tickers = held_tickers(
+                    conn,
+                    "SELECT si.ticker, si.quantity FROM stock_investments si \
+                     JOIN stock_investment_tags sit ON sit.investment_id = si.id \
+                     WHERE sit.tag_id = ?1 ORDER BY si.ticker",
+                    [tag_id],
+                )?;
 
                 let data =
                     investment_service::compute_twr_for_tickers(conn, &tickers, from_ts, to_ts)?;
@@ -1105,15 +1144,13 @@ pub async fn get_stock_twr(
 
         // Untagged series: stocks that have no tag assignments
         if include_untagged {
-            let tickers: Vec<String> = conn
-                .prepare(
-                    "SELECT si.ticker FROM stock_investments si \
-                     WHERE CAST(si.quantity AS REAL) > 0 \
-                     AND NOT EXISTS (SELECT 1 FROM stock_investment_tags sit WHERE sit.investment_id = si.id) \
-                     ORDER BY si.ticker",
-                )?
-                .query_map([], |row| row.get(0))?
-                .collect::<rusqlite::Result<_>>()?;
+            let tickers = held_tickers(
+                conn,
+                "SELECT si.ticker, si.quantity FROM stock_investments si \
+                 WHERE NOT EXISTS (SELECT 1 FROM stock_investment_tags sit WHERE sit.investment_id = si.id) \
+                 ORDER BY si.ticker",
+                [],
+            )?;
 
             let data =
                 investment_service::compute_twr_for_tickers(conn, &tickers, from_ts, to_ts)?;
@@ -1123,3 +1160,55 @@ pub async fn get_stock_twr(
         Ok(series)
     })
 }
+
+/// Get money-weighted returns (XIRR) per holding, tag, asset class or for the whole portfolio.
+///
+/// `from_ts` / `to_ts`: optional Unix timestamps bounding the range. Without `from_ts` the
+/// range starts at the first cash flow, without `to_ts` it ends now.
+#[tauri::command]
+pub async fn get_xirr(
+    db: State<'_, Database>,
+    scope: XirrScope,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<XirrResult>> {
+    db.with_conn(|conn| xirr_service::compute_xirr(conn, scope, from_ts, to_ts))
+}
+
+/// Get risk metrics (volatility, drawdown, Sharpe/Sortino, beta) per holding, tag,
+/// asset class or for the whole portfolio.
+///
+/// `benchmark`: ticker whose price history is used for beta.
+/// `risk_free_rate`: annual rate in percent; None uses the saved setting.
+#[tauri::command]
+pub async fn get_risk_metrics(
+    db: State<'_, Database>,
+    scope: RiskScope,
+    benchmark: Option<String>,
+    risk_free_rate: Option<f64>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<RiskMetrics>> {
+    db.with_conn(|conn| {
+        risk_service::compute_risk(
+            conn,
+            scope,
+            benchmark.as_deref(),
+            risk_free_rate,
+            from_ts,
+            to_ts,
+        )
+    })
+}
+
+/// Get the annual risk-free rate (percent) used for Sharpe and Sortino ratios
+#[tauri::command]
+pub async fn get_risk_free_rate(db: State<'_, Database>) -> Result<f64> {
+    db.with_conn(|conn| Ok(risk_service::get_risk_free_rate(conn)))
+}
+
+/// Set the annual risk-free rate (percent)
+#[tauri::command]
+pub async fn set_risk_free_rate(db: State<'_, Database>, rate: f64) -> Result<()> {
+    db.with_conn(|conn| risk_service::set_risk_free_rate(conn, rate))
+}
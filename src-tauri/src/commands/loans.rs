@@ -2,41 +2,22 @@
 
 use crate::db::Database;
 use crate::error::{AppError, Result};
-use crate::models::{InsertLoan, Loan, RealEstate};
+use crate::models::{InsertLoan, Loan, LoanScenario, LoanSchedule, LoanSimulation, RealEstate};
+use crate::services::loans as loan_service;
+use crate::services::real_estate as real_estate_service;
 use tauri::State;
 use uuid::Uuid;
 
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
 /// Get all loans
 #[tauri::command]
 pub async fn get_all_loans(db: State<'_, Database>) -> Result<Vec<Loan>> {
-    db.with_conn(|conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, name, principal, currency, interest_rate, interest_rate_validity_date,
-                    monthly_payment, start_date, end_date, created_at, updated_at
-             FROM loans ORDER BY name",
-        )?;
-
-        let loans = stmt
-            .query_map([], |row| {
-                Ok(Loan {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    principal: row.get(2)?,
-                    currency: row.get(3)?,
-                    interest_rate: row.get(4)?,
-                    interest_rate_validity_date: row.get(5)?,
-                    monthly_payment: row.get(6)?,
-                    start_date: row.get(7)?,
-                    end_date: row.get(8)?,
-                    created_at: row.get(9)?,
-                    updated_at: row.get(10)?,
-                })
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
-
-        Ok(loans)
-    })
+    db.with_conn(loan_service::get_loans)
 }
 
 /// Create loan
@@ -51,31 +32,24 @@ pub async fn create_loan(db: State<'_, Database>, data: InsertLoan) -> Result<Lo
     let interest_rate = data.interest_rate.unwrap_or_else(|| "0".to_string());
     let monthly_payment = data.monthly_payment.unwrap_or_else(|| "0".to_string());
     let start_date = data.start_date.unwrap_or(now);
+    let repayment_type = data.repayment_type.unwrap_or_else(|| "annuity".to_string());
+    let payment_iban = non_empty(data.payment_iban);
+    let payment_variable_symbol = non_empty(data.payment_variable_symbol);
 
     db.with_conn(|conn| {
         conn.execute(
             "INSERT INTO loans (id, name, principal, currency, interest_rate, interest_rate_validity_date,
-             monthly_payment, start_date, end_date, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)",
+             monthly_payment, start_date, end_date, repayment_type, payment_iban, payment_variable_symbol,
+             created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13)",
             rusqlite::params![
                 id, data.name, data.principal, currency, interest_rate,
-                data.interest_rate_validity_date, monthly_payment, start_date, data.end_date, now
+                data.interest_rate_validity_date, monthly_payment, start_date, data.end_date,
+                repayment_type, payment_iban, payment_variable_symbol, now
             ],
         )?;
 
-        Ok(Loan {
-            id,
-            name: data.name,
-            principal: data.principal,
-            currency,
-            interest_rate,
-            interest_rate_validity_date: data.interest_rate_validity_date,
-            monthly_payment,
-            start_date,
-            end_date: data.end_date,
-            created_at: now,
-            updated_at: now,
-        })
+        loan_service::get_loan(conn, &id)
     })
 }
 
@@ -92,8 +66,10 @@ pub async fn update_loan(db: State<'_, Database>, id: String, data: InsertLoan)
             "UPDATE loans SET name = ?1, principal = ?2,
              currency = COALESCE(?3, currency), interest_rate = COALESCE(?4, interest_rate),
              interest_rate_validity_date = ?5, monthly_payment = COALESCE(?6, monthly_payment),
-             start_date = COALESCE(?7, start_date), end_date = ?8, updated_at = ?9
-             WHERE id = ?10",
+             start_date = COALESCE(?7, start_date), end_date = ?8,
+             repayment_type = COALESCE(?9, repayment_type), payment_iban = ?10,
+             payment_variable_symbol = ?11, updated_at = ?12
+             WHERE id = ?13",
             rusqlite::params![
                 data.name,
                 data.principal,
@@ -103,33 +79,62 @@ pub async fn update_loan(db: State<'_, Database>, id: String, data: InsertLoan)
                 data.monthly_payment,
                 data.start_date,
                 data.end_date,
+                data.repayment_type,
+                non_empty(data.payment_iban),
+                non_empty(data.payment_variable_symbol),
                 now,
                 id
             ],
         )?;
 
-        conn.query_row(
-            "SELECT id, name, principal, currency, interest_rate, interest_rate_validity_date,
-                    monthly_payment, start_date, end_date, created_at, updated_at
-             FROM loans WHERE id = ?1",
-            [&id],
-            |row| {
-                Ok(Loan {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    principal: row.get(2)?,
-                    currency: row.get(3)?,
-                    interest_rate: row.get(4)?,
-                    interest_rate_validity_date: row.get(5)?,
-                    monthly_payment: row.get(6)?,
-                    start_date: row.get(7)?,
-                    end_date: row.get(8)?,
-                    created_at: row.get(9)?,
-                    updated_at: row.get(10)?,
-                })
-            },
-        )
-        .map_err(|_| AppError::NotFound("Loan not found".into()))
+        loan_service::get_loan(conn, &id)
+    })
+}
+
+/// Amortization schedule of a loan with installments matched in bank transactions
+#[tauri::command]
+pub async fn get_loan_schedule(db: State<'_, Database>, id: String) -> Result<LoanSchedule> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        let loan = loan_service::get_loan(conn, &id)?;
+        let payments = loan_service::matched_payments(conn, &loan)?;
+        Ok(loan_service::schedule(&loan, &payments, now))
+    })
+}
+
+/// Amortization schedules of all loans as of today
+#[tauri::command]
+pub async fn get_loan_schedules(db: State<'_, Database>) -> Result<Vec<LoanSchedule>> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        let loans = loan_service::get_loans(conn)?;
+        let mut payments = loan_service::payments_by_loan(conn, &loans)?;
+        Ok(loans
+            .iter()
+            .map(|loan| {
+                let payments = payments.remove(&loan.id).unwrap_or_default();
+                loan_service::schedule(loan, &payments, now)
+            })
+            .collect())
+    })
+}
+
+/// Compare prepayment and refixation scenarios for a loan against its current schedule
+#[tauri::command]
+pub async fn simulate_loan_scenarios(
+    db: State<'_, Database>,
+    id: String,
+    scenarios: Vec<LoanScenario>,
+) -> Result<LoanSimulation> {
+    for scenario in &scenarios {
+        scenario.validate()?;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        let loan = loan_service::get_loan(conn, &id)?;
+        let payments = loan_service::matched_payments(conn, &loan)?;
+        Ok(loan_service::simulate(&loan, &payments, &scenarios, now))
     })
 }
 
@@ -159,35 +164,13 @@ pub async fn get_loan_real_estate(
 ) -> Result<Option<RealEstate>> {
     db.with_conn(|conn| {
         let result = conn.query_row(
-            "SELECT r.id, r.name, r.address, r.type, r.purchase_price, r.purchase_price_currency,
-                    r.market_price, r.market_price_currency, r.monthly_rent, r.monthly_rent_currency,
-                    r.recurring_costs, r.photos, r.notes, r.created_at, r.updated_at
-             FROM real_estate r
-             INNER JOIN real_estate_loans rel ON r.id = rel.real_estate_id
-             WHERE rel.loan_id = ?1",
+            &format!(
+                "SELECT {} FROM real_estate
+                 WHERE id IN (SELECT real_estate_id FROM real_estate_loans WHERE loan_id = ?1)",
+                real_estate_service::REAL_ESTATE_COLUMNS
+            ),
             [&loan_id],
-            |row| {
-                let rc_json: String = row.get(10)?;
-                let photos_json: String = row.get(11)?;
-
-                Ok(RealEstate {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    address: row.get(2)?,
-                    property_type: row.get(3)?,
-                    purchase_price: row.get(4)?,
-                    purchase_price_currency: row.get(5)?,
-                    market_price: row.get(6)?,
-                    market_price_currency: row.get(7)?,
-                    monthly_rent: row.get(8)?,
-                    monthly_rent_currency: row.get(9)?,
-                    recurring_costs: serde_json::from_str(&rc_json).unwrap_or_default(),
-                    photos: serde_json::from_str(&photos_json).unwrap_or_default(),
-                    notes: row.get(12)?,
-                    created_at: row.get(13)?,
-                    updated_at: row.get(14)?,
-                })
-            },
+            real_estate_service::real_estate_from_row,
         );
 
         match result {
@@ -202,30 +185,15 @@ pub async fn get_loan_real_estate(
 #[tauri::command]
 pub async fn get_available_loans(db: State<'_, Database>) -> Result<Vec<Loan>> {
     db.with_conn(|conn| {
-        let mut stmt = conn.prepare(
-            "SELECT l.id, l.name, l.principal, l.currency, l.interest_rate, l.interest_rate_validity_date,
-                    l.monthly_payment, l.start_date, l.end_date, l.created_at, l.updated_at
-             FROM loans l
-             WHERE l.id NOT IN (SELECT loan_id FROM real_estate_loans)
-             ORDER BY l.name",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM loans
+             WHERE id NOT IN (SELECT loan_id FROM real_estate_loans)
+             ORDER BY name",
+            loan_service::LOAN_COLUMNS
+        ))?;
 
         let loans = stmt
-            .query_map([], |row| {
-                Ok(Loan {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    principal: row.get(2)?,
-                    currency: row.get(3)?,
-                    interest_rate: row.get(4)?,
-                    interest_rate_validity_date: row.get(5)?,
-                    monthly_payment: row.get(6)?,
-                    start_date: row.get(7)?,
-                    end_date: row.get(8)?,
-                    created_at: row.get(9)?,
-                    updated_at: row.get(10)?,
-                })
-            })?
+            .query_map([], loan_service::loan_from_row)?
             .filter_map(|r| r.ok())
             .collect();
 
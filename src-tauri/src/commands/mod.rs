@@ -2,14 +2,21 @@
 //!
 //! All commands are registered in lib.rs and called via invoke() from frontend
 
+pub mod allocation;
 pub mod auth;
 pub mod bank_accounts;
+pub mod benchmarks;
 pub mod bonds;
+pub mod broker_import;
+pub mod brokerage_accounts;
 pub mod budgeting;
 pub mod cashflow;
 pub mod categorization;
 pub mod crypto;
+pub mod crypto_exchange_import;
+pub mod dividends;
 pub mod export;
+pub mod goals;
 pub mod insurance;
 pub mod investments;
 pub mod loans;
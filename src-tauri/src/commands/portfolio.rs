@@ -2,8 +2,20 @@
 
 use crate::db::Database;
 use crate::error::Result;
-use crate::models::PortfolioMetricsHistory;
-use crate::services::currency::convert_to_czk;
+use crate::models::{
+    AccountValue, ExposureDimension, ExposureHistoryPoint, ExposureReport, FxSettings,
+    InsertManualExchangeRate, Loan, LoanInstallment, ManualExchangeRate, PortfolioMetricsHistory,
+    RealEstate, RealEstateValuation, UnderlyingCurrency,
+};
+use crate::services::bank_accounts as bank_service;
+use crate::services::bonds as bond_service;
+use crate::services::exchange_rate_history::{historical_rates, HistoricalRates};
+use crate::services::exchange_rates;
+use crate::services::exposure as exposure_service;
+use crate::services::loans as loan_service;
+use crate::services::money::{self, Decimal};
+use crate::services::real_estate as real_estate_service;
+use crate::services::reporting::Reporting;
 use serde::Serialize;
 use specta::Type;
 use tauri::State;
@@ -34,6 +46,9 @@ pub struct PortfolioMetrics {
     pub total_assets: f64,
     #[serde(rename = "netWorth")]
     pub net_worth: f64,
+    /// Stock holdings split by brokerage account
+    #[serde(rename = "accountValues")]
+    pub account_values: Vec<AccountValue>,
 }
 
 /// Get current portfolio metrics
@@ -45,50 +60,43 @@ pub async fn get_portfolio_metrics(
     calculate_portfolio_metrics(&db, exclude_personal_real_estate)
 }
 
-fn calculate_portfolio_metrics(
+pub(crate) fn calculate_portfolio_metrics(
     db: &Database,
     exclude_personal_real_estate: bool,
 ) -> Result<PortfolioMetrics> {
     db.with_conn(|conn| {
-        // Calculate total savings from bank_accounts
-        let mut bank_stmt = conn.prepare("SELECT balance, currency FROM bank_accounts")?;
-        let total_savings: f64 = bank_stmt
+        // Sums are exact decimals; only prices converted at float rates enter as f64
+        // Calculate total savings from bank_accounts counted in the balance
+        let mut bank_stmt = conn.prepare(
+            "SELECT balance, currency FROM bank_accounts WHERE exclude_from_balance = 0",
+        )?;
+        let total_savings: Decimal = bank_stmt
             .query_map([], |row| {
-                let balance: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
+                let balance = money::parse_or_zero(&row.get::<_, String>(0)?);
                 let currency: String = row.get(1)?;
-                Ok(convert_to_czk(balance, &currency))
+                Ok(money::to_czk(balance, &currency))
             })?
             .filter_map(|r| r.ok())
             .sum();
 
-        // Calculate total bonds
-        let mut bonds_stmt = conn.prepare("SELECT coupon_value, quantity, currency FROM bonds")?;
-        let total_bonds: f64 = bonds_stmt
-            .query_map([], |row| {
-                let value: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let quantity: f64 = row.get::<_, String>(1)?.parse().unwrap_or(1.0);
-                let currency: String = row.get(2)?;
-                Ok(convert_to_czk(value * quantity, &currency))
-            })?
-            .filter_map(|r| r.ok())
+        // Calculate total bonds (market value with accrued interest)
+        let now = chrono::Utc::now().timestamp();
+        let total_bonds: Decimal = bond_service::get_bonds(conn)?
+            .iter()
+            .map(|bond| money::to_czk(bond_service::market_value(bond, now), &bond.currency))
             .sum();
 
-        // Calculate total loans (liabilities)
-        let mut loans_stmt = conn.prepare("SELECT principal, currency FROM loans")?;
-        let total_liabilities: f64 = loans_stmt
-            .query_map([], |row| {
-                let principal: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let currency: String = row.get(1)?;
-                Ok(convert_to_czk(principal, &currency))
-            })?
-            .filter_map(|r| r.ok())
+        // Calculate total loans (liabilities: outstanding principal)
+        let total_liabilities: Decimal = loan_service::outstanding_balances(conn, now)?
+            .iter()
+            .map(|(loan, balance)| money::to_czk(*balance, &loan.currency))
             .sum();
 
         // Calculate real estate
         let mut stmt =
             conn.prepare("SELECT type, market_price, market_price_currency FROM real_estate")?;
-        let mut total_re_personal = 0.0;
-        let mut total_re_investment = 0.0;
+        let mut total_re_personal = Decimal::ZERO;
+        let mut total_re_investment = Decimal::ZERO;
 
         let rows = stmt.query_map([], |row| {
             Ok((
@@ -99,8 +107,7 @@ fn calculate_portfolio_metrics(
         })?;
 
         for row in rows.filter_map(|r| r.ok()) {
-            let price: f64 = row.1.parse().unwrap_or(0.0);
-            let price_czk = convert_to_czk(price, &row.2);
+            let price_czk = money::to_czk(money::parse_or_zero(&row.1), &row.2);
             if row.0 == "personal" {
                 total_re_personal += price_czk;
             } else {
@@ -109,36 +116,36 @@ fn calculate_portfolio_metrics(
         }
 
         // Calculate investments value
-        let mut total_investments = 0.0;
+        let mut total_investments = Decimal::ZERO;
         let mut inv_stmt = conn.prepare("SELECT ticker, quantity FROM stock_investments")?;
         let investments = inv_stmt.query_map([], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         })?;
 
         for inv in investments.filter_map(|r| r.ok()) {
-            let qty: f64 = inv.1.parse().unwrap_or(0.0);
+            let qty = money::parse_or_zero(&inv.1);
             if let Some(resolved) = crate::services::pricing::resolve_stock_price(conn, &inv.0) {
-                total_investments += resolved.price_czk * qty;
+                total_investments += money::from_f64(resolved.price_czk) * qty;
             }
         }
 
         // Calculate crypto value
-        let mut total_crypto = 0.0;
+        let mut total_crypto = Decimal::ZERO;
         let mut crypto_stmt = conn.prepare("SELECT ticker, quantity FROM crypto_investments")?;
         let cryptos = crypto_stmt.query_map([], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         })?;
 
         for crypto in cryptos.filter_map(|r| r.ok()) {
-            let qty: f64 = crypto.1.parse().unwrap_or(0.0);
+            let qty = money::parse_or_zero(&crypto.1);
             if let Some(resolved) = crate::services::pricing::resolve_crypto_price(conn, &crypto.0)
             {
-                total_crypto += resolved.price_czk * qty;
+                total_crypto += money::from_f64(resolved.price_czk) * qty;
             }
         }
 
         // Calculate details
-        let mut total_other_assets = 0.0;
+        let mut total_other_assets = Decimal::ZERO;
         let mut other_stmt =
             conn.prepare("SELECT quantity, market_price, currency FROM other_assets")?;
         let other_assets = other_stmt.query_map([], |row| {
@@ -150,12 +157,22 @@ fn calculate_portfolio_metrics(
         })?;
 
         for asset in other_assets.filter_map(|r| r.ok()) {
-            let qty: f64 = asset.0.parse().unwrap_or(0.0);
-            let price: f64 = asset.1.parse().unwrap_or(0.0);
-            let currency = asset.2;
-            total_other_assets += convert_to_czk(qty * price, &currency);
+            let qty = money::parse_or_zero(&asset.0);
+            let price = money::parse_or_zero(&asset.1);
+            total_other_assets += money::to_czk(qty * price, &asset.2);
         }
 
+        // Round each class to the haléř so the totals add up to what is shown
+        let round = |total: Decimal| money::round(total, "CZK");
+        let total_savings = round(total_savings);
+        let total_bonds = round(total_bonds);
+        let total_liabilities = round(total_liabilities);
+        let total_re_personal = round(total_re_personal);
+        let total_re_investment = round(total_re_investment);
+        let total_investments = round(total_investments);
+        let total_crypto = round(total_crypto);
+        let total_other_assets = round(total_other_assets);
+
         // Calculate totals
         let total_real_estate = if exclude_personal_real_estate {
             total_re_investment
@@ -170,19 +187,21 @@ fn calculate_portfolio_metrics(
             + total_real_estate
             + total_other_assets;
         let net_worth = total_assets - total_liabilities;
+        let account_values = crate::services::brokerage_accounts::get_account_values(conn)?;
 
         Ok(PortfolioMetrics {
-            total_savings,
-            total_investments,
-            total_crypto,
-            total_bonds,
-            total_real_estate_personal: total_re_personal,
-            total_real_estate_investment: total_re_investment,
-            total_real_estate,
-            total_other_assets,
-            total_liabilities,
-            total_assets,
-            net_worth,
+            total_savings: money::to_f64(total_savings),
+            total_investments: money::to_f64(total_investments),
+            total_crypto: money::to_f64(total_crypto),
+            total_bonds: money::to_f64(total_bonds),
+            total_real_estate_personal: money::to_f64(total_re_personal),
+            total_real_estate_investment: money::to_f64(total_re_investment),
+            total_real_estate: money::to_f64(total_real_estate),
+            total_other_assets: money::to_f64(total_other_assets),
+            total_liabilities: money::to_f64(total_liabilities),
+            total_assets: money::to_f64(total_assets),
+            net_worth: money::to_f64(net_worth),
+            account_values,
         })
     })
 }
@@ -237,12 +256,72 @@ pub async fn get_portfolio_history(
                 })
             })?
             .filter_map(|r| r.ok())
-            .collect();
+            .collect::<Vec<_>>();
+
+        let reporting = Reporting::load(conn)?;
+        Ok(history
+            .into_iter()
+            .map(|mut entry| {
+                reporting.convert_portfolio_history(&mut entry);
+                entry
+            })
+            .collect())
+    })
+}
 
+/// Get portfolio exposure by sector, industry, listing country or currency
+#[tauri::command]
+pub async fn get_portfolio_exposure(
+    db: State<'_, Database>,
+    dimension: ExposureDimension,
+    stocks_only: bool,
+    exclude_personal_real_estate: bool,
+) -> Result<ExposureReport> {
+    db.with_conn(|conn| {
+        exposure_service::get_exposure(conn, dimension, stocks_only, exclude_personal_real_estate)
+    })
+}
+
+/// Get daily stock exposure history from per-ticker value history
+#[tauri::command]
+pub async fn get_exposure_history(
+    db: State<'_, Database>,
+    dimension: ExposureDimension,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<ExposureHistoryPoint>> {
+    db.with_conn(|conn| {
+        let mut history = exposure_service::get_exposure_history(conn, dimension, from_ts, to_ts)?;
+        let reporting = Reporting::load(conn)?;
+        for point in &mut history {
+            reporting.convert_exposure_history(point);
+        }
         Ok(history)
     })
 }
 
+/// Get the underlying currencies set per ticker
+#[tauri::command]
+pub async fn get_underlying_currencies(db: State<'_, Database>) -> Result<Vec<UnderlyingCurrency>> {
+    db.with_conn(exposure_service::get_underlying_currencies)
+}
+
+/// Set the currency a ticker's holdings are denominated in, used by the currency exposure
+#[tauri::command]
+pub async fn set_underlying_currency(
+    db: State<'_, Database>,
+    ticker: String,
+    currency: String,
+) -> Result<UnderlyingCurrency> {
+    db.with_conn(|conn| exposure_service::set_underlying_currency(conn, &ticker, &currency))
+}
+
+/// Forget the underlying currency of a ticker
+#[tauri::command]
+pub async fn delete_underlying_currency(db: State<'_, Database>, ticker: String) -> Result<()> {
+    db.with_conn(|conn| exposure_service::delete_underlying_currency(conn, &ticker))
+}
+
 /// Record today's per-ticker values to stock_value_history and crypto_value_history tables
 /// This is called alongside update_todays_snapshot to ensure per-ticker chart data is available
 fn record_todays_ticker_values(db: &Database) -> Result<()> {
@@ -265,17 +344,17 @@ fn record_todays_ticker_values(db: &Database) -> Result<()> {
             .collect();
 
         for (ticker, qty_str) in investments {
-            let qty: f64 = qty_str.parse().unwrap_or(0.0);
-            if qty <= 0.0 {
+            let qty = money::parse_or_zero(&qty_str);
+            if qty <= Decimal::ZERO {
                 continue;
             }
 
             if let Some(resolved) = crate::services::pricing::resolve_stock_price(conn, &ticker) {
-                let price: f64 = resolved.original_price.parse().unwrap_or(0.0);
-                if price <= 0.0 {
+                let price = money::parse_or_zero(&resolved.original_price);
+                if price <= Decimal::ZERO {
                     continue;
                 }
-                let value_czk = resolved.price_czk * qty;
+                let value_czk = money::from_f64(resolved.price_czk) * qty;
                 let id = Uuid::new_v4().to_string();
 
                 conn.execute(
@@ -290,9 +369,9 @@ fn record_todays_ticker_values(db: &Database) -> Result<()> {
                         id,
                         ticker,
                         today_start,
-                        value_czk.to_string(),
-                        qty.to_string(),
-                        price.to_string(),
+                        money::format(value_czk, "CZK"),
+                        money::format_exact(qty),
+                        money::format_price(price),
                         resolved.currency,
                     ],
                 )?;
@@ -309,17 +388,17 @@ fn record_todays_ticker_values(db: &Database) -> Result<()> {
             .collect();
 
         for (ticker, qty_str) in cryptos {
-            let qty: f64 = qty_str.parse().unwrap_or(0.0);
-            if qty <= 0.0 {
+            let qty = money::parse_or_zero(&qty_str);
+            if qty <= Decimal::ZERO {
                 continue;
             }
 
             if let Some(resolved) = crate::services::pricing::resolve_crypto_price(conn, &ticker) {
-                let price: f64 = resolved.original_price.parse().unwrap_or(0.0);
-                if price <= 0.0 {
+                let price = money::parse_or_zero(&resolved.original_price);
+                if price <= Decimal::ZERO {
                     continue;
                 }
-                let value_czk = resolved.price_czk * qty;
+                let value_czk = money::from_f64(resolved.price_czk) * qty;
                 let id = Uuid::new_v4().to_string();
 
                 conn.execute(
@@ -334,9 +413,9 @@ fn record_todays_ticker_values(db: &Database) -> Result<()> {
                         id,
                         ticker,
                         today_start,
-                        value_czk.to_string(),
-                        qty.to_string(),
-                        price.to_string(),
+                        money::format(value_czk, "CZK"),
+                        money::format_exact(qty),
+                        money::format_price(price),
                         resolved.currency,
                     ],
                 )?;
@@ -427,15 +506,19 @@ pub async fn record_portfolio_snapshot(db: State<'_, Database>) -> Result<()> {
     update_todays_snapshot(&db).await
 }
 
-/// Update exchange rates from ECB and return them
+/// Update exchange rates from the valuation source (ECB or ČNB) and return them
 #[tauri::command]
 pub async fn refresh_exchange_rates(
     db: State<'_, Database>,
 ) -> Result<std::collections::HashMap<String, f64>> {
-    let rates = crate::services::currency::fetch_ecb_rates().await?;
+    let source = db.with_conn(exchange_rates::get_settings)?.valuation_source;
+    let rates = exchange_rates::fetch_current_rates(source).await?;
 
-    // Persist to database for offline use
-    db.with_conn(|conn| crate::services::currency::save_rates_to_db(conn, &rates))?;
+    // Persist to database for offline use, then fill gaps with manual rates
+    db.with_conn(|conn| {
+        crate::services::currency::save_rates_to_db(conn, &rates)?;
+        exchange_rates::apply_manual_rates(conn)
+    })?;
 
     Ok(rates)
 }
@@ -446,6 +529,44 @@ pub fn get_exchange_rates() -> std::collections::HashMap<String, f64> {
     crate::services::currency::get_all_rates()
 }
 
+/// Get the exchange rate source per purpose
+#[tauri::command]
+pub async fn get_fx_settings(db: State<'_, Database>) -> Result<FxSettings> {
+    db.with_conn(exchange_rates::get_settings)
+}
+
+/// Save the exchange rate source per purpose
+#[tauri::command]
+pub async fn set_fx_settings(db: State<'_, Database>, settings: FxSettings) -> Result<FxSettings> {
+    settings.validate()?;
+    db.with_conn(|conn| {
+        exchange_rates::set_settings(conn, &settings)?;
+        exchange_rates::get_settings(conn)
+    })
+}
+
+/// Get all manual exchange rates
+#[tauri::command]
+pub async fn get_manual_exchange_rates(db: State<'_, Database>) -> Result<Vec<ManualExchangeRate>> {
+    db.with_conn(exchange_rates::get_manual_rates)
+}
+
+/// Set the manual rate of a currency neither source publishes
+#[tauri::command]
+pub async fn set_manual_exchange_rate(
+    db: State<'_, Database>,
+    data: InsertManualExchangeRate,
+) -> Result<ManualExchangeRate> {
+    data.validate()?;
+    db.with_conn(|conn| exchange_rates::set_manual_rate(conn, &data))
+}
+
+/// Delete the manual rate of a currency
+#[tauri::command]
+pub async fn delete_manual_exchange_rate(db: State<'_, Database>, currency: String) -> Result<()> {
+    db.with_conn(|conn| exchange_rates::delete_manual_rate(conn, &currency))
+}
+
 // ============================================================================
 // Price Status (for stale indicator)
 // ============================================================================
@@ -581,8 +702,7 @@ pub fn get_price_status(db: State<'_, Database>) -> Result<PriceStatus> {
 // ============================================================================
 
 use crate::services::price_api::{
-    get_api_keys, get_historical_crypto_prices_coingecko, get_historical_stock_prices_yahoo,
-    HistoricalPrice,
+    get_api_keys, get_historical_crypto_prices, get_historical_stock_prices, HistoricalPrice,
 };
 use std::collections::HashMap;
 
@@ -659,185 +779,227 @@ fn get_crypto_id_map(db: &Database) -> Result<HashMap<String, String>> {
     })
 }
 
-/// Calculate portfolio metrics for a specific day using historical prices
-fn calculate_metrics_for_day(
+/// Loan installments and property valuations, which do not change from day to day
+#[allow(clippy::type_complexity)]
+fn load_loans_and_properties(
     db: &Database,
-    day_timestamp: i64,
-    stock_prices: &HashMap<String, Vec<HistoricalPrice>>,
-    crypto_prices: &HashMap<String, Vec<HistoricalPrice>>,
-    last_known_stock_prices: &mut HashMap<String, (f64, String)>,
-    last_known_crypto_prices: &mut HashMap<String, (f64, String)>,
-) -> Result<PortfolioMetrics> {
-    use crate::services::currency::convert_to_czk;
-
+) -> Result<(
+    Vec<(Loan, Vec<LoanInstallment>)>,
+    Vec<(RealEstate, Vec<RealEstateValuation>)>,
+)> {
     db.with_conn(|conn| {
-        // Calculate total savings from bank_accounts
-        let mut bank_stmt = conn.prepare("SELECT balance, currency FROM bank_accounts")?;
-        let total_savings: f64 = bank_stmt
-            .query_map([], |row| {
-                let balance: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let currency: String = row.get(1)?;
-                Ok(convert_to_czk(balance, &currency))
-            })?
-            .filter_map(|r| r.ok())
-            .sum();
+        let now = chrono::Utc::now().timestamp();
+        Ok((
+            loan_service::installment_plans(conn, now)?,
+            real_estate_service::properties_with_valuations(conn)?,
+        ))
+    })
+}
 
-        // Calculate total bonds (same as current - static values)
-        let mut bonds_stmt = conn.prepare("SELECT coupon_value, quantity, currency FROM bonds")?;
-        let total_bonds: f64 = bonds_stmt
-            .query_map([], |row| {
-                let value: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let quantity: f64 = row.get::<_, String>(1)?.parse().unwrap_or(1.0);
-                let currency: String = row.get(2)?;
-                Ok(convert_to_czk(value * quantity, &currency))
-            })?
-            .filter_map(|r| r.ok())
-            .sum();
+/// Class totals of one history day in CZK, summed as decimals
+#[derive(Default)]
+struct DayTotals {
+    savings: Decimal,
+    bonds: Decimal,
+    liabilities: Decimal,
+    re_personal: Decimal,
+    re_investment: Decimal,
+    investments: Decimal,
+    crypto: Decimal,
+    other_assets: Decimal,
+}
 
-        // Calculate total loans (same as current - static values)
-        let mut loans_stmt = conn.prepare("SELECT principal, currency FROM loans")?;
-        let total_liabilities: f64 = loans_stmt
-            .query_map([], |row| {
-                let principal: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let currency: String = row.get(1)?;
-                Ok(convert_to_czk(principal, &currency))
-            })?
-            .filter_map(|r| r.ok())
+impl DayTotals {
+    /// Savings, bonds, loans and real estate of a day, which do not depend on
+    /// the prices of traded assets
+    fn fixed_classes(
+        conn: &rusqlite::Connection,
+        day_timestamp: i64,
+        fx: &HistoricalRates,
+        loans: &[(Loan, Vec<LoanInstallment>)],
+        properties: &[(RealEstate, Vec<RealEstateValuation>)],
+    ) -> Result<Self> {
+        // Bank balances on the day, rolled back from transactions
+        let savings = bank_service::balances_on(conn, day_timestamp, fx)?
+            .iter()
+            .map(|(currency, balance)| fx.to_czk_decimal(*balance, currency, day_timestamp))
             .sum();
 
-        // Calculate real estate (same as current - static values)
-        let mut re_stmt =
-            conn.prepare("SELECT type, market_price, market_price_currency FROM real_estate")?;
-        let mut total_re_personal = 0.0;
-        let mut total_re_investment = 0.0;
+        // Bonds (current holdings and prices, accrued interest of the day),
+        // skipping bonds not yet bought on the day
+        let bonds = bond_service::get_bonds(conn)?
+            .iter()
+            .filter(|bond| bond.purchase_date.is_none_or(|date| date <= day_timestamp))
+            .map(|bond| {
+                let value = bond_service::market_value(bond, day_timestamp);
+                fx.to_czk_decimal(value, &bond.currency, day_timestamp)
+            })
+            .sum();
 
-        let re_rows = re_stmt.query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-            ))
-        })?;
+        // Loans (outstanding principal on the day)
+        let liabilities = loans
+            .iter()
+            .map(|(loan, installments)| {
+                let balance = loan_service::balance_on(loan, installments, day_timestamp);
+                fx.to_czk_decimal(balance, &loan.currency, day_timestamp)
+            })
+            .sum();
 
-        for row in re_rows.filter_map(|r| r.ok()) {
-            let price: f64 = row.1.parse().unwrap_or(0.0);
-            let price_czk = convert_to_czk(price, &row.2);
-            if row.0 == "personal" {
-                total_re_personal += price_czk;
+        // Real estate (valuation in effect on the day)
+        let mut re_personal = Decimal::ZERO;
+        let mut re_investment = Decimal::ZERO;
+        for (property, valuations) in properties {
+            let value = real_estate_service::value_on(property, valuations, day_timestamp);
+            let value_czk =
+                fx.to_czk_decimal(value, &property.market_price_currency, day_timestamp);
+            if property.property_type == "personal" {
+                re_personal += value_czk;
             } else {
-                total_re_investment += price_czk;
+                re_investment += value_czk;
             }
         }
 
+        Ok(Self {
+            savings,
+            bonds,
+            liabilities,
+            re_personal,
+            re_investment,
+            ..Default::default()
+        })
+    }
+
+    /// Metrics with each class rounded to the haléř, as for current metrics
+    fn into_metrics(self) -> PortfolioMetrics {
+        let round = |total: Decimal| money::round(total, "CZK");
+        let total_savings = round(self.savings);
+        let total_bonds = round(self.bonds);
+        let total_liabilities = round(self.liabilities);
+        let total_re_personal = round(self.re_personal);
+        let total_re_investment = round(self.re_investment);
+        let total_investments = round(self.investments);
+        let total_crypto = round(self.crypto);
+        let total_other_assets = round(self.other_assets);
+
+        let total_real_estate = total_re_personal + total_re_investment;
+        let total_assets = total_savings
+            + total_investments
+            + total_crypto
+            + total_bonds
+            + total_real_estate
+            + total_other_assets;
+        let net_worth = total_assets - total_liabilities;
+
+        PortfolioMetrics {
+            total_savings: money::to_f64(total_savings),
+            total_investments: money::to_f64(total_investments),
+            total_crypto: money::to_f64(total_crypto),
+            total_bonds: money::to_f64(total_bonds),
+            total_real_estate_personal: money::to_f64(total_re_personal),
+            total_real_estate_investment: money::to_f64(total_re_investment),
+            total_real_estate: money::to_f64(total_real_estate),
+            total_other_assets: money::to_f64(total_other_assets),
+            total_liabilities: money::to_f64(total_liabilities),
+            total_assets: money::to_f64(total_assets),
+            net_worth: money::to_f64(net_worth),
+            // Account breakdown is only reported for current metrics
+            account_values: Vec::new(),
+        }
+    }
+}
+
+/// Price of a ticker on a day: the closest historical price (remembered as the
+/// last known), else the last known price
+fn price_on_day(
+    prices: Option<&Vec<HistoricalPrice>>,
+    last_known: &mut HashMap<String, (Decimal, String)>,
+    ticker: &str,
+    day_timestamp: i64,
+) -> Option<(Decimal, String)> {
+    if let Some(price) = prices.and_then(|p| find_closest_price(p, day_timestamp)) {
+        let price = (money::from_f64(price.price), price.currency.clone());
+        last_known.insert(ticker.to_string(), price.clone());
+        return Some(price);
+    }
+    last_known.get(ticker).cloned()
+}
+
+/// Calculate portfolio metrics for a specific day using historical prices and exchange rates
+#[allow(clippy::too_many_arguments)]
+fn calculate_metrics_for_day(
+    db: &Database,
+    day_timestamp: i64,
+    stock_prices: &HashMap<String, Vec<HistoricalPrice>>,
+    crypto_prices: &HashMap<String, Vec<HistoricalPrice>>,
+    last_known_stock_prices: &mut HashMap<String, (Decimal, String)>,
+    last_known_crypto_prices: &mut HashMap<String, (Decimal, String)>,
+    fx: &HistoricalRates,
+    loans: &[(Loan, Vec<LoanInstallment>)],
+    properties: &[(RealEstate, Vec<RealEstateValuation>)],
+) -> Result<PortfolioMetrics> {
+    db.with_conn(|conn| {
+        let mut totals = DayTotals::fixed_classes(conn, day_timestamp, fx, loans, properties)?;
+
         // Calculate other assets (static values)
         let mut other_stmt =
             conn.prepare("SELECT quantity, market_price, currency FROM other_assets")?;
-        let total_other_assets: f64 = other_stmt
+        totals.other_assets = other_stmt
             .query_map([], |row| {
-                let qty: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let price: f64 = row.get::<_, String>(1)?.parse().unwrap_or(0.0);
+                let qty = money::parse_or_zero(&row.get::<_, String>(0)?);
+                let price = money::parse_or_zero(&row.get::<_, String>(1)?);
                 let currency = row.get::<_, String>(2)?;
-                Ok(convert_to_czk(qty * price, &currency))
+                Ok(fx.to_czk_decimal(qty * price, &currency, day_timestamp))
             })?
             .filter_map(|r| r.ok())
             .sum();
 
         // Calculate investments using historical prices with fallback
-        let mut total_investments = 0.0;
         let mut inv_stmt = conn.prepare("SELECT ticker, quantity FROM stock_investments")?;
-        let investments: Vec<(String, f64)> = inv_stmt
+        let investments: Vec<(String, Decimal)> = inv_stmt
             .query_map([], |row| {
                 let ticker: String = row.get(0)?;
-                let qty: f64 = row.get::<_, String>(1)?.parse().unwrap_or(0.0);
+                let qty = money::parse_or_zero(&row.get::<_, String>(1)?);
                 Ok((ticker, qty))
             })?
             .filter_map(|r| r.ok())
             .collect();
 
         for (ticker, qty) in investments {
-            let (price, currency) = if let Some(prices) = stock_prices.get(&ticker) {
-                if let Some(price) = find_closest_price(prices, day_timestamp) {
-                    // Update last known
-                    last_known_stock_prices
-                        .insert(ticker.clone(), (price.price, price.currency.clone()));
-                    (price.price, price.currency.as_str())
-                } else if let Some((last_price, last_curr)) = last_known_stock_prices.get(&ticker) {
-                    (*last_price, last_curr.as_str())
-                } else {
-                    (0.0, "USD")
-                }
-            } else if let Some((last_price, last_curr)) = last_known_stock_prices.get(&ticker) {
-                (*last_price, last_curr.as_str())
-            } else {
-                (0.0, "USD")
-            };
-
-            if price > 0.0 {
-                let value_in_czk = convert_to_czk(price * qty, currency);
-                total_investments += value_in_czk;
+            let price = price_on_day(
+                stock_prices.get(&ticker),
+                last_known_stock_prices,
+                &ticker,
+                day_timestamp,
+            );
+            if let Some((price, currency)) = price.filter(|(p, _)| *p > Decimal::ZERO) {
+                totals.investments += fx.to_czk_decimal(price * qty, &currency, day_timestamp);
             }
         }
 
         // Calculate crypto using historical prices
-        let mut total_crypto = 0.0;
         let mut crypto_stmt = conn.prepare("SELECT ticker, quantity FROM crypto_investments")?;
-        let cryptos: Vec<(String, f64)> = crypto_stmt
+        let cryptos: Vec<(String, Decimal)> = crypto_stmt
             .query_map([], |row| {
                 let ticker: String = row.get(0)?;
-                let qty: f64 = row.get::<_, String>(1)?.parse().unwrap_or(0.0);
+                let qty = money::parse_or_zero(&row.get::<_, String>(1)?);
                 Ok((ticker, qty))
             })?
             .filter_map(|r| r.ok())
             .collect();
 
         for (ticker, qty) in cryptos {
-            let (price, currency) = if let Some(prices) = crypto_prices.get(&ticker) {
-                if let Some(price) = find_closest_price(prices, day_timestamp) {
-                    // Update last known
-                    last_known_crypto_prices
-                        .insert(ticker.clone(), (price.price, price.currency.clone()));
-                    (price.price, price.currency.as_str())
-                } else if let Some((last_price, last_curr)) = last_known_crypto_prices.get(&ticker)
-                {
-                    (*last_price, last_curr.as_str())
-                } else {
-                    (0.0, "USD")
-                }
-            } else if let Some((last_price, last_curr)) = last_known_crypto_prices.get(&ticker) {
-                (*last_price, last_curr.as_str())
-            } else {
-                (0.0, "USD")
-            };
-
-            if price > 0.0 {
-                let value_in_czk = convert_to_czk(price * qty, currency);
-                total_crypto += value_in_czk;
+            let price = price_on_day(
+                crypto_prices.get(&ticker),
+                last_known_crypto_prices,
+                &ticker,
+                day_timestamp,
+            );
+            if let Some((price, currency)) = price.filter(|(p, _)| *p > Decimal::ZERO) {
+                totals.crypto += fx.to_czk_decimal(price * qty, &currency, day_timestamp);
             }
         }
 
-        // Calculate totals
-        let total_real_estate = total_re_personal + total_re_investment;
-        let total_assets = total_savings
-            + total_investments
-            + total_crypto
-            + total_bonds
-            + total_real_estate
-            + total_other_assets;
-        let net_worth = total_assets - total_liabilities;
-
-        Ok(PortfolioMetrics {
-            total_savings,
-            total_investments,
-            total_crypto,
-            total_bonds,
-            total_real_estate_personal: total_re_personal,
-            total_real_estate_investment: total_re_investment,
-            total_real_estate,
-            total_other_assets,
-            total_liabilities,
-            total_assets,
-            net_worth,
-        })
+        Ok(totals.into_metrics())
     })
 }
 
@@ -977,40 +1139,40 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
 
     // Fetch historical prices
     let stock_prices = if !stock_tickers.is_empty() {
-        get_historical_stock_prices_yahoo(&stock_tickers, fetch_start, fetch_end).await?
+        get_historical_stock_prices(db, &stock_tickers, fetch_start, fetch_end).await?
     } else {
         HashMap::new()
     };
 
     let api_keys = get_api_keys(db)?;
     let crypto_prices = if !crypto_id_map.is_empty() {
-        get_historical_crypto_prices_coingecko(
-            api_keys.coingecko.as_deref(),
-            &crypto_id_map,
-            fetch_start,
-            fetch_end,
-        )
-        .await?
+        get_historical_crypto_prices(db, &crypto_id_map, fetch_start, fetch_end).await?
     } else {
         HashMap::new()
     };
 
+    // Exchange rates of each missing day
+    let fx = historical_rates(db, fetch_start, fetch_end).await?;
+
+    // Loan schedules and property valuations, read once for all days
+    let (loans, properties) = load_loans_and_properties(db)?;
+
     // Create snapshots for each missing day
     let mut days_processed = 0;
 
     // Initialize last known prices for fallback
     // Try to get current prices as a baseline if no history available
-    let mut last_known_crypto_prices: HashMap<String, (f64, String)> = HashMap::new();
-    let mut last_known_stock_prices: HashMap<String, (f64, String)> = HashMap::new();
+    let mut last_known_crypto_prices: HashMap<String, (Decimal, String)> = HashMap::new();
+    let mut last_known_stock_prices: HashMap<String, (Decimal, String)> = HashMap::new();
 
     // Seed crypto with current prices
     if !crypto_id_map.is_empty() {
         let current_prices = db.with_conn(|conn| {
             let mut stmt = conn.prepare("SELECT symbol, price, currency FROM crypto_prices")?;
-            let prices: HashMap<String, (f64, String)> = stmt
+            let prices: HashMap<String, (Decimal, String)> = stmt
                 .query_map([], |row| {
                     let symbol: String = row.get(0)?;
-                    let price: f64 = row.get::<_, String>(1)?.parse().unwrap_or(0.0);
+                    let price = money::parse_or_zero(&row.get::<_, String>(1)?);
                     let currency: String = row.get(2)?;
                     Ok((symbol, (price, currency)))
                 })?
@@ -1034,10 +1196,10 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
                     sd.currency
                 ) FROM stock_data sd",
             )?;
-            let prices: HashMap<String, (f64, String)> = stmt
+            let prices: HashMap<String, (Decimal, String)> = stmt
                 .query_map([], |row| {
                     let ticker: String = row.get(0)?;
-                    let price: f64 = row.get::<_, String>(1)?.parse().unwrap_or(0.0);
+                    let price = money::parse_or_zero(&row.get::<_, String>(1)?);
                     let currency: String = row.get(2)?;
                     Ok((ticker, (price, currency)))
                 })?
@@ -1058,6 +1220,9 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
             &crypto_prices,
             &mut last_known_stock_prices,
             &mut last_known_crypto_prices,
+            &fx,
+            &loans,
+            &properties,
         )?;
 
         // Insert snapshot
@@ -1080,13 +1245,13 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
             for day_timestamp in &missing_days {
                 let quantity = get_stock_quantity_at_date(conn, ticker, *day_timestamp);
 
-                if quantity <= 0.0 {
+                if quantity <= Decimal::ZERO {
                     continue;
                 }
 
                 let (price, currency) = if let Some(prices) = stock_prices.get(ticker) {
                     if let Some(hp) = find_closest_price(prices, *day_timestamp) {
-                        (hp.price, hp.currency.clone())
+                        (money::from_f64(hp.price), hp.currency.clone())
                     } else {
                         continue;
                     }
@@ -1094,7 +1259,7 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
                     continue;
                 };
 
-                let value_czk = convert_to_czk(quantity * price, &currency);
+                let value_czk = fx.to_czk_decimal(quantity * price, &currency, *day_timestamp);
                 let id = Uuid::new_v4().to_string();
 
                 conn.execute(
@@ -1109,9 +1274,9 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
                         id,
                         ticker,
                         day_timestamp,
-                        value_czk.to_string(),
-                        quantity.to_string(),
-                        price.to_string(),
+                        money::format(value_czk, "CZK"),
+                        money::format_exact(quantity),
+                        money::format_price(price),
                         currency,
                     ],
                 )?;
@@ -1126,13 +1291,13 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
             for day_timestamp in &missing_days {
                 let quantity = get_crypto_quantity_at_date(conn, ticker, *day_timestamp);
 
-                if quantity <= 0.0 {
+                if quantity <= Decimal::ZERO {
                     continue;
                 }
 
                 let (price, currency) = if let Some(prices) = crypto_prices.get(ticker) {
                     if let Some(hp) = find_closest_price(prices, *day_timestamp) {
-                        (hp.price, hp.currency.clone())
+                        (money::from_f64(hp.price), hp.currency.clone())
                     } else {
                         continue;
                     }
@@ -1140,7 +1305,7 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
                     continue;
                 };
 
-                let value_czk = convert_to_czk(quantity * price, &currency);
+                let value_czk = fx.to_czk_decimal(quantity * price, &currency, *day_timestamp);
                 let id = Uuid::new_v4().to_string();
 
                 conn.execute(
@@ -1155,9 +1320,9 @@ pub async fn backfill_missing_snapshots(db: &Database) -> Result<BackfillResult>
                         id,
                         ticker,
                         day_timestamp,
-                        value_czk.to_string(),
-                        quantity.to_string(),
-                        price.to_string(),
+                        money::format(value_czk, "CZK"),
+                        money::format_exact(quantity),
+                        money::format_price(price),
                         currency,
                     ],
                 )?;
@@ -1397,22 +1562,52 @@ pub async fn backfill_crypto_ticker_history(
 // Historical Recalculation for Retrospective Transactions
 // ============================================================================
 
+/// Net quantity from (type, quantity) transaction rows, summed as decimals in
+/// Rust (SQL SUM over REAL would drift). `sign` maps a type to +1, -1 or 0;
+/// a negative result counts as zero.
+fn net_quantity(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+    sign: fn(&str) -> i64,
+) -> Decimal {
+    let Ok(mut stmt) = conn.prepare(sql) else {
+        return Decimal::ZERO;
+    };
+    let total: Decimal = match stmt.query_map(params, |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    }) {
+        Ok(rows) => rows
+            .filter_map(|r| r.ok())
+            .map(|(tx_type, qty)| Decimal::from(sign(&tx_type)) * money::parse_or_zero(&qty))
+            .sum(),
+        Err(_) => Decimal::ZERO,
+    };
+    total.max(Decimal::ZERO)
+}
+
+/// Buys add, everything else (sells) subtracts
+fn buy_sell_sign(tx_type: &str) -> i64 {
+    if tx_type == "buy" {
+        1
+    } else {
+        -1
+    }
+}
+
 /// Calculate stock quantity at a specific point in time by summing transactions
 fn get_stock_quantity_at_date(
     conn: &rusqlite::Connection,
     ticker: &str,
     date_timestamp: i64,
-) -> f64 {
-    let result: rusqlite::Result<f64> = conn.query_row(
-        "SELECT COALESCE(
-            SUM(CASE WHEN type = 'buy' THEN CAST(quantity AS REAL) ELSE -CAST(quantity AS REAL) END),
-            0.0
-        ) FROM investment_transactions 
-        WHERE ticker = ?1 AND transaction_date <= ?2",
+) -> Decimal {
+    net_quantity(
+        conn,
+        "SELECT type, quantity FROM investment_transactions
+         WHERE ticker = ?1 AND transaction_date <= ?2",
         rusqlite::params![ticker, date_timestamp],
-        |row| row.get(0),
-    );
-    result.unwrap_or(0.0).max(0.0)
+        buy_sell_sign,
+    )
 }
 
 /// Calculate crypto quantity at a specific point in time by summing transactions
@@ -1420,17 +1615,18 @@ fn get_crypto_quantity_at_date(
     conn: &rusqlite::Connection,
     ticker: &str,
     date_timestamp: i64,
-) -> f64 {
-    let result: rusqlite::Result<f64> = conn.query_row(
-        "SELECT COALESCE(
-            SUM(CASE WHEN type = 'buy' THEN CAST(quantity AS REAL) ELSE -CAST(quantity AS REAL) END),
-            0.0
-        ) FROM crypto_transactions 
-        WHERE ticker = ?1 AND transaction_date <= ?2",
+) -> Decimal {
+    net_quantity(
+        conn,
+        "SELECT type, quantity FROM crypto_transactions
+         WHERE ticker = ?1 AND transaction_date <= ?2",
         rusqlite::params![ticker, date_timestamp],
-        |row| row.get(0),
-    );
-    result.unwrap_or(0.0).max(0.0)
+        |tx_type| match tx_type {
+            "sell" => -1,
+            "transfer_in" | "transfer_out" => 0,
+            _ => 1,
+        },
+    )
 }
 
 /// Calculate other asset quantity at a specific point in time by summing transactions
@@ -1438,17 +1634,14 @@ fn get_other_asset_quantity_at_date(
     conn: &rusqlite::Connection,
     asset_id: &str,
     date_timestamp: i64,
-) -> f64 {
-    let result: rusqlite::Result<f64> = conn.query_row(
-        "SELECT COALESCE(
-            SUM(CASE WHEN type = 'buy' THEN CAST(quantity AS REAL) ELSE -CAST(quantity AS REAL) END),
-            0.0
-        ) FROM other_asset_transactions 
-        WHERE asset_id = ?1 AND transaction_date <= ?2",
+) -> Decimal {
+    net_quantity(
+        conn,
+        "SELECT type, quantity FROM other_asset_transactions
+         WHERE asset_id = ?1 AND transaction_date <= ?2",
         rusqlite::params![asset_id, date_timestamp],
-        |row| row.get(0),
-    );
-    result.unwrap_or(0.0).max(0.0)
+        buy_sell_sign,
+    )
 }
 
 /// Calculate portfolio metrics for a specific day using historical quantities
@@ -1457,153 +1650,111 @@ fn calculate_metrics_for_day_historical(
     day_timestamp: i64,
     stock_prices: &HashMap<String, Vec<HistoricalPrice>>,
     crypto_prices: &HashMap<String, Vec<HistoricalPrice>>,
+    fx: &HistoricalRates,
+    loans: &[(Loan, Vec<LoanInstallment>)],
+    properties: &[(RealEstate, Vec<RealEstateValuation>)],
 ) -> Result<PortfolioMetrics> {
-    use crate::services::currency::convert_to_czk;
-
     db.with_conn(|conn| {
-        // Calculate total savings from bank_accounts (current values - static)
-        let mut bank_stmt = conn.prepare(
-            "SELECT balance, currency FROM bank_accounts WHERE exclude_from_balance = 0",
-        )?;
-        let total_savings: f64 = bank_stmt
-            .query_map([], |row| {
-                let balance: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let currency: String = row.get(1)?;
-                Ok(convert_to_czk(balance, &currency))
-            })?
-            .filter_map(|r| r.ok())
-            .sum();
-
-        // Calculate total bonds (static values)
-        let mut bonds_stmt = conn.prepare("SELECT coupon_value, quantity, currency FROM bonds")?;
-        let total_bonds: f64 = bonds_stmt
-            .query_map([], |row| {
-                let value: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let quantity: f64 = row.get::<_, String>(1)?.parse().unwrap_or(1.0);
-                let currency: String = row.get(2)?;
-                Ok(convert_to_czk(value * quantity, &currency))
-            })?
-            .filter_map(|r| r.ok())
-            .sum();
-
-        // Calculate total loans (static values)
-        let mut loans_stmt = conn.prepare("SELECT principal, currency FROM loans")?;
-        let total_liabilities: f64 = loans_stmt
-            .query_map([], |row| {
-                let principal: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                let currency: String = row.get(1)?;
-                Ok(convert_to_czk(principal, &currency))
-            })?
-            .filter_map(|r| r.ok())
-            .sum();
-
-        // Calculate real estate (static values)
-        let mut re_stmt =
-            conn.prepare("SELECT type, market_price, market_price_currency FROM real_estate")?;
-        let mut total_re_personal = 0.0;
-        let mut total_re_investment = 0.0;
+        let mut totals = DayTotals::fixed_classes(conn, day_timestamp, fx, loans, properties)?;
+        totals.other_assets = other_assets_value_on(conn, day_timestamp, fx)?;
+        totals.investments = stock_value_on(conn, day_timestamp, stock_prices, fx)?;
+        totals.crypto = crypto_value_on(conn, day_timestamp, crypto_prices, fx)?;
+        Ok(totals.into_metrics())
+    })
+}
 
-        let re_rows = re_stmt.query_map([], |row| {
+/// Value of other assets on a day from their HISTORICAL quantities (CZK, unrounded)
+fn other_assets_value_on(
+    conn: &rusqlite::Connection,
+    day_timestamp: i64,
+    fx: &HistoricalRates,
+) -> Result<Decimal> {
+    let mut stmt = conn.prepare("SELECT id, market_price, currency FROM other_assets")?;
+    let rows: Vec<(String, Decimal, String)> = stmt
+        .query_map([], |row| {
             Ok((
                 row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
+                money::parse_or_zero(&row.get::<_, String>(1)?),
                 row.get::<_, String>(2)?,
             ))
-        })?;
-
-        for row in re_rows.filter_map(|r| r.ok()) {
-            let price: f64 = row.1.parse().unwrap_or(0.0);
-            let price_czk = convert_to_czk(price, &row.2);
-            if row.0 == "personal" {
-                total_re_personal += price_czk;
-            } else {
-                total_re_investment += price_czk;
-            }
-        }
-
-        // Calculate other assets using HISTORICAL quantities
-        let mut other_stmt = conn.prepare("SELECT id, market_price, currency FROM other_assets")?;
-        let mut total_other_assets = 0.0;
-        let other_rows: Vec<(String, f64, String)> = other_stmt
-            .query_map([], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?.parse().unwrap_or(0.0),
-                    row.get::<_, String>(2)?,
-                ))
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
 
-        for (asset_id, price, currency) in other_rows {
+    Ok(rows
+        .into_iter()
+        .map(|(asset_id, price, currency)| {
             let qty = get_other_asset_quantity_at_date(conn, &asset_id, day_timestamp);
-            total_other_assets += convert_to_czk(qty * price, &currency);
-        }
-
-        // Calculate investments using HISTORICAL quantities and prices
-        let mut total_investments = 0.0;
-        let mut inv_stmt = conn.prepare("SELECT DISTINCT ticker FROM stock_investments")?;
-        let tickers: Vec<String> = inv_stmt
-            .query_map([], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
+            fx.to_czk_decimal(qty * price, &currency, day_timestamp)
+        })
+        .sum())
+}
 
-        for ticker in tickers {
-            let qty = get_stock_quantity_at_date(conn, &ticker, day_timestamp);
-            if qty > 0.0 {
-                if let Some(prices) = stock_prices.get(&ticker) {
-                    if let Some(price) = find_closest_price(prices, day_timestamp) {
-                        let value_in_czk = convert_to_czk(price.price * qty, &price.currency);
-                        total_investments += value_in_czk;
-                    }
-                }
-            }
+/// Value of held quantities of tickers at their closest historical price (CZK, unrounded)
+fn tickers_value_on(
+    tickers: &[String],
+    day_timestamp: i64,
+    prices: &HashMap<String, Vec<HistoricalPrice>>,
+    fx: &HistoricalRates,
+    quantity_at_date: impl Fn(&str) -> Decimal,
+) -> Decimal {
+    let mut total = Decimal::ZERO;
+    for ticker in tickers {
+        let qty = quantity_at_date(ticker);
+        if qty <= Decimal::ZERO {
+            continue;
         }
-
-        // Calculate crypto using HISTORICAL quantities and prices
-        let mut total_crypto = 0.0;
-        let mut crypto_stmt = conn.prepare("SELECT DISTINCT ticker FROM crypto_investments")?;
-        let crypto_tickers: Vec<String> = crypto_stmt
-            .query_map([], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
-
-        for ticker in crypto_tickers {
-            let qty = get_crypto_quantity_at_date(conn, &ticker, day_timestamp);
-            if qty > 0.0 {
-                if let Some(prices) = crypto_prices.get(&ticker) {
-                    if let Some(price) = find_closest_price(prices, day_timestamp) {
-                        let value_in_czk = convert_to_czk(price.price * qty, &price.currency);
-                        total_crypto += value_in_czk;
-                    }
-                }
-            }
+        let price = prices
+            .get(ticker)
+            .and_then(|p| find_closest_price(p, day_timestamp));
+        if let Some(price) = price {
+            let value = money::from_f64(price.price) * qty;
+            total += fx.to_czk_decimal(value, &price.currency, day_timestamp);
         }
+    }
+    total
+}
 
-        // Calculate totals
-        let total_real_estate = total_re_personal + total_re_investment;
-        let total_assets = total_savings
-            + total_investments
-            + total_crypto
-            + total_bonds
-            + total_real_estate
-            + total_other_assets;
-        let net_worth = total_assets - total_liabilities;
+/// Value of stocks on a day from HISTORICAL quantities and prices (CZK, unrounded)
+fn stock_value_on(
+    conn: &rusqlite::Connection,
+    day_timestamp: i64,
+    prices: &HashMap<String, Vec<HistoricalPrice>>,
+    fx: &HistoricalRates,
+) -> Result<Decimal> {
+    let mut stmt = conn.prepare("SELECT DISTINCT ticker FROM stock_investments")?;
+    let tickers: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(tickers_value_on(
+        &tickers,
+        day_timestamp,
+        prices,
+        fx,
+        |ticker| get_stock_quantity_at_date(conn, ticker, day_timestamp),
+    ))
+}
 
-        Ok(PortfolioMetrics {
-            total_savings,
-            total_investments,
-            total_crypto,
-            total_bonds,
-            total_real_estate_personal: total_re_personal,
-            total_real_estate_investment: total_re_investment,
-            total_real_estate,
-            total_other_assets,
-            total_liabilities,
-            total_assets,
-            net_worth,
-        })
-    })
+/// Value of crypto on a day from HISTORICAL quantities and prices (CZK, unrounded)
+fn crypto_value_on(
+    conn: &rusqlite::Connection,
+    day_timestamp: i64,
+    prices: &HashMap<String, Vec<HistoricalPrice>>,
+    fx: &HistoricalRates,
+) -> Result<Decimal> {
+    let mut stmt = conn.prepare("SELECT DISTINCT ticker FROM crypto_investments")?;
+    let tickers: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(tickers_value_on(
+        &tickers,
+        day_timestamp,
+        prices,
+        fx,
+        |ticker| get_crypto_quantity_at_date(conn, ticker, day_timestamp),
+    ))
 }
 
 /// Update or insert a snapshot for a specific day
@@ -1739,29 +1890,32 @@ pub async fn recalculate_history_from_date(db: &Database, from_timestamp: i64) -
     let fetch_end = today_start;
 
     let stock_prices = if !stock_tickers.is_empty() {
-        get_historical_stock_prices_yahoo(&stock_tickers, fetch_start, fetch_end).await?
+        get_historical_stock_prices(db, &stock_tickers, fetch_start, fetch_end).await?
     } else {
         HashMap::new()
     };
 
-    let api_keys = get_api_keys(db)?;
     let crypto_prices = if !crypto_id_map.is_empty() {
-        get_historical_crypto_prices_coingecko(
-            api_keys.coingecko.as_deref(),
-            &crypto_id_map,
-            fetch_start,
-            fetch_end,
-        )
-        .await?
+        get_historical_crypto_prices(db, &crypto_id_map, fetch_start, fetch_end).await?
     } else {
         HashMap::new()
     };
 
+    let fx = historical_rates(db, fetch_start, fetch_end).await?;
+    let (loans, properties) = load_loans_and_properties(db)?;
+
     // Recalculate each day
     let mut days_processed = 0;
     for day_timestamp in days_to_recalc {
-        let metrics =
-            calculate_metrics_for_day_historical(db, day_timestamp, &stock_prices, &crypto_prices)?;
+        let metrics = calculate_metrics_for_day_historical(
+            db,
+            day_timestamp,
+            &stock_prices,
+            &crypto_prices,
+            &fx,
+            &loans,
+            &properties,
+        )?;
 
         update_or_insert_snapshot(db, &metrics, day_timestamp)?;
         days_processed += 1;
@@ -1792,91 +1946,24 @@ fn calculate_asset_value_for_day(
     asset_type: AssetType,
     stock_prices: Option<&HashMap<String, Vec<HistoricalPrice>>>,
     crypto_prices: Option<&HashMap<String, Vec<HistoricalPrice>>>,
-) -> Result<f64> {
-    use crate::services::currency::convert_to_czk;
-
-    match asset_type {
+    fx: &HistoricalRates,
+) -> Result<Decimal> {
+    let empty_map = HashMap::new();
+    let total = db.with_conn(|conn| match asset_type {
         AssetType::Stocks => {
-            let empty_map = HashMap::new();
-            let prices_map = stock_prices.unwrap_or(&empty_map);
-            let stock_tickers = get_stock_tickers(db)?;
-
-            db.with_conn(move |conn| {
-                let mut total = 0.0;
-                for ticker in &stock_tickers {
-                    let qty = get_stock_quantity_at_date(conn, ticker, day_timestamp);
-                    if qty > 0.0 {
-                        if let Some(prices) = prices_map.get(ticker) {
-                            if let Some(price) = find_closest_price(prices, day_timestamp) {
-                                total += convert_to_czk(price.price * qty, &price.currency);
-                            }
-                        }
-                    }
-                }
-                Ok(total)
-            })
+            stock_value_on(conn, day_timestamp, stock_prices.unwrap_or(&empty_map), fx)
         }
         AssetType::Crypto => {
-            let empty_map = HashMap::new();
-            let prices_map = crypto_prices.unwrap_or(&empty_map);
-
-            db.with_conn(move |conn| {
-                let mut total = 0.0;
-                let mut stmt = conn.prepare("SELECT DISTINCT ticker FROM crypto_investments")?;
-                let crypto_tickers: Vec<String> = stmt
-                    .query_map([], |row| row.get(0))?
-                    .filter_map(|r| r.ok())
-                    .collect();
-
-                for ticker in crypto_tickers {
-                    let qty = get_crypto_quantity_at_date(conn, &ticker, day_timestamp);
-                    if qty > 0.0 {
-                        if let Some(prices) = prices_map.get(&ticker) {
-                            if let Some(price) = find_closest_price(prices, day_timestamp) {
-                                total += convert_to_czk(price.price * qty, &price.currency);
-                            }
-                        }
-                    }
-                }
-                Ok(total)
-            })
+            crypto_value_on(conn, day_timestamp, crypto_prices.unwrap_or(&empty_map), fx)
         }
-        AssetType::OtherAssets => db.with_conn(|conn| {
-            let mut stmt = conn.prepare("SELECT id, market_price, currency FROM other_assets")?;
-            let mut total = 0.0;
-            let rows: Vec<(String, f64, String)> = stmt
-                .query_map([], |row| {
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, String>(1)?.parse().unwrap_or(0.0),
-                        row.get::<_, String>(2)?,
-                    ))
-                })?
-                .filter_map(|r| r.ok())
-                .collect();
-
-            for (asset_id, price, currency) in rows {
-                let qty = get_other_asset_quantity_at_date(conn, &asset_id, day_timestamp);
-                total += convert_to_czk(qty * price, &currency);
-            }
-            Ok(total)
-        }),
-        AssetType::Savings => db.with_conn(|conn| {
-            // Calculate total savings from bank_accounts (current values - static for now)
-            let mut bank_stmt = conn.prepare(
-                "SELECT balance, currency FROM bank_accounts WHERE exclude_from_balance = 0",
-            )?;
-            let total_savings: f64 = bank_stmt
-                .query_map([], |row| {
-                    let balance: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-                    let currency: String = row.get(1)?;
-                    Ok(convert_to_czk(balance, &currency))
-                })?
-                .filter_map(|r| r.ok())
-                .sum();
-            Ok(total_savings)
-        }),
-    }
+        AssetType::OtherAssets => other_assets_value_on(conn, day_timestamp, fx),
+        // Bank balances on the day, rolled back from transactions
+        AssetType::Savings => Ok(bank_service::balances_on(conn, day_timestamp, fx)?
+            .iter()
+            .map(|(currency, balance)| fx.to_czk_decimal(*balance, currency, day_timestamp))
+            .sum()),
+    })?;
+    Ok(money::round(total, "CZK"))
 }
 
 /// Update only a specific asset column in portfolio_metrics_history
@@ -1884,7 +1971,7 @@ fn update_asset_column_for_day(
     db: &Database,
     day_timestamp: i64,
     asset_type: AssetType,
-    value: f64,
+    value: Decimal,
 ) -> Result<()> {
     db.with_conn(|conn| {
         let day_start = (day_timestamp / 86400) * 86400;
@@ -1912,7 +1999,10 @@ fn update_asset_column_for_day(
                 "UPDATE portfolio_metrics_history SET {} = ?1 WHERE recorded_at >= ?2 AND recorded_at <= ?3",
                 column_name
             );
-            conn.execute(&sql, rusqlite::params![value.to_string(), day_start, day_end])?;
+            conn.execute(
+                &sql,
+                rusqlite::params![money::format(value, "CZK"), day_start, day_end],
+            )?;
         }
         // If no snapshot exists, we don't create one - the full recalc will handle it
 
@@ -1980,10 +2070,7 @@ pub async fn recalculate_asset_history_from_date(
         // Get tickers for fetching historical prices
         let stock_tickers = get_stock_tickers(db)?;
         if !stock_tickers.is_empty() {
-            Some(
-                get_historical_stock_prices_yahoo(&stock_tickers, recalc_start, today_start)
-                    .await?,
-            )
+            Some(get_historical_stock_prices(db, &stock_tickers, recalc_start, today_start).await?)
         } else {
             None
         }
@@ -1994,16 +2081,7 @@ pub async fn recalculate_asset_history_from_date(
     let crypto_prices = if asset_type == AssetType::Crypto {
         let crypto_id_map = get_crypto_id_map(db)?;
         if !crypto_id_map.is_empty() {
-            let api_keys = get_api_keys(db)?;
-            Some(
-                get_historical_crypto_prices_coingecko(
-                    api_keys.coingecko.as_deref(),
-                    &crypto_id_map,
-                    recalc_start,
-                    today_start,
-                )
-                .await?,
-            )
+            Some(get_historical_crypto_prices(db, &crypto_id_map, recalc_start, today_start).await?)
         } else {
             None
         }
@@ -2011,6 +2089,8 @@ pub async fn recalculate_asset_history_from_date(
         None
     };
 
+    let fx = historical_rates(db, recalc_start, today_start).await?;
+
     // Recalculate each day
     for day_timestamp in days_to_recalc {
         let value = calculate_asset_value_for_day(
@@ -2019,6 +2099,7 @@ pub async fn recalculate_asset_history_from_date(
             asset_type,
             stock_prices.as_ref(),
             crypto_prices.as_ref(), // Passed as Optional references
+            &fx,
         )?;
         update_asset_column_for_day(db, day_timestamp, asset_type, value)?;
     }
@@ -2108,12 +2189,14 @@ pub async fn recalculate_stock_ticker_history(
     );
 
     // Fetch historical prices for just this ticker
-    let stock_prices = crate::services::price_api::get_historical_stock_prices_yahoo(
+    let stock_prices = crate::services::price_api::get_historical_stock_prices(
+        db,
         &[ticker.to_string()],
         from_day,
         today_start,
     )
     .await?;
+    let fx = historical_rates(db, from_day, today_start).await?;
 
     // Calculate and store value for each day
     let ticker_clone = ticker.to_string();
@@ -2122,7 +2205,7 @@ pub async fn recalculate_stock_ticker_history(
             // Get quantity at this date
             let quantity = get_stock_quantity_at_date(conn, &ticker_clone, day_timestamp);
 
-            if quantity <= 0.0 {
+            if quantity <= Decimal::ZERO {
                 // No holdings at this date, delete any existing entry
                 conn.execute(
                     "DELETE FROM stock_value_history WHERE ticker = ?1 AND recorded_at = ?2",
@@ -2134,7 +2217,7 @@ pub async fn recalculate_stock_ticker_history(
             // Get price for this ticker at this date
             let (price, currency) = if let Some(prices) = stock_prices.get(&ticker_clone) {
                 if let Some(hp) = find_closest_price(prices, day_timestamp) {
-                    (hp.price, hp.currency.clone())
+                    (money::from_f64(hp.price), hp.currency.clone())
                 } else {
                     continue; // No price data available
                 }
@@ -2142,7 +2225,7 @@ pub async fn recalculate_stock_ticker_history(
                 continue; // No price data available
             };
 
-            let value_czk = convert_to_czk(quantity * price, &currency);
+            let value_czk = fx.to_czk_decimal(quantity * price, &currency, day_timestamp);
 
             // Upsert into stock_value_history
             let id = Uuid::new_v4().to_string();
@@ -2158,9 +2241,9 @@ pub async fn recalculate_stock_ticker_history(
                     id,
                     ticker_clone,
                     day_timestamp,
-                    value_czk.to_string(),
-                    quantity.to_string(),
-                    price.to_string(),
+                    money::format(value_czk, "CZK"),
+                    money::format_exact(quantity),
+                    money::format_price(price),
                     currency,
                 ],
             )?;
@@ -2207,9 +2290,8 @@ pub async fn recalculate_crypto_ticker_history(
     let mut crypto_map = HashMap::new();
     crypto_map.insert(coingecko_id.to_string(), ticker.to_string());
 
-    let api_keys = crate::services::price_api::get_api_keys(db)?;
-    let crypto_prices = crate::services::price_api::get_historical_crypto_prices_coingecko(
-        api_keys.coingecko.as_deref(),
+    let crypto_prices = crate::services::price_api::get_historical_crypto_prices(
+        db,
         &crypto_map,
         from_day,
         today_start,
@@ -2218,7 +2300,7 @@ pub async fn recalculate_crypto_ticker_history(
 
     // Get the last known price before the recalculation start date to seed the fallback
     // This ensures continuity if the API fails for the first few days
-    let mut last_known_price: Option<(f64, String)> = db.with_conn(|conn| {
+    let mut last_known_price: Option<(Decimal, String)> = db.with_conn(|conn| {
         let res = conn
             .query_row(
                 "SELECT price, currency FROM crypto_value_history 
@@ -2226,7 +2308,7 @@ pub async fn recalculate_crypto_ticker_history(
              ORDER BY recorded_at DESC LIMIT 1",
                 rusqlite::params![ticker, from_day],
                 |row| {
-                    let price: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
+                    let price = money::parse_or_zero(&row.get::<_, String>(0)?);
                     let currency: String = row.get(1)?;
                     Ok((price, currency))
                 },
@@ -2244,7 +2326,7 @@ pub async fn recalculate_crypto_ticker_history(
                     "SELECT price, currency FROM crypto_prices WHERE symbol = ?1",
                     rusqlite::params![ticker],
                     |row| {
-                        let price: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
+                        let price = money::parse_or_zero(&row.get::<_, String>(0)?);
                         let currency: String = row.get(1)?;
                         Ok((price, currency))
                     },
@@ -2254,13 +2336,15 @@ pub async fn recalculate_crypto_ticker_history(
         })?;
     }
 
+    let fx = historical_rates(db, from_day, today_start).await?;
+
     // Calculate and store value for each day
     let ticker_clone = ticker.to_string();
     db.with_conn(move |conn| {
         for day_timestamp in days_to_recalc {
             let quantity = get_crypto_quantity_at_date(conn, &ticker_clone, day_timestamp);
 
-            if quantity <= 0.0 {
+            if quantity <= Decimal::ZERO {
                 conn.execute(
                     "DELETE FROM crypto_value_history WHERE ticker = ?1 AND recorded_at = ?2",
                     rusqlite::params![ticker_clone, day_timestamp],
@@ -2272,8 +2356,9 @@ pub async fn recalculate_crypto_ticker_history(
             let (price, currency) = if let Some(prices) = crypto_prices.get(&ticker_clone) {
                 if let Some(hp) = find_closest_price(prices, day_timestamp) {
                     // Found price in API data, update last known
-                    last_known_price = Some((hp.price, hp.currency.clone()));
-                    (hp.price, hp.currency.clone())
+                    let price = money::from_f64(hp.price);
+                    last_known_price = Some((price, hp.currency.clone()));
+                    (price, hp.currency.clone())
                 } else if let Some((last_price, last_currency)) = &last_known_price {
                     // Gap in API data, use last known
                     (*last_price, last_currency.clone())
@@ -2288,7 +2373,7 @@ pub async fn recalculate_crypto_ticker_history(
                 continue;
             };
 
-            let value_czk = convert_to_czk(quantity * price, &currency);
+            let value_czk = fx.to_czk_decimal(quantity * price, &currency, day_timestamp);
 
             let id = Uuid::new_v4().to_string();
             conn.execute(
@@ -2303,9 +2388,9 @@ pub async fn recalculate_crypto_ticker_history(
                     id,
                     ticker_clone,
                     day_timestamp,
-                    value_czk.to_string(),
-                    quantity.to_string(),
-                    price.to_string(),
+                    money::format(value_czk, "CZK"),
+                    money::format_exact(quantity),
+                    money::format_price(price),
                     currency,
                 ],
             )?;
@@ -2321,6 +2406,31 @@ pub async fn recalculate_crypto_ticker_history(
     Ok(())
 }
 
+/// Sum of the latest value per ticker up to a day in a per-ticker history table
+/// (CZK), summed as decimals in Rust
+fn sum_latest_ticker_values(
+    conn: &rusqlite::Connection,
+    table: &str,
+    portfolio_day: i64,
+) -> Result<Decimal> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT s.value_czk
+         FROM {table} s
+         JOIN (
+             SELECT ticker, MAX(recorded_at) AS max_ts
+             FROM {table}
+             WHERE (recorded_at / 86400) * 86400 <= ?1
+             GROUP BY ticker
+         ) latest ON s.ticker = latest.ticker AND s.recorded_at = latest.max_ts"
+    ))?;
+    let total: Decimal = stmt
+        .query_map([portfolio_day], |row| row.get::<_, String>(0))?
+        .filter_map(|r| r.ok())
+        .map(|value| money::parse_or_zero(&value))
+        .sum();
+    Ok(total)
+}
+
 /// Update only stock investments in portfolio_metrics_history from stock_value_history
 /// Ensures all stock tickers have their history populated before aggregating
 pub async fn update_portfolio_stocks_from_ticker_table(
@@ -2382,27 +2492,15 @@ pub async fn update_portfolio_stocks_from_ticker_table(
             // stock_value_history entry up to this portfolio day. This correctly handles
             // non-trading days (weekends/holidays) by carrying forward Friday's price,
             // rather than doing a partial sum of only the tickers recalculated for that day.
-            let total_investments: f64 = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(CAST(s.value_czk AS REAL)), 0)
-                     FROM stock_value_history s
-                     JOIN (
-                         SELECT ticker, MAX(recorded_at) AS max_ts
-                         FROM stock_value_history
-                         WHERE (recorded_at / 86400) * 86400 <= ?1
-                         GROUP BY ticker
-                     ) latest ON s.ticker = latest.ticker AND s.recorded_at = latest.max_ts",
-                    [portfolio_day],
-                    |row| row.get(0),
-                )
-                .unwrap_or(0.0);
+            let total_investments =
+                sum_latest_ticker_values(conn, "stock_value_history", portfolio_day)?;
 
             // Update only total_investments column
             conn.execute(
                 "UPDATE portfolio_metrics_history
                  SET total_investments = ?2
                  WHERE (recorded_at / 86400) * 86400 = ?1",
-                rusqlite::params![portfolio_day, total_investments.to_string()],
+                rusqlite::params![portfolio_day, money::format(total_investments, "CZK")],
             )?;
         }
         Ok(())
@@ -2468,27 +2566,15 @@ pub async fn update_portfolio_crypto_from_ticker_table(
         for portfolio_day in portfolio_days {
             // Use latest-per-ticker aggregation: for each crypto ticker, find its most recent
             // crypto_value_history entry up to this portfolio day.
-            let total_crypto: f64 = conn
-                .query_row(
-                    "SELECT COALESCE(SUM(CAST(s.value_czk AS REAL)), 0)
-                     FROM crypto_value_history s
-                     JOIN (
-                         SELECT ticker, MAX(recorded_at) AS max_ts
-                         FROM crypto_value_history
-                         WHERE (recorded_at / 86400) * 86400 <= ?1
-                         GROUP BY ticker
-                     ) latest ON s.ticker = latest.ticker AND s.recorded_at = latest.max_ts",
-                    [portfolio_day],
-                    |row| row.get(0),
-                )
-                .unwrap_or(0.0);
+            let total_crypto =
+                sum_latest_ticker_values(conn, "crypto_value_history", portfolio_day)?;
 
             // Update only total_crypto column
             conn.execute(
                 "UPDATE portfolio_metrics_history
                  SET total_crypto = ?2
                  WHERE (recorded_at / 86400) * 86400 = ?1",
-                rusqlite::params![portfolio_day, total_crypto.to_string()],
+                rusqlite::params![portfolio_day, money::format(total_crypto, "CZK")],
             )?;
         }
         Ok(())
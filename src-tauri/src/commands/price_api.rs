@@ -2,10 +2,16 @@
 
 use crate::db::Database;
 use crate::error::Result;
+use crate::models::{
+    InsertPriceProviderPreference, PriceAssetKind, PriceProviderPreference, PriceProviderSettings,
+    TickerCurrency,
+};
 use crate::services::price_api::{
     self, ApiKeys, CoinGeckoSearchResult, CryptoPriceResult, DividendResult,
     StockPriceRefreshResult, StockSearchResult,
 };
+use crate::services::price_providers;
+use crate::services::pricing;
 use std::collections::HashMap;
 use tauri::State;
 
@@ -21,9 +27,8 @@ pub async fn set_api_keys(db: State<'_, Database>, keys: ApiKeys) -> Result<()>
     price_api::set_api_keys(&db, &keys)
 }
 
-/// Refresh stock prices from Yahoo Finance
-/// Batches all tickers into a single request (no rate limiting issues)
-/// No API key required
+/// Refresh stock prices through the price provider chain
+/// (Yahoo Finance first by default, no API key required)
 #[tauri::command]
 pub async fn refresh_stock_prices(
     db: State<'_, Database>,
@@ -44,12 +49,13 @@ pub async fn refresh_stock_prices(
             updated: vec![],
             remaining_tickers: vec![],
             rate_limit_hit: false,
+            failed_tickers: vec![],
         });
     }
 
-    // Fetch prices using Yahoo Finance (batched request, no API key needed)
+    // Fetch prices, falling back to the next provider per ticker
     let result =
-        price_api::refresh_stock_prices_yahoo(&db, tickers, force_refresh.unwrap_or(false)).await?;
+        price_api::refresh_stock_prices(&db, tickers, force_refresh.unwrap_or(false)).await?;
 
     // Update portfolio snapshot
     crate::commands::portfolio::update_todays_snapshot(&db).await?;
@@ -57,13 +63,9 @@ pub async fn refresh_stock_prices(
     Ok(result)
 }
 
-/// Refresh crypto prices from CoinGecko
+/// Refresh crypto prices through the price provider chain (CoinGecko first by default)
 #[tauri::command]
 pub async fn refresh_crypto_prices(db: State<'_, Database>) -> Result<Vec<CryptoPriceResult>> {
-    // Get API key (optional for CoinGecko)
-    let keys = price_api::get_api_keys(&db)?;
-    let api_key = keys.coingecko.as_deref();
-
     // Get all crypto investments with their coingecko IDs
     let id_to_ticker: HashMap<String, String> = db.with_conn(|conn| {
         let mut stmt = conn.prepare(
@@ -85,7 +87,7 @@ pub async fn refresh_crypto_prices(db: State<'_, Database>) -> Result<Vec<Crypto
     }
 
     // Fetch prices
-    let result = price_api::refresh_crypto_prices(&db, api_key, id_to_ticker).await?;
+    let result = price_api::refresh_crypto_prices(&db, id_to_ticker).await?;
 
     // Update portfolio snapshot
     crate::commands::portfolio::update_todays_snapshot(&db).await?;
@@ -133,3 +135,72 @@ pub async fn refresh_dividends(db: State<'_, Database>) -> Result<Vec<DividendRe
 pub async fn search_stock_tickers(query: String) -> Result<Vec<StockSearchResult>> {
     price_api::search_stock_tickers(&query).await
 }
+
+/// Get the provider fallback order and local price file path
+#[tauri::command]
+pub async fn get_price_provider_settings(db: State<'_, Database>) -> Result<PriceProviderSettings> {
+    db.with_conn(price_providers::get_settings)
+}
+
+/// Save the provider fallback order and local price file path
+#[tauri::command]
+pub async fn set_price_provider_settings(
+    db: State<'_, Database>,
+    settings: PriceProviderSettings,
+) -> Result<PriceProviderSettings> {
+    settings.validate()?;
+    db.with_conn(|conn| {
+        price_providers::set_settings(conn, &settings)?;
+        price_providers::get_settings(conn)
+    })
+}
+
+/// Get all per-ticker provider preferences
+#[tauri::command]
+pub async fn get_price_provider_preferences(
+    db: State<'_, Database>,
+) -> Result<Vec<PriceProviderPreference>> {
+    db.with_conn(price_providers::get_preferences)
+}
+
+/// Set the provider tried first for one ticker
+#[tauri::command]
+pub async fn set_price_provider_preference(
+    db: State<'_, Database>,
+    data: InsertPriceProviderPreference,
+) -> Result<PriceProviderPreference> {
+    data.validate()?;
+    db.with_conn(|conn| price_providers::set_preference(conn, &data))
+}
+
+/// Remove a per-ticker provider preference
+#[tauri::command]
+pub async fn delete_price_provider_preference(
+    db: State<'_, Database>,
+    ticker: String,
+    asset_kind: PriceAssetKind,
+) -> Result<()> {
+    db.with_conn(|conn| price_providers::delete_preference(conn, &ticker, asset_kind))
+}
+
+/// Get all stored stock ticker currencies
+#[tauri::command]
+pub async fn get_ticker_currencies(db: State<'_, Database>) -> Result<Vec<TickerCurrency>> {
+    db.with_conn(pricing::get_ticker_currencies)
+}
+
+/// Set the quote currency of a ticker whose provider reports none
+#[tauri::command]
+pub async fn set_ticker_currency(
+    db: State<'_, Database>,
+    ticker: String,
+    currency: String,
+) -> Result<TickerCurrency> {
+    db.with_conn(|conn| pricing::set_manual_ticker_currency(conn, &ticker, &currency))
+}
+
+/// Forget the stored currency of a ticker
+#[tauri::command]
+pub async fn delete_ticker_currency(db: State<'_, Database>, ticker: String) -> Result<()> {
+    db.with_conn(|conn| pricing::delete_ticker_currency(conn, &ticker))
+}
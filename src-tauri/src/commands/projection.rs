@@ -6,9 +6,12 @@
 use crate::db::Database;
 use crate::error::Result;
 use crate::models::{
-    CalculatedDefaults, PortfolioProjection, ProjectionSettings, ProjectionTimelinePoint,
+    BondCashFlow, CalculatedDefaults, PortfolioProjection, ProjectionSettings,
+    ProjectionTimelinePoint,
 };
-use crate::services::currency::convert_to_czk;
+use crate::services::bonds as bond_service;
+use crate::services::loans as loan_service;
+use crate::services::money::{self, Decimal};
 use chrono::{Duration, Utc};
 use serde::Deserialize;
 use tauri::State;
@@ -159,14 +162,14 @@ pub async fn calculate_portfolio_projection(
             let is_final_period = period == total_periods;
             let mut period_contributions = 0.0;
 
-            // Calculate each category
+            // Calculate each category; bond coupons and repayments land in savings
             let savings = project_savings(
                 &current,
                 &settings,
                 years_elapsed,
                 months_elapsed,
                 &mut period_contributions,
-            );
+            ) + bond_cash_received(&current, period_date.timestamp());
             let investments = project_investments(
                 &current,
                 &settings,
@@ -186,6 +189,7 @@ pub async fn calculate_portfolio_projection(
                 &settings,
                 years_elapsed,
                 months_elapsed,
+                period_date.timestamp(),
                 &mut period_contributions,
             );
             let real_estate = project_real_estate(&current, &settings, years_elapsed);
@@ -260,6 +264,14 @@ struct CurrentValues {
     savings_weighted_interest: f64,
     // For bonds yield
     bonds_weighted_yield: f64,
+    // (maturity, market value) of bonds not yet matured (part of `bonds`);
+    // they follow their schedule instead of the growth rate
+    scheduled_bonds: Vec<(i64, f64)>,
+    // Future coupons and repayments of those bonds
+    bond_cash_flows: Vec<BondCashFlow>,
+    // (outstanding principal, balance after each upcoming monthly installment)
+    // of loans with an amortization schedule (part of `total_liabilities`)
+    scheduled_loans: Vec<(f64, Vec<f64>)>,
 }
 
 /// Settings map with defaults
@@ -284,107 +296,122 @@ fn get_current_portfolio_values(
     // Calculate savings
     let mut savings_stmt =
         conn.prepare("SELECT balance, currency, interest_rate FROM bank_accounts")?;
-    let savings_data: Vec<(f64, f64)> = savings_stmt
+    let savings_data: Vec<(Decimal, Decimal)> = savings_stmt
         .query_map([], |row| {
-            let balance: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
+            let balance = money::parse_or_zero(&row.get::<_, String>(0)?);
             let currency: String = row.get(1)?;
-            let rate: f64 = row.get::<_, String>(2)?.parse().unwrap_or(0.0);
-            Ok((convert_to_czk(balance, &currency), rate))
+            let rate = money::parse_or_zero(&row.get::<_, String>(2)?);
+            Ok((money::to_czk(balance, &currency), rate))
         })?
         .filter_map(|r| r.ok())
         .collect();
 
-    let total_savings: f64 = savings_data.iter().map(|(b, _)| b).sum();
+    let total_savings: Decimal = savings_data.iter().map(|(b, _)| b).sum();
 
     // Weighted average interest rate (excluding 0% accounts)
     let non_zero_savings: Vec<_> = savings_data
         .iter()
-        .filter(|(b, r)| *r > 0.0 && *b > 0.0)
+        .filter(|(b, r)| *r > Decimal::ZERO && *b > Decimal::ZERO)
         .collect();
-    let weighted_interest = if non_zero_savings.is_empty() {
-        0.0
+    let total_balance: Decimal = non_zero_savings.iter().map(|(b, _)| b).sum();
+    let weighted_interest = if total_balance > Decimal::ZERO {
+        money::to_f64(non_zero_savings.iter().map(|(b, r)| b * r).sum::<Decimal>() / total_balance)
     } else {
-        let total_balance: f64 = non_zero_savings.iter().map(|(b, _)| b).sum();
-        if total_balance > 0.0 {
-            non_zero_savings.iter().map(|(b, r)| b * r).sum::<f64>() / total_balance
-        } else {
-            0.0
-        }
+        0.0
     };
 
-    // Calculate bonds with weighted yield
-    let mut bonds_stmt =
-        conn.prepare("SELECT coupon_value, quantity, currency, interest_rate FROM bonds")?;
-    let bonds_data: Vec<(f64, f64)> = bonds_stmt
-        .query_map([], |row| {
-            let value: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-            let quantity: f64 = row.get::<_, String>(1)?.parse().unwrap_or(1.0);
-            let currency: String = row.get(2)?;
-            let yield_rate: f64 = row.get::<_, String>(3)?.parse().unwrap_or(0.0);
-            Ok((convert_to_czk(value * quantity, &currency), yield_rate))
-        })?
-        .filter_map(|r| r.ok())
-        .collect();
+    // Calculate bonds (market value) with weighted yield to maturity, or the
+    // coupon rate for bonds without a maturity date. Bonds that have not matured
+    // yet pay their coupons and face value on known dates.
+    let now = Utc::now().timestamp();
+    let mut bonds_data: Vec<(Decimal, f64)> = Vec::new();
+    let mut scheduled_bonds = Vec::new();
+    let mut bond_cash_flows = Vec::new();
+    for bond in bond_service::get_bonds(conn)? {
+        let analytics = bond_service::analytics(&bond, now);
+        let yield_rate = analytics
+            .yield_to_maturity
+            .unwrap_or_else(|| money::to_f64(money::parse_or_zero(&bond.interest_rate)));
+        let value = money::to_czk(bond_service::market_value(&bond, now), &bond.currency);
+        bonds_data.push((value, yield_rate));
+
+        if let Some(maturity) = bond.maturity_date.filter(|m| *m > now) {
+            scheduled_bonds.push((maturity, money::to_f64(value)));
+            let schedule = bond_service::schedule(&bond, now);
+            bond_cash_flows.extend(schedule.cash_flows.into_iter().filter(|f| f.date > now));
+        }
+    }
 
-    let total_bonds: f64 = bonds_data.iter().map(|(v, _)| v).sum();
+    let total_bonds: Decimal = bonds_data.iter().map(|(v, _)| v).sum();
 
     // Weighted average bond yield (excluding 0% bonds)
     let non_zero_bonds: Vec<_> = bonds_data
         .iter()
-        .filter(|(v, y)| *y > 0.0 && *v > 0.0)
+        .filter(|(v, y)| *y > 0.0 && *v > Decimal::ZERO)
         .collect();
-    let weighted_bond_yield = if non_zero_bonds.is_empty() {
-        0.0
+    let total_value: Decimal = non_zero_bonds.iter().map(|(v, _)| v).sum();
+    let weighted_bond_yield = if total_value > Decimal::ZERO {
+        non_zero_bonds
+            .iter()
+            .map(|(v, y)| money::to_f64(*v) * y)
+            .sum::<f64>()
+            / money::to_f64(total_value)
     } else {
-        let total_value: f64 = non_zero_bonds.iter().map(|(v, _)| v).sum();
-        if total_value > 0.0 {
-            non_zero_bonds.iter().map(|(v, y)| v * y).sum::<f64>() / total_value
-        } else {
-            0.0
-        }
+        0.0
     };
 
-    // Calculate loans
-    let mut loans_stmt =
-        conn.prepare("SELECT principal, currency, interest_rate, monthly_payment FROM loans")?;
-    let loans_data: Vec<(f64, f64, f64)> = loans_stmt
-        .query_map([], |row| {
-            let principal: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-            let currency: String = row.get(1)?;
-            let rate: f64 = row.get::<_, String>(2)?.parse().unwrap_or(0.0);
-            let payment: f64 = row.get::<_, String>(3)?.parse().unwrap_or(0.0);
-            Ok((
-                convert_to_czk(principal, &currency),
-                rate,
-                convert_to_czk(payment, &currency),
-            ))
-        })?
-        .filter_map(|r| r.ok())
-        .collect();
+    // Calculate loans (outstanding principal following each amortization schedule)
+    let mut loans_data: Vec<(Decimal, Decimal, Decimal)> = Vec::new();
+    let mut scheduled_loans = Vec::new();
+    let loans = loan_service::get_loans(conn)?;
+    let mut payments_by_loan = loan_service::payments_by_loan(conn, &loans)?;
+    for loan in loans {
+        let payments = payments_by_loan.remove(&loan.id).unwrap_or_default();
+        let schedule = loan_service::schedule(&loan, &payments, now);
+        let principal = money::to_czk(
+            money::parse_or_zero(&schedule.outstanding_principal),
+            &loan.currency,
+        );
+        let rate = money::parse_or_zero(&loan.interest_rate);
+        let payment = money::parse_or_zero(&loan.monthly_payment);
+        loans_data.push((principal, rate, money::to_czk(payment, &loan.currency)));
+        let upcoming: Vec<f64> = schedule
+            .installments
+            .iter()
+            .filter(|installment| installment.date > now)
+            .map(|installment| {
+                let balance = money::parse_or_zero(&installment.balance);
+                money::to_f64(money::to_czk(balance, &loan.currency))
+            })
+            .collect();
+        scheduled_loans.push((money::to_f64(principal), upcoming));
+    }
 
-    let total_liabilities: f64 = loans_data.iter().map(|(p, _, _)| p).sum();
-    let total_monthly_payment: f64 = loans_data.iter().map(|(_, _, m)| m).sum();
+    let total_liabilities: Decimal = loans_data.iter().map(|(p, _, _)| p).sum();
+    let total_monthly_payment: Decimal = loans_data.iter().map(|(_, _, m)| m).sum();
 
     // Weighted avg interest on loans for simplicity
-    let weighted_loan_interest = if total_liabilities > 0.0 {
-        loans_data
-            .iter()
-            .map(|(p, r, _)| p * r / 100.0)
-            .sum::<f64>()
-            / total_liabilities
+    let weighted_loan_interest = if total_liabilities > Decimal::ZERO {
+        money::to_f64(
+            loans_data
+                .iter()
+                .map(|(p, r, _)| p * r / Decimal::ONE_HUNDRED)
+                .sum::<Decimal>()
+                / total_liabilities,
+        )
     } else {
         0.0
     };
 
     // Calculate investments
-    let mut total_investments = 0.0;
+    let mut total_investments = Decimal::ZERO;
     let mut inv_stmt = conn.prepare("SELECT ticker, quantity FROM stock_investments")?;
     let investments = inv_stmt.query_map([], |row| {
         Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
     })?;
 
     for inv in investments.filter_map(|r| r.ok()) {
-        let qty: f64 = inv.1.parse().unwrap_or(0.0);
+        let qty = money::parse_or_zero(&inv.1);
         let price_data: rusqlite::Result<(Option<String>, Option<String>)> = conn.query_row(
             "SELECT COALESCE(
                 (SELECT price FROM stock_price_overrides WHERE ticker = ?1),
@@ -399,20 +426,20 @@ fn get_current_portfolio_values(
         );
 
         if let Ok((Some(price_str), Some(currency))) = price_data {
-            let price: f64 = price_str.parse().unwrap_or(0.0);
-            total_investments += convert_to_czk(price * qty, &currency);
+            let price = money::parse_or_zero(&price_str);
+            total_investments += money::to_czk(price * qty, &currency);
         }
     }
 
     // Calculate crypto
-    let mut total_crypto = 0.0;
+    let mut total_crypto = Decimal::ZERO;
     let mut crypto_stmt = conn.prepare("SELECT ticker, quantity FROM crypto_investments")?;
     let cryptos = crypto_stmt.query_map([], |row| {
         Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
     })?;
 
     for crypto in cryptos.filter_map(|r| r.ok()) {
-        let qty: f64 = crypto.1.parse().unwrap_or(0.0);
+        let qty = money::parse_or_zero(&crypto.1);
         let price_data: rusqlite::Result<(Option<String>, Option<String>)> = conn.query_row(
             "SELECT price, currency FROM crypto_prices WHERE symbol = ?1",
             [&crypto.0],
@@ -420,15 +447,15 @@ fn get_current_portfolio_values(
         );
 
         if let Ok((Some(price_str), Some(currency))) = price_data {
-            let price: f64 = price_str.parse().unwrap_or(0.0);
-            total_crypto += convert_to_czk(price * qty, &currency);
+            let price = money::parse_or_zero(&price_str);
+            total_crypto += money::to_czk(price * qty, &currency);
         }
     }
 
     // Calculate real estate (respecting exclude personal RE flag)
     let mut re_stmt =
         conn.prepare("SELECT type, market_price, market_price_currency FROM real_estate")?;
-    let mut total_real_estate = 0.0;
+    let mut total_real_estate = Decimal::ZERO;
     let re_rows = re_stmt.query_map([], |row| {
         Ok((
             row.get::<_, String>(0)?,
@@ -438,8 +465,7 @@ fn get_current_portfolio_values(
     })?;
 
     for row in re_rows.filter_map(|r| r.ok()) {
-        let price: f64 = row.1.parse().unwrap_or(0.0);
-        let price_czk = convert_to_czk(price, &row.2);
+        let price_czk = money::to_czk(money::parse_or_zero(&row.1), &row.2);
         // Skip personal properties if exclude flag is set
         if exclude_personal_real_estate && row.0 == "personal" {
             continue;
@@ -450,12 +476,12 @@ fn get_current_portfolio_values(
     // Calculate other assets
     let mut other_stmt =
         conn.prepare("SELECT quantity, market_price, currency FROM other_assets")?;
-    let total_other: f64 = other_stmt
+    let total_other: Decimal = other_stmt
         .query_map([], |row| {
-            let qty: f64 = row.get::<_, String>(0)?.parse().unwrap_or(0.0);
-            let price: f64 = row.get::<_, String>(1)?.parse().unwrap_or(0.0);
+            let qty = money::parse_or_zero(&row.get::<_, String>(0)?);
+            let price = money::parse_or_zero(&row.get::<_, String>(1)?);
             let currency: String = row.get(2)?;
-            Ok(convert_to_czk(qty * price, &currency))
+            Ok(money::to_czk(qty * price, &currency))
         })?
         .filter_map(|r| r.ok())
         .sum();
@@ -468,19 +494,22 @@ fn get_current_portfolio_values(
         + total_other;
 
     Ok(CurrentValues {
-        savings: total_savings,
-        investments: total_investments,
-        crypto: total_crypto,
-        bonds: total_bonds,
-        real_estate: total_real_estate,
-        other_assets: total_other,
-        total_assets,
-        total_liabilities,
-        net_worth: total_assets - total_liabilities,
-        loan_monthly_payment: total_monthly_payment,
+        savings: money::to_f64(total_savings),
+        investments: money::to_f64(total_investments),
+        crypto: money::to_f64(total_crypto),
+        bonds: money::to_f64(total_bonds),
+        real_estate: money::to_f64(total_real_estate),
+        other_assets: money::to_f64(total_other),
+        total_assets: money::to_f64(total_assets),
+        total_liabilities: money::to_f64(total_liabilities),
+        net_worth: money::to_f64(total_assets - total_liabilities),
+        loan_monthly_payment: money::to_f64(total_monthly_payment),
         loan_interest_rate: weighted_loan_interest,
         savings_weighted_interest: weighted_interest,
         bonds_weighted_yield: weighted_bond_yield,
+        scheduled_bonds,
+        scheduled_loans,
+        bond_cash_flows,
     })
 }
 
@@ -632,11 +661,22 @@ fn project_crypto(
     base + contrib_value
 }
 
+/// Coupons and repayments of scheduled bonds paid out by `until`
+fn bond_cash_received(current: &CurrentValues, until: i64) -> f64 {
+    current
+        .bond_cash_flows
+        .iter()
+        .filter(|f| f.date <= until)
+        .map(|f| f.amount_czk)
+        .sum()
+}
+
 fn project_bonds(
     current: &CurrentValues,
     settings: &SettingsMap,
     years: f64,
     months: i32,
+    until: i64,
     contributions: &mut f64,
 ) -> f64 {
     // Use weighted avg yield from bonds, or override if set
@@ -647,7 +687,14 @@ fn project_bonds(
     };
 
     let growth_factor = (1.0 + rate).powf(years);
-    let base = current.bonds * growth_factor;
+    let scheduled: f64 = current.scheduled_bonds.iter().map(|(_, v)| v).sum();
+    let outstanding: f64 = current
+        .scheduled_bonds
+        .iter()
+        .filter(|(maturity, _)| *maturity > until)
+        .map(|(_, v)| v)
+        .sum();
+    let base = (current.bonds - scheduled) * growth_factor + outstanding;
 
     let monthly_contrib = settings.bonds_contribution;
     let total_contrib = monthly_contrib * months as f64;
@@ -694,14 +741,29 @@ fn project_other_assets(
 }
 
 fn project_loans(current: &CurrentValues, months: i32) -> f64 {
-    // Simple amortization: reduce principal by monthly payments
-    // This is a simplified model - actual amortization depends on specific loan terms
-    if current.total_liabilities <= 0.0 || current.loan_monthly_payment <= 0.0 {
-        return current.total_liabilities;
+    // Loans with a schedule follow their balance after `months` installments
+    let scheduled: f64 = current.scheduled_loans.iter().map(|(b, _)| b).sum();
+    let scheduled_balance: f64 = current
+        .scheduled_loans
+        .iter()
+        .map(|(balance, upcoming)| match months {
+            m if m <= 0 => *balance,
+            m => upcoming
+                .get(m as usize - 1)
+                .or(upcoming.last())
+                .copied()
+                .unwrap_or(*balance),
+        })
+        .sum();
+
+    // Simple amortization of the rest: reduce principal by monthly payments
+    let rest = current.total_liabilities - scheduled;
+    if rest <= 0.0 || current.loan_monthly_payment <= 0.0 {
+        return scheduled_balance + rest.max(0.0);
     }
 
     let monthly_rate = current.loan_interest_rate / 12.0;
-    let mut balance = current.total_liabilities;
+    let mut balance = rest;
 
     for _ in 0..months {
         if balance <= 0.0 {
@@ -712,7 +774,7 @@ fn project_loans(current: &CurrentValues, months: i32) -> f64 {
         balance -= principal_payment;
     }
 
-    balance.max(0.0)
+    scheduled_balance + balance.max(0.0)
 }
 
 #[cfg(test)]
@@ -735,6 +797,9 @@ mod tests {
             loan_interest_rate: 0.05, // 5% annual
             savings_weighted_interest: 3.0,
             bonds_weighted_yield: 5.0,
+            scheduled_bonds: Vec::new(),
+            bond_cash_flows: Vec::new(),
+            scheduled_loans: Vec::new(),
         }
     }
 
@@ -965,7 +1030,7 @@ mod tests {
         settings.bonds_contribution = 0.0;
 
         let mut contributions = 0.0;
-        let result = project_bonds(&current, &settings, 1.0, 12, &mut contributions);
+        let result = project_bonds(&current, &settings, 1.0, 12, 0, &mut contributions);
 
         // 100,000 * 1.06 = 106,000
         assert!(
@@ -986,7 +1051,7 @@ mod tests {
         settings.bonds_contribution = 0.0;
 
         let mut contributions = 0.0;
-        let result = project_bonds(&current, &settings, 1.0, 12, &mut contributions);
+        let result = project_bonds(&current, &settings, 1.0, 12, 0, &mut contributions);
 
         // 100,000 * 1.04 = 104,000
         assert!(
@@ -996,6 +1061,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scheduled_bonds_follow_their_cash_flows() {
+        let flow = |date: i64, kind: &str, amount_czk: f64| BondCashFlow {
+            bond_id: "b1".to_string(),
+            bond_name: "Bond".to_string(),
+            date,
+            kind: kind.to_string(),
+            amount: amount_czk.to_string(),
+            currency: "CZK".to_string(),
+            amount_czk,
+        };
+        let mut current = make_test_current();
+        current.bonds = 150_000.0;
+        current.bonds_weighted_yield = 4.0;
+        current.scheduled_bonds = vec![(200, 50_000.0)];
+        current.bond_cash_flows = vec![
+            flow(100, "coupon", 2_000.0),
+            flow(200, "coupon", 2_000.0),
+            flow(200, "principal", 50_000.0),
+        ];
+        let mut settings = make_test_settings();
+        settings.bonds_growth = 0.0;
+        settings.bonds_contribution = 0.0;
+
+        // Before maturity: unscheduled part grows, scheduled face unchanged
+        let mut contributions = 0.0;
+        let before = project_bonds(&current, &settings, 1.0, 12, 150, &mut contributions);
+        assert!((before - 154_000.0).abs() < 1.0, "got {}", before);
+        assert_eq!(bond_cash_received(&current, 150), 2_000.0);
+
+        // After maturity the face value has been repaid into savings
+        let after = project_bonds(&current, &settings, 1.0, 12, 250, &mut contributions);
+        assert!((after - 104_000.0).abs() < 1.0, "got {}", after);
+        assert_eq!(bond_cash_received(&current, 250), 54_000.0);
+    }
+
     // =====================================================================
     // PROJECT_REAL_ESTATE TESTS
     // =====================================================================
@@ -1111,6 +1212,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scheduled_loans_follow_their_installments() {
+        let mut current = make_test_current();
+        current.total_liabilities = 100_000.0;
+        current.loan_monthly_payment = 0.0;
+        current.scheduled_loans = vec![(100_000.0, vec![90_000.0, 80_000.0, 0.0])];
+
+        assert!((project_loans(&current, 0) - 100_000.0).abs() < 0.01);
+        assert!((project_loans(&current, 2) - 80_000.0).abs() < 0.01);
+        // Paid off after the last installment
+        assert!(project_loans(&current, 24).abs() < 0.01);
+    }
+
     // =====================================================================
     // INTEGRATION TESTS
     // =====================================================================
@@ -1127,7 +1241,7 @@ mod tests {
         contributions = 0.0;
         let crypto = project_crypto(&current, &settings, 1.0, 12, &mut contributions);
         contributions = 0.0;
-        let bonds = project_bonds(&current, &settings, 1.0, 12, &mut contributions);
+        let bonds = project_bonds(&current, &settings, 1.0, 12, 0, &mut contributions);
         let real_estate = project_real_estate(&current, &settings, 1.0);
         contributions = 0.0;
         let other = project_other_assets(&current, &settings, 1.0, 12, &mut contributions);
@@ -1152,7 +1266,7 @@ mod tests {
         let savings = project_savings(&current, &settings, 1.0, 12, &mut contributions);
         let investments = project_investments(&current, &settings, 1.0, 12, &mut contributions);
         let crypto = project_crypto(&current, &settings, 1.0, 12, &mut contributions);
-        let bonds = project_bonds(&current, &settings, 1.0, 12, &mut contributions);
+        let bonds = project_bonds(&current, &settings, 1.0, 12, 0, &mut contributions);
         let real_estate = project_real_estate(&current, &settings, 1.0);
         let other = project_other_assets(&current, &settings, 1.0, 12, &mut contributions);
         let loans = project_loans(&current, 12);
@@ -3,10 +3,14 @@
 use crate::db::Database;
 use crate::error::{AppError, Result};
 use crate::models::{
-    InsertPhotoBatch, InsertRealEstate, InsertRealEstateDocument, InsertRealEstateOneTimeCost,
-    InsurancePolicy, Loan, RealEstate, RealEstateDocument, RealEstateOneTimeCost, RealEstatePhoto,
-    RealEstatePhotoBatch, UpdatePhotoBatch,
+    BankTransaction, InsertPhotoBatch, InsertRealEstate, InsertRealEstateDocument,
+    InsertRealEstateOneTimeCost, InsertRealEstateValuation, InsurancePolicy, Loan, RealEstate,
+    RealEstateDocument, RealEstateOneTimeCost, RealEstatePhoto, RealEstatePhotoBatch,
+    RealEstateValuation, RealEstateValueHistory, RentalPerformance, UpdatePhotoBatch,
 };
+use crate::services::loans as loan_service;
+use crate::services::money;
+use crate::services::real_estate as real_estate_service;
 use std::fs;
 use std::path::PathBuf;
 use tauri::{Manager, State};
@@ -15,84 +19,13 @@ use uuid::Uuid;
 /// Get all real estate
 #[tauri::command]
 pub async fn get_all_real_estate(db: State<'_, Database>) -> Result<Vec<RealEstate>> {
-    db.with_conn(|conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, name, address, type, purchase_price, purchase_price_currency,
-                    market_price, market_price_currency, monthly_rent, monthly_rent_currency,
-                    recurring_costs, photos, notes, created_at, updated_at
-             FROM real_estate ORDER BY name",
-        )?;
-
-        let properties = stmt
-            .query_map([], |row| {
-                let rc_json: String = row.get(10)?;
-                let photos_json: String = row.get(11)?;
-
-                Ok(RealEstate {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    address: row.get(2)?,
-                    property_type: row.get(3)?,
-                    purchase_price: row.get(4)?,
-                    purchase_price_currency: row.get(5)?,
-                    market_price: row.get(6)?,
-                    market_price_currency: row.get(7)?,
-                    monthly_rent: row.get(8)?,
-                    monthly_rent_currency: row.get(9)?,
-                    recurring_costs: serde_json::from_str(&rc_json).unwrap_or_default(),
-                    photos: serde_json::from_str(&photos_json).unwrap_or_default(),
-                    notes: row.get(12)?,
-                    created_at: row.get(13)?,
-                    updated_at: row.get(14)?,
-                })
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
-
-        Ok(properties)
-    })
+    db.with_conn(real_estate_service::get_properties)
 }
 
 /// Get single real estate
 #[tauri::command]
 pub async fn get_real_estate(db: State<'_, Database>, id: String) -> Result<Option<RealEstate>> {
-    db.with_conn(|conn| {
-        let result = conn.query_row(
-            "SELECT id, name, address, type, purchase_price, purchase_price_currency,
-                    market_price, market_price_currency, monthly_rent, monthly_rent_currency,
-                    recurring_costs, photos, notes, created_at, updated_at
-             FROM real_estate WHERE id = ?1",
-            [&id],
-            |row| {
-                let rc_json: String = row.get(10)?;
-                let photos_json: String = row.get(11)?;
-
-                Ok(RealEstate {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    address: row.get(2)?,
-                    property_type: row.get(3)?,
-                    purchase_price: row.get(4)?,
-                    purchase_price_currency: row.get(5)?,
-                    market_price: row.get(6)?,
-                    market_price_currency: row.get(7)?,
-                    monthly_rent: row.get(8)?,
-                    monthly_rent_currency: row.get(9)?,
-                    recurring_costs: serde_json::from_str(&rc_json).unwrap_or_default(),
-                    photos: serde_json::from_str(&photos_json).unwrap_or_default(),
-                    notes: row.get(12)?,
-                    created_at: row.get(13)?,
-                    updated_at: row.get(14)?,
-                })
-            },
-        );
-
-        match result {
-            Ok(re) => Ok(Some(re)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
-    })
+    db.with_conn(|conn| real_estate_service::get_property(conn, &id))
 }
 
 /// Create real estate
@@ -113,8 +46,8 @@ pub async fn create_real_estate(
         conn.execute(
             "INSERT INTO real_estate (id, name, address, type, purchase_price, purchase_price_currency,
              market_price, market_price_currency, monthly_rent, monthly_rent_currency,
-             recurring_costs, photos, notes, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?14)",
+             recurring_costs, photos, notes, created_at, updated_at, purchase_date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?14, ?15)",
             rusqlite::params![
                 id,
                 data.name,
@@ -130,6 +63,7 @@ pub async fn create_real_estate(
                 photos_json,
                 data.notes,
                 now,
+                data.purchase_date,
             ],
         )?;
         Ok(())
@@ -158,6 +92,21 @@ pub async fn update_real_estate(
             .and_then(|rc| serde_json::to_string(&rc).ok());
         let photos_json = data.photos.and_then(|p| serde_json::to_string(&p).ok());
 
+        // An edited market price becomes a dated valuation
+        let previous = real_estate_service::get_property(conn, &id)?
+            .ok_or_else(|| AppError::NotFound("Real estate not found".into()))?;
+        if let Some(price) = data.market_price.as_deref() {
+            let currency = data
+                .market_price_currency
+                .as_deref()
+                .unwrap_or(&previous.market_price_currency);
+            if money::parse(price) != money::parse(&previous.market_price)
+                || currency != previous.market_price_currency
+            {
+                real_estate_service::record_market_price(conn, &previous, price, currency, now)?;
+            }
+        }
+
         conn.execute(
             "UPDATE real_estate SET name = ?1, address = ?2, type = ?3,
              purchase_price = COALESCE(?4, purchase_price),
@@ -166,8 +115,9 @@ pub async fn update_real_estate(
              market_price_currency = COALESCE(?7, market_price_currency),
              monthly_rent = ?8, monthly_rent_currency = ?9,
              recurring_costs = COALESCE(?10, recurring_costs),
-             photos = COALESCE(?11, photos), notes = ?12, updated_at = ?13
-             WHERE id = ?14",
+             photos = COALESCE(?11, photos), notes = ?12, updated_at = ?13,
+             purchase_date = ?14
+             WHERE id = ?15",
             rusqlite::params![
                 data.name,
                 data.address,
@@ -182,6 +132,7 @@ pub async fn update_real_estate(
                 photos_json,
                 data.notes,
                 now,
+                data.purchase_date,
                 id
             ],
         )?;
@@ -379,29 +330,16 @@ pub async fn get_real_estate_loans(
     real_estate_id: String,
 ) -> Result<Vec<Loan>> {
     db.with_conn(|conn| {
-        let mut stmt = conn.prepare(
-            "SELECT l.id, l.name, l.principal, l.currency, l.interest_rate, l.interest_rate_validity_date,
-                    l.monthly_payment, l.start_date, l.end_date, l.created_at, l.updated_at
-             FROM loans l
-             INNER JOIN real_estate_loans rel ON l.id = rel.loan_id
-             WHERE rel.real_estate_id = ?1"
-        )?;
-
-        let loans = stmt.query_map([&real_estate_id], |row| {
-            Ok(Loan {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                principal: row.get(2)?,
-                currency: row.get(3)?,
-                interest_rate: row.get(4)?,
-                interest_rate_validity_date: row.get(5)?,
-                monthly_payment: row.get(6)?,
-                start_date: row.get(7)?,
-                end_date: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })?.filter_map(|r| r.ok()).collect();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM loans
+             WHERE id IN (SELECT loan_id FROM real_estate_loans WHERE real_estate_id = ?1)",
+            loan_service::LOAN_COLUMNS
+        ))?;
+
+        let loans = stmt
+            .query_map([&real_estate_id], loan_service::loan_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
 
         Ok(loans)
     })
@@ -497,6 +435,100 @@ pub async fn get_real_estate_insurances(
 // Photo Batch Commands
 // ============================================================================
 
+/// Get valuations of a property, oldest first
+#[tauri::command]
+pub async fn get_real_estate_valuations(
+    db: State<'_, Database>,
+    real_estate_id: String,
+) -> Result<Vec<RealEstateValuation>> {
+    db.with_conn(|conn| real_estate_service::get_valuations(conn, &real_estate_id))
+}
+
+/// Add a dated valuation (appraisal, price index, listing or manual estimate)
+#[tauri::command]
+pub async fn add_real_estate_valuation(
+    db: State<'_, Database>,
+    data: InsertRealEstateValuation,
+) -> Result<RealEstateValuation> {
+    // Validate inputs at the trust boundary
+    data.validate()?;
+
+    db.with_conn(|conn| real_estate_service::add_valuation(conn, &data))
+}
+
+/// Delete a valuation
+#[tauri::command]
+pub async fn delete_real_estate_valuation(
+    db: State<'_, Database>,
+    valuation_id: String,
+) -> Result<()> {
+    db.with_conn(|conn| real_estate_service::delete_valuation(conn, &valuation_id))
+}
+
+/// Get value and equity history with appreciation since purchase
+#[tauri::command]
+pub async fn get_real_estate_value_history(
+    db: State<'_, Database>,
+    real_estate_id: String,
+) -> Result<RealEstateValueHistory> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| real_estate_service::value_history(conn, &real_estate_id, now))
+}
+
+/// Get bank transactions linked to real estate
+#[tauri::command]
+pub async fn get_real_estate_transactions(
+    db: State<'_, Database>,
+    real_estate_id: String,
+) -> Result<Vec<BankTransaction>> {
+    db.with_conn(|conn| real_estate_service::get_linked_transactions(conn, &real_estate_id))
+}
+
+/// Link bank transactions (rent, repairs, fees) to real estate
+#[tauri::command]
+pub async fn link_transactions_to_real_estate(
+    db: State<'_, Database>,
+    real_estate_id: String,
+    transaction_ids: Vec<String>,
+) -> Result<()> {
+    db.with_conn(|conn| {
+        real_estate_service::link_transactions(conn, &real_estate_id, &transaction_ids)
+    })
+}
+
+/// Unlink a bank transaction from real estate
+#[tauri::command]
+pub async fn unlink_transaction_from_real_estate(
+    db: State<'_, Database>,
+    real_estate_id: String,
+    transaction_id: String,
+) -> Result<()> {
+    db.with_conn(|conn| {
+        real_estate_service::unlink_transaction(conn, &real_estate_id, &transaction_id)
+    })
+}
+
+/// Get rental performance of all investment properties
+#[tauri::command]
+pub async fn get_rental_performance(db: State<'_, Database>) -> Result<Vec<RentalPerformance>> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| real_estate_service::get_performance(conn, now))
+}
+
+/// Get rental performance of a property
+#[tauri::command]
+pub async fn get_real_estate_performance(
+    db: State<'_, Database>,
+    real_estate_id: String,
+) -> Result<RentalPerformance> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        let property = real_estate_service::get_property(conn, &real_estate_id)?
+            .ok_or_else(|| AppError::NotFound("Real estate not found".into()))?;
+        real_estate_service::performance(conn, &property, now)
+    })
+}
+
 /// Get photos directory for a real estate property
 fn get_photos_dir(
     app_handle: &tauri::AppHandle,
@@ -3,11 +3,16 @@
 use crate::db::Database;
 use crate::error::{AppError, Result};
 use crate::models::{
-    InsertSavingsAccount, InsertSavingsAccountZone, SavingsAccount, SavingsAccountZone,
+    InsertSavingsAccount, InsertSavingsAccountZone, InterestAccrual, InterestPayment,
+    SavingsAccount, SavingsAccountZone,
 };
+use crate::services::interest as interest_service;
 use tauri::State;
 use uuid::Uuid;
 
+/// Default calendar range when `to_ts` is not given
+const DEFAULT_CALENDAR_DAYS: i64 = 365;
+
 /// Get all savings accounts
 /// Get all savings accounts
 #[tauri::command]
@@ -306,3 +311,28 @@ pub async fn delete_account_zone(db: State<'_, Database>, zone_id: String) -> Re
         Ok(())
     })
 }
+
+/// Interest accrued today on every savings account and term deposit
+#[tauri::command]
+pub async fn get_interest_accruals(db: State<'_, Database>) -> Result<Vec<InterestAccrual>> {
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        Ok(interest_service::interest_accounts(conn)?
+            .iter()
+            .map(|(account, zones)| interest_service::accrual(account, zones, now))
+            .collect())
+    })
+}
+
+/// Interest credits and term deposit payouts between `from_ts` (default now)
+/// and `to_ts` (default a year later)
+#[tauri::command]
+pub async fn get_interest_calendar(
+    db: State<'_, Database>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<InterestPayment>> {
+    let from = from_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let to = to_ts.unwrap_or(from + DEFAULT_CALENDAR_DAYS * 86400);
+    db.with_conn(|conn| interest_service::calendar(conn, from, to))
+}
@@ -214,7 +214,7 @@ pub async fn delete_stock_tag(db: State<'_, Database>, id: String) -> Result<()>
         if changes == 0 {
             return Err(AppError::NotFound("Stock tag not found".into()));
         }
-        Ok(())
+        crate::services::allocation::delete_tag_target(conn, &id)
     })
 }
 
@@ -65,6 +65,25 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         ("034_add_exchange_rates_table", MIGRATION_034),
         ("035_add_stale_data_columns", MIGRATION_035),
         ("036_add_mcp_server_enabled", MIGRATION_036),
+        ("037_add_stock_dividends", MIGRATION_037),
+        ("038_add_broker_import", MIGRATION_038),
+        ("039_add_crypto_external_id", MIGRATION_039),
+        ("040_add_crypto_wallet", MIGRATION_040),
+        ("041_add_brokerage_accounts", MIGRATION_041),
+        ("042_add_allocation_targets", MIGRATION_042),
+        ("043_add_benchmarks", MIGRATION_043),
+        ("044_add_price_provider_preferences", MIGRATION_044),
+        ("045_add_stock_ticker_currencies", MIGRATION_045),
+        ("046_add_exchange_rate_history", MIGRATION_046),
+        ("047_add_manual_exchange_rates", MIGRATION_047),
+        ("048_add_bond_coupon_terms", MIGRATION_048),
+        ("049_add_bond_prices", MIGRATION_049),
+        ("050_add_loan_repayment_terms", MIGRATION_050),
+        ("051_add_deposit_interest_terms", MIGRATION_051),
+        ("052_add_savings_goals", MIGRATION_052),
+        ("053_add_real_estate_valuations", MIGRATION_053),
+        ("054_add_real_estate_transactions", MIGRATION_054),
+        ("055_add_stock_underlying_currencies", MIGRATION_055),
     ];
 
     for (name, sql) in migrations {
@@ -1167,3 +1186,270 @@ ALTER TABLE crypto_value_history ADD COLUMN is_stale INTEGER NOT NULL DEFAULT 0;
 const MIGRATION_036: &str = r#"
 ALTER TABLE user_profile ADD COLUMN mcp_server_enabled INTEGER NOT NULL DEFAULT 0;
 "#;
+
+/// Migration 037: Add stock_dividends table for received dividend payments
+/// Each row is one payout with the tax withheld at source, used for the foreign tax credit report
+const MIGRATION_037: &str = r#"
+CREATE TABLE IF NOT EXISTS stock_dividends (
+    id TEXT PRIMARY KEY,
+    ticker TEXT NOT NULL,
+    payment_date INTEGER NOT NULL,
+    gross_amount TEXT NOT NULL,
+    withholding_tax TEXT NOT NULL DEFAULT '0',
+    currency TEXT NOT NULL,
+    source_country TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_dividends_ticker ON stock_dividends(ticker);
+CREATE INDEX IF NOT EXISTS idx_stock_dividends_date ON stock_dividends(payment_date);
+"#;
+
+/// Migration 038: Broker statement import support
+/// external_id dedupes re-imported broker rows; isin_tickers caches ISIN resolution
+const MIGRATION_038: &str = r#"
+ALTER TABLE investment_transactions ADD COLUMN external_id TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_investment_transactions_external_id
+    ON investment_transactions(external_id) WHERE external_id IS NOT NULL;
+
+ALTER TABLE stock_dividends ADD COLUMN external_id TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stock_dividends_external_id
+    ON stock_dividends(external_id) WHERE external_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS isin_tickers (
+    isin TEXT PRIMARY KEY,
+    ticker TEXT NOT NULL,
+    name TEXT,
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+-- Broker fees and currency conversions (not tied to a position)
+CREATE TABLE IF NOT EXISTS broker_cash_movements (
+    id TEXT PRIMARY KEY,
+    broker TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    ticker TEXT,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    counter_amount TEXT,
+    counter_currency TEXT,
+    occurred_at INTEGER NOT NULL,
+    external_id TEXT UNIQUE,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX IF NOT EXISTS idx_broker_cash_movements_date ON broker_cash_movements(occurred_at);
+"#;
+
+/// Migration 039: Add external_id to crypto_transactions
+/// Exchange imports store the exchange's trade/ledger ID to skip re-imported rows
+const MIGRATION_039: &str = r#"
+ALTER TABLE crypto_transactions ADD COLUMN external_id TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_crypto_transactions_external_id
+    ON crypto_transactions(external_id) WHERE external_id IS NOT NULL;
+"#;
+
+/// Migration 040: Add wallet to crypto_transactions
+/// Tracks which wallet/exchange holds the coins for per-wallet holdings and transfers
+const MIGRATION_040: &str = r#"
+ALTER TABLE crypto_transactions ADD COLUMN wallet TEXT;
+"#;
+
+/// Migration 041: Add brokerage accounts owning stock transactions
+/// The same ticker can be held in several accounts with separate positions and cost bases
+const MIGRATION_041: &str = r#"
+CREATE TABLE IF NOT EXISTS brokerage_accounts (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    broker TEXT,
+    account_type TEXT NOT NULL DEFAULT 'taxable',
+    currency TEXT NOT NULL DEFAULT 'CZK',
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+ALTER TABLE investment_transactions ADD COLUMN account_id TEXT REFERENCES brokerage_accounts(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_investment_transactions_account ON investment_transactions(account_id);
+"#;
+
+/// Migration 042: Add target allocation weights
+/// Targets per stock tag or asset class with a tolerance band, used for drift and rebalancing
+const MIGRATION_042: &str = r#"
+CREATE TABLE IF NOT EXISTS allocation_targets (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    target_key TEXT NOT NULL,
+    target_percent TEXT NOT NULL,
+    tolerance_percent TEXT NOT NULL DEFAULT '5',
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    UNIQUE(kind, target_key)
+);
+"#;
+
+/// Migration 043: Add benchmark tickers and their daily price history
+/// Benchmarks need not be held; their prices come from the Yahoo historical fetch
+const MIGRATION_043: &str = r#"
+CREATE TABLE IF NOT EXISTS benchmarks (
+    ticker TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS benchmark_prices (
+    ticker TEXT NOT NULL REFERENCES benchmarks(ticker) ON DELETE CASCADE,
+    recorded_at INTEGER NOT NULL,
+    price TEXT NOT NULL,
+    PRIMARY KEY (ticker, recorded_at)
+);
+"#;
+
+/// Migration 044: Add per-ticker price provider preferences
+/// The preferred provider is tried first, then the global fallback order from app_config
+const MIGRATION_044: &str = r#"
+CREATE TABLE IF NOT EXISTS price_provider_preferences (
+    ticker TEXT NOT NULL,
+    asset_kind TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    PRIMARY KEY (ticker, asset_kind)
+);
+"#;
+
+/// Migration 045: Add the stored quote currency per stock ticker
+/// Learned from provider metadata or set by the user; trusted over the ticker-suffix guess
+const MIGRATION_045: &str = r#"
+CREATE TABLE IF NOT EXISTS stock_ticker_currencies (
+    ticker TEXT PRIMARY KEY,
+    currency TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'provider',
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+"#;
+
+/// Migration 046: Add dated exchange rates for historical valuations
+/// One row per source, currency and day, in CZK per unit
+const MIGRATION_046: &str = r#"
+CREATE TABLE IF NOT EXISTS exchange_rate_history (
+    currency TEXT NOT NULL,
+    rate_date INTEGER NOT NULL,
+    rate REAL NOT NULL,
+    source TEXT NOT NULL,
+    PRIMARY KEY (currency, rate_date, source)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exchange_rate_history_date ON exchange_rate_history(rate_date);
+"#;
+
+/// Migration 047: Add manual exchange rates
+/// CZK per unit for currencies neither the ECB nor the ČNB publishes
+const MIGRATION_047: &str = r#"
+CREATE TABLE IF NOT EXISTS manual_exchange_rates (
+    currency TEXT PRIMARY KEY,
+    rate REAL NOT NULL,
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+"#;
+
+/// Migration 048: Add coupon terms to bonds
+/// Frequency, day-count convention, issue and first coupon dates drive the coupon schedule
+const MIGRATION_048: &str = r#"
+ALTER TABLE bonds ADD COLUMN coupon_frequency TEXT NOT NULL DEFAULT 'annual';
+ALTER TABLE bonds ADD COLUMN day_count TEXT NOT NULL DEFAULT '30/360';
+ALTER TABLE bonds ADD COLUMN issue_date INTEGER;
+ALTER TABLE bonds ADD COLUMN first_coupon_date INTEGER;
+"#;
+
+/// Migration 049: Add purchase and market prices to bonds
+/// Clean prices in percent of face value; the market price is manual or fetched for `price_ticker`
+const MIGRATION_049: &str = r#"
+ALTER TABLE bonds ADD COLUMN purchase_price TEXT;
+ALTER TABLE bonds ADD COLUMN purchase_date INTEGER;
+ALTER TABLE bonds ADD COLUMN market_price TEXT;
+ALTER TABLE bonds ADD COLUMN market_price_source TEXT;
+ALTER TABLE bonds ADD COLUMN market_price_updated_at INTEGER;
+ALTER TABLE bonds ADD COLUMN price_ticker TEXT;
+"#;
+
+/// Migration 050: Add repayment terms to loans
+/// Annuity, linear or interest-only repayment; installments are matched in bank transactions
+/// by the counterparty IBAN and/or variable symbol
+const MIGRATION_050: &str = r#"
+ALTER TABLE loans ADD COLUMN repayment_type TEXT NOT NULL DEFAULT 'annuity';
+ALTER TABLE loans ADD COLUMN payment_iban TEXT;
+ALTER TABLE loans ADD COLUMN payment_variable_symbol TEXT;
+"#;
+
+/// Migration 051: Add interest terms to bank accounts
+/// Term deposits mature on `termination_date` and may renew; interest accrues daily from
+/// `deposit_start_date`, is capitalized monthly, annually or at maturity and taxed at source
+const MIGRATION_051: &str = r#"
+ALTER TABLE bank_accounts ADD COLUMN deposit_start_date INTEGER;
+ALTER TABLE bank_accounts ADD COLUMN auto_renew INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE bank_accounts ADD COLUMN capitalization TEXT NOT NULL DEFAULT 'monthly';
+ALTER TABLE bank_accounts ADD COLUMN interest_tax_rate TEXT NOT NULL DEFAULT '15';
+"#;
+
+/// Migration 052: Add savings goals
+/// A goal's progress is the current value of its linked bank accounts, bonds and stock tags
+const MIGRATION_052: &str = r#"
+CREATE TABLE IF NOT EXISTS savings_goals (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    target_amount TEXT NOT NULL,
+    currency TEXT NOT NULL DEFAULT 'CZK',
+    target_date INTEGER NOT NULL,
+    expected_return TEXT NOT NULL DEFAULT '0',
+    monthly_contribution TEXT NOT NULL DEFAULT '0',
+    created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE TABLE IF NOT EXISTS savings_goal_links (
+    goal_id TEXT NOT NULL REFERENCES savings_goals(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    link_id TEXT NOT NULL,
+    PRIMARY KEY (goal_id, kind, link_id)
+);
+"#;
+
+/// Migration 053: Add dated real estate valuations and purchase dates
+/// The latest valuation sets `market_price`; earlier ones value the property in history
+const MIGRATION_053: &str = r#"
+ALTER TABLE real_estate ADD COLUMN purchase_date INTEGER;
+
+CREATE TABLE IF NOT EXISTS real_estate_valuations (
+    id TEXT PRIMARY KEY,
+    real_estate_id TEXT NOT NULL REFERENCES real_estate(id) ON DELETE CASCADE,
+    valuation_date INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    currency TEXT NOT NULL DEFAULT 'CZK',
+    source TEXT NOT NULL DEFAULT 'manual',
+    notes TEXT,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX IF NOT EXISTS idx_real_estate_valuations_property
+    ON real_estate_valuations(real_estate_id, valuation_date);
+"#;
+
+/// Migration 054: Link bank transactions to real estate
+/// Linked credits and debits count as rent and operating costs in rental performance
+const MIGRATION_054: &str = r#"
+CREATE TABLE IF NOT EXISTS real_estate_transactions (
+    transaction_id TEXT PRIMARY KEY REFERENCES bank_transactions(id) ON DELETE CASCADE,
+    real_estate_id TEXT NOT NULL REFERENCES real_estate(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_real_estate_transactions_property
+    ON real_estate_transactions(real_estate_id);
+"#;
+
+/// Migration 055: Add underlying currencies of stock tickers
+/// Currency the holdings of a fund are denominated in, when it differs from the listing currency
+const MIGRATION_055: &str = r#"
+CREATE TABLE IF NOT EXISTS stock_underlying_currencies (
+    ticker TEXT PRIMARY KEY,
+    currency TEXT NOT NULL,
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+"#;
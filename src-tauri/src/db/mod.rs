@@ -63,6 +63,9 @@ impl Database {
         if let Err(e) = crate::services::currency::load_rates_from_db(&conn) {
             println!("[DB] Warning: Failed to load exchange rates: {}", e);
         }
+        if let Err(e) = crate::services::exchange_rates::apply_manual_rates(&conn) {
+            println!("[DB] Warning: Failed to load manual exchange rates: {}", e);
+        }
 
         // Store connection and path
         *self
@@ -99,6 +102,9 @@ impl Database {
         if let Err(e) = crate::services::currency::load_rates_from_db(&conn) {
             println!("[DB] Warning: Failed to load exchange rates: {}", e);
         }
+        if let Err(e) = crate::services::exchange_rates::apply_manual_rates(&conn) {
+            println!("[DB] Warning: Failed to load manual exchange rates: {}", e);
+        }
 
         // Store connection and path
         *self
@@ -68,6 +68,8 @@ pub async fn run() {
             commands::savings::get_account_zones,
             commands::savings::create_account_zone,
             commands::savings::delete_account_zone,
+            commands::savings::get_interest_accruals,
+            commands::savings::get_interest_calendar,
             // Investment commands
             commands::investments::get_all_investments,
             commands::investments::get_investment,
@@ -87,6 +89,17 @@ pub async fn run() {
             commands::investments::delete_manual_dividend,
             commands::investments::get_stock_value_history,
             commands::investments::get_stock_twr,
+            commands::investments::get_xirr,
+            commands::investments::get_risk_metrics,
+            commands::investments::get_risk_free_rate,
+            commands::investments::set_risk_free_rate,
+            // Dividend commands
+            commands::dividends::get_received_dividends,
+            commands::dividends::create_received_dividend,
+            commands::dividends::delete_received_dividend,
+            commands::dividends::get_dividend_tax_report,
+            // Broker import commands
+            commands::broker_import::import_broker_statement,
             // Crypto commands
             commands::crypto::get_all_crypto,
             commands::crypto::create_crypto,
@@ -98,16 +111,28 @@ pub async fn run() {
             commands::crypto::update_crypto_price,
             commands::crypto::delete_crypto_manual_price,
             commands::crypto::get_crypto_value_history,
+            commands::crypto::get_crypto_wallet_holdings,
+            commands::crypto::get_crypto_income_cost_basis,
+            commands::crypto::set_crypto_income_cost_basis,
+            // Crypto exchange import commands
+            commands::crypto_exchange_import::import_crypto_exchange,
             // Bond commands
             commands::bonds::get_all_bonds,
             commands::bonds::create_bond,
             commands::bonds::update_bond,
             commands::bonds::delete_bond,
+            commands::bonds::get_bond_schedule,
+            commands::bonds::get_bond_cash_flow_calendar,
+            commands::bonds::get_bond_analytics,
+            commands::bonds::refresh_bond_prices,
             // Loan commands
             commands::loans::get_all_loans,
             commands::loans::create_loan,
             commands::loans::update_loan,
             commands::loans::delete_loan,
+            commands::loans::get_loan_schedule,
+            commands::loans::get_loan_schedules,
+            commands::loans::simulate_loan_scenarios,
             commands::loans::get_loan_real_estate,
             commands::loans::get_available_loans,
             // Real estate commands
@@ -127,6 +152,17 @@ pub async fn run() {
             commands::real_estate::link_insurance_to_real_estate,
             commands::real_estate::unlink_insurance_from_real_estate,
             commands::real_estate::get_real_estate_insurances,
+            // Real estate valuation commands
+            commands::real_estate::get_real_estate_valuations,
+            commands::real_estate::add_real_estate_valuation,
+            commands::real_estate::delete_real_estate_valuation,
+            commands::real_estate::get_real_estate_value_history,
+            // Real estate transaction and performance commands
+            commands::real_estate::get_real_estate_transactions,
+            commands::real_estate::link_transactions_to_real_estate,
+            commands::real_estate::unlink_transaction_from_real_estate,
+            commands::real_estate::get_rental_performance,
+            commands::real_estate::get_real_estate_performance,
             // Real estate photo commands
             commands::real_estate::get_real_estate_photo_batches,
             commands::real_estate::create_photo_batch,
@@ -163,9 +199,19 @@ pub async fn run() {
             // Portfolio commands
             commands::portfolio::get_portfolio_metrics,
             commands::portfolio::get_portfolio_history,
+            commands::portfolio::get_portfolio_exposure,
+            commands::portfolio::get_exposure_history,
+            commands::portfolio::get_underlying_currencies,
+            commands::portfolio::set_underlying_currency,
+            commands::portfolio::delete_underlying_currency,
             commands::portfolio::record_portfolio_snapshot,
             commands::portfolio::refresh_exchange_rates,
             commands::portfolio::get_exchange_rates,
+            commands::portfolio::get_fx_settings,
+            commands::portfolio::set_fx_settings,
+            commands::portfolio::get_manual_exchange_rates,
+            commands::portfolio::set_manual_exchange_rate,
+            commands::portfolio::delete_manual_exchange_rate,
             commands::portfolio::get_price_status,
             commands::portfolio::start_snapshot_backfill,
             commands::portfolio::recalculate_all_portfolio_history,
@@ -179,6 +225,14 @@ pub async fn run() {
             commands::price_api::search_crypto,
             commands::price_api::refresh_dividends,
             commands::price_api::search_stock_tickers,
+            commands::price_api::get_price_provider_settings,
+            commands::price_api::set_price_provider_settings,
+            commands::price_api::get_price_provider_preferences,
+            commands::price_api::set_price_provider_preference,
+            commands::price_api::delete_price_provider_preference,
+            commands::price_api::get_ticker_currencies,
+            commands::price_api::set_ticker_currency,
+            commands::price_api::delete_ticker_currency,
             // Cashflow commands
             commands::cashflow::get_cashflow_report,
             commands::cashflow::get_all_cashflow_items,
@@ -235,6 +289,14 @@ pub async fn run() {
             commands::bank_accounts::import_csv_transactions,
             commands::bank_accounts::get_import_batches,
             commands::bank_accounts::delete_import_batch,
+            // Brokerage account commands
+            commands::brokerage_accounts::get_brokerage_accounts,
+            commands::brokerage_accounts::create_brokerage_account,
+            commands::brokerage_accounts::update_brokerage_account,
+            commands::brokerage_accounts::delete_brokerage_account,
+            commands::brokerage_accounts::get_account_positions,
+            commands::brokerage_accounts::get_account_values,
+            commands::brokerage_accounts::set_transaction_account,
             // Stock tags commands
             commands::stock_tags::get_all_stock_tags,
             commands::stock_tags::create_stock_tag,
@@ -249,6 +311,24 @@ pub async fn run() {
             commands::stock_tags::create_stock_tag_group,
             commands::stock_tags::update_stock_tag_group,
             commands::stock_tags::delete_stock_tag_group,
+            // Benchmark commands
+            commands::benchmarks::get_benchmarks,
+            commands::benchmarks::add_benchmark,
+            commands::benchmarks::delete_benchmark,
+            commands::benchmarks::backfill_benchmark_history,
+            commands::benchmarks::compare_with_benchmark,
+            // Allocation commands
+            commands::allocation::get_allocation_targets,
+            commands::allocation::set_allocation_target,
+            commands::allocation::delete_allocation_target,
+            commands::allocation::get_allocation_drift,
+            commands::allocation::get_rebalancing_plan,
+            // Savings goal commands
+            commands::goals::get_savings_goals,
+            commands::goals::create_savings_goal,
+            commands::goals::update_savings_goal,
+            commands::goals::delete_savings_goal,
+            commands::goals::get_savings_goal_progress,
             // Categorization commands
             commands::categorization::categorize_transaction,
             commands::categorization::categorize_batch,
@@ -0,0 +1,173 @@
+//! Target allocation models for drift reporting and rebalancing
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Asset classes that can carry an allocation target
+pub const ALLOCATION_ASSET_CLASSES: [&str; 6] = [
+    "savings",
+    "stocks",
+    "bonds",
+    "crypto",
+    "real_estate",
+    "other_assets",
+];
+
+/// What an allocation target refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationKind {
+    /// Stock tag ID; percent of the stock portfolio
+    Tag,
+    /// Asset class name; percent of total assets
+    AssetClass,
+}
+
+impl AllocationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AllocationKind::Tag => "tag",
+            AllocationKind::AssetClass => "asset_class",
+        }
+    }
+}
+
+/// Target weight of one tag or asset class
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AllocationTarget {
+    pub id: String,
+    pub kind: AllocationKind,
+    /// Tag ID or asset class name
+    #[serde(rename = "targetKey")]
+    pub target_key: String,
+    #[serde(rename = "targetPercent")]
+    pub target_percent: String,
+    /// Allowed deviation in percentage points before the group counts as drifted
+    #[serde(rename = "tolerancePercent")]
+    pub tolerance_percent: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
+/// Data for setting an allocation target (upsert by kind + key)
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct InsertAllocationTarget {
+    pub kind: AllocationKind,
+    #[serde(rename = "targetKey")]
+    pub target_key: String,
+    #[serde(rename = "targetPercent")]
+    pub target_percent: String,
+    #[serde(rename = "tolerancePercent")]
+    pub tolerance_percent: Option<String>,
+}
+
+/// Position of a group relative to its tolerance band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftStatus {
+    NoTarget,
+    Within,
+    Over,
+    Under,
+}
+
+/// Current vs. target weight of one tag or asset class
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationDrift {
+    pub key: String,
+    pub name: String,
+    pub current_value: f64,
+    pub current_percent: f64,
+    pub target_percent: Option<f64>,
+    pub tolerance_percent: Option<f64>,
+    /// Current minus target, in percentage points
+    pub drift_percent: Option<f64>,
+    /// Value needed to reach the target exactly (positive = buy)
+    pub drift_value: Option<f64>,
+    pub status: DriftStatus,
+}
+
+/// Drift report for all tags or all asset classes. Amounts are in CZK.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationReport {
+    pub kind: AllocationKind,
+    pub total_value: f64,
+    /// Sum of all target percentages
+    pub targets_total_percent: f64,
+    /// True if any targeted group is outside its tolerance band
+    pub needs_rebalancing: bool,
+    pub items: Vec<AllocationDrift>,
+}
+
+/// Proposed trade for one tag or asset class
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalanceTrade {
+    pub key: String,
+    pub name: String,
+    pub current_value: f64,
+    pub target_value: f64,
+    /// Positive = buy, negative = sell
+    pub amount: f64,
+    pub resulting_percent: f64,
+}
+
+/// Rebalancing proposal. Amounts are in CZK.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalancePlan {
+    pub kind: AllocationKind,
+    /// Only new money is allocated, nothing is sold
+    pub cash_only: bool,
+    pub contribution: f64,
+    pub total_value_after: f64,
+    /// Part of the contribution not needed by any targeted group
+    pub unallocated_cash: f64,
+    pub trades: Vec<RebalanceTrade>,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+fn parse_percent(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && (0.0..=100.0).contains(v))
+}
+
+impl InsertAllocationTarget {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.target_key.trim().is_empty() || self.target_key.len() > 100 {
+            return Err(AppError::Validation(
+                "validation.allocationKeyInvalid".into(),
+            ));
+        }
+        if self.kind == AllocationKind::AssetClass
+            && !ALLOCATION_ASSET_CLASSES.contains(&self.target_key.as_str())
+        {
+            return Err(AppError::Validation(
+                "validation.allocationKeyInvalid".into(),
+            ));
+        }
+
+        if parse_percent(&self.target_percent).is_none() {
+            return Err(AppError::Validation(
+                "validation.allocationPercentInvalid".into(),
+            ));
+        }
+        if let Some(ref tolerance) = self.tolerance_percent {
+            if parse_percent(tolerance).is_none() {
+                return Err(AppError::Validation(
+                    "validation.allocationPercentInvalid".into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@ pub enum AccountType {
     Savings,
     CreditCard,
     Investment,
+    TermDeposit,
 }
 
 impl std::fmt::Display for AccountType {
@@ -21,6 +22,7 @@ impl std::fmt::Display for AccountType {
             AccountType::Savings => write!(f, "savings"),
             AccountType::CreditCard => write!(f, "credit_card"),
             AccountType::Investment => write!(f, "investment"),
+            AccountType::TermDeposit => write!(f, "term_deposit"),
         }
     }
 }
@@ -33,6 +35,7 @@ impl std::str::FromStr for AccountType {
             "savings" => Ok(AccountType::Savings),
             "credit_card" => Ok(AccountType::CreditCard),
             "investment" => Ok(AccountType::Investment),
+            "term_deposit" => Ok(AccountType::TermDeposit),
             _ => Err(format!("Unknown account type: {}", s)),
         }
     }
@@ -94,8 +97,20 @@ pub struct BankAccount {
     pub interest_rate: Option<String>,
     #[serde(rename = "hasZoneDesignation")]
     pub has_zone_designation: bool,
+    /// Maturity of a term deposit
     #[serde(rename = "terminationDate")]
     pub termination_date: Option<i64>,
+    /// Interest accrues from this date (defaults to when the account was added)
+    #[serde(rename = "depositStartDate")]
+    pub deposit_start_date: Option<i64>,
+    /// Term deposit renews for the same term at maturity
+    #[serde(rename = "autoRenew")]
+    pub auto_renew: bool,
+    /// "monthly", "annual" or "maturity"
+    pub capitalization: String,
+    /// Withholding tax on interest (percent)
+    #[serde(rename = "interestTaxRate")]
+    pub interest_tax_rate: String,
     #[serde(rename = "createdAt")]
     pub created_at: i64,
     #[serde(rename = "updatedAt")]
@@ -120,6 +135,13 @@ pub struct InsertBankAccount {
     pub has_zone_designation: Option<bool>,
     #[serde(rename = "terminationDate")]
     pub termination_date: Option<i64>,
+    #[serde(rename = "depositStartDate")]
+    pub deposit_start_date: Option<i64>,
+    #[serde(rename = "autoRenew")]
+    pub auto_renew: Option<bool>,
+    pub capitalization: Option<String>,
+    #[serde(rename = "interestTaxRate")]
+    pub interest_tax_rate: Option<String>,
 }
 
 /// Financial institution (bank)
@@ -176,7 +198,13 @@ impl InsertBankAccount {
 
         // Account type validation (if provided)
         if let Some(ref account_type) = self.account_type {
-            let valid_types = ["checking", "savings", "credit_card", "investment"];
+            let valid_types = [
+                "checking",
+                "savings",
+                "credit_card",
+                "investment",
+                "term_deposit",
+            ];
             if !valid_types.contains(&account_type.to_lowercase().as_str()) {
                 return Err(AppError::Validation("validation.accountTypeInvalid".into()));
             }
@@ -213,6 +241,43 @@ impl InsertBankAccount {
             }
         }
 
+        // Capitalization validation (if provided)
+        if let Some(ref capitalization) = self.capitalization {
+            if !crate::services::interest::CAPITALIZATIONS.contains(&capitalization.as_str()) {
+                return Err(AppError::Validation(
+                    "validation.capitalizationInvalid".into(),
+                ));
+            }
+            if capitalization == "maturity" && self.termination_date.is_none() {
+                return Err(AppError::Validation("validation.maturityRequired".into()));
+            }
+        }
+
+        // Withholding tax validation (if provided)
+        if let Some(ref tax) = self.interest_tax_rate {
+            let tax_val: f64 = tax
+                .parse()
+                .map_err(|_| AppError::Validation("validation.taxRateRange".into()))?;
+            if !(0.0..=100.0).contains(&tax_val) {
+                return Err(AppError::Validation("validation.taxRateRange".into()));
+            }
+        }
+
+        // Term deposits need a maturity after the start
+        if self.account_type.as_deref() == Some("term_deposit") {
+            let Some(maturity) = self.termination_date else {
+                return Err(AppError::Validation("validation.maturityRequired".into()));
+            };
+            if self
+                .deposit_start_date
+                .is_some_and(|start| start >= maturity)
+            {
+                return Err(AppError::Validation(
+                    "validation.maturityBeforeStart".into(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
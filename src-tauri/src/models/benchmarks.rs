@@ -0,0 +1,122 @@
+//! Benchmark models for comparing portfolio performance with market indices
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Benchmark ticker (index or ETF) with backfilled daily prices
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Benchmark {
+    /// Yahoo ticker, e.g. "VWCE.DE", "^GSPC"
+    pub ticker: String,
+    pub name: String,
+    /// Quote currency of the prices
+    pub currency: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "priceCount")]
+    pub price_count: i32,
+    #[serde(rename = "firstDate")]
+    pub first_date: Option<i64>,
+    #[serde(rename = "lastDate")]
+    pub last_date: Option<i64>,
+}
+
+/// Data for adding a benchmark
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct InsertBenchmark {
+    pub ticker: String,
+    pub name: String,
+    /// Defaults to the currency implied by the ticker suffix
+    pub currency: Option<String>,
+}
+
+/// Cumulative returns of the portfolio and the benchmark on one day
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkPoint {
+    /// Calendar date as "YYYY-MM-DD"
+    pub date: String,
+    /// Cumulative time-weighted return in percent
+    pub portfolio_twr: f64,
+    /// Cumulative benchmark price return in percent (in the reporting currency)
+    pub benchmark_return: Option<f64>,
+}
+
+/// "What if every contribution had gone into the benchmark" simulation.
+/// Amounts are in the reporting currency of the comparison.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkSimulation {
+    /// Value held at the start of the range, invested into the benchmark on day one
+    pub start_value: f64,
+    /// Purchases within the range
+    pub invested: f64,
+    /// Sales within the range
+    pub withdrawn: f64,
+    /// Actual value at the end of the range
+    pub actual_value: f64,
+    /// Value if the same cash flows had bought/sold the benchmark
+    pub benchmark_value: f64,
+    /// actual_value - benchmark_value
+    pub difference: f64,
+    pub cash_flow_count: i32,
+}
+
+/// Portfolio or tag performance compared with a benchmark over one range
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkComparison {
+    pub benchmark: String,
+    /// Reporting currency the returns and simulation amounts are measured in
+    pub currency: String,
+    /// None = whole stock portfolio
+    pub tag_id: Option<String>,
+    pub from_ts: i64,
+    pub to_ts: i64,
+    /// Time-weighted return in percent over the range
+    pub portfolio_return: f64,
+    /// Benchmark price return in percent, None without prices for the range
+    pub benchmark_return: Option<f64>,
+    /// portfolio_return - benchmark_return in percentage points
+    pub excess_return: Option<f64>,
+    pub series: Vec<BenchmarkPoint>,
+    pub simulation: Option<BenchmarkSimulation>,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+impl InsertBenchmark {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        let ticker = self.ticker.trim();
+        if ticker.is_empty() {
+            return Err(AppError::Validation("validation.tickerRequired".into()));
+        }
+        if ticker.len() > 20 {
+            return Err(AppError::Validation("validation.tickerTooLong".into()));
+        }
+        // Index tickers start with "^", futures/FX use "="
+        if !ticker
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '^' | '='))
+        {
+            return Err(AppError::Validation("validation.tickerInvalid".into()));
+        }
+
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation("validation.nameRequired".into()));
+        }
+        if self.name.len() > 100 {
+            return Err(AppError::Validation("validation.nameTooLong".into()));
+        }
+
+        if let Some(ref currency) = self.currency {
+            if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(AppError::Validation("validation.currencyInvalid".into()));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -17,6 +17,32 @@ pub struct Bond {
     pub interest_rate: String,
     #[serde(rename = "maturityDate")]
     pub maturity_date: Option<i64>,
+    /// "annual", "semiannual", "quarterly" or "monthly"
+    #[serde(rename = "couponFrequency")]
+    pub coupon_frequency: String,
+    /// "30/360", "act/360", "act/365" or "act/act"
+    #[serde(rename = "dayCount")]
+    pub day_count: String,
+    #[serde(rename = "issueDate")]
+    pub issue_date: Option<i64>,
+    #[serde(rename = "firstCouponDate")]
+    pub first_coupon_date: Option<i64>,
+    /// Clean purchase price in percent of face value
+    #[serde(rename = "purchasePrice")]
+    pub purchase_price: Option<String>,
+    #[serde(rename = "purchaseDate")]
+    pub purchase_date: Option<i64>,
+    /// Clean market price in percent of face value
+    #[serde(rename = "marketPrice")]
+    pub market_price: Option<String>,
+    /// "manual" or the price provider that reported it
+    #[serde(rename = "marketPriceSource")]
+    pub market_price_source: Option<String>,
+    #[serde(rename = "marketPriceUpdatedAt")]
+    pub market_price_updated_at: Option<i64>,
+    /// Ticker or ISIN the price providers quote the bond under
+    #[serde(rename = "priceTicker")]
+    pub price_ticker: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: i64,
     #[serde(rename = "updatedAt")]
@@ -36,6 +62,90 @@ pub struct InsertBond {
     pub interest_rate: Option<String>,
     #[serde(rename = "maturityDate")]
     pub maturity_date: Option<i64>,
+    #[serde(rename = "couponFrequency")]
+    pub coupon_frequency: Option<String>,
+    #[serde(rename = "dayCount")]
+    pub day_count: Option<String>,
+    #[serde(rename = "issueDate")]
+    pub issue_date: Option<i64>,
+    #[serde(rename = "firstCouponDate")]
+    pub first_coupon_date: Option<i64>,
+    #[serde(rename = "purchasePrice")]
+    pub purchase_price: Option<String>,
+    #[serde(rename = "purchaseDate")]
+    pub purchase_date: Option<i64>,
+    /// Manual market price; clearing it lets the price providers fill it in
+    #[serde(rename = "marketPrice")]
+    pub market_price: Option<String>,
+    #[serde(rename = "priceTicker")]
+    pub price_ticker: Option<String>,
+}
+
+/// A scheduled coupon or principal repayment
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BondCashFlow {
+    #[serde(rename = "bondId")]
+    pub bond_id: String,
+    #[serde(rename = "bondName")]
+    pub bond_name: String,
+    pub date: i64,
+    /// "coupon" or "principal"
+    pub kind: String,
+    /// Amount for the whole holding in the bond currency
+    pub amount: String,
+    pub currency: String,
+    #[serde(rename = "amountCzk")]
+    pub amount_czk: f64,
+}
+
+/// Price, yield and risk figures of a bond holding
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BondAnalytics {
+    #[serde(rename = "bondId")]
+    pub bond_id: String,
+    /// Clean price in percent of face value (market, else purchase, else par)
+    #[serde(rename = "cleanPrice")]
+    pub clean_price: String,
+    /// Clean price plus accrued interest, in percent of face value
+    #[serde(rename = "dirtyPrice")]
+    pub dirty_price: String,
+    #[serde(rename = "accruedInterest")]
+    pub accrued_interest: String,
+    /// Dirty value of the holding in the bond currency
+    #[serde(rename = "marketValue")]
+    pub market_value: String,
+    #[serde(rename = "marketValueCzk")]
+    pub market_value_czk: f64,
+    /// Purchase cost of the holding (clean), if the purchase price is known
+    #[serde(rename = "purchaseCost")]
+    pub purchase_cost: Option<String>,
+    /// Annual coupon over clean price, percent
+    #[serde(rename = "currentYield")]
+    pub current_yield: f64,
+    /// Effective annual yield to maturity at the dirty price, percent
+    /// (comparable to a savings account rate)
+    #[serde(rename = "yieldToMaturity")]
+    pub yield_to_maturity: Option<f64>,
+    /// Macaulay duration in years
+    #[serde(rename = "macaulayDuration")]
+    pub macaulay_duration: Option<f64>,
+    /// Percentage price change per 1 percentage point of yield
+    #[serde(rename = "modifiedDuration")]
+    pub modified_duration: Option<f64>,
+}
+
+/// Coupon and principal schedule of a bond with its accrued interest
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct BondSchedule {
+    #[serde(rename = "bondId")]
+    pub bond_id: String,
+    /// Interest accrued since the last coupon date, in the bond currency
+    #[serde(rename = "accruedInterest")]
+    pub accrued_interest: String,
+    #[serde(rename = "nextCouponDate")]
+    pub next_coupon_date: Option<i64>,
+    #[serde(rename = "cashFlows")]
+    pub cash_flows: Vec<BondCashFlow>,
 }
 
 // Input validation at trust boundary
@@ -93,6 +203,46 @@ impl InsertBond {
             }
         }
 
+        // Coupon terms validation (if provided)
+        if let Some(ref frequency) = self.coupon_frequency {
+            if !crate::services::bonds::FREQUENCIES.contains(&frequency.as_str()) {
+                return Err(AppError::Validation(
+                    "validation.couponFrequencyInvalid".into(),
+                ));
+            }
+        }
+        if let Some(ref day_count) = self.day_count {
+            if !crate::services::bonds::DAY_COUNTS.contains(&day_count.as_str()) {
+                return Err(AppError::Validation("validation.dayCountInvalid".into()));
+            }
+        }
+        if let Some(issue) = self.issue_date {
+            let later = [self.first_coupon_date, self.maturity_date];
+            if later.iter().flatten().any(|date| *date <= issue) {
+                return Err(AppError::Validation("validation.bondDatesInvalid".into()));
+            }
+        }
+        if let (Some(first), Some(maturity)) = (self.first_coupon_date, self.maturity_date) {
+            if first > maturity {
+                return Err(AppError::Validation("validation.bondDatesInvalid".into()));
+            }
+        }
+
+        // Prices validation (if provided)
+        for price in [&self.purchase_price, &self.market_price]
+            .into_iter()
+            .flatten()
+        {
+            if !price.is_empty() {
+                let value: f64 = price
+                    .parse()
+                    .map_err(|_| AppError::Validation("validation.invalidAmount".into()))?;
+                if value <= 0.0 {
+                    return Err(AppError::Validation("validation.bondPricePositive".into()));
+                }
+            }
+        }
+
         // Interest rate validation (if provided)
         if let Some(ref rate) = self.interest_rate {
             if !rate.is_empty() {
@@ -0,0 +1,103 @@
+//! Brokerage account models for grouping stock holdings by custody account
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Valid brokerage account types
+pub const BROKERAGE_ACCOUNT_TYPES: [&str; 3] = ["taxable", "pension", "other"];
+
+/// Broker or custody account owning stock transactions
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BrokerageAccount {
+    pub id: String,
+    pub name: String,
+    /// Broker name (e.g. "degiro", "ibkr"), free text
+    pub broker: Option<String>,
+    /// "taxable", "pension" or "other"
+    #[serde(rename = "accountType")]
+    pub account_type: String,
+    /// Cash/reporting currency of the account
+    pub currency: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// Data for creating/updating a brokerage account
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct InsertBrokerageAccount {
+    pub name: String,
+    pub broker: Option<String>,
+    #[serde(rename = "accountType")]
+    pub account_type: Option<String>,
+    pub currency: Option<String>,
+}
+
+/// Position of one ticker within one brokerage account
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AccountPosition {
+    /// None = transactions not assigned to any account
+    #[serde(rename = "accountId")]
+    pub account_id: Option<String>,
+    #[serde(rename = "investmentId")]
+    pub investment_id: String,
+    pub ticker: String,
+    #[serde(rename = "companyName")]
+    pub company_name: String,
+    pub quantity: String,
+    /// Average buy price in the transactions' currency
+    #[serde(rename = "averagePrice")]
+    pub average_price: String,
+    pub currency: String,
+    #[serde(rename = "currentValueCzk")]
+    pub current_value_czk: f64,
+    #[serde(rename = "costBasisCzk")]
+    pub cost_basis_czk: f64,
+}
+
+/// Total value of one brokerage account (used in portfolio metrics)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct AccountValue {
+    /// None = transactions not assigned to any account
+    #[serde(rename = "accountId")]
+    pub account_id: Option<String>,
+    pub name: Option<String>,
+    #[serde(rename = "accountType")]
+    pub account_type: Option<String>,
+    #[serde(rename = "totalValue")]
+    pub total_value: f64,
+    #[serde(rename = "totalCost")]
+    pub total_cost: f64,
+    #[serde(rename = "positionsCount")]
+    pub positions_count: i32,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+impl InsertBrokerageAccount {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation(
+                "validation.accountNameRequired".into(),
+            ));
+        }
+        if self.name.len() > 100 {
+            return Err(AppError::Validation("validation.accountNameTooLong".into()));
+        }
+
+        if let Some(ref account_type) = self.account_type {
+            if !BROKERAGE_ACCOUNT_TYPES.contains(&account_type.to_lowercase().as_str()) {
+                return Err(AppError::Validation("validation.accountTypeInvalid".into()));
+            }
+        }
+
+        if let Some(ref currency) = self.currency {
+            if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(AppError::Validation("validation.currencyInvalid".into()));
+            }
+        }
+
+        Ok(())
+    }
+}
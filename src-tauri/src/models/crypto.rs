@@ -56,7 +56,24 @@ pub struct InsertCryptoInvestment {
     pub average_price: Option<String>,
 }
 
-/// Crypto transaction (buy/sell)
+/// All supported crypto transaction types
+pub const CRYPTO_TX_TYPES: &[&str] = &[
+    "buy",
+    "sell",
+    "staking",
+    "airdrop",
+    "interest",
+    "mining",
+    "gift",
+    "transfer_in",
+    "transfer_out",
+];
+
+/// Income types - coins received without paying for them
+/// Their cost basis follows the `crypto_income_cost_basis` setting
+pub const CRYPTO_INCOME_TYPES: &[&str] = &["staking", "airdrop", "interest", "mining"];
+
+/// Crypto transaction (buy/sell, income, gift or transfer between own wallets)
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct CryptoTransaction {
     pub id: String,
@@ -72,6 +89,8 @@ pub struct CryptoTransaction {
     pub currency: String,
     #[serde(rename = "transactionDate")]
     pub transaction_date: i64,
+    /// Wallet or exchange holding the coins (source for transfer_out, target for transfer_in)
+    pub wallet: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: i64,
 }
@@ -91,6 +110,26 @@ pub struct InsertCryptoTransaction {
     pub currency: String,
     #[serde(rename = "transactionDate")]
     pub transaction_date: i64,
+    pub wallet: Option<String>,
+}
+
+/// Quantity of a coin held in one wallet
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CryptoWalletHolding {
+    pub ticker: String,
+    /// None for transactions without an assigned wallet
+    pub wallet: Option<String>,
+    pub quantity: f64,
+}
+
+/// How income (staking, airdrops, interest, mining) enters the cost basis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CryptoIncomeCostBasis {
+    /// Cost basis is the fair value at receipt (price entered on the transaction)
+    FairValue,
+    /// Income is acquired at zero cost
+    Zero,
 }
 
 /// Crypto price cache
@@ -169,7 +208,7 @@ impl InsertCryptoTransaction {
     pub fn validate(&self) -> Result<()> {
         // Transaction type validation
         let tx_type = self.tx_type.to_lowercase();
-        if tx_type != "buy" && tx_type != "sell" {
+        if !CRYPTO_TX_TYPES.contains(&tx_type.as_str()) {
             return Err(AppError::Validation(
                 "validation.transactionTypeInvalid".into(),
             ));
@@ -194,14 +233,24 @@ impl InsertCryptoTransaction {
             return Err(AppError::Validation("validation.quantityPositive".into()));
         }
 
-        // Price validation
+        // Price validation - only trades need a price, income/gifts/transfers may be zero
         let price: f64 = self
             .price_per_unit
             .parse()
             .map_err(|_| AppError::Validation("validation.invalidPrice".into()))?;
-        if price <= 0.0 {
+        if (tx_type == "buy" || tx_type == "sell") && price <= 0.0 {
             return Err(AppError::Validation("validation.pricePositive".into()));
         }
+        if price < 0.0 {
+            return Err(AppError::Validation("validation.priceNonNegative".into()));
+        }
+
+        // Wallet validation
+        if let Some(ref wallet) = self.wallet {
+            if wallet.len() > 100 {
+                return Err(AppError::Validation("validation.walletTooLong".into()));
+            }
+        }
 
         // Currency validation
         if self.currency.len() != 3 {
@@ -0,0 +1,111 @@
+//! Received dividend models and the foreign dividend tax report
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A dividend payment actually received (gross amount + tax withheld at source)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ReceivedDividend {
+    pub id: String,
+    pub ticker: String,
+    #[serde(rename = "paymentDate")]
+    pub payment_date: i64,
+    #[serde(rename = "grossAmount")]
+    pub gross_amount: String,
+    #[serde(rename = "withholdingTax")]
+    pub withholding_tax: String,
+    pub currency: String,
+    /// ISO 3166-1 alpha-2 code of the country the dividend comes from (e.g., US, DE)
+    #[serde(rename = "sourceCountry")]
+    pub source_country: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// Data for recording a received dividend
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct InsertReceivedDividend {
+    pub ticker: String,
+    #[serde(rename = "paymentDate")]
+    pub payment_date: i64,
+    #[serde(rename = "grossAmount")]
+    pub gross_amount: String,
+    #[serde(rename = "withholdingTax")]
+    pub withholding_tax: Option<String>,
+    pub currency: String,
+    #[serde(rename = "sourceCountry")]
+    pub source_country: String,
+}
+
+/// Per-country totals of the foreign dividend tax report (all amounts in CZK)
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendTaxCountry {
+    pub country: String,
+    /// Maximum source tax rate allowed by the double taxation treaty (e.g., 0.15)
+    pub treaty_rate: f64,
+    pub dividend_count: i32,
+    pub gross_czk: f64,
+    pub withheld_czk: f64,
+    /// Withheld tax that can be credited in the Czech return (capped by the treaty rate)
+    pub creditable_czk: f64,
+    /// Withholding above the treaty rate - must be reclaimed from the source country
+    pub non_creditable_czk: f64,
+}
+
+/// Yearly foreign dividend report for §8 income and the foreign income appendix
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendTaxReport {
+    pub year: i32,
+    pub total_gross_czk: f64,
+    pub total_withheld_czk: f64,
+    pub total_creditable_czk: f64,
+    pub total_non_creditable_czk: f64,
+    pub countries: Vec<DividendTaxCountry>,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+impl InsertReceivedDividend {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.ticker.trim().is_empty() || self.ticker.len() > 20 {
+            return Err(AppError::Validation("validation.tickerRequired".into()));
+        }
+
+        let gross: f64 = self
+            .gross_amount
+            .parse()
+            .map_err(|_| AppError::Validation("validation.invalidAmount".into()))?;
+        if gross <= 0.0 {
+            return Err(AppError::Validation("validation.amountPositive".into()));
+        }
+
+        if let Some(ref tax) = self.withholding_tax {
+            if !tax.is_empty() {
+                let tax_val: f64 = tax
+                    .parse()
+                    .map_err(|_| AppError::Validation("validation.invalidAmount".into()))?;
+                if tax_val < 0.0 || tax_val > gross {
+                    return Err(AppError::Validation(
+                        "validation.withholdingTaxInvalid".into(),
+                    ));
+                }
+            }
+        }
+
+        if self.currency.len() != 3 {
+            return Err(AppError::Validation("validation.currencyInvalid".into()));
+        }
+
+        if self.source_country.len() != 2
+            || !self.source_country.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(AppError::Validation("validation.countryInvalid".into()));
+        }
+
+        Ok(())
+    }
+}
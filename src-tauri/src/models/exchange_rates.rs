@@ -0,0 +1,115 @@
+//! Exchange rate models: rate sources per purpose and manual rates
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Source of CZK exchange rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum FxSource {
+    /// ECB reference rates (cross rates via EUR)
+    Ecb,
+    /// ČNB daily fixing
+    CnbDaily,
+    /// Uniform annual rate ("jednotný kurz"): average of the ČNB month-end fixings.
+    /// Only meaningful for tax reporting.
+    CnbAnnual,
+}
+
+impl FxSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FxSource::Ecb => "ecb",
+            FxSource::CnbDaily => "cnb_daily",
+            FxSource::CnbAnnual => "cnb_annual",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ecb" => Some(FxSource::Ecb),
+            "cnb_daily" => Some(FxSource::CnbDaily),
+            "cnb_annual" => Some(FxSource::CnbAnnual),
+            _ => None,
+        }
+    }
+
+    /// Source name of the daily rates in `exchange_rate_history`
+    pub fn history_source(&self) -> &'static str {
+        match self {
+            FxSource::Ecb => "ecb",
+            FxSource::CnbDaily | FxSource::CnbAnnual => "cnb",
+        }
+    }
+}
+
+/// Rate source per purpose
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FxSettings {
+    /// Rates for portfolio values and history
+    pub valuation_source: FxSource,
+    /// Rates for tax reports
+    pub tax_source: FxSource,
+}
+
+impl Default for FxSettings {
+    fn default() -> Self {
+        Self {
+            valuation_source: FxSource::Ecb,
+            tax_source: FxSource::CnbDaily,
+        }
+    }
+}
+
+/// CZK rate set by the user for a currency neither source publishes
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualExchangeRate {
+    pub currency: String,
+    /// CZK per unit
+    pub rate: f64,
+    pub updated_at: i64,
+}
+
+/// Data for setting a manual exchange rate
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertManualExchangeRate {
+    pub currency: String,
+    pub rate: f64,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+impl FxSettings {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.valuation_source == FxSource::CnbAnnual {
+            return Err(AppError::Validation(
+                "validation.fxValuationSourceInvalid".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl InsertManualExchangeRate {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        let currency = self.currency.trim();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(AppError::Validation("validation.currencyInvalid".into()));
+        }
+        if currency.eq_ignore_ascii_case("CZK") {
+            return Err(AppError::Validation("validation.currencyInvalid".into()));
+        }
+        if !self.rate.is_finite() || self.rate <= 0.0 {
+            return Err(AppError::Validation(
+                "validation.exchangeRateInvalid".into(),
+            ));
+        }
+        Ok(())
+    }
+}
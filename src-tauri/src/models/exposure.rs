@@ -0,0 +1,62 @@
+//! Portfolio exposure models (sector, industry, country, currency)
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Dimension an exposure breakdown groups holdings by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ExposureDimension {
+    /// Yahoo sector of each stock
+    Sector,
+    /// Yahoo industry of each stock
+    Industry,
+    /// Listing country (exchange, else ticker suffix)
+    Country,
+    /// Underlying currency of stocks (quote currency unless set per ticker),
+    /// currency of cash, bonds, real estate and other assets
+    Currency,
+}
+
+/// Value of one bucket of an exposure breakdown. Amounts are in CZK.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureItem {
+    /// Sector/industry name, ISO country code, currency code or "unknown"
+    pub key: String,
+    /// Set for buckets holding a whole non-stock asset class (key = class name)
+    pub asset_class: Option<String>,
+    pub value: f64,
+    pub percent: f64,
+    pub holdings_count: i32,
+}
+
+/// Exposure breakdown of the current portfolio
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureReport {
+    pub dimension: ExposureDimension,
+    /// Only stocks are included
+    pub stocks_only: bool,
+    pub total_value: f64,
+    pub items: Vec<ExposureItem>,
+}
+
+/// Underlying currency of a stock ticker, e.g. USD for a US equity ETF listed in EUR
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UnderlyingCurrency {
+    pub ticker: String,
+    pub currency: String,
+    pub updated_at: i64,
+}
+
+/// Stock exposure on one day of `stock_value_history`, in the reporting currency
+/// at the rate of that day
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureHistoryPoint {
+    pub date: i64,
+    pub total_value: f64,
+    pub items: Vec<ExposureItem>,
+}
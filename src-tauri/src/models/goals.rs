@@ -0,0 +1,152 @@
+//! Savings goal models
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Holdings a goal can be linked to: a bank account (including savings
+/// accounts and term deposits), a bond or a stock tag
+pub const GOAL_LINK_KINDS: [&str; 3] = ["bank_account", "bond", "tag"];
+
+/// A holding counted towards a goal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalLink {
+    /// One of `GOAL_LINK_KINDS`
+    pub kind: String,
+    /// Bank account, bond or stock tag ID
+    pub link_id: String,
+}
+
+/// Named savings goal with a target amount and date
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SavingsGoal {
+    pub id: String,
+    pub name: String,
+    pub target_amount: String,
+    pub currency: String,
+    pub target_date: i64,
+    /// Expected yearly return of the linked holdings, percent
+    pub expected_return: String,
+    /// Planned monthly contribution in the goal currency
+    pub monthly_contribution: String,
+    pub links: Vec<GoalLink>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Data for creating/updating a savings goal
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertSavingsGoal {
+    pub name: String,
+    pub target_amount: String,
+    pub currency: Option<String>,
+    pub target_date: i64,
+    pub expected_return: Option<String>,
+    pub monthly_contribution: Option<String>,
+    #[serde(default)]
+    pub links: Vec<GoalLink>,
+}
+
+/// Where a goal stands relative to its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    /// The linked holdings already cover the target
+    Achieved,
+    /// The planned contribution reaches the target by the date
+    OnTrack,
+    /// The planned contribution falls short
+    Behind,
+    /// The target date has passed without reaching the target
+    Overdue,
+}
+
+/// Current value of one linked holding in the goal currency
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalLinkValue {
+    pub kind: String,
+    pub link_id: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// Progress of a goal. Amounts are in the goal currency.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SavingsGoalProgress {
+    pub goal: SavingsGoal,
+    pub current_value: String,
+    pub progress_percent: f64,
+    pub remaining: String,
+    /// Whole months until the target date (0 once it has passed)
+    pub months_left: i64,
+    /// Monthly contribution reaching the target by the date at the expected return
+    pub required_monthly: String,
+    /// Value at the target date with the planned contribution
+    pub projected_value: String,
+    pub status: GoalStatus,
+    pub links: Vec<GoalLinkValue>,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+impl InsertSavingsGoal {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation("validation.goalNameRequired".into()));
+        }
+        if self.name.len() > 100 {
+            return Err(AppError::Validation("validation.nameTooLong".into()));
+        }
+
+        let target: f64 = self
+            .target_amount
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Validation("validation.invalidAmount".into()))?;
+        if target <= 0.0 {
+            return Err(AppError::Validation("validation.goalTargetPositive".into()));
+        }
+
+        if let Some(ref currency) = self.currency {
+            if currency.len() != 3 {
+                return Err(AppError::Validation("validation.currencyInvalid".into()));
+            }
+        }
+
+        if let Some(ref rate) = self.expected_return {
+            let rate_val: f64 = rate
+                .trim()
+                .parse()
+                .map_err(|_| AppError::Validation("validation.goalReturnRange".into()))?;
+            if !(-100.0..=100.0).contains(&rate_val) {
+                return Err(AppError::Validation("validation.goalReturnRange".into()));
+            }
+        }
+
+        if let Some(ref contribution) = self.monthly_contribution {
+            let value: f64 = contribution
+                .trim()
+                .parse()
+                .map_err(|_| AppError::Validation("validation.invalidAmount".into()))?;
+            if value < 0.0 {
+                return Err(AppError::Validation("validation.invalidAmount".into()));
+            }
+        }
+
+        if self
+            .links
+            .iter()
+            .any(|link| !GOAL_LINK_KINDS.contains(&link.kind.as_str()) || link.link_id.is_empty())
+        {
+            return Err(AppError::Validation("validation.goalLinkInvalid".into()));
+        }
+
+        Ok(())
+    }
+}
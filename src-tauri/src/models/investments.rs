@@ -85,6 +85,9 @@ pub struct InvestmentTransaction {
     pub transaction_date: i64,
     #[serde(rename = "createdAt")]
     pub created_at: i64,
+    /// Brokerage account holding the position (None = unassigned)
+    #[serde(rename = "accountId")]
+    pub account_id: Option<String>,
 }
 
 /// Data for creating transaction
@@ -103,6 +106,8 @@ pub struct InsertInvestmentTransaction {
     pub currency: String,
     #[serde(rename = "transactionDate")]
     pub transaction_date: i64,
+    #[serde(rename = "accountId", default)]
+    pub account_id: Option<String>,
 }
 
 /// Stock data cache (price + Yahoo Finance metadata)
@@ -186,7 +191,30 @@ pub struct DividendOverride {
     pub updated_at: i64,
 }
 
+/// Where a stored ticker currency came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TickerCurrencySource {
+    /// Reported in price provider metadata
+    Provider,
+    /// Set by the user for tickers whose provider reports no currency
+    Manual,
+}
+
+/// Stored quote currency of a stock ticker, trusted over the ticker-suffix guess
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct TickerCurrency {
+    pub ticker: String,
+    pub currency: String,
+    pub source: TickerCurrencySource,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+}
+
 /// Per-ticker value history record
+///
+/// `value_czk` is stored in CZK; the value history commands return it in the
+/// reporting currency at the rate of `recorded_at`.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct TickerValueHistory {
     pub ticker: String,
@@ -323,3 +351,93 @@ pub struct TwrSeries {
     pub is_untagged: bool,
     pub data: Vec<TwrDataPoint>,
 }
+
+/// Grouping level of a money-weighted return calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum XirrScope {
+    /// One result per stock or crypto holding
+    Holding,
+    /// One result per stock tag
+    Tag,
+    /// Stocks and crypto separately
+    AssetClass,
+    /// Stocks and crypto combined
+    Portfolio,
+}
+
+/// Money-weighted return (XIRR) of one holding, tag, asset class or the portfolio.
+/// All amounts are in `currency`, the reporting currency.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct XirrResult {
+    pub scope: XirrScope,
+    pub currency: String,
+    /// Ticker, tag ID, asset class ("stocks" / "crypto") or "portfolio"
+    pub id: String,
+    pub name: String,
+    /// Asset class of a holding ("stocks" / "crypto"), None for other scopes
+    pub asset_class: Option<String>,
+    /// Annualized return in percent, None when it cannot be solved
+    /// (e.g. no cash flows in the range or all flows in one direction)
+    pub xirr: Option<f64>,
+    /// Value at the start of the range (0 when the range is open-ended)
+    pub start_value: f64,
+    /// Value at the end of the range
+    pub end_value: f64,
+    /// Money put in: purchases and fees
+    pub invested: f64,
+    /// Money taken out: sales and net dividends
+    pub withdrawn: f64,
+    /// end_value + withdrawn - start_value - invested
+    pub gain: f64,
+    pub cash_flow_count: i32,
+}
+
+/// Grouping level of risk metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskScope {
+    /// One result per stock or crypto holding
+    Holding,
+    /// One result per stock tag
+    Tag,
+    /// Stocks and crypto separately
+    AssetClass,
+    /// Stocks and crypto combined
+    Portfolio,
+}
+
+/// Risk metrics of one holding, tag, asset class or the portfolio, computed from
+/// flow-adjusted daily returns in CZK. Percentages are annualized where noted.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskMetrics {
+    pub scope: RiskScope,
+    /// Ticker, tag ID, asset class ("stocks" / "crypto") or "portfolio"
+    pub id: String,
+    pub name: String,
+    /// Asset class of a holding ("stocks" / "crypto"), None for other scopes
+    pub asset_class: Option<String>,
+    /// Number of daily returns used
+    pub observations: i32,
+    pub start_date: Option<i64>,
+    pub end_date: Option<i64>,
+    /// Annualized time-weighted return in percent
+    pub annualized_return: Option<f64>,
+    /// Annualized standard deviation of returns in percent
+    pub volatility: Option<f64>,
+    /// Largest peak-to-trough decline in percent (negative)
+    pub max_drawdown: Option<f64>,
+    pub drawdown_peak_date: Option<i64>,
+    pub drawdown_trough_date: Option<i64>,
+    /// First day back at the previous peak, None if not recovered yet
+    pub drawdown_recovery_date: Option<i64>,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    /// Beta versus `benchmark`, None without a benchmark or enough overlapping data
+    pub beta: Option<f64>,
+    pub benchmark: Option<String>,
+    /// Annual risk-free rate in percent used for Sharpe and Sortino
+    pub risk_free_rate: f64,
+}
@@ -20,6 +20,15 @@ pub struct Loan {
     pub start_date: i64,
     #[serde(rename = "endDate")]
     pub end_date: Option<i64>,
+    /// "annuity", "linear" or "interest_only"
+    #[serde(rename = "repaymentType")]
+    pub repayment_type: String,
+    /// Counterparty IBAN of installments in bank transactions
+    #[serde(rename = "paymentIban")]
+    pub payment_iban: Option<String>,
+    /// Variable symbol of installments in bank transactions
+    #[serde(rename = "paymentVariableSymbol")]
+    pub payment_variable_symbol: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: i64,
     #[serde(rename = "updatedAt")]
@@ -42,6 +51,122 @@ pub struct InsertLoan {
     pub start_date: Option<i64>,
     #[serde(rename = "endDate")]
     pub end_date: Option<i64>,
+    #[serde(rename = "repaymentType")]
+    pub repayment_type: Option<String>,
+    #[serde(rename = "paymentIban")]
+    pub payment_iban: Option<String>,
+    #[serde(rename = "paymentVariableSymbol")]
+    pub payment_variable_symbol: Option<String>,
+}
+
+/// One monthly installment of a loan
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LoanInstallment {
+    pub date: i64,
+    /// Installment paid or due, in the loan currency
+    pub payment: String,
+    pub interest: String,
+    pub principal: String,
+    /// Outstanding principal after the installment
+    pub balance: String,
+    /// "paid" (matched in bank transactions), "assumed" (past, paid as scheduled)
+    /// or "upcoming"
+    pub status: String,
+}
+
+/// Amortization schedule of a loan with its current outstanding principal
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LoanSchedule {
+    #[serde(rename = "loanId")]
+    pub loan_id: String,
+    #[serde(rename = "outstandingPrincipal")]
+    pub outstanding_principal: String,
+    #[serde(rename = "outstandingPrincipalCzk")]
+    pub outstanding_principal_czk: f64,
+    #[serde(rename = "paidPrincipal")]
+    pub paid_principal: String,
+    #[serde(rename = "paidInterest")]
+    pub paid_interest: String,
+    /// Interest of the upcoming installments
+    #[serde(rename = "remainingInterest")]
+    pub remaining_interest: String,
+    /// Date of the last installment, if the loan is paid off within the schedule
+    #[serde(rename = "payoffDate")]
+    pub payoff_date: Option<i64>,
+    /// Number of installments matched in bank transactions
+    #[serde(rename = "matchedPayments")]
+    pub matched_payments: i32,
+    pub installments: Vec<LoanInstallment>,
+}
+
+/// A one-off extra payment of principal
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct LoanPrepayment {
+    pub date: i64,
+    pub amount: String,
+}
+
+/// What-if scenario for a loan: prepayments and/or a new rate after refixation
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+pub struct LoanScenario {
+    pub name: String,
+    #[serde(rename = "oneOffPrepayments", default)]
+    pub one_off_prepayments: Vec<LoanPrepayment>,
+    /// Extra principal paid with every installment
+    #[serde(rename = "monthlyPrepayment")]
+    pub monthly_prepayment: Option<String>,
+    /// "shorten_term" keeps the installment, "lower_payment" keeps the payoff date
+    pub strategy: Option<String>,
+    /// Annual rate (percent) from the end of the current fixation
+    #[serde(rename = "refixationRate")]
+    pub refixation_rate: Option<String>,
+    /// Share of the outstanding principal (percent) that can be prepaid each year without a
+    /// fee; none when unset
+    #[serde(rename = "feeFreePercent")]
+    pub fee_free_percent: Option<String>,
+    /// Fee (percent) charged on prepayments above the fee-free limit
+    #[serde(rename = "prepaymentFeePercent")]
+    pub prepayment_fee_percent: Option<String>,
+}
+
+/// Outcome of a loan scenario from today until payoff
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LoanScenarioResult {
+    pub name: String,
+    /// Next regular installment
+    #[serde(rename = "monthlyPayment")]
+    pub monthly_payment: String,
+    /// Regular installment after the refixation, if the rate changes
+    #[serde(rename = "paymentAfterRefixation")]
+    pub payment_after_refixation: Option<String>,
+    #[serde(rename = "totalInterest")]
+    pub total_interest: String,
+    /// Interest saved against keeping the loan as it is
+    #[serde(rename = "interestSaved")]
+    pub interest_saved: String,
+    #[serde(rename = "totalPrepaid")]
+    pub total_prepaid: String,
+    /// Prepayment fees charged above the fee-free limit
+    pub fees: String,
+    #[serde(rename = "payoffDate")]
+    pub payoff_date: Option<i64>,
+    /// Remaining installments
+    pub installments: i32,
+}
+
+/// Loan scenarios compared against the current schedule
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct LoanSimulation {
+    #[serde(rename = "loanId")]
+    pub loan_id: String,
+    pub currency: String,
+    #[serde(rename = "outstandingPrincipal")]
+    pub outstanding_principal: String,
+    /// End of the current rate fixation, if it is still ahead
+    #[serde(rename = "refixationDate")]
+    pub refixation_date: Option<i64>,
+    pub baseline: LoanScenarioResult,
+    pub scenarios: Vec<LoanScenarioResult>,
 }
 
 // Input validation at trust boundary
@@ -105,6 +230,15 @@ impl InsertLoan {
             }
         }
 
+        // Repayment type validation (if provided)
+        if let Some(ref repayment_type) = self.repayment_type {
+            if !crate::services::loans::REPAYMENT_TYPES.contains(&repayment_type.as_str()) {
+                return Err(AppError::Validation(
+                    "validation.repaymentTypeInvalid".into(),
+                ));
+            }
+        }
+
         // Date validation
         if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
             if end <= start {
@@ -115,3 +249,58 @@ impl InsertLoan {
         Ok(())
     }
 }
+
+impl LoanScenario {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.name.len() > 100 {
+            return Err(AppError::Validation(
+                "validation.scenarioNameInvalid".into(),
+            ));
+        }
+
+        if let Some(ref strategy) = self.strategy {
+            if !crate::services::loans::PREPAYMENT_STRATEGIES.contains(&strategy.as_str()) {
+                return Err(AppError::Validation(
+                    "validation.prepaymentStrategyInvalid".into(),
+                ));
+            }
+        }
+
+        let amounts = self
+            .one_off_prepayments
+            .iter()
+            .map(|p| Some(&p.amount))
+            .chain([self.monthly_prepayment.as_ref()]);
+        for amount in amounts.flatten() {
+            if amount.is_empty() {
+                continue;
+            }
+            match amount.parse::<f64>() {
+                Ok(value) if value >= 0.0 => {}
+                _ => return Err(AppError::Validation("validation.prepaymentInvalid".into())),
+            }
+        }
+
+        let percents = [
+            &self.refixation_rate,
+            &self.fee_free_percent,
+            &self.prepayment_fee_percent,
+        ];
+        for percent in percents.into_iter().flatten() {
+            if percent.is_empty() {
+                continue;
+            }
+            match percent.parse::<f64>() {
+                Ok(value) if (0.0..=100.0).contains(&value) => {}
+                _ => {
+                    return Err(AppError::Validation(
+                        "validation.interestRatePositive".into(),
+                    ))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -4,16 +4,24 @@
 
 #![allow(dead_code)]
 
+pub mod allocation;
 pub mod bank_accounts;
 pub mod bank_transactions;
+pub mod benchmarks;
 pub mod bonds;
+pub mod brokerage_accounts;
 pub mod budgeting;
 pub mod cashflow;
 pub mod crypto;
+pub mod dividends;
+pub mod exchange_rates;
+pub mod exposure;
+pub mod goals;
 pub mod insurance;
 pub mod investments;
 pub mod loans;
 pub mod other_assets;
+pub mod price_providers;
 pub mod projection;
 pub mod real_estate;
 pub mod savings;
@@ -21,16 +29,24 @@ pub mod stock_tags;
 pub mod user;
 
 // Re-export commonly used types
+pub use allocation::*;
 pub use bank_accounts::*;
 pub use bank_transactions::*;
+pub use benchmarks::*;
 pub use bonds::*;
+pub use brokerage_accounts::*;
 pub use budgeting::*;
 pub use cashflow::*;
 pub use crypto::*;
+pub use dividends::*;
+pub use exchange_rates::*;
+pub use exposure::*;
+pub use goals::*;
 pub use insurance::*;
 pub use investments::*;
 pub use loans::*;
 pub use other_assets::*;
+pub use price_providers::*;
 pub use projection::*;
 pub use real_estate::*;
 pub use savings::*;
@@ -0,0 +1,155 @@
+//! Price provider models: provider identifiers, fallback order and per-ticker preferences
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Source of market prices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceProviderId {
+    Yahoo,
+    #[serde(rename = "coingecko")]
+    CoinGecko,
+    Stooq,
+    AlphaVantage,
+    /// User-maintained CSV/JSON price file
+    LocalFile,
+}
+
+impl PriceProviderId {
+    pub const ALL: [PriceProviderId; 5] = [
+        PriceProviderId::Yahoo,
+        PriceProviderId::CoinGecko,
+        PriceProviderId::Stooq,
+        PriceProviderId::AlphaVantage,
+        PriceProviderId::LocalFile,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceProviderId::Yahoo => "yahoo",
+            PriceProviderId::CoinGecko => "coingecko",
+            PriceProviderId::Stooq => "stooq",
+            PriceProviderId::AlphaVantage => "alpha_vantage",
+            PriceProviderId::LocalFile => "local_file",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_str() == value)
+    }
+}
+
+/// Asset kind a price is requested for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceAssetKind {
+    Stock,
+    Crypto,
+}
+
+impl PriceAssetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceAssetKind::Stock => "stock",
+            PriceAssetKind::Crypto => "crypto",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stock" => Some(PriceAssetKind::Stock),
+            "crypto" => Some(PriceAssetKind::Crypto),
+            _ => None,
+        }
+    }
+}
+
+/// Global provider settings: fallback order per asset kind and the local price file
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceProviderSettings {
+    /// Providers tried in order for stocks
+    pub stock_order: Vec<PriceProviderId>,
+    /// Providers tried in order for crypto
+    pub crypto_order: Vec<PriceProviderId>,
+    /// Path of the CSV/JSON file read by the local file provider
+    pub local_file_path: Option<String>,
+}
+
+/// Provider tried first for one ticker, before the fallback order
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceProviderPreference {
+    pub ticker: String,
+    pub asset_kind: PriceAssetKind,
+    pub provider: PriceProviderId,
+    pub updated_at: i64,
+}
+
+/// Data for setting a per-ticker provider preference
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertPriceProviderPreference {
+    pub ticker: String,
+    pub asset_kind: PriceAssetKind,
+    pub provider: PriceProviderId,
+}
+
+// Input validation at trust boundary
+use crate::error::{AppError, Result};
+
+impl PriceProviderSettings {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        for (order, kind) in [
+            (&self.stock_order, PriceAssetKind::Stock),
+            (&self.crypto_order, PriceAssetKind::Crypto),
+        ] {
+            if order.is_empty() {
+                return Err(AppError::Validation(
+                    "validation.priceProviderOrderEmpty".into(),
+                ));
+            }
+            for (i, provider) in order.iter().enumerate() {
+                if order[..i].contains(provider) {
+                    return Err(AppError::Validation(
+                        "validation.priceProviderDuplicate".into(),
+                    ));
+                }
+                if !crate::services::price_providers::supports(*provider, kind) {
+                    return Err(AppError::Validation(
+                        "validation.priceProviderUnsupported".into(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(ref path) = self.local_file_path {
+            if path.len() > 1000 {
+                return Err(AppError::Validation("validation.pathTooLong".into()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl InsertPriceProviderPreference {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        let ticker = self.ticker.trim();
+        if ticker.is_empty() {
+            return Err(AppError::Validation("validation.tickerRequired".into()));
+        }
+        if ticker.len() > 20 {
+            return Err(AppError::Validation("validation.tickerTooLong".into()));
+        }
+        if !crate::services::price_providers::supports(self.provider, self.asset_kind) {
+            return Err(AppError::Validation(
+                "validation.priceProviderUnsupported".into(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,10 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
+/// Where a valuation comes from: an appraisal, a price index, a sale listing
+/// of a comparable property, or an own estimate
+pub const VALUATION_SOURCES: [&str; 4] = ["appraisal", "index", "listing", "manual"];
+
 /// Recurring cost for real estate
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct RecurringCost {
@@ -24,6 +28,8 @@ pub struct RealEstate {
     pub purchase_price: String,
     #[serde(rename = "purchasePriceCurrency")]
     pub purchase_price_currency: String,
+    #[serde(rename = "purchaseDate")]
+    pub purchase_date: Option<i64>,
     #[serde(rename = "marketPrice")]
     pub market_price: String,
     #[serde(rename = "marketPriceCurrency")]
@@ -53,6 +59,8 @@ pub struct InsertRealEstate {
     pub purchase_price: Option<String>,
     #[serde(rename = "purchasePriceCurrency")]
     pub purchase_price_currency: Option<String>,
+    #[serde(rename = "purchaseDate")]
+    pub purchase_date: Option<i64>,
     #[serde(rename = "marketPrice")]
     pub market_price: Option<String>,
     #[serde(rename = "marketPriceCurrency")]
@@ -94,6 +102,124 @@ pub struct InsertRealEstateOneTimeCost {
     pub date: i64,
 }
 
+/// Dated market value of a property
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct RealEstateValuation {
+    pub id: String,
+    #[serde(rename = "realEstateId")]
+    pub real_estate_id: String,
+    #[serde(rename = "valuationDate")]
+    pub valuation_date: i64,
+    pub value: String,
+    pub currency: String,
+    /// One of `VALUATION_SOURCES`
+    pub source: String,
+    pub notes: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// Data for adding a valuation
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct InsertRealEstateValuation {
+    #[serde(rename = "realEstateId")]
+    pub real_estate_id: String,
+    #[serde(rename = "valuationDate")]
+    pub valuation_date: i64,
+    pub value: String,
+    pub currency: Option<String>,
+    pub source: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Property value, linked loan balance and equity on a day, in the market price currency
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RealEstateValuePoint {
+    pub date: i64,
+    pub value: f64,
+    pub loan_balance: f64,
+    pub equity: f64,
+}
+
+/// Value history and appreciation of a property, in the market price currency
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RealEstateValueHistory {
+    pub real_estate_id: String,
+    pub currency: String,
+    /// Purchase price converted to `currency`
+    pub purchase_price: String,
+    pub purchase_date: Option<i64>,
+    pub current_value: String,
+    /// Outstanding principal of the linked loans
+    pub loan_balance: String,
+    pub equity: String,
+    /// Current value minus purchase price
+    pub appreciation: String,
+    pub appreciation_percent: Option<f64>,
+    /// Compound yearly appreciation since the purchase date
+    pub annualized_appreciation_percent: Option<f64>,
+    pub points: Vec<RealEstateValuePoint>,
+}
+
+/// Whether a rental figure comes from bank transactions, from the property terms or both
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PerformanceSource {
+    /// Linked transactions or matched loan payments of the trailing year
+    Actual,
+    /// Monthly rent, recurring costs, insurance premiums or scheduled installments
+    Estimated,
+    /// Linked transactions together with estimates from the property terms
+    Mixed,
+}
+
+/// Rental performance of a property over the trailing year, in the market price currency
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RentalPerformance {
+    pub real_estate_id: String,
+    pub name: String,
+    pub currency: String,
+    pub annual_rent: String,
+    pub rent_source: PerformanceSource,
+    /// Recurring costs and insurance premiums, plus linked debits
+    pub operating_costs: String,
+    pub costs_source: PerformanceSource,
+    pub net_operating_income: String,
+    /// Loan installments, principal and interest
+    pub debt_service: String,
+    pub debt_source: PerformanceSource,
+    pub annual_cash_flow: String,
+    /// Purchase price plus one-time costs
+    pub total_cost: String,
+    /// Total cost minus the principal of the linked loans
+    pub cash_invested: String,
+    pub current_value: String,
+    pub equity: String,
+    /// Cash flow over the holding period: linked transactions where there are
+    /// any, the property terms for the rest
+    pub cumulative_cash_flow: String,
+    pub years_held: f64,
+    /// Annual rent over total cost
+    pub gross_yield_percent: Option<f64>,
+    /// Net operating income over total cost
+    pub net_yield_percent: Option<f64>,
+    /// Net operating income over current value
+    pub cap_rate_percent: Option<f64>,
+    /// Annual cash flow over cash invested
+    pub cash_on_cash_percent: Option<f64>,
+    /// Equity plus cumulative cash flow, less cash invested, over cash invested
+    pub total_roi_percent: Option<f64>,
+    /// Monthly rent covering operating costs and debt service
+    pub break_even_monthly_rent: String,
+    /// Share of the annual rent needed to cover operating costs and debt service
+    pub break_even_occupancy_percent: Option<f64>,
+    /// Years of annual cash flow returning the cash invested
+    pub payback_years: Option<f64>,
+}
+
 /// Photo batch (groups photos by date and description)
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct RealEstatePhotoBatch {
@@ -283,6 +409,42 @@ impl InsertRealEstateOneTimeCost {
     }
 }
 
+impl InsertRealEstateValuation {
+    /// Validate input data at the trust boundary
+    pub fn validate(&self) -> Result<()> {
+        if self.real_estate_id.is_empty() {
+            return Err(AppError::Validation(
+                "validation.realEstateIdRequired".into(),
+            ));
+        }
+
+        let value: f64 = self
+            .value
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Validation("validation.invalidAmount".into()))?;
+        if value < 0.0 {
+            return Err(AppError::Validation("validation.priceNonNegative".into()));
+        }
+
+        if let Some(ref currency) = self.currency {
+            if currency.len() != 3 {
+                return Err(AppError::Validation("validation.currencyInvalid".into()));
+            }
+        }
+
+        if let Some(ref source) = self.source {
+            if !VALUATION_SOURCES.contains(&source.as_str()) {
+                return Err(AppError::Validation(
+                    "validation.valuationSourceInvalid".into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl InsertRealEstateDocument {
     /// Validate input data at the trust boundary
     pub fn validate(&self) -> Result<()> {
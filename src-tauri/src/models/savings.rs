@@ -72,6 +72,62 @@ pub struct InsertSavingsAccountZone {
     pub interest_rate: String,
 }
 
+/// Interest accrued on a savings account or term deposit since the last capitalization
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct InterestAccrual {
+    #[serde(rename = "accountId")]
+    pub account_id: String,
+    #[serde(rename = "accountName")]
+    pub account_name: String,
+    pub currency: String,
+    /// Yearly interest over the balance across rate zones, percent
+    #[serde(rename = "effectiveRate")]
+    pub effective_rate: f64,
+    /// Gross interest accrued since `last_capitalization`
+    #[serde(rename = "accruedInterest")]
+    pub accrued_interest: String,
+    /// Withholding tax on the accrued interest
+    #[serde(rename = "accruedTax")]
+    pub accrued_tax: String,
+    #[serde(rename = "accruedNet")]
+    pub accrued_net: String,
+    #[serde(rename = "lastCapitalization")]
+    pub last_capitalization: i64,
+    #[serde(rename = "nextCapitalization")]
+    pub next_capitalization: Option<i64>,
+    /// End of the current term of a term deposit (rolled over when it renews)
+    #[serde(rename = "maturityDate")]
+    pub maturity_date: Option<i64>,
+    /// Principal and net interest paid out or renewed at `maturity_date`
+    #[serde(rename = "maturityPayout")]
+    pub maturity_payout: Option<String>,
+    /// Net interest credited over the next year
+    #[serde(rename = "projectedYearlyInterest")]
+    pub projected_yearly_interest: String,
+}
+
+/// A projected interest credit or term deposit payout
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct InterestPayment {
+    #[serde(rename = "accountId")]
+    pub account_id: String,
+    #[serde(rename = "accountName")]
+    pub account_name: String,
+    pub date: i64,
+    /// "interest" (capitalized) or "maturity" (principal and interest paid out)
+    pub kind: String,
+    #[serde(rename = "grossInterest")]
+    pub gross_interest: String,
+    pub tax: String,
+    #[serde(rename = "netInterest")]
+    pub net_interest: String,
+    /// Net interest, or principal and net interest at maturity
+    pub amount: String,
+    pub currency: String,
+    #[serde(rename = "amountCzk")]
+    pub amount_czk: f64,
+}
+
 // Input validation at trust boundary
 use crate::error::{AppError, Result};
 
@@ -116,6 +116,9 @@ pub struct RecoverData {
 }
 
 /// Portfolio metrics history entry
+///
+/// Stored in CZK; `get_portfolio_history` returns amounts in the reporting
+/// currency at the rate of `recorded_at`.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct PortfolioMetricsHistory {
     pub id: String,
@@ -0,0 +1,434 @@
+//! Target allocation service
+//!
+//! Targets are stored per stock tag (percent of the stock portfolio) or per
+//! asset class (percent of total assets). Current values are gathered by the
+//! command layer and passed in, so drift and rebalancing stay pure functions
+//! of values and targets.
+//!
+//! Stocks can carry several tags, so tag groups may overlap; rebalancing
+//! amounts per tag are then approximate and should be read per tag.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    AllocationDrift, AllocationKind, AllocationReport, AllocationTarget, DriftStatus,
+    InsertAllocationTarget, RebalancePlan, RebalanceTrade,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Default tolerance band in percentage points
+const DEFAULT_TOLERANCE: f64 = 5.0;
+
+/// Current value of one tag or asset class
+#[derive(Debug, Clone)]
+pub struct AllocationGroup {
+    pub key: String,
+    pub name: String,
+    pub value: f64,
+}
+
+fn parse_kind(value: &str) -> AllocationKind {
+    match value {
+        "tag" => AllocationKind::Tag,
+        _ => AllocationKind::AssetClass,
+    }
+}
+
+fn row_to_target(row: &rusqlite::Row) -> rusqlite::Result<AllocationTarget> {
+    let kind: String = row.get(1)?;
+    Ok(AllocationTarget {
+        id: row.get(0)?,
+        kind: parse_kind(&kind),
+        target_key: row.get(2)?,
+        target_percent: row.get(3)?,
+        tolerance_percent: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// Get allocation targets, optionally only of one kind
+pub fn get_targets(
+    conn: &rusqlite::Connection,
+    kind: Option<AllocationKind>,
+) -> Result<Vec<AllocationTarget>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, target_key, target_percent, tolerance_percent, updated_at
+         FROM allocation_targets
+         WHERE ?1 IS NULL OR kind = ?1
+         ORDER BY kind, target_key",
+    )?;
+    let targets = stmt
+        .query_map([kind.map(|k| k.as_str())], row_to_target)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(targets)
+}
+
+/// Create or replace the target for a tag or asset class
+pub fn set_target(
+    conn: &rusqlite::Connection,
+    data: &InsertAllocationTarget,
+) -> Result<AllocationTarget> {
+    if data.kind == AllocationKind::Tag {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM stock_tags WHERE id = ?1)",
+            [&data.target_key],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(AppError::NotFound("Stock tag not found".into()));
+        }
+    }
+
+    let target_percent: f64 = data.target_percent.trim().parse().unwrap_or(0.0);
+    let others: f64 = get_targets(conn, Some(data.kind))?
+        .iter()
+        .filter(|t| t.target_key != data.target_key)
+        .map(|t| t.target_percent.parse::<f64>().unwrap_or(0.0))
+        .sum();
+    if others + target_percent > 100.0 + 1e-9 {
+        return Err(AppError::Validation(
+            "validation.allocationTotalTooHigh".into(),
+        ));
+    }
+
+    let tolerance = data
+        .tolerance_percent
+        .as_deref()
+        .map(|t| t.trim().to_string())
+        .unwrap_or_else(|| DEFAULT_TOLERANCE.to_string());
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO allocation_targets (id, kind, target_key, target_percent, tolerance_percent, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(kind, target_key) DO UPDATE SET
+             target_percent = excluded.target_percent,
+             tolerance_percent = excluded.tolerance_percent,
+             updated_at = excluded.updated_at",
+        rusqlite::params![
+            Uuid::new_v4().to_string(),
+            data.kind.as_str(),
+            data.target_key,
+            data.target_percent.trim(),
+            tolerance,
+            now
+        ],
+    )?;
+
+    conn.query_row(
+        "SELECT id, kind, target_key, target_percent, tolerance_percent, updated_at
+         FROM allocation_targets WHERE kind = ?1 AND target_key = ?2",
+        [data.kind.as_str(), data.target_key.as_str()],
+        row_to_target,
+    )
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Delete an allocation target
+pub fn delete_target(conn: &rusqlite::Connection, id: &str) -> Result<()> {
+    let changes = conn.execute("DELETE FROM allocation_targets WHERE id = ?1", [id])?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Allocation target not found".into()));
+    }
+    Ok(())
+}
+
+/// Remove the target of a deleted tag
+pub fn delete_tag_target(conn: &rusqlite::Connection, tag_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM allocation_targets WHERE kind = 'tag' AND target_key = ?1",
+        [tag_id],
+    )?;
+    Ok(())
+}
+
+/// Build the drift report for the given groups against the stored targets.
+/// `total_value` is the base for percentages (tags may overlap, so it is not
+/// always the sum of the groups).
+pub fn build_report(
+    conn: &rusqlite::Connection,
+    kind: AllocationKind,
+    groups: &[AllocationGroup],
+    total_value: f64,
+) -> Result<AllocationReport> {
+    let targets: HashMap<String, (f64, f64)> = get_targets(conn, Some(kind))?
+        .into_iter()
+        .map(|t| {
+            let target = t.target_percent.parse().unwrap_or(0.0);
+            let tolerance = t.tolerance_percent.parse().unwrap_or(DEFAULT_TOLERANCE);
+            (t.target_key, (target, tolerance))
+        })
+        .collect();
+
+    Ok(compute_drift(kind, groups, &targets, total_value))
+}
+
+/// Compare current weights with targets (percent, tolerance) keyed by group key
+pub fn compute_drift(
+    kind: AllocationKind,
+    groups: &[AllocationGroup],
+    targets: &HashMap<String, (f64, f64)>,
+    total_value: f64,
+) -> AllocationReport {
+    let items: Vec<AllocationDrift> = groups
+        .iter()
+        .map(|group| {
+            let current_percent = if total_value > 0.0 {
+                group.value / total_value * 100.0
+            } else {
+                0.0
+            };
+
+            match targets.get(&group.key) {
+                Some(&(target, tolerance)) => {
+                    let drift = current_percent - target;
+                    let status = if drift > tolerance {
+                        DriftStatus::Over
+                    } else if drift < -tolerance {
+                        DriftStatus::Under
+                    } else {
+                        DriftStatus::Within
+                    };
+                    AllocationDrift {
+                        key: group.key.clone(),
+                        name: group.name.clone(),
+                        current_value: group.value,
+                        current_percent,
+                        target_percent: Some(target),
+                        tolerance_percent: Some(tolerance),
+                        drift_percent: Some(drift),
+                        drift_value: Some(total_value * target / 100.0 - group.value),
+                        status,
+                    }
+                }
+                None => AllocationDrift {
+                    key: group.key.clone(),
+                    name: group.name.clone(),
+                    current_value: group.value,
+                    current_percent,
+                    target_percent: None,
+                    tolerance_percent: None,
+                    drift_percent: None,
+                    drift_value: None,
+                    status: DriftStatus::NoTarget,
+                },
+            }
+        })
+        .collect();
+
+    let targets_total_percent = items.iter().filter_map(|i| i.target_percent).sum();
+    let needs_rebalancing = items
+        .iter()
+        .any(|i| matches!(i.status, DriftStatus::Over | DriftStatus::Under));
+
+    AllocationReport {
+        kind,
+        total_value,
+        targets_total_percent,
+        needs_rebalancing,
+        items,
+    }
+}
+
+/// Propose trades bringing targeted groups back to their weights.
+///
+/// With `cash_only` nothing is sold: the contribution is split between
+/// underweight groups in proportion to their shortfall. Otherwise every
+/// targeted group is bought or sold to its exact target after adding the
+/// contribution. Groups without a target are left untouched.
+pub fn plan_rebalance(
+    report: &AllocationReport,
+    contribution: f64,
+    cash_only: bool,
+) -> RebalancePlan {
+    let contribution = contribution.max(0.0);
+    let total_after = report.total_value + contribution;
+
+    let targeted: Vec<(&AllocationDrift, f64)> = report
+        .items
+        .iter()
+        .filter_map(|i| i.target_percent.map(|t| (i, total_after * t / 100.0)))
+        .collect();
+
+    let amounts: Vec<f64> = if cash_only {
+        let shortfall: Vec<f64> = targeted
+            .iter()
+            .map(|(item, target_value)| (target_value - item.current_value).max(0.0))
+            .collect();
+        let total_shortfall: f64 = shortfall.iter().sum();
+        if total_shortfall <= contribution {
+            shortfall
+        } else {
+            shortfall
+                .iter()
+                .map(|s| contribution * s / total_shortfall)
+                .collect()
+        }
+    } else {
+        targeted
+            .iter()
+            .map(|(item, target_value)| target_value - item.current_value)
+            .collect()
+    };
+
+    let net: f64 = amounts.iter().sum();
+    let trades = targeted
+        .iter()
+        .zip(amounts)
+        .map(|((item, target_value), amount)| RebalanceTrade {
+            key: item.key.clone(),
+            name: item.name.clone(),
+            current_value: item.current_value,
+            target_value: *target_value,
+            amount,
+            resulting_percent: if total_after > 0.0 {
+                (item.current_value + amount) / total_after * 100.0
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    RebalancePlan {
+        kind: report.kind,
+        cash_only,
+        contribution,
+        total_value_after: total_after,
+        unallocated_cash: (contribution - net).max(0.0),
+        trades,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE stock_tags (id TEXT PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE allocation_targets (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                target_key TEXT NOT NULL,
+                target_percent TEXT NOT NULL,
+                tolerance_percent TEXT NOT NULL DEFAULT '5',
+                updated_at INTEGER NOT NULL DEFAULT 0,
+                UNIQUE(kind, target_key)
+             );
+             INSERT INTO stock_tags (id, name) VALUES ('tech', 'Tech');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn group(key: &str, value: f64) -> AllocationGroup {
+        AllocationGroup {
+            key: key.to_string(),
+            name: key.to_string(),
+            value,
+        }
+    }
+
+    fn target(kind: AllocationKind, key: &str, percent: &str) -> InsertAllocationTarget {
+        InsertAllocationTarget {
+            kind,
+            target_key: key.to_string(),
+            target_percent: percent.to_string(),
+            tolerance_percent: None,
+        }
+    }
+
+    #[test]
+    fn test_set_target_upserts_and_limits_total() {
+        let conn = setup_test_db();
+        set_target(&conn, &target(AllocationKind::AssetClass, "stocks", "60")).unwrap();
+        set_target(&conn, &target(AllocationKind::AssetClass, "bonds", "40")).unwrap();
+        // Replacing an existing target does not count it twice
+        set_target(&conn, &target(AllocationKind::AssetClass, "stocks", "50")).unwrap();
+        assert_eq!(get_targets(&conn, None).unwrap().len(), 2);
+
+        let err = set_target(&conn, &target(AllocationKind::AssetClass, "crypto", "20"));
+        assert!(matches!(err, Err(AppError::Validation(_))));
+
+        let err = set_target(&conn, &target(AllocationKind::Tag, "missing", "10"));
+        assert!(matches!(err, Err(AppError::NotFound(_))));
+        set_target(&conn, &target(AllocationKind::Tag, "tech", "30")).unwrap();
+        delete_tag_target(&conn, "tech").unwrap();
+        assert!(get_targets(&conn, Some(AllocationKind::Tag))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_drift_status_with_tolerance() {
+        let groups = vec![
+            group("stocks", 70.0),
+            group("bonds", 30.0),
+            group("crypto", 0.0),
+        ];
+        let targets = HashMap::from([
+            ("stocks".to_string(), (60.0, 5.0)),
+            ("bonds".to_string(), (40.0, 15.0)),
+        ]);
+        let report = compute_drift(AllocationKind::AssetClass, &groups, &targets, 100.0);
+
+        assert_eq!(report.items[0].status, DriftStatus::Over);
+        assert_eq!(report.items[1].status, DriftStatus::Within);
+        assert_eq!(report.items[2].status, DriftStatus::NoTarget);
+        assert!((report.items[0].drift_value.unwrap() + 10.0).abs() < 1e-9);
+        assert!(report.needs_rebalancing);
+        assert!((report.targets_total_percent - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_full_rebalance_with_contribution() {
+        let groups = vec![group("stocks", 70.0), group("bonds", 30.0)];
+        let targets = HashMap::from([
+            ("stocks".to_string(), (60.0, 5.0)),
+            ("bonds".to_string(), (40.0, 5.0)),
+        ]);
+        let report = compute_drift(AllocationKind::AssetClass, &groups, &targets, 100.0);
+        let plan = plan_rebalance(&report, 100.0, false);
+
+        // 200 after contribution: stocks 120, bonds 80
+        assert!((plan.trades[0].amount - 50.0).abs() < 1e-9);
+        assert!((plan.trades[1].amount - 50.0).abs() < 1e-9);
+        assert!(plan.unallocated_cash.abs() < 1e-9);
+
+        let plan = plan_rebalance(&report, 0.0, false);
+        assert!((plan.trades[0].amount + 10.0).abs() < 1e-9);
+        assert!((plan.trades[1].amount - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cash_only_rebalance_never_sells() {
+        let groups = vec![
+            group("stocks", 80.0),
+            group("bonds", 20.0),
+            group("crypto", 0.0),
+        ];
+        let targets = HashMap::from([
+            ("stocks".to_string(), (50.0, 5.0)),
+            ("bonds".to_string(), (30.0, 5.0)),
+            ("crypto".to_string(), (20.0, 5.0)),
+        ]);
+        let report = compute_drift(AllocationKind::AssetClass, &groups, &targets, 100.0);
+
+        // 120 after: bonds short 16, crypto short 24, total 40 > 20 -> split 8/12
+        let plan = plan_rebalance(&report, 20.0, true);
+        assert!(plan.trades.iter().all(|t| t.amount >= 0.0));
+        assert!(plan.trades[0].amount.abs() < 1e-9);
+        assert!((plan.trades[1].amount - 8.0).abs() < 1e-9);
+        assert!((plan.trades[2].amount - 12.0).abs() < 1e-9);
+
+        // Targets covering only half the portfolio leave cash unallocated
+        let half = HashMap::from([("bonds".to_string(), (30.0, 5.0))]);
+        let report = compute_drift(AllocationKind::AssetClass, &groups, &half, 100.0);
+        let plan = plan_rebalance(&report, 100.0, true);
+        assert!((plan.trades[0].amount - 40.0).abs() < 1e-9);
+        assert!((plan.unallocated_cash - 60.0).abs() < 1e-9);
+    }
+}
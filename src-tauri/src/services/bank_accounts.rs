@@ -6,6 +6,9 @@
 
 use crate::error::{AppError, Result};
 use crate::models::{BankAccount, InsertBankAccount};
+use crate::services::exchange_rate_history::HistoricalRates;
+use crate::services::interest;
+use crate::services::money::{self, Decimal};
 use rusqlite::params;
 use uuid::Uuid;
 
@@ -15,7 +18,8 @@ pub fn get_account_by_id(conn: &rusqlite::Connection, id: &str) -> Result<BankAc
     let account = conn.query_row(
         "SELECT id, name, account_type, iban, bban, currency, balance, institution_id,
          external_account_id, data_source, last_synced_at, interest_rate, has_zone_designation,
-         termination_date, created_at, updated_at FROM bank_accounts WHERE id = ?1",
+         termination_date, created_at, updated_at, deposit_start_date, auto_renew,
+         capitalization, interest_tax_rate FROM bank_accounts WHERE id = ?1",
         [id],
         |row| {
             Ok(BankAccount {
@@ -33,6 +37,10 @@ pub fn get_account_by_id(conn: &rusqlite::Connection, id: &str) -> Result<BankAc
                 interest_rate: row.get(11)?,
                 has_zone_designation: row.get::<_, i32>(12)? != 0,
                 termination_date: row.get(13)?,
+                deposit_start_date: row.get(16)?,
+                auto_renew: row.get::<_, i32>(17)? != 0,
+                capitalization: row.get(18)?,
+                interest_tax_rate: row.get(19)?,
                 created_at: row.get(14)?,
                 updated_at: row.get(15)?,
             })
@@ -56,13 +64,23 @@ pub fn create_account(
     let currency = data.currency.clone().unwrap_or_else(|| "CZK".to_string());
     let balance = data.balance.clone().unwrap_or_else(|| "0".to_string());
     let has_zone = data.has_zone_designation.unwrap_or(false);
+    let auto_renew = data.auto_renew.unwrap_or(false);
+    let capitalization = data
+        .capitalization
+        .clone()
+        .unwrap_or_else(|| "monthly".to_string());
+    let interest_tax_rate = data
+        .interest_tax_rate
+        .clone()
+        .unwrap_or_else(|| interest::DEFAULT_TAX_RATE.to_string());
 
     conn.execute(
         "INSERT INTO bank_accounts (
             id, name, account_type, iban, bban, currency, balance,
             institution_id, data_source, interest_rate, has_zone_designation,
-            termination_date, created_at, updated_at
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            termination_date, created_at, updated_at, deposit_start_date, auto_renew,
+            capitalization, interest_tax_rate
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
         params![
             id,
             data.name,
@@ -78,6 +96,10 @@ pub fn create_account(
             data.termination_date,
             now,
             now,
+            data.deposit_start_date,
+            auto_renew as i32,
+            capitalization,
+            interest_tax_rate,
         ],
     )?;
 
@@ -96,6 +118,10 @@ pub fn create_account(
         interest_rate: data.interest_rate.clone(),
         has_zone_designation: has_zone,
         termination_date: data.termination_date,
+        deposit_start_date: data.deposit_start_date,
+        auto_renew,
+        capitalization,
+        interest_tax_rate,
         created_at: now,
         updated_at: now,
     })
@@ -119,12 +145,22 @@ pub fn update_account(
     let has_zone = data
         .has_zone_designation
         .unwrap_or(existing.has_zone_designation);
+    let auto_renew = data.auto_renew.unwrap_or(existing.auto_renew);
+    let capitalization = data
+        .capitalization
+        .clone()
+        .unwrap_or(existing.capitalization);
+    let interest_tax_rate = data
+        .interest_tax_rate
+        .clone()
+        .unwrap_or(existing.interest_tax_rate);
 
     conn.execute(
         "UPDATE bank_accounts SET 
             name = ?1, account_type = ?2, iban = ?3, bban = ?4, currency = ?5,
             balance = ?6, institution_id = ?7, interest_rate = ?8, has_zone_designation = ?9,
-            termination_date = ?10, updated_at = ?11
+            termination_date = ?10, updated_at = ?11, deposit_start_date = ?13, auto_renew = ?14,
+            capitalization = ?15, interest_tax_rate = ?16
         WHERE id = ?12",
         params![
             data.name,
@@ -139,6 +175,10 @@ pub fn update_account(
             data.termination_date,
             now,
             id,
+            data.deposit_start_date,
+            auto_renew as i32,
+            capitalization,
+            interest_tax_rate,
         ],
     )?;
 
@@ -157,6 +197,10 @@ pub fn update_account(
         interest_rate: data.interest_rate.clone(),
         has_zone_designation: has_zone,
         termination_date: data.termination_date,
+        deposit_start_date: data.deposit_start_date,
+        auto_renew,
+        capitalization,
+        interest_tax_rate,
         created_at: existing.created_at,
         updated_at: now,
     })
@@ -173,6 +217,79 @@ pub fn delete_account(conn: &rusqlite::Connection, id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Balance of every account counted in savings at the end of `day_ts`, in the
+/// account currency
+///
+/// The current balance is rolled back by the booked transactions after the day,
+/// each converted at the rate of its booking day, so accounts without
+/// transactions keep their current balance. Accounts with transactions hold
+/// nothing before they were created or their first booked transaction,
+/// whichever is earlier (e.g. history imported from the bank).
+pub fn balances_on(
+    conn: &rusqlite::Connection,
+    day_ts: i64,
+    fx: &HistoricalRates,
+) -> Result<Vec<(String, Decimal)>> {
+    let day_end = (day_ts / 86400) * 86400 + 86399;
+
+    let mut stmt = conn.prepare(
+        "SELECT a.id, a.currency, a.balance, a.created_at,
+                (SELECT MIN(t.booking_date) FROM bank_transactions t
+                 WHERE t.bank_account_id = a.id AND t.status = 'booked')
+         FROM bank_accounts a WHERE a.exclude_from_balance = 0",
+    )?;
+    let accounts: Vec<(String, String, String, i64, Option<i64>)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut tx_stmt = conn.prepare(
+        "SELECT tx_type, amount, currency, booking_date FROM bank_transactions
+         WHERE bank_account_id = ?1 AND booking_date > ?2 AND status = 'booked'",
+    )?;
+    let mut balances = Vec::with_capacity(accounts.len());
+    for (id, currency, balance, created_at, first_booking) in accounts {
+        if first_booking.is_some_and(|first| day_end < first.min(created_at)) {
+            balances.push((currency, Decimal::ZERO));
+            continue;
+        }
+        let later: Decimal = tx_stmt
+            .query_map(params![id, day_end], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .map(|(tx_type, amount, tx_currency, booked)| {
+                let amount = fx.convert_decimal(
+                    money::parse_or_zero(&amount).abs(),
+                    &tx_currency,
+                    &currency,
+                    booked,
+                );
+                if tx_type.eq_ignore_ascii_case("credit") {
+                    amount
+                } else {
+                    -amount
+                }
+            })
+            .sum();
+        balances.push((currency, money::parse_or_zero(&balance) - later));
+    }
+    Ok(balances)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,8 +315,22 @@ mod tests {
                 interest_rate TEXT,
                 has_zone_designation INTEGER NOT NULL DEFAULT 0,
                 termination_date INTEGER,
+                exclude_from_balance INTEGER NOT NULL DEFAULT 0,
                 created_at INTEGER NOT NULL DEFAULT 0,
-                updated_at INTEGER NOT NULL DEFAULT 0
+                updated_at INTEGER NOT NULL DEFAULT 0,
+                deposit_start_date INTEGER,
+                auto_renew INTEGER NOT NULL DEFAULT 0,
+                capitalization TEXT NOT NULL DEFAULT 'monthly',
+                interest_tax_rate TEXT NOT NULL DEFAULT '15'
+            );
+            CREATE TABLE bank_transactions (
+                id TEXT PRIMARY KEY,
+                bank_account_id TEXT NOT NULL,
+                tx_type TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                booking_date INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'booked'
             );
             "#,
         )
@@ -219,6 +350,10 @@ mod tests {
             interest_rate: None,
             has_zone_designation: None,
             termination_date: None,
+            deposit_start_date: None,
+            auto_renew: None,
+            capitalization: None,
+            interest_tax_rate: None,
         }
     }
 
@@ -275,4 +410,137 @@ mod tests {
         let result = delete_account(&conn, "does-not-exist");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_balances_on_rolls_back_later_transactions() {
+        let conn = setup_test_db();
+        let mut data = minimal_insert("Main Account");
+        data.balance = Some("10000".to_string());
+        let account = create_account(&conn, &data).expect("create");
+        let excluded = create_account(&conn, &minimal_insert("Excluded")).expect("create");
+        conn.execute(
+            "UPDATE bank_accounts SET exclude_from_balance = 1 WHERE id = ?1",
+            [&excluded.id],
+        )
+        .expect("exclude");
+        // 2024-01-10
+        conn.execute(
+            "UPDATE bank_accounts SET created_at = 1704844800 WHERE id = ?1",
+            [&account.id],
+        )
+        .expect("created_at");
+        conn.execute(
+            "INSERT INTO bank_transactions (id, bank_account_id, tx_type, amount, currency, booking_date, status) VALUES
+                ('t1', ?1, 'credit', '3000', 'CZK', 1706745600, 'booked'),
+                ('t2', ?1, 'debit', '500.50', 'CZK', 1709251200, 'booked'),
+                ('t3', ?1, 'debit', '999', 'CZK', 1709251200, 'pending')",
+            [&account.id],
+        )
+        .expect("transactions");
+        let fx = HistoricalRates::default();
+
+        // 2024-01-01: before the account was created
+        let balances = balances_on(&conn, 1704067200, &fx).expect("balances");
+        assert_eq!(balances, vec![("CZK".to_string(), Decimal::ZERO)]);
+
+        // 2024-01-15: before both booked transactions
+        let balances = balances_on(&conn, 1705276800, &fx).expect("balances");
+        assert_eq!(balances, vec![("CZK".to_string(), Decimal::new(750050, 2))]);
+
+        // 2024-02-01: the credit booked that day is included
+        let balances = balances_on(&conn, 1706745600, &fx).expect("balances");
+        assert_eq!(
+            balances,
+            vec![("CZK".to_string(), Decimal::new(1050050, 2))]
+        );
+
+        // Today: the current balance
+        let balances = balances_on(&conn, 1709337600, &fx).expect("balances");
+        assert_eq!(balances, vec![("CZK".to_string(), Decimal::from(10000))]);
+    }
+
+    #[test]
+    fn test_balances_on_counts_transactions_booked_before_creation() {
+        let conn = setup_test_db();
+        let mut data = minimal_insert("Imported Account");
+        data.balance = Some("5000".to_string());
+        let account = create_account(&conn, &data).expect("create");
+        // Created 2024-03-01, with history imported back to 2024-01-05
+        conn.execute(
+            "UPDATE bank_accounts SET created_at = 1709251200 WHERE id = ?1",
+            [&account.id],
+        )
+        .expect("created_at");
+        conn.execute(
+            "INSERT INTO bank_transactions (id, bank_account_id, tx_type, amount, currency, booking_date, status) VALUES
+                ('t1', ?1, 'credit', '4000', 'CZK', 1704412800, 'booked'),
+                ('t2', ?1, 'credit', '1000', 'CZK', 1706745600, 'booked'),
+                ('t3', ?1, 'credit', '700', 'CZK', 1704067200, 'pending')",
+            [&account.id],
+        )
+        .expect("transactions");
+        let fx = HistoricalRates::default();
+
+        // 2024-01-01: before the first booked transaction and the creation
+        let balances = balances_on(&conn, 1704067200, &fx).expect("balances");
+        assert_eq!(balances, vec![("CZK".to_string(), Decimal::ZERO)]);
+
+        // 2024-01-10: after the first transaction, before the account was created
+        let balances = balances_on(&conn, 1704844800, &fx).expect("balances");
+        assert_eq!(balances, vec![("CZK".to_string(), Decimal::from(4000))]);
+
+        // 2024-02-15: both transactions booked, still before the creation
+        let balances = balances_on(&conn, 1707955200, &fx).expect("balances");
+        assert_eq!(balances, vec![("CZK".to_string(), Decimal::from(5000))]);
+    }
+
+    #[test]
+    fn test_balances_on_keeps_accounts_without_transactions_and_uses_booking_day_rates() {
+        use crate::models::FxSource;
+        use crate::services::exchange_rate_history::save_history;
+
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TABLE exchange_rate_history (
+                currency TEXT NOT NULL,
+                rate_date INTEGER NOT NULL,
+                rate REAL NOT NULL,
+                source TEXT NOT NULL,
+                PRIMARY KEY (currency, rate_date, source)
+            );",
+        )
+        .expect("rates table");
+        let mut data = minimal_insert("Cash Reserve");
+        data.balance = Some("1000".to_string());
+        data.currency = Some("EUR".to_string());
+        create_account(&conn, &data).expect("create");
+        let mut data = minimal_insert("Main Account");
+        data.balance = Some("5000".to_string());
+        let account = create_account(&conn, &data).expect("create");
+        // Both created 2024-03-01
+        conn.execute("UPDATE bank_accounts SET created_at = 1709251200", [])
+            .expect("created_at");
+        conn.execute(
+            "INSERT INTO bank_transactions (id, bank_account_id, tx_type, amount, currency, booking_date, status) VALUES
+                ('t1', ?1, 'credit', '5000', 'CZK', 1704153600, 'booked'),
+                ('t2', ?1, 'debit', '100', 'EUR', 1706745600, 'booked')",
+            [&account.id],
+        )
+        .expect("transactions");
+        // 2024-02-01 fixing of the EUR debit
+        save_history(&conn, "ecb", &[(1706745600, "EUR".to_string(), 25.0)]).expect("rates");
+        let fx = HistoricalRates::load(&conn, 1704067200, 1709337600, FxSource::Ecb).expect("fx");
+
+        // 2024-01-15: the EUR debit is rolled back at its booking day rate, the
+        // account without transactions keeps its current balance
+        let mut balances = balances_on(&conn, 1705276800, &fx).expect("balances");
+        balances.sort();
+        assert_eq!(
+            balances,
+            vec![
+                ("CZK".to_string(), Decimal::from(7500)),
+                ("EUR".to_string(), Decimal::from(1000)),
+            ]
+        );
+    }
 }
@@ -0,0 +1,470 @@
+//! Benchmark service
+//!
+//! Benchmark prices are stored per day in the benchmark's quote currency and
+//! converted at the rate of each day when compared: to CZK, then to the
+//! reporting currency, so returns line up with the TWR from
+//! `investments::compute_twr_for_tickers`, which is built the same way. Benchmark returns are price
+//! returns; dividends are left out on both sides of the simulation.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    Benchmark, BenchmarkComparison, BenchmarkPoint, BenchmarkSimulation, InsertBenchmark,
+};
+use crate::services::exchange_rate_history::HistoricalRates;
+use crate::services::exchange_rates;
+use crate::services::investments as investment_service;
+use crate::services::money::{self, Decimal};
+use crate::services::price_api::get_currency_from_ticker;
+use crate::services::pricing;
+use crate::services::reporting::Reporting;
+use std::collections::BTreeMap;
+
+/// Get all benchmarks with their price coverage
+pub fn get_benchmarks(conn: &rusqlite::Connection) -> Result<Vec<Benchmark>> {
+    let mut stmt = conn.prepare(
+        "SELECT b.ticker, b.name, b.currency, b.created_at,
+                COUNT(p.recorded_at), MIN(p.recorded_at), MAX(p.recorded_at)
+         FROM benchmarks b
+         LEFT JOIN benchmark_prices p ON p.ticker = b.ticker
+         GROUP BY b.ticker
+         ORDER BY b.name",
+    )?;
+    let benchmarks = stmt
+        .query_map([], |row| {
+            Ok(Benchmark {
+                ticker: row.get(0)?,
+                name: row.get(1)?,
+                currency: row.get(2)?,
+                created_at: row.get(3)?,
+                price_count: row.get(4)?,
+                first_date: row.get(5)?,
+                last_date: row.get(6)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(benchmarks)
+}
+
+/// Get a benchmark by ticker
+pub fn get_benchmark(conn: &rusqlite::Connection, ticker: &str) -> Result<Benchmark> {
+    get_benchmarks(conn)?
+        .into_iter()
+        .find(|b| b.ticker == ticker.to_uppercase())
+        .ok_or_else(|| AppError::NotFound("Benchmark not found".into()))
+}
+
+/// Add a benchmark (or rename an existing one)
+pub fn add_benchmark(conn: &rusqlite::Connection, data: &InsertBenchmark) -> Result<Benchmark> {
+    let ticker = data.ticker.trim().to_uppercase();
+    let currency = data
+        .currency
+        .clone()
+        .or_else(|| pricing::stored_ticker_currency(conn, &ticker))
+        .unwrap_or_else(|| get_currency_from_ticker(&ticker).to_string())
+        .to_uppercase();
+
+    conn.execute(
+        "INSERT INTO benchmarks (ticker, name, currency, created_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(ticker) DO UPDATE SET name = excluded.name, currency = excluded.currency",
+        rusqlite::params![
+            ticker,
+            data.name.trim(),
+            currency,
+            chrono::Utc::now().timestamp()
+        ],
+    )?;
+
+    get_benchmark(conn, &ticker)
+}
+
+/// Delete a benchmark and its prices
+pub fn delete_benchmark(conn: &rusqlite::Connection, ticker: &str) -> Result<()> {
+    let ticker = ticker.to_uppercase();
+    conn.execute("DELETE FROM benchmark_prices WHERE ticker = ?1", [&ticker])?;
+    let changes = conn.execute("DELETE FROM benchmarks WHERE ticker = ?1", [&ticker])?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Benchmark not found".into()));
+    }
+    Ok(())
+}
+
+/// Store daily closing prices (timestamps are normalized to midnight UTC)
+pub fn store_prices(
+    conn: &rusqlite::Connection,
+    ticker: &str,
+    prices: &[(i64, f64)],
+) -> Result<usize> {
+    let ticker = ticker.to_uppercase();
+    let mut stmt = conn.prepare(
+        "INSERT INTO benchmark_prices (ticker, recorded_at, price) VALUES (?1, ?2, ?3)
+         ON CONFLICT(ticker, recorded_at) DO UPDATE SET price = excluded.price",
+    )?;
+    let mut stored = 0;
+    for (ts, price) in prices {
+        if !price.is_finite() || *price <= 0.0 {
+            continue;
+        }
+        let day = (ts / 86400) * 86400;
+        stmt.execute(rusqlite::params![ticker, day, price.to_string()])?;
+        stored += 1;
+    }
+    Ok(stored)
+}
+
+/// Daily benchmark prices converted to CZK at the rate of each day
+pub fn load_prices_czk(conn: &rusqlite::Connection, ticker: &str) -> Result<BTreeMap<i64, f64>> {
+    let ticker = ticker.to_uppercase();
+    let currency: Option<String> = conn
+        .query_row(
+            "SELECT currency FROM benchmarks WHERE ticker = ?1",
+            [&ticker],
+            |row| row.get(0),
+        )
+        .ok();
+    let Some(currency) = currency else {
+        return Ok(BTreeMap::new());
+    };
+
+    let source = exchange_rates::get_settings(conn)?.valuation_source;
+    let fx = HistoricalRates::load_currency(conn, &currency, source)?;
+
+    let mut stmt =
+        conn.prepare("SELECT recorded_at, price FROM benchmark_prices WHERE ticker = ?1")?;
+    let prices = stmt
+        .query_map([&ticker], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .filter_map(|r| r.ok())
+        .map(|(day, price)| {
+            let czk = fx.to_czk_decimal(money::parse_or_zero(&price), &currency, day);
+            (day, money::to_f64(czk))
+        })
+        .collect();
+    Ok(prices)
+}
+
+/// Last known price at or before `day`, else the first price after it
+fn price_at(prices: &BTreeMap<i64, f64>, day: i64) -> Option<f64> {
+    prices
+        .range(..=day)
+        .next_back()
+        .or_else(|| prices.range(day..).next())
+        .map(|(_, p)| *p)
+}
+
+fn date_str_to_ts(date: &str) -> Option<i64> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+/// Total value of the tickers at or before `ts` from the stock value history,
+/// in the reporting currency at the rate of `ts`
+fn group_value_at(
+    conn: &rusqlite::Connection,
+    reporting: &Reporting,
+    tickers: &[String],
+    ts: i64,
+) -> f64 {
+    let czk: Decimal = tickers
+        .iter()
+        .filter_map(|ticker| {
+            conn.query_row(
+                "SELECT value_czk FROM stock_value_history WHERE ticker = ?1 AND recorded_at <= ?2
+                 ORDER BY recorded_at DESC LIMIT 1",
+                rusqlite::params![ticker, ts],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+        })
+        .filter_map(|v| money::parse(&v))
+        .sum();
+    money::to_f64(reporting.amount_decimal_on(czk, ts))
+}
+
+/// Replay the group's buys and sells within (from_ts, to_ts] as benchmark trades
+fn simulate(
+    conn: &rusqlite::Connection,
+    reporting: &Reporting,
+    tickers: &[String],
+    prices: &BTreeMap<i64, f64>,
+    from_ts: i64,
+    to_ts: i64,
+) -> Result<Option<BenchmarkSimulation>> {
+    let (Some(start_price), Some(end_price)) = (price_at(prices, from_ts), price_at(prices, to_ts))
+    else {
+        return Ok(None);
+    };
+
+    let start_value = group_value_at(conn, reporting, tickers, from_ts);
+    let mut units = start_value / start_price;
+    let mut invested = Decimal::ZERO;
+    let mut withdrawn = Decimal::ZERO;
+    let mut cash_flow_count = 0;
+
+    let source = exchange_rates::get_settings(conn)?.valuation_source;
+    let fx = HistoricalRates::load(conn, from_ts, to_ts, source)?;
+    let mut stmt = conn.prepare(
+        "SELECT ticker, type, quantity, price_per_unit, currency, transaction_date
+         FROM investment_transactions
+         WHERE transaction_date > ?1 AND transaction_date <= ?2
+         ORDER BY transaction_date",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![from_ts, to_ts], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, i64>(5)?,
+        ))
+    })?;
+    for row in rows {
+        let (ticker, tx_type, quantity, price, currency, date) = row?;
+        if !tickers.contains(&ticker) {
+            continue;
+        }
+        let cost = money::parse_or_zero(&quantity) * money::parse_or_zero(&price);
+        let amount = reporting.amount_decimal_on(fx.to_czk_decimal(cost, &currency, date), date);
+        let Some(day_price) = price_at(prices, date).filter(|p| *p > 0.0) else {
+            continue;
+        };
+
+        match tx_type.as_str() {
+            "buy" => {
+                invested += amount;
+                units += money::to_f64(amount) / day_price;
+            }
+            "sell" => {
+                withdrawn += amount;
+                units = (units - money::to_f64(amount) / day_price).max(0.0);
+            }
+            _ => continue,
+        }
+        cash_flow_count += 1;
+    }
+
+    let actual_value = group_value_at(conn, reporting, tickers, to_ts);
+    let benchmark_value = units * end_price;
+    Ok(Some(BenchmarkSimulation {
+        start_value,
+        invested: money::to_f64(invested),
+        withdrawn: money::to_f64(withdrawn),
+        actual_value,
+        benchmark_value,
+        difference: actual_value - benchmark_value,
+        cash_flow_count,
+    }))
+}
+
+/// Compare the stock portfolio (or one tag) with a benchmark over [from_ts, to_ts]
+pub fn compare(
+    conn: &rusqlite::Connection,
+    benchmark: &str,
+    tag_id: Option<&str>,
+    from_ts: i64,
+    to_ts: i64,
+) -> Result<BenchmarkComparison> {
+    if from_ts >= to_ts {
+        return Err(AppError::Validation("validation.dateRangeInvalid".into()));
+    }
+    let benchmark = get_benchmark(conn, benchmark)?;
+
+    let tickers: Vec<String> = match tag_id {
+        Some(tag_id) => conn
+            .prepare(
+                "SELECT si.ticker FROM stock_investments si
+                 JOIN stock_investment_tags sit ON sit.investment_id = si.id
+                 WHERE sit.tag_id = ?1 ORDER BY si.ticker",
+            )?
+            .query_map([tag_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?,
+        None => conn
+            .prepare("SELECT ticker FROM stock_investments ORDER BY ticker")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?,
+    };
+
+    let twr = investment_service::compute_twr_for_tickers(conn, &tickers, from_ts, to_ts)?;
+    let reporting = Reporting::load(conn)?;
+    let prices: BTreeMap<i64, f64> = load_prices_czk(conn, &benchmark.ticker)?
+        .into_iter()
+        .map(|(day, price)| (day, reporting.amount_on(price, day)))
+        .collect();
+    let base_price = price_at(&prices, from_ts).filter(|p| *p > 0.0);
+
+    let series: Vec<BenchmarkPoint> = twr
+        .into_iter()
+        .map(|point| {
+            let benchmark_return = base_price.and_then(|base| {
+                let ts = date_str_to_ts(&point.date)?;
+                price_at(&prices, ts).map(|p| (p / base - 1.0) * 100.0)
+            });
+            BenchmarkPoint {
+                date: point.date,
+                portfolio_twr: point.twr,
+                benchmark_return,
+            }
+        })
+        .collect();
+
+    let portfolio_return = series.last().map(|p| p.portfolio_twr).unwrap_or(0.0);
+    let benchmark_return = series.last().and_then(|p| p.benchmark_return);
+    let simulation = simulate(conn, &reporting, &tickers, &prices, from_ts, to_ts)?;
+
+    Ok(BenchmarkComparison {
+        benchmark: benchmark.ticker,
+        currency: reporting.currency,
+        tag_id: tag_id.map(str::to_string),
+        from_ts,
+        to_ts,
+        portfolio_return,
+        benchmark_return,
+        excess_return: benchmark_return.map(|b| portfolio_return - b),
+        series,
+        simulation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    const DAY: i64 = 86400;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE benchmarks (
+                 ticker TEXT PRIMARY KEY, name TEXT NOT NULL, currency TEXT NOT NULL,
+                 created_at INTEGER NOT NULL DEFAULT 0
+             );
+             CREATE TABLE benchmark_prices (
+                 ticker TEXT NOT NULL, recorded_at INTEGER NOT NULL, price TEXT NOT NULL,
+                 PRIMARY KEY (ticker, recorded_at)
+             );
+             CREATE TABLE stock_investments (id TEXT PRIMARY KEY, ticker TEXT, quantity TEXT);
+             CREATE TABLE stock_investment_tags (investment_id TEXT, tag_id TEXT);
+             CREATE TABLE stock_value_history (
+                 ticker TEXT, recorded_at INTEGER, value_czk TEXT, quantity TEXT
+             );
+             CREATE TABLE investment_transactions (
+                 ticker TEXT, type TEXT, quantity TEXT, price_per_unit TEXT,
+                 currency TEXT, transaction_date INTEGER
+             );
+             CREATE TABLE app_config (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE exchange_rate_history (
+                 currency TEXT, rate_date INTEGER, rate REAL, source TEXT
+             );
+             INSERT INTO stock_investments VALUES ('1', 'AAA', '2');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn benchmark(ticker: &str) -> InsertBenchmark {
+        InsertBenchmark {
+            ticker: ticker.to_string(),
+            name: "Index".to_string(),
+            currency: Some("CZK".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_add_benchmark_and_store_prices() {
+        let conn = setup_test_db();
+        let added = add_benchmark(&conn, &benchmark("^px")).unwrap();
+        assert_eq!(added.ticker, "^PX");
+        assert_eq!(added.price_count, 0);
+
+        // Intraday timestamps collapse to one day; invalid prices are skipped
+        let stored = store_prices(
+            &conn,
+            "^PX",
+            &[(DAY + 100, 10.0), (DAY + 200, 11.0), (2 * DAY, 0.0)],
+        )
+        .unwrap();
+        assert_eq!(stored, 2);
+        let added = get_benchmark(&conn, "^px").unwrap();
+        assert_eq!(added.price_count, 1);
+        assert_eq!(load_prices_czk(&conn, "^PX").unwrap()[&DAY], 11.0);
+
+        delete_benchmark(&conn, "^PX").unwrap();
+        assert!(get_benchmarks(&conn).unwrap().is_empty());
+        assert!(matches!(
+            delete_benchmark(&conn, "^PX"),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_compare_with_contribution_simulation() {
+        let conn = setup_test_db();
+        add_benchmark(&conn, &benchmark("IDX")).unwrap();
+        // Benchmark doubles over the range
+        store_prices(&conn, "IDX", &[(0, 100.0), (DAY, 150.0), (2 * DAY, 200.0)]).unwrap();
+
+        // Holding: 1 unit worth 100, second unit bought on day 1 at 100, flat price
+        conn.execute_batch(
+            "INSERT INTO stock_value_history VALUES
+                 ('AAA', 0, '100', '1'), ('AAA', 86400, '200', '2'), ('AAA', 172800, '200', '2');
+             INSERT INTO investment_transactions VALUES ('AAA', 'buy', '1', '100', 'CZK', 86400);",
+        )
+        .unwrap();
+
+        let result = compare(&conn, "IDX", None, 0, 2 * DAY).unwrap();
+        assert_eq!(result.series.len(), 3);
+        assert!(result.portfolio_return.abs() < 1e-9);
+        assert!((result.benchmark_return.unwrap() - 100.0).abs() < 1e-9);
+        assert!((result.excess_return.unwrap() + 100.0).abs() < 1e-9);
+
+        // 100 at start buys 1 unit (worth 200 at end), 100 on day 1 buys 2/3 unit
+        let sim = result.simulation.unwrap();
+        assert!((sim.start_value - 100.0).abs() < 1e-9);
+        assert!((sim.invested - 100.0).abs() < 1e-9);
+        assert!((sim.actual_value - 200.0).abs() < 1e-9);
+        assert!((sim.benchmark_value - (200.0 + 100.0 / 150.0 * 200.0)).abs() < 1e-9);
+        assert_eq!(sim.cash_flow_count, 1);
+
+        assert!(compare(&conn, "IDX", None, DAY, DAY).is_err());
+        assert!(compare(&conn, "NONE", None, 0, DAY).is_err());
+    }
+
+    #[test]
+    fn test_foreign_prices_and_contributions_use_day_rates() {
+        let conn = setup_test_db();
+        add_benchmark(
+            &conn,
+            &InsertBenchmark {
+                currency: Some("USD".to_string()),
+                ..benchmark("SPY")
+            },
+        )
+        .unwrap();
+        store_prices(&conn, "SPY", &[(0, 10.0), (DAY, 10.0), (2 * DAY, 10.0)]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO exchange_rate_history VALUES
+                 ('USD', 0, 20.0, 'ecb'), ('USD', 86400, 25.0, 'ecb'), ('USD', 172800, 25.0, 'ecb');
+             INSERT INTO stock_value_history VALUES ('AAA', 0, '200', '1'), ('AAA', 172800, '500', '2');
+             INSERT INTO investment_transactions VALUES ('AAA', 'buy', '1', '10', 'USD', 86400);",
+        )
+        .unwrap();
+
+        // A flat USD price gains 25 % in CZK as the dollar strengthens
+        let prices = load_prices_czk(&conn, "SPY").unwrap();
+        assert_eq!(prices[&0], 200.0);
+        assert_eq!(prices[&DAY], 250.0);
+
+        // The day-1 purchase of 10 USD costs 250 CZK at that day's rate
+        let sim = compare(&conn, "SPY", None, 0, 2 * DAY)
+            .unwrap()
+            .simulation
+            .unwrap();
+        assert!((sim.invested - 250.0).abs() < 1e-9);
+        assert!((sim.benchmark_value - 500.0).abs() < 1e-9);
+    }
+}
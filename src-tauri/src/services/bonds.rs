@@ -0,0 +1,620 @@
+//! Bond coupon schedules
+//!
+//! Coupon dates step by the coupon period from the first coupon date, or back
+//! from maturity when it is not known. Regular coupons pay face × rate /
+//! frequency; an odd first coupon and accrued interest follow the bond's
+//! day-count convention. The face value is repaid at maturity.
+//!
+//! Prices are clean prices in percent of face value. Holdings are valued at
+//! the market price (else the purchase price, else par) plus accrued interest.
+
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::{Bond, BondAnalytics, BondCashFlow, BondSchedule};
+use crate::services::money::{self, Decimal};
+use crate::services::price_providers::{PriceRequest, ProviderChain};
+use chrono::{Datelike, Months, NaiveDate};
+use rusqlite::{Connection, Row};
+
+/// Supported coupon frequencies
+pub const FREQUENCIES: [&str; 4] = ["annual", "semiannual", "quarterly", "monthly"];
+
+/// Supported day-count conventions
+pub const DAY_COUNTS: [&str; 4] = ["30/360", "act/360", "act/365", "act/act"];
+
+/// Columns read by `bond_from_row`
+pub const BOND_COLUMNS: &str =
+    "id, name, isin, coupon_value, quantity, currency, interest_rate, maturity_date,
+     coupon_frequency, day_count, issue_date, first_coupon_date, purchase_price, purchase_date,
+     market_price, market_price_source, market_price_updated_at, price_ticker, created_at, updated_at";
+
+/// Upper bound on generated coupons (a century of monthly coupons)
+const MAX_COUPONS: u32 = 1200;
+
+pub fn bond_from_row(row: &Row) -> rusqlite::Result<Bond> {
+    Ok(Bond {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        isin: row.get(2)?,
+        coupon_value: row.get(3)?,
+        quantity: row.get(4)?,
+        currency: row.get(5)?,
+        interest_rate: row.get(6)?,
+        maturity_date: row.get(7)?,
+        coupon_frequency: row.get(8)?,
+        day_count: row.get(9)?,
+        issue_date: row.get(10)?,
+        first_coupon_date: row.get(11)?,
+        purchase_price: row.get(12)?,
+        purchase_date: row.get(13)?,
+        market_price: row.get(14)?,
+        market_price_source: row.get(15)?,
+        market_price_updated_at: row.get(16)?,
+        price_ticker: row.get(17)?,
+        created_at: row.get(18)?,
+        updated_at: row.get(19)?,
+    })
+}
+
+pub fn get_bonds(conn: &Connection) -> Result<Vec<Bond>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM bonds ORDER BY name", BOND_COLUMNS))?;
+    let bonds = stmt
+        .query_map([], bond_from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(bonds)
+}
+
+pub fn get_bond(conn: &Connection, id: &str) -> Result<Bond> {
+    conn.query_row(
+        &format!("SELECT {} FROM bonds WHERE id = ?1", BOND_COLUMNS),
+        [id],
+        bond_from_row,
+    )
+    .map_err(|_| AppError::NotFound("Bond not found".into()))
+}
+
+/// Months between coupons
+pub fn period_months(frequency: &str) -> u32 {
+    match frequency {
+        "semiannual" => 6,
+        "quarterly" => 3,
+        "monthly" => 1,
+        _ => 12,
+    }
+}
+
+fn date_of(ts: i64) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp(ts, 0).map(|dt| dt.date_naive())
+}
+
+fn timestamp_of(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+fn add_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.checked_add_months(Months::new(months))
+}
+
+fn sub_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.checked_sub_months(Months::new(months))
+}
+
+/// Days between two dates under 30E/360
+fn days_30_360(start: NaiveDate, end: NaiveDate) -> i64 {
+    let d1 = start.day().min(30) as i64;
+    let d2 = end.day().min(30) as i64;
+    360 * (end.year() - start.year()) as i64 + 30 * (end.month() as i64 - start.month() as i64) + d2
+        - d1
+}
+
+/// Share of the annual rate earned from `start` to `end`. `period` is the
+/// (possibly notional) coupon period containing them, used by act/act.
+fn accrual_fraction(
+    start: NaiveDate,
+    end: NaiveDate,
+    period: (NaiveDate, NaiveDate),
+    months: u32,
+    day_count: &str,
+) -> Decimal {
+    let actual = Decimal::from((end - start).num_days());
+    match day_count {
+        "act/360" => actual / Decimal::from(360),
+        "act/365" => actual / Decimal::from(365),
+        "act/act" => {
+            let period_days = (period.1 - period.0).num_days();
+            if period_days <= 0 {
+                return Decimal::ZERO;
+            }
+            actual / Decimal::from(period_days) * Decimal::from(months) / Decimal::from(12)
+        }
+        _ => Decimal::from(days_30_360(start, end)) / Decimal::from(360),
+    }
+}
+
+/// Parsed terms of a bond holding that has a maturity date
+struct Terms {
+    face: Decimal,
+    rate: Decimal,
+    months: u32,
+    day_count: String,
+    issue: Option<NaiveDate>,
+    first_coupon: Option<NaiveDate>,
+    /// Earliest date a generated coupon period may end after
+    schedule_from: NaiveDate,
+    maturity: NaiveDate,
+}
+
+/// One coupon period ending on a payment date
+struct Period {
+    start: NaiveDate,
+    end: NaiveDate,
+    coupon: Decimal,
+}
+
+impl Terms {
+    /// `fallback_start` bounds the schedule when neither the issue date nor
+    /// the first coupon date is known
+    fn of(bond: &Bond, fallback_start: NaiveDate) -> Option<Self> {
+        let maturity = date_of(bond.maturity_date?)?;
+        let quantity = money::parse(&bond.quantity).unwrap_or(Decimal::ONE);
+        let issue = bond.issue_date.and_then(date_of);
+        let first_coupon = bond.first_coupon_date.and_then(date_of);
+        Some(Self {
+            face: money::parse_or_zero(&bond.coupon_value) * quantity,
+            rate: money::parse_or_zero(&bond.interest_rate) / Decimal::from(100),
+            months: period_months(&bond.coupon_frequency),
+            day_count: bond.day_count.clone(),
+            issue,
+            first_coupon,
+            schedule_from: issue.unwrap_or(fallback_start),
+            maturity,
+        })
+    }
+
+    /// Coupon payment dates in ascending order, ending at maturity
+    fn payment_dates(&self) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        if let Some(first) = self.first_coupon {
+            for k in 0..MAX_COUPONS {
+                match add_months(first, k * self.months) {
+                    Some(date) if date < self.maturity => dates.push(date),
+                    _ => break,
+                }
+            }
+            dates.push(self.maturity);
+        } else {
+            for k in 0..MAX_COUPONS {
+                match sub_months(self.maturity, k * self.months) {
+                    Some(date) if date > self.schedule_from => dates.push(date),
+                    _ => break,
+                }
+            }
+            dates.reverse();
+        }
+        dates
+    }
+
+    fn periods(&self) -> Vec<Period> {
+        let regular = self.face * self.rate * Decimal::from(self.months) / Decimal::from(12);
+        let mut periods = Vec::new();
+        let mut previous: Option<NaiveDate> = None;
+        for end in self.payment_dates() {
+            let notional_start = sub_months(end, self.months).unwrap_or(end);
+            let start = previous.or(self.issue).unwrap_or(notional_start);
+            // Month-end dates clamp (31 Jan + 1 month = 28 Feb), so check both ways
+            let regular_period =
+                start == notional_start || add_months(start, self.months) == Some(end);
+            let coupon = if regular_period {
+                regular
+            } else {
+                // Odd (short or long) period
+                self.face
+                    * self.rate
+                    * accrual_fraction(
+                        start,
+                        end,
+                        (notional_start, end),
+                        self.months,
+                        &self.day_count,
+                    )
+            };
+            periods.push(Period { start, end, coupon });
+            previous = Some(end);
+        }
+        periods
+    }
+
+    fn accrued_on(&self, periods: &[Period], day: NaiveDate) -> Decimal {
+        periods
+            .iter()
+            .find(|p| p.start <= day && day < p.end)
+            .map(|p| {
+                self.face
+                    * self.rate
+                    * accrual_fraction(p.start, day, (p.start, p.end), self.months, &self.day_count)
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn cash_flow(bond: &Bond, date: NaiveDate, kind: &str, amount: Decimal) -> BondCashFlow {
+    let amount = money::round(amount, &bond.currency);
+    BondCashFlow {
+        bond_id: bond.id.clone(),
+        bond_name: bond.name.clone(),
+        date: timestamp_of(date),
+        kind: kind.to_string(),
+        amount: money::format(amount, &bond.currency),
+        currency: bond.currency.clone(),
+        amount_czk: money::to_f64(money::to_czk(amount, &bond.currency)),
+    }
+}
+
+/// Full coupon and principal schedule of a bond as of `now`.
+/// Bonds without a maturity date have no schedule.
+pub fn schedule(bond: &Bond, now: i64) -> BondSchedule {
+    let today = date_of(now).unwrap_or_default();
+    let created = date_of(bond.created_at).unwrap_or(today).min(today);
+    let mut result = BondSchedule {
+        bond_id: bond.id.clone(),
+        accrued_interest: money::format(Decimal::ZERO, &bond.currency),
+        next_coupon_date: None,
+        cash_flows: Vec::new(),
+    };
+    let Some(terms) = Terms::of(bond, created) else {
+        return result;
+    };
+
+    let periods = terms.periods();
+    result.accrued_interest = money::format(terms.accrued_on(&periods, today), &bond.currency);
+    result.next_coupon_date = periods
+        .iter()
+        .find(|p| p.end > today && !p.coupon.is_zero())
+        .map(|p| timestamp_of(p.end));
+    result.cash_flows = periods
+        .iter()
+        .filter(|p| !p.coupon.is_zero())
+        .map(|p| cash_flow(bond, p.end, "coupon", p.coupon))
+        .collect();
+    result
+        .cash_flows
+        .push(cash_flow(bond, terms.maturity, "principal", terms.face));
+    result
+}
+
+/// Coupon and principal payments of all bonds in `[from, to)`, by date
+pub fn cash_flow_calendar(conn: &Connection, from: i64, to: i64) -> Result<Vec<BondCashFlow>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut flows: Vec<BondCashFlow> = get_bonds(conn)?
+        .iter()
+        .flat_map(|bond| schedule(bond, now).cash_flows)
+        .filter(|flow| flow.date >= from && flow.date < to)
+        .collect();
+    flows.sort_by(|a, b| a.date.cmp(&b.date).then(a.bond_name.cmp(&b.bond_name)));
+    Ok(flows)
+}
+
+/// Face value of the holding (coupon value × quantity) in the bond currency
+pub fn face_value(bond: &Bond) -> Decimal {
+    money::parse_or_zero(&bond.coupon_value) * money::parse(&bond.quantity).unwrap_or(Decimal::ONE)
+}
+
+/// Clean price in percent of face value: market price, else purchase price, else par
+pub fn clean_price(bond: &Bond) -> Decimal {
+    [&bond.market_price, &bond.purchase_price]
+        .into_iter()
+        .flatten()
+        .filter_map(|price| money::parse(price))
+        .find(|price| *price > Decimal::ZERO)
+        .unwrap_or(Decimal::ONE_HUNDRED)
+}
+
+/// Interest accrued on the holding on a day, in the bond currency (unrounded)
+pub fn accrued_interest(bond: &Bond, day_ts: i64) -> Decimal {
+    let Some(day) = date_of(day_ts) else {
+        return Decimal::ZERO;
+    };
+    let created = date_of(bond.created_at).unwrap_or(day).min(day);
+    Terms::of(bond, created)
+        .map(|terms| terms.accrued_on(&terms.periods(), day))
+        .unwrap_or_default()
+}
+
+/// Dirty value of the holding on a day in the bond currency: face value at
+/// the clean price plus accrued interest
+pub fn market_value(bond: &Bond, day_ts: i64) -> Decimal {
+    face_value(bond) * clean_price(bond) / Decimal::ONE_HUNDRED + accrued_interest(bond, day_ts)
+}
+
+/// Dirty value of the holding on a day in CZK at the current rate
+pub fn market_value_czk(bond: &Bond, day_ts: i64) -> f64 {
+    money::to_f64(money::to_czk(market_value(bond, day_ts), &bond.currency))
+}
+
+/// Present value of `(years, amount)` flows at an effective annual yield
+fn present_value(flows: &[(f64, f64)], y: f64) -> f64 {
+    flows.iter().map(|(t, a)| a / (1.0 + y).powf(*t)).sum()
+}
+
+/// Effective annual yield at which the flows are worth `price` (bisection;
+/// the present value falls as the yield rises)
+fn solve_yield(price: f64, flows: &[(f64, f64)]) -> Option<f64> {
+    if price <= 0.0 || flows.is_empty() {
+        return None;
+    }
+    let (mut low, mut high) = (-0.99, 10.0);
+    if present_value(flows, low) < price || present_value(flows, high) > price {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if present_value(flows, mid) > price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+/// Price, yield to maturity and duration of a bond holding as of `now`.
+/// Yield and duration need a maturity date.
+pub fn analytics(bond: &Bond, now: i64) -> BondAnalytics {
+    let face = face_value(bond);
+    let clean = clean_price(bond);
+    let accrued = accrued_interest(bond, now);
+    let value = face * clean / Decimal::ONE_HUNDRED + accrued;
+    let dirty = if face.is_zero() {
+        clean
+    } else {
+        value / face * Decimal::ONE_HUNDRED
+    };
+    let coupon_rate = money::parse_or_zero(&bond.interest_rate);
+
+    let flows: Vec<(f64, f64)> = schedule(bond, now)
+        .cash_flows
+        .iter()
+        .filter(|f| f.date > now)
+        .map(|f| {
+            let years = (f.date - now) as f64 / (365.0 * 86400.0);
+            (years, f.amount.parse::<f64>().unwrap_or(0.0))
+        })
+        .collect();
+    let price = money::to_f64(value);
+    let ytm = solve_yield(price, &flows);
+    let macaulay = ytm.map(|y| {
+        flows
+            .iter()
+            .map(|(t, a)| t * a / (1.0 + y).powf(*t))
+            .sum::<f64>()
+            / price
+    });
+
+    BondAnalytics {
+        bond_id: bond.id.clone(),
+        clean_price: money::format_exact(clean),
+        dirty_price: money::format_exact(dirty.round_dp(4)),
+        accrued_interest: money::format(accrued, &bond.currency),
+        market_value: money::format(value, &bond.currency),
+        market_value_czk: money::to_f64(money::to_czk(value, &bond.currency)),
+        purchase_cost: bond
+            .purchase_price
+            .as_deref()
+            .and_then(money::parse)
+            .map(|p| money::format(face * p / Decimal::ONE_HUNDRED, &bond.currency)),
+        current_yield: money::to_f64(coupon_rate * Decimal::ONE_HUNDRED / clean),
+        yield_to_maturity: ytm.map(|y| y * 100.0),
+        macaulay_duration: macaulay,
+        modified_duration: ytm.zip(macaulay).map(|(y, d)| d / (1.0 + y)),
+    }
+}
+
+/// Fetch market prices for bonds with a price ticker through the provider
+/// chain. Quotes are read as clean prices in percent of face value.
+/// Returns the number of updated bonds.
+pub async fn refresh_market_prices(db: &Database) -> Result<i32> {
+    let tickers: Vec<(String, String)> = db.with_conn(|conn| {
+        Ok(get_bonds(conn)?
+            .into_iter()
+            .filter_map(|b| {
+                let ticker = b.price_ticker?.trim().to_uppercase();
+                (!ticker.is_empty()).then_some((b.id, ticker))
+            })
+            .collect())
+    })?;
+    if tickers.is_empty() {
+        return Ok(0);
+    }
+
+    let requests: Vec<PriceRequest> = tickers
+        .iter()
+        .map(|(_, ticker)| PriceRequest::stock(ticker))
+        .collect();
+    let chain = db.with_conn(ProviderChain::load)?;
+    let fetched = chain.latest_quotes(&requests).await;
+    for (ticker, reason) in &fetched.failed {
+        log::warn!("[BONDS] Price for {} failed: {}", ticker, reason);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    db.with_conn(|conn| {
+        let mut updated = 0;
+        for (id, ticker) in &tickers {
+            let Some((provider, quote)) = fetched.quotes.get(ticker) else {
+                continue;
+            };
+            updated += conn.execute(
+                "UPDATE bonds SET market_price = ?1, market_price_source = ?2,
+                 market_price_updated_at = ?3 WHERE id = ?4",
+                rusqlite::params![
+                    money::format_exact(money::from_f64(quote.price)),
+                    provider.as_str(),
+                    now,
+                    id
+                ],
+            )? as i32;
+        }
+        log::info!(
+            "[BONDS] Updated {} of {} bond prices",
+            updated,
+            tickers.len()
+        );
+        Ok(updated)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(y: i32, m: u32, d: u32) -> i64 {
+        timestamp_of(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+    }
+
+    fn bond(frequency: &str, day_count: &str) -> Bond {
+        Bond {
+            id: "b1".into(),
+            name: "CZ Gov 2027".into(),
+            isin: None,
+            coupon_value: "10000".into(),
+            quantity: "3".into(),
+            currency: "CZK".into(),
+            interest_rate: "4".into(),
+            maturity_date: Some(ts(2027, 6, 15)),
+            coupon_frequency: frequency.into(),
+            day_count: day_count.into(),
+            issue_date: Some(ts(2024, 6, 15)),
+            first_coupon_date: None,
+            purchase_price: None,
+            purchase_date: None,
+            market_price: None,
+            market_price_source: None,
+            market_price_updated_at: None,
+            price_ticker: None,
+            created_at: ts(2024, 7, 1),
+            updated_at: ts(2024, 7, 1),
+        }
+    }
+
+    fn amounts(schedule: &BondSchedule) -> Vec<(&str, &str)> {
+        schedule
+            .cash_flows
+            .iter()
+            .map(|f| (f.kind.as_str(), f.amount.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_semiannual_schedule_and_accrued_interest() {
+        let result = schedule(&bond("semiannual", "30/360"), ts(2025, 3, 15));
+
+        assert_eq!(result.cash_flows.len(), 7);
+        assert_eq!(result.cash_flows[0].date, ts(2024, 12, 15));
+        assert!(result.cash_flows[..6]
+            .iter()
+            .all(|f| f.kind == "coupon" && f.amount == "600.00"));
+        assert_eq!(result.cash_flows[6].kind, "principal");
+        assert_eq!(result.cash_flows[6].amount, "30000.00");
+        assert_eq!(result.cash_flows[6].date, ts(2027, 6, 15));
+        // 90 of 180 days since the December coupon
+        assert_eq!(result.accrued_interest, "300.00");
+        assert_eq!(result.next_coupon_date, Some(ts(2025, 6, 15)));
+    }
+
+    #[test]
+    fn test_short_first_coupon_uses_day_count() {
+        let mut b = bond("annual", "act/365");
+        b.issue_date = Some(ts(2024, 12, 16));
+        b.first_coupon_date = Some(ts(2025, 6, 15));
+        let result = schedule(&b, ts(2025, 1, 1));
+
+        // 181 days of 4 % on 30 000
+        assert_eq!(
+            amounts(&result),
+            vec![
+                ("coupon", "595.07"),
+                ("coupon", "1200.00"),
+                ("coupon", "1200.00"),
+                ("principal", "30000.00"),
+            ]
+        );
+        // 16 days since issue
+        assert_eq!(result.accrued_interest, "52.60");
+    }
+
+    #[test]
+    fn test_act_act_accrual_and_unknown_issue_date() {
+        let mut b = bond("annual", "act/act");
+        b.issue_date = None;
+        let result = schedule(&b, ts(2025, 12, 15));
+
+        // Schedule starts at the coupon period covering the creation date
+        assert_eq!(result.cash_flows[0].date, ts(2025, 6, 15));
+        assert_eq!(result.cash_flows.len(), 4);
+        // 183 of 365 days
+        assert_eq!(result.accrued_interest, "601.64");
+    }
+
+    #[test]
+    fn test_zero_coupon_and_perpetual_bonds() {
+        let mut zero = bond("annual", "30/360");
+        zero.interest_rate = "0".into();
+        assert_eq!(
+            amounts(&schedule(&zero, ts(2025, 1, 1))),
+            vec![("principal", "30000.00")]
+        );
+
+        let mut perpetual = bond("annual", "30/360");
+        perpetual.maturity_date = None;
+        let result = schedule(&perpetual, ts(2025, 1, 1));
+        assert!(result.cash_flows.is_empty());
+        assert_eq!(result.accrued_interest, "0.00");
+    }
+
+    #[test]
+    fn test_market_value_uses_clean_price_plus_accrued() {
+        let mut b = bond("semiannual", "30/360");
+        assert_eq!(clean_price(&b), Decimal::ONE_HUNDRED);
+        b.purchase_price = Some("98.5".into());
+        assert_eq!(clean_price(&b), Decimal::from_str_exact("98.5").unwrap());
+        b.market_price = Some("102".into());
+
+        // 30 000 at 102 % plus 300 accrued
+        let value = market_value(&b, ts(2025, 3, 15));
+        assert_eq!(money::format(value, "CZK"), "30900.00");
+    }
+
+    #[test]
+    fn test_yield_to_maturity_and_duration() {
+        // Annual 4 % at par on a coupon date yields 4 %
+        let mut b = bond("annual", "act/act");
+        b.maturity_date = Some(ts(2028, 6, 15));
+        let at_par = analytics(&b, ts(2025, 6, 15));
+        let ytm = at_par.yield_to_maturity.unwrap();
+        assert!((ytm - 4.0).abs() < 0.01, "ytm {}", ytm);
+        assert!((at_par.current_yield - 4.0).abs() < 1e-9);
+
+        // Below par the yield is higher
+        b.market_price = Some("95".into());
+        let discount = analytics(&b, ts(2025, 6, 15));
+        assert!(discount.yield_to_maturity.unwrap() > 5.5);
+        assert_eq!(discount.purchase_cost, None);
+
+        // Zero coupon: duration equals time to maturity
+        let mut zero = bond("annual", "act/act");
+        zero.interest_rate = "0".into();
+        zero.market_price = Some("95".into());
+        zero.maturity_date = Some(ts(2026, 6, 15));
+        let result = analytics(&zero, ts(2025, 6, 15));
+        let ytm = result.yield_to_maturity.unwrap();
+        assert!((ytm - 5.2632).abs() < 0.01, "ytm {}", ytm);
+        assert!((result.macaulay_duration.unwrap() - 1.0).abs() < 1e-6);
+
+        // No maturity, no yield
+        b.maturity_date = None;
+        assert_eq!(analytics(&b, ts(2025, 6, 15)).yield_to_maturity, None);
+    }
+}
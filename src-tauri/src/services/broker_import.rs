@@ -0,0 +1,1490 @@
+//! Broker statement import service
+//!
+//! Parses account statements exported by brokers into a common list of
+//! records (buys, sells, dividends, fees and FX conversions) and stores them:
+//! - Degiro: Transactions.csv and Account.csv
+//! - Trading212: history CSV export
+//! - Interactive Brokers: Flex Query XML (Trades + Cash Transactions)
+//! - XTB: Cash Operations sheet (XLSX or CSV)
+//!
+//! Every stored record carries an `external_id` derived from the broker's
+//! order/transaction ID so re-importing the same statement skips duplicates.
+
+use crate::error::{AppError, Result};
+use crate::services::csv_import::clean_and_parse_decimal;
+use crate::services::investments::import_single_transaction;
+use crate::services::money::{self, Decimal};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Supported brokers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Broker {
+    Degiro,
+    Trading212,
+    Ibkr,
+    Xtb,
+}
+
+impl Broker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Broker::Degiro => "degiro",
+            Broker::Trading212 => "trading212",
+            Broker::Ibkr => "ibkr",
+            Broker::Xtb => "xtb",
+        }
+    }
+}
+
+/// Kind of a parsed statement record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrokerRecordKind {
+    Buy,
+    Sell,
+    Dividend,
+    /// Tax withheld on a dividend; merged into the matching dividend after parsing
+    WithholdingTax,
+    Fee,
+    FxConversion,
+}
+
+impl BrokerRecordKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BrokerRecordKind::Buy => "buy",
+            BrokerRecordKind::Sell => "sell",
+            BrokerRecordKind::Dividend => "dividend",
+            BrokerRecordKind::WithholdingTax => "tax",
+            BrokerRecordKind::Fee => "fee",
+            BrokerRecordKind::FxConversion => "fx",
+        }
+    }
+}
+
+/// One normalized record from a broker statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokerRecord {
+    pub kind: BrokerRecordKind,
+    pub date: i64,
+    pub ticker: Option<String>,
+    pub isin: Option<String>,
+    pub name: Option<String>,
+    /// Number of shares (buy/sell)
+    pub quantity: Decimal,
+    /// Price per share (buy/sell)
+    pub price: Decimal,
+    /// Gross dividend, fee amount or FX source amount
+    pub amount: Decimal,
+    pub currency: String,
+    /// Tax withheld at source (dividends only)
+    pub withholding_tax: Decimal,
+    /// FX target amount and currency
+    pub counter_amount: Decimal,
+    pub counter_currency: Option<String>,
+    /// Broker order/transaction ID (prefixed with broker and kind before storing)
+    pub external_id: Option<String>,
+}
+
+impl BrokerRecord {
+    fn new(kind: BrokerRecordKind, date: i64, currency: &str) -> Self {
+        Self {
+            kind,
+            date,
+            ticker: None,
+            isin: None,
+            name: None,
+            quantity: Decimal::ZERO,
+            price: Decimal::ZERO,
+            amount: Decimal::ZERO,
+            currency: currency.to_uppercase(),
+            withholding_tax: Decimal::ZERO,
+            counter_amount: Decimal::ZERO,
+            counter_currency: None,
+            external_id: None,
+        }
+    }
+}
+
+/// Parsed statement with non-fatal parsing warnings
+#[derive(Debug, Clone, Default)]
+pub struct ParsedStatement {
+    pub records: Vec<BrokerRecord>,
+    pub warnings: Vec<String>,
+}
+
+/// Result of a broker statement import
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerImportResult {
+    pub trades_imported: i32,
+    pub dividends_imported: i32,
+    pub fees_imported: i32,
+    pub fx_conversions_imported: i32,
+    pub duplicates_skipped: i32,
+    pub imported: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+// ============================================================================
+// Parsing helpers
+// ============================================================================
+
+/// Split CSV content into trimmed header and data rows (delimiter auto-detected)
+pub(crate) fn read_csv_table(content: &str) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let content = content.trim_start_matches('\u{feff}');
+    // Look past preamble lines (e.g., report titles) when detecting the delimiter
+    let sample: Vec<&str> = content.lines().take(10).collect();
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| sample.iter().map(|l| l.matches(*d).count()).max())
+        .unwrap_or(',');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let mut rows: Vec<Vec<String>> = reader
+        .records()
+        .filter_map(|r| r.ok())
+        .map(|r| r.iter().map(|s| s.trim().to_string()).collect())
+        .collect();
+
+    if rows.is_empty() {
+        return Err(AppError::Validation("File is empty".into()));
+    }
+    let headers = rows.remove(0);
+    Ok((headers, rows))
+}
+
+/// Find a column by any of the given names (case-insensitive)
+pub(crate) fn find_col(headers: &[String], names: &[&str]) -> Option<usize> {
+    headers
+        .iter()
+        .position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)))
+}
+
+/// Find a column whose header starts with any of the given prefixes (case-insensitive)
+pub(crate) fn find_col_prefix(headers: &[String], prefixes: &[&str]) -> Option<usize> {
+    headers.iter().position(|h| {
+        let lower = h.to_lowercase();
+        prefixes.iter().any(|p| lower.starts_with(p))
+    })
+}
+
+pub(crate) fn cell(row: &[String], idx: Option<usize>) -> &str {
+    idx.and_then(|i| row.get(i))
+        .map(|s| s.as_str())
+        .unwrap_or("")
+}
+
+pub(crate) fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+pub(crate) fn parse_date(value: &str) -> Option<i64> {
+    crate::services::date_parser::parse_date_to_timestamp(value, None).ok()
+}
+
+/// Parse IBKR dates like "20240115", "20240115;093000" or "2024-01-15"
+fn parse_compact_date(value: &str) -> Option<i64> {
+    let day: String = value
+        .split([';', ' ', ','])
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+    NaiveDate::parse_from_str(&day, "%Y%m%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc().timestamp())
+}
+
+/// Check that a string looks like an ISIN (2 letters + 9 alphanumerics + 1 digit)
+pub fn is_valid_isin(value: &str) -> bool {
+    value.len() == 12
+        && value[..2].chars().all(|c| c.is_ascii_uppercase())
+        && value[2..].chars().all(|c| c.is_ascii_alphanumeric())
+        && value[11..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Attach withholding tax rows to the dividend of the same instrument and day
+fn merge_withholding_tax(statement: &mut ParsedStatement) {
+    let (taxes, mut others): (Vec<BrokerRecord>, Vec<BrokerRecord>) = statement
+        .records
+        .drain(..)
+        .partition(|r| r.kind == BrokerRecordKind::WithholdingTax);
+
+    for tax in taxes {
+        let same_instrument = |d: &BrokerRecord| match (&tax.isin, &d.isin) {
+            (Some(a), Some(b)) => a == b,
+            _ => tax.ticker.is_some() && tax.ticker == d.ticker,
+        };
+        let matching = others.iter_mut().find(|d| {
+            d.kind == BrokerRecordKind::Dividend && d.date == tax.date && same_instrument(d)
+        });
+        match matching {
+            Some(dividend) => dividend.withholding_tax += tax.amount,
+            None => statement.warnings.push(format!(
+                "Withholding tax {} {} without a matching dividend was skipped",
+                tax.amount,
+                tax.ticker.as_deref().or(tax.isin.as_deref()).unwrap_or("?")
+            )),
+        }
+    }
+
+    statement.records = others;
+}
+
+// ============================================================================
+// Degiro
+// ============================================================================
+
+/// Parse a Degiro export - Transactions.csv (trades) or Account.csv (cash movements)
+pub fn parse_degiro_csv(content: &str) -> Result<ParsedStatement> {
+    let (headers, rows) = read_csv_table(content)?;
+    let mut statement = if find_col(&headers, &["Description", "Popis", "Omschrijving"]).is_some() {
+        parse_degiro_account(&headers, &rows)
+    } else {
+        parse_degiro_transactions(&headers, &rows)?
+    };
+    merge_withholding_tax(&mut statement);
+    Ok(statement)
+}
+
+fn parse_degiro_transactions(headers: &[String], rows: &[Vec<String>]) -> Result<ParsedStatement> {
+    let date_col = find_col(headers, &["Date", "Datum"]);
+    let product_col = find_col(headers, &["Product", "Produkt"]);
+    let isin_col = find_col(headers, &["ISIN"]);
+    let qty_col = find_col(headers, &["Quantity", "Počet", "Aantal", "Anzahl"]);
+    let price_col = find_col(headers, &["Price", "Cena", "Koers", "Kurs"]);
+    let order_col = find_col(headers, &["Order ID", "ID objednávky", "Order-ID"]);
+    let fee_col = find_col_prefix(
+        headers,
+        &[
+            "transaction and/or third",
+            "transaction costs",
+            "transakční",
+            "transactiekosten",
+        ],
+    );
+
+    if date_col.is_none() || qty_col.is_none() || price_col.is_none() {
+        return Err(AppError::Validation(
+            "Unrecognized Degiro transactions file".into(),
+        ));
+    }
+    // Currency columns have an empty header right after the value column
+    let price_currency_col = price_col.map(|i| i + 1);
+    let fee_currency = |row: &[String]| -> String {
+        let fee_idx = match fee_col {
+            Some(i) => i,
+            None => return "EUR".to_string(),
+        };
+        match headers.get(fee_idx + 1) {
+            Some(h) if h.is_empty() => {
+                non_empty(cell(row, Some(fee_idx + 1))).unwrap_or_else(|| "EUR".to_string())
+            }
+            _ => headers[fee_idx]
+                .rsplit(' ')
+                .next()
+                .filter(|c| c.len() == 3 && c.chars().all(|ch| ch.is_ascii_uppercase()))
+                .unwrap_or("EUR")
+                .to_string(),
+        }
+    };
+
+    let mut statement = ParsedStatement::default();
+    for (index, row) in rows.iter().enumerate() {
+        let Some(date) = parse_date(cell(row, date_col)) else {
+            statement.warnings.push(format!(
+                "Row {}: invalid date '{}'",
+                index + 2,
+                cell(row, date_col)
+            ));
+            continue;
+        };
+        let qty = clean_and_parse_decimal(cell(row, qty_col));
+        if qty.is_zero() {
+            continue;
+        }
+        let currency = non_empty(cell(row, price_currency_col)).unwrap_or_else(|| "EUR".into());
+        let order_id = non_empty(cell(row, order_col));
+
+        let kind = if qty > Decimal::ZERO {
+            BrokerRecordKind::Buy
+        } else {
+            BrokerRecordKind::Sell
+        };
+        let mut record = BrokerRecord::new(kind, date, &currency);
+        record.isin = non_empty(cell(row, isin_col));
+        record.name = non_empty(cell(row, product_col));
+        record.quantity = qty.abs();
+        record.price = clean_and_parse_decimal(cell(row, price_col));
+        record.external_id = order_id.clone();
+
+        let fee = clean_and_parse_decimal(cell(row, fee_col)).abs();
+        statement.records.push(record.clone());
+
+        if fee > Decimal::ZERO {
+            let mut fee_record = BrokerRecord::new(BrokerRecordKind::Fee, date, &fee_currency(row));
+            fee_record.isin = record.isin;
+            fee_record.name = record.name;
+            fee_record.amount = fee;
+            fee_record.external_id = order_id;
+            statement.records.push(fee_record);
+        }
+    }
+    Ok(statement)
+}
+
+fn parse_degiro_account(headers: &[String], rows: &[Vec<String>]) -> ParsedStatement {
+    let date_col = find_col(headers, &["Date", "Datum"]);
+    let product_col = find_col(headers, &["Product", "Produkt"]);
+    let isin_col = find_col(headers, &["ISIN"]);
+    let desc_col = find_col(headers, &["Description", "Popis", "Omschrijving"]);
+    let change_col = find_col(headers, &["Change", "Změna", "Mutatie", "Änderung"]);
+    let order_col = find_col(headers, &["Order Id", "Order ID", "ID objednávky"]);
+    // "Change" holds the currency, the amount is in the unnamed column after it
+    let amount_col = change_col.map(|i| i + 1);
+
+    let mut statement = ParsedStatement::default();
+    // Pending FX leg waiting for its counterpart: (date, amount, currency, order id)
+    let mut pending_fx: Option<(i64, Decimal, String, Option<String>)> = None;
+
+    for row in rows {
+        let Some(date) = parse_date(cell(row, date_col)) else {
+            continue;
+        };
+        let description = cell(row, desc_col).to_lowercase();
+        let currency = cell(row, change_col).to_uppercase();
+        let amount = clean_and_parse_decimal(cell(row, amount_col));
+        let order_id = non_empty(cell(row, order_col));
+
+        let is_fx = description.starts_with("fx ")
+            || description.contains("valuta creditering")
+            || description.contains("valuta debitering")
+            || description.contains("převod měny");
+        if is_fx {
+            match pending_fx.take() {
+                Some((leg_date, leg_amount, leg_currency, leg_order))
+                    if leg_date == date && leg_amount.signum() != amount.signum() =>
+                {
+                    let (from, to) = if leg_amount < Decimal::ZERO {
+                        ((leg_amount, leg_currency), (amount, currency))
+                    } else {
+                        ((amount, currency), (leg_amount, leg_currency))
+                    };
+                    let mut record =
+                        BrokerRecord::new(BrokerRecordKind::FxConversion, date, &from.1);
+                    record.amount = from.0.abs();
+                    record.counter_amount = to.0.abs();
+                    record.counter_currency = Some(to.1);
+                    record.external_id = leg_order.or(order_id);
+                    statement.records.push(record);
+                }
+                _ => pending_fx = Some((date, amount, currency, order_id)),
+            }
+            continue;
+        }
+
+        let kind = if description.contains("dividend tax")
+            || description.contains("dividendová daň")
+            || description.contains("dividendbelasting")
+            || description.contains("dividendensteuer")
+        {
+            BrokerRecordKind::WithholdingTax
+        } else if description.contains("dividend") {
+            BrokerRecordKind::Dividend
+        } else if amount < Decimal::ZERO
+            && (description.contains("fee")
+                || description.contains("poplat")
+                || description.contains("costs")
+                || description.contains("kosten"))
+        {
+            BrokerRecordKind::Fee
+        } else {
+            continue;
+        };
+
+        let mut record = BrokerRecord::new(kind, date, &currency);
+        record.isin = non_empty(cell(row, isin_col));
+        record.name = non_empty(cell(row, product_col));
+        record.amount = amount.abs();
+        record.external_id = order_id;
+        statement.records.push(record);
+    }
+
+    statement
+}
+
+// ============================================================================
+// Trading212
+// ============================================================================
+
+/// Fee columns in the Trading212 export, each followed by "Currency (<name>)"
+const T212_FEE_COLUMNS: &[&str] = &[
+    "Currency conversion fee",
+    "Stamp duty reserve tax",
+    "Transaction fee",
+    "Finra fee",
+    "French transaction tax",
+];
+
+/// Parse a Trading212 history CSV export
+pub fn parse_trading212_csv(content: &str) -> Result<ParsedStatement> {
+    let (headers, rows) = read_csv_table(content)?;
+    let action_col = find_col(&headers, &["Action"]);
+    let time_col = find_col(&headers, &["Time"]);
+    let isin_col = find_col(&headers, &["ISIN"]);
+    let ticker_col = find_col(&headers, &["Ticker"]);
+    let name_col = find_col(&headers, &["Name"]);
+    let qty_col = find_col(&headers, &["No. of shares"]);
+    let price_col = find_col(&headers, &["Price / share"]);
+    let price_currency_col = find_col(&headers, &["Currency (Price / share)"]);
+    let tax_col = find_col(&headers, &["Withholding tax"]);
+    let id_col = find_col(&headers, &["ID"]);
+    let fx_from_col = find_col(&headers, &["Currency conversion from amount"]);
+    let fx_from_currency_col = find_col(&headers, &["Currency (Currency conversion from amount)"]);
+    let fx_to_col = find_col(&headers, &["Currency conversion to amount"]);
+    let fx_to_currency_col = find_col(&headers, &["Currency (Currency conversion to amount)"]);
+
+    if action_col.is_none() || time_col.is_none() {
+        return Err(AppError::Validation(
+            "Unrecognized Trading212 export".into(),
+        ));
+    }
+
+    let fee_cols: Vec<(usize, Option<usize>)> = T212_FEE_COLUMNS
+        .iter()
+        .filter_map(|name| {
+            find_col(&headers, &[name])
+                .map(|i| (i, find_col(&headers, &[&format!("Currency ({})", name)])))
+        })
+        .collect();
+
+    let mut statement = ParsedStatement::default();
+    for (index, row) in rows.iter().enumerate() {
+        let action = cell(row, action_col).to_lowercase();
+        let Some(date) = parse_date(cell(row, time_col)) else {
+            statement.warnings.push(format!(
+                "Row {}: invalid date '{}'",
+                index + 2,
+                cell(row, time_col)
+            ));
+            continue;
+        };
+        let currency = non_empty(cell(row, price_currency_col)).unwrap_or_else(|| "USD".into());
+        let external_id = non_empty(cell(row, id_col));
+
+        let kind = if action.ends_with(" buy") {
+            BrokerRecordKind::Buy
+        } else if action.ends_with(" sell") {
+            BrokerRecordKind::Sell
+        } else if action.starts_with("dividend") {
+            BrokerRecordKind::Dividend
+        } else if action == "currency conversion" {
+            let mut record = BrokerRecord::new(
+                BrokerRecordKind::FxConversion,
+                date,
+                cell(row, fx_from_currency_col),
+            );
+            record.amount = clean_and_parse_decimal(cell(row, fx_from_col)).abs();
+            record.counter_amount = clean_and_parse_decimal(cell(row, fx_to_col)).abs();
+            record.counter_currency = non_empty(cell(row, fx_to_currency_col));
+            record.external_id = external_id;
+            statement.records.push(record);
+            continue;
+        } else {
+            continue;
+        };
+
+        let mut record = BrokerRecord::new(kind, date, &currency);
+        record.ticker = non_empty(cell(row, ticker_col));
+        record.isin = non_empty(cell(row, isin_col));
+        record.name = non_empty(cell(row, name_col));
+        record.external_id = external_id.clone();
+        let quantity = clean_and_parse_decimal(cell(row, qty_col)).abs();
+        let price = clean_and_parse_decimal(cell(row, price_col));
+
+        if kind == BrokerRecordKind::Dividend {
+            // "Price / share" is the gross dividend per share
+            record.amount = quantity * price;
+            record.withholding_tax = clean_and_parse_decimal(cell(row, tax_col)).abs();
+        } else {
+            record.quantity = quantity;
+            record.price = price;
+        }
+        statement.records.push(record.clone());
+
+        for (fee_idx, fee_currency_idx) in &fee_cols {
+            let fee = clean_and_parse_decimal(cell(row, Some(*fee_idx))).abs();
+            if fee > Decimal::ZERO {
+                let fee_currency =
+                    non_empty(cell(row, *fee_currency_idx)).unwrap_or_else(|| currency.clone());
+                let mut fee_record = BrokerRecord::new(BrokerRecordKind::Fee, date, &fee_currency);
+                fee_record.ticker = record.ticker.clone();
+                fee_record.isin = record.isin.clone();
+                fee_record.amount = fee;
+                fee_record.external_id = external_id
+                    .as_ref()
+                    .map(|id| format!("{}:{}", id, headers[*fee_idx].to_lowercase()));
+                statement.records.push(fee_record);
+            }
+        }
+    }
+    Ok(statement)
+}
+
+// ============================================================================
+// Interactive Brokers
+// ============================================================================
+
+/// Collect the attribute sections of all `<tag .../>` elements
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{} ", tag);
+    let mut elements = Vec::new();
+    let mut search_start = 0;
+    while let Some(pos) = xml[search_start..].find(&open) {
+        let start = search_start + pos + open.len();
+        let Some(end) = xml[start..].find('>') else {
+            break;
+        };
+        elements.push(&xml[start..start + end]);
+        search_start = start + end;
+    }
+    elements
+}
+
+/// Extract an attribute value from an element's attribute section
+fn xml_attr(element: &str, name: &str) -> Option<String> {
+    let needle = format!(" {}=\"", name);
+    let padded = format!(" {}", element);
+    let start = padded.find(&needle)? + needle.len();
+    let end = padded[start..].find('"')?;
+    let value = padded[start..start + end]
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">");
+    non_empty(&value)
+}
+
+/// Parse an Interactive Brokers Flex Query XML report
+pub fn parse_ibkr_flex_xml(xml: &str) -> Result<ParsedStatement> {
+    if !xml.contains("<FlexQueryResponse") && !xml.contains("<FlexStatement") {
+        return Err(AppError::Validation("Unrecognized IBKR Flex report".into()));
+    }
+
+    let mut statement = ParsedStatement::default();
+    let attr_num = |el: &str, name: &str| -> Decimal {
+        xml_attr(el, name)
+            .and_then(|v| money::parse(&v))
+            .unwrap_or_default()
+    };
+    let attr_date = |el: &str| -> Option<i64> {
+        xml_attr(el, "tradeDate")
+            .or_else(|| xml_attr(el, "dateTime"))
+            .or_else(|| xml_attr(el, "reportDate"))
+            .and_then(|d| parse_compact_date(&d))
+    };
+
+    for el in xml_elements(xml, "Trade") {
+        let Some(date) = attr_date(el) else {
+            statement
+                .warnings
+                .push("Trade without a valid date was skipped".into());
+            continue;
+        };
+        let category = xml_attr(el, "assetCategory").unwrap_or_default();
+        let currency = xml_attr(el, "currency").unwrap_or_else(|| "USD".into());
+        let quantity = attr_num(el, "quantity");
+        let price = attr_num(el, "tradePrice");
+        let order_id = xml_attr(el, "ibOrderID").or_else(|| xml_attr(el, "tradeID"));
+
+        if category == "CASH" {
+            // Symbol "EUR.USD": quantity of EUR bought (or sold) at price in USD
+            let symbol = xml_attr(el, "symbol").unwrap_or_default();
+            let Some((base, _)) = symbol.split_once('.') else {
+                continue;
+            };
+            let quote_amount = (quantity * price).abs();
+            let mut record = if quantity > Decimal::ZERO {
+                let mut r = BrokerRecord::new(BrokerRecordKind::FxConversion, date, &currency);
+                r.amount = quote_amount;
+                r.counter_amount = quantity;
+                r.counter_currency = Some(base.to_uppercase());
+                r
+            } else {
+                let mut r = BrokerRecord::new(BrokerRecordKind::FxConversion, date, base);
+                r.amount = quantity.abs();
+                r.counter_amount = quote_amount;
+                r.counter_currency = Some(currency.clone());
+                r
+            };
+            record.external_id = order_id;
+            statement.records.push(record);
+            continue;
+        }
+        if category != "STK" && category != "ETF" && !category.is_empty() {
+            continue;
+        }
+
+        let kind = match xml_attr(el, "buySell").as_deref() {
+            Some("SELL") => BrokerRecordKind::Sell,
+            Some("BUY") => BrokerRecordKind::Buy,
+            _ if quantity < Decimal::ZERO => BrokerRecordKind::Sell,
+            _ => BrokerRecordKind::Buy,
+        };
+        let mut record = BrokerRecord::new(kind, date, &currency);
+        record.ticker = xml_attr(el, "symbol");
+        record.isin = xml_attr(el, "isin");
+        record.name = xml_attr(el, "description");
+        record.quantity = quantity.abs();
+        record.price = price;
+        record.external_id = order_id.clone();
+        statement.records.push(record.clone());
+
+        let commission = attr_num(el, "ibCommission").abs();
+        if commission > Decimal::ZERO {
+            let fee_currency = xml_attr(el, "ibCommissionCurrency").unwrap_or(currency);
+            let mut fee = BrokerRecord::new(BrokerRecordKind::Fee, date, &fee_currency);
+            fee.ticker = record.ticker;
+            fee.isin = record.isin;
+            fee.amount = commission;
+            fee.external_id = order_id;
+            statement.records.push(fee);
+        }
+    }
+
+    for el in xml_elements(xml, "CashTransaction") {
+        let tx_type = xml_attr(el, "type").unwrap_or_default().to_lowercase();
+        let kind = if tx_type.contains("withholding") {
+            BrokerRecordKind::WithholdingTax
+        } else if tx_type.contains("dividend") {
+            BrokerRecordKind::Dividend
+        } else if tx_type.contains("fee") || tx_type.contains("commission") {
+            BrokerRecordKind::Fee
+        } else {
+            continue;
+        };
+        let Some(date) = xml_attr(el, "dateTime")
+            .or_else(|| xml_attr(el, "reportDate"))
+            .and_then(|d| parse_compact_date(&d))
+        else {
+            continue;
+        };
+        let amount = attr_num(el, "amount");
+        // Refunds/reversals of withholding or fees are positive; skip them
+        if kind != BrokerRecordKind::Dividend && amount > Decimal::ZERO {
+            continue;
+        }
+        let mut record = BrokerRecord::new(
+            kind,
+            date,
+            &xml_attr(el, "currency").unwrap_or_else(|| "USD".into()),
+        );
+        record.ticker = xml_attr(el, "symbol");
+        record.isin = xml_attr(el, "isin");
+        record.name = xml_attr(el, "description");
+        record.amount = amount.abs();
+        record.external_id = xml_attr(el, "transactionID");
+        statement.records.push(record);
+    }
+
+    merge_withholding_tax(&mut statement);
+    Ok(statement)
+}
+
+// ============================================================================
+// XTB
+// ============================================================================
+
+/// Map an XTB symbol ("AAPL.US", "SAP.DE") to a Yahoo ticker and its currency
+pub fn xtb_symbol_to_ticker(symbol: &str) -> (String, Option<&'static str>) {
+    let Some((base, market)) = symbol.rsplit_once('.') else {
+        return (symbol.to_uppercase(), None);
+    };
+    let base = base.to_uppercase();
+    match market.to_uppercase().as_str() {
+        "US" => (base, Some("USD")),
+        "UK" => (format!("{}.L", base), Some("GBP")),
+        "DE" => (format!("{}.DE", base), Some("EUR")),
+        "FR" => (format!("{}.PA", base), Some("EUR")),
+        "NL" => (format!("{}.AS", base), Some("EUR")),
+        "ES" => (format!("{}.MC", base), Some("EUR")),
+        "IT" => (format!("{}.MI", base), Some("EUR")),
+        "BE" => (format!("{}.BR", base), Some("EUR")),
+        "PT" => (format!("{}.LS", base), Some("EUR")),
+        "FI" => (format!("{}.HE", base), Some("EUR")),
+        "PL" => (format!("{}.WA", base), Some("PLN")),
+        "CZ" => (format!("{}.PR", base), Some("CZK")),
+        "CH" => (format!("{}.SW", base), Some("CHF")),
+        "DK" => (format!("{}.CO", base), Some("DKK")),
+        "SE" => (format!("{}.ST", base), Some("SEK")),
+        "NO" => (format!("{}.OL", base), Some("NOK")),
+        _ => (symbol.to_uppercase(), None),
+    }
+}
+
+/// Read the cash operations sheet of an XTB XLSX report into string rows
+pub fn read_xtb_xlsx(bytes: &[u8]) -> Result<Vec<Vec<String>>> {
+    use calamine::{open_workbook_auto_from_rs, Data, Reader};
+
+    let mut workbook = open_workbook_auto_from_rs(std::io::Cursor::new(bytes.to_vec()))
+        .map_err(|e| AppError::Validation(format!("Cannot read XLSX file: {}", e)))?;
+    let sheet_names = workbook.sheet_names();
+    let sheet = sheet_names
+        .iter()
+        .find(|name| name.to_uppercase().contains("CASH"))
+        .or_else(|| sheet_names.first())
+        .cloned()
+        .ok_or_else(|| AppError::Validation("XLSX file has no sheets".into()))?;
+    let range = workbook
+        .worksheet_range(&sheet)
+        .map_err(|e| AppError::Validation(format!("Cannot read sheet '{}': {}", sheet, e)))?;
+
+    Ok(range
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|c| match c {
+                    // Excel serial date: days since 1899-12-30
+                    Data::DateTime(d) => NaiveDate::from_ymd_opt(1899, 12, 30)
+                        .and_then(|epoch| epoch.and_hms_opt(0, 0, 0))
+                        .map(|epoch| {
+                            let seconds = (d.as_f64() * 86400.0).round() as i64;
+                            (epoch + chrono::Duration::seconds(seconds))
+                                .format("%Y-%m-%d %H:%M:%S")
+                                .to_string()
+                        })
+                        .unwrap_or_default(),
+                    Data::Empty => String::new(),
+                    other => other.to_string().trim().to_string(),
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Parse XTB cash operations rows (header row is located automatically)
+///
+/// Amounts are in the account currency; trade prices come from the comment
+/// ("OPEN BUY 2/5 @ 175.50") and are in the instrument's currency.
+pub fn parse_xtb_rows(rows: &[Vec<String>], account_currency: &str) -> Result<ParsedStatement> {
+    let header_idx = rows
+        .iter()
+        .position(|r| find_col(r, &["Type"]).is_some() && find_col(r, &["Comment"]).is_some())
+        .ok_or_else(|| AppError::Validation("Unrecognized XTB cash operations report".into()))?;
+    let headers = &rows[header_idx];
+    let id_col = find_col(headers, &["ID"]);
+    let type_col = find_col(headers, &["Type"]);
+    let time_col = find_col(headers, &["Time"]);
+    let symbol_col = find_col(headers, &["Symbol", "Instrument"]);
+    let comment_col = find_col(headers, &["Comment"]);
+    let amount_col = find_col(headers, &["Amount"]);
+
+    let trade_re = Regex::new(r"(?i)(?:OPEN|CLOSE)\s+BUY\s+([\d.,]+)(?:/[\d.,]+)?\s*@\s*([\d.,]+)")
+        .expect("valid regex");
+
+    let mut statement = ParsedStatement::default();
+    for row in &rows[header_idx + 1..] {
+        let tx_type = cell(row, type_col).to_lowercase();
+        if tx_type.is_empty() {
+            continue;
+        }
+        let Some(date) = parse_date(cell(row, time_col)) else {
+            continue;
+        };
+        let amount = clean_and_parse_decimal(cell(row, amount_col));
+        let symbol = cell(row, symbol_col);
+        let (ticker, instrument_currency) = xtb_symbol_to_ticker(symbol);
+        let ticker = non_empty(&ticker);
+        let external_id = non_empty(cell(row, id_col));
+
+        let kind = if tx_type.contains("purchase") {
+            BrokerRecordKind::Buy
+        } else if tx_type.contains("sale") {
+            BrokerRecordKind::Sell
+        } else if tx_type.contains("withholding") {
+            BrokerRecordKind::WithholdingTax
+        } else if tx_type.starts_with("divident") || tx_type.starts_with("dividend") {
+            BrokerRecordKind::Dividend
+        } else if (tx_type.contains("commission") || tx_type.contains("fee"))
+            && amount < Decimal::ZERO
+        {
+            BrokerRecordKind::Fee
+        } else {
+            continue;
+        };
+
+        let mut record = if kind == BrokerRecordKind::Buy || kind == BrokerRecordKind::Sell {
+            let comment = cell(row, comment_col);
+            let Some(caps) = trade_re.captures(comment) else {
+                statement.warnings.push(format!(
+                    "{}: cannot parse trade comment '{}'",
+                    symbol, comment
+                ));
+                continue;
+            };
+            let mut r =
+                BrokerRecord::new(kind, date, instrument_currency.unwrap_or(account_currency));
+            r.quantity = clean_and_parse_decimal(&caps[1]);
+            r.price = clean_and_parse_decimal(&caps[2]);
+            r
+        } else {
+            let mut r = BrokerRecord::new(kind, date, account_currency);
+            r.amount = amount.abs();
+            r
+        };
+        record.ticker = ticker;
+        record.external_id = external_id;
+        statement.records.push(record);
+    }
+
+    merge_withholding_tax(&mut statement);
+    Ok(statement)
+}
+
+/// Parse a broker statement file
+pub fn parse_broker_file(
+    broker: Broker,
+    bytes: &[u8],
+    account_currency: &str,
+) -> Result<ParsedStatement> {
+    let text = || {
+        let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+        String::from_utf8_lossy(bytes).into_owned()
+    };
+    match broker {
+        Broker::Degiro => parse_degiro_csv(&text()),
+        Broker::Trading212 => parse_trading212_csv(&text()),
+        Broker::Ibkr => parse_ibkr_flex_xml(&text()),
+        Broker::Xtb => {
+            let rows = if bytes.starts_with(b"PK") {
+                read_xtb_xlsx(bytes)?
+            } else {
+                let (headers, mut rows) = read_csv_table(&text())?;
+                rows.insert(0, headers);
+                rows
+            };
+            parse_xtb_rows(&rows, account_currency)
+        }
+    }
+}
+
+// ============================================================================
+// ISIN resolution
+// ============================================================================
+
+/// Get a cached ISIN -> (ticker, name) mapping
+pub fn get_cached_isin_ticker(
+    conn: &rusqlite::Connection,
+    isin: &str,
+) -> Option<(String, Option<String>)> {
+    conn.query_row(
+        "SELECT ticker, name FROM isin_tickers WHERE isin = ?1",
+        [isin],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .ok()
+}
+
+/// Store an ISIN -> ticker mapping for later imports
+pub fn save_isin_ticker(
+    conn: &rusqlite::Connection,
+    isin: &str,
+    ticker: &str,
+    name: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO isin_tickers (isin, ticker, name, updated_at) VALUES (?1, ?2, ?3, unixepoch())
+         ON CONFLICT(isin) DO UPDATE SET ticker = excluded.ticker, name = excluded.name, updated_at = excluded.updated_at",
+        rusqlite::params![isin, ticker.to_uppercase(), name],
+    )?;
+    Ok(())
+}
+
+/// Source country for the dividend tax report: ISIN prefix, else ticker suffix
+fn source_country(isin: Option<&str>, ticker: &str) -> String {
+    if let Some(isin) = isin.filter(|i| is_valid_isin(i)) {
+        return isin[..2].to_string();
+    }
+    crate::services::exposure::ticker_country(ticker).to_string()
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+fn external_id_exists(conn: &rusqlite::Connection, table: &str, external_id: &str) -> bool {
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM {} WHERE external_id = ?1", table),
+        [external_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|c| c > 0)
+    .unwrap_or(false)
+}
+
+/// Build stable external IDs "<broker>:<kind>:<id>" for all records.
+/// Records without a broker ID get one derived from their content; repeated
+/// IDs within one statement (partial fills) get a "#n" suffix.
+fn assign_external_ids(broker: Broker, records: &[BrokerRecord]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    records
+        .iter()
+        .map(|r| {
+            let raw = r.external_id.clone().unwrap_or_else(|| {
+                format!(
+                    "{}|{}|{}|{}|{}",
+                    r.date,
+                    r.isin.as_deref().or(r.ticker.as_deref()).unwrap_or(""),
+                    money::format_exact(r.quantity),
+                    money::format_exact(r.amount),
+                    r.currency
+                )
+            });
+            let base = format!("{}:{}:{}", broker.as_str(), r.kind.as_str(), raw);
+            let count = seen.entry(base.clone()).or_insert(0);
+            let id = if *count == 0 {
+                base.clone()
+            } else {
+                format!("{}#{}", base, count)
+            };
+            *count += 1;
+            id
+        })
+        .collect()
+}
+
+/// Store parsed records. `isin_tickers` maps ISINs to resolved (ticker, name).
+///
+/// Everything is stored in one transaction, committed after the last record;
+/// a record that fails is rolled back alone and reported in the errors.
+/// Returns the import summary and the earliest trade date per ticker
+/// (for historical recalculation).
+pub fn import_broker_records(
+    conn: &rusqlite::Connection,
+    broker: Broker,
+    statement: ParsedStatement,
+    isin_tickers: &HashMap<String, (String, Option<String>)>,
+    account_id: Option<&str>,
+) -> Result<(BrokerImportResult, HashMap<String, i64>)> {
+    let mut result = BrokerImportResult {
+        errors: statement.warnings,
+        ..Default::default()
+    };
+    let mut ticker_earliest_dates: HashMap<String, i64> = HashMap::new();
+
+    let mut tx = conn.unchecked_transaction()?;
+    let external_ids = assign_external_ids(broker, &statement.records);
+    let mut records: Vec<(BrokerRecord, String)> =
+        statement.records.into_iter().zip(external_ids).collect();
+    // Chronological order with buys first so sells always find a position
+    records.sort_by_key(|(r, _)| (r.date, r.kind != BrokerRecordKind::Buy));
+
+    for (record, external_id) in records {
+        // Each record is written under a savepoint so a failing record leaves nothing behind
+        let sp = tx.savepoint()?;
+        let resolved = record.isin.as_ref().and_then(|isin| isin_tickers.get(isin));
+        let ticker = resolved
+            .map(|(t, _)| t.clone())
+            .or_else(|| record.ticker.clone())
+            .map(|t| t.to_uppercase());
+        let name = record
+            .name
+            .clone()
+            .or_else(|| resolved.and_then(|(_, n)| n.clone()));
+
+        let outcome: Result<Option<String>> = match record.kind {
+            BrokerRecordKind::Buy | BrokerRecordKind::Sell => (|| {
+                let ticker = ticker.clone().ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Cannot resolve ticker for {}",
+                        record.isin.as_deref().unwrap_or("unknown instrument")
+                    ))
+                })?;
+                if external_id_exists(&sp, "investment_transactions", &external_id) {
+                    return Ok(None);
+                }
+                let company_name = name.clone().unwrap_or_else(|| ticker.clone());
+                let (description, date, ticker) = import_single_transaction(
+                    &sp,
+                    &ticker,
+                    &company_name,
+                    record.kind.as_str(),
+                    &money::format_exact(record.quantity),
+                    &money::format_price(record.price),
+                    &record.currency,
+                    record.date,
+                    Some(&external_id),
+                    account_id,
+                )?;
+                ticker_earliest_dates
+                    .entry(ticker)
+                    .and_modify(|e| *e = (*e).min(date))
+                    .or_insert(date);
+                result.trades_imported += 1;
+                Ok(Some(description))
+            })(),
+            BrokerRecordKind::Dividend => (|| {
+                let ticker = ticker.clone().ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Cannot resolve ticker for dividend from {}",
+                        record.isin.as_deref().unwrap_or("unknown instrument")
+                    ))
+                })?;
+                if external_id_exists(&sp, "stock_dividends", &external_id) {
+                    return Ok(None);
+                }
+                sp.execute(
+                    "INSERT INTO stock_dividends (id, ticker, payment_date, gross_amount, withholding_tax, currency, source_country, external_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    rusqlite::params![
+                        Uuid::new_v4().to_string(),
+                        ticker,
+                        record.date,
+                        money::format(record.amount, &record.currency),
+                        money::format(record.withholding_tax, &record.currency),
+                        record.currency,
+                        source_country(record.isin.as_deref(), &ticker),
+                        external_id,
+                    ],
+                )?;
+                result.dividends_imported += 1;
+                Ok(Some(format!(
+                    "DIVIDEND {} {} {}",
+                    ticker,
+                    money::format(record.amount, &record.currency),
+                    record.currency
+                )))
+            })(),
+            BrokerRecordKind::Fee | BrokerRecordKind::FxConversion => (|| {
+                if external_id_exists(&sp, "broker_cash_movements", &external_id) {
+                    return Ok(None);
+                }
+                sp.execute(
+                    "INSERT INTO broker_cash_movements
+                     (id, broker, kind, ticker, amount, currency, counter_amount, counter_currency, occurred_at, external_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    rusqlite::params![
+                        Uuid::new_v4().to_string(),
+                        broker.as_str(),
+                        record.kind.as_str(),
+                        ticker,
+                        money::format(record.amount, &record.currency),
+                        record.currency,
+                        record
+                            .counter_currency
+                            .as_ref()
+                            .map(|c| money::format(record.counter_amount, c)),
+                        record.counter_currency,
+                        record.date,
+                        external_id,
+                    ],
+                )?;
+                if record.kind == BrokerRecordKind::Fee {
+                    result.fees_imported += 1;
+                    Ok(Some(format!(
+                        "FEE {} {}",
+                        money::format(record.amount, &record.currency),
+                        record.currency
+                    )))
+                } else {
+                    result.fx_conversions_imported += 1;
+                    Ok(Some(format!(
+                        "FX {} {} -> {} {}",
+                        money::format(record.amount, &record.currency),
+                        record.currency,
+                        money::format_exact(record.counter_amount),
+                        record.counter_currency.as_deref().unwrap_or("")
+                    )))
+                }
+            })(),
+            BrokerRecordKind::WithholdingTax => Ok(None),
+        };
+
+        match outcome {
+            Ok(outcome) => {
+                sp.commit()?;
+                match outcome {
+                    Some(description) => result.imported.push(description),
+                    None => result.duplicates_skipped += 1,
+                }
+            }
+            Err(e) => {
+                // Dropping the savepoint rolls back the record's partial writes
+                drop(sp);
+                result.errors.push(e.to_string());
+            }
+        }
+    }
+
+    tx.commit()?;
+    Ok((result, ticker_earliest_dates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::str::FromStr;
+
+    fn dec(text: &str) -> Decimal {
+        Decimal::from_str(text).unwrap()
+    }
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        conn.execute_batch(
+            r#"
+            CREATE TABLE stock_investments (
+                id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL UNIQUE,
+                company_name TEXT NOT NULL,
+                quantity TEXT NOT NULL DEFAULT '0',
+                average_price TEXT NOT NULL DEFAULT '0',
+                currency TEXT NOT NULL DEFAULT 'CZK'
+            );
+            CREATE TABLE investment_transactions (
+                id TEXT PRIMARY KEY,
+                investment_id TEXT NOT NULL,
+                type TEXT NOT NULL,
+                ticker TEXT NOT NULL,
+                company_name TEXT NOT NULL,
+                quantity TEXT NOT NULL,
+                price_per_unit TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                transaction_date INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                external_id TEXT UNIQUE,
+                account_id TEXT
+            );
+            CREATE TABLE stock_dividends (
+                id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL,
+                payment_date INTEGER NOT NULL,
+                gross_amount TEXT NOT NULL,
+                withholding_tax TEXT NOT NULL DEFAULT '0',
+                currency TEXT NOT NULL,
+                source_country TEXT NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT 0,
+                external_id TEXT UNIQUE
+            );
+            CREATE TABLE broker_cash_movements (
+                id TEXT PRIMARY KEY,
+                broker TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                ticker TEXT,
+                amount TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                counter_amount TEXT,
+                counter_currency TEXT,
+                occurred_at INTEGER NOT NULL,
+                external_id TEXT UNIQUE,
+                created_at INTEGER NOT NULL DEFAULT 0
+            );
+            "#,
+        )
+        .expect("schema");
+        conn
+    }
+
+    const DEGIRO_TRANSACTIONS: &str = "Date,Time,Product,ISIN,Reference exchange,Venue,Quantity,Price,,Local value,,Value,,Exchange rate,Transaction and/or third party fees,,Total,,Order ID
+15-01-2024,15:30,APPLE INC,US0378331005,NDQ,XNAS,10,\"185,50\",USD,\"-1855,00\",USD,\"-1690,00\",EUR,\"1,0975\",\"-1,00\",EUR,\"-1691,00\",EUR,abc-1
+16-01-2024,10:00,APPLE INC,US0378331005,NDQ,XNAS,-4,\"190,00\",USD,\"760,00\",USD,\"692,00\",EUR,\"1,0975\",,,\"692,00\",EUR,abc-2
+";
+
+    const DEGIRO_ACCOUNT: &str =
+        "Date,Time,Value date,Product,ISIN,Description,FX,Change,,Balance,,Order Id
+15-02-2024,07:00,14-02-2024,APPLE INC,US0378331005,Dividend,,USD,\"2,40\",USD,\"2,40\",
+15-02-2024,07:00,14-02-2024,APPLE INC,US0378331005,Dividend Tax,,USD,\"-0,36\",USD,\"2,04\",
+20-02-2024,08:00,20-02-2024,,,FX Debit,,USD,\"-2,04\",USD,\"0,00\",fx-1
+20-02-2024,08:00,20-02-2024,,,FX Credit,\"1,08\",EUR,\"1,89\",EUR,\"1,89\",fx-1
+";
+
+    #[test]
+    fn test_degiro_transactions() {
+        let parsed = parse_degiro_csv(DEGIRO_TRANSACTIONS).unwrap();
+        assert_eq!(parsed.records.len(), 3);
+
+        let buy = &parsed.records[0];
+        assert_eq!(buy.kind, BrokerRecordKind::Buy);
+        assert_eq!(buy.isin.as_deref(), Some("US0378331005"));
+        assert_eq!(buy.quantity, dec("10"));
+        assert_eq!(buy.price, dec("185.5"));
+        assert_eq!(buy.currency, "USD");
+
+        let fee = &parsed.records[1];
+        assert_eq!(fee.kind, BrokerRecordKind::Fee);
+        assert_eq!(fee.amount, dec("1"));
+        assert_eq!(fee.currency, "EUR");
+        assert_eq!(fee.external_id.as_deref(), Some("abc-1"));
+
+        assert_eq!(parsed.records[2].kind, BrokerRecordKind::Sell);
+        assert_eq!(parsed.records[2].quantity, dec("4"));
+    }
+
+    #[test]
+    fn test_degiro_account_dividend_and_fx() {
+        let parsed = parse_degiro_csv(DEGIRO_ACCOUNT).unwrap();
+        assert_eq!(parsed.records.len(), 2, "{:?}", parsed.records);
+
+        let fx = parsed
+            .records
+            .iter()
+            .find(|r| r.kind == BrokerRecordKind::FxConversion)
+            .unwrap();
+        assert_eq!(fx.currency, "USD");
+        assert_eq!(fx.amount, dec("2.04"));
+        assert_eq!(fx.counter_currency.as_deref(), Some("EUR"));
+        assert_eq!(fx.counter_amount, dec("1.89"));
+
+        let dividend = parsed
+            .records
+            .iter()
+            .find(|r| r.kind == BrokerRecordKind::Dividend)
+            .unwrap();
+        assert_eq!(dividend.amount, dec("2.40"));
+        assert_eq!(dividend.withholding_tax, dec("0.36"));
+    }
+
+    #[test]
+    fn test_trading212_csv() {
+        let csv = "Action,Time,ISIN,Ticker,Name,No. of shares,Price / share,Currency (Price / share),Exchange rate,Total,Currency (Total),Withholding tax,Currency (Withholding tax),Currency conversion from amount,Currency (Currency conversion from amount),Currency conversion to amount,Currency (Currency conversion to amount),Currency conversion fee,Currency (Currency conversion fee),ID
+Market buy,2024-01-15 14:30:00,US0378331005,AAPL,Apple,2,185.00,USD,1.09,339.45,EUR,,,,,,,0.51,EUR,EOF1
+Dividend (Ordinary),2024-02-15 10:00:00,US0378331005,AAPL,Apple,2,0.24,USD,1.08,0.38,EUR,0.07,USD,,,,,,,
+Currency conversion,2024-03-01 09:00:00,,,,,,,,,,,,100.00,EUR,108.00,USD,,,FX1
+Deposit,2024-01-01 09:00:00,,,,,,,,1000,EUR,,,,,,,,,D1
+";
+        let parsed = parse_trading212_csv(csv).unwrap();
+        let kinds: Vec<BrokerRecordKind> = parsed.records.iter().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                BrokerRecordKind::Buy,
+                BrokerRecordKind::Fee,
+                BrokerRecordKind::Dividend,
+                BrokerRecordKind::FxConversion
+            ]
+        );
+        assert_eq!(parsed.records[0].ticker.as_deref(), Some("AAPL"));
+        assert_eq!(parsed.records[1].amount, dec("0.51"));
+        assert_eq!(parsed.records[2].amount, dec("0.48"));
+        assert_eq!(parsed.records[2].withholding_tax, dec("0.07"));
+        assert_eq!(parsed.records[3].counter_currency.as_deref(), Some("USD"));
+    }
+
+    #[test]
+    fn test_ibkr_flex_xml() {
+        let xml = r#"<FlexQueryResponse queryName="q" type="AF"><FlexStatements count="1"><FlexStatement accountId="U1">
+<Trades>
+<Trade assetCategory="STK" symbol="MSFT" description="MICROSOFT CORP" isin="US5949181045" currency="USD" tradeDate="20240115" quantity="5" tradePrice="390.1" buySell="BUY" ibCommission="-1.0" ibCommissionCurrency="USD" ibOrderID="555" />
+<Trade assetCategory="CASH" symbol="EUR.USD" currency="USD" tradeDate="20240114" quantity="1000" tradePrice="1.09" buySell="BUY" ibCommission="-2" ibCommissionCurrency="USD" ibOrderID="556" />
+</Trades>
+<CashTransactions>
+<CashTransaction type="Dividends" symbol="MSFT" isin="US5949181045" currency="USD" amount="3.75" dateTime="20240314;202000" transactionID="T1" />
+<CashTransaction type="Withholding Tax" symbol="MSFT" isin="US5949181045" currency="USD" amount="-0.56" dateTime="20240314;202000" transactionID="T2" />
+</CashTransactions>
+</FlexStatement></FlexStatements></FlexQueryResponse>"#;
+        let parsed = parse_ibkr_flex_xml(xml).unwrap();
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(parsed.records.len(), 4);
+
+        let fx = &parsed.records[2];
+        assert_eq!(fx.kind, BrokerRecordKind::FxConversion);
+        assert_eq!(fx.currency, "USD");
+        assert_eq!(fx.amount, dec("1090"));
+        assert_eq!(fx.counter_currency.as_deref(), Some("EUR"));
+
+        let dividend = &parsed.records[3];
+        assert_eq!(dividend.kind, BrokerRecordKind::Dividend);
+        assert_eq!(dividend.withholding_tax, dec("0.56"));
+    }
+
+    #[test]
+    fn test_xtb_rows() {
+        let rows: Vec<Vec<String>> = [
+            vec!["Cash operations", "", "", "", "", ""],
+            vec!["ID", "Type", "Time", "Symbol", "Comment", "Amount"],
+            vec![
+                "1",
+                "Stocks/ETF purchase",
+                "15.01.2024 14:30:00",
+                "AAPL.US",
+                "OPEN BUY 2/5 @ 185.50",
+                "-8500.00",
+            ],
+            vec![
+                "2",
+                "DIVIDENT",
+                "15.02.2024 10:00:00",
+                "AAPL.US",
+                "AAPL.US USD 0.2400/ SHR",
+                "11.00",
+            ],
+            vec![
+                "3",
+                "Withholding Tax",
+                "15.02.2024 10:00:00",
+                "AAPL.US",
+                "AAPL.US USD WHT 15%",
+                "-1.65",
+            ],
+            vec!["4", "deposit", "01.01.2024 09:00:00", "", "", "10000"],
+        ]
+        .iter()
+        .map(|r| r.iter().map(|s| s.to_string()).collect())
+        .collect();
+
+        let parsed = parse_xtb_rows(&rows, "CZK").unwrap();
+        assert_eq!(parsed.records.len(), 2);
+        let buy = &parsed.records[0];
+        assert_eq!(buy.ticker.as_deref(), Some("AAPL"));
+        assert_eq!(buy.quantity, dec("2"));
+        assert_eq!(buy.currency, "USD");
+        let dividend = &parsed.records[1];
+        assert_eq!(dividend.currency, "CZK");
+        assert_eq!(dividend.withholding_tax, dec("1.65"));
+    }
+
+    #[test]
+    fn test_xtb_symbol_mapping() {
+        assert_eq!(
+            xtb_symbol_to_ticker("SAP.DE"),
+            ("SAP.DE".into(), Some("EUR"))
+        );
+        assert_eq!(
+            xtb_symbol_to_ticker("VUSA.UK"),
+            ("VUSA.L".into(), Some("GBP"))
+        );
+        assert_eq!(
+            xtb_symbol_to_ticker("CEZ.CZ"),
+            ("CEZ.PR".into(), Some("CZK"))
+        );
+    }
+
+    #[test]
+    fn test_import_resolves_isin_and_dedupes() {
+        let conn = setup_test_db();
+        let isin_map: HashMap<String, (String, Option<String>)> = [(
+            "US0378331005".to_string(),
+            ("AAPL".to_string(), Some("Apple Inc.".to_string())),
+        )]
+        .into_iter()
+        .collect();
+
+        let parsed = parse_degiro_csv(DEGIRO_TRANSACTIONS).unwrap();
+        let (result, dates) =
+            import_broker_records(&conn, Broker::Degiro, parsed, &isin_map, None).unwrap();
+        assert_eq!(result.trades_imported, 2, "{:?}", result.errors);
+        assert_eq!(result.fees_imported, 1);
+        assert!(dates.contains_key("AAPL"));
+
+        let qty: String = conn
+            .query_row(
+                "SELECT quantity FROM stock_investments WHERE ticker = 'AAPL'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(qty, "6");
+
+        // Importing the same statement again only skips
+        let parsed = parse_degiro_csv(DEGIRO_TRANSACTIONS).unwrap();
+        let (result, _) =
+            import_broker_records(&conn, Broker::Degiro, parsed, &isin_map, None).unwrap();
+        assert_eq!(result.trades_imported, 0);
+        assert_eq!(result.duplicates_skipped, 3);
+    }
+
+    #[test]
+    fn test_import_dividend_uses_isin_country() {
+        let conn = setup_test_db();
+        let isin_map: HashMap<String, (String, Option<String>)> =
+            [("US0378331005".to_string(), ("AAPL".to_string(), None))]
+                .into_iter()
+                .collect();
+
+        let parsed = parse_degiro_csv(DEGIRO_ACCOUNT).unwrap();
+        let (result, _) =
+            import_broker_records(&conn, Broker::Degiro, parsed, &isin_map, None).unwrap();
+        assert_eq!(result.dividends_imported, 1);
+        assert_eq!(result.fx_conversions_imported, 1);
+
+        let (country, tax): (String, String) = conn
+            .query_row(
+                "SELECT source_country, withholding_tax FROM stock_dividends",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(country, "US");
+        assert_eq!(tax, "0.36");
+    }
+
+    #[test]
+    fn test_failed_record_leaves_no_partial_writes() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TRIGGER reject_trades BEFORE INSERT ON investment_transactions
+             BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+        )
+        .unwrap();
+        let mut buy = BrokerRecord::new(BrokerRecordKind::Buy, 1_700_000_000, "USD");
+        buy.ticker = Some("AAPL".into());
+        buy.quantity = dec("1");
+        buy.price = dec("190.5");
+        let statement = ParsedStatement {
+            records: vec![buy],
+            warnings: Vec::new(),
+        };
+
+        let (result, _) =
+            import_broker_records(&conn, Broker::Ibkr, statement, &HashMap::new(), None).unwrap();
+        assert_eq!(result.trades_imported, 0);
+        assert_eq!(result.errors.len(), 1);
+        // The position created before the trade failed is rolled back with it
+        let positions: i64 = conn
+            .query_row("SELECT COUNT(*) FROM stock_investments", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(positions, 0);
+    }
+
+    #[test]
+    fn test_partial_fills_get_distinct_ids() {
+        let mut a = BrokerRecord::new(BrokerRecordKind::Buy, 0, "USD");
+        a.external_id = Some("X".into());
+        let ids = assign_external_ids(Broker::Ibkr, &[a.clone(), a]);
+        assert_eq!(
+            ids,
+            vec!["ibkr:buy:X".to_string(), "ibkr:buy:X#1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_content_ids_keep_exact_decimals() {
+        let mut a = BrokerRecord::new(BrokerRecordKind::Fee, 0, "EUR");
+        a.amount = dec("0.10");
+        let mut b = a.clone();
+        b.amount = dec("0.1000000000000000001");
+        let ids = assign_external_ids(Broker::Degiro, &[a, b]);
+        assert_eq!(ids[0], "degiro:fee:0||0|0.1|EUR");
+        assert_ne!(ids[0], ids[1]);
+    }
+}
@@ -0,0 +1,384 @@
+//! Brokerage accounts service
+//!
+//! Accounts own stock transactions. `stock_investments` keeps the merged position
+//! per ticker; per-account positions and cost bases are derived from the
+//! transactions assigned to each account, using the same averaging as
+//! `investments::recalculate_investment_metrics`.
+
+use crate::error::{AppError, Result};
+use crate::models::{AccountPosition, AccountValue, BrokerageAccount, InsertBrokerageAccount};
+use crate::services::money::{self, Decimal};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+fn row_to_account(row: &rusqlite::Row) -> rusqlite::Result<BrokerageAccount> {
+    Ok(BrokerageAccount {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        broker: row.get(2)?,
+        account_type: row.get(3)?,
+        currency: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Get all brokerage accounts
+pub fn get_all_accounts(conn: &rusqlite::Connection) -> Result<Vec<BrokerageAccount>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, broker, account_type, currency, created_at
+         FROM brokerage_accounts ORDER BY name",
+    )?;
+    let accounts = stmt
+        .query_map([], row_to_account)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(accounts)
+}
+
+/// Get a brokerage account by ID
+pub fn get_account(conn: &rusqlite::Connection, id: &str) -> Result<BrokerageAccount> {
+    conn.query_row(
+        "SELECT id, name, broker, account_type, currency, created_at
+         FROM brokerage_accounts WHERE id = ?1",
+        [id],
+        row_to_account,
+    )
+    .map_err(|_| AppError::NotFound("Brokerage account not found".into()))
+}
+
+/// Create a brokerage account
+pub fn create_account(
+    conn: &rusqlite::Connection,
+    data: &InsertBrokerageAccount,
+) -> Result<BrokerageAccount> {
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp();
+    let account_type = data
+        .account_type
+        .as_deref()
+        .unwrap_or("taxable")
+        .to_lowercase();
+    let currency = data.currency.as_deref().unwrap_or("CZK").to_uppercase();
+
+    conn.execute(
+        "INSERT INTO brokerage_accounts (id, name, broker, account_type, currency, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            id,
+            data.name.trim(),
+            data.broker,
+            account_type,
+            currency,
+            now
+        ],
+    )?;
+
+    get_account(conn, &id)
+}
+
+/// Update a brokerage account
+pub fn update_account(
+    conn: &rusqlite::Connection,
+    id: &str,
+    data: &InsertBrokerageAccount,
+) -> Result<BrokerageAccount> {
+    let existing = get_account(conn, id)?;
+    let account_type = data
+        .account_type
+        .as_deref()
+        .map(|t| t.to_lowercase())
+        .unwrap_or(existing.account_type);
+    let currency = data
+        .currency
+        .as_deref()
+        .map(|c| c.to_uppercase())
+        .unwrap_or(existing.currency);
+
+    conn.execute(
+        "UPDATE brokerage_accounts SET name = ?1, broker = ?2, account_type = ?3, currency = ?4
+         WHERE id = ?5",
+        rusqlite::params![data.name.trim(), data.broker, account_type, currency, id],
+    )?;
+
+    get_account(conn, id)
+}
+
+/// Delete a brokerage account; its transactions become unassigned
+pub fn delete_account(conn: &rusqlite::Connection, id: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE investment_transactions SET account_id = NULL WHERE account_id = ?1",
+        [id],
+    )?;
+    let changes = conn.execute("DELETE FROM brokerage_accounts WHERE id = ?1", [id])?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Brokerage account not found".into()));
+    }
+    Ok(())
+}
+
+/// Move a transaction to another account (None = unassigned)
+pub fn set_transaction_account(
+    conn: &rusqlite::Connection,
+    tx_id: &str,
+    account_id: Option<&str>,
+) -> Result<()> {
+    if let Some(account_id) = account_id {
+        get_account(conn, account_id)?;
+    }
+    let changes = conn.execute(
+        "UPDATE investment_transactions SET account_id = ?1 WHERE id = ?2",
+        rusqlite::params![account_id, tx_id],
+    )?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Transaction not found".into()));
+    }
+    Ok(())
+}
+
+/// Running totals of one (account, investment) pair
+#[derive(Default)]
+struct PositionTotals {
+    ticker: String,
+    company_name: String,
+    currency: String,
+    quantity: Decimal,
+    weighted_buy_sum: Decimal,
+    weighted_buy_qty: Decimal,
+}
+
+/// Get open positions per brokerage account.
+///
+/// `account_id` = Some(id) limits the result to one account; None returns every
+/// account including transactions that are not assigned to any.
+pub fn get_account_positions(
+    conn: &rusqlite::Connection,
+    account_id: Option<&str>,
+) -> Result<Vec<AccountPosition>> {
+    let mut stmt = conn.prepare(
+        "SELECT account_id, investment_id, ticker, company_name, LOWER(type), quantity,
+                price_per_unit, currency
+         FROM investment_transactions
+         WHERE ?1 IS NULL OR account_id = ?1
+         ORDER BY transaction_date ASC, created_at ASC",
+    )?;
+    let rows = stmt.query_map([account_id], |row| {
+        Ok((
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, String>(6)?,
+            row.get::<_, String>(7)?,
+        ))
+    })?;
+
+    let mut totals: BTreeMap<(Option<String>, String), PositionTotals> = BTreeMap::new();
+    for row in rows.filter_map(|r| r.ok()) {
+        let (account, investment_id, ticker, company_name, tx_type, qty, price, currency) = row;
+        let qty = money::parse_or_zero(&qty);
+        let price = money::parse_or_zero(&price);
+        let position = totals
+            .entry((account, investment_id))
+            .or_insert_with(|| PositionTotals {
+                ticker,
+                company_name,
+                currency: currency.to_uppercase(),
+                ..Default::default()
+            });
+        if tx_type == "buy" {
+            position.quantity += qty;
+            position.weighted_buy_sum += qty * price;
+            position.weighted_buy_qty += qty;
+        } else {
+            position.quantity -= qty;
+        }
+    }
+
+    let positions = totals
+        .into_iter()
+        .filter(|(_, p)| p.quantity > Decimal::ZERO)
+        .map(|((account_id, investment_id), p)| {
+            let average_price = if p.weighted_buy_qty > Decimal::ZERO {
+                p.weighted_buy_sum / p.weighted_buy_qty
+            } else {
+                Decimal::ZERO
+            };
+            let current_value_czk = crate::services::pricing::resolve_stock_price(conn, &p.ticker)
+                .map(|resolved| money::from_f64(resolved.price_czk) * p.quantity)
+                .unwrap_or_default();
+            let cost_basis_czk = money::to_czk(p.quantity * average_price, &p.currency);
+            AccountPosition {
+                account_id,
+                investment_id,
+                cost_basis_czk: money::to_f64(money::round(cost_basis_czk, "CZK")),
+                ticker: p.ticker,
+                company_name: p.company_name,
+                quantity: money::format_exact(p.quantity),
+                average_price: money::format_price(average_price),
+                currency: p.currency,
+                current_value_czk: money::to_f64(money::round(current_value_czk, "CZK")),
+            }
+        })
+        .collect();
+
+    Ok(positions)
+}
+
+/// Get the total value and cost basis of every brokerage account (CZK).
+/// Holdings not assigned to an account are reported with `account_id` = None.
+pub fn get_account_values(conn: &rusqlite::Connection) -> Result<Vec<AccountValue>> {
+    let mut values: Vec<AccountValue> = get_all_accounts(conn)?
+        .into_iter()
+        .map(|account| AccountValue {
+            account_id: Some(account.id),
+            name: Some(account.name),
+            account_type: Some(account.account_type),
+            total_value: 0.0,
+            total_cost: 0.0,
+            positions_count: 0,
+        })
+        .collect();
+
+    for position in get_account_positions(conn, None)? {
+        let index = match values
+            .iter()
+            .position(|v| v.account_id == position.account_id)
+        {
+            Some(index) => index,
+            None => {
+                values.push(AccountValue {
+                    account_id: position.account_id.clone(),
+                    name: None,
+                    account_type: None,
+                    total_value: 0.0,
+                    total_cost: 0.0,
+                    positions_count: 0,
+                });
+                values.len() - 1
+            }
+        };
+        let value = &mut values[index];
+        value.total_value += position.current_value_czk;
+        value.total_cost += position.cost_basis_czk;
+        value.positions_count += 1;
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE brokerage_accounts (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                broker TEXT,
+                account_type TEXT NOT NULL DEFAULT 'taxable',
+                currency TEXT NOT NULL DEFAULT 'CZK',
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE investment_transactions (
+                id TEXT PRIMARY KEY,
+                investment_id TEXT NOT NULL,
+                type TEXT NOT NULL,
+                ticker TEXT NOT NULL,
+                company_name TEXT NOT NULL,
+                quantity TEXT NOT NULL,
+                price_per_unit TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                transaction_date INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                account_id TEXT
+            );
+            CREATE TABLE stock_price_overrides (
+                ticker TEXT PRIMARY KEY, price TEXT, currency TEXT, updated_at INTEGER
+            );
+            CREATE TABLE stock_data (
+                ticker TEXT PRIMARY KEY, original_price TEXT, currency TEXT, fetched_at INTEGER
+            );
+            INSERT INTO stock_data VALUES ('VWCE', '120', 'CZK', 0);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn add_tx(
+        conn: &Connection,
+        id: &str,
+        account: Option<&str>,
+        tx_type: &str,
+        qty: &str,
+        price: &str,
+    ) {
+        conn.execute(
+            "INSERT INTO investment_transactions
+             (id, investment_id, type, ticker, company_name, quantity, price_per_unit, currency, transaction_date, created_at, account_id)
+             VALUES (?1, 'inv1', ?2, 'VWCE', 'Vanguard FTSE All-World', ?3, ?4, 'CZK', 0, 0, ?5)",
+            rusqlite::params![id, tx_type, qty, price, account],
+        )
+        .unwrap();
+    }
+
+    fn account(conn: &Connection, name: &str, account_type: &str) -> String {
+        create_account(
+            conn,
+            &InsertBrokerageAccount {
+                name: name.into(),
+                broker: None,
+                account_type: Some(account_type.into()),
+                currency: None,
+            },
+        )
+        .unwrap()
+        .id
+    }
+
+    #[test]
+    fn test_same_ticker_in_two_accounts_has_separate_cost_basis() {
+        let conn = setup_test_db();
+        let taxable = account(&conn, "Degiro", "taxable");
+        let pension = account(&conn, "DIP", "pension");
+        add_tx(&conn, "t1", Some(&taxable), "buy", "10", "100");
+        add_tx(&conn, "t2", Some(&pension), "buy", "5", "110");
+        add_tx(&conn, "t3", Some(&taxable), "sell", "4", "115");
+
+        let positions = get_account_positions(&conn, Some(&taxable)).unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, "6");
+        assert_eq!(positions[0].average_price, "100");
+        assert!((positions[0].current_value_czk - 720.0).abs() < 1e-9);
+
+        let values = get_account_values(&conn).unwrap();
+        let pension_value = values
+            .iter()
+            .find(|v| v.account_id.as_deref() == Some(pension.as_str()))
+            .unwrap();
+        assert!((pension_value.total_value - 600.0).abs() < 1e-9);
+        assert!((pension_value.total_cost - 550.0).abs() < 1e-9);
+        assert_eq!(pension_value.positions_count, 1);
+    }
+
+    #[test]
+    fn test_unassigned_and_deleted_accounts() {
+        let conn = setup_test_db();
+        let taxable = account(&conn, "XTB", "taxable");
+        add_tx(&conn, "t1", None, "buy", "2", "100");
+        add_tx(&conn, "t2", Some(&taxable), "buy", "3", "100");
+
+        delete_account(&conn, &taxable).unwrap();
+        let values = get_account_values(&conn).unwrap();
+        assert_eq!(values.len(), 1);
+        assert!(values[0].account_id.is_none());
+        assert_eq!(values[0].positions_count, 1);
+        assert!((values[0].total_value - 600.0).abs() < 1e-9);
+
+        assert!(set_transaction_account(&conn, "t1", Some("missing")).is_err());
+    }
+}
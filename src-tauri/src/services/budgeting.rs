@@ -10,6 +10,7 @@ use crate::error::{AppError, Result};
 use crate::models::budgeting::{
     BudgetGoal, BudgetingReport, BudgetingTransaction, CategorySpendingSummary, InsertBudgetGoal,
 };
+use crate::services::money::{self, Decimal};
 use rusqlite::{params, Connection};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -134,6 +135,15 @@ pub fn delete_goal(conn: &Connection, id: &str) -> Result<()> {
 
 // ========================== Budgeting Report ==========================
 
+/// Transactions of one category, type and currency
+struct CategoryGroup {
+    cat_name: String,
+    cat_icon: Option<String>,
+    cat_color: Option<String>,
+    amount: Decimal,
+    count: i32,
+}
+
 /// Get the full budgeting report for a time period
 pub fn get_report(
     conn: &Connection,
@@ -157,7 +167,8 @@ pub fn get_report(
         .collect();
 
     // Aggregate transactions by category, tx_type, and currency
-    // Exclude internal transfers at the SQL level for efficiency
+    // Exclude internal transfers at the SQL level for efficiency. Amounts are
+    // summed as decimals in Rust; SQL SUM over REAL would drift off the haléř.
     let mut stmt = conn.prepare(
         "SELECT
             COALESCE(bt.category_id, 'uncategorized') as cat_id,
@@ -166,55 +177,76 @@ pub fn get_report(
             tc.color as cat_color,
             bt.tx_type,
             bt.currency,
-            SUM(CAST(bt.amount AS REAL)) as total_amount,
-            COUNT(*) as tx_count
+            bt.amount
          FROM bank_transactions bt
          LEFT JOIN transaction_categories tc ON bt.category_id = tc.id
          WHERE bt.booking_date >= ?1 AND bt.booking_date <= ?2
-           AND COALESCE(bt.category_id, '') != 'cat_internal_transfers'
-         GROUP BY cat_id, bt.tx_type, bt.currency
-         ORDER BY cat_name",
+           AND COALESCE(bt.category_id, '') != 'cat_internal_transfers'",
     )?;
 
-    #[allow(clippy::type_complexity)]
-    let rows: Vec<(
-        String,
-        String,
-        Option<String>,
-        Option<String>,
-        String,
-        String,
-        f64,
-        i32,
-    )> = stmt
-        .query_map(params![start_date, end_date], |row| {
-            Ok((
-                row.get(0)?,
-                row.get(1)?,
-                row.get(2)?,
-                row.get(3)?,
-                row.get(4)?,
-                row.get(5)?,
-                row.get(6)?,
-                row.get(7)?,
-            ))
-        })?
-        .filter_map(|r| r.ok())
-        .collect();
+    let mut groups: HashMap<(String, String, String), CategoryGroup> = HashMap::new();
+    let rows = stmt.query_map(params![start_date, end_date], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, String>(5)?,
+            row.get::<_, String>(6)?,
+        ))
+    })?;
+    for (cat_id, cat_name, cat_icon, cat_color, tx_type, currency, amount) in
+        rows.filter_map(|r| r.ok())
+    {
+        let group = groups
+            .entry((cat_id, tx_type, currency))
+            .or_insert_with(|| CategoryGroup {
+                cat_name,
+                cat_icon,
+                cat_color,
+                amount: Decimal::ZERO,
+                count: 0,
+            });
+        group.amount += money::parse_or_zero(&amount);
+        group.count += 1;
+    }
 
     // Separate income (cat_income credits only) and expenses (all other categories, net amount)
-    let mut income_map: HashMap<String, CategorySpendingSummary> = HashMap::new();
-    let mut expense_map: HashMap<String, CategorySpendingSummary> = HashMap::new();
-    let mut total_income = 0.0_f64;
-    let mut total_expenses = 0.0_f64;
-    let mut uncategorized_income = 0.0_f64;
-    let mut uncategorized_expenses = 0.0_f64;
+    let mut income_map: HashMap<String, (CategorySpendingSummary, Decimal)> = HashMap::new();
+    let mut expense_map: HashMap<String, (CategorySpendingSummary, Decimal)> = HashMap::new();
+    let mut total_income = Decimal::ZERO;
+    let mut total_expenses = Decimal::ZERO;
+    let mut uncategorized_income = Decimal::ZERO;
+    let mut uncategorized_expenses = Decimal::ZERO;
     let mut uncategorized_count = 0_i32;
 
-    for (cat_id, cat_name, cat_icon, cat_color, tx_type, currency, amount, count) in rows {
+    let new_summary =
+        |cat_id: &str, cat_name: &str, icon: &Option<String>, color: &Option<String>| {
+            CategorySpendingSummary {
+                category_id: cat_id.to_string(),
+                category_name: cat_name.to_string(),
+                category_icon: icon.clone(),
+                category_color: color.clone(),
+                total_amount: "0".to_string(),
+                transaction_count: 0,
+                budget_goal: goals_map.get(cat_id).cloned(),
+                budget_percentage: None,
+            }
+        };
+
+    for ((cat_id, tx_type, currency), group) in groups {
+        let CategoryGroup {
+            cat_name,
+            cat_icon,
+            cat_color,
+            amount,
+            count,
+        } = group;
         let is_credit = tx_type.to_lowercase() == "credit";
-        // Convert amount to CZK for consistent totals
-        let abs_amount_czk = crate::services::currency::convert_to_czk(amount.abs(), &currency);
+        // Convert amount to CZK for consistent totals, rounded to the haléř so
+        // category amounts add up to the totals exactly
+        let abs_amount_czk = money::round(money::to_czk(amount.abs(), &currency), "CZK");
 
         // Handle uncategorized transactions
         if cat_id == "uncategorized" {
@@ -232,21 +264,13 @@ pub fn get_report(
             if is_credit {
                 total_income += abs_amount_czk;
                 let entry = income_map.entry(cat_id.clone()).or_insert_with(|| {
-                    let goal = goals_map.get(&cat_id).cloned();
-                    CategorySpendingSummary {
-                        category_id: cat_id.clone(),
-                        category_name: cat_name.clone(),
-                        category_icon: cat_icon.clone(),
-                        category_color: cat_color.clone(),
-                        total_amount: "0".to_string(),
-                        transaction_count: 0,
-                        budget_goal: goal,
-                        budget_percentage: None,
-                    }
+                    (
+                        new_summary(&cat_id, &cat_name, &cat_icon, &cat_color),
+                        Decimal::ZERO,
+                    )
                 });
-                let current: f64 = entry.total_amount.parse().unwrap_or(0.0);
-                entry.total_amount = format!("{:.2}", current + abs_amount_czk);
-                entry.transaction_count += count;
+                entry.1 += abs_amount_czk;
+                entry.0.transaction_count += count;
             }
             // Ignore debits in income category (unusual case)
             continue;
@@ -255,44 +279,32 @@ pub fn get_report(
         // All other expense categories: calculate NET amount
         // Debits add to expense, credits (refunds) reduce expense
         let entry = expense_map.entry(cat_id.clone()).or_insert_with(|| {
-            let goal = goals_map.get(&cat_id).cloned();
-            CategorySpendingSummary {
-                category_id: cat_id.clone(),
-                category_name: cat_name.clone(),
-                category_icon: cat_icon.clone(),
-                category_color: cat_color.clone(),
-                total_amount: "0".to_string(),
-                transaction_count: 0,
-                budget_goal: goal,
-                budget_percentage: None,
-            }
+            (
+                new_summary(&cat_id, &cat_name, &cat_icon, &cat_color),
+                Decimal::ZERO,
+            )
         });
-
-        let current: f64 = entry.total_amount.parse().unwrap_or(0.0);
-        // Credits (refunds) reduce the net expense amount
-        let delta = if is_credit {
-            -abs_amount_czk // Refund reduces expense
+        if is_credit {
+            entry.1 -= abs_amount_czk; // Refund reduces expense
         } else {
-            abs_amount_czk // Debit adds to expense
-        };
-        entry.total_amount = format!("{:.2}", current + delta);
-        entry.transaction_count += count;
+            entry.1 += abs_amount_czk; // Debit adds to expense
+        }
+        entry.0.transaction_count += count;
     }
 
     // Calculate total expenses from expense_map (only positive net amounts)
-    for summary in expense_map.values() {
-        let amount: f64 = summary.total_amount.parse().unwrap_or(0.0);
-        if amount > 0.0 {
-            total_expenses += amount;
+    for (_, amount) in expense_map.values() {
+        if *amount > Decimal::ZERO {
+            total_expenses += *amount;
         }
     }
     // Add uncategorized expenses to total
     total_expenses += uncategorized_expenses;
 
     // Calculate budget percentages (scale monthly budget by timeframe multiplier)
-    for summary in expense_map.values_mut() {
+    for (summary, amount) in expense_map.values_mut() {
         if let Some(ref goal) = summary.budget_goal {
-            let spent: f64 = summary.total_amount.parse::<f64>().unwrap_or(0.0).max(0.0);
+            let spent = money::to_f64(*amount).max(0.0);
             let monthly_budget: f64 = goal.amount.parse().unwrap_or(1.0);
             let scaled_budget = monthly_budget * budget_multiplier;
             if scaled_budget > 0.0 {
@@ -301,43 +313,36 @@ pub fn get_report(
         }
     }
 
-    // Convert maps to sorted vectors, filtering out categories with zero or negative net
-    let mut income_categories: Vec<CategorySpendingSummary> = income_map.into_values().collect();
-    let mut expense_categories: Vec<CategorySpendingSummary> = expense_map
-        .into_values()
-        .filter(|c| {
-            let amount: f64 = c.total_amount.parse().unwrap_or(0.0);
-            amount > 0.0 // Only show categories with positive net expense
-        })
-        .collect();
-
-    // Sort by total amount descending
-    income_categories.sort_by(|a, b| {
-        let a_val: f64 = a.total_amount.parse().unwrap_or(0.0);
-        let b_val: f64 = b.total_amount.parse().unwrap_or(0.0);
-        b_val
-            .partial_cmp(&a_val)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    expense_categories.sort_by(|a, b| {
-        let a_val: f64 = a.total_amount.parse().unwrap_or(0.0);
-        let b_val: f64 = b.total_amount.parse().unwrap_or(0.0);
-        b_val
-            .partial_cmp(&a_val)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    // Convert maps to vectors sorted by total amount descending,
+    // filtering out expense categories with zero or negative net
+    let into_sorted = |map: HashMap<String, (CategorySpendingSummary, Decimal)>, positive_only| {
+        let mut entries: Vec<(CategorySpendingSummary, Decimal)> = map
+            .into_values()
+            .filter(|(_, amount)| !positive_only || *amount > Decimal::ZERO)
+            .collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1));
+        entries
+            .into_iter()
+            .map(|(mut summary, amount)| {
+                summary.total_amount = money::format(amount, "CZK");
+                summary
+            })
+            .collect::<Vec<_>>()
+    };
+    let income_categories = into_sorted(income_map, false);
+    let expense_categories = into_sorted(expense_map, true);
 
     Ok(BudgetingReport {
         period_start: start_date,
         period_end: end_date,
         timeframe: timeframe.to_string(),
-        total_income: format!("{:.2}", total_income),
-        total_expenses: format!("{:.2}", total_expenses),
-        net_balance: format!("{:.2}", total_income - total_expenses),
+        total_income: money::format(total_income, "CZK"),
+        total_expenses: money::format(total_expenses, "CZK"),
+        net_balance: money::format(total_income - total_expenses, "CZK"),
         income_categories,
         expense_categories,
-        uncategorized_income: format!("{:.2}", uncategorized_income),
-        uncategorized_expenses: format!("{:.2}", uncategorized_expenses),
+        uncategorized_income: money::format(uncategorized_income, "CZK"),
+        uncategorized_expenses: money::format(uncategorized_expenses, "CZK"),
         uncategorized_transaction_count: uncategorized_count,
     })
 }
@@ -595,6 +600,30 @@ mod tests {
         assert_eq!(report.expense_categories[0].transaction_count, 2);
     }
 
+    #[test]
+    fn test_get_report_sums_exactly() {
+        let conn = setup_test_db();
+
+        let mut sql = String::from(
+            "INSERT INTO bank_transactions (id, bank_account_id, booking_date, amount, currency, category_id, tx_type) VALUES ",
+        );
+        let rows: Vec<String> = (0..1000)
+            .map(|i| {
+                format!("('tx{i}', 'acc1', 1704067200, '0.10', 'CZK', 'cat_groceries', 'debit')")
+            })
+            .collect();
+        sql.push_str(&rows.join(", "));
+        sql.push_str(", ('refund', 'acc1', 1704067200, '0.05', 'CZK', 'cat_groceries', 'credit')");
+        conn.execute_batch(&sql)
+            .expect("Failed to insert transactions");
+
+        let report = get_report(&conn, 0, i64::MAX, "monthly").expect("Failed to get report");
+
+        assert_eq!(report.expense_categories[0].total_amount, "99.95");
+        assert_eq!(report.total_expenses, "99.95");
+        assert_eq!(report.net_balance, "-99.95");
+    }
+
     #[test]
     fn test_validate_budget_goal() {
         // Valid goal
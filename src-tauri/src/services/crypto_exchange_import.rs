@@ -0,0 +1,1106 @@
+//! Crypto exchange import service
+//!
+//! Parses exchange exports into a common list of entries and turns them into
+//! crypto transactions:
+//! - Binance: Trade History ("Pair" column) or Transaction History ("Operation")
+//! - Coinbase: transaction report
+//! - Kraken: ledger export (trade legs grouped by refid)
+//!
+//! Trades against fiat (or a stablecoin, valued as its fiat peg) become a buy
+//! or sell. Crypto-to-crypto swaps become a sell of the spent coin plus a buy
+//! of the received coin, valued at the fiat value reported by the exchange or,
+//! when there is none, at the spent coin's average cost.
+
+use crate::error::{AppError, Result};
+use crate::services::broker_import::{cell, find_col, non_empty, parse_date, read_csv_table};
+use crate::services::crypto_investments::{
+    create_crypto_transaction_internal, get_or_create_crypto, recalculate_crypto_metrics,
+};
+use crate::services::csv_import::clean_and_parse_decimal;
+use crate::services::money::{self, Decimal};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+
+/// Supported crypto exchanges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum Exchange {
+    Binance,
+    Coinbase,
+    Kraken,
+}
+
+impl Exchange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Exchange::Binance => "binance",
+            Exchange::Coinbase => "coinbase",
+            Exchange::Kraken => "kraken",
+        }
+    }
+
+    /// Wallet name stored on imported transactions
+    pub fn wallet_name(&self) -> &'static str {
+        match self {
+            Exchange::Binance => "Binance",
+            Exchange::Coinbase => "Coinbase",
+            Exchange::Kraken => "Kraken",
+        }
+    }
+}
+
+/// Kind of a parsed exchange entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeEntryKind {
+    /// `spent` exchanged for `received`
+    Trade,
+    Deposit,
+    Withdrawal,
+    /// Staking, interest, airdrop or mining income (carries the crypto transaction type)
+    Income(&'static str),
+}
+
+/// One normalized entry from an exchange export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeEntry {
+    pub kind: ExchangeEntryKind,
+    pub date: i64,
+    /// Asset and quantity received (trade, deposit, reward)
+    pub received: Option<(String, Decimal)>,
+    /// Asset and quantity given away (trade, withdrawal)
+    pub spent: Option<(String, Decimal)>,
+    /// Fees as (asset, quantity)
+    pub fees: Vec<(String, Decimal)>,
+    /// Fiat value reported by the exchange as (amount, currency)
+    pub fiat_value: Option<(Decimal, String)>,
+    pub external_id: Option<String>,
+}
+
+impl ExchangeEntry {
+    fn new(kind: ExchangeEntryKind, date: i64) -> Self {
+        Self {
+            kind,
+            date,
+            received: None,
+            spent: None,
+            fees: Vec::new(),
+            fiat_value: None,
+            external_id: None,
+        }
+    }
+}
+
+/// Parsed export with non-fatal parsing warnings
+#[derive(Debug, Clone, Default)]
+pub struct ParsedExchangeExport {
+    pub entries: Vec<ExchangeEntry>,
+    pub warnings: Vec<String>,
+}
+
+/// Result of a crypto exchange import
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CryptoImportResult {
+    pub trades_imported: i32,
+    pub income_imported: i32,
+    pub fees_imported: i32,
+    /// Crypto deposits/withdrawals, stored as transfer_in/transfer_out
+    pub transfers_imported: i32,
+    pub duplicates_skipped: i32,
+    pub imported: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+const FIAT_CURRENCIES: &[&str] = &[
+    "USD", "EUR", "GBP", "CZK", "CHF", "PLN", "JPY", "AUD", "CAD", "HUF", "SEK", "NOK", "DKK",
+    "TRY", "BRL",
+];
+
+/// Fiat currency an asset is valued in: fiat itself, or the peg of a stablecoin
+pub fn fiat_of(asset: &str) -> Option<&'static str> {
+    let asset = asset.to_uppercase();
+    if let Some(fiat) = FIAT_CURRENCIES.iter().find(|f| **f == asset) {
+        return Some(fiat);
+    }
+    match asset.as_str() {
+        "USDT" | "USDC" | "BUSD" | "FDUSD" | "DAI" | "TUSD" | "USDP" | "PYUSD" => Some("USD"),
+        "EURC" | "EURI" | "EURT" => Some("EUR"),
+        _ => None,
+    }
+}
+
+/// Split "0.00100000BTC" into (0.001, "BTC")
+fn split_amount_asset(value: &str) -> Option<(Decimal, String)> {
+    let value = value.trim();
+    let split = value.find(|c: char| c.is_ascii_alphabetic())?;
+    let (amount, asset) = value.split_at(split);
+    Some((clean_and_parse_decimal(amount), asset.to_uppercase()))
+}
+
+/// Signed quantities per asset and the fees of one trade
+type TradeLegs = (BTreeMap<String, Decimal>, Vec<(String, Decimal)>);
+
+/// Build a trade from signed legs: positive = received, negative = spent
+fn trade_from_legs(
+    date: i64,
+    legs: &BTreeMap<String, Decimal>,
+    fees: Vec<(String, Decimal)>,
+    external_id: Option<String>,
+) -> Option<ExchangeEntry> {
+    let received = legs.iter().find(|(_, q)| **q > Decimal::ZERO)?;
+    let spent = legs.iter().find(|(_, q)| **q < Decimal::ZERO)?;
+    let mut entry = ExchangeEntry::new(ExchangeEntryKind::Trade, date);
+    entry.received = Some((received.0.clone(), *received.1));
+    entry.spent = Some((spent.0.clone(), spent.1.abs()));
+    entry.fees = fees
+        .into_iter()
+        .filter(|(_, q)| *q > Decimal::ZERO)
+        .collect();
+    entry.external_id = external_id;
+    Some(entry)
+}
+
+// ============================================================================
+// Binance
+// ============================================================================
+
+/// Parse a Binance export (Trade History or Transaction History)
+pub fn parse_binance_csv(content: &str) -> Result<ParsedExchangeExport> {
+    let (headers, rows) = read_csv_table(content)?;
+    if find_col(&headers, &["Pair"]).is_some() {
+        parse_binance_trades(&headers, &rows)
+    } else if find_col(&headers, &["Operation"]).is_some() {
+        Ok(parse_binance_transactions(&headers, &rows))
+    } else {
+        Err(AppError::Validation("Unrecognized Binance export".into()))
+    }
+}
+
+fn parse_binance_trades(headers: &[String], rows: &[Vec<String>]) -> Result<ParsedExchangeExport> {
+    let date_col = find_col(headers, &["Date(UTC)", "Date"]);
+    let side_col = find_col(headers, &["Side"]);
+    let executed_col = find_col(headers, &["Executed"]);
+    let amount_col = find_col(headers, &["Amount"]);
+    let fee_col = find_col(headers, &["Fee"]);
+
+    let mut export = ParsedExchangeExport::default();
+    for (index, row) in rows.iter().enumerate() {
+        let raw_date = cell(row, date_col);
+        let Some(date) = parse_date(raw_date) else {
+            export
+                .warnings
+                .push(format!("Row {}: invalid date '{}'", index + 2, raw_date));
+            continue;
+        };
+        let (Some((base_qty, base)), Some((quote_qty, quote))) = (
+            split_amount_asset(cell(row, executed_col)),
+            split_amount_asset(cell(row, amount_col)),
+        ) else {
+            export
+                .warnings
+                .push(format!("Row {}: cannot parse executed amount", index + 2));
+            continue;
+        };
+
+        let mut legs = BTreeMap::new();
+        if cell(row, side_col).eq_ignore_ascii_case("SELL") {
+            legs.insert(base, -base_qty);
+            legs.insert(quote, quote_qty);
+        } else {
+            legs.insert(base, base_qty);
+            legs.insert(quote, -quote_qty);
+        }
+        let fees = split_amount_asset(cell(row, fee_col))
+            .map(|(q, a)| vec![(a, q)])
+            .unwrap_or_default();
+        if let Some(entry) = trade_from_legs(date, &legs, fees, None) {
+            export.entries.push(entry);
+        }
+    }
+    Ok(export)
+}
+
+fn parse_binance_transactions(headers: &[String], rows: &[Vec<String>]) -> ParsedExchangeExport {
+    let time_col = find_col(headers, &["UTC_Time", "Time"]);
+    let op_col = find_col(headers, &["Operation"]);
+    let coin_col = find_col(headers, &["Coin"]);
+    let change_col = find_col(headers, &["Change"]);
+
+    let mut export = ParsedExchangeExport::default();
+    // Trade legs grouped by their exact timestamp: (legs, fees)
+    let mut trades: BTreeMap<String, TradeLegs> = BTreeMap::new();
+
+    for row in rows {
+        let time = cell(row, time_col).to_string();
+        let Some(date) = parse_date(&time) else {
+            continue;
+        };
+        let op = cell(row, op_col).to_lowercase();
+        let coin = cell(row, coin_col).to_uppercase();
+        let change = clean_and_parse_decimal(cell(row, change_col));
+        if coin.is_empty() || change.is_zero() {
+            continue;
+        }
+
+        if op.contains("fee") {
+            trades.entry(time).or_default().1.push((coin, change.abs()));
+        } else if op == "buy"
+            || op == "sell"
+            || op.starts_with("transaction")
+            || op.contains("convert")
+            || op.contains("small assets exchange")
+        {
+            *trades.entry(time).or_default().0.entry(coin).or_default() += change;
+        } else if op == "deposit" || op == "withdraw" {
+            let kind = if change > Decimal::ZERO {
+                ExchangeEntryKind::Deposit
+            } else {
+                ExchangeEntryKind::Withdrawal
+            };
+            let mut entry = ExchangeEntry::new(kind, date);
+            if change > Decimal::ZERO {
+                entry.received = Some((coin, change));
+            } else {
+                entry.spent = Some((coin, change.abs()));
+            }
+            export.entries.push(entry);
+        } else if change > Decimal::ZERO {
+            let income_type = if op.contains("staking") {
+                "staking"
+            } else if op.contains("interest") || op.contains("earn") || op.contains("reward") {
+                "interest"
+            } else if op.contains("distribution") || op.contains("airdrop") {
+                "airdrop"
+            } else if op.contains("mining") || op.contains("pool") {
+                "mining"
+            } else {
+                continue;
+            };
+            let mut entry = ExchangeEntry::new(ExchangeEntryKind::Income(income_type), date);
+            entry.received = Some((coin, change));
+            export.entries.push(entry);
+        }
+    }
+
+    for (time, (legs, fees)) in trades {
+        let date = parse_date(&time).unwrap_or(0);
+        match trade_from_legs(date, &legs, fees, None) {
+            Some(entry) => export.entries.push(entry),
+            None => export
+                .warnings
+                .push(format!("{}: incomplete trade legs were skipped", time)),
+        }
+    }
+    export
+}
+
+// ============================================================================
+// Coinbase
+// ============================================================================
+
+/// Parse a Coinbase transaction report (preamble rows before the header are skipped)
+pub fn parse_coinbase_csv(content: &str) -> Result<ParsedExchangeExport> {
+    let (first, mut rows) = read_csv_table(content)?;
+    rows.insert(0, first);
+    let header_idx = rows
+        .iter()
+        .position(|r| find_col(r, &["Transaction Type"]).is_some())
+        .ok_or_else(|| AppError::Validation("Unrecognized Coinbase report".into()))?;
+    let headers = &rows[header_idx];
+
+    let id_col = find_col(headers, &["ID"]);
+    let time_col = find_col(headers, &["Timestamp"]);
+    let type_col = find_col(headers, &["Transaction Type"]);
+    let asset_col = find_col(headers, &["Asset"]);
+    let qty_col = find_col(headers, &["Quantity Transacted"]);
+    let currency_col = find_col(headers, &["Spot Price Currency", "Price Currency"]);
+    let price_col = find_col(
+        headers,
+        &["Spot Price at Transaction", "Price at Transaction"],
+    );
+    let subtotal_col = find_col(headers, &["Subtotal"]);
+    let fee_col = find_col(headers, &["Fees and/or Spread", "Fees"]);
+    let notes_col = find_col(headers, &["Notes"]);
+
+    let convert_re =
+        regex::Regex::new(r"(?i)converted\s+([\d.,]+)\s+(\w+)\s+to\s+([\d.,]+)\s+(\w+)")
+            .expect("valid regex");
+
+    let mut export = ParsedExchangeExport::default();
+    for row in &rows[header_idx + 1..] {
+        let tx_type = cell(row, type_col).to_lowercase();
+        let Some(date) = parse_date(cell(row, time_col)) else {
+            continue;
+        };
+        let asset = cell(row, asset_col).to_uppercase();
+        let quantity = clean_and_parse_decimal(cell(row, qty_col)).abs();
+        let currency = non_empty(cell(row, currency_col))
+            .map(|c| c.to_uppercase())
+            .unwrap_or_else(|| "USD".into());
+        let spot = clean_and_parse_decimal(cell(row, price_col));
+        let subtotal = match clean_and_parse_decimal(cell(row, subtotal_col)).abs() {
+            s if s > Decimal::ZERO => s,
+            _ => quantity * spot,
+        };
+        let fee = clean_and_parse_decimal(cell(row, fee_col)).abs();
+        let external_id = non_empty(cell(row, id_col));
+
+        let mut entry = if tx_type.ends_with("buy") {
+            let mut e = ExchangeEntry::new(ExchangeEntryKind::Trade, date);
+            e.received = Some((asset, quantity));
+            e.spent = Some((currency.clone(), subtotal));
+            e
+        } else if tx_type.ends_with("sell") {
+            let mut e = ExchangeEntry::new(ExchangeEntryKind::Trade, date);
+            e.spent = Some((asset, quantity));
+            e.received = Some((currency.clone(), subtotal));
+            e
+        } else if tx_type == "convert" {
+            let notes = cell(row, notes_col);
+            let Some(caps) = convert_re.captures(notes) else {
+                export
+                    .warnings
+                    .push(format!("Cannot parse convert note '{}'", notes));
+                continue;
+            };
+            let mut e = ExchangeEntry::new(ExchangeEntryKind::Trade, date);
+            e.spent = Some((caps[2].to_uppercase(), clean_and_parse_decimal(&caps[1])));
+            e.received = Some((caps[4].to_uppercase(), clean_and_parse_decimal(&caps[3])));
+            e.fiat_value = Some((subtotal, currency.clone()));
+            e
+        } else if tx_type == "send" {
+            let mut e = ExchangeEntry::new(ExchangeEntryKind::Withdrawal, date);
+            e.spent = Some((asset, quantity));
+            e
+        } else if tx_type == "receive" {
+            let mut e = ExchangeEntry::new(ExchangeEntryKind::Deposit, date);
+            e.received = Some((asset, quantity));
+            e
+        } else if tx_type.contains("income")
+            || tx_type.contains("reward")
+            || tx_type.contains("earn")
+        {
+            let income_type = if tx_type.contains("staking") || tx_type.contains("inflation") {
+                "staking"
+            } else if tx_type.contains("learning") || tx_type.contains("earn") {
+                "airdrop"
+            } else {
+                "interest"
+            };
+            let mut e = ExchangeEntry::new(ExchangeEntryKind::Income(income_type), date);
+            e.received = Some((asset, quantity));
+            e.fiat_value = Some((quantity * spot, currency.clone()));
+            e
+        } else {
+            continue;
+        };
+
+        if fee > Decimal::ZERO {
+            entry.fees.push((currency, fee));
+        }
+        entry.external_id = external_id;
+        export.entries.push(entry);
+    }
+    Ok(export)
+}
+
+// ============================================================================
+// Kraken
+// ============================================================================
+
+/// Normalize Kraken asset codes (XXBT -> BTC, ZEUR -> EUR, DOT.S -> DOT)
+pub fn normalize_kraken_asset(asset: &str) -> String {
+    let asset = asset.to_uppercase();
+    let base = asset.split('.').next().unwrap_or(&asset).to_string();
+    let base = match base.as_str() {
+        "XXBT" | "XBT" => "BTC".to_string(),
+        "XXDG" | "XDG" => "DOGE".to_string(),
+        "ETH2" => "ETH".to_string(),
+        b if b.len() == 4 && (b.starts_with('X') || b.starts_with('Z')) => b[1..].to_string(),
+        b => b.to_string(),
+    };
+    base
+}
+
+/// Parse a Kraken ledger export
+pub fn parse_kraken_csv(content: &str) -> Result<ParsedExchangeExport> {
+    let (headers, rows) = read_csv_table(content)?;
+    let txid_col = find_col(&headers, &["txid"]);
+    let refid_col = find_col(&headers, &["refid"]);
+    let time_col = find_col(&headers, &["time"]);
+    let type_col = find_col(&headers, &["type"]);
+    let subtype_col = find_col(&headers, &["subtype"]);
+    let asset_col = find_col(&headers, &["asset"]);
+    let amount_col = find_col(&headers, &["amount"]);
+    let fee_col = find_col(&headers, &["fee"]);
+
+    if refid_col.is_none() || amount_col.is_none() {
+        return Err(AppError::Validation("Unrecognized Kraken ledger".into()));
+    }
+
+    let mut export = ParsedExchangeExport::default();
+    // Trade legs grouped by refid
+    let mut trades: BTreeMap<String, TradeLegs> = BTreeMap::new();
+    let mut trade_dates: HashMap<String, i64> = HashMap::new();
+
+    for row in &rows {
+        // Rows without txid are pending duplicates of a later confirmed row
+        if cell(row, txid_col).is_empty() {
+            continue;
+        }
+        let Some(date) = parse_date(cell(row, time_col)) else {
+            continue;
+        };
+        let refid = cell(row, refid_col).to_string();
+        let tx_type = cell(row, type_col).to_lowercase();
+        let subtype = cell(row, subtype_col).to_lowercase();
+        let asset = normalize_kraken_asset(cell(row, asset_col));
+        let amount = clean_and_parse_decimal(cell(row, amount_col));
+        let fee = clean_and_parse_decimal(cell(row, fee_col)).abs();
+
+        match tx_type.as_str() {
+            "trade" | "spend" | "receive" => {
+                trade_dates.entry(refid.clone()).or_insert(date);
+                let trade = trades.entry(refid).or_default();
+                *trade.0.entry(asset.clone()).or_default() += amount;
+                if fee > Decimal::ZERO {
+                    trade.1.push((asset, fee));
+                }
+            }
+            "deposit" | "withdrawal" => {
+                let mut entry = if amount > Decimal::ZERO {
+                    let mut e = ExchangeEntry::new(ExchangeEntryKind::Deposit, date);
+                    e.received = Some((asset.clone(), amount));
+                    e
+                } else {
+                    let mut e = ExchangeEntry::new(ExchangeEntryKind::Withdrawal, date);
+                    e.spent = Some((asset.clone(), amount.abs()));
+                    e
+                };
+                if fee > Decimal::ZERO {
+                    entry.fees.push((asset, fee));
+                }
+                entry.external_id = Some(refid);
+                export.entries.push(entry);
+            }
+            // Only rewards count; other subtypes move funds between spot and staking wallets
+            "staking" | "earn"
+                if amount > Decimal::ZERO && (subtype.is_empty() || subtype == "reward") =>
+            {
+                let mut entry = ExchangeEntry::new(ExchangeEntryKind::Income("staking"), date);
+                entry.received = Some((asset.clone(), amount - fee));
+                entry.external_id = Some(refid);
+                export.entries.push(entry);
+            }
+            _ => {}
+        }
+    }
+
+    for (refid, (legs, fees)) in trades {
+        let date = trade_dates.get(&refid).copied().unwrap_or(0);
+        match trade_from_legs(date, &legs, fees, Some(refid.clone())) {
+            Some(entry) => export.entries.push(entry),
+            None => export
+                .warnings
+                .push(format!("{}: incomplete trade legs were skipped", refid)),
+        }
+    }
+    Ok(export)
+}
+
+/// Parse an exchange export file
+pub fn parse_exchange_file(exchange: Exchange, bytes: &[u8]) -> Result<ParsedExchangeExport> {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    let content = String::from_utf8_lossy(bytes);
+    match exchange {
+        Exchange::Binance => parse_binance_csv(&content),
+        Exchange::Coinbase => parse_coinbase_csv(&content),
+        Exchange::Kraken => parse_kraken_csv(&content),
+    }
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// One crypto transaction to be stored
+#[derive(Debug, Clone, PartialEq)]
+struct LedgerOp {
+    tx_type: &'static str,
+    asset: String,
+    quantity: Decimal,
+    price: Decimal,
+    currency: String,
+    /// Suffix distinguishing multiple operations of one entry
+    leg: String,
+    is_fee: bool,
+}
+
+impl LedgerOp {
+    fn new(
+        tx_type: &'static str,
+        asset: &str,
+        quantity: Decimal,
+        price: Decimal,
+        currency: &str,
+    ) -> Self {
+        Self {
+            tx_type,
+            asset: asset.to_uppercase(),
+            quantity,
+            price,
+            currency: currency.to_string(),
+            leg: format!("{}:{}", tx_type, asset.to_uppercase()),
+            is_fee: false,
+        }
+    }
+
+    /// Fee paid in a coin: disposal without proceeds
+    fn fee(asset: &str, quantity: Decimal, currency: &str) -> Self {
+        Self {
+            leg: format!("fee:{}", asset.to_uppercase()),
+            is_fee: true,
+            ..Self::new("sell", asset, quantity, Decimal::ZERO, currency)
+        }
+    }
+
+    /// Deposit or withdrawal between wallets, stored without a price
+    fn transfer(tx_type: &'static str, asset: &str, quantity: Decimal) -> Self {
+        Self::new(tx_type, asset, quantity, Decimal::ZERO, "USD")
+    }
+}
+
+/// Average cost of a held coin as (price per unit, currency)
+fn average_cost(conn: &rusqlite::Connection, ticker: &str) -> Option<(Decimal, String)> {
+    conn.query_row(
+        "SELECT average_price, currency FROM crypto_investments WHERE ticker = ?1",
+        [ticker.to_uppercase()],
+        |row| {
+            let avg: String = row.get(0)?;
+            Ok((money::parse_or_zero(&avg), row.get(1)?))
+        },
+    )
+    .ok()
+}
+
+/// Turn a trade entry into buy/sell/fee operations
+fn expand_trade(conn: &rusqlite::Connection, entry: &ExchangeEntry) -> Result<Vec<LedgerOp>> {
+    let (Some((received, mut received_qty)), Some((spent, spent_qty))) =
+        (entry.received.clone(), entry.spent.clone())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let received_fiat = fiat_of(&received);
+    let spent_fiat = fiat_of(&spent);
+    if received_fiat.is_some() && spent_fiat.is_some() {
+        // Fiat/stablecoin conversion - nothing to track
+        return Ok(Vec::new());
+    }
+
+    // Value of the trade in fiat
+    let (mut value, currency) = if let Some(fiat) = spent_fiat {
+        (spent_qty, fiat.to_string())
+    } else if let Some(fiat) = received_fiat {
+        (received_qty, fiat.to_string())
+    } else if let Some((value, currency)) = entry.fiat_value.clone() {
+        (value, currency)
+    } else {
+        let (avg, currency) = average_cost(conn, &spent).ok_or_else(|| {
+            AppError::Validation(format!(
+                "{}: Cannot value swap - no existing position found",
+                spent
+            ))
+        })?;
+        (avg * spent_qty, currency)
+    };
+
+    let mut ops = Vec::new();
+    for (fee_asset, fee_qty) in &entry.fees {
+        if fiat_of(fee_asset) == Some(currency.as_str()) {
+            // Fee in the valuation currency: part of the cost / reduces proceeds
+            if received_fiat.is_some() {
+                value -= fee_qty;
+            } else {
+                value += fee_qty;
+            }
+        } else if *fee_asset == received && received_fiat.is_none() {
+            received_qty -= fee_qty;
+        } else if fiat_of(fee_asset).is_none() {
+            ops.push(LedgerOp::fee(fee_asset, *fee_qty, &currency));
+        }
+    }
+    let value = value.max(Decimal::ZERO);
+
+    if spent_fiat.is_none() && spent_qty > Decimal::ZERO {
+        ops.insert(
+            0,
+            LedgerOp::new("sell", &spent, spent_qty, value / spent_qty, &currency),
+        );
+    }
+    if received_fiat.is_none() && received_qty > Decimal::ZERO {
+        ops.insert(
+            0,
+            LedgerOp::new(
+                "buy",
+                &received,
+                received_qty,
+                value / received_qty,
+                &currency,
+            ),
+        );
+    }
+    Ok(ops)
+}
+
+fn external_id_exists(conn: &rusqlite::Connection, external_id: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM crypto_transactions WHERE external_id = ?1",
+        [external_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|c| c > 0)
+    .unwrap_or(false)
+}
+
+/// Store one operation, creating the crypto investment when needed
+fn store_op(
+    conn: &rusqlite::Connection,
+    op: &LedgerOp,
+    date: i64,
+    wallet: &str,
+    external_id: &str,
+    coins: &HashMap<String, (String, String)>,
+) -> Result<()> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM crypto_investments WHERE ticker = ?1",
+            [&op.asset],
+            |row| row.get(0),
+        )
+        .ok();
+    if op.tx_type == "sell" && existing.is_none() {
+        return Err(AppError::Validation(format!(
+            "{}: Cannot sell - no existing position found",
+            op.asset
+        )));
+    }
+
+    let (coingecko_id, name) = coins
+        .get(&op.asset)
+        .map(|(id, name)| (Some(id.as_str()), name.clone()))
+        .unwrap_or((None, op.asset.clone()));
+    let investment_id = match existing {
+        Some(id) => id,
+        None => get_or_create_crypto(conn, &op.asset, coingecko_id, &name, None, None)?,
+    };
+
+    let tx = create_crypto_transaction_internal(
+        conn,
+        &investment_id,
+        &op.asset,
+        &name,
+        op.tx_type,
+        &money::format_exact(op.quantity),
+        &money::format_price(op.price),
+        &op.currency,
+        date,
+        Some(wallet),
+    )?;
+    conn.execute(
+        "UPDATE crypto_transactions SET external_id = ?1 WHERE id = ?2",
+        rusqlite::params![external_id, tx.id],
+    )?;
+    recalculate_crypto_metrics(conn, &investment_id)
+}
+
+/// ID of an entry without one from the exchange, derived from its content
+fn fallback_entry_id(entry: &ExchangeEntry) -> String {
+    let leg = |leg: &Option<(String, Decimal)>| {
+        leg.as_ref()
+            .map(|(asset, qty)| (asset.clone(), money::format_exact(*qty)))
+    };
+    let fees: Vec<(String, String)> = entry
+        .fees
+        .iter()
+        .map(|(asset, qty)| (asset.clone(), money::format_exact(*qty)))
+        .collect();
+    format!(
+        "{}|{:?}|{:?}|{:?}",
+        entry.date,
+        leg(&entry.received),
+        leg(&entry.spent),
+        fees
+    )
+}
+
+/// Store parsed entries. `coins` maps tickers to CoinGecko (id, name) for new investments.
+///
+/// Everything is stored in one transaction, committed after the last entry;
+/// an entry with a failing leg is rolled back whole and reported in the errors.
+/// Returns the import summary and the earliest transaction date per ticker
+/// (for historical recalculation).
+pub fn import_exchange_entries(
+    conn: &rusqlite::Connection,
+    exchange: Exchange,
+    export: ParsedExchangeExport,
+    coins: &HashMap<String, (String, String)>,
+) -> Result<(CryptoImportResult, HashMap<String, i64>)> {
+    let mut result = CryptoImportResult {
+        errors: export.warnings,
+        ..Default::default()
+    };
+    let mut ticker_earliest_dates: HashMap<String, i64> = HashMap::new();
+
+    let mut tx = conn.unchecked_transaction()?;
+    let mut entries = export.entries;
+    entries.sort_by_key(|e| e.date);
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        // Stable ID per entry; repeated IDs within one export get a "#n" suffix
+        let raw = entry
+            .external_id
+            .clone()
+            .unwrap_or_else(|| fallback_entry_id(&entry));
+        let base = format!("{}:{}", exchange.as_str(), raw);
+        let count = seen.entry(base.clone()).or_insert(0);
+        let entry_id = if *count == 0 {
+            base.clone()
+        } else {
+            format!("{}#{}", base, count)
+        };
+        *count += 1;
+
+        let ops = match entry.kind {
+            ExchangeEntryKind::Trade => match expand_trade(&tx, &entry) {
+                Ok(ops) => ops,
+                Err(e) => {
+                    result.errors.push(e.to_string());
+                    continue;
+                }
+            },
+            ExchangeEntryKind::Income(income_type) => match &entry.received {
+                // Priced at the fair value when the exchange reports it, zero otherwise
+                Some((asset, qty)) if fiat_of(asset).is_none() && *qty > Decimal::ZERO => {
+                    let (price, currency) = match &entry.fiat_value {
+                        Some((value, currency)) => (value / qty, currency.as_str()),
+                        None => (Decimal::ZERO, "USD"),
+                    };
+                    vec![LedgerOp::new(income_type, asset, *qty, price, currency)]
+                }
+                _ => Vec::new(),
+            },
+            ExchangeEntryKind::Deposit | ExchangeEntryKind::Withdrawal => {
+                let mut ops: Vec<LedgerOp> = entry
+                    .fees
+                    .iter()
+                    .filter(|(a, _)| fiat_of(a).is_none())
+                    .map(|(a, q)| LedgerOp::fee(a, *q, "USD"))
+                    .collect();
+                let transfer = match (entry.kind, &entry.received, &entry.spent) {
+                    (ExchangeEntryKind::Deposit, Some((asset, qty)), _) => {
+                        Some(LedgerOp::transfer("transfer_in", asset, *qty))
+                    }
+                    (ExchangeEntryKind::Withdrawal, _, Some((asset, qty))) => {
+                        Some(LedgerOp::transfer("transfer_out", asset, *qty))
+                    }
+                    _ => None,
+                };
+                if let Some(op) = transfer.filter(|op| fiat_of(&op.asset).is_none()) {
+                    ops.insert(0, op);
+                }
+                ops
+            }
+        };
+
+        // The legs of an entry are stored under one savepoint, so a failing leg
+        // leaves none of the others behind
+        let sp = tx.savepoint()?;
+        let mut stored: Vec<&LedgerOp> = Vec::new();
+        let mut duplicates = 0;
+        let outcome: Result<()> = ops.iter().try_for_each(|op| {
+            let external_id = format!("{}:{}", entry_id, op.leg);
+            if external_id_exists(&sp, &external_id) {
+                duplicates += 1;
+                return Ok(());
+            }
+            store_op(
+                &sp,
+                op,
+                entry.date,
+                exchange.wallet_name(),
+                &external_id,
+                coins,
+            )?;
+            stored.push(op);
+            Ok(())
+        });
+        if let Err(e) = outcome {
+            // Dropping the savepoint rolls back the legs stored so far
+            drop(sp);
+            result.errors.push(e.to_string());
+            continue;
+        }
+        sp.commit()?;
+
+        result.duplicates_skipped += duplicates;
+        for op in stored {
+            if op.is_fee {
+                result.fees_imported += 1;
+            } else {
+                match entry.kind {
+                    ExchangeEntryKind::Trade => result.trades_imported += 1,
+                    ExchangeEntryKind::Income(_) => result.income_imported += 1,
+                    _ => result.transfers_imported += 1,
+                }
+            }
+            result.imported.push(format!(
+                "{} {} {} @ {} {}",
+                op.tx_type.to_uppercase(),
+                money::format_exact(op.quantity),
+                op.asset,
+                money::format_price(op.price),
+                op.currency
+            ));
+            ticker_earliest_dates
+                .entry(op.asset.clone())
+                .and_modify(|d| *d = (*d).min(entry.date))
+                .or_insert(entry.date);
+        }
+    }
+
+    tx.commit()?;
+    Ok((result, ticker_earliest_dates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::str::FromStr;
+
+    fn dec(text: &str) -> Decimal {
+        Decimal::from_str(text).unwrap()
+    }
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        conn.execute_batch(
+            r#"
+            CREATE TABLE crypto_investments (
+                id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL UNIQUE,
+                coingecko_id TEXT,
+                name TEXT NOT NULL,
+                quantity TEXT NOT NULL DEFAULT '0',
+                average_price TEXT NOT NULL DEFAULT '0',
+                currency TEXT NOT NULL DEFAULT 'CZK'
+            );
+            CREATE TABLE crypto_transactions (
+                id TEXT PRIMARY KEY,
+                investment_id TEXT NOT NULL,
+                type TEXT NOT NULL,
+                ticker TEXT NOT NULL,
+                name TEXT NOT NULL,
+                quantity TEXT NOT NULL,
+                price_per_unit TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                transaction_date INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                external_id TEXT UNIQUE,
+                wallet TEXT
+            );
+            CREATE TABLE app_config (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            "#,
+        )
+        .expect("schema");
+        conn
+    }
+
+    fn holding(conn: &Connection, ticker: &str) -> (Decimal, Decimal) {
+        conn.query_row(
+            "SELECT quantity, average_price FROM crypto_investments WHERE ticker = ?1",
+            [ticker],
+            |r| {
+                let q: String = r.get(0)?;
+                let a: String = r.get(1)?;
+                Ok((dec(&q), dec(&a)))
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_binance_trade_history_fee_in_quote() {
+        let csv = "Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-15 10:00:00,BTCUSDT,BUY,40000,0.10000000BTC,4000.00USDT,4.00USDT
+2024-02-01 10:00:00,ETHBTC,BUY,0.05,1.00000000ETH,0.05000000BTC,0.00100000ETH
+";
+        let export = parse_binance_csv(csv).unwrap();
+        assert_eq!(export.entries.len(), 2);
+        assert_eq!(export.entries[0].received, Some(("BTC".into(), dec("0.1"))));
+        assert_eq!(export.entries[0].spent, Some(("USDT".into(), dec("4000"))));
+
+        let conn = setup_test_db();
+        let (result, dates) =
+            import_exchange_entries(&conn, Exchange::Binance, export, &HashMap::new()).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        // BTC buy, then swap: BTC sell + ETH buy
+        assert_eq!(result.trades_imported, 3);
+        assert!(dates.contains_key("ETH"));
+
+        // Quote fee is part of the cost: (4000 + 4) / 0.1
+        let (btc_qty, btc_avg) = holding(&conn, "BTC");
+        assert_eq!(btc_qty, dec("0.05"));
+        assert_eq!(btc_avg, dec("40040"));
+        // Swap carries over BTC cost; ETH fee reduces received quantity exactly
+        let (eth_qty, eth_avg) = holding(&conn, "ETH");
+        assert_eq!(eth_qty, dec("0.999"));
+        assert!((eth_avg * eth_qty - dec("2002")).abs() < dec("0.000001"));
+        let stored: String = conn
+            .query_row(
+                "SELECT quantity FROM crypto_transactions WHERE ticker = 'ETH'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored, "0.999");
+    }
+
+    #[test]
+    fn test_binance_transaction_history_groups_legs() {
+        let csv = "User_ID,UTC_Time,Account,Operation,Coin,Change,Remark
+1,2024-01-15 10:00:00,Spot,Transaction Buy,BTC,0.01,
+1,2024-01-15 10:00:00,Spot,Transaction Spend,EUR,-370,
+1,2024-01-15 10:00:00,Spot,Transaction Fee,BNB,0.001,
+1,2024-01-20 00:00:00,Spot,Simple Earn Flexible Interest,BTC,0.0001,
+1,2024-01-21 00:00:00,Spot,Withdraw,BTC,-0.005,
+";
+        let export = parse_binance_csv(csv).unwrap();
+        let trade = export
+            .entries
+            .iter()
+            .find(|e| e.kind == ExchangeEntryKind::Trade)
+            .unwrap();
+        assert_eq!(trade.spent, Some(("EUR".into(), dec("370"))));
+        assert_eq!(trade.fees, vec![("BNB".to_string(), dec("0.001"))]);
+        assert!(export
+            .entries
+            .iter()
+            .any(|e| e.kind == ExchangeEntryKind::Income("interest")));
+        assert!(export
+            .entries
+            .iter()
+            .any(|e| e.kind == ExchangeEntryKind::Withdrawal));
+    }
+
+    #[test]
+    fn test_coinbase_convert_uses_spot_value() {
+        let csv = "Transactions
+User,someone@example.com,abc
+ID,Timestamp,Transaction Type,Asset,Quantity Transacted,Price Currency,Price at Transaction,Subtotal,Total (inclusive of fees and/or spread),Fees and/or Spread,Notes
+a1,2024-01-10 12:00:00 UTC,Buy,ETH,1,EUR,€2000.00,€2000.00,€2010.00,€10.00,Bought 1 ETH
+a2,2024-02-10 12:00:00 UTC,Convert,ETH,0.5,EUR,€2200.00,€1100.00,€1100.00,€0.00,Converted 0.5 ETH to 0.025 BTC
+a3,2024-02-11 12:00:00 UTC,Staking Income,ETH,0.01,EUR,€2200.00,€22.00,€22.00,€0.00,
+";
+        let export = parse_coinbase_csv(csv).unwrap();
+        assert_eq!(export.entries.len(), 3);
+
+        let conn = setup_test_db();
+        let (result, _) =
+            import_exchange_entries(&conn, Exchange::Coinbase, export, &HashMap::new()).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.trades_imported, 3);
+        assert_eq!(result.income_imported, 1);
+
+        let (btc_qty, btc_avg) = holding(&conn, "BTC");
+        assert_eq!(btc_qty, dec("0.025"));
+        assert_eq!(btc_avg, dec("44000"));
+        // Buy fee added to cost; reward is zero-cost
+        let (eth_qty, _) = holding(&conn, "ETH");
+        assert_eq!(eth_qty, dec("0.51"));
+    }
+
+    #[test]
+    fn test_kraken_ledger() {
+        let csv = r#""txid","refid","time","type","subtype","aclass","asset","amount","fee","balance"
+"L1","T1","2024-01-15 10:00:00","trade","","currency","ZEUR","-1000.0000","1.6000","0"
+"L2","T1","2024-01-15 10:00:00","trade","","currency","XXBT","0.0250000000","0","0.025"
+"","D1","2024-01-16 10:00:00","deposit","","currency","XETH","1.0","0","1"
+"L3","D1","2024-01-16 10:05:00","deposit","","currency","XETH","1.0","0","1"
+"L4","S1","2024-01-20 00:00:00","staking","","currency","DOT.S","0.5","0","0.5"
+"#;
+        let export = parse_kraken_csv(csv).unwrap();
+        assert_eq!(export.entries.len(), 3);
+
+        let conn = setup_test_db();
+        let (result, _) =
+            import_exchange_entries(&conn, Exchange::Kraken, export, &HashMap::new()).unwrap();
+        assert_eq!(result.trades_imported, 1);
+        assert_eq!(result.income_imported, 1);
+        assert_eq!(result.transfers_imported, 1);
+
+        let (_, btc_avg) = holding(&conn, "BTC");
+        assert_eq!(btc_avg, dec("40064"));
+    }
+
+    #[test]
+    fn test_reimport_skips_duplicates() {
+        let csv = "Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-15 10:00:00,BTCEUR,BUY,40000,0.01000000BTC,400.00EUR,0.00001000BTC
+";
+        let conn = setup_test_db();
+        let export = parse_binance_csv(csv).unwrap();
+        import_exchange_entries(&conn, Exchange::Binance, export, &HashMap::new()).unwrap();
+        let export = parse_binance_csv(csv).unwrap();
+        let (result, _) =
+            import_exchange_entries(&conn, Exchange::Binance, export, &HashMap::new()).unwrap();
+        assert_eq!(result.trades_imported, 0);
+        assert_eq!(result.duplicates_skipped, 1);
+    }
+
+    #[test]
+    fn test_failed_leg_rolls_back_the_whole_entry() {
+        let csv = "Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-15 10:00:00,BTCUSDT,BUY,40000,0.10000000BTC,4000.00USDT,4.00USDT
+2024-02-01 10:00:00,ETHBTC,BUY,0.05,1.00000000ETH,0.05000000BTC,0.00100000ETH
+";
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TRIGGER reject_eth BEFORE INSERT ON crypto_transactions
+             WHEN NEW.ticker = 'ETH'
+             BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+        )
+        .unwrap();
+        let export = parse_binance_csv(csv).unwrap();
+        let (result, dates) =
+            import_exchange_entries(&conn, Exchange::Binance, export, &HashMap::new()).unwrap();
+        assert_eq!(result.trades_imported, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!(!dates.contains_key("ETH"));
+
+        // Neither the BTC leg of the swap nor the ETH position survive
+        let (btc_qty, _) = holding(&conn, "BTC");
+        assert_eq!(btc_qty, dec("0.1"));
+        let eth: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM crypto_investments WHERE ticker = 'ETH'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(eth, 0);
+    }
+
+    #[test]
+    fn test_asset_normalization() {
+        assert_eq!(normalize_kraken_asset("XXBT"), "BTC");
+        assert_eq!(normalize_kraken_asset("ZEUR"), "EUR");
+        assert_eq!(normalize_kraken_asset("DOT.S"), "DOT");
+        assert_eq!(normalize_kraken_asset("SOL"), "SOL");
+        assert_eq!(fiat_of("usdt"), Some("USD"));
+        assert_eq!(fiat_of("BTC"), None);
+    }
+}
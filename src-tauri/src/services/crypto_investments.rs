@@ -5,10 +5,58 @@
 //! This is the SINGLE SOURCE OF TRUTH for crypto investment operations.
 
 use crate::error::Result;
-use crate::models::{CryptoInvestment, CryptoTransaction, InsertCryptoTransaction};
-use crate::services::currency::convert_between;
+use crate::models::{
+    CryptoIncomeCostBasis, CryptoInvestment, CryptoTransaction, CryptoWalletHolding,
+    InsertCryptoTransaction, CRYPTO_INCOME_TYPES,
+};
+use crate::services::money::{self, Decimal};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+/// app_config key of the income cost basis setting
+const INCOME_COST_BASIS_KEY: &str = "crypto_income_cost_basis";
+
+/// Get how crypto income enters the cost basis (defaults to fair value)
+pub fn get_income_cost_basis(conn: &rusqlite::Connection) -> CryptoIncomeCostBasis {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_config WHERE key = ?1",
+            [INCOME_COST_BASIS_KEY],
+            |row| row.get(0),
+        )
+        .ok();
+    match value.as_deref() {
+        Some("zero") => CryptoIncomeCostBasis::Zero,
+        _ => CryptoIncomeCostBasis::FairValue,
+    }
+}
+
+/// Save the income cost basis setting and recalculate all crypto metrics
+pub fn set_income_cost_basis(
+    conn: &rusqlite::Connection,
+    basis: CryptoIncomeCostBasis,
+) -> Result<()> {
+    let value = match basis {
+        CryptoIncomeCostBasis::FairValue => "fair_value",
+        CryptoIncomeCostBasis::Zero => "zero",
+    };
+    conn.execute(
+        "INSERT INTO app_config (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [INCOME_COST_BASIS_KEY, value],
+    )?;
+
+    let ids: Vec<String> = conn
+        .prepare("SELECT id FROM crypto_investments")?
+        .query_map([], |row| row.get(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    for id in ids {
+        recalculate_crypto_metrics(conn, &id)?;
+    }
+    Ok(())
+}
+
 /// Recalculate crypto investment metrics from transactions
 /// SINGLE SOURCE OF TRUTH for crypto metrics calculation
 /// NOTE: Average price is calculated in the investment's native currency (from first transaction)
@@ -19,7 +67,7 @@ pub fn recalculate_crypto_metrics(conn: &rusqlite::Connection, investment_id: &s
         "SELECT type, quantity, price_per_unit, currency FROM crypto_transactions WHERE investment_id = ?1 ORDER BY transaction_date ASC, created_at ASC"
     )?;
 
-    let txs: Vec<(String, f64, f64, String)> = stmt
+    let txs: Vec<(String, Decimal, Decimal, String)> = stmt
         .query_map([investment_id], |row| {
             let tx_type: String = row.get(0)?;
             let qty: String = row.get(1)?;
@@ -27,8 +75,8 @@ pub fn recalculate_crypto_metrics(conn: &rusqlite::Connection, investment_id: &s
             let currency: String = row.get(3)?;
             Ok((
                 tx_type,
-                qty.parse().unwrap_or(0.0),
-                price.parse().unwrap_or(0.0),
+                money::parse_or_zero(&qty),
+                money::parse_or_zero(&price),
                 currency.to_uppercase(),
             ))
         })?
@@ -43,23 +91,30 @@ pub fn recalculate_crypto_metrics(conn: &rusqlite::Connection, investment_id: &s
     // Native currency is the currency of the first transaction
     let native_currency = txs[0].3.clone();
 
-    let mut total_qty = 0.0f64;
-    let mut total_cost = 0.0f64;
+    let income_basis = get_income_cost_basis(conn);
+    let mut total_qty = Decimal::ZERO;
+    let mut total_cost = Decimal::ZERO;
 
     for (tx_type, qty, price, currency) in txs {
         // Convert price to native currency if different
         let price_in_native = if currency == native_currency {
             price
         } else {
-            convert_between(price, &currency, &native_currency)
+            money::convert_between(price, &currency, &native_currency)
         };
 
-        if tx_type == "buy" {
+        if CRYPTO_INCOME_TYPES.contains(&tx_type.as_str()) {
+            if income_basis == CryptoIncomeCostBasis::FairValue {
+                total_cost += qty * price_in_native;
+            }
+            total_qty += qty;
+        } else if tx_type == "buy" || tx_type == "gift" {
+            // Gifts carry the price entered (donor's cost basis, may be zero)
             total_cost += qty * price_in_native;
             total_qty += qty;
         } else if tx_type == "sell" {
             // Reduce quantity, adjust cost proportionally
-            if total_qty > 0.0 {
+            if total_qty > Decimal::ZERO {
                 let avg_cost = total_cost / total_qty;
                 total_cost -= qty * avg_cost;
             }
@@ -68,23 +123,28 @@ pub fn recalculate_crypto_metrics(conn: &rusqlite::Connection, investment_id: &s
     }
 
     // Prevent negative values
-    if total_qty < 0.0 {
-        total_qty = 0.0;
+    if total_qty < Decimal::ZERO {
+        total_qty = Decimal::ZERO;
     }
-    if total_cost < 0.0 {
-        total_cost = 0.0;
+    if total_cost < Decimal::ZERO {
+        total_cost = Decimal::ZERO;
     }
 
-    let avg_price = if total_qty > 0.0 {
+    let avg_price = if total_qty > Decimal::ZERO {
         total_cost / total_qty
     } else {
-        0.0
+        Decimal::ZERO
     };
 
     // Update both average_price (in native currency) and currency column
     conn.execute(
         "UPDATE crypto_investments SET quantity = ?1, average_price = ?2, currency = ?3 WHERE id = ?4",
-        rusqlite::params![total_qty.to_string(), avg_price.to_string(), native_currency, investment_id],
+        rusqlite::params![
+            money::format_exact(total_qty),
+            money::format_price(avg_price),
+            native_currency,
+            investment_id
+        ],
     )?;
 
     Ok(())
@@ -104,14 +164,16 @@ pub fn create_crypto_transaction_internal(
     price_per_unit: &str,
     currency: &str,
     transaction_date: i64,
+    wallet: Option<&str>,
 ) -> Result<CryptoTransaction> {
     let tx_id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().timestamp();
+    let wallet = wallet.map(str::trim).filter(|w| !w.is_empty());
 
     conn.execute(
         "INSERT INTO crypto_transactions
-         (id, investment_id, type, ticker, name, quantity, price_per_unit, currency, transaction_date, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+         (id, investment_id, type, ticker, name, quantity, price_per_unit, currency, transaction_date, wallet, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         rusqlite::params![
             tx_id,
             investment_id,
@@ -122,6 +184,7 @@ pub fn create_crypto_transaction_internal(
             price_per_unit,
             currency,
             transaction_date,
+            wallet,
             now,
         ],
     )?;
@@ -136,6 +199,7 @@ pub fn create_crypto_transaction_internal(
         price_per_unit: price_per_unit.to_string(),
         currency: currency.to_string(),
         transaction_date,
+        wallet: wallet.map(|w| w.to_string()),
         created_at: now,
     })
 }
@@ -236,6 +300,7 @@ pub fn create_crypto_with_transaction(
             &tx.price_per_unit,
             &tx.currency,
             tx.transaction_date,
+            tx.wallet.as_deref(),
         )?;
 
         // Recalculate metrics from the transaction
@@ -269,6 +334,7 @@ pub fn add_transaction_to_crypto(
         &data.price_per_unit,
         &data.currency,
         data.transaction_date,
+        data.wallet.as_deref(),
     )?;
 
     recalculate_crypto_metrics(conn, investment_id)?;
@@ -276,6 +342,47 @@ pub fn add_transaction_to_crypto(
     Ok(tx)
 }
 
+/// Get holdings per coin and wallet, optionally for one ticker
+///
+/// Acquisitions and transfer_in add to the transaction's wallet, sells and
+/// transfer_out subtract from it. Wallets with nothing left are omitted.
+pub fn get_wallet_holdings(
+    conn: &rusqlite::Connection,
+    ticker: Option<&str>,
+) -> Result<Vec<CryptoWalletHolding>> {
+    let mut stmt = conn.prepare(
+        "SELECT ticker, wallet, type, quantity FROM crypto_transactions
+         WHERE ?1 IS NULL OR ticker = ?1",
+    )?;
+    let rows: Vec<(String, Option<String>, String, String)> = stmt
+        .query_map([ticker.map(|t| t.to_uppercase())], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // BTreeMap keeps the output sorted by ticker and wallet
+    let mut holdings: BTreeMap<(String, Option<String>), Decimal> = BTreeMap::new();
+    for (ticker, wallet, tx_type, quantity) in rows {
+        let qty = money::parse_or_zero(&quantity);
+        let entry = holdings.entry((ticker, wallet)).or_default();
+        match tx_type.as_str() {
+            "sell" | "transfer_out" => *entry -= qty,
+            _ => *entry += qty,
+        }
+    }
+
+    Ok(holdings
+        .into_iter()
+        .filter(|(_, qty)| !qty.is_zero())
+        .map(|((ticker, wallet), quantity)| CryptoWalletHolding {
+            ticker,
+            wallet,
+            quantity: money::to_f64(quantity),
+        })
+        .collect())
+}
+
 /// Get value history for a specific crypto ticker
 pub fn get_value_history(
     conn: &rusqlite::Connection,
@@ -378,4 +485,117 @@ mod tests {
         let history = get_value_history(&conn, "BTC", Some(2000), Some(2500)).unwrap();
         assert_eq!(history.len(), 1); // Only 2000
     }
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE crypto_investments (
+                id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL UNIQUE,
+                coingecko_id TEXT,
+                name TEXT NOT NULL,
+                quantity TEXT NOT NULL DEFAULT '0',
+                average_price TEXT NOT NULL DEFAULT '0',
+                currency TEXT NOT NULL DEFAULT 'CZK'
+            );
+            CREATE TABLE crypto_transactions (
+                id TEXT PRIMARY KEY,
+                investment_id TEXT NOT NULL,
+                type TEXT NOT NULL,
+                ticker TEXT NOT NULL,
+                name TEXT NOT NULL,
+                quantity TEXT NOT NULL,
+                price_per_unit TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                transaction_date INTEGER NOT NULL,
+                wallet TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE app_config (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+            INSERT INTO crypto_investments (id, ticker, name) VALUES ('eth', 'ETH', 'Ethereum');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn add_tx(conn: &Connection, tx_type: &str, qty: &str, price: &str, date: i64, wallet: &str) {
+        create_crypto_transaction_internal(
+            conn,
+            "eth",
+            "ETH",
+            "Ethereum",
+            tx_type,
+            qty,
+            price,
+            "USD",
+            date,
+            Some(wallet),
+        )
+        .unwrap();
+    }
+
+    fn position(conn: &Connection) -> (f64, f64) {
+        conn.query_row(
+            "SELECT quantity, average_price FROM crypto_investments WHERE id = 'eth'",
+            [],
+            |r| {
+                let q: String = r.get(0)?;
+                let a: String = r.get(1)?;
+                Ok((q.parse().unwrap(), a.parse().unwrap()))
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_income_cost_basis() {
+        let conn = setup_test_db();
+        add_tx(&conn, "buy", "1", "2000", 1000, "Ledger");
+        add_tx(&conn, "staking", "1", "3000", 2000, "Ledger");
+        recalculate_crypto_metrics(&conn, "eth").unwrap();
+
+        // Fair value: staking reward adds 3000 to cost
+        assert_eq!(
+            get_income_cost_basis(&conn),
+            CryptoIncomeCostBasis::FairValue
+        );
+        let (qty, avg) = position(&conn);
+        assert!((qty - 2.0).abs() < 1e-9);
+        assert!((avg - 2500.0).abs() < 1e-9);
+
+        // Zero cost: reward only adds quantity
+        set_income_cost_basis(&conn, CryptoIncomeCostBasis::Zero).unwrap();
+        assert_eq!(get_income_cost_basis(&conn), CryptoIncomeCostBasis::Zero);
+        let (qty, avg) = position(&conn);
+        assert!((qty - 2.0).abs() < 1e-9);
+        assert!((avg - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_wallet_holdings_with_transfers() {
+        let conn = setup_test_db();
+        add_tx(&conn, "buy", "2", "2000", 1000, "Binance");
+        add_tx(&conn, "transfer_out", "1.5", "0", 2000, "Binance");
+        add_tx(&conn, "transfer_in", "1.5", "0", 2000, "Ledger");
+        add_tx(&conn, "airdrop", "0.1", "0", 3000, "Ledger");
+        recalculate_crypto_metrics(&conn, "eth").unwrap();
+
+        // Transfers move coins between wallets without changing the total or cost
+        let (qty, avg) = position(&conn);
+        assert!((qty - 2.1).abs() < 1e-9);
+        assert!((avg - 4000.0 / 2.1).abs() < 1e-9);
+
+        let holdings = get_wallet_holdings(&conn, Some("ETH")).unwrap();
+        assert_eq!(holdings.len(), 2);
+        let binance = holdings
+            .iter()
+            .find(|h| h.wallet.as_deref() == Some("Binance"))
+            .unwrap();
+        let ledger = holdings
+            .iter()
+            .find(|h| h.wallet.as_deref() == Some("Ledger"))
+            .unwrap();
+        assert!((binance.quantity - 0.5).abs() < 1e-9);
+        assert!((ledger.quantity - 1.6).abs() < 1e-9);
+    }
 }
@@ -6,6 +6,7 @@
 //! - Regex patterns for EN/CZ column matching
 //! - Duplicate detection
 
+use crate::services::money::{self, Decimal};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -499,6 +500,18 @@ pub fn suggest_column_mappings(headers: &[String]) -> HashMap<String, (String, f
 /// - Multiple number formats (1,234.56 vs 1.234,56 vs 1 234,56)
 /// - Currency symbols (basic removal)
 pub fn clean_and_parse_amount(value: &str) -> f64 {
+    normalize_amount(value).parse::<f64>().unwrap_or(0.0)
+}
+
+/// Clean and parse amount string as an exact decimal (same formats as
+/// `clean_and_parse_amount`), zero if empty or invalid
+pub fn clean_and_parse_decimal(value: &str) -> Decimal {
+    money::parse_or_zero(&normalize_amount(value))
+}
+
+/// Amount string without whitespace and currency symbols, with a dot as the
+/// decimal separator
+fn normalize_amount(value: &str) -> String {
     // 1. Remove all whitespace (including non-breaking spaces) and currency symbols
     // \p{White_Space} matches all unicode whitespace
     // Also remove common currency symbols if attached
@@ -530,12 +543,8 @@ pub fn clean_and_parse_amount(value: &str) -> f64 {
         char_count += 1;
     }
 
-    if cleaned.is_empty() {
-        return 0.0;
-    }
-
     // 2. Determine format based on separators
-    let normalized = if has_comma && has_dot {
+    if has_comma && has_dot {
         // Mixed separators: implicit logic based on position
         if last_comma_idx > last_dot_idx {
             // 1.234,56 -> 1234.56 (European)
@@ -551,10 +560,7 @@ pub fn clean_and_parse_amount(value: &str) -> f64 {
     } else {
         // Only dot or no separator: standard float parsing
         cleaned
-    };
-
-    // 3. Parse
-    normalized.parse::<f64>().unwrap_or(0.0)
+    }
 }
 
 #[cfg(test)]
@@ -589,6 +595,20 @@ mod tests {
         assert_eq!(clean_and_parse_amount("Kč 1 234,50"), 1234.5);
     }
 
+    #[test]
+    fn test_clean_and_parse_decimal() {
+        use std::str::FromStr;
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+        assert_eq!(
+            clean_and_parse_decimal("-300\u{00a0}000,00"),
+            dec("-300000")
+        );
+        assert_eq!(clean_and_parse_decimal("1.234,56"), dec("1234.56"));
+        assert_eq!(clean_and_parse_decimal("€0.00100000"), dec("0.001"));
+        assert_eq!(clean_and_parse_decimal("abc"), Decimal::ZERO);
+        assert_eq!(clean_and_parse_decimal(""), Decimal::ZERO);
+    }
+
     #[test]
     fn test_get_bank_presets_non_empty() {
         let presets = get_bank_presets();
@@ -1,7 +1,8 @@
 //! Currency conversion service
 //!
 //! Handles exchange rate caching and currency conversion
-//! Base currency is CZK
+//! Base currency is CZK. These are the current rates; dated rates for
+//! historical valuations live in `exchange_rate_history`.
 
 use lazy_static::lazy_static;
 use std::collections::HashMap;
@@ -22,6 +23,29 @@ lazy_static! {
     static ref EXCHANGE_RATES_FETCHED_AT: RwLock<Option<i64>> = RwLock::new(None);
 }
 
+/// Quote currencies in minor units (1/100 of the main currency) and their main currency.
+/// Codes are case-sensitive: "GBp" is pence while "GBP" is pounds.
+const MINOR_UNIT_CURRENCIES: [(&str, &str); 5] = [
+    ("GBp", "GBP"), // London pence
+    ("GBX", "GBP"),
+    ("ILA", "ILS"), // Tel Aviv agorot
+    ("ZAc", "ZAR"), // Johannesburg cents
+    ("ZAC", "ZAR"),
+];
+
+/// Convert an amount quoted in a minor unit (GBp, ILA, ZAc) to its main currency.
+/// Other currencies pass through unchanged, upper-cased.
+pub fn normalize_minor_unit(amount: f64, currency: &str) -> (f64, String) {
+    let currency = currency.trim();
+    match MINOR_UNIT_CURRENCIES
+        .iter()
+        .find(|(minor, _)| *minor == currency)
+    {
+        Some((_, major)) => (amount / 100.0, major.to_string()),
+        None => (amount, currency.to_uppercase()),
+    }
+}
+
 /// Convert an amount from a currency to CZK
 #[allow(dead_code)]
 pub fn convert_to_czk(amount: f64, currency: &str) -> f64 {
@@ -78,6 +102,14 @@ pub fn update_exchange_rates(new_rates: HashMap<String, f64>) {
     }
 }
 
+/// Remove a currency from the in-memory rates (e.g., a deleted manual rate)
+pub fn remove_exchange_rate(currency: &str) {
+    let mut rates = EXCHANGE_RATES
+        .write()
+        .expect("Exchange rates lock poisoned");
+    rates.remove(&currency.to_uppercase());
+}
+
 /// Update the fetched_at timestamp
 pub fn set_exchange_rates_fetched_at(timestamp: i64) {
     let mut fetched_at = EXCHANGE_RATES_FETCHED_AT
@@ -247,7 +279,7 @@ pub async fn fetch_ecb_rates() -> crate::error::Result<HashMap<String, f64>> {
 }
 
 /// Extract all currency codes from ECB XML response
-fn extract_all_currencies(xml: &str) -> Vec<String> {
+pub(crate) fn extract_all_currencies(xml: &str) -> Vec<String> {
     let mut currencies = Vec::new();
 
     // Find all occurrences of currency="XXX" or currency='XXX'
@@ -279,7 +311,7 @@ fn extract_all_currencies(xml: &str) -> Vec<String> {
 }
 
 /// Extract rate from ECB XML response
-fn extract_rate(xml: &str, currency: &str) -> Option<f64> {
+pub(crate) fn extract_rate(xml: &str, currency: &str) -> Option<f64> {
     // Try double quotes first: currency="USD"
     let mut pos = xml.find(&format!("currency=\"{}\"", currency));
 
@@ -343,6 +375,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_normalize_minor_unit() {
+        assert_eq!(
+            normalize_minor_unit(1234.0, "GBp"),
+            (12.34, "GBP".to_string())
+        );
+        assert_eq!(normalize_minor_unit(500.0, "ILA"), (5.0, "ILS".to_string()));
+        assert_eq!(normalize_minor_unit(12.5, "GBP"), (12.5, "GBP".to_string()));
+        assert_eq!(normalize_minor_unit(10.0, "usd"), (10.0, "USD".to_string()));
+    }
+
     #[test]
     fn test_convert_between_same_currency() {
         assert_eq!(convert_between(100.0, "EUR", "EUR"), 100.0);
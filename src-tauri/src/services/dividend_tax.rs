@@ -0,0 +1,316 @@
+//! Foreign dividend tax credit service
+//!
+//! Builds the yearly report of foreign dividends for the Czech tax return
+//! (§8 income and the foreign income appendix). Dividends are grouped by
+//! source country, converted to CZK and the tax withheld abroad is split into
+//! the part creditable under the double taxation treaty and the excess.
+
+use crate::error::Result;
+use crate::models::{DividendTaxCountry, DividendTaxReport, ReceivedDividend};
+use crate::services::exchange_rate_history::HistoricalRates;
+use crate::services::money::{self, Decimal};
+use chrono::{TimeZone, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+/// Treaty rate used for countries not listed in `treaty_rate`
+pub const DEFAULT_TREATY_RATE: f64 = 0.15;
+
+/// Maximum withholding rate on portfolio dividends allowed by the Czech
+/// double taxation treaty with the given country (ISO alpha-2 code)
+pub fn treaty_rate(country: &str) -> f64 {
+    match country.to_uppercase().as_str() {
+        "US" | "DE" | "GB" | "NL" | "CH" | "CA" | "IE" | "ES" | "IT" | "BE" | "DK" | "FI"
+        | "NO" | "JP" | "AU" | "SK" => 0.15,
+        "FR" | "AT" | "PL" | "SE" | "LU" => 0.10,
+        _ => DEFAULT_TREATY_RATE,
+    }
+}
+
+/// Get unix timestamp range [start, end) covering a calendar year (UTC)
+pub fn year_bounds(year: i32) -> (i64, i64) {
+    let start = Utc
+        .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+        .single()
+        .map(|d| d.timestamp())
+        .unwrap_or(0);
+    let end = Utc
+        .with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0)
+        .single()
+        .map(|d| d.timestamp())
+        .unwrap_or(i64::MAX);
+    (start, end)
+}
+
+/// Get all received dividends paid within a calendar year
+pub fn get_dividends_for_year(
+    conn: &rusqlite::Connection,
+    year: i32,
+) -> Result<Vec<ReceivedDividend>> {
+    let (start, end) = year_bounds(year);
+    let mut stmt = conn.prepare(
+        "SELECT id, ticker, payment_date, gross_amount, withholding_tax, currency, source_country, created_at
+         FROM stock_dividends
+         WHERE payment_date >= ?1 AND payment_date < ?2
+         ORDER BY payment_date",
+    )?;
+
+    let dividends = stmt
+        .query_map([start, end], |row| {
+            Ok(ReceivedDividend {
+                id: row.get(0)?,
+                ticker: row.get(1)?,
+                payment_date: row.get(2)?,
+                gross_amount: row.get(3)?,
+                withholding_tax: row.get(4)?,
+                currency: row.get(5)?,
+                source_country: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(dividends)
+}
+
+/// Build the foreign dividend tax report for a year
+///
+/// `annual_rates` are optional CZK-per-unit rates (e.g., the uniform annual
+/// exchange rate published for the tax year). Currencies without an entry
+/// use `daily_rates` at the payment date, then the current exchange rates.
+/// Dividends from CZ are domestic (taxed at source as final tax) and are excluded.
+pub fn build_dividend_tax_report(
+    conn: &rusqlite::Connection,
+    year: i32,
+    annual_rates: Option<&HashMap<String, f64>>,
+    daily_rates: Option<&HistoricalRates>,
+) -> Result<DividendTaxReport> {
+    let dividends = get_dividends_for_year(conn, year)?;
+
+    let to_czk = |amount: Decimal, currency: &str, day: i64| -> Decimal {
+        let currency = currency.to_uppercase();
+        match (annual_rates.and_then(|r| r.get(&currency)), daily_rates) {
+            (Some(rate), _) => amount * money::from_f64(*rate),
+            (None, Some(fx)) => fx.to_czk_decimal(amount, &currency, day),
+            (None, None) => money::to_czk(amount, &currency),
+        }
+    };
+
+    // BTreeMap keeps countries sorted for stable output
+    let mut by_country: BTreeMap<String, CountrySums> = BTreeMap::new();
+
+    for dividend in dividends {
+        let country = dividend.source_country.to_uppercase();
+        if country == "CZ" {
+            continue;
+        }
+
+        let gross = money::parse_or_zero(&dividend.gross_amount);
+        let withheld = money::parse_or_zero(&dividend.withholding_tax);
+        let gross_czk = to_czk(gross, &dividend.currency, dividend.payment_date);
+        let withheld_czk = to_czk(withheld, &dividend.currency, dividend.payment_date);
+
+        let rate = treaty_rate(&country);
+        let creditable_czk = withheld_czk.min(gross_czk * money::from_f64(rate));
+
+        let entry = by_country.entry(country).or_insert_with(|| CountrySums {
+            treaty_rate: rate,
+            ..Default::default()
+        });
+        entry.dividend_count += 1;
+        entry.gross += gross_czk;
+        entry.withheld += withheld_czk;
+        entry.creditable += creditable_czk;
+    }
+
+    // Sums stay unrounded until the report is built
+    let czk = |amount: Decimal| money::to_f64(money::round(amount, "CZK"));
+    let total = |field: fn(&CountrySums) -> Decimal| czk(by_country.values().map(field).sum());
+
+    Ok(DividendTaxReport {
+        year,
+        total_gross_czk: total(|c| c.gross),
+        total_withheld_czk: total(|c| c.withheld),
+        total_creditable_czk: total(|c| c.creditable),
+        total_non_creditable_czk: total(|c| c.withheld - c.creditable),
+        countries: by_country
+            .iter()
+            .map(|(country, sums)| DividendTaxCountry {
+                country: country.clone(),
+                treaty_rate: sums.treaty_rate,
+                dividend_count: sums.dividend_count,
+                gross_czk: czk(sums.gross),
+                withheld_czk: czk(sums.withheld),
+                creditable_czk: czk(sums.creditable),
+                non_creditable_czk: czk(sums.withheld - sums.creditable),
+            })
+            .collect(),
+    })
+}
+
+/// Per-country CZK sums of one report
+#[derive(Default)]
+struct CountrySums {
+    treaty_rate: f64,
+    dividend_count: i32,
+    gross: Decimal,
+    withheld: Decimal,
+    creditable: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().expect("in-memory db");
+        conn.execute_batch(
+            r#"
+            CREATE TABLE stock_dividends (
+                id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL,
+                payment_date INTEGER NOT NULL,
+                gross_amount TEXT NOT NULL,
+                withholding_tax TEXT NOT NULL DEFAULT '0',
+                currency TEXT NOT NULL,
+                source_country TEXT NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT 0
+            );
+            "#,
+        )
+        .expect("schema");
+        conn
+    }
+
+    fn insert(
+        conn: &Connection,
+        id: &str,
+        date: i64,
+        gross: &str,
+        tax: &str,
+        cur: &str,
+        country: &str,
+    ) {
+        conn.execute(
+            "INSERT INTO stock_dividends (id, ticker, payment_date, gross_amount, withholding_tax, currency, source_country)
+             VALUES (?1, 'T', ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![id, date, gross, tax, cur, country],
+        )
+        .unwrap();
+    }
+
+    // 2024-06-15 and 2023-06-15 (UTC)
+    const MID_2024: i64 = 1_718_409_600;
+    const MID_2023: i64 = 1_686_787_200;
+
+    fn rates() -> HashMap<String, f64> {
+        [("USD".to_string(), 23.0), ("EUR".to_string(), 25.0)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_report_filters_by_year() {
+        let conn = setup_test_db();
+        insert(&conn, "a", MID_2024, "100", "15", "USD", "US");
+        insert(&conn, "b", MID_2023, "100", "15", "USD", "US");
+
+        let report = build_dividend_tax_report(&conn, 2024, Some(&rates()), None).unwrap();
+        assert_eq!(report.countries.len(), 1);
+        assert_eq!(report.countries[0].dividend_count, 1);
+        assert!((report.total_gross_czk - 2300.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_withholding_above_treaty_rate_is_not_creditable() {
+        let conn = setup_test_db();
+        // US W-8BEN missing: 30% withheld, treaty allows 15%
+        insert(&conn, "a", MID_2024, "100", "30", "USD", "US");
+
+        let report = build_dividend_tax_report(&conn, 2024, Some(&rates()), None).unwrap();
+        let us = &report.countries[0];
+        assert!((us.withheld_czk - 690.0).abs() < 1e-9);
+        assert!((us.creditable_czk - 345.0).abs() < 1e-9);
+        assert!((us.non_creditable_czk - 345.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_withholding_below_treaty_rate_fully_creditable() {
+        let conn = setup_test_db();
+        // France: 10% treaty rate, 5% withheld
+        insert(&conn, "a", MID_2024, "200", "10", "EUR", "fr");
+
+        let report = build_dividend_tax_report(&conn, 2024, Some(&rates()), None).unwrap();
+        let fr = &report.countries[0];
+        assert_eq!(fr.country, "FR");
+        assert!((fr.treaty_rate - 0.10).abs() < 1e-9);
+        assert!((fr.creditable_czk - 250.0).abs() < 1e-9);
+        assert_eq!(fr.non_creditable_czk, 0.0);
+    }
+
+    #[test]
+    fn test_groups_by_country_and_skips_domestic() {
+        let conn = setup_test_db();
+        insert(&conn, "a", MID_2024, "100", "15", "USD", "US");
+        insert(&conn, "b", MID_2024 + 86400, "50", "7.5", "USD", "US");
+        insert(&conn, "c", MID_2024, "100", "26.375", "EUR", "DE");
+        insert(&conn, "d", MID_2024, "1000", "150", "CZK", "CZ");
+
+        let report = build_dividend_tax_report(&conn, 2024, Some(&rates()), None).unwrap();
+        let countries: Vec<&str> = report
+            .countries
+            .iter()
+            .map(|c| c.country.as_str())
+            .collect();
+        assert_eq!(countries, vec!["DE", "US"]);
+        assert_eq!(report.countries[1].dividend_count, 2);
+        assert!((report.total_gross_czk - (150.0 * 23.0 + 100.0 * 25.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_daily_rates_convert_each_dividend_on_its_payment_day() {
+        use crate::models::FxSource;
+        use crate::services::exchange_rate_history::save_history;
+
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TABLE exchange_rate_history (
+                currency TEXT NOT NULL,
+                rate_date INTEGER NOT NULL,
+                rate REAL NOT NULL,
+                source TEXT NOT NULL,
+                PRIMARY KEY (currency, rate_date, source)
+            );",
+        )
+        .unwrap();
+        let next_day = MID_2024 + 86400;
+        save_history(
+            &conn,
+            "cnb",
+            &[
+                (MID_2024, "USD".to_string(), 23.1),
+                (next_day, "USD".to_string(), 23.3),
+            ],
+        )
+        .unwrap();
+        insert(&conn, "a", MID_2024, "0.1", "0.015", "USD", "US");
+        insert(&conn, "b", next_day, "0.2", "0.03", "USD", "US");
+
+        let (start, end) = year_bounds(2024);
+        let fx = HistoricalRates::load(&conn, start, end, FxSource::CnbDaily).unwrap();
+        let report = build_dividend_tax_report(&conn, 2024, None, Some(&fx)).unwrap();
+        // 0.1 * 23.1 + 0.2 * 23.3 = 6.97, rounded only once
+        assert_eq!(report.total_gross_czk, 6.97);
+        assert_eq!(report.countries[0].gross_czk, 6.97);
+        // 0.015 * 23.1 + 0.03 * 23.3 = 1.0455
+        assert_eq!(report.total_withheld_czk, 1.05);
+        assert_eq!(report.total_non_creditable_czk, 0.0);
+    }
+
+    #[test]
+    fn test_unknown_country_uses_default_rate() {
+        assert_eq!(treaty_rate("ZZ"), DEFAULT_TREATY_RATE);
+        assert_eq!(treaty_rate("us"), 0.15);
+    }
+}
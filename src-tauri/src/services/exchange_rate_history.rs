@@ -0,0 +1,531 @@
+//! Dated exchange rate history
+//!
+//! Daily rates from the ECB reference rates and ČNB fixings, stored in
+//! `exchange_rate_history` as CZK per unit (same convention as `currency`).
+//! Historical valuations convert at the rate of the valuation day; days without
+//! a fixing (weekends, holidays) use the last earlier rate, and days without a
+//! fixing within `COVERAGE_TOLERANCE_DAYS` fall back to the current rate.
+
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::FxSource;
+use crate::services::currency::{
+    convert_from_czk, convert_to_czk, extract_all_currencies, extract_rate,
+};
+use crate::services::exchange_rates;
+use crate::services::money::{self, Decimal};
+use chrono::Datelike;
+use std::collections::{BTreeMap, HashMap};
+
+const DAY: i64 = 86400;
+
+/// First day of the ECB reference rates (1999-01-04)
+const ECB_FIRST_DAY: i64 = 915_408_000;
+
+/// Daily rate sources, see `FxSource::history_source`
+const SOURCES: [&str; 2] = ["ecb", "cnb"];
+
+/// Stored days may be this far apart or trail the requested range by this much
+/// (weekends, holidays, today's fixing not yet published) and still count as
+/// covered; fixings further away are not carried to a day
+const COVERAGE_TOLERANCE_DAYS: i64 = 5;
+
+/// One daily rate: (day start timestamp, currency, CZK per unit)
+pub type DailyRate = (i64, String, f64);
+
+/// Exchange rates by day, loaded for a valuation range
+#[derive(Debug, Default)]
+pub struct HistoricalRates {
+    rates: HashMap<String, BTreeMap<i64, f64>>,
+}
+
+impl HistoricalRates {
+    /// Load stored rates for a range, preferring one source when several have a
+    /// rate for the same day. Rates from a few days before `start` are included
+    /// so a range starting on a weekend has a rate.
+    pub fn load(
+        conn: &rusqlite::Connection,
+        start: i64,
+        end: i64,
+        preferred: FxSource,
+    ) -> Result<Self> {
+        Self::query(conn, start, end, preferred, None)
+    }
+
+    /// Load all stored rates of one currency
+    pub fn load_currency(
+        conn: &rusqlite::Connection,
+        currency: &str,
+        preferred: FxSource,
+    ) -> Result<Self> {
+        Self::query(conn, i64::MIN / 2, i64::MAX / 2, preferred, Some(currency))
+    }
+
+    fn query(
+        conn: &rusqlite::Connection,
+        start: i64,
+        end: i64,
+        preferred: FxSource,
+        currency: Option<&str>,
+    ) -> Result<Self> {
+        let mut stmt = conn.prepare(
+            "SELECT currency, rate_date, rate, source FROM exchange_rate_history
+             WHERE rate_date >= ?1 AND rate_date <= ?2 AND (?3 IS NULL OR currency = ?3)",
+        )?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params![
+                    start - COVERAGE_TOLERANCE_DAYS * DAY * 2,
+                    end + DAY,
+                    currency.map(str::to_uppercase)
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, f64>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )?
+            .filter_map(|r| r.ok());
+
+        // Keep the most preferred source per currency and day
+        let mut ranked: HashMap<(String, i64), (usize, f64)> = HashMap::new();
+        for (currency, day, rate, source) in rows {
+            let rank = source_order(preferred)
+                .iter()
+                .position(|s| *s == source)
+                .unwrap_or(SOURCES.len());
+            let entry = ranked.entry((currency, day)).or_insert((rank, rate));
+            if rank < entry.0 {
+                *entry = (rank, rate);
+            }
+        }
+
+        let mut rates: HashMap<String, BTreeMap<i64, f64>> = HashMap::new();
+        for ((currency, day), (_, rate)) in ranked {
+            rates.entry(currency).or_default().insert(day, rate);
+        }
+        Ok(Self { rates })
+    }
+
+    /// CZK per unit of a currency on a day: the last fixing up to the day, else
+    /// the first one after it, if within `COVERAGE_TOLERANCE_DAYS` of the day
+    pub fn rate(&self, currency: &str, day: i64) -> Option<f64> {
+        let currency = currency.to_uppercase();
+        if currency == "CZK" {
+            return Some(1.0);
+        }
+        let days = self.rates.get(&currency)?;
+        // Fixings are stored at day start, `day` may be any time of the day
+        let max_distance = (COVERAGE_TOLERANCE_DAYS + 1) * DAY;
+        days.range(..=day)
+            .next_back()
+            .filter(|(fixed, _)| day - **fixed < max_distance)
+            .or_else(|| {
+                days.range(day..)
+                    .next()
+                    .filter(|(fixed, _)| **fixed - day < max_distance)
+            })
+            .map(|(_, &rate)| rate)
+    }
+
+    /// Convert an amount to CZK at the rate of a day (current rate if unknown)
+    pub fn to_czk(&self, amount: f64, currency: &str, day: i64) -> f64 {
+        match self.rate(currency, day) {
+            Some(rate) => amount * rate,
+            None => convert_to_czk(amount, currency),
+        }
+    }
+
+    /// Convert a decimal amount to CZK at the rate of a day (current rate if unknown, unrounded)
+    pub fn to_czk_decimal(&self, amount: Decimal, currency: &str, day: i64) -> Decimal {
+        match self.rate(currency, day) {
+            Some(rate) => amount * money::from_f64(rate),
+            None => money::to_czk(amount, currency),
+        }
+    }
+
+    /// Convert a decimal CZK amount to a currency at the rate of a day (current rate if unknown, unrounded)
+    pub fn from_czk_decimal(&self, amount: Decimal, currency: &str, day: i64) -> Decimal {
+        match self.rate(currency, day) {
+            Some(rate) if rate > 0.0 => amount / money::from_f64(rate),
+            _ => money::convert_between(amount, "CZK", currency),
+        }
+    }
+
+    /// Convert a decimal amount between currencies at the rates of a day (unrounded)
+    pub fn convert_decimal(&self, amount: Decimal, from: &str, to: &str, day: i64) -> Decimal {
+        if from.eq_ignore_ascii_case(to) {
+            return amount;
+        }
+        self.from_czk_decimal(self.to_czk_decimal(amount, from, day), to, day)
+    }
+
+    /// Convert a CZK amount to a currency at the rate of a day (current rate if unknown)
+    pub fn from_czk(&self, amount: f64, currency: &str, day: i64) -> f64 {
+        match self.rate(currency, day) {
+            Some(rate) if rate > 0.0 => amount / rate,
+            _ => convert_from_czk(amount, currency),
+        }
+    }
+}
+
+/// Daily rate sources with the preferred one first
+fn source_order(preferred: FxSource) -> [&'static str; 2] {
+    let first = preferred.history_source();
+    let mut order = SOURCES;
+    order.sort_by_key(|s| *s != first);
+    order
+}
+
+/// Store daily rates from a source, replacing rates already stored for the same days
+pub fn save_history(conn: &rusqlite::Connection, source: &str, rates: &[DailyRate]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    let mut stmt = tx.prepare(
+        "INSERT INTO exchange_rate_history (currency, rate_date, rate, source)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(currency, rate_date, source) DO UPDATE SET rate = excluded.rate",
+    )?;
+    for (day, currency, rate) in rates {
+        stmt.execute(rusqlite::params![currency, day, rate, source])?;
+    }
+    drop(stmt);
+    tx.commit()?;
+    Ok(())
+}
+
+/// Whether stored rates span the range with no more than
+/// `COVERAGE_TOLERANCE_DAYS` between stored days
+fn is_covered(conn: &rusqlite::Connection, start: i64, end: i64) -> bool {
+    let tolerance = COVERAGE_TOLERANCE_DAYS * DAY;
+    let start = start.max(ECB_FIRST_DAY);
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT DISTINCT rate_date FROM exchange_rate_history
+         WHERE rate_date >= ?1 AND rate_date <= ?2 ORDER BY rate_date",
+    ) else {
+        return false;
+    };
+    let days: Vec<i64> = match stmt.query_map([start - tolerance, end], |row| row.get(0)) {
+        Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
+        Err(_) => return false,
+    };
+    if days.is_empty() {
+        return false;
+    }
+
+    // The range bounds count as stored days, so gaps at either end are found too
+    let mut previous = start;
+    for day in days.into_iter().chain(std::iter::once(end)) {
+        if day - previous > tolerance {
+            return false;
+        }
+        previous = previous.max(day);
+    }
+    true
+}
+
+/// Download and store missing rates for a range.
+/// Tries the preferred source, then the others, until the range is covered;
+/// failures are logged.
+pub async fn ensure_history(
+    db: &Database,
+    start: i64,
+    end: i64,
+    preferred: FxSource,
+) -> Result<()> {
+    if db.with_conn(|conn| Ok(is_covered(conn, start, end)))? {
+        return Ok(());
+    }
+
+    for source in source_order(preferred) {
+        let fetched = match source {
+            "ecb" => fetch_ecb_history(start).await,
+            _ => fetch_cnb_history(start, end).await,
+        };
+        match fetched {
+            Ok(rates) => {
+                log::info!(
+                    "[FX HISTORY] Fetched {} daily rates from {}",
+                    rates.len(),
+                    source
+                );
+                db.with_conn(|conn| save_history(conn, source, &rates))?;
+                if db.with_conn(|conn| Ok(is_covered(conn, start, end)))? {
+                    return Ok(());
+                }
+            }
+            Err(e) => log::warn!("[FX HISTORY] {} history failed: {}", source, e),
+        }
+    }
+
+    log::warn!("[FX HISTORY] Rates incomplete for range, missing days use current rates");
+    Ok(())
+}
+
+/// Make sure rates for a range are stored, then load them from the valuation source
+pub async fn historical_rates(db: &Database, start: i64, end: i64) -> Result<HistoricalRates> {
+    let source = db.with_conn(exchange_rates::get_settings)?.valuation_source;
+    historical_rates_from(db, start, end, source).await
+}
+
+/// Make sure rates for a range are stored, then load them from a given source
+pub async fn historical_rates_from(
+    db: &Database,
+    start: i64,
+    end: i64,
+    source: FxSource,
+) -> Result<HistoricalRates> {
+    ensure_history(db, start, end, source).await?;
+    db.with_conn(|conn| HistoricalRates::load(conn, start, end, source))
+}
+
+/// Fetch ECB reference rates since a day, converted to CZK per unit.
+/// The 90-day file is used for recent ranges, the full history otherwise.
+pub async fn fetch_ecb_history(since: i64) -> Result<Vec<DailyRate>> {
+    let now = chrono::Utc::now().timestamp();
+    let url = if now - since < 85 * DAY {
+        "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-hist-90d.xml"
+    } else {
+        "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-hist.xml"
+    };
+
+    let response = reqwest::get(url).await?;
+    if !response.status().is_success() {
+        return Err(AppError::ExternalApi(format!(
+            "ECB history returned {}",
+            response.status()
+        )));
+    }
+    let body = response.text().await?;
+
+    Ok(parse_ecb_history(&body)
+        .into_iter()
+        .filter(|(day, _, _)| *day >= since - COVERAGE_TOLERANCE_DAYS * DAY * 2)
+        .collect())
+}
+
+/// Fetch ČNB daily fixings for every year of a range
+pub async fn fetch_cnb_history(start: i64, end: i64) -> Result<Vec<DailyRate>> {
+    let year = |ts: i64| {
+        chrono::DateTime::from_timestamp(ts, 0)
+            .map(|d| d.year())
+            .unwrap_or(1991)
+    };
+
+    let mut rates = Vec::new();
+    for y in year(start - COVERAGE_TOLERANCE_DAYS * DAY * 2)..=year(end) {
+        rates.extend(fetch_cnb_year(y).await?);
+    }
+    Ok(rates)
+}
+
+/// Fetch the ČNB daily fixings of one year
+pub async fn fetch_cnb_year(year: i32) -> Result<Vec<DailyRate>> {
+    let url = format!(
+        "https://www.cnb.cz/en/financial-markets/foreign-exchange-market/central-bank-exchange-rate-fixing/central-bank-exchange-rate-fixing/year.txt?year={}",
+        year
+    );
+    let response = reqwest::get(&url).await?;
+    if !response.status().is_success() {
+        return Err(AppError::ExternalApi(format!(
+            "ČNB fixings for {} returned {}",
+            year,
+            response.status()
+        )));
+    }
+    Ok(parse_cnb_year(&response.text().await?))
+}
+
+/// Parse the ECB history XML (`<Cube time="YYYY-MM-DD">` blocks of
+/// `<Cube currency="USD" rate="1.08"/>`, per EUR) into CZK per unit.
+/// Days without a CZK rate are skipped.
+fn parse_ecb_history(xml: &str) -> Vec<DailyRate> {
+    let mut rates = Vec::new();
+
+    for block in xml.split("time=").skip(1) {
+        let Some(day) = block
+            .get(1..11)
+            .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc().timestamp())
+        else {
+            continue;
+        };
+        let Some(czk_per_eur) = extract_rate(block, "CZK") else {
+            continue;
+        };
+
+        rates.push((day, "EUR".to_string(), czk_per_eur));
+        for currency in extract_all_currencies(block) {
+            if currency == "CZK" {
+                continue;
+            }
+            if let Some(per_eur) = extract_rate(block, &currency).filter(|r| *r > 0.0) {
+                rates.push((day, currency, czk_per_eur / per_eur));
+            }
+        }
+    }
+
+    rates
+}
+
+/// Parse a ČNB yearly fixing file: "Date|1 AUD|100 JPY|..." header rows followed by
+/// "02.01.2024|15.124|15.870|..." rows in CZK per amount
+pub(crate) fn parse_cnb_year(text: &str) -> Vec<DailyRate> {
+    let mut rates = Vec::new();
+    // (currency, amount) per column; the header repeats when the currency list changes
+    let mut columns: Vec<Option<(String, f64)>> = Vec::new();
+
+    for line in text.lines() {
+        let mut fields = line.split('|');
+        let Some(first) = fields.next() else {
+            continue;
+        };
+
+        if first.trim().eq_ignore_ascii_case("date") {
+            columns = fields
+                .map(|h| {
+                    let (amount, currency) = h.trim().split_once(' ')?;
+                    Some((currency.trim().to_uppercase(), amount.parse().ok()?))
+                })
+                .collect();
+            continue;
+        }
+
+        let Some(day) = chrono::NaiveDate::parse_from_str(first.trim(), "%d.%m.%Y")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc().timestamp())
+        else {
+            continue;
+        };
+
+        for (column, value) in columns.iter().zip(fields) {
+            let Some((currency, amount)) = column else {
+                continue;
+            };
+            if let Ok(rate) = value.trim().replace(',', ".").parse::<f64>() {
+                if rate > 0.0 && *amount > 0.0 {
+                    rates.push((day, currency.clone(), rate / amount));
+                }
+            }
+        }
+    }
+
+    rates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE exchange_rate_history (
+                currency TEXT NOT NULL,
+                rate_date INTEGER NOT NULL,
+                rate REAL NOT NULL,
+                source TEXT NOT NULL,
+                PRIMARY KEY (currency, rate_date, source)
+            );",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_ecb_history() {
+        let xml = r#"<Cube>
+            <Cube time="2024-05-10">
+                <Cube currency="USD" rate="1.0772"/>
+                <Cube currency="CZK" rate="24.900"/>
+            </Cube>
+            <Cube time="2024-05-09">
+                <Cube currency="USD" rate="1.0780"/>
+                <Cube currency="CZK" rate="25.000"/>
+            </Cube>
+        </Cube>"#;
+        let rates = parse_ecb_history(xml);
+
+        assert_eq!(rates.len(), 4);
+        assert_eq!(rates[0], (1_715_299_200, "EUR".to_string(), 24.9));
+        assert_eq!(rates[1].1, "USD");
+        assert!((rates[1].2 - 24.9 / 1.0772).abs() < 1e-9);
+        assert_eq!(rates[2].0, 1_715_299_200 - DAY);
+    }
+
+    #[test]
+    fn test_parse_cnb_year() {
+        let text = "Date|1 EUR|100 JPY\n02.01.2024|24.725|15,870\n03.01.2024|24.620|15.650\n";
+        let rates = parse_cnb_year(text);
+
+        assert_eq!(rates.len(), 4);
+        assert_eq!(rates[0], (1_704_153_600, "EUR".to_string(), 24.725));
+        assert!((rates[1].2 - 0.1587).abs() < 1e-9);
+        assert_eq!(rates[3].0, 1_704_153_600 + DAY);
+    }
+
+    #[test]
+    fn test_rate_of_day_uses_last_fixing_and_preferred_source() {
+        let conn = setup_test_db();
+        let friday = 1_715_299_200;
+        save_history(
+            &conn,
+            "cnb",
+            &[
+                (friday - DAY, "EUR".to_string(), 25.1),
+                (friday, "EUR".to_string(), 25.2),
+            ],
+        )
+        .unwrap();
+        save_history(&conn, "ecb", &[(friday, "EUR".to_string(), 24.9)]).unwrap();
+
+        let fx =
+            HistoricalRates::load(&conn, friday - DAY, friday + 2 * DAY, FxSource::Ecb).unwrap();
+        assert_eq!(fx.rate("EUR", friday - DAY), Some(25.1));
+        assert_eq!(fx.rate("eur", friday), Some(24.9));
+        // Weekend uses Friday's fixing
+        assert_eq!(fx.rate("EUR", friday + 2 * DAY), Some(24.9));
+        assert_eq!(fx.to_czk(10.0, "CZK", friday), 10.0);
+        assert_eq!(fx.rate("USD", friday), None);
+        assert!(is_covered(&conn, friday - DAY, friday + 2 * DAY));
+        assert!(!is_covered(&conn, friday - 30 * DAY, friday));
+        // A fixing is not carried further than the tolerance
+        assert_eq!(fx.rate("EUR", friday + 7 * DAY), None);
+
+        let cnb = HistoricalRates::load(&conn, friday, friday, FxSource::CnbDaily).unwrap();
+        assert_eq!(cnb.rate("EUR", friday), Some(25.2));
+    }
+
+    #[test]
+    fn test_coverage_finds_gaps_between_stored_days() {
+        let conn = setup_test_db();
+        let first = 1_546_300_800; // 2019-01-01
+        let old: Vec<DailyRate> = (0..365)
+            .map(|i| (first + i * DAY, "EUR".to_string(), 25.7))
+            .collect();
+        save_history(&conn, "cnb", &old).unwrap();
+        let recent_start = first + 5 * 365 * DAY;
+        let recent: Vec<DailyRate> = (0..90)
+            .map(|i| (recent_start + i * DAY, "EUR".to_string(), 25.0))
+            .collect();
+        save_history(&conn, "ecb", &recent).unwrap();
+
+        assert!(is_covered(&conn, first, first + 300 * DAY));
+        assert!(is_covered(&conn, recent_start, recent_start + 89 * DAY));
+        // Years without any rate between the two downloads
+        assert!(!is_covered(&conn, first, recent_start + 89 * DAY));
+        // Requested range running past the last stored day
+        assert!(!is_covered(&conn, recent_start, recent_start + 120 * DAY));
+
+        let fx =
+            HistoricalRates::load(&conn, first, recent_start + 89 * DAY, FxSource::Ecb).unwrap();
+        assert_eq!(fx.rate("EUR", first + 364 * DAY + 3 * DAY), Some(25.7));
+        assert_eq!(fx.rate("EUR", first + 3 * 365 * DAY), None);
+        assert_eq!(fx.rate("EUR", recent_start - 2 * DAY), Some(25.0));
+    }
+}
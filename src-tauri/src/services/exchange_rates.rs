@@ -0,0 +1,261 @@
+//! Exchange rate sources and manual rates
+//!
+//! Current rates come from the ECB or the ČNB daily fixing, chosen per purpose
+//! in `FxSettings`. Tax reports may instead use the uniform annual rate
+//! ("jednotný kurz"): the average of the ČNB fixings on the last business day
+//! of each month. Manual rates fill in currencies neither source publishes.
+
+use crate::error::{AppError, Result};
+use crate::models::{FxSettings, FxSource, InsertManualExchangeRate, ManualExchangeRate};
+use crate::services::currency;
+use crate::services::exchange_rate_history::{fetch_cnb_year, DailyRate};
+use chrono::Datelike;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap};
+
+const VALUATION_SOURCE_KEY: &str = "fx_valuation_source";
+const TAX_SOURCE_KEY: &str = "fx_tax_source";
+
+const CNB_DAILY_URL: &str = "https://www.cnb.cz/en/financial-markets/foreign-exchange-market/central-bank-exchange-rate-fixing/central-bank-exchange-rate-fixing/daily.txt";
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+fn config_source(conn: &Connection, key: &str) -> Option<FxSource> {
+    conn.query_row(
+        "SELECT value FROM app_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| FxSource::parse(&value))
+}
+
+/// Get the rate source per purpose
+pub fn get_settings(conn: &Connection) -> Result<FxSettings> {
+    let default = FxSettings::default();
+    Ok(FxSettings {
+        valuation_source: config_source(conn, VALUATION_SOURCE_KEY)
+            .filter(|s| *s != FxSource::CnbAnnual)
+            .unwrap_or(default.valuation_source),
+        tax_source: config_source(conn, TAX_SOURCE_KEY).unwrap_or(default.tax_source),
+    })
+}
+
+/// Save the rate source per purpose
+pub fn set_settings(conn: &Connection, settings: &FxSettings) -> Result<()> {
+    for (key, source) in [
+        (VALUATION_SOURCE_KEY, settings.valuation_source),
+        (TAX_SOURCE_KEY, settings.tax_source),
+    ] {
+        conn.execute(
+            "INSERT INTO app_config (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![key, source.as_str()],
+        )?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Manual rates
+// ============================================================================
+
+/// Get all manual rates
+pub fn get_manual_rates(conn: &Connection) -> Result<Vec<ManualExchangeRate>> {
+    let mut stmt = conn.prepare(
+        "SELECT currency, rate, updated_at FROM manual_exchange_rates ORDER BY currency",
+    )?;
+    let rates = stmt
+        .query_map([], |row| {
+            Ok(ManualExchangeRate {
+                currency: row.get(0)?,
+                rate: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rates)
+}
+
+/// Set the manual rate of a currency and use it right away if no source publishes it
+pub fn set_manual_rate(
+    conn: &Connection,
+    data: &InsertManualExchangeRate,
+) -> Result<ManualExchangeRate> {
+    let currency = data.currency.trim().to_uppercase();
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO manual_exchange_rates (currency, rate, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(currency) DO UPDATE SET rate = excluded.rate, updated_at = excluded.updated_at",
+        rusqlite::params![currency, data.rate, now],
+    )?;
+    apply_manual_rates(conn)?;
+
+    Ok(ManualExchangeRate {
+        currency,
+        rate: data.rate,
+        updated_at: now,
+    })
+}
+
+/// Delete the manual rate of a currency
+pub fn delete_manual_rate(conn: &Connection, currency: &str) -> Result<()> {
+    let currency = currency.trim().to_uppercase();
+    let changes = conn.execute(
+        "DELETE FROM manual_exchange_rates WHERE currency = ?1",
+        [&currency],
+    )?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Manual exchange rate not found".into()));
+    }
+    if !published_currencies(conn)?.contains(&currency) {
+        currency::remove_exchange_rate(&currency);
+    }
+    Ok(())
+}
+
+/// Currencies with a rate from a source (the persisted current rates)
+fn published_currencies(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT currency FROM exchange_rates")?;
+    let currencies = stmt
+        .query_map([], |row| row.get(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(currencies)
+}
+
+/// Load manual rates into the current rates for currencies no source publishes
+pub fn apply_manual_rates(conn: &Connection) -> Result<()> {
+    let published = published_currencies(conn)?;
+    let manual: HashMap<String, f64> = get_manual_rates(conn)?
+        .into_iter()
+        .filter(|r| !published.contains(&r.currency))
+        .map(|r| (r.currency, r.rate))
+        .collect();
+    if !manual.is_empty() {
+        currency::update_exchange_rates(manual);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Fetching
+// ============================================================================
+
+/// Fetch current rates from a source and make them the active rates
+pub async fn fetch_current_rates(source: FxSource) -> Result<HashMap<String, f64>> {
+    match source {
+        FxSource::Ecb => currency::fetch_ecb_rates().await,
+        FxSource::CnbDaily | FxSource::CnbAnnual => fetch_cnb_daily_rates().await,
+    }
+}
+
+/// Fetch today's ČNB fixing (CZK per unit)
+pub async fn fetch_cnb_daily_rates() -> Result<HashMap<String, f64>> {
+    let response = reqwest::get(CNB_DAILY_URL).await?;
+    if !response.status().is_success() {
+        return Err(AppError::ExternalApi(format!(
+            "ČNB fixing returned {}",
+            response.status()
+        )));
+    }
+    let rates = parse_cnb_daily(&response.text().await?);
+    if rates.len() <= 1 {
+        return Err(AppError::ExternalApi("ČNB fixing has no rates".into()));
+    }
+
+    currency::update_exchange_rates(rates.clone());
+    currency::set_exchange_rates_fetched_at(chrono::Utc::now().timestamp());
+    Ok(rates)
+}
+
+/// Uniform annual rates of a year from the ČNB fixings
+pub async fn fetch_cnb_annual_rates(year: i32) -> Result<HashMap<String, f64>> {
+    Ok(annual_uniform_rates(&fetch_cnb_year(year).await?))
+}
+
+/// Parse the ČNB daily fixing:
+/// "18 Oct 2024 #202", "Country|Currency|Amount|Code|Rate", "Japan|yen|100|JPY|15.620"
+fn parse_cnb_daily(text: &str) -> HashMap<String, f64> {
+    let mut rates = HashMap::new();
+    rates.insert("CZK".to_string(), 1.0);
+
+    for line in text.lines().skip(2) {
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [_, _, amount, code, rate] = fields[..] else {
+            continue;
+        };
+        let amount: Option<f64> = amount.parse().ok();
+        let rate: Option<f64> = rate.replace(',', ".").parse().ok();
+        if let (Some(amount), Some(rate)) = (amount, rate) {
+            if amount > 0.0 && rate > 0.0 {
+                rates.insert(code.to_uppercase(), rate / amount);
+            }
+        }
+    }
+
+    rates
+}
+
+/// Uniform annual rate per currency: the average of the last fixing of each month
+pub(crate) fn annual_uniform_rates(daily: &[DailyRate]) -> HashMap<String, f64> {
+    // currency -> (year, month) -> (day, rate) of the latest fixing in the month
+    let mut month_ends: HashMap<&str, BTreeMap<(i32, u32), (i64, f64)>> = HashMap::new();
+    for (day, currency, rate) in daily {
+        let Some(date) = chrono::DateTime::from_timestamp(*day, 0) else {
+            continue;
+        };
+        let entry = month_ends
+            .entry(currency.as_str())
+            .or_default()
+            .entry((date.year(), date.month()))
+            .or_insert((*day, *rate));
+        if *day > entry.0 {
+            *entry = (*day, *rate);
+        }
+    }
+
+    month_ends
+        .into_iter()
+        .map(|(currency, months)| {
+            let sum: f64 = months.values().map(|(_, rate)| rate).sum();
+            (currency.to_string(), sum / months.len() as f64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAN_31: i64 = 1_706_659_200; // 2024-01-31
+    const DAY: i64 = 86400;
+
+    #[test]
+    fn test_parse_cnb_daily() {
+        let text = "18 Oct 2024 #202\nCountry|Currency|Amount|Code|Rate\nEMU|euro|1|EUR|25.040\nJapan|yen|100|JPY|15,620\nbroken line\n";
+        let rates = parse_cnb_daily(text);
+
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates["EUR"], 25.04);
+        assert!((rates["JPY"] - 0.1562).abs() < 1e-9);
+        assert_eq!(rates["CZK"], 1.0);
+    }
+
+    #[test]
+    fn test_annual_uniform_rate_averages_month_end_fixings() {
+        let daily = vec![
+            (JAN_31 - DAY, "USD".to_string(), 22.0),
+            (JAN_31, "USD".to_string(), 22.5),
+            (JAN_31 + DAY, "USD".to_string(), 23.0),
+            (JAN_31 + 29 * DAY, "USD".to_string(), 23.5), // 2024-02-29
+        ];
+        let rates = annual_uniform_rates(&daily);
+
+        assert_eq!(rates.len(), 1);
+        assert!((rates["USD"] - 23.0).abs() < 1e-9);
+    }
+}
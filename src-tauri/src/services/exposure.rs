@@ -0,0 +1,637 @@
+//! Portfolio exposure service
+//!
+//! Groups holdings by sector, industry, listing country or currency using the
+//! Yahoo metadata stored in `stock_data`. Stocks count in their underlying
+//! currency when one is set per ticker (`stock_underlying_currencies`), else in
+//! their quote currency. Non-stock assets have no sector or country and are
+//! reported as one bucket per asset class; in the currency breakdown cash,
+//! bonds, real estate and other assets are split by their own currency.
+//! History covers stocks only, as `stock_value_history` does; a ticker without
+//! a row on some day keeps its last value while it is still held.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    ExposureDimension, ExposureHistoryPoint, ExposureItem, ExposureReport, UnderlyingCurrency,
+};
+use crate::services::bonds as bond_service;
+use crate::services::money::{self, Decimal};
+use crate::services::pricing::{resolve_crypto_price, resolve_stock_price};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bucket key for stocks without metadata
+const UNKNOWN: &str = "unknown";
+
+/// Country of a listing from the ticker suffix (no suffix = US)
+pub fn ticker_country(ticker: &str) -> &'static str {
+    match ticker.rsplit_once('.').map(|(_, s)| s) {
+        Some("DE") | Some("F") => "DE",
+        Some("L") => "GB",
+        Some("PA") => "FR",
+        Some("AS") => "NL",
+        Some("MC") => "ES",
+        Some("MI") => "IT",
+        Some("PR") => "CZ",
+        Some("WA") => "PL",
+        Some("SW") => "CH",
+        Some("TO") => "CA",
+        _ => "US",
+    }
+}
+
+/// Country of a Yahoo exchange code
+fn exchange_country(exchange: &str) -> Option<&'static str> {
+    let country = match exchange.to_uppercase().as_str() {
+        "NMS" | "NYQ" | "NGM" | "NCM" | "ASE" | "PCX" | "BTS" | "NAS" | "NYS" => "US",
+        "GER" | "FRA" | "STU" | "MUN" | "BER" | "DUS" | "HAM" => "DE",
+        "LSE" => "GB",
+        "PAR" => "FR",
+        "AMS" => "NL",
+        "MCE" => "ES",
+        "MIL" => "IT",
+        "PRA" => "CZ",
+        "WSE" => "PL",
+        "EBS" => "CH",
+        "TOR" | "VAN" => "CA",
+        "VIE" => "AT",
+        "BRU" => "BE",
+        "STO" => "SE",
+        "CPH" => "DK",
+        "OSL" => "NO",
+        "HEL" => "FI",
+        "LIS" => "PT",
+        "ISE" => "IE",
+        "HKG" => "HK",
+        "JPX" => "JP",
+        "ASX" => "AU",
+        _ => return None,
+    };
+    Some(country)
+}
+
+/// Listing country: exchange metadata first, ticker suffix as fallback
+pub fn listing_country(exchange: Option<&str>, ticker: &str) -> String {
+    exchange
+        .and_then(exchange_country)
+        .unwrap_or_else(|| ticker_country(ticker))
+        .to_string()
+}
+
+/// Yahoo metadata of one ticker
+#[derive(Debug, Clone, Default)]
+struct StockMeta {
+    sector: Option<String>,
+    industry: Option<String>,
+    exchange: Option<String>,
+    currency: Option<String>,
+    underlying_currency: Option<String>,
+}
+
+fn load_stock_meta(conn: &rusqlite::Connection) -> Result<HashMap<String, StockMeta>> {
+    let mut stmt =
+        conn.prepare("SELECT ticker, sector, industry, exchange, currency FROM stock_data")?;
+    let mut meta: HashMap<String, StockMeta> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                StockMeta {
+                    sector: row.get(1)?,
+                    industry: row.get(2)?,
+                    exchange: row.get(3)?,
+                    currency: row.get(4)?,
+                    underlying_currency: None,
+                },
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    for underlying in get_underlying_currencies(conn)? {
+        meta.entry(underlying.ticker)
+            .or_default()
+            .underlying_currency = Some(underlying.currency);
+    }
+    Ok(meta)
+}
+
+/// Get all underlying currencies set per ticker
+pub fn get_underlying_currencies(conn: &rusqlite::Connection) -> Result<Vec<UnderlyingCurrency>> {
+    let mut stmt = conn.prepare(
+        "SELECT ticker, currency, updated_at FROM stock_underlying_currencies ORDER BY ticker",
+    )?;
+    let currencies = stmt
+        .query_map([], |row| {
+            Ok(UnderlyingCurrency {
+                ticker: row.get(0)?,
+                currency: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(currencies)
+}
+
+/// Set the currency a ticker's holdings are denominated in, for funds whose
+/// listing currency differs from what they hold
+pub fn set_underlying_currency(
+    conn: &rusqlite::Connection,
+    ticker: &str,
+    currency: &str,
+) -> Result<UnderlyingCurrency> {
+    let ticker = ticker.trim().to_uppercase();
+    let currency = currency.trim().to_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::Validation("validation.currencyInvalid".into()));
+    }
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO stock_underlying_currencies (ticker, currency, updated_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(ticker) DO UPDATE SET
+           currency = excluded.currency, updated_at = excluded.updated_at",
+        rusqlite::params![ticker, currency, now],
+    )?;
+
+    Ok(UnderlyingCurrency {
+        ticker,
+        currency,
+        updated_at: now,
+    })
+}
+
+/// Forget the underlying currency; the ticker counts in its quote currency again
+pub fn delete_underlying_currency(conn: &rusqlite::Connection, ticker: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM stock_underlying_currencies WHERE ticker = ?1",
+        [ticker.trim().to_uppercase()],
+    )?;
+    Ok(())
+}
+
+/// Units held per ticker after each of its transactions: (transaction date, units)
+fn load_quantity_timelines(
+    conn: &rusqlite::Connection,
+) -> Result<HashMap<String, Vec<(i64, Decimal)>>> {
+    let mut stmt = conn.prepare(
+        "SELECT ticker, LOWER(type), quantity, transaction_date FROM investment_transactions
+         ORDER BY transaction_date",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .filter_map(|r| r.ok());
+
+    let mut timelines: HashMap<String, Vec<(i64, Decimal)>> = HashMap::new();
+    for (ticker, tx_type, quantity, date) in rows {
+        let quantity = money::parse_or_zero(&quantity);
+        let timeline = timelines.entry(ticker).or_default();
+        let held = timeline.last().map_or(Decimal::ZERO, |(_, q)| *q);
+        let change = if tx_type == "sell" {
+            -quantity
+        } else {
+            quantity
+        };
+        timeline.push((date, held + change));
+    }
+    Ok(timelines)
+}
+
+/// Whether a ticker is held on a day; tickers without transactions count as
+/// held while they have values
+fn held_on(timeline: Option<&Vec<(i64, Decimal)>>, date: i64) -> bool {
+    match timeline {
+        None => true,
+        Some(timeline) => timeline
+            .iter()
+            .rev()
+            .find(|(d, _)| *d <= date)
+            .is_some_and(|(_, held)| *held > Decimal::ZERO),
+    }
+}
+
+/// Bucket of a stock; `currency` is the fallback when metadata has none
+fn stock_key(
+    dimension: ExposureDimension,
+    ticker: &str,
+    meta: Option<&StockMeta>,
+    currency: &str,
+) -> String {
+    let non_empty = |v: Option<&String>| v.filter(|s| !s.trim().is_empty()).cloned();
+    match dimension {
+        ExposureDimension::Sector => {
+            non_empty(meta.and_then(|m| m.sector.as_ref())).unwrap_or_else(|| UNKNOWN.into())
+        }
+        ExposureDimension::Industry => {
+            non_empty(meta.and_then(|m| m.industry.as_ref())).unwrap_or_else(|| UNKNOWN.into())
+        }
+        ExposureDimension::Country => {
+            listing_country(meta.and_then(|m| m.exchange.as_deref()), ticker)
+        }
+        ExposureDimension::Currency => non_empty(meta.and_then(|m| m.underlying_currency.as_ref()))
+            .or_else(|| non_empty(meta.and_then(|m| m.currency.as_ref())))
+            .unwrap_or_else(|| currency.to_string())
+            .to_uppercase(),
+    }
+}
+
+/// Accumulates values per (key, asset class) bucket
+#[derive(Default)]
+struct Buckets(BTreeMap<(String, Option<String>), (Decimal, i32)>);
+
+impl Buckets {
+    fn add(&mut self, key: String, asset_class: Option<&str>, value: Decimal) {
+        let entry = self
+            .0
+            .entry((key, asset_class.map(str::to_string)))
+            .or_insert((Decimal::ZERO, 0));
+        entry.0 += value;
+        entry.1 += 1;
+    }
+
+    /// Add a non-stock asset: by currency in the currency breakdown, else as its class
+    fn add_asset(
+        &mut self,
+        dimension: ExposureDimension,
+        asset_class: &str,
+        currency: &str,
+        value: Decimal,
+    ) {
+        if dimension == ExposureDimension::Currency {
+            self.add(currency.to_uppercase(), None, value);
+        } else {
+            self.add(asset_class.to_string(), Some(asset_class), value);
+        }
+    }
+
+    fn into_items(self) -> (f64, Vec<ExposureItem>) {
+        let total: Decimal = self.0.values().map(|(v, _)| v).sum();
+        let mut items: Vec<ExposureItem> = self
+            .0
+            .into_iter()
+            .map(|((key, asset_class), (value, count))| ExposureItem {
+                key,
+                asset_class,
+                value: money::to_f64(value),
+                percent: if total > Decimal::ZERO {
+                    money::to_f64(value / total) * 100.0
+                } else {
+                    0.0
+                },
+                holdings_count: count,
+            })
+            .collect();
+        items.sort_by(|a, b| b.value.total_cmp(&a.value));
+        (money::to_f64(total), items)
+    }
+}
+
+fn query_rows(conn: &rusqlite::Connection, sql: &str) -> Result<Vec<(String, String, String)>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+/// Exposure of the current portfolio by the given dimension
+pub fn get_exposure(
+    conn: &rusqlite::Connection,
+    dimension: ExposureDimension,
+    stocks_only: bool,
+    exclude_personal_real_estate: bool,
+) -> Result<ExposureReport> {
+    let meta = load_stock_meta(conn)?;
+    let mut buckets = Buckets::default();
+
+    let mut stmt = conn.prepare("SELECT ticker, quantity FROM stock_investments")?;
+    let stocks: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (ticker, quantity) in stocks {
+        let qty = money::parse_or_zero(&quantity);
+        if qty <= Decimal::ZERO {
+            continue;
+        }
+        if let Some(resolved) = resolve_stock_price(conn, &ticker) {
+            let key = stock_key(dimension, &ticker, meta.get(&ticker), &resolved.currency);
+            buckets.add(key, None, money::from_f64(resolved.price_czk) * qty);
+        }
+    }
+
+    if !stocks_only {
+        // Cash
+        for (balance, currency, _) in query_rows(
+            conn,
+            "SELECT balance, currency, '' FROM bank_accounts WHERE exclude_from_balance = 0",
+        )? {
+            let value = money::to_czk(money::parse_or_zero(&balance), &currency);
+            buckets.add_asset(dimension, "savings", &currency, value);
+        }
+
+        // Bonds (market value with accrued interest)
+        let now = chrono::Utc::now().timestamp();
+        for bond in bond_service::get_bonds(conn)? {
+            buckets.add_asset(
+                dimension,
+                "bonds",
+                &bond.currency,
+                money::to_czk(bond_service::market_value(&bond, now), &bond.currency),
+            );
+        }
+
+        // Real estate
+        for (kind, price, currency) in query_rows(
+            conn,
+            "SELECT type, market_price, market_price_currency FROM real_estate",
+        )? {
+            if exclude_personal_real_estate && kind == "personal" {
+                continue;
+            }
+            let value = money::to_czk(money::parse_or_zero(&price), &currency);
+            buckets.add_asset(dimension, "real_estate", &currency, value);
+        }
+
+        // Other assets
+        for (quantity, price, currency) in query_rows(
+            conn,
+            "SELECT quantity, market_price, currency FROM other_assets",
+        )? {
+            let qty = money::parse_or_zero(&quantity);
+            let price = money::parse_or_zero(&price);
+            buckets.add_asset(
+                dimension,
+                "other_assets",
+                &currency,
+                money::to_czk(qty * price, &currency),
+            );
+        }
+
+        // Crypto has no sector, country or fiat currency
+        let mut stmt = conn.prepare("SELECT ticker, quantity FROM crypto_investments")?;
+        let cryptos: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        for (ticker, quantity) in cryptos {
+            let qty = money::parse_or_zero(&quantity);
+            if let Some(resolved) = resolve_crypto_price(conn, &ticker) {
+                buckets.add(
+                    "crypto".into(),
+                    Some("crypto"),
+                    money::from_f64(resolved.price_czk) * qty,
+                );
+            }
+        }
+    }
+
+    let (total_value, items) = buckets.into_items();
+    Ok(ExposureReport {
+        dimension,
+        stocks_only,
+        total_value,
+        items,
+    })
+}
+
+/// Daily stock exposure from `stock_value_history`, using current metadata.
+/// Tickers without a row on a day are carried forward at their last value
+/// until they are sold.
+pub fn get_exposure_history(
+    conn: &rusqlite::Connection,
+    dimension: ExposureDimension,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<ExposureHistoryPoint>> {
+    if let (Some(from), Some(to)) = (from_ts, to_ts) {
+        if from >= to {
+            return Err(AppError::Validation("validation.dateRangeInvalid".into()));
+        }
+    }
+
+    let meta = load_stock_meta(conn)?;
+    let timelines = load_quantity_timelines(conn)?;
+    // Rows before the range seed the values carried into it
+    let mut stmt = conn.prepare(
+        "SELECT recorded_at, ticker, value_czk, currency FROM stock_value_history
+         WHERE ?1 IS NULL OR recorded_at <= ?1
+         ORDER BY recorded_at",
+    )?;
+    let rows = stmt
+        .query_map([to_ts], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .filter_map(|r| r.ok());
+
+    let mut days: BTreeMap<i64, Vec<(String, Decimal, String)>> = BTreeMap::new();
+    for (date, ticker, value, currency) in rows {
+        let value = money::parse_or_zero(&value);
+        days.entry(date)
+            .or_default()
+            .push((ticker, value, currency));
+    }
+
+    // Last known (value, currency) per ticker
+    let mut latest: BTreeMap<String, (Decimal, String)> = BTreeMap::new();
+    let mut points = Vec::new();
+    for (date, entries) in days {
+        for (ticker, value, currency) in entries {
+            if value > Decimal::ZERO {
+                latest.insert(ticker, (value, currency));
+            } else {
+                latest.remove(&ticker);
+            }
+        }
+        if from_ts.is_some_and(|from| date < from) {
+            continue;
+        }
+
+        let mut buckets = Buckets::default();
+        for (ticker, (value, currency)) in &latest {
+            if held_on(timelines.get(ticker), date) {
+                let key = stock_key(dimension, ticker, meta.get(ticker), currency);
+                buckets.add(key, None, *value);
+            }
+        }
+        if buckets.0.is_empty() {
+            continue;
+        }
+        let (total_value, items) = buckets.into_items();
+        points.push(ExposureHistoryPoint {
+            date,
+            total_value,
+            items,
+        });
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE stock_investments (id TEXT PRIMARY KEY, ticker TEXT, quantity TEXT);
+             CREATE TABLE stock_data (
+                 ticker TEXT PRIMARY KEY, original_price TEXT, currency TEXT, fetched_at INTEGER,
+                 sector TEXT, industry TEXT, exchange TEXT
+             );
+             CREATE TABLE stock_price_overrides (ticker TEXT, price TEXT, currency TEXT, updated_at INTEGER);
+             CREATE TABLE stock_value_history (
+                 ticker TEXT, recorded_at INTEGER, value_czk TEXT, currency TEXT
+             );
+             CREATE TABLE stock_underlying_currencies (
+                 ticker TEXT PRIMARY KEY, currency TEXT NOT NULL, updated_at INTEGER NOT NULL
+             );
+             CREATE TABLE investment_transactions (
+                 ticker TEXT, type TEXT, quantity TEXT, transaction_date INTEGER
+             );
+             CREATE TABLE bank_accounts (
+                 balance TEXT, currency TEXT, exclude_from_balance INTEGER DEFAULT 0
+             );
+             CREATE TABLE bonds (
+                 id TEXT, name TEXT, isin TEXT, coupon_value TEXT, quantity TEXT, currency TEXT,
+                 interest_rate TEXT DEFAULT '0', maturity_date INTEGER,
+                 coupon_frequency TEXT DEFAULT 'annual', day_count TEXT DEFAULT '30/360',
+                 issue_date INTEGER, first_coupon_date INTEGER, purchase_price TEXT,
+                 purchase_date INTEGER, market_price TEXT, market_price_source TEXT,
+                 market_price_updated_at INTEGER, price_ticker TEXT,
+                 created_at INTEGER DEFAULT 0, updated_at INTEGER DEFAULT 0
+             );
+             CREATE TABLE real_estate (type TEXT, market_price TEXT, market_price_currency TEXT);
+             CREATE TABLE other_assets (quantity TEXT, market_price TEXT, currency TEXT);
+             CREATE TABLE crypto_investments (ticker TEXT, quantity TEXT);
+             CREATE TABLE crypto_prices (symbol TEXT, price TEXT, currency TEXT, fetched_at INTEGER);
+             CREATE TABLE crypto_price_overrides (symbol TEXT, price TEXT, currency TEXT, updated_at INTEGER);
+
+             INSERT INTO stock_investments VALUES ('1', 'CEZ.PR', '10'), ('2', 'KOMB.PR', '5'), ('3', 'XYZ', '1');
+             INSERT INTO stock_data VALUES
+                 ('CEZ.PR', '100', 'CZK', 0, 'Utilities', 'Utilities - Regulated', 'PRA'),
+                 ('KOMB.PR', '200', 'CZK', 0, 'Financial Services', 'Banks', NULL),
+                 ('XYZ', '500', 'CZK', 0, NULL, NULL, NULL);
+             INSERT INTO bank_accounts (balance, currency) VALUES ('1000', 'CZK');
+             INSERT INTO bank_accounts VALUES ('9999', 'CZK', 1);
+             INSERT INTO bonds (id, name, coupon_value, quantity, currency) VALUES ('b', 'Bond', '500', '2', 'CZK');
+             INSERT INTO real_estate VALUES ('personal', '5000', 'CZK');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn item<'a>(report: &'a [ExposureItem], key: &str) -> &'a ExposureItem {
+        report.iter().find(|i| i.key == key).unwrap()
+    }
+
+    #[test]
+    fn test_listing_country() {
+        assert_eq!(listing_country(Some("PRA"), "CEZ"), "CZ");
+        assert_eq!(listing_country(Some("NMS"), "VWCE.DE"), "US");
+        assert_eq!(listing_country(None, "VWCE.DE"), "DE");
+        assert_eq!(listing_country(Some("???"), "AAPL"), "US");
+    }
+
+    #[test]
+    fn test_stock_exposure_by_sector() {
+        let conn = setup_test_db();
+        let report = get_exposure(&conn, ExposureDimension::Sector, true, false).unwrap();
+
+        assert!((report.total_value - 2500.0).abs() < 1e-9);
+        assert!((item(&report.items, "Utilities").percent - 40.0).abs() < 1e-9);
+        assert!((item(&report.items, UNKNOWN).value - 500.0).abs() < 1e-9);
+        // Largest bucket first
+        assert_eq!(report.items[0].key, "Financial Services");
+    }
+
+    #[test]
+    fn test_whole_portfolio_exposure() {
+        let conn = setup_test_db();
+        let report = get_exposure(&conn, ExposureDimension::Country, false, true).unwrap();
+        assert!((report.total_value - 4500.0).abs() < 1e-9);
+        let bonds = item(&report.items, "bonds");
+        assert_eq!(bonds.asset_class.as_deref(), Some("bonds"));
+        assert!((bonds.value - 1000.0).abs() < 1e-9);
+        assert!((item(&report.items, "CZ").value - 2000.0).abs() < 1e-9);
+        assert!(report.items.iter().all(|i| i.key != "real_estate"));
+
+        // Cash and bonds are split by currency together with stocks
+        let report = get_exposure(&conn, ExposureDimension::Currency, false, false).unwrap();
+        let czk = item(&report.items, "CZK");
+        assert!((czk.value - 9500.0).abs() < 1e-9);
+        assert_eq!(czk.holdings_count, 6);
+    }
+
+    #[test]
+    fn test_exposure_history() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO stock_value_history VALUES
+                 ('CEZ.PR', 100, '1000', 'CZK'), ('KOMB.PR', 100, '3000', 'CZK'),
+                 ('CEZ.PR', 200, '2000', 'CZK'), ('KOMB.PR', 200, '2000', 'CZK'),
+                 ('CEZ.PR', 300, '0', 'CZK');",
+        )
+        .unwrap();
+
+        let history =
+            get_exposure_history(&conn, ExposureDimension::Sector, None, Some(250)).unwrap();
+        assert_eq!(history.len(), 2);
+        assert!((item(&history[0].items, "Utilities").percent - 25.0).abs() < 1e-9);
+        assert!((item(&history[1].items, "Utilities").percent - 50.0).abs() < 1e-9);
+
+        assert!(
+            get_exposure_history(&conn, ExposureDimension::Sector, Some(300), Some(100)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_exposure_history_carries_values_forward_until_sold() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO investment_transactions VALUES
+                 ('CEZ.PR', 'buy', '10', 0), ('KOMB.PR', 'buy', '5', 0),
+                 ('KOMB.PR', 'sell', '5', 250);
+             INSERT INTO stock_value_history VALUES
+                 ('CEZ.PR', 100, '1000', 'CZK'), ('KOMB.PR', 100, '3000', 'CZK'),
+                 ('CEZ.PR', 200, '2000', 'CZK'), ('CEZ.PR', 300, '2000', 'CZK');",
+        )
+        .unwrap();
+
+        let history =
+            get_exposure_history(&conn, ExposureDimension::Sector, Some(150), None).unwrap();
+        assert_eq!(history.len(), 2);
+        // KOMB.PR has no row on day 200 but is still held
+        assert!((history[0].total_value - 5000.0).abs() < 1e-9);
+        // and is gone after its sale
+        assert!((history[1].total_value - 2000.0).abs() < 1e-9);
+        assert_eq!(history[1].items.len(), 1);
+    }
+
+    #[test]
+    fn test_underlying_currency_overrides_quote_currency() {
+        let conn = setup_test_db();
+        set_underlying_currency(&conn, "xyz", "usd").unwrap();
+        assert!(set_underlying_currency(&conn, "XYZ", "DOLLAR").is_err());
+
+        let report = get_exposure(&conn, ExposureDimension::Currency, true, false).unwrap();
+        assert!((item(&report.items, "USD").value - 500.0).abs() < 1e-9);
+        assert!((item(&report.items, "CZK").value - 2000.0).abs() < 1e-9);
+
+        delete_underlying_currency(&conn, "XYZ").unwrap();
+        let report = get_exposure(&conn, ExposureDimension::Currency, true, false).unwrap();
+        assert!(report.items.iter().all(|i| i.key != "USD"));
+    }
+}
@@ -0,0 +1,548 @@
+//! Savings goals service
+//!
+//! A goal's current value is the sum of its linked holdings in the goal
+//! currency: bank account balances, bond market values (with accrued
+//! interest) and the stock holdings carrying a tag. A holding may count
+//! towards several goals.
+//!
+//! The expected yearly return compounds monthly. The required monthly
+//! contribution is the annuity that, together with the grown current value,
+//! reaches the target at the target date; the goal is on track when the
+//! planned contribution is at least that much.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    GoalLink, GoalLinkValue, GoalStatus, InsertSavingsGoal, SavingsGoal, SavingsGoalProgress,
+};
+use crate::services::bonds::{self as bond_service, BOND_COLUMNS};
+use crate::services::money::{self, Decimal};
+use crate::services::pricing::resolve_stock_price;
+use chrono::{DateTime, Datelike};
+use rusqlite::{Connection, OptionalExtension};
+use uuid::Uuid;
+
+fn row_to_goal(row: &rusqlite::Row) -> rusqlite::Result<SavingsGoal> {
+    Ok(SavingsGoal {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        target_amount: row.get(2)?,
+        currency: row.get(3)?,
+        target_date: row.get(4)?,
+        expected_return: row.get(5)?,
+        monthly_contribution: row.get(6)?,
+        links: Vec::new(),
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+fn load_links(conn: &Connection, goal_id: &str) -> Result<Vec<GoalLink>> {
+    let mut stmt = conn.prepare(
+        "SELECT kind, link_id FROM savings_goal_links WHERE goal_id = ?1 ORDER BY kind, link_id",
+    )?;
+    let links = stmt
+        .query_map([goal_id], |row| {
+            Ok(GoalLink {
+                kind: row.get(0)?,
+                link_id: row.get(1)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(links)
+}
+
+fn save_links(conn: &Connection, goal_id: &str, links: &[GoalLink]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM savings_goal_links WHERE goal_id = ?1",
+        [goal_id],
+    )?;
+    for link in links {
+        conn.execute(
+            "INSERT OR IGNORE INTO savings_goal_links (goal_id, kind, link_id) VALUES (?1, ?2, ?3)",
+            [goal_id, &link.kind, &link.link_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Get all savings goals with their links, by target date
+pub fn get_goals(conn: &Connection) -> Result<Vec<SavingsGoal>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, target_amount, currency, target_date, expected_return,
+                monthly_contribution, created_at, updated_at
+         FROM savings_goals ORDER BY target_date, name",
+    )?;
+    let goals: Vec<SavingsGoal> = stmt
+        .query_map([], row_to_goal)?
+        .filter_map(|r| r.ok())
+        .collect();
+    goals
+        .into_iter()
+        .map(|mut goal| {
+            goal.links = load_links(conn, &goal.id)?;
+            Ok(goal)
+        })
+        .collect()
+}
+
+/// Get a savings goal with its links
+pub fn get_goal(conn: &Connection, id: &str) -> Result<SavingsGoal> {
+    let mut goal = conn
+        .query_row(
+            "SELECT id, name, target_amount, currency, target_date, expected_return,
+                    monthly_contribution, created_at, updated_at
+             FROM savings_goals WHERE id = ?1",
+            [id],
+            row_to_goal,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound("Savings goal not found".into()))?;
+    goal.links = load_links(conn, id)?;
+    Ok(goal)
+}
+
+/// Create a savings goal
+pub fn create_goal(conn: &Connection, data: &InsertSavingsGoal) -> Result<SavingsGoal> {
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO savings_goals (id, name, target_amount, currency, target_date,
+                                    expected_return, monthly_contribution, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+        rusqlite::params![
+            id,
+            data.name.trim(),
+            data.target_amount.trim(),
+            data.currency.as_deref().unwrap_or("CZK"),
+            data.target_date,
+            data.expected_return
+                .as_deref()
+                .map(str::trim)
+                .unwrap_or("0"),
+            data.monthly_contribution
+                .as_deref()
+                .map(str::trim)
+                .unwrap_or("0"),
+            now,
+        ],
+    )?;
+    save_links(conn, &id, &data.links)?;
+    get_goal(conn, &id)
+}
+
+/// Update a savings goal, replacing its links
+pub fn update_goal(conn: &Connection, id: &str, data: &InsertSavingsGoal) -> Result<SavingsGoal> {
+    let now = chrono::Utc::now().timestamp();
+    let changes = conn.execute(
+        "UPDATE savings_goals SET name = ?1, target_amount = ?2,
+             currency = COALESCE(?3, currency),
+             target_date = ?4,
+             expected_return = COALESCE(?5, expected_return),
+             monthly_contribution = COALESCE(?6, monthly_contribution),
+             updated_at = ?7
+         WHERE id = ?8",
+        rusqlite::params![
+            data.name.trim(),
+            data.target_amount.trim(),
+            data.currency,
+            data.target_date,
+            data.expected_return.as_deref().map(str::trim),
+            data.monthly_contribution.as_deref().map(str::trim),
+            now,
+            id,
+        ],
+    )?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Savings goal not found".into()));
+    }
+    save_links(conn, id, &data.links)?;
+    get_goal(conn, id)
+}
+
+/// Delete a savings goal and its links
+pub fn delete_goal(conn: &Connection, id: &str) -> Result<()> {
+    let changes = conn.execute("DELETE FROM savings_goals WHERE id = ?1", [id])?;
+    if changes == 0 {
+        return Err(AppError::NotFound("Savings goal not found".into()));
+    }
+    Ok(())
+}
+
+/// Name and current value of a linked holding in `currency`, or None if it
+/// no longer exists
+fn link_value(
+    conn: &Connection,
+    link: &GoalLink,
+    currency: &str,
+    now: i64,
+) -> Result<Option<(String, Decimal)>> {
+    match link.kind.as_str() {
+        "bank_account" => {
+            let account: Option<(String, String, String)> = conn
+                .query_row(
+                    "SELECT name, balance, currency FROM bank_accounts WHERE id = ?1",
+                    [&link.link_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?;
+            Ok(account.map(|(name, balance, from)| {
+                let value = money::convert_between(money::parse_or_zero(&balance), &from, currency);
+                (name, value)
+            }))
+        }
+        "bond" => {
+            let bond = conn
+                .query_row(
+                    &format!("SELECT {} FROM bonds WHERE id = ?1", BOND_COLUMNS),
+                    [&link.link_id],
+                    bond_service::bond_from_row,
+                )
+                .optional()?;
+            Ok(bond.map(|bond| {
+                let value = money::convert_between(
+                    bond_service::market_value(&bond, now),
+                    &bond.currency,
+                    currency,
+                );
+                (bond.name, value)
+            }))
+        }
+        "tag" => {
+            let name: Option<String> = conn
+                .query_row(
+                    "SELECT name FROM stock_tags WHERE id = ?1",
+                    [&link.link_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(name) = name else {
+                return Ok(None);
+            };
+            let mut stmt = conn.prepare(
+                "SELECT si.ticker, si.quantity
+                 FROM stock_investments si
+                 JOIN stock_investment_tags sit ON si.id = sit.investment_id
+                 WHERE sit.tag_id = ?1",
+            )?;
+            let holdings: Vec<(String, String)> = stmt
+                .query_map([&link.link_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .filter_map(|r| r.ok())
+                .collect();
+            let value: Decimal = holdings
+                .iter()
+                .filter_map(|(ticker, quantity)| {
+                    let price = resolve_stock_price(conn, ticker)?;
+                    let value = money::parse_or_zero(&price.original_price)
+                        * money::parse_or_zero(quantity);
+                    Some(money::convert_between(value, &price.currency, currency))
+                })
+                .sum();
+            Ok(Some((name, value)))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Whole calendar months from `from` to `to` (0 if `to` is not later)
+fn months_between(from: i64, to: i64) -> i64 {
+    let (Some(from), Some(to)) = (
+        DateTime::from_timestamp(from, 0),
+        DateTime::from_timestamp(to, 0),
+    ) else {
+        return 0;
+    };
+    let mut months =
+        (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64;
+    if to.day() < from.day() {
+        months -= 1;
+    }
+    months.max(0)
+}
+
+/// Progress of a goal whose linked holdings are worth `links` (in the goal currency)
+pub fn progress(
+    goal: SavingsGoal,
+    links: Vec<(GoalLinkValue, Decimal)>,
+    now: i64,
+) -> SavingsGoalProgress {
+    let currency = goal.currency.clone();
+    let current: Decimal = links.iter().map(|(_, value)| *value).sum();
+    let target = money::parse_or_zero(&goal.target_amount);
+    let remaining = (target - current).max(Decimal::ZERO);
+    let months_left = months_between(now, goal.target_date);
+
+    // Growth of the current value and of a unit monthly contribution over the
+    // months left; only the monthly rate (a twelfth root) is taken as a float
+    let yearly: f64 = goal.expected_return.parse().unwrap_or(0.0);
+    let monthly_rate = money::from_f64((1.0 + yearly / 100.0).max(0.0).powf(1.0 / 12.0) - 1.0);
+    let mut growth = Decimal::ONE;
+    for _ in 0..months_left {
+        growth *= Decimal::ONE + monthly_rate;
+    }
+    let annuity = if monthly_rate.is_zero() {
+        Decimal::from(months_left)
+    } else {
+        (growth - Decimal::ONE) / monthly_rate
+    };
+
+    let planned = money::parse_or_zero(&goal.monthly_contribution);
+    let shortfall = target - current * growth;
+    let required = if shortfall <= Decimal::ZERO {
+        Decimal::ZERO
+    } else if annuity > Decimal::ZERO {
+        shortfall / annuity
+    } else {
+        shortfall
+    };
+    let projected = current * growth + planned * annuity;
+
+    let status = if current >= target {
+        GoalStatus::Achieved
+    } else if goal.target_date <= now {
+        GoalStatus::Overdue
+    } else if projected >= target {
+        GoalStatus::OnTrack
+    } else {
+        GoalStatus::Behind
+    };
+    let progress_percent = if target > Decimal::ZERO {
+        money::to_f64(current / target * Decimal::ONE_HUNDRED).clamp(0.0, 100.0)
+    } else {
+        100.0
+    };
+
+    SavingsGoalProgress {
+        goal,
+        current_value: money::format(current, &currency),
+        progress_percent,
+        remaining: money::format(remaining, &currency),
+        months_left,
+        required_monthly: money::format(required, &currency),
+        projected_value: money::format(projected, &currency),
+        status,
+        links: links.into_iter().map(|(link, _)| link).collect(),
+    }
+}
+
+/// Progress of every savings goal
+pub fn get_progress(conn: &Connection, now: i64) -> Result<Vec<SavingsGoalProgress>> {
+    get_goals(conn)?
+        .into_iter()
+        .map(|goal| {
+            let mut links = Vec::new();
+            for link in &goal.links {
+                if let Some((name, value)) = link_value(conn, link, &goal.currency, now)? {
+                    links.push((
+                        GoalLinkValue {
+                            kind: link.kind.clone(),
+                            link_id: link.link_id.clone(),
+                            name,
+                            value: money::format(value, &goal.currency),
+                        },
+                        value,
+                    ));
+                }
+            }
+            Ok(progress(goal, links, now))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2026-01-15 00:00 UTC
+    const NOW: i64 = 1_768_435_200;
+    /// 2027-01-15 00:00 UTC
+    const IN_A_YEAR: i64 = 1_799_971_200;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE savings_goals (
+                 id TEXT PRIMARY KEY,
+                 name TEXT NOT NULL,
+                 target_amount TEXT NOT NULL,
+                 currency TEXT NOT NULL DEFAULT 'CZK',
+                 target_date INTEGER NOT NULL,
+                 expected_return TEXT NOT NULL DEFAULT '0',
+                 monthly_contribution TEXT NOT NULL DEFAULT '0',
+                 created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+                 updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+             );
+             CREATE TABLE savings_goal_links (
+                 goal_id TEXT NOT NULL REFERENCES savings_goals(id) ON DELETE CASCADE,
+                 kind TEXT NOT NULL,
+                 link_id TEXT NOT NULL,
+                 PRIMARY KEY (goal_id, kind, link_id)
+             );
+             CREATE TABLE bank_accounts (
+                 id TEXT PRIMARY KEY, name TEXT NOT NULL, balance TEXT NOT NULL, currency TEXT NOT NULL
+             );
+             CREATE TABLE stock_tags (id TEXT PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE stock_investments (id TEXT PRIMARY KEY, ticker TEXT NOT NULL, quantity TEXT NOT NULL);
+             CREATE TABLE stock_investment_tags (investment_id TEXT NOT NULL, tag_id TEXT NOT NULL);
+             CREATE TABLE stock_price_overrides (
+                 ticker TEXT PRIMARY KEY, price TEXT NOT NULL, currency TEXT NOT NULL, updated_at INTEGER NOT NULL
+             );",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert_goal(target: &str, links: Vec<GoalLink>) -> InsertSavingsGoal {
+        InsertSavingsGoal {
+            name: "Emergency fund".into(),
+            target_amount: target.into(),
+            currency: Some("CZK".into()),
+            target_date: IN_A_YEAR,
+            expected_return: None,
+            monthly_contribution: None,
+            links,
+        }
+    }
+
+    fn link(kind: &str, id: &str) -> GoalLink {
+        GoalLink {
+            kind: kind.into(),
+            link_id: id.into(),
+        }
+    }
+
+    fn goal(target: &str, expected_return: &str, contribution: &str, date: i64) -> SavingsGoal {
+        SavingsGoal {
+            id: "g1".into(),
+            name: "House deposit".into(),
+            target_amount: target.into(),
+            currency: "CZK".into(),
+            target_date: date,
+            expected_return: expected_return.into(),
+            monthly_contribution: contribution.into(),
+            links: Vec::new(),
+            created_at: NOW,
+            updated_at: NOW,
+        }
+    }
+
+    fn worth(value: &str) -> Vec<(GoalLinkValue, Decimal)> {
+        vec![(
+            GoalLinkValue {
+                kind: "bank_account".into(),
+                link_id: "a1".into(),
+                name: "Savings".into(),
+                value: value.into(),
+            },
+            money::parse_or_zero(value),
+        )]
+    }
+
+    #[test]
+    fn test_goal_crud_replaces_and_cascades_links() {
+        let conn = setup_test_db();
+        let created = create_goal(
+            &conn,
+            &insert_goal(
+                "100000",
+                vec![link("bank_account", "a1"), link("bank_account", "a1")],
+            ),
+        )
+        .unwrap();
+        assert_eq!(created.expected_return, "0");
+        assert_eq!(created.links, vec![link("bank_account", "a1")]);
+
+        let updated = update_goal(
+            &conn,
+            &created.id,
+            &insert_goal("150000", vec![link("tag", "t1")]),
+        )
+        .unwrap();
+        assert_eq!(updated.target_amount, "150000");
+        assert_eq!(updated.links, vec![link("tag", "t1")]);
+
+        delete_goal(&conn, &created.id).unwrap();
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM savings_goal_links", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(remaining, 0);
+        assert!(matches!(
+            get_goal(&conn, &created.id),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_progress_sums_linked_holdings() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO bank_accounts VALUES ('a1', 'Reserve', '40000', 'CZK');
+             INSERT INTO stock_tags VALUES ('t1', 'Long term');
+             INSERT INTO stock_investments VALUES ('s1', 'CEZ.PR', '10');
+             INSERT INTO stock_investment_tags VALUES ('s1', 't1');
+             INSERT INTO stock_price_overrides VALUES ('CEZ.PR', '1000', 'CZK', 0);",
+        )
+        .unwrap();
+        create_goal(
+            &conn,
+            &insert_goal(
+                "100000",
+                vec![
+                    link("bank_account", "a1"),
+                    link("tag", "t1"),
+                    link("bank_account", "deleted"),
+                ],
+            ),
+        )
+        .unwrap();
+
+        let progress = get_progress(&conn, NOW).unwrap();
+        assert_eq!(progress.len(), 1);
+        assert_eq!(progress[0].current_value, "50000.00");
+        assert_eq!(progress[0].remaining, "50000.00");
+        assert_eq!(progress[0].progress_percent, 50.0);
+        assert_eq!(progress[0].links.len(), 2);
+    }
+
+    #[test]
+    fn test_required_monthly_without_return() {
+        let on_track = progress(goal("120000", "0", "5000", IN_A_YEAR), worth("60000"), NOW);
+        assert_eq!(on_track.months_left, 12);
+        assert_eq!(on_track.required_monthly, "5000.00");
+        assert_eq!(on_track.projected_value, "120000.00");
+        assert_eq!(on_track.status, GoalStatus::OnTrack);
+
+        let behind = progress(goal("120000", "0", "4000", IN_A_YEAR), worth("60000"), NOW);
+        assert_eq!(behind.projected_value, "108000.00");
+        assert_eq!(behind.status, GoalStatus::Behind);
+    }
+
+    #[test]
+    fn test_required_monthly_compounds_expected_return() {
+        // 12 % a year is 0.9489 % a month; 12 contributions of 1 grow to 12.6465
+        let result = progress(goal("126464.98", "12", "0", IN_A_YEAR), worth("0"), NOW);
+        assert_eq!(result.required_monthly, "10000.00");
+
+        // The current value alone grows by 12 % to exceed the target
+        let grown = progress(goal("110000", "12", "0", IN_A_YEAR), worth("100000"), NOW);
+        assert_eq!(grown.required_monthly, "0.00");
+        assert_eq!(grown.projected_value, "112000.00");
+        assert_eq!(grown.status, GoalStatus::OnTrack);
+    }
+
+    #[test]
+    fn test_achieved_and_overdue_status() {
+        let achieved = progress(goal("50000", "0", "0", NOW - 86400), worth("60000"), NOW);
+        assert_eq!(achieved.status, GoalStatus::Achieved);
+        assert_eq!(achieved.progress_percent, 100.0);
+        assert_eq!(achieved.remaining, "0.00");
+
+        let overdue = progress(goal("50000", "0", "1000", NOW - 86400), worth("10000"), NOW);
+        assert_eq!(overdue.status, GoalStatus::Overdue);
+        assert_eq!(overdue.months_left, 0);
+        assert_eq!(overdue.required_monthly, "40000.00");
+    }
+}
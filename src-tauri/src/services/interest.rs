@@ -0,0 +1,522 @@
+//! Interest on savings accounts and term deposits
+//!
+//! Interest accrues daily (act/365) on the balance, split over the rate zones
+//! of tiered accounts, and is capitalized monthly, annually or at maturity,
+//! counting from the deposit start. Each capitalization withholds the interest
+//! tax at source and credits the net interest, so later periods compound. The
+//! stored balance is taken to include the interest capitalized so far.
+//!
+//! A term deposit pays out its principal and net interest at maturity, or
+//! renews for the same term when it rolls over automatically.
+
+use crate::error::Result;
+use crate::models::{BankAccount, InterestAccrual, InterestPayment};
+use crate::services::money::{self, Decimal};
+use chrono::{Datelike, Months, NaiveDate};
+use rusqlite::Connection;
+
+/// Supported capitalization rules
+pub const CAPITALIZATIONS: [&str; 3] = ["monthly", "annual", "maturity"];
+
+/// Withholding tax on interest in the Czech Republic (percent)
+pub const DEFAULT_TAX_RATE: &str = "15";
+
+/// Upper bound on generated capitalization dates (a century of months)
+const MAX_PERIODS: u32 = 1200;
+
+/// Columns read into `BankAccount` for interest calculations
+const ACCOUNT_COLUMNS: &str =
+    "id, name, account_type, iban, bban, currency, balance, institution_id, external_account_id,
+     data_source, last_synced_at, interest_rate, has_zone_designation, termination_date,
+     deposit_start_date, auto_renew, capitalization, interest_tax_rate, created_at, updated_at";
+
+/// Rate zone of a tiered account: yearly rate (percent) on the balance between the bounds
+#[derive(Debug, Clone)]
+pub struct Zone {
+    pub from: Decimal,
+    pub to: Option<Decimal>,
+    pub rate: Decimal,
+}
+
+/// Savings accounts and term deposits with their rate zones
+pub fn interest_accounts(conn: &Connection) -> Result<Vec<(BankAccount, Vec<Zone>)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM bank_accounts WHERE account_type IN ('savings', 'term_deposit') ORDER BY name",
+        ACCOUNT_COLUMNS
+    ))?;
+    let accounts: Vec<BankAccount> = stmt
+        .query_map([], |row| {
+            Ok(BankAccount {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                account_type: row.get(2)?,
+                iban: row.get(3)?,
+                bban: row.get(4)?,
+                currency: row.get(5)?,
+                balance: row.get(6)?,
+                institution_id: row.get(7)?,
+                external_account_id: row.get(8)?,
+                data_source: row.get(9)?,
+                last_synced_at: row.get(10)?,
+                interest_rate: row.get(11)?,
+                has_zone_designation: row.get::<_, i32>(12)? != 0,
+                termination_date: row.get(13)?,
+                deposit_start_date: row.get(14)?,
+                auto_renew: row.get::<_, i32>(15)? != 0,
+                capitalization: row.get(16)?,
+                interest_tax_rate: row.get(17)?,
+                created_at: row.get(18)?,
+                updated_at: row.get(19)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut zone_stmt = conn.prepare(
+        "SELECT from_amount, to_amount, interest_rate FROM bank_account_zones
+         WHERE bank_account_id = ?1",
+    )?;
+    let mut result = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let mut zones: Vec<Zone> = zone_stmt
+            .query_map([&account.id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .map(|(from, to, rate)| Zone {
+                from: money::parse_or_zero(&from),
+                to: to.as_deref().and_then(money::parse),
+                rate: money::parse_or_zero(&rate),
+            })
+            .collect();
+        zones.sort_by(|a, b| a.from.cmp(&b.from));
+        result.push((account, zones));
+    }
+    Ok(result)
+}
+
+fn date_of(ts: i64) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp(ts, 0).map(|dt| dt.date_naive())
+}
+
+fn timestamp_of(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+fn add_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.checked_add_months(Months::new(months))
+}
+
+/// Yearly interest on `balance` at the account rate, or across its zones
+pub fn yearly_interest(account: &BankAccount, zones: &[Zone], balance: Decimal) -> Decimal {
+    if balance <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    if account.has_zone_designation && !zones.is_empty() {
+        return zones
+            .iter()
+            .map(|zone| {
+                let top = zone.to.map_or(balance, |to| to.min(balance));
+                (top - zone.from).max(Decimal::ZERO) * zone.rate / Decimal::ONE_HUNDRED
+            })
+            .sum();
+    }
+    let rate = account
+        .interest_rate
+        .as_deref()
+        .map(money::parse_or_zero)
+        .unwrap_or_default();
+    balance * rate / Decimal::ONE_HUNDRED
+}
+
+/// Length of a term deposit, repeated when it renews
+#[derive(Debug, Clone, Copy)]
+enum Term {
+    Months(u32),
+    Days(i64),
+}
+
+impl Term {
+    fn between(start: NaiveDate, end: NaiveDate) -> Term {
+        let months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+        if months > 0 && add_months(start, months as u32) == Some(end) {
+            Term::Months(months as u32)
+        } else {
+            Term::Days((end - start).num_days().max(1))
+        }
+    }
+
+    fn after(self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Term::Months(months) => add_months(date, months),
+            Term::Days(days) => date.checked_add_signed(chrono::Duration::days(days)),
+        }
+    }
+}
+
+/// A capitalization date; `maturity` marks the end of a deposit term
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Event {
+    date: NaiveDate,
+    maturity: bool,
+}
+
+/// Parsed interest terms of an account
+struct Terms {
+    start: NaiveDate,
+    maturity: Option<NaiveDate>,
+    term: Option<Term>,
+    auto_renew: bool,
+    /// Months between capitalizations within a term; `None` capitalizes at maturity only
+    step: Option<u32>,
+    tax: Decimal,
+}
+
+impl Terms {
+    fn of(account: &BankAccount) -> Option<Terms> {
+        let start = date_of(account.deposit_start_date.unwrap_or(account.created_at))?;
+        let maturity = account
+            .termination_date
+            .filter(|_| account.account_type == "term_deposit")
+            .and_then(date_of)
+            .filter(|maturity| *maturity > start);
+        let step = match (account.capitalization.as_str(), maturity) {
+            ("maturity", Some(_)) => None,
+            ("annual", _) | ("maturity", None) => Some(12),
+            _ => Some(1),
+        };
+        Some(Terms {
+            start,
+            maturity,
+            term: maturity.map(|maturity| Term::between(start, maturity)),
+            auto_renew: account.auto_renew,
+            step,
+            tax: money::parse_or_zero(&account.interest_tax_rate) / Decimal::ONE_HUNDRED,
+        })
+    }
+
+    /// Capitalization dates from the start up to and including the first on or after `until`
+    fn events(&self, until: NaiveDate) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut term_start = self.start;
+        let mut maturity = self.maturity;
+        let mut k = 1;
+        while events.len() < MAX_PERIODS as usize {
+            let regular = self
+                .step
+                .and_then(|months| add_months(term_start, months * k));
+            let event = match (regular, maturity) {
+                (Some(date), Some(end)) if date < end => Event {
+                    date,
+                    maturity: false,
+                },
+                (_, Some(end)) => Event {
+                    date: end,
+                    maturity: true,
+                },
+                (Some(date), None) => Event {
+                    date,
+                    maturity: false,
+                },
+                (None, None) => break,
+            };
+            events.push(event);
+            if event.date >= until {
+                break;
+            }
+            if event.maturity {
+                if !self.auto_renew {
+                    break;
+                }
+                term_start = event.date;
+                maturity = self.term.and_then(|term| term.after(event.date));
+                k = 1;
+            } else {
+                k += 1;
+            }
+        }
+        events
+    }
+
+    /// Gross interest on `balance` from `from` to `to`, and the tax withheld on it
+    fn interest(
+        &self,
+        account: &BankAccount,
+        zones: &[Zone],
+        balance: Decimal,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> (Decimal, Decimal) {
+        let days = (to - from).num_days().max(0);
+        let gross = money::round(
+            yearly_interest(account, zones, balance) * Decimal::from(days) / Decimal::from(365),
+            &account.currency,
+        );
+        (gross, money::round(gross * self.tax, &account.currency))
+    }
+}
+
+/// Capitalizations and payouts of an account after `now` and before `to`,
+/// compounding net interest into the balance
+pub fn payments(account: &BankAccount, zones: &[Zone], now: i64, to: i64) -> Vec<InterestPayment> {
+    let (Some(terms), Some(today), Some(end)) = (Terms::of(account), date_of(now), date_of(to))
+    else {
+        return Vec::new();
+    };
+    let currency = account.currency.as_str();
+    let mut balance = money::parse_or_zero(&account.balance);
+    let mut period_start = terms.start;
+    let mut payments = Vec::new();
+
+    for event in terms.events(end) {
+        if event.date <= today {
+            period_start = event.date;
+            if event.maturity && !terms.auto_renew {
+                break;
+            }
+            continue;
+        }
+        if event.date >= end {
+            break;
+        }
+        let (gross, tax) = terms.interest(account, zones, balance, period_start, event.date);
+        let net = gross - tax;
+        let payout = event.maturity && !terms.auto_renew;
+        let amount = if payout { balance + net } else { net };
+        payments.push(InterestPayment {
+            account_id: account.id.clone(),
+            account_name: account.name.clone(),
+            date: timestamp_of(event.date),
+            kind: if payout { "maturity" } else { "interest" }.to_string(),
+            gross_interest: money::format(gross, currency),
+            tax: money::format(tax, currency),
+            net_interest: money::format(net, currency),
+            amount: money::format(amount, currency),
+            currency: account.currency.clone(),
+            amount_czk: money::to_f64(money::to_czk(amount, currency)),
+        });
+        if payout {
+            break;
+        }
+        balance += net;
+        period_start = event.date;
+    }
+    payments
+}
+
+/// Interest accrued on an account as of `now`, with its next capitalization and maturity payout
+pub fn accrual(account: &BankAccount, zones: &[Zone], now: i64) -> InterestAccrual {
+    let currency = account.currency.as_str();
+    let balance = money::parse_or_zero(&account.balance);
+    let yearly = yearly_interest(account, zones, balance);
+    let effective_rate = if balance > Decimal::ZERO {
+        money::to_f64(yearly / balance * Decimal::ONE_HUNDRED)
+    } else {
+        0.0
+    };
+    let upcoming = payments(account, zones, now, now + 365 * 86400);
+    let mut result = InterestAccrual {
+        account_id: account.id.clone(),
+        account_name: account.name.clone(),
+        currency: account.currency.clone(),
+        effective_rate,
+        accrued_interest: money::format(Decimal::ZERO, currency),
+        accrued_tax: money::format(Decimal::ZERO, currency),
+        accrued_net: money::format(Decimal::ZERO, currency),
+        last_capitalization: account.deposit_start_date.unwrap_or(account.created_at),
+        next_capitalization: None,
+        maturity_date: None,
+        maturity_payout: None,
+        projected_yearly_interest: money::format(
+            upcoming
+                .iter()
+                .map(|payment| money::parse_or_zero(&payment.net_interest))
+                .sum(),
+            currency,
+        ),
+    };
+    let (Some(terms), Some(today)) = (Terms::of(account), date_of(now)) else {
+        return result;
+    };
+
+    let events = terms.events(today + chrono::Duration::days(1));
+    let last = events.iter().rev().find(|event| event.date <= today);
+    if let Some(paid_out) = last.filter(|event| event.maturity && !terms.auto_renew) {
+        // Matured and paid out: nothing accrues any more
+        result.last_capitalization = timestamp_of(paid_out.date);
+        return result;
+    }
+    let from = last.map_or(terms.start, |event| event.date);
+    let (gross, tax) = terms.interest(account, zones, balance, from, today.max(from));
+    result.accrued_interest = money::format(gross, currency);
+    result.accrued_tax = money::format(tax, currency);
+    result.accrued_net = money::format(gross - tax, currency);
+    result.last_capitalization = timestamp_of(from);
+
+    // The next capitalization and the end of the current term, however far ahead
+    let ahead = terms.events(NaiveDate::MAX);
+    result.next_capitalization = ahead
+        .iter()
+        .find(|event| event.date > today)
+        .map(|event| timestamp_of(event.date));
+    if let Some(maturity) = ahead
+        .iter()
+        .find(|event| event.date > today && event.maturity)
+    {
+        let horizon = timestamp_of(maturity.date) + 86400;
+        let credited: Decimal = payments(account, zones, now, horizon)
+            .iter()
+            .map(|payment| money::parse_or_zero(&payment.net_interest))
+            .sum();
+        result.maturity_date = Some(timestamp_of(maturity.date));
+        result.maturity_payout = Some(money::format(balance + credited, currency));
+    }
+    result
+}
+
+/// Interest credits and term deposit payouts of all accounts in `[from, to)`, by date
+pub fn calendar(conn: &Connection, from: i64, to: i64) -> Result<Vec<InterestPayment>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut payments: Vec<InterestPayment> = interest_accounts(conn)?
+        .iter()
+        .flat_map(|(account, zones)| payments(account, zones, now, to))
+        .filter(|payment| payment.date >= from)
+        .collect();
+    payments.sort_by(|a, b| {
+        a.date
+            .cmp(&b.date)
+            .then(a.account_name.cmp(&b.account_name))
+    });
+    Ok(payments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(year: i32, month: u32, day: u32) -> i64 {
+        timestamp_of(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+    }
+
+    fn account(account_type: &str, rate: &str, capitalization: &str) -> BankAccount {
+        BankAccount {
+            id: "a1".into(),
+            name: "Deposit".into(),
+            account_type: account_type.into(),
+            iban: None,
+            bban: None,
+            currency: "CZK".into(),
+            balance: "100000".into(),
+            institution_id: None,
+            external_account_id: None,
+            data_source: "manual".into(),
+            last_synced_at: None,
+            interest_rate: Some(rate.into()),
+            has_zone_designation: false,
+            termination_date: None,
+            deposit_start_date: Some(ts(2024, 1, 1)),
+            auto_renew: false,
+            capitalization: capitalization.into(),
+            interest_tax_rate: DEFAULT_TAX_RATE.into(),
+            created_at: ts(2024, 1, 1),
+            updated_at: ts(2024, 1, 1),
+        }
+    }
+
+    #[test]
+    fn test_daily_accrual_with_withholding_tax() {
+        let savings = account("savings", "3.65", "monthly");
+        let accrual = accrual(&savings, &[], ts(2024, 3, 11));
+
+        // 10 days since the capitalization on 1 March at 10 CZK a day
+        assert_eq!(accrual.last_capitalization, ts(2024, 3, 1));
+        assert_eq!(accrual.next_capitalization, Some(ts(2024, 4, 1)));
+        assert_eq!(accrual.accrued_interest, "100.00");
+        assert_eq!(accrual.accrued_tax, "15.00");
+        assert_eq!(accrual.accrued_net, "85.00");
+        assert_eq!(accrual.maturity_date, None);
+    }
+
+    #[test]
+    fn test_tiered_zones() {
+        let mut savings = account("savings", "0", "monthly");
+        savings.has_zone_designation = true;
+        let zones = [
+            Zone {
+                from: Decimal::ZERO,
+                to: Some(Decimal::from(50000)),
+                rate: Decimal::from(4),
+            },
+            Zone {
+                from: Decimal::from(50000),
+                to: None,
+                rate: Decimal::ONE,
+            },
+        ];
+        assert_eq!(
+            yearly_interest(&savings, &zones, Decimal::from(100000)),
+            Decimal::from(2500)
+        );
+    }
+
+    #[test]
+    fn test_monthly_capitalization_compounds() {
+        let savings = account("savings", "3.65", "monthly");
+        let payments = payments(&savings, &[], ts(2024, 1, 15), ts(2024, 3, 15));
+
+        assert_eq!(payments.len(), 2);
+        // January: 31 days at 10 CZK, taxed 15 %
+        assert_eq!(payments[0].date, ts(2024, 2, 1));
+        assert_eq!(payments[0].gross_interest, "310.00");
+        assert_eq!(payments[0].net_interest, "263.50");
+        // February on the balance with January's net interest
+        assert_eq!(payments[1].gross_interest, "290.77");
+        assert!(payments.iter().all(|p| p.kind == "interest"));
+    }
+
+    #[test]
+    fn test_term_deposit_pays_out_at_maturity() {
+        let mut deposit = account("term_deposit", "3.65", "maturity");
+        deposit.termination_date = Some(ts(2024, 7, 1));
+        let payments = payments(&deposit, &[], ts(2024, 2, 1), ts(2025, 1, 1));
+
+        // 182 days at 10 CZK, paid out with the principal
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].kind, "maturity");
+        assert_eq!(payments[0].gross_interest, "1820.00");
+        assert_eq!(payments[0].amount, "101547.00");
+
+        let accrual = accrual(&deposit, &[], ts(2024, 2, 1));
+        assert_eq!(accrual.maturity_date, Some(ts(2024, 7, 1)));
+        assert_eq!(accrual.maturity_payout.as_deref(), Some("101547.00"));
+        assert_eq!(accrual.accrued_interest, "310.00");
+
+        // Nothing accrues after the payout
+        let matured = super::accrual(&deposit, &[], ts(2024, 8, 1));
+        assert_eq!(matured.accrued_interest, "0.00");
+        assert_eq!(matured.next_capitalization, None);
+    }
+
+    #[test]
+    fn test_term_deposit_renews_for_the_same_term() {
+        let mut deposit = account("term_deposit", "3.65", "maturity");
+        deposit.termination_date = Some(ts(2024, 4, 1));
+        deposit.auto_renew = true;
+        let payments = payments(&deposit, &[], ts(2024, 5, 1), ts(2025, 1, 1));
+
+        // Renewed on 1 April for three months at a time
+        let dates: Vec<i64> = payments.iter().map(|p| p.date).collect();
+        assert_eq!(dates, vec![ts(2024, 7, 1), ts(2024, 10, 1)]);
+        assert!(payments.iter().all(|p| p.kind == "interest"));
+
+        let accrual = accrual(&deposit, &[], ts(2024, 5, 1));
+        assert_eq!(accrual.last_capitalization, ts(2024, 4, 1));
+        assert_eq!(accrual.maturity_date, Some(ts(2024, 7, 1)));
+    }
+}
@@ -6,6 +6,8 @@
 
 use crate::error::{AppError, Result};
 use crate::models::{InsertInvestmentTransaction, InvestmentTransaction, StockInvestment};
+use crate::services::money::{self, Decimal};
+use crate::services::reporting::Reporting;
 use chrono::DateTime;
 use rusqlite::params_from_iter;
 use rusqlite::types::Value;
@@ -24,34 +26,26 @@ pub fn recalculate_investment_metrics(
         "SELECT type, quantity, price_per_unit, currency FROM investment_transactions WHERE investment_id = ?1"
     )?;
 
-    let txs: Vec<(String, f64, f64, String)> = stmt
+    let txs: Vec<(String, Decimal, Decimal, String)> = stmt
         .query_map([investment_id], |row| {
             let type_: String = row.get(0)?;
             let qty_str: String = row.get(1)?;
             let price_str: String = row.get(2)?;
             let currency: String = row.get(3)?;
 
-            let qty = qty_str
-                .split_whitespace()
-                .next()
-                .unwrap_or("0")
-                .parse::<f64>()
-                .unwrap_or(0.0);
-            let price = price_str
-                .split_whitespace()
-                .next()
-                .unwrap_or("0")
-                .parse::<f64>()
-                .unwrap_or(0.0);
-
-            Ok((type_, qty, price, currency))
+            Ok((
+                type_,
+                money::parse_or_zero(&qty_str),
+                money::parse_or_zero(&price_str),
+                currency,
+            ))
         })?
         .filter_map(|r| r.ok())
         .collect();
 
-    let mut total_quantity = 0.0;
-    let mut weighted_buy_sum = 0.0;
-    let mut weighted_buy_qty = 0.0;
+    let mut total_quantity = Decimal::ZERO;
+    let mut weighted_buy_sum = Decimal::ZERO;
+    let mut weighted_buy_qty = Decimal::ZERO;
 
     // Calculate average price in native currency (no conversion)
     // All transactions for an investment should have the same currency
@@ -65,17 +59,17 @@ pub fn recalculate_investment_metrics(
         }
     }
 
-    let average_price = if weighted_buy_qty > 0.0 {
+    let average_price = if weighted_buy_qty > Decimal::ZERO {
         weighted_buy_sum / weighted_buy_qty
     } else {
-        0.0
+        Decimal::ZERO
     };
 
     conn.execute(
         "UPDATE stock_investments SET quantity = ?1, average_price = ?2 WHERE id = ?3",
         rusqlite::params![
-            total_quantity.to_string(),
-            average_price.to_string(),
+            money::format_exact(total_quantity),
+            money::format_price(average_price),
             investment_id
         ],
     )?;
@@ -97,6 +91,7 @@ pub fn create_transaction_internal(
     price_per_unit: &str,
     currency: &str,
     transaction_date: i64,
+    account_id: Option<&str>,
 ) -> Result<InvestmentTransaction> {
     let tx_id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().timestamp();
@@ -104,8 +99,8 @@ pub fn create_transaction_internal(
 
     conn.execute(
         "INSERT INTO investment_transactions
-         (id, investment_id, type, ticker, company_name, quantity, price_per_unit, currency, transaction_date, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+         (id, investment_id, type, ticker, company_name, quantity, price_per_unit, currency, transaction_date, created_at, account_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
         rusqlite::params![
             tx_id,
             investment_id,
@@ -117,6 +112,7 @@ pub fn create_transaction_internal(
             currency_upper,
             transaction_date,
             now,
+            account_id,
         ],
     )?;
 
@@ -131,6 +127,7 @@ pub fn create_transaction_internal(
         currency: currency_upper,
         transaction_date,
         created_at: now,
+        account_id: account_id.map(|a| a.to_string()),
     })
 }
 
@@ -216,6 +213,8 @@ pub fn get_investment_by_ticker(
 
 /// Unified import logic for a single transaction
 /// Used by CSV import and creates both investment (if needed) and transaction
+/// `external_id` is the broker's order ID (used to skip re-imported statements)
+/// `account_id` is the brokerage account the trade belongs to
 /// Returns (description, transaction_date, ticker) on success
 #[allow(clippy::too_many_arguments)]
 pub fn import_single_transaction(
@@ -227,6 +226,8 @@ pub fn import_single_transaction(
     price_per_unit: &str,
     currency: &str,
     transaction_date: i64,
+    external_id: Option<&str>,
+    account_id: Option<&str>,
 ) -> Result<(String, i64, String)> {
     let ticker_upper = ticker.to_uppercase();
     let tx_type_lower = tx_type.to_lowercase();
@@ -288,7 +289,7 @@ pub fn import_single_transaction(
     };
 
     // Create transaction using the shared function
-    create_transaction_internal(
+    let tx = create_transaction_internal(
         conn,
         &investment_id,
         &ticker_upper,
@@ -298,8 +299,16 @@ pub fn import_single_transaction(
         price_per_unit,
         currency,
         transaction_date,
+        account_id,
     )?;
 
+    if let Some(external_id) = external_id {
+        conn.execute(
+            "UPDATE investment_transactions SET external_id = ?1 WHERE id = ?2",
+            rusqlite::params![external_id, tx.id],
+        )?;
+    }
+
     // Recalculate metrics
     recalculate_investment_metrics(conn, &investment_id)?;
 
@@ -355,6 +364,7 @@ pub fn create_investment_with_transaction(
             &tx.price_per_unit,
             &tx.currency,
             tx.transaction_date,
+            tx.account_id.as_deref(),
         )?;
 
         // Recalculate metrics from the transaction
@@ -397,6 +407,7 @@ pub fn add_transaction_to_investment(
         &data.price_per_unit,
         &data.currency,
         data.transaction_date,
+        data.account_id.as_deref(),
     )?;
 
     recalculate_investment_metrics(conn, investment_id)?;
@@ -462,10 +473,10 @@ fn ts_to_date_str(ts: i64) -> String {
 /// Uses binary search — O(log N) — so entries must be sorted ascending by timestamp
 /// (as guaranteed by the SQL `ORDER BY ticker, recorded_at ASC` in `compute_twr_for_tickers`).
 fn lookup_ticker_at(
-    history: &HashMap<String, Vec<(i64, f64, f64)>>,
+    history: &HashMap<String, Vec<(i64, Decimal, Decimal)>>,
     ticker: &str,
     target_ts: i64,
-) -> (f64, f64) {
+) -> (Decimal, Decimal) {
     history
         .get(ticker)
         .and_then(|entries| {
@@ -479,7 +490,7 @@ fn lookup_ticker_at(
                 None
             }
         })
-        .unwrap_or((0.0, 0.0))
+        .unwrap_or((Decimal::ZERO, Decimal::ZERO))
 }
 
 /// Compute daily chain-linked time-weighted return for a group of tickers.
@@ -487,6 +498,8 @@ fn lookup_ticker_at(
 /// Returns one `TwrDataPoint` per calendar day from `from_ts` to `to_ts` inclusive.
 /// The first point is always `{ date: from_ts, twr: 0.0 }`.
 /// Cash flows are estimated from quantity changes in `stock_value_history`.
+/// Values are taken in the reporting currency at the rate of their day, so the
+/// return is the one measured in that currency.
 ///
 /// `from_ts` and `to_ts` must be Unix timestamps (seconds, midnight UTC).
 pub fn compute_twr_for_tickers(
@@ -508,11 +521,11 @@ pub fn compute_twr_for_tickers(
     let main_placeholders: Vec<String> = (3..=n + 2).map(|i| format!("?{i}")).collect();
     let pre_placeholders: Vec<String> = (n + 3..=2 * n + 2).map(|i| format!("?{i}")).collect();
     let sql = format!(
-        "SELECT ticker, recorded_at, CAST(value_czk AS REAL), CAST(quantity AS REAL) \
+        "SELECT ticker, recorded_at, value_czk, quantity \
          FROM stock_value_history \
          WHERE ticker IN ({main}) AND recorded_at >= ?1 AND recorded_at <= ?2 \
          UNION ALL \
-         SELECT s.ticker, s.recorded_at, CAST(s.value_czk AS REAL), CAST(s.quantity AS REAL) \
+         SELECT s.ticker, s.recorded_at, s.value_czk, s.quantity \
          FROM stock_value_history s \
          WHERE s.ticker IN ({pre}) \
            AND s.recorded_at = ( \
@@ -530,20 +543,25 @@ pub fn compute_twr_for_tickers(
         .chain(tickers.iter().map(|t| Value::Text(t.clone()))) // pre-range
         .collect();
 
-    let mut history: HashMap<String, Vec<(i64, f64, f64)>> = HashMap::new();
+    let reporting = Reporting::load(conn)?;
+    let mut history: HashMap<String, Vec<(i64, Decimal, Decimal)>> = HashMap::new();
     {
         let mut stmt = conn.prepare(&sql)?;
         let rows = stmt.query_map(params_from_iter(params), |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, i64>(1)?,
-                row.get::<_, f64>(2)?,
-                row.get::<_, f64>(3)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
             ))
         })?;
         for row in rows {
             let (ticker, ts, val, qty) = row?;
-            history.entry(ticker).or_default().push((ts, val, qty));
+            history.entry(ticker).or_default().push((
+                ts,
+                reporting.amount_decimal_on(money::parse_or_zero(&val), ts),
+                money::parse_or_zero(&qty),
+            ));
         }
     }
 
@@ -564,9 +582,9 @@ pub fn compute_twr_for_tickers(
     while day <= to_ts {
         day_index += 1;
         let prev_day = day - 86400;
-        let mut v_curr = 0.0f64;
-        let mut v_prev = 0.0f64;
-        let mut cf = 0.0f64;
+        let mut v_curr = Decimal::ZERO;
+        let mut v_prev = Decimal::ZERO;
+        let mut cf = Decimal::ZERO;
 
         for ticker in tickers {
             let (val_curr, qty_curr) = lookup_ticker_at(&history, ticker, day);
@@ -575,21 +593,21 @@ pub fn compute_twr_for_tickers(
             v_prev += val_prev;
 
             let delta_qty = qty_curr - qty_prev;
-            if delta_qty.abs() > 1e-9 {
-                let price_czk = if qty_curr > 1e-9 {
+            if !delta_qty.is_zero() {
+                let price_czk = if qty_curr > Decimal::ZERO {
                     val_curr / qty_curr
-                } else if qty_prev > 1e-9 {
+                } else if qty_prev > Decimal::ZERO {
                     val_prev / qty_prev
                 } else {
-                    0.0
+                    Decimal::ZERO
                 };
                 cf += delta_qty * price_czk;
             }
         }
 
         let denominator = v_prev + cf;
-        if denominator > 1e-9 {
-            let daily_r = (v_curr - v_prev - cf) / denominator;
+        if denominator > Decimal::ZERO {
+            let daily_r = money::to_f64((v_curr - v_prev - cf) / denominator);
             twr_factor *= 1.0 + daily_r;
         }
 
@@ -687,7 +705,8 @@ mod tests {
                 price_per_unit TEXT NOT NULL,
                 currency TEXT NOT NULL,
                 transaction_date INTEGER NOT NULL,
-                created_at INTEGER NOT NULL
+                created_at INTEGER NOT NULL,
+                account_id TEXT
             );
             "#,
         )
@@ -782,16 +801,18 @@ mod tests {
     #[test]
     fn test_import_single_transaction_invalid_type() {
         let conn = setup_test_db();
-        let result =
-            import_single_transaction(&conn, "AAPL", "Apple", "hold", "10", "150", "USD", 0);
+        let result = import_single_transaction(
+            &conn, "AAPL", "Apple", "hold", "10", "150", "USD", 0, None, None,
+        );
         assert!(result.is_err());
     }
 
     #[test]
     fn test_import_single_transaction_sell_without_position() {
         let conn = setup_test_db();
-        let result =
-            import_single_transaction(&conn, "GOOG", "Google", "sell", "5", "100", "USD", 0);
+        let result = import_single_transaction(
+            &conn, "GOOG", "Google", "sell", "5", "100", "USD", 0, None, None,
+        );
         assert!(result.is_err());
     }
 
@@ -807,6 +828,8 @@ mod tests {
             "500",
             "USD",
             1_700_000_000,
+            None,
+            None,
         );
         assert!(result.is_ok());
         let (desc, _date, ticker) = result.unwrap();
@@ -817,8 +840,12 @@ mod tests {
     #[test]
     fn test_import_single_transaction_currency_mismatch() {
         let conn = setup_test_db();
-        import_single_transaction(&conn, "AMD", "AMD", "buy", "10", "100", "USD", 0).unwrap();
-        let result = import_single_transaction(&conn, "AMD", "AMD", "buy", "5", "90", "EUR", 1);
+        import_single_transaction(
+            &conn, "AMD", "AMD", "buy", "10", "100", "USD", 0, None, None,
+        )
+        .unwrap();
+        let result =
+            import_single_transaction(&conn, "AMD", "AMD", "buy", "5", "90", "EUR", 1, None, None);
         assert!(result.is_err());
     }
 
@@ -0,0 +1,971 @@
+//! Loan amortization schedules
+//!
+//! Installments fall monthly from the start date and interest accrues at
+//! rate / 12 on the outstanding principal. Annuity loans pay a fixed
+//! installment (the stored monthly payment, else the one repaying the loan by
+//! its end date), linear loans an equal share of the principal plus interest,
+//! and interest-only loans the interest with the principal due at the end.
+//!
+//! Installments matched in bank transactions (by variable symbol, or by
+//! counterparty IBAN with the scheduled amount near a due date) replace the
+//! scheduled amount of their month, so extra or missed payments move the
+//! outstanding principal. Each transaction pays one loan at most.
+//!
+//! Scenarios replay the remaining installments from today with extra
+//! prepayments (shortening the term or lowering the installment) and a new
+//! rate from the end of the rate fixation, when the bank recalculates the
+//! installment. Prepayments on the fixation end are fee-free; otherwise only a
+//! yearly share of the outstanding principal is.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    Loan, LoanInstallment, LoanScenario, LoanScenarioResult, LoanSchedule, LoanSimulation,
+};
+use crate::services::money::{self, Decimal};
+use chrono::{Months, NaiveDate};
+use rusqlite::{Connection, Row};
+use std::collections::HashMap;
+
+/// Supported repayment types
+pub const REPAYMENT_TYPES: [&str; 3] = ["annuity", "linear", "interest_only"];
+
+/// Supported prepayment strategies
+pub const PREPAYMENT_STRATEGIES: [&str; 2] = ["shorten_term", "lower_payment"];
+
+/// Columns read by `loan_from_row`
+pub const LOAN_COLUMNS: &str =
+    "id, name, principal, currency, interest_rate, interest_rate_validity_date, monthly_payment,
+     start_date, end_date, repayment_type, payment_iban, payment_variable_symbol, created_at,
+     updated_at";
+
+/// Upper bound on generated installments (50 years)
+const MAX_MONTHS: u32 = 600;
+
+/// Months shown of a schedule that never repays the principal
+const OPEN_ENDED_MONTHS: u32 = 12;
+
+/// Days between a due date and the booking of a debit matched by the IBAN alone
+const PAYMENT_WINDOW_DAYS: i64 = 10;
+
+/// Deviation from the scheduled installment allowed for a debit matched by the IBAN alone
+const PAYMENT_TOLERANCE_PERCENT: i64 = 1;
+
+const DAY: i64 = 86400;
+
+/// Booking date and amount (in the loan currency) of an installment paid from a bank account
+pub type Payment = (i64, Decimal);
+
+pub fn loan_from_row(row: &Row) -> rusqlite::Result<Loan> {
+    Ok(Loan {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        principal: row.get(2)?,
+        currency: row.get(3)?,
+        interest_rate: row.get(4)?,
+        interest_rate_validity_date: row.get(5)?,
+        monthly_payment: row.get(6)?,
+        start_date: row.get(7)?,
+        end_date: row.get(8)?,
+        repayment_type: row.get(9)?,
+        payment_iban: row.get(10)?,
+        payment_variable_symbol: row.get(11)?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+    })
+}
+
+pub fn get_loans(conn: &Connection) -> Result<Vec<Loan>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM loans ORDER BY name", LOAN_COLUMNS))?;
+    let loans = stmt
+        .query_map([], loan_from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(loans)
+}
+
+pub fn get_loan(conn: &Connection, id: &str) -> Result<Loan> {
+    conn.query_row(
+        &format!("SELECT {} FROM loans WHERE id = ?1", LOAN_COLUMNS),
+        [id],
+        loan_from_row,
+    )
+    .map_err(|_| AppError::NotFound("Loan not found".into()))
+}
+
+fn date_of(ts: i64) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp(ts, 0).map(|dt| dt.date_naive())
+}
+
+fn timestamp_of(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+fn add_months(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    date.checked_add_months(Months::new(months))
+}
+
+fn normalize_iban(iban: &str) -> String {
+    iban.chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn normalize_symbol(symbol: &str) -> &str {
+    symbol.trim().trim_start_matches('0')
+}
+
+/// Installments of the loan found in booked bank debits since its start,
+/// oldest first (see `payments_by_loan`)
+pub fn matched_payments(conn: &Connection, loan: &Loan) -> Result<Vec<Payment>> {
+    let mut loans = get_loans(conn)?;
+    match loans.iter_mut().find(|stored| stored.id == loan.id) {
+        Some(stored) => *stored = loan.clone(),
+        None => loans.push(loan.clone()),
+    }
+    Ok(payments_by_loan(conn, &loans)?
+        .remove(&loan.id)
+        .unwrap_or_default())
+}
+
+/// Installments of the loans found in booked bank debits, oldest first, by loan id
+///
+/// Each debit is credited to one loan at most. Loans with a variable symbol
+/// take the debits carrying it first; a debit matched by the counterparty IBAN
+/// alone must also be booked within `PAYMENT_WINDOW_DAYS` of a due date and
+/// match the scheduled installment within `PAYMENT_TOLERANCE_PERCENT`.
+pub fn payments_by_loan(
+    conn: &Connection,
+    loans: &[Loan],
+) -> Result<HashMap<String, Vec<Payment>>> {
+    let mut payments: HashMap<String, Vec<Payment>> = HashMap::new();
+    let references: Vec<(&Loan, Option<String>, Option<&str>)> = loans
+        .iter()
+        .map(|loan| {
+            let iban = loan
+                .payment_iban
+                .as_deref()
+                .map(normalize_iban)
+                .filter(|iban| !iban.is_empty());
+            let symbol = loan
+                .payment_variable_symbol
+                .as_deref()
+                .map(normalize_symbol)
+                .filter(|symbol| !symbol.is_empty());
+            (loan, iban, symbol)
+        })
+        .filter(|(_, iban, symbol)| iban.is_some() || symbol.is_some())
+        .collect();
+    if references.is_empty() {
+        return Ok(payments);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT booking_date, amount, currency, counterparty_iban, variable_symbol
+         FROM bank_transactions
+         WHERE tx_type = 'debit' AND status = 'booked'
+         ORDER BY booking_date, id",
+    )?;
+    let debits: Vec<(i64, Decimal, String, Option<String>, Option<String>)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                money::parse_or_zero(&row.get::<_, String>(1)?).abs(),
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?
+                    .map(|iban| normalize_iban(&iban)),
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    let last_booking = debits.last().map_or(0, |(date, ..)| *date);
+    let mut consumed = vec![false; debits.len()];
+
+    // Variable symbols identify the loan, so those loans pick their debits first
+    let (by_symbol, by_iban): (Vec<_>, Vec<_>) = references
+        .into_iter()
+        .partition(|(_, _, symbol)| symbol.is_some());
+    for (loan, iban, symbol) in by_symbol {
+        for (i, (date, amount, currency, tx_iban, tx_symbol)) in debits.iter().enumerate() {
+            if consumed[i] || *date < loan.start_date {
+                continue;
+            }
+            if iban.is_some() && tx_iban != &iban {
+                continue;
+            }
+            if tx_symbol.as_deref().map(normalize_symbol) != symbol {
+                continue;
+            }
+            consumed[i] = true;
+            payments.entry(loan.id.clone()).or_default().push((
+                *date,
+                money::convert_between(*amount, currency, &loan.currency),
+            ));
+        }
+    }
+
+    for (loan, iban, _) in by_iban {
+        let scheduled = date_of(loan.start_date)
+            .map(|start| installments(loan, start, &[], last_booking))
+            .unwrap_or_default();
+        for (i, (date, amount, currency, tx_iban, _)) in debits.iter().enumerate() {
+            if consumed[i] || *date < loan.start_date || tx_iban != &iban {
+                continue;
+            }
+            let amount = money::convert_between(*amount, currency, &loan.currency);
+            let due = scheduled.iter().find(|installment| {
+                (installment.date - date).abs() <= PAYMENT_WINDOW_DAYS * DAY
+                    && is_scheduled_amount(amount, money::parse_or_zero(&installment.payment))
+            });
+            if due.is_none() {
+                continue;
+            }
+            consumed[i] = true;
+            payments
+                .entry(loan.id.clone())
+                .or_default()
+                .push((*date, amount));
+        }
+    }
+    Ok(payments)
+}
+
+/// Whether a debit pays a scheduled installment, within `PAYMENT_TOLERANCE_PERCENT`
+fn is_scheduled_amount(amount: Decimal, scheduled: Decimal) -> bool {
+    scheduled > Decimal::ZERO
+        && (amount - scheduled).abs() * Decimal::ONE_HUNDRED
+            <= scheduled * Decimal::from(PAYMENT_TOLERANCE_PERCENT)
+}
+
+/// Parsed repayment terms of a loan
+struct Terms {
+    principal: Decimal,
+    monthly_rate: Decimal,
+    /// Annuity installment, or the principal share of a linear installment
+    installment: Decimal,
+    /// Number of installments, when the end date is known
+    term: Option<u32>,
+    repayment_type: String,
+    currency: String,
+}
+
+impl Terms {
+    fn of(loan: &Loan, start: NaiveDate) -> Terms {
+        let principal = money::parse_or_zero(&loan.principal);
+        let monthly_rate = money::parse_or_zero(&loan.interest_rate) / Decimal::from(1200);
+        let monthly_payment = money::parse_or_zero(&loan.monthly_payment);
+        let term = loan.end_date.and_then(date_of).map(|end| {
+            (1..=MAX_MONTHS)
+                .take_while(|k| add_months(start, *k).is_some_and(|due| due <= end))
+                .count()
+                .max(1) as u32
+        });
+
+        let installment = match (loan.repayment_type.as_str(), term) {
+            ("linear", Some(n)) => money::round(principal / Decimal::from(n), &loan.currency),
+            ("linear", None) => monthly_payment - principal * monthly_rate,
+            ("interest_only", _) => Decimal::ZERO,
+            (_, Some(n)) if monthly_payment.is_zero() => {
+                money::round(annuity(principal, monthly_rate, n), &loan.currency)
+            }
+            _ => monthly_payment,
+        };
+
+        Terms {
+            principal,
+            monthly_rate,
+            installment,
+            term,
+            repayment_type: loan.repayment_type.clone(),
+            currency: loan.currency.clone(),
+        }
+    }
+
+    /// Scheduled principal of installment `k` (from 1)
+    fn principal_due(&self, k: u32, balance: Decimal, interest: Decimal) -> Decimal {
+        if self.term.is_some_and(|n| k >= n) {
+            return balance;
+        }
+        let due = match self.repayment_type.as_str() {
+            "linear" => self.installment,
+            "interest_only" => Decimal::ZERO,
+            _ => self.installment - interest,
+        };
+        due.max(Decimal::ZERO).min(balance)
+    }
+}
+
+/// Installment repaying `principal` in `n` equal monthly payments
+fn annuity(principal: Decimal, monthly_rate: Decimal, n: u32) -> Decimal {
+    if monthly_rate.is_zero() {
+        return principal / Decimal::from(n);
+    }
+    let mut growth = Decimal::ONE;
+    for _ in 0..n {
+        growth *= Decimal::ONE + monthly_rate;
+    }
+    principal * monthly_rate * growth / (growth - Decimal::ONE)
+}
+
+/// Amortization schedule of a loan as of `now`, with bank `payments` (oldest
+/// first) replacing the scheduled installment of the month they were booked in
+pub fn schedule(loan: &Loan, payments: &[Payment], now: i64) -> LoanSchedule {
+    let installments = date_of(loan.start_date)
+        .map(|start| installments(loan, start, payments, now))
+        .unwrap_or_default();
+
+    let mut paid_principal = Decimal::ZERO;
+    let mut paid_interest = Decimal::ZERO;
+    let mut remaining_interest = Decimal::ZERO;
+    let mut matched = 0;
+    for installment in &installments {
+        let interest = money::parse_or_zero(&installment.interest);
+        if installment.date <= now {
+            paid_principal += money::parse_or_zero(&installment.principal);
+            paid_interest += interest;
+        } else {
+            remaining_interest += interest;
+        }
+        if installment.status == "paid" {
+            matched += 1;
+        }
+    }
+
+    let outstanding = balance_on(loan, &installments, now);
+    let payoff_date = installments
+        .last()
+        .filter(|last| money::parse_or_zero(&last.balance).is_zero())
+        .map(|last| last.date);
+
+    LoanSchedule {
+        loan_id: loan.id.clone(),
+        outstanding_principal: money::format(outstanding, &loan.currency),
+        outstanding_principal_czk: money::to_f64(money::to_czk(outstanding, &loan.currency)),
+        paid_principal: money::format(paid_principal, &loan.currency),
+        paid_interest: money::format(paid_interest, &loan.currency),
+        remaining_interest: money::format(remaining_interest, &loan.currency),
+        payoff_date,
+        matched_payments: matched,
+        installments,
+    }
+}
+
+fn installments(
+    loan: &Loan,
+    start: NaiveDate,
+    payments: &[Payment],
+    now: i64,
+) -> Vec<LoanInstallment> {
+    let terms = Terms::of(loan, start);
+    let horizon = date_of(now)
+        .and_then(|today| add_months(today, OPEN_ENDED_MONTHS))
+        .unwrap_or(start);
+    let currency = terms.currency.as_str();
+
+    let mut rows = Vec::new();
+    let mut balance = terms.principal;
+    let mut previous = loan.start_date;
+    for k in 1..=MAX_MONTHS {
+        if balance <= Decimal::ZERO {
+            break;
+        }
+        let Some(due) = add_months(start, k) else {
+            break;
+        };
+        let date = timestamp_of(due);
+
+        let interest = money::round(balance * terms.monthly_rate, currency);
+        let scheduled = terms.principal_due(k, balance, interest);
+        if terms.term.is_none() && scheduled.is_zero() && due > horizon {
+            break;
+        }
+
+        let paid: Vec<Decimal> = payments
+            .iter()
+            .filter(|(booked, _)| *booked > previous && *booked <= date)
+            .map(|(_, amount)| *amount)
+            .collect();
+        let (payment, principal, status) = if !paid.is_empty() {
+            let payment: Decimal = paid.into_iter().sum();
+            let principal = (payment - interest).min(balance);
+            (payment, principal, "paid")
+        } else {
+            let status = if date <= now { "assumed" } else { "upcoming" };
+            (interest + scheduled, scheduled, status)
+        };
+        balance -= principal;
+
+        rows.push(LoanInstallment {
+            date,
+            payment: money::format(payment, currency),
+            interest: money::format(interest, currency),
+            principal: money::format(principal, currency),
+            balance: money::format(balance, currency),
+            status: status.to_string(),
+        });
+        previous = date;
+    }
+    rows
+}
+
+/// Outstanding principal after the installments due by `day_ts`; nothing before the loan started
+pub fn balance_on(loan: &Loan, installments: &[LoanInstallment], day_ts: i64) -> Decimal {
+    if day_ts < loan.start_date {
+        return Decimal::ZERO;
+    }
+    installments
+        .iter()
+        .take_while(|installment| installment.date <= day_ts)
+        .last()
+        .map(|installment| money::parse_or_zero(&installment.balance))
+        .unwrap_or_else(|| money::parse_or_zero(&loan.principal))
+}
+
+/// Every loan with its installments as of `now`, for reading balances of
+/// many days with `balance_on`
+pub fn installment_plans(conn: &Connection, now: i64) -> Result<Vec<(Loan, Vec<LoanInstallment>)>> {
+    let loans = get_loans(conn)?;
+    let mut payments = payments_by_loan(conn, &loans)?;
+    Ok(loans
+        .into_iter()
+        .map(|loan| {
+            let payments = payments.remove(&loan.id).unwrap_or_default();
+            let installments = schedule(&loan, &payments, now).installments;
+            (loan, installments)
+        })
+        .collect())
+}
+
+/// Outstanding principal of every loan on `day_ts`, in the loan currency
+pub fn outstanding_balances(conn: &Connection, day_ts: i64) -> Result<Vec<(Loan, Decimal)>> {
+    Ok(installment_plans(conn, day_ts)?
+        .into_iter()
+        .map(|(loan, installments)| {
+            let balance = balance_on(&loan, &installments, day_ts);
+            (loan, balance)
+        })
+        .collect())
+}
+
+/// Months an annuity `installment` needs to repay `balance`, if it covers the interest
+fn months_to_repay(balance: Decimal, monthly_rate: Decimal, installment: Decimal) -> Option<u32> {
+    let mut balance = balance;
+    for month in 1..=MAX_MONTHS {
+        let principal = installment - balance * monthly_rate;
+        if principal <= Decimal::ZERO {
+            return None;
+        }
+        balance -= principal;
+        if balance <= Decimal::ZERO {
+            return Some(month);
+        }
+    }
+    None
+}
+
+fn percent(value: &Option<String>) -> Option<Decimal> {
+    value
+        .as_deref()
+        .and_then(money::parse)
+        .map(|p| p / Decimal::ONE_HUNDRED)
+}
+
+/// Where the loan stands today
+struct Position {
+    start: NaiveDate,
+    /// Index of the next installment (from 1)
+    next: u32,
+    balance: Decimal,
+    /// Installments left in the current schedule
+    remaining: u32,
+    /// End of the rate fixation, if it is still ahead
+    refixation: Option<i64>,
+}
+
+/// Replays the remaining installments of a loan under a scenario
+fn run_scenario(
+    loan: &Loan,
+    position: &Position,
+    scenario: &LoanScenario,
+) -> (LoanScenarioResult, Decimal) {
+    let mut terms = Terms::of(loan, position.start);
+    let currency = loan.currency.as_str();
+    let lower_payment = scenario.strategy.as_deref() == Some("lower_payment");
+    let monthly_prepayment = scenario
+        .monthly_prepayment
+        .as_deref()
+        .map(money::parse_or_zero)
+        .unwrap_or_default();
+    // Without a fee-free limit the whole prepayment is charged
+    let fee_free = percent(&scenario.fee_free_percent).unwrap_or_default();
+    let fee = percent(&scenario.prepayment_fee_percent).unwrap_or_default();
+    let new_rate = scenario
+        .refixation_rate
+        .as_deref()
+        .and_then(money::parse)
+        .map(|rate| rate / Decimal::from(1200));
+    let previous_due = |k: u32| {
+        add_months(position.start, k - 1)
+            .map(timestamp_of)
+            .unwrap_or_default()
+    };
+
+    let mut balance = position.balance;
+    let mut refixed = false;
+    let mut allowance_from = previous_due(position.next);
+    let mut allowance = fee_free * balance;
+    let mut total_interest = Decimal::ZERO;
+    let mut prepaid = Decimal::ZERO;
+    let mut fees = Decimal::ZERO;
+    let mut next_payment = None;
+    let mut payment_after_refixation = None;
+    let mut payoff_date = None;
+    let mut count = 0;
+
+    for (i, k) in (position.next..position.next + MAX_MONTHS).enumerate() {
+        if balance <= Decimal::ZERO {
+            break;
+        }
+        let Some(due) = add_months(position.start, k) else {
+            break;
+        };
+        let date = timestamp_of(due);
+        let previous = previous_due(k);
+        let months_left = position.remaining.saturating_sub(i as u32).max(1);
+
+        // The new rate applies from the first installment after the fixation ends
+        if let Some(rate) = new_rate.filter(|_| !refixed) {
+            if !position.refixation.is_some_and(|end| date <= end) {
+                refixed = true;
+                if terms.repayment_type == "annuity" {
+                    let months = if lower_payment {
+                        months_left
+                    } else {
+                        months_to_repay(balance, terms.monthly_rate, terms.installment)
+                            .unwrap_or(months_left)
+                    };
+                    terms.installment = money::round(annuity(balance, rate, months), currency);
+                }
+                terms.monthly_rate = rate;
+            }
+        }
+
+        let interest = money::round(balance * terms.monthly_rate, currency);
+        let principal = terms.principal_due(k, balance, interest);
+        balance -= principal;
+        total_interest += interest;
+        count += 1;
+        let payment = money::format(interest + principal, currency);
+        if next_payment.is_none() {
+            next_payment = Some(payment.clone());
+        }
+        if refixed && payment_after_refixation.is_none() {
+            payment_after_refixation = Some(payment);
+        }
+
+        let extra: Decimal = scenario
+            .one_off_prepayments
+            .iter()
+            .filter(|p| p.date > previous && p.date <= date)
+            .map(|p| money::parse_or_zero(&p.amount))
+            .sum::<Decimal>()
+            + monthly_prepayment;
+        let extra = extra.min(balance);
+        if extra > Decimal::ZERO {
+            // The fee-free limit renews every year
+            if date_of(allowance_from)
+                .and_then(|from| add_months(from, 12))
+                .is_some_and(|renewal| due > renewal)
+            {
+                allowance_from = previous;
+                allowance = fee_free * balance;
+            }
+            let at_refixation = position
+                .refixation
+                .is_some_and(|end| end > previous && end <= date);
+            if !at_refixation {
+                let charged = (extra - allowance).max(Decimal::ZERO);
+                fees += money::round(charged * fee, currency);
+                allowance = (allowance - extra).max(Decimal::ZERO);
+            }
+            balance -= extra;
+            prepaid += extra;
+
+            if lower_payment && months_left > 1 {
+                terms.installment = match terms.repayment_type.as_str() {
+                    "linear" => money::round(balance / Decimal::from(months_left - 1), currency),
+                    "interest_only" => terms.installment,
+                    _ => money::round(
+                        annuity(balance, terms.monthly_rate, months_left - 1),
+                        currency,
+                    ),
+                };
+            }
+        }
+
+        if balance <= Decimal::ZERO {
+            payoff_date = Some(date);
+        }
+    }
+
+    let result = LoanScenarioResult {
+        name: scenario.name.clone(),
+        monthly_payment: next_payment.unwrap_or_else(|| money::format(Decimal::ZERO, currency)),
+        payment_after_refixation,
+        total_interest: money::format(total_interest, currency),
+        interest_saved: money::format(Decimal::ZERO, currency),
+        total_prepaid: money::format(prepaid, currency),
+        fees: money::format(fees, currency),
+        payoff_date,
+        installments: count,
+    };
+    (result, total_interest)
+}
+
+/// Compares `scenarios` for a loan against its current schedule, from `now` until payoff
+pub fn simulate(
+    loan: &Loan,
+    payments: &[Payment],
+    scenarios: &[LoanScenario],
+    now: i64,
+) -> LoanSimulation {
+    let current = schedule(loan, payments, now);
+    let balance = money::parse_or_zero(&current.outstanding_principal);
+    let start = date_of(loan.start_date).unwrap_or_default();
+    let position = Position {
+        start,
+        next: current
+            .installments
+            .iter()
+            .filter(|installment| installment.date <= now)
+            .count() as u32
+            + 1,
+        balance,
+        remaining: current
+            .installments
+            .iter()
+            .filter(|installment| installment.date > now)
+            .count() as u32,
+        refixation: loan.interest_rate_validity_date.filter(|end| *end > now),
+    };
+    let baseline_scenario = LoanScenario {
+        name: "baseline".into(),
+        ..Default::default()
+    };
+    let (baseline, baseline_interest) = run_scenario(loan, &position, &baseline_scenario);
+    let scenarios = scenarios
+        .iter()
+        .map(|scenario| {
+            let (mut result, interest) = run_scenario(loan, &position, scenario);
+            result.interest_saved = money::format(baseline_interest - interest, &loan.currency);
+            result
+        })
+        .collect();
+
+    LoanSimulation {
+        loan_id: loan.id.clone(),
+        currency: loan.currency.clone(),
+        outstanding_principal: current.outstanding_principal,
+        refixation_date: position.refixation,
+        baseline,
+        scenarios,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LoanPrepayment;
+
+    const JAN_2024: i64 = 1704067200; // 2024-01-01
+
+    fn loan(repayment_type: &str, monthly_payment: &str, end_date: Option<i64>) -> Loan {
+        Loan {
+            id: "l1".into(),
+            name: "Mortgage".into(),
+            principal: "120000".into(),
+            currency: "CZK".into(),
+            interest_rate: "6".into(),
+            interest_rate_validity_date: None,
+            monthly_payment: monthly_payment.into(),
+            start_date: JAN_2024,
+            end_date,
+            repayment_type: repayment_type.into(),
+            payment_iban: None,
+            payment_variable_symbol: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn ts(year: i32, month: u32, day: u32) -> i64 {
+        timestamp_of(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+    }
+
+    #[test]
+    fn test_annuity_repays_principal_by_end_date() {
+        let loan = loan("annuity", "0", Some(ts(2025, 1, 1)));
+        let result = schedule(&loan, &[], JAN_2024);
+
+        assert_eq!(result.installments.len(), 12);
+        // 120,000 at 6 % over 12 months: 10,327.97 a month
+        assert_eq!(result.installments[0].payment, "10327.97");
+        assert_eq!(result.installments[0].interest, "600.00");
+        assert_eq!(result.installments[0].principal, "9727.97");
+        assert_eq!(result.installments.last().unwrap().balance, "0.00");
+        assert_eq!(result.payoff_date, Some(ts(2025, 1, 1)));
+        assert_eq!(result.outstanding_principal, "120000.00");
+    }
+
+    #[test]
+    fn test_linear_and_interest_only() {
+        let linear = schedule(&loan("linear", "0", Some(ts(2025, 1, 1))), &[], JAN_2024);
+        assert_eq!(linear.installments[0].payment, "10600.00");
+        assert_eq!(linear.installments[1].payment, "10550.00");
+        assert_eq!(linear.installments.last().unwrap().balance, "0.00");
+
+        let bullet = schedule(
+            &loan("interest_only", "600", Some(ts(2025, 1, 1))),
+            &[],
+            JAN_2024,
+        );
+        assert_eq!(bullet.installments[0].principal, "0.00");
+        assert_eq!(bullet.installments.last().unwrap().principal, "120000.00");
+        assert_eq!(bullet.remaining_interest, "7200.00");
+    }
+
+    #[test]
+    fn test_outstanding_principal_follows_time_and_payments() {
+        let loan = loan("annuity", "10327.97", None);
+
+        // Three installments assumed paid as scheduled
+        let assumed = schedule(&loan, &[], ts(2024, 4, 15));
+        assert_eq!(assumed.installments[2].status, "assumed");
+        let after_three = money::parse_or_zero(&assumed.outstanding_principal);
+        assert!(after_three < Decimal::from(92_000) && after_three > Decimal::from(90_000));
+
+        // An extra payment in February lowers the principal
+        let payments = vec![
+            (ts(2024, 2, 1), money::parse_or_zero("10327.97")),
+            (ts(2024, 3, 1), money::parse_or_zero("30327.97")),
+        ];
+        let actual = schedule(&loan, &payments, ts(2024, 4, 15));
+        assert_eq!(actual.matched_payments, 2);
+        assert_eq!(actual.installments[1].status, "paid");
+        assert_eq!(actual.installments[1].payment, "30327.97");
+        let reduced = money::parse_or_zero(&actual.outstanding_principal);
+        assert!(after_three - reduced > Decimal::from(19_000));
+
+        // No liability before the loan started
+        assert_eq!(
+            balance_on(&loan, &actual.installments, JAN_2024 - 1),
+            Decimal::ZERO
+        );
+    }
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE loans (
+                id TEXT PRIMARY KEY, name TEXT NOT NULL, principal TEXT NOT NULL,
+                currency TEXT NOT NULL, interest_rate TEXT NOT NULL,
+                interest_rate_validity_date INTEGER, monthly_payment TEXT NOT NULL,
+                start_date INTEGER NOT NULL, end_date INTEGER, repayment_type TEXT NOT NULL,
+                payment_iban TEXT, payment_variable_symbol TEXT,
+                created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE bank_transactions (
+                id TEXT PRIMARY KEY, tx_type TEXT NOT NULL, amount TEXT NOT NULL,
+                currency TEXT NOT NULL, counterparty_iban TEXT, variable_symbol TEXT,
+                booking_date INTEGER NOT NULL, status TEXT NOT NULL DEFAULT 'booked'
+            );",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn insert_loan(conn: &Connection, loan: &Loan) {
+        conn.execute(
+            &format!(
+                "INSERT INTO loans ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                LOAN_COLUMNS
+            ),
+            rusqlite::params![
+                loan.id,
+                loan.name,
+                loan.principal,
+                loan.currency,
+                loan.interest_rate,
+                loan.interest_rate_validity_date,
+                loan.monthly_payment,
+                loan.start_date,
+                loan.end_date,
+                loan.repayment_type,
+                loan.payment_iban,
+                loan.payment_variable_symbol,
+                loan.created_at,
+                loan.updated_at,
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_matched_payments_by_iban_and_symbol() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO bank_transactions (id, tx_type, amount, currency, counterparty_iban, variable_symbol, booking_date) VALUES
+                ('t1', 'debit', '10327.97', 'CZK', 'CZ65 0800 0000 1920 0014 5399', '0012345', 1706745600),
+                ('t2', 'debit', '10327.97', 'CZK', 'CZ6508000000192000145399', '999', 1706745600),
+                ('t3', 'credit', '10327.97', 'CZK', 'CZ6508000000192000145399', '12345', 1706745600),
+                ('t4', 'debit', '10327.97', 'CZK', 'CZ6508000000192000145399', '12345', 1700000000);",
+        )
+        .unwrap();
+
+        let mut loan = loan("annuity", "10327.97", None);
+        assert!(matched_payments(&conn, &loan).unwrap().is_empty());
+
+        loan.payment_iban = Some("cz6508000000192000145399".into());
+        loan.payment_variable_symbol = Some("12345".into());
+        let payments = matched_payments(&conn, &loan).unwrap();
+        assert_eq!(
+            payments,
+            vec![(1706745600, money::parse_or_zero("10327.97"))]
+        );
+    }
+
+    #[test]
+    fn test_iban_only_debits_match_the_installment_and_credit_one_loan() {
+        let conn = setup_test_db();
+        let iban = "CZ6508000000192000145399";
+        let mut mortgage = loan("annuity", "10327.97", None);
+        mortgage.payment_iban = Some(iban.into());
+        insert_loan(&conn, &mortgage);
+        let mut second = loan("annuity", "5000", None);
+        second.id = "l2".into();
+        second.name = "Second Mortgage".into();
+        second.payment_iban = Some(iban.into());
+        insert_loan(&conn, &second);
+        conn.execute_batch(&format!(
+            "INSERT INTO bank_transactions (id, tx_type, amount, currency, counterparty_iban, booking_date) VALUES
+                ('t1', 'debit', '10327.97', 'CZK', '{iban}', {feb}),
+                ('t2', 'debit', '5000', 'CZK', '{iban}', {feb}),
+                ('t3', 'debit', '10327.97', 'CZK', '{iban}', {mid_feb}),
+                ('t4', 'debit', '250', 'CZK', '{iban}', {mar});",
+            feb = ts(2024, 2, 1),
+            mid_feb = ts(2024, 2, 15),
+            mar = ts(2024, 3, 2),
+        ))
+        .unwrap();
+
+        // The off-schedule and mismatched debits are credited to neither loan
+        let payments = payments_by_loan(&conn, &get_loans(&conn).unwrap()).unwrap();
+        assert_eq!(
+            payments["l1"],
+            vec![(ts(2024, 2, 1), money::parse_or_zero("10327.97"))]
+        );
+        assert_eq!(payments["l2"], vec![(ts(2024, 2, 1), Decimal::from(5000))]);
+        assert_eq!(matched_payments(&conn, &second).unwrap(), payments["l2"]);
+    }
+
+    #[test]
+    fn test_prepayment_shortens_term_or_lowers_payment() {
+        let loan = loan("annuity", "0", Some(ts(2034, 1, 1)));
+        let prepayment = |strategy: &str| LoanScenario {
+            name: strategy.into(),
+            one_off_prepayments: vec![LoanPrepayment {
+                date: ts(2024, 6, 15),
+                amount: "20000".into(),
+            }],
+            strategy: Some(strategy.into()),
+            ..Default::default()
+        };
+        let result = simulate(
+            &loan,
+            &[],
+            &[prepayment("shorten_term"), prepayment("lower_payment")],
+            JAN_2024,
+        );
+
+        assert_eq!(result.baseline.installments, 120);
+        assert_eq!(result.baseline.payoff_date, Some(ts(2034, 1, 1)));
+
+        let shorter = &result.scenarios[0];
+        assert!(shorter.payoff_date < result.baseline.payoff_date);
+        assert_eq!(shorter.monthly_payment, result.baseline.monthly_payment);
+        assert_eq!(shorter.total_prepaid, "20000.00");
+
+        let lower = &result.scenarios[1];
+        assert_eq!(lower.payoff_date, result.baseline.payoff_date);
+        assert!(
+            money::parse_or_zero(&shorter.interest_saved)
+                > money::parse_or_zero(&lower.interest_saved)
+        );
+        assert!(money::parse_or_zero(&lower.interest_saved) > Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_refixation_rate_and_prepayment_fees() {
+        let mut loan = loan("annuity", "0", Some(ts(2034, 1, 1)));
+        loan.interest_rate_validity_date = Some(ts(2026, 1, 1));
+
+        let refixed = LoanScenario {
+            name: "refix".into(),
+            refixation_rate: Some("8".into()),
+            ..Default::default()
+        };
+        // 25 % of 120,000 is fee-free: 10,000 of the 40,000 is charged 1 %
+        let over_limit = LoanScenario {
+            name: "fee".into(),
+            one_off_prepayments: vec![LoanPrepayment {
+                date: ts(2024, 1, 15),
+                amount: "40000".into(),
+            }],
+            fee_free_percent: Some("25".into()),
+            prepayment_fee_percent: Some("1".into()),
+            ..Default::default()
+        };
+        // Prepaid when the fixation ends: no fee
+        let at_refixation = LoanScenario {
+            name: "free".into(),
+            one_off_prepayments: vec![LoanPrepayment {
+                date: ts(2025, 12, 20),
+                amount: "40000".into(),
+            }],
+            fee_free_percent: Some("0".into()),
+            prepayment_fee_percent: Some("1".into()),
+            ..Default::default()
+        };
+        // No fee-free limit: the whole 40,000 is charged 1 %
+        let no_limit = LoanScenario {
+            name: "no limit".into(),
+            one_off_prepayments: vec![LoanPrepayment {
+                date: ts(2024, 1, 15),
+                amount: "40000".into(),
+            }],
+            prepayment_fee_percent: Some("1".into()),
+            ..Default::default()
+        };
+        let result = simulate(
+            &loan,
+            &[],
+            &[refixed, over_limit, at_refixation, no_limit],
+            JAN_2024,
+        );
+
+        assert_eq!(result.refixation_date, Some(ts(2026, 1, 1)));
+        let refix = &result.scenarios[0];
+        let before = money::parse_or_zero(&refix.monthly_payment);
+        let after = money::parse_or_zero(refix.payment_after_refixation.as_ref().unwrap());
+        assert!(after > before);
+        assert!(money::parse_or_zero(&refix.interest_saved) < Decimal::ZERO);
+        assert_eq!(refix.payoff_date, result.baseline.payoff_date);
+
+        assert_eq!(result.scenarios[1].fees, "100.00");
+        assert_eq!(result.scenarios[2].fees, "0.00");
+        assert_eq!(result.scenarios[3].fees, "400.00");
+    }
+}
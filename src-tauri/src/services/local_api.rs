@@ -19,7 +19,9 @@ use std::{
 use tokio::task::JoinHandle;
 
 use crate::db::Database;
-use crate::models::InsertInsurancePolicy;
+use crate::models::{ExposureDimension, InsertInsurancePolicy, RiskScope, XirrScope};
+use crate::services::money::{self, Decimal};
+use crate::services::reporting::Reporting;
 
 // ============================================================================
 // Session file
@@ -101,6 +103,92 @@ fn sql_to_json(v: rusqlite::types::Value) -> Value {
     }
 }
 
+/// Add reporting currency amounts to a response. Every `*_czk` / `*Czk` amount
+/// gets a sibling without the suffix in the reporting currency, converted at
+/// the rate of the row's `recordedAt`/`date` when it has one and at the current
+/// rate otherwise. Objects get `reportingCurrency` at the top level, arrays on
+/// each row.
+fn in_reporting_currency(
+    conn: &rusqlite::Connection,
+    mut value: Value,
+) -> crate::error::Result<Value> {
+    let reporting = Reporting::load(conn)?;
+    add_reporting_amounts(&mut value, &reporting, None);
+    let currency = Value::String(reporting.currency.clone());
+    match &mut value {
+        Value::Object(map) => {
+            map.insert("reportingCurrency".into(), currency);
+        }
+        Value::Array(rows) => {
+            for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+                row.insert("reportingCurrency".into(), currency.clone());
+            }
+        }
+        _ => {}
+    }
+    Ok(value)
+}
+
+/// `*Czk` copies of amounts given in `currency`, at the current rate, for
+/// `in_reporting_currency` to express in the reporting currency
+fn czk_amounts(currency: &str, amounts: &[(&str, &str)]) -> Value {
+    let map = amounts
+        .iter()
+        .map(|(key, amount)| {
+            let czk =
+                crate::services::currency::convert_to_czk(amount.parse().unwrap_or(0.0), currency);
+            (format!("{key}Czk"), Value::String(format!("{:.2}", czk)))
+        })
+        .collect();
+    Value::Object(map)
+}
+
+fn add_reporting_amounts(value: &mut Value, reporting: &Reporting, day: Option<i64>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                add_reporting_amounts(item, reporting, day);
+            }
+        }
+        Value::Object(map) => {
+            let day = map
+                .get("recordedAt")
+                .or_else(|| map.get("date"))
+                .and_then(Value::as_i64)
+                .or(day);
+            let mut converted = Vec::new();
+            for (key, v) in map.iter_mut() {
+                let Some(base_key) = key.strip_suffix("_czk").or_else(|| key.strip_suffix("Czk"))
+                else {
+                    add_reporting_amounts(v, reporting, day);
+                    continue;
+                };
+                let amount = match v {
+                    Value::String(text) => text.parse::<f64>().ok(),
+                    Value::Number(number) => number.as_f64(),
+                    _ => None,
+                };
+                if let Some(amount) = amount {
+                    let amount = match day {
+                        Some(day) => reporting.amount_on(amount, day),
+                        None => reporting.amount(amount),
+                    };
+                    let amount = if v.is_string() {
+                        Value::String(format!("{:.2}", amount))
+                    } else {
+                        serde_json::json!(amount)
+                    };
+                    converted.push((base_key.to_string(), amount));
+                }
+            }
+            for (key, amount) in converted {
+                map.entry(key).or_insert(amount);
+            }
+        }
+        _ => {}
+    }
+}
+
 // ============================================================================
 // Query params structs
 // ============================================================================
@@ -126,6 +214,20 @@ struct TransactionsParams {
     limit: Option<i64>,
 }
 
+#[derive(Deserialize)]
+struct InvestmentsParams {
+    #[serde(rename = "accountId")]
+    account_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InvestmentTransactionsParams {
+    ticker: Option<String>,
+    #[serde(rename = "accountId")]
+    account_id: Option<String>,
+    limit: Option<i64>,
+}
+
 #[derive(Deserialize)]
 struct HistoryParams {
     #[serde(rename = "startDate")]
@@ -170,6 +272,51 @@ struct TagMetricsParams {
     tag_ids: Option<String>, // comma-separated
 }
 
+#[derive(Deserialize)]
+struct XirrParams {
+    scope: Option<XirrScope>,
+    #[serde(rename = "startDate")]
+    start_date: Option<i64>,
+    #[serde(rename = "endDate")]
+    end_date: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct RiskParams {
+    scope: Option<RiskScope>,
+    benchmark: Option<String>,
+    #[serde(rename = "riskFreeRate")]
+    risk_free_rate: Option<f64>,
+    #[serde(rename = "startDate")]
+    start_date: Option<i64>,
+    #[serde(rename = "endDate")]
+    end_date: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct BenchmarkParams {
+    benchmark: String,
+    #[serde(rename = "tagId")]
+    tag_id: Option<String>,
+    #[serde(rename = "startDate")]
+    start_date: i64,
+    #[serde(rename = "endDate")]
+    end_date: i64,
+}
+
+#[derive(Deserialize)]
+struct ExposureParams {
+    dimension: Option<ExposureDimension>,
+    #[serde(rename = "stocksOnly")]
+    stocks_only: Option<bool>,
+    #[serde(rename = "excludePersonalRealEstate")]
+    exclude_personal_real_estate: Option<bool>,
+    #[serde(rename = "startDate")]
+    start_date: Option<i64>,
+    #[serde(rename = "endDate")]
+    end_date: Option<i64>,
+}
+
 // ============================================================================
 // Endpoints
 // ============================================================================
@@ -230,17 +377,15 @@ async fn portfolio_metrics(
                 result
             };
 
-            // Loans
+            // Loans (outstanding principal)
             let loans: f64 = {
-                let mut stmt = conn.prepare("SELECT principal, currency FROM loans")?;
-                let result = stmt
-                    .query_map([], |row| {
-                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-                    })?
-                    .filter_map(|r| r.ok())
-                    .map(|(p, c)| czk_rate(&c, p.parse::<f64>().unwrap_or(0.0)))
-                    .sum();
-                result
+                let now = chrono::Utc::now().timestamp();
+                crate::services::loans::outstanding_balances(conn, now)?
+                    .iter()
+                    .map(|(loan, balance)| {
+                        czk_rate(&loan.currency, crate::services::money::to_f64(*balance))
+                    })
+                    .sum()
             };
 
             // Stocks
@@ -297,26 +442,18 @@ async fn portfolio_metrics(
                 result
             };
 
-            // Bonds
+            // Bonds (market value with accrued interest)
             let bonds: f64 = {
-                let mut stmt =
-                    conn.prepare("SELECT coupon_value, quantity, currency FROM bonds")?;
-                let result = stmt
-                    .query_map([], |row| {
-                        Ok((
-                            row.get::<_, String>(0)?,
-                            row.get::<_, String>(1)?,
-                            row.get::<_, String>(2)?,
-                        ))
-                    })?
-                    .filter_map(|r| r.ok())
-                    .map(|(cv, q, c)| {
-                        let val =
-                            cv.parse::<f64>().unwrap_or(0.0) * q.parse::<f64>().unwrap_or(0.0);
-                        czk_rate(&c, val)
+                let now = chrono::Utc::now().timestamp();
+                crate::services::bonds::get_bonds(conn)?
+                    .iter()
+                    .map(|bond| {
+                        let value = crate::services::money::to_f64(
+                            crate::services::bonds::market_value(bond, now),
+                        );
+                        czk_rate(&bond.currency, value)
                     })
-                    .sum();
-                result
+                    .sum()
             };
 
             // Real estate
@@ -375,7 +512,7 @@ async fn portfolio_metrics(
                 savings + investments + crypto + bonds + re_personal_final + re_investment + other;
             let net_worth = total_assets - loans;
 
-            Ok(serde_json::json!({
+            in_reporting_currency(conn, serde_json::json!({
                 "net_worth_czk": format!("{:.2}", net_worth),
                 "total_assets_czk": format!("{:.2}", total_assets),
                 "total_liabilities_czk": format!("{:.2}", loans),
@@ -389,7 +526,7 @@ async fn portfolio_metrics(
                     "other_assets_czk": format!("{:.2}", other),
                     "loans_czk": format!("{:.2}", loans),
                 },
-                "note": "All values in CZK."
+                "note": "*_czk values are in CZK, the same keys without the suffix in reportingCurrency."
             }))
         })
         .map(Json)
@@ -430,20 +567,27 @@ async fn portfolio_history(
             );
             p.push(Box::new(limit));
             let refs: Vec<&dyn rusqlite::ToSql> = p.iter().map(|x| x.as_ref()).collect();
+            // Snapshots are stored in CZK; each day is converted at its own rate
+            let reporting = Reporting::load(conn)?;
             let mut stmt = conn.prepare(&sql)?;
             let rows: Vec<Value> = stmt
                 .query_map(refs.as_slice(), |row| {
+                    let day = row.get::<_, i64>(9)?;
+                    let amount = |idx: usize| -> rusqlite::Result<String> {
+                        Ok(reporting.amount_str_on(&row.get::<_, String>(idx)?, day))
+                    };
                     Ok(serde_json::json!({
                         "id": row.get::<_, String>(0)?,
-                        "totalSavings": row.get::<_, String>(1)?,
-                        "totalLoansPrincipal": row.get::<_, String>(2)?,
-                        "totalInvestments": row.get::<_, String>(3)?,
-                        "totalCrypto": row.get::<_, String>(4)?,
-                        "totalBonds": row.get::<_, String>(5)?,
-                        "totalRealEstatePersonal": row.get::<_, String>(6)?,
-                        "totalRealEstateInvestment": row.get::<_, String>(7)?,
-                        "totalOtherAssets": row.get::<_, String>(8)?,
-                        "recordedAt": row.get::<_, i64>(9)?,
+                        "totalSavings": amount(1)?,
+                        "totalLoansPrincipal": amount(2)?,
+                        "totalInvestments": amount(3)?,
+                        "totalCrypto": amount(4)?,
+                        "totalBonds": amount(5)?,
+                        "totalRealEstatePersonal": amount(6)?,
+                        "totalRealEstateInvestment": amount(7)?,
+                        "totalOtherAssets": amount(8)?,
+                        "recordedAt": day,
+                        "reportingCurrency": reporting.currency,
                     }))
                 })?
                 .filter_map(|r| r.ok())
@@ -457,9 +601,22 @@ async fn portfolio_history(
 async fn investments_list(
     AxumState(state): AxumState<Arc<ApiState>>,
     headers: HeaderMap,
+    Query(params): Query<InvestmentsParams>,
 ) -> ApiResult {
     auth!(headers, state);
     state.db.with_conn(|conn| {
+        // With an account filter, quantity and average price come from that account's transactions
+        let account_positions: Option<std::collections::HashMap<String, crate::models::AccountPosition>> =
+            match params.account_id.as_deref() {
+                Some(account_id) => Some(
+                    crate::services::brokerage_accounts::get_account_positions(conn, Some(account_id))?
+                        .into_iter()
+                        .map(|p| (p.investment_id.clone(), p))
+                        .collect(),
+                ),
+                None => None,
+            };
+
         let mut stmt = conn.prepare(
             "SELECT si.id, si.ticker, si.company_name, si.quantity,
                     si.average_price, si.currency,
@@ -493,6 +650,20 @@ async fn investments_list(
                 "priceFetchedAt": sql_to_json(row.get::<_, rusqlite::types::Value>(16).unwrap_or(rusqlite::types::Value::Null)),
             }))
         })?.filter_map(|r| r.ok()).collect();
+        let rows = match account_positions {
+            Some(positions) => rows
+                .into_iter()
+                .filter_map(|mut row| {
+                    let position = positions.get(row["id"].as_str()?)?;
+                    row["quantity"] = serde_json::json!(position.quantity);
+                    row["averagePrice"] = serde_json::json!(position.average_price);
+                    row["currency"] = serde_json::json!(position.currency);
+                    row["accountId"] = serde_json::json!(position.account_id);
+                    Some(row)
+                })
+                .collect(),
+            None => rows,
+        };
         Ok(Value::Array(rows))
     }).map(Json).map_err(db_err)
 }
@@ -534,7 +705,7 @@ async fn investment_detail(
                 let txs: Vec<Value> = {
                     let mut s = conn.prepare(
                         "SELECT id, type, ticker, company_name, quantity, price_per_unit,
-                                currency, transaction_date, created_at
+                                currency, transaction_date, created_at, account_id
                          FROM investment_transactions WHERE investment_id = ?
                          ORDER BY transaction_date DESC"
                     )?;
@@ -548,6 +719,7 @@ async fn investment_detail(
                         "currency": row.get::<_, String>(6)?,
                         "transactionDate": row.get::<_, i64>(7)?,
                         "createdAt": row.get::<_, i64>(8)?,
+                        "accountId": row.get::<_, Option<String>>(9)?,
                     })))?.filter_map(|r| r.ok()).collect();
                     result
                 };
@@ -561,69 +733,82 @@ async fn investment_detail(
 async fn investment_transactions(
     AxumState(state): AxumState<Arc<ApiState>>,
     headers: HeaderMap,
-    Query(params): Query<TransactionsParams>,
+    Query(params): Query<InvestmentTransactionsParams>,
 ) -> ApiResult {
     auth!(headers, state);
     let limit = params.limit.unwrap_or(200);
     state
         .db
         .with_conn(|conn| {
-            let sql = if params.ticker.is_some() {
-                "SELECT id, investment_id, type, ticker, company_name, quantity,
-                    price_per_unit, currency, transaction_date, created_at
-             FROM investment_transactions WHERE ticker = ? ORDER BY transaction_date DESC LIMIT ?"
-            } else {
-                "SELECT id, investment_id, type, ticker, company_name, quantity,
-                    price_per_unit, currency, transaction_date, created_at
-             FROM investment_transactions ORDER BY transaction_date DESC LIMIT ?"
-            };
-            let rows: Vec<Value> = if let Some(ref t) = params.ticker {
-                let mut stmt = conn.prepare(sql)?;
-                let result = stmt
-                    .query_map(rusqlite::params![t, limit], |row| {
-                        Ok(serde_json::json!({
-                            "id": row.get::<_, String>(0)?,
-                            "investmentId": row.get::<_, String>(1)?,
-                            "type": row.get::<_, String>(2)?,
-                            "ticker": row.get::<_, String>(3)?,
-                            "companyName": row.get::<_, String>(4)?,
-                            "quantity": row.get::<_, String>(5)?,
-                            "pricePerUnit": row.get::<_, String>(6)?,
-                            "currency": row.get::<_, String>(7)?,
-                            "transactionDate": row.get::<_, i64>(8)?,
-                            "createdAt": row.get::<_, i64>(9)?,
-                        }))
-                    })?
-                    .filter_map(|r| r.ok())
-                    .collect();
-                result
+            let mut conditions = Vec::new();
+            let mut p: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+            if let Some(ref t) = params.ticker {
+                conditions.push("ticker = ?");
+                p.push(Box::new(t.clone()));
+            }
+            if let Some(ref a) = params.account_id {
+                conditions.push("account_id = ?");
+                p.push(Box::new(a.clone()));
+            }
+            let where_clause = if conditions.is_empty() {
+                String::new()
             } else {
-                let mut stmt = conn.prepare(sql)?;
-                let result = stmt
-                    .query_map(rusqlite::params![limit], |row| {
-                        Ok(serde_json::json!({
-                            "id": row.get::<_, String>(0)?,
-                            "investmentId": row.get::<_, String>(1)?,
-                            "type": row.get::<_, String>(2)?,
-                            "ticker": row.get::<_, String>(3)?,
-                            "companyName": row.get::<_, String>(4)?,
-                            "quantity": row.get::<_, String>(5)?,
-                            "pricePerUnit": row.get::<_, String>(6)?,
-                            "currency": row.get::<_, String>(7)?,
-                            "transactionDate": row.get::<_, i64>(8)?,
-                            "createdAt": row.get::<_, i64>(9)?,
-                        }))
-                    })?
-                    .filter_map(|r| r.ok())
-                    .collect();
-                result
+                format!("WHERE {}", conditions.join(" AND "))
             };
+            let sql = format!(
+                "SELECT id, investment_id, type, ticker, company_name, quantity,
+                    price_per_unit, currency, transaction_date, created_at, account_id
+             FROM investment_transactions {} ORDER BY transaction_date DESC LIMIT ?",
+                where_clause
+            );
+            p.push(Box::new(limit));
+            let refs: Vec<&dyn rusqlite::ToSql> = p.iter().map(|x| x.as_ref()).collect();
+            let mut stmt = conn.prepare(&sql)?;
+            let rows: Vec<Value> = stmt
+                .query_map(refs.as_slice(), |row| {
+                    Ok(serde_json::json!({
+                        "id": row.get::<_, String>(0)?,
+                        "investmentId": row.get::<_, String>(1)?,
+                        "type": row.get::<_, String>(2)?,
+                        "ticker": row.get::<_, String>(3)?,
+                        "companyName": row.get::<_, String>(4)?,
+                        "quantity": row.get::<_, String>(5)?,
+                        "pricePerUnit": row.get::<_, String>(6)?,
+                        "currency": row.get::<_, String>(7)?,
+                        "transactionDate": row.get::<_, i64>(8)?,
+                        "createdAt": row.get::<_, i64>(9)?,
+                        "accountId": row.get::<_, Option<String>>(10)?,
+                    }))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
             Ok(Value::Array(rows))
         })
         .map(Json)
         .map_err(db_err)
 }
 
+async fn brokerage_accounts_list(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> ApiResult {
+    auth!(headers, state);
+    state
+        .db
+        .with_conn(|conn| {
+            let accounts = crate::services::brokerage_accounts::get_all_accounts(conn)?;
+            let values = crate::services::brokerage_accounts::get_account_values(conn)?;
+            in_reporting_currency(conn, serde_json::json!({
+                "accounts": accounts,
+                "values": values,
+                "note": "*Czk values are in CZK, the same keys without the suffix in reportingCurrency. \
+                         An entry with accountId null holds transactions not assigned to any account."
+            }))
+        })
+        .map(Json)
+        .map_err(db_err)
+}
+
 async fn stock_value_history(
     AxumState(state): AxumState<Arc<ApiState>>,
     headers: HeaderMap,
@@ -652,7 +837,7 @@ async fn stock_value_history(
             "price": sql_to_json(row.get::<_, rusqlite::types::Value>(5).unwrap_or(rusqlite::types::Value::Null)),
             "currency": sql_to_json(row.get::<_, rusqlite::types::Value>(6).unwrap_or(rusqlite::types::Value::Null)),
         })))?.filter_map(|r| r.ok()).collect();
-        Ok(Value::Array(rows))
+        in_reporting_currency(conn, Value::Array(rows))
     }).map(Json).map_err(db_err)
 }
 
@@ -699,7 +884,7 @@ async fn crypto_transactions(
             let rows: Vec<Value> = if let Some(ref t) = params.ticker {
                 let mut stmt = conn.prepare(
                     "SELECT id, investment_id, type, ticker, name, quantity,
-                        price_per_unit, currency, transaction_date, created_at
+                        price_per_unit, currency, transaction_date, created_at, wallet
                  FROM crypto_transactions WHERE ticker = ? ORDER BY transaction_date DESC LIMIT ?",
                 )?;
                 let result = stmt
@@ -715,6 +900,7 @@ async fn crypto_transactions(
                             "currency": row.get::<_, String>(7)?,
                             "transactionDate": row.get::<_, i64>(8)?,
                             "createdAt": row.get::<_, i64>(9)?,
+                            "wallet": row.get::<_, Option<String>>(10)?,
                         }))
                     })?
                     .filter_map(|r| r.ok())
@@ -723,7 +909,7 @@ async fn crypto_transactions(
             } else {
                 let mut stmt = conn.prepare(
                     "SELECT id, investment_id, type, ticker, name, quantity,
-                        price_per_unit, currency, transaction_date, created_at
+                        price_per_unit, currency, transaction_date, created_at, wallet
                  FROM crypto_transactions ORDER BY transaction_date DESC LIMIT ?",
                 )?;
                 let result = stmt
@@ -739,6 +925,7 @@ async fn crypto_transactions(
                             "currency": row.get::<_, String>(7)?,
                             "transactionDate": row.get::<_, i64>(8)?,
                             "createdAt": row.get::<_, i64>(9)?,
+                            "wallet": row.get::<_, Option<String>>(10)?,
                         }))
                     })?
                     .filter_map(|r| r.ok())
@@ -779,7 +966,7 @@ async fn crypto_value_history(
             "price": sql_to_json(row.get::<_, rusqlite::types::Value>(5).unwrap_or(rusqlite::types::Value::Null)),
             "currency": sql_to_json(row.get::<_, rusqlite::types::Value>(6).unwrap_or(rusqlite::types::Value::Null)),
         })))?.filter_map(|r| r.ok()).collect();
-        Ok(Value::Array(rows))
+        in_reporting_currency(conn, Value::Array(rows))
     }).map(Json).map_err(db_err)
 }
 
@@ -912,7 +1099,13 @@ async fn bank_categories(
 
 async fn bonds_list(AxumState(state): AxumState<Arc<ApiState>>, headers: HeaderMap) -> ApiResult {
     auth!(headers, state);
+    let now = chrono::Utc::now().timestamp();
     state.db.with_conn(|conn| {
+        let market_values: std::collections::HashMap<String, f64> =
+            crate::services::bonds::get_bonds(conn)?
+                .iter()
+                .map(|bond| (bond.id.clone(), crate::services::bonds::market_value_czk(bond, now)))
+                .collect();
         let mut stmt = conn.prepare(
             "SELECT id, name, isin, coupon_value, quantity, currency,
                     interest_rate, maturity_date, created_at, updated_at
@@ -929,17 +1122,31 @@ async fn bonds_list(AxumState(state): AxumState<Arc<ApiState>>, headers: HeaderM
             "maturityDate": sql_to_json(row.get::<_, rusqlite::types::Value>(7).unwrap_or(rusqlite::types::Value::Null)),
             "createdAt": row.get::<_, i64>(8)?,
             "updatedAt": row.get::<_, i64>(9)?,
-        })))?.filter_map(|r| r.ok()).collect();
-        Ok(Value::Array(rows))
+        })))?.filter_map(|r| r.ok()).map(|mut row| {
+            // Dirty market value (clean price plus accrued interest)
+            let value = row["id"].as_str().and_then(|id| market_values.get(id)).copied().unwrap_or(0.0);
+            row["marketValueCzk"] = Value::String(format!("{:.2}", value));
+            row
+        }).collect();
+        in_reporting_currency(conn, Value::Array(rows))
     }).map(Json).map_err(db_err)
 }
 
 async fn loans_list(AxumState(state): AxumState<Arc<ApiState>>, headers: HeaderMap) -> ApiResult {
     auth!(headers, state);
+    let now = chrono::Utc::now().timestamp();
     state.db.with_conn(|conn| {
+        let balances: std::collections::HashMap<String, f64> =
+            crate::services::loans::outstanding_balances(conn, now)?
+                .into_iter()
+                .map(|(loan, balance)| {
+                    let balance = crate::services::money::to_f64(balance);
+                    (loan.id, crate::services::currency::convert_to_czk(balance, &loan.currency))
+                })
+                .collect();
         let mut stmt = conn.prepare(
             "SELECT id, name, principal, currency, interest_rate,
-                    monthly_payment, start_date, end_date, created_at, updated_at
+                    monthly_payment, start_date, end_date, created_at, updated_at, repayment_type
              FROM loans ORDER BY name"
         )?;
         let rows: Vec<Value> = stmt.query_map([], |row| Ok(serde_json::json!({
@@ -953,8 +1160,13 @@ async fn loans_list(AxumState(state): AxumState<Arc<ApiState>>, headers: HeaderM
             "endDate": sql_to_json(row.get::<_, rusqlite::types::Value>(7).unwrap_or(rusqlite::types::Value::Null)),
             "createdAt": row.get::<_, i64>(8)?,
             "updatedAt": row.get::<_, i64>(9)?,
-        })))?.filter_map(|r| r.ok()).collect();
-        Ok(Value::Array(rows))
+            "repaymentType": row.get::<_, String>(10)?,
+        })))?.filter_map(|r| r.ok()).map(|mut row| {
+            let balance = row["id"].as_str().and_then(|id| balances.get(id)).copied().unwrap_or(0.0);
+            row["outstandingBalanceCzk"] = Value::String(format!("{:.2}", balance));
+            row
+        }).collect();
+        in_reporting_currency(conn, Value::Array(rows))
     }).map(Json).map_err(db_err)
 }
 
@@ -998,7 +1210,7 @@ async fn real_estate_list(
         let mut stmt = conn.prepare(
             "SELECT id, name, address, type, purchase_price, purchase_price_currency,
                     market_price, market_price_currency, monthly_rent, monthly_rent_currency,
-                    recurring_costs, notes, created_at, updated_at
+                    recurring_costs, notes, created_at, updated_at, purchase_date
              FROM real_estate ORDER BY name"
         )?;
         let rows: Vec<Value> = stmt.query_map([], |row| Ok(serde_json::json!({
@@ -1016,6 +1228,7 @@ async fn real_estate_list(
             "notes": sql_to_json(row.get::<_, rusqlite::types::Value>(11).unwrap_or(rusqlite::types::Value::Null)),
             "createdAt": row.get::<_, i64>(12)?,
             "updatedAt": row.get::<_, i64>(13)?,
+            "purchaseDate": row.get::<_, Option<i64>>(14)?,
         })))?.filter_map(|r| r.ok()).collect();
         Ok(Value::Array(rows))
     }).map(Json).map_err(db_err)
@@ -1069,12 +1282,58 @@ async fn real_estate_detail(
                     result
                 };
                 obj["costs"] = Value::Array(costs);
+                let valuations = crate::services::real_estate::get_valuations(conn, &id)?;
+                obj["valuations"] = serde_json::to_value(valuations).unwrap_or_default();
                 Ok(obj)
             }
         }
     }).map(Json).map_err(db_err)
 }
 
+async fn real_estate_performance(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> ApiResult {
+    auth!(headers, state);
+    let now = chrono::Utc::now().timestamp();
+    state
+        .db
+        .with_conn(|conn| {
+            let properties: Vec<Value> = crate::services::real_estate::get_performance(conn, now)?
+                .into_iter()
+                .map(|p| {
+                    let amounts = czk_amounts(
+                        &p.currency,
+                        &[
+                            ("annualRent", &p.annual_rent),
+                            ("operatingCosts", &p.operating_costs),
+                            ("netOperatingIncome", &p.net_operating_income),
+                            ("debtService", &p.debt_service),
+                            ("annualCashFlow", &p.annual_cash_flow),
+                            ("cashInvested", &p.cash_invested),
+                            ("currentValue", &p.current_value),
+                            ("equity", &p.equity),
+                        ],
+                    );
+                    let mut value = serde_json::to_value(p).unwrap_or_default();
+                    value["reportingAmounts"] = amounts;
+                    value
+                })
+                .collect();
+            in_reporting_currency(conn, serde_json::json!({
+                "properties": properties,
+                "note": "Trailing year in each property's market price currency. Linked credits \
+                         are the rent of their months and the monthly rent fills the rest; linked \
+                         debits add to the recurring costs and insurance premiums. \
+                         cumulativeCashFlow applies the same over the holding period. \
+                         reportingAmounts holds the main amounts in CZK (*Czk) and in \
+                         reportingCurrency (same keys without the suffix) at the current rate."
+            }))
+        })
+        .map(Json)
+        .map_err(db_err)
+}
+
 async fn insurance_list(
     AxumState(state): AxumState<Arc<ApiState>>,
     headers: HeaderMap,
@@ -1328,6 +1587,7 @@ async fn exchange_rates(
                 .collect();
             Ok(serde_json::json!({
                 "baseCurrency": "CZK",
+                "reportingCurrency": crate::services::reporting::reporting_currency(conn),
                 "rates": rows,
                 "note": "Multiply amount in currency by rate to get CZK equivalent."
             }))
@@ -1436,7 +1696,7 @@ async fn cashflow_report(
             "amount_czk": format!("{:.2}", v["monthly_czk"].as_f64().unwrap_or(0.0) * multiplier)
         })).collect();
 
-        Ok(serde_json::json!({
+        in_reporting_currency(conn, serde_json::json!({
             "viewType": view_type,
             "summary": {
                 "totalIncomeCzk": format!("{:.2}", total_income * multiplier),
@@ -1466,57 +1726,56 @@ async fn budgeting_report(
                 .filter_map(|r| r.ok()).collect();
                 result
         };
-        let czk = |currency: &str, amount: f64| -> f64 {
+        let czk = |currency: &str, amount: Decimal| -> Decimal {
             if currency == "CZK" { return amount; }
-            amount * rates.get(currency).copied().unwrap_or(1.0)
+            amount * money::from_f64(rates.get(currency).copied().unwrap_or(1.0))
         };
 
         // Goals
-        let goals: Vec<(String, String, f64, String)> = {
+        let goals: Vec<(String, String, Decimal, String)> = {
             let mut stmt = conn.prepare(
                 "SELECT bg.category_id, tc.name, bg.amount, bg.currency
                  FROM budget_goals bg JOIN transaction_categories tc ON bg.category_id = tc.id
                  WHERE bg.timeframe = ?"
             )?;
-            let result = stmt.query_map([&timeframe], |row| Ok((row.get(0)?, row.get(1)?, row.get::<_, f64>(2)?, row.get(3)?)))?
+            let result = stmt.query_map([&timeframe], |row| Ok((row.get(0)?, row.get(1)?, money::parse_or_zero(&row.get::<_, String>(2)?), row.get(3)?)))?
                 .filter_map(|r| r.ok()).collect();
                 result
         };
 
         // Actual spending
-        let spending: Vec<(String, f64, String)> = {
+        let spending: Vec<(String, String, String)> = {
             let mut stmt = conn.prepare(
-                "SELECT bt.category_id, SUM(CAST(bt.amount AS REAL)), bt.currency
+                "SELECT bt.category_id, bt.amount, bt.currency
                  FROM bank_transactions bt
-                 WHERE bt.booking_date >= ? AND bt.booking_date <= ? AND bt.tx_type = 'debit' AND bt.category_id IS NOT NULL
-                 GROUP BY bt.category_id, bt.currency"
+                 WHERE bt.booking_date >= ? AND bt.booking_date <= ? AND bt.tx_type = 'debit' AND bt.category_id IS NOT NULL"
             )?;
             let result = stmt.query_map(rusqlite::params![start_date, end_date], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?, row.get::<_, String>(2)?))
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
             })?.filter_map(|r| r.ok()).collect();
             result
         };
 
-        let mut spending_map: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+        let mut spending_map: std::collections::HashMap<String, Decimal> = std::collections::HashMap::new();
         for (cat_id, amount, currency) in spending {
-            *spending_map.entry(cat_id).or_insert(0.0) += czk(&currency, amount);
+            *spending_map.entry(cat_id).or_default() += czk(&currency, money::parse_or_zero(&amount));
         }
 
         let report: Vec<Value> = goals.iter().map(|(cat_id, cat_name, goal_amount, goal_currency)| {
             let budget_czk = czk(goal_currency, *goal_amount);
-            let actual_czk = spending_map.get(cat_id).copied().unwrap_or(0.0);
+            let actual_czk = spending_map.get(cat_id).copied().unwrap_or_default();
             serde_json::json!({
                 "categoryId": cat_id,
                 "categoryName": cat_name,
-                "budgetCzk": format!("{:.2}", budget_czk),
-                "actualCzk": format!("{:.2}", actual_czk),
-                "remainingCzk": format!("{:.2}", budget_czk - actual_czk),
-                "usagePercent": if budget_czk > 0.0 { format!("{:.1}", (actual_czk / budget_czk) * 100.0) } else { "N/A".to_string() },
+                "budgetCzk": money::format(budget_czk, "CZK"),
+                "actualCzk": money::format(actual_czk, "CZK"),
+                "remainingCzk": money::format(budget_czk - actual_czk, "CZK"),
+                "usagePercent": if budget_czk > Decimal::ZERO { format!("{:.1}", money::to_f64(actual_czk / budget_czk) * 100.0) } else { "N/A".to_string() },
                 "overBudget": actual_czk > budget_czk,
             })
         }).collect();
 
-        Ok(serde_json::json!({ "timeframe": timeframe, "budgetCategories": report }))
+        in_reporting_currency(conn, serde_json::json!({ "timeframe": timeframe, "budgetCategories": report }))
     }).map(Json).map_err(db_err)
 }
 
@@ -1532,9 +1791,9 @@ async fn stocks_analysis(
                 .filter_map(|r| r.ok()).collect();
                 result
         };
-        let czk = |currency: &str, amount: f64| -> f64 {
+        let czk = |currency: &str, amount: Decimal| -> Decimal {
             if currency == "CZK" { return amount; }
-            amount * rates.get(currency).copied().unwrap_or(1.0)
+            amount * money::from_f64(rates.get(currency).copied().unwrap_or(1.0))
         };
 
         let mut stmt = conn.prepare(
@@ -1551,48 +1810,50 @@ async fn stocks_analysis(
              LEFT JOIN dividend_overrides do2 ON si.ticker = do2.ticker"
         )?;
 
-        let stocks: Vec<Value> = stmt.query_map([], |row| {
+        let rows: Vec<(Value, Decimal, Decimal, Decimal)> = stmt.query_map([], |row| {
             let id: String = row.get(0)?;
             let ticker: String = row.get(1)?;
             let company: String = row.get(2)?;
-            let qty: f64 = row.get::<_, String>(3)?.parse().unwrap_or(0.0);
-            let avg: f64 = row.get::<_, String>(4)?.parse().unwrap_or(0.0);
+            let qty = money::parse_or_zero(&row.get::<_, String>(3)?);
+            let avg = money::parse_or_zero(&row.get::<_, String>(4)?);
             let currency: String = row.get(5)?;
-            let curr_price: f64 = row.get::<_, String>(6)?.parse().unwrap_or(0.0);
+            let curr_price = money::parse_or_zero(&row.get::<_, String>(6)?);
             let price_currency: String = row.get(7)?;
-            let yearly_div: f64 = row.get::<_, String>(8)?.parse().unwrap_or(0.0);
+            let yearly_div = money::parse_or_zero(&row.get::<_, String>(8)?);
             let div_currency: String = row.get(9)?;
             Ok((id, ticker, company, qty, avg, currency, curr_price, price_currency, yearly_div, div_currency))
         })?.filter_map(|r| r.ok()).map(|(id, ticker, company, qty, avg, currency, curr_price, price_currency, yearly_div, div_currency)| {
             let curr_val_czk = czk(&price_currency, qty * curr_price);
             let cost_czk = czk(&currency, qty * avg);
             let gain_czk = curr_val_czk - cost_czk;
-            let gain_pct = if cost_czk > 0.0 { (gain_czk / cost_czk) * 100.0 } else { 0.0 };
+            let gain_pct = if cost_czk > Decimal::ZERO { money::to_f64(gain_czk / cost_czk) * 100.0 } else { 0.0 };
             let div_czk = czk(&div_currency, qty * yearly_div);
-            let div_yield = if curr_val_czk > 0.0 { (div_czk / curr_val_czk) * 100.0 } else { 0.0 };
-            serde_json::json!({
+            let div_yield = if curr_val_czk > Decimal::ZERO { money::to_f64(div_czk / curr_val_czk) * 100.0 } else { 0.0 };
+            let stock = serde_json::json!({
                 "id": id,
                 "ticker": ticker,
                 "companyName": company,
-                "quantity": qty,
-                "currentValueCzk": format!("{:.2}", curr_val_czk),
-                "costBasisCzk": format!("{:.2}", cost_czk),
-                "gainLossCzk": format!("{:.2}", gain_czk),
+                "quantity": money::to_f64(qty),
+                "currentValueCzk": money::format(curr_val_czk, "CZK"),
+                "costBasisCzk": money::format(cost_czk, "CZK"),
+                "gainLossCzk": money::format(gain_czk, "CZK"),
                 "gainLossPct": format!("{:.2}", gain_pct),
-                "annualDividendCzk": format!("{:.2}", div_czk),
+                "annualDividendCzk": money::format(div_czk, "CZK"),
                 "dividendYieldPct": format!("{:.2}", div_yield),
-            })
+            });
+            (stock, curr_val_czk, gain_czk, div_czk)
         }).collect();
 
-        let total_val: f64 = stocks.iter().filter_map(|v| v["currentValueCzk"].as_str()?.parse::<f64>().ok()).sum();
-        let total_gain: f64 = stocks.iter().filter_map(|v| v["gainLossCzk"].as_str()?.parse::<f64>().ok()).sum();
-        let total_div: f64 = stocks.iter().filter_map(|v| v["annualDividendCzk"].as_str()?.parse::<f64>().ok()).sum();
+        let total_val: Decimal = rows.iter().map(|(_, value, _, _)| *value).sum();
+        let total_gain: Decimal = rows.iter().map(|(_, _, gain, _)| *gain).sum();
+        let total_div: Decimal = rows.iter().map(|(_, _, _, dividend)| *dividend).sum();
+        let stocks: Vec<Value> = rows.into_iter().map(|(stock, _, _, _)| stock).collect();
 
-        Ok(serde_json::json!({
+        in_reporting_currency(conn, serde_json::json!({
             "summary": {
-                "totalValueCzk": format!("{:.2}", total_val),
-                "totalGainLossCzk": format!("{:.2}", total_gain),
-                "annualDividendsCzk": format!("{:.2}", total_div),
+                "totalValueCzk": money::format(total_val, "CZK"),
+                "totalGainLossCzk": money::format(total_gain, "CZK"),
+                "annualDividendsCzk": money::format(total_div, "CZK"),
             },
             "stocks": stocks,
         }))
@@ -1718,7 +1979,217 @@ async fn tag_metrics(
                 })
                 .collect();
 
-            Ok(Value::Array(result))
+            in_reporting_currency(conn, Value::Array(result))
+        })
+        .map(Json)
+        .map_err(db_err)
+}
+
+async fn xirr_report(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(params): Query<XirrParams>,
+) -> ApiResult {
+    auth!(headers, state);
+    let scope = params.scope.unwrap_or(XirrScope::Portfolio);
+    state
+        .db
+        .with_conn(|conn| {
+            crate::services::xirr::compute_xirr(conn, scope, params.start_date, params.end_date)
+        })
+        .map_err(|e| match e {
+            crate::error::AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })
+        .map(|results| {
+            Json(serde_json::json!({
+                "scope": scope,
+                "results": results,
+                "note": "Annualized money-weighted return in percent; amounts in each result's \
+                         currency, the reporting currency, converted at the rate of each cash \
+                         flow's day. Without startDate the range starts at the first cash flow."
+            }))
+        })
+}
+
+async fn risk_report(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(params): Query<RiskParams>,
+) -> ApiResult {
+    auth!(headers, state);
+    let scope = params.scope.unwrap_or(RiskScope::Portfolio);
+    state
+        .db
+        .with_conn(|conn| {
+            let results = crate::services::risk::compute_risk(
+                conn,
+                scope,
+                params.benchmark.as_deref(),
+                params.risk_free_rate,
+                params.start_date,
+                params.end_date,
+            )?;
+            Ok((
+                results,
+                crate::services::reporting::reporting_currency(conn),
+            ))
+        })
+        .map_err(|e| match e {
+            crate::error::AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })
+        .map(|(results, currency)| {
+            Json(serde_json::json!({
+                "scope": scope,
+                "reportingCurrency": currency,
+                "results": results,
+                "note": "Returns, volatility and drawdown in percent from flow-adjusted daily \
+                         returns in the reporting currency at the rate of each day; volatility \
+                         and return are annualized."
+            }))
+        })
+}
+
+async fn benchmarks_list(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> ApiResult {
+    auth!(headers, state);
+    state
+        .db
+        .with_conn(crate::services::benchmarks::get_benchmarks)
+        .map(|benchmarks| Json(serde_json::to_value(benchmarks).unwrap_or_default()))
+        .map_err(db_err)
+}
+
+async fn benchmark_comparison(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(params): Query<BenchmarkParams>,
+) -> ApiResult {
+    auth!(headers, state);
+    state
+        .db
+        .with_conn(|conn| {
+            crate::services::benchmarks::compare(
+                conn,
+                &params.benchmark,
+                params.tag_id.as_deref(),
+                params.start_date,
+                params.end_date,
+            )
+        })
+        .map_err(|e| match e {
+            crate::error::AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            crate::error::AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })
+        .map(|comparison| Json(serde_json::to_value(comparison).unwrap_or_default()))
+}
+
+async fn exposure_report(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(params): Query<ExposureParams>,
+) -> ApiResult {
+    auth!(headers, state);
+    let dimension = params.dimension.unwrap_or(ExposureDimension::Sector);
+    state
+        .db
+        .with_conn(|conn| {
+            let mut report = crate::services::exposure::get_exposure(
+                conn,
+                dimension,
+                params.stocks_only.unwrap_or(false),
+                params.exclude_personal_real_estate.unwrap_or(false),
+            )?;
+            let reporting = Reporting::load(conn)?;
+            report.total_value = reporting.amount(report.total_value);
+            for item in &mut report.items {
+                item.value = reporting.amount(item.value);
+            }
+            let mut value = serde_json::to_value(report).unwrap_or_default();
+            if let Value::Object(map) = &mut value {
+                map.insert(
+                    "reportingCurrency".into(),
+                    Value::String(reporting.currency),
+                );
+            }
+            Ok(value)
+        })
+        .map(Json)
+        .map_err(db_err)
+}
+
+async fn exposure_history(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+    Query(params): Query<ExposureParams>,
+) -> ApiResult {
+    auth!(headers, state);
+    let dimension = params.dimension.unwrap_or(ExposureDimension::Sector);
+    state
+        .db
+        .with_conn(|conn| {
+            let mut history = crate::services::exposure::get_exposure_history(
+                conn,
+                dimension,
+                params.start_date,
+                params.end_date,
+            )?;
+            let reporting = Reporting::load(conn)?;
+            for point in &mut history {
+                reporting.convert_exposure_history(point);
+            }
+            Ok((history, reporting.currency))
+        })
+        .map_err(|e| match e {
+            crate::error::AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        })
+        .map(|(history, currency)| {
+            Json(serde_json::json!({
+                "dimension": dimension,
+                "reportingCurrency": currency,
+                "history": history,
+                "note": "Stock holdings only; amounts in reportingCurrency at the rate of each day."
+            }))
+        })
+}
+
+async fn savings_goals(
+    AxumState(state): AxumState<Arc<ApiState>>,
+    headers: HeaderMap,
+) -> ApiResult {
+    auth!(headers, state);
+    let now = chrono::Utc::now().timestamp();
+    state
+        .db
+        .with_conn(|conn| {
+            let goals: Vec<Value> = crate::services::goals::get_progress(conn, now)?
+                .into_iter()
+                .map(|p| {
+                    let amounts = czk_amounts(
+                        &p.goal.currency,
+                        &[
+                            ("targetAmount", &p.goal.target_amount),
+                            ("currentValue", &p.current_value),
+                            ("remaining", &p.remaining),
+                            ("requiredMonthly", &p.required_monthly),
+                        ],
+                    );
+                    let mut value = serde_json::to_value(p).unwrap_or_default();
+                    value["reportingAmounts"] = amounts;
+                    value
+                })
+                .collect();
+            in_reporting_currency(conn, serde_json::json!({
+                "goals": goals,
+                "note": "Amounts in each goal's currency; requiredMonthly reaches the target by \
+                         targetDate at the goal's expected yearly return. reportingAmounts holds \
+                         them in CZK (*Czk) and in reportingCurrency (same keys without the suffix)."
+            }))
         })
         .map(Json)
         .map_err(db_err)
@@ -1798,6 +2269,7 @@ impl LocalApiServer {
             .route("/investments", get(investments_list))
             .route("/investments/{id}", get(investment_detail))
             .route("/investments/transactions", get(investment_transactions))
+            .route("/brokerage-accounts", get(brokerage_accounts_list))
             .route("/investments/{ticker}/history", get(stock_value_history))
             .route("/crypto", get(crypto_list))
             .route("/crypto/transactions", get(crypto_transactions))
@@ -1809,6 +2281,7 @@ impl LocalApiServer {
             .route("/loans", get(loans_list))
             .route("/savings", get(savings_list))
             .route("/real-estate", get(real_estate_list))
+            .route("/real-estate/performance", get(real_estate_performance))
             .route("/real-estate/{id}", get(real_estate_detail))
             .route("/insurance", get(insurance_list).post(insurance_create))
             .route("/insurance/{id}", get(insurance_detail))
@@ -1821,6 +2294,13 @@ impl LocalApiServer {
             .route("/analytics/budgeting", get(budgeting_report))
             .route("/analytics/stocks", get(stocks_analysis))
             .route("/analytics/tags", get(tag_metrics))
+            .route("/analytics/xirr", get(xirr_report))
+            .route("/analytics/risk", get(risk_report))
+            .route("/benchmarks", get(benchmarks_list))
+            .route("/analytics/benchmark", get(benchmark_comparison))
+            .route("/analytics/exposure", get(exposure_report))
+            .route("/analytics/exposure/history", get(exposure_history))
+            .route("/goals", get(savings_goals))
             .route("/exchange-rates", get(exchange_rates))
             .with_state(state);
 
@@ -2,16 +2,35 @@
 //!
 //! Services contain the core business logic and external API integrations
 
+pub mod allocation;
 pub mod auth;
 pub mod bank_accounts;
+pub mod benchmarks;
+pub mod bonds;
+pub mod broker_import;
+pub mod brokerage_accounts;
 pub mod budgeting;
 pub mod categorization;
 pub mod crypto;
+pub mod crypto_exchange_import;
 pub mod crypto_investments;
 pub mod csv_import;
 pub mod currency;
 pub mod date_parser;
+pub mod dividend_tax;
+pub mod exchange_rate_history;
+pub mod exchange_rates;
+pub mod exposure;
+pub mod goals;
+pub mod interest;
 pub mod investments;
+pub mod loans;
 pub mod local_api;
+pub mod money;
 pub mod price_api;
+pub mod price_providers;
 pub mod pricing;
+pub mod real_estate;
+pub mod reporting;
+pub mod risk;
+pub mod xirr;
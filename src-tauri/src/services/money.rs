@@ -0,0 +1,178 @@
+//! Exact decimal money arithmetic
+//!
+//! Amounts, prices and quantities are stored as decimal strings. They are
+//! parsed into `Decimal` and summed exactly; only exchange rates (published as
+//! floats) enter as `f64`. Money is rounded to the minor unit of its currency
+//! (half away from zero, like bank statements) when stored or reported.
+//! Quantities and unit prices keep their full precision.
+
+use crate::services::currency;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::RoundingStrategy;
+use std::str::FromStr;
+
+pub use rust_decimal::Decimal;
+
+/// Decimal places kept for derived unit prices (e.g. average buy price)
+pub const PRICE_DP: u32 = 10;
+
+/// Decimal places of the minor unit of a currency (ISO 4217).
+/// Crypto assets priced in themselves use 8 (satoshi).
+pub fn minor_units(currency: &str) -> u32 {
+    match currency.trim().to_uppercase().as_str() {
+        "JPY" | "KRW" | "ISK" | "CLP" | "VND" | "PYG" | "UGX" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" | "LYD" | "IQD" => 3,
+        "BTC" | "ETH" => 8,
+        _ => 2,
+    }
+}
+
+/// Parse a stored decimal string ("1234.5", "1 234,50", "1,234.50", "1e-8", "10 USD").
+/// Returns None for empty or invalid input.
+pub fn parse(text: &str) -> Option<Decimal> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    // Drop a trailing unit ("10 USD") but keep digit groups ("1 234,50")
+    let number: String = text
+        .split_whitespace()
+        .take_while(|part| !part.chars().any(|c| c.is_ascii_alphabetic()) || is_exponent(part))
+        .collect::<Vec<_>>()
+        .concat();
+    // A comma is the decimal separator unless a dot is present ("1,234.50")
+    let number = if number.contains('.') {
+        number.replace(',', "")
+    } else {
+        number.replace(',', ".")
+    };
+    Decimal::from_str(&number)
+        .or_else(|_| Decimal::from_scientific(&number))
+        .ok()
+}
+
+fn is_exponent(part: &str) -> bool {
+    let lower = part.to_ascii_lowercase();
+    lower.contains('e') && Decimal::from_scientific(&lower).is_ok()
+}
+
+/// Parse a stored decimal string, zero if empty or invalid
+pub fn parse_or_zero(text: &str) -> Decimal {
+    parse(text).unwrap_or_default()
+}
+
+/// Decimal from a float (prices from providers, converted amounts)
+pub fn from_f64(value: f64) -> Decimal {
+    if !value.is_finite() {
+        return Decimal::ZERO;
+    }
+    Decimal::from_f64(value).unwrap_or_default()
+}
+
+/// Float for charts and ratios
+pub fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+/// Round to the minor unit of a currency, half away from zero
+pub fn round(amount: Decimal, currency: &str) -> Decimal {
+    amount.round_dp_with_strategy(
+        minor_units(currency),
+        RoundingStrategy::MidpointAwayFromZero,
+    )
+}
+
+/// Amount rounded to the minor unit of its currency, with all its decimals ("12.50")
+pub fn format(amount: Decimal, currency: &str) -> String {
+    let dp = minor_units(currency) as usize;
+    format!("{:.dp$}", round(amount, currency))
+}
+
+/// Quantity or unit price at full precision without trailing zeros
+pub fn format_exact(value: Decimal) -> String {
+    value.normalize().to_string()
+}
+
+/// Derived unit price (e.g. cost / quantity) at `PRICE_DP` precision
+pub fn format_price(value: Decimal) -> String {
+    format_exact(value.round_dp_with_strategy(PRICE_DP, RoundingStrategy::MidpointAwayFromZero))
+}
+
+/// CZK per unit of a currency as a decimal
+fn rate(currency: &str) -> Decimal {
+    from_f64(currency::get_exchange_rate(&currency.to_uppercase()))
+}
+
+/// Convert an amount to CZK at the current rate (unrounded)
+pub fn to_czk(amount: Decimal, currency: &str) -> Decimal {
+    if currency.eq_ignore_ascii_case("CZK") {
+        return amount;
+    }
+    amount * rate(currency)
+}
+
+/// Convert an amount between currencies at the current rates (unrounded)
+pub fn convert_between(amount: Decimal, from: &str, to: &str) -> Decimal {
+    if from.eq_ignore_ascii_case(to) {
+        return amount;
+    }
+    let czk = to_czk(amount, from);
+    if to.eq_ignore_ascii_case("CZK") {
+        return czk;
+    }
+    let to_rate = rate(to);
+    if to_rate.is_zero() {
+        return czk;
+    }
+    czk / to_rate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(text: &str) -> Decimal {
+        Decimal::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        assert_eq!(parse("1234.5"), Some(dec("1234.5")));
+        assert_eq!(parse(" 1 234,50 "), Some(dec("1234.50")));
+        assert_eq!(parse("1,234.50"), Some(dec("1234.50")));
+        assert_eq!(parse("10 USD"), Some(dec("10")));
+        assert_eq!(parse("1e-8"), Some(dec("0.00000001")));
+        assert_eq!(parse("0.123456789012"), Some(dec("0.123456789012")));
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("abc"), None);
+        assert_eq!(parse_or_zero("abc"), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_sums_are_exact() {
+        let total: Decimal = ["0.10", "0.20", "0.30"]
+            .iter()
+            .map(|a| parse_or_zero(a))
+            .sum();
+        assert_eq!(total, dec("0.60"));
+        assert_eq!(format(total, "CZK"), "0.60");
+        // The same sum in floats does not reconcile
+        assert_ne!(0.1_f64 + 0.2 + 0.3, 0.6);
+    }
+
+    #[test]
+    fn test_rounding_per_currency() {
+        assert_eq!(format(dec("2.345"), "CZK"), "2.35");
+        assert_eq!(format(dec("-2.345"), "EUR"), "-2.35");
+        assert_eq!(format(dec("1234.5"), "JPY"), "1235");
+        assert_eq!(format(dec("1.2345"), "KWD"), "1.235");
+        assert_eq!(format(dec("7"), "CZK"), "7.00");
+    }
+
+    #[test]
+    fn test_exact_formats_keep_precision() {
+        assert_eq!(format_exact(dec("0.12345678900")), "0.123456789");
+        assert_eq!(format_exact(dec("10.000")), "10");
+        assert_eq!(format_price(dec("10") / dec("3")), "3.3333333333");
+    }
+}
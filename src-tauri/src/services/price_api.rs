@@ -1,10 +1,16 @@
 //! External API services for price fetching
 //!
-//! - Yahoo Finance API for stock prices and dividends (via yahoo_finance_api crate)
-//! - CoinGecko API for cryptocurrency prices and search
+//! - Latest and historical prices through the provider chain (see `price_providers`)
+//! - Yahoo Finance API for dividends and ticker search (via yahoo_finance_api crate)
+//! - CoinGecko API for cryptocurrency search
 
 use crate::db::Database;
 use crate::error::{AppError, Result};
+use crate::models::PriceProviderId;
+use crate::services::currency::normalize_minor_unit;
+use crate::services::money;
+use crate::services::price_providers::{ChainQuotes, PriceRequest, ProviderChain};
+use crate::services::pricing;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -54,6 +60,7 @@ pub struct StockPriceResult {
     pub ticker: String,
     pub price: f64,
     pub currency: String,
+    pub provider: PriceProviderId,
 }
 
 /// Result from stock price refresh
@@ -62,160 +69,168 @@ pub struct StockPriceRefreshResult {
     pub updated: Vec<StockPriceResult>,
     pub remaining_tickers: Vec<String>,
     pub rate_limit_hit: bool,
+    /// Tickers no provider could price; their stored price is unchanged
+    pub failed_tickers: Vec<String>,
 }
 
-/// Refresh stock prices from Yahoo Finance using yahoo_finance_api crate
-/// This crate handles the cookie/crumb authentication that Yahoo requires
-/// Works for US, European (.DE, .PA, .MI, etc.) and HK (.HK) stocks
-pub async fn refresh_stock_prices_yahoo(
+/// Refresh stock prices through the provider chain (see `price_providers`).
+/// Each ticker tries its preferred provider first, then the fallback order,
+/// so one failing source does not leave the price stale.
+pub async fn refresh_stock_prices(
     db: &Database,
     tickers: Vec<String>,
     force_refresh: bool,
 ) -> Result<StockPriceRefreshResult> {
-    use yahoo_finance_api as yahoo;
-
     if tickers.is_empty() {
         return Ok(StockPriceRefreshResult {
             updated: vec![],
             remaining_tickers: vec![],
             rate_limit_hit: false,
+            failed_tickers: vec![],
         });
     }
 
-    let mut updated_prices = Vec::new();
-    let mut failed_tickers: Vec<(String, String)> = Vec::new();
     let now = unix_timestamp_now();
 
-    log::info!(
-        "[YAHOO FINANCE] Fetching prices for {} tickers",
-        tickers.len()
-    );
-    log::info!("[YAHOO FINANCE] Tickers: {:?}", tickers);
+    log::info!("[PRICE API] Fetching prices for {} tickers", tickers.len());
+    log::info!("[PRICE API] Tickers: {:?}", tickers);
 
-    // Create Yahoo connector (handles cookies and authentication)
-    let provider = yahoo::YahooConnector::new()
-        .map_err(|e| AppError::ExternalApi(format!("Yahoo connector failed: {}", e)))?;
-
-    // Process each ticker (with caching check)
-    for ticker in &tickers {
-        let ticker_upper = ticker.to_uppercase();
-
-        // Check if we have a recent price (within PRICE_CACHE_HOURS)
-        // Skip cache check if force_refresh is true
-        let should_fetch = if force_refresh {
-            true
-        } else {
-            db.with_conn(|conn| {
-                let last_fetched: Option<i64> = conn
-                    .query_row(
-                        "SELECT fetched_at FROM stock_data WHERE ticker = ?1",
-                        [&ticker_upper],
-                        |row| row.get(0),
-                    )
-                    .ok();
-
-                if let Some(fetched_at) = last_fetched {
-                    let hours_since = (now - fetched_at) / 3600;
-                    Ok(hours_since >= PRICE_CACHE_HOURS)
-                } else {
-                    Ok(true) // No cached price, should fetch
-                }
-            })?
-        };
-
-        if !should_fetch {
-            log::info!(
-                "[YAHOO FINANCE] Skipping {} (cached within {} hours)",
-                ticker,
-                PRICE_CACHE_HOURS
-            );
-            continue;
-        }
-
-        // Use get_latest_quotes and read regular_market_price from the response metadata.
-        // This field is always populated by Yahoo Finance with the most recent market price
-        // regardless of market hours (open, pre-market, after-hours, weekends).
-        // last_quote().close only returns completed candle closes — during open market
-        // hours today's bar has close=null and it falls back to yesterday's close.
-        match provider.get_latest_quotes(ticker, "1d").await {
-            Ok(response) => {
-                // Prefer regular_market_price from metadata (reflects current price),
-                // fall back to last_quote().close if metadata price is unavailable.
-                let price = response
-                    .metadata()
-                    .ok()
-                    .and_then(|m| m.regular_market_price)
-                    .filter(|&p| p > 0.0)
-                    .or_else(|| {
-                        response
-                            .last_quote()
-                            .ok()
-                            .map(|q| q.close)
-                            .filter(|&p| p > 0.0)
-                    });
-                match price {
-                    Some(price) => {
-                        let currency = get_currency_from_ticker(ticker);
-                        if let Err(e) = db.with_conn(|conn| {
-                            conn.execute(
-                                "INSERT INTO stock_data (id, ticker, original_price, currency, price_date, fetched_at)
-                                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)
-                                 ON CONFLICT(ticker) DO UPDATE SET
-                                   original_price = ?3, currency = ?4, price_date = ?5, fetched_at = ?5",
-                                rusqlite::params![
-                                    uuid::Uuid::new_v4().to_string(),
-                                    &ticker_upper,
-                                    format!("{:.2}", price),
-                                    &currency,
-                                    now,
-                                ],
-                            )?;
-                            Ok(())
-                        }) {
-                            failed_tickers.push((ticker.clone(), format!("DB error: {}", e)));
-                            continue;
-                        }
-                        updated_prices.push(StockPriceResult {
-                            ticker: ticker_upper,
-                            price,
-                            currency: currency.to_string(),
-                        });
-                    }
-                    None => {
-                        failed_tickers.push((ticker.clone(), "price unavailable".to_string()));
-                    }
-                }
-            }
-            Err(e) => {
-                failed_tickers.push((ticker.clone(), format!("{}", e)));
+    // Check if we have a recent price (within PRICE_CACHE_HOURS)
+    // Skip cache check if force_refresh is true
+    let requests: Vec<PriceRequest> = db.with_conn(|conn| {
+        let mut requests = Vec::new();
+        for ticker in &tickers {
+            if force_refresh || is_stock_price_stale(conn, ticker, now) {
+                requests.push(PriceRequest::stock(ticker));
+            } else {
+                log::info!(
+                    "[PRICE API] Skipping {} (cached within {} hours)",
+                    ticker,
+                    PRICE_CACHE_HOURS
+                );
             }
         }
-    }
+        Ok(requests)
+    })?;
+
+    let chain = db.with_conn(ProviderChain::load)?;
+    let fetched = chain.latest_quotes(&requests).await;
+    let (updated_prices, failed_tickers) =
+        db.with_conn(|conn| Ok(store_stock_quotes(conn, fetched, now)))?;
 
     // Summary logging
-    log::info!("[YAHOO FINANCE] ========== SUMMARY ==========");
-    log::info!("[YAHOO FINANCE] Updated: {} tickers", updated_prices.len());
-    if !updated_prices.is_empty() {
-        let updated_list: Vec<_> = updated_prices.iter().map(|p| p.ticker.as_str()).collect();
-        log::info!("[YAHOO FINANCE] Successfully updated: {:?}", updated_list);
+    log::info!("[PRICE API] ========== SUMMARY ==========");
+    log::info!("[PRICE API] Updated: {} tickers", updated_prices.len());
+    for price in &updated_prices {
+        log::info!(
+            "[PRICE API]   + {}: {} {} ({})",
+            price.ticker,
+            price.price,
+            price.currency,
+            price.provider.as_str()
+        );
     }
     if !failed_tickers.is_empty() {
-        log::warn!("[YAHOO FINANCE] Failed: {} tickers", failed_tickers.len());
+        log::warn!("[PRICE API] Failed: {} tickers", failed_tickers.len());
         for (ticker, reason) in &failed_tickers {
-            log::warn!("[YAHOO FINANCE]   - {}: {}", ticker, reason);
+            log::warn!("[PRICE API]   - {}: {}", ticker, reason);
         }
     }
-    log::info!("[YAHOO FINANCE] ==============================");
+    log::info!("[PRICE API] ==============================");
 
     Ok(StockPriceRefreshResult {
         updated: updated_prices,
         remaining_tickers: vec![],
         rate_limit_hit: false,
+        failed_tickers: failed_tickers
+            .into_iter()
+            .map(|(ticker, _)| ticker)
+            .collect(),
     })
 }
 
-/// Get currency from ticker suffix
-fn get_currency_from_ticker(ticker: &str) -> &'static str {
+fn is_stock_price_stale(conn: &rusqlite::Connection, ticker: &str, now: i64) -> bool {
+    let last_fetched: Option<i64> = conn
+        .query_row(
+            "SELECT fetched_at FROM stock_data WHERE ticker = ?1",
+            [ticker.to_uppercase()],
+            |row| row.get(0),
+        )
+        .ok();
+
+    match last_fetched {
+        Some(fetched_at) => (now - fetched_at) / 3600 >= PRICE_CACHE_HOURS,
+        None => true, // No cached price, should fetch
+    }
+}
+
+/// Upsert fetched quotes into stock_data.
+/// Returns the stored prices and the tickers that failed, with the reason.
+pub fn store_stock_quotes(
+    conn: &rusqlite::Connection,
+    fetched: ChainQuotes,
+    now: i64,
+) -> (Vec<StockPriceResult>, Vec<(String, String)>) {
+    let mut updated = Vec::new();
+    let mut failed = fetched.failed;
+
+    for (ticker, (provider, quote)) in fetched.quotes {
+        let stored = conn.execute(
+            "INSERT INTO stock_data (id, ticker, original_price, currency, price_date, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(ticker) DO UPDATE SET
+               original_price = ?3, currency = ?4, price_date = ?5, fetched_at = ?5",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                &ticker,
+                money::format_exact(money::from_f64(quote.price)),
+                &quote.currency,
+                now,
+            ],
+        );
+        if stored.is_ok() && quote.currency_reported {
+            if let Err(e) = pricing::record_provider_currency(conn, &ticker, &quote.currency, now) {
+                log::warn!("[PRICE API] Could not store currency of {}: {}", ticker, e);
+            }
+        }
+        match stored {
+            Ok(_) => updated.push(StockPriceResult {
+                ticker,
+                price: quote.price,
+                currency: quote.currency,
+                provider,
+            }),
+            Err(e) => failed.push((ticker, format!("DB error: {}", e))),
+        }
+    }
+
+    updated.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+    (updated, failed)
+}
+
+/// Quote currency of a dividend response (may be a minor unit such as "GBp"):
+/// provider metadata, else the stored ticker currency, else the ticker suffix
+fn dividend_currency(
+    db: &Database,
+    response: &yahoo_finance_api::YResponse,
+    ticker: &str,
+) -> String {
+    response
+        .metadata()
+        .ok()
+        .and_then(|m| m.currency)
+        .filter(|c| !c.is_empty())
+        .or_else(|| {
+            db.with_conn(|conn| Ok(pricing::stored_ticker_currency(conn, ticker)))
+                .ok()
+                .flatten()
+        })
+        .unwrap_or_else(|| get_currency_from_ticker(ticker).to_string())
+}
+
+/// Get currency from ticker suffix.
+/// Only a fallback: prefer the stored ticker currency (`pricing::stored_ticker_currency`).
+pub fn get_currency_from_ticker(ticker: &str) -> &'static str {
     if let Some(suffix) = ticker.split('.').nth(1) {
         match suffix.to_uppercase().as_str() {
             "L" | "LON" => "GBP",          // London
@@ -249,85 +264,76 @@ pub struct CryptoPriceResult {
     pub ticker: String,
     pub price: f64,
     pub currency: String,
+    pub provider: PriceProviderId,
 }
 
-/// Refresh crypto prices from CoinGecko API
+/// Refresh crypto prices through the provider chain (CoinGecko first by default)
 /// Takes coingecko_id -> ticker mapping
 pub async fn refresh_crypto_prices(
     db: &Database,
-    api_key: Option<&str>,
     id_to_ticker: HashMap<String, String>,
 ) -> Result<Vec<CryptoPriceResult>> {
     if id_to_ticker.is_empty() {
         return Ok(vec![]);
     }
 
-    let ids: Vec<&str> = id_to_ticker.keys().map(|s| s.as_str()).collect();
-    let ids_param = ids.join(",");
-    let url = format!(
-        "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd",
-        ids_param
-    );
-
-    log::info!("[PRICE API] Fetching crypto prices for: {}", ids_param);
-
-    let client = reqwest::Client::new();
-    let mut request = client.get(&url);
+    let requests: Vec<PriceRequest> = id_to_ticker
+        .iter()
+        .map(|(coingecko_id, ticker)| PriceRequest::crypto(ticker, Some(coingecko_id)))
+        .collect();
 
-    // Add API key header if provided
-    if let Some(key) = api_key {
-        if !key.is_empty() {
-            request = request.header("x-cg-demo-api-key", key);
-        }
+    let chain = db.with_conn(ProviderChain::load)?;
+    let fetched = chain.latest_quotes(&requests).await;
+    for (ticker, reason) in &fetched.failed {
+        log::warn!("[PRICE API] Crypto {} failed: {}", ticker, reason);
     }
 
-    let response = request
-        .send()
-        .await
-        .map_err(|e| AppError::ExternalApi(format!("CoinGecko request failed: {}", e)))?;
-
-    // CoinGecko returns { "bitcoin": { "usd": 12345.67 }, ... }
-    let price_data: HashMap<String, HashMap<String, f64>> = response
-        .json::<HashMap<String, HashMap<String, f64>>>()
-        .await
-        .map_err(|e| AppError::ExternalApi(format!("CoinGecko parse error: {}", e)))?;
+    db.with_conn(|conn| store_crypto_quotes(conn, &requests, fetched, unix_timestamp_now()))
+}
 
+/// Upsert fetched crypto quotes into crypto_prices
+pub fn store_crypto_quotes(
+    conn: &rusqlite::Connection,
+    requests: &[PriceRequest],
+    fetched: ChainQuotes,
+    now: i64,
+) -> Result<Vec<CryptoPriceResult>> {
     let mut updated_prices = Vec::new();
-    let now = unix_timestamp_now();
 
-    for (coingecko_id, prices) in price_data {
-        if let Some(usd_price) = prices.get("usd") {
-            if let Some(ticker) = id_to_ticker.get(&coingecko_id) {
-                let ticker_upper = ticker.to_uppercase();
-
-                // Upsert into crypto_prices table
-                db.with_conn(|conn| {
-                    conn.execute(
-                        "INSERT INTO crypto_prices (id, symbol, coingecko_id, price, currency, fetched_at)
-                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-                         ON CONFLICT(symbol) DO UPDATE SET
-                           price = ?4, currency = ?5, coingecko_id = ?3, fetched_at = ?6",
-                        rusqlite::params![
-                            uuid::Uuid::new_v4().to_string(),
-                            &ticker_upper,
-                            &coingecko_id,
-                            format!("{:.2}", usd_price),
-                            "USD",
-                            now,
-                        ],
-                    )?;
-                    Ok(())
-                })?;
-
-                log::info!("[PRICE API] Updated {}: {} USD", ticker_upper, usd_price);
-
-                updated_prices.push(CryptoPriceResult {
-                    ticker: ticker_upper,
-                    price: *usd_price,
-                    currency: "USD".to_string(),
-                });
-            }
-        }
+    for request in requests {
+        let Some((provider, quote)) = fetched.quotes.get(&request.ticker) else {
+            continue;
+        };
+
+        conn.execute(
+            "INSERT INTO crypto_prices (id, symbol, coingecko_id, price, currency, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(symbol) DO UPDATE SET
+               price = ?4, currency = ?5, coingecko_id = ?3, fetched_at = ?6",
+            rusqlite::params![
+                uuid::Uuid::new_v4().to_string(),
+                &request.ticker,
+                &request.coingecko_id,
+                money::format_exact(money::from_f64(quote.price)),
+                &quote.currency,
+                now,
+            ],
+        )?;
+
+        log::info!(
+            "[PRICE API] Updated {}: {} {} ({})",
+            request.ticker,
+            quote.price,
+            quote.currency,
+            provider.as_str()
+        );
+
+        updated_prices.push(CryptoPriceResult {
+            ticker: request.ticker.clone(),
+            price: quote.price,
+            currency: quote.currency.clone(),
+            provider: *provider,
+        });
     }
 
     Ok(updated_prices)
@@ -531,8 +537,11 @@ pub async fn refresh_dividends(db: &Database, tickers: Vec<String>) -> Result<Ve
                 // Get dividends from the response - dividends() returns Result, not Option
                 match response.dividends() {
                     Ok(div_data) => {
-                        let yearly_sum: f64 = div_data.iter().map(|d| d.amount).sum();
-                        let currency = get_currency_from_ticker(&ticker).to_string();
+                        let currency = dividend_currency(db, &response, &ticker);
+                        let (yearly_sum, currency) = normalize_minor_unit(
+                            div_data.iter().map(|d| d.amount).sum(),
+                            &currency,
+                        );
 
                         // Store in database
                         if let Err(e) = db.with_conn(|conn| {
@@ -544,7 +553,7 @@ pub async fn refresh_dividends(db: &Database, tickers: Vec<String>) -> Result<Ve
                                 rusqlite::params![
                                     uuid::Uuid::new_v4().to_string(),
                                     &ticker,
-                                    format!("{:.2}", yearly_sum),
+                                    money::format_exact(money::from_f64(yearly_sum)),
                                     &currency,
                                     now,
                                 ],
@@ -563,7 +572,8 @@ pub async fn refresh_dividends(db: &Database, tickers: Vec<String>) -> Result<Ve
                     }
                     Err(_) => {
                         // No dividends found - store as 0
-                        let currency = get_currency_from_ticker(&ticker).to_string();
+                        let (_, currency) =
+                            normalize_minor_unit(0.0, &dividend_currency(db, &response, &ticker));
 
                         db.with_conn(|conn| {
                             conn.execute(
@@ -630,92 +640,33 @@ pub struct BackfillProgress {
     pub completed: bool,
 }
 
-/// Get historical stock prices from Yahoo Finance for a date range
-/// Returns a map of ticker -> (date_timestamp -> price)
-/// Uses batched requests with throttling to avoid rate limits
-pub async fn get_historical_stock_prices_yahoo(
+/// Get historical stock prices for a date range through the provider chain
+/// Returns a map of ticker -> daily prices; tickers without data are left out
+pub async fn get_historical_stock_prices(
+    db: &Database,
     tickers: &[String],
     start_timestamp: i64,
     end_timestamp: i64,
 ) -> Result<HashMap<String, Vec<HistoricalPrice>>> {
-    use yahoo_finance_api as yahoo;
-
     if tickers.is_empty() {
         return Ok(HashMap::new());
     }
 
-    let mut results: HashMap<String, Vec<HistoricalPrice>> = HashMap::new();
-
     log::info!(
-        "[YAHOO HISTORICAL] Fetching historical prices for {} tickers from {} to {}",
+        "[HISTORICAL] Fetching historical prices for {} tickers from {} to {}",
         tickers.len(),
         start_timestamp,
         end_timestamp
     );
 
-    // Create Yahoo connector - return empty if offline/failed
-    let provider = match yahoo::YahooConnector::new() {
-        Ok(p) => p,
-        Err(e) => {
-            log::warn!(
-                "[YAHOO HISTORICAL] Connector failed (offline?): {} - returning empty",
-                e
-            );
-            return Ok(HashMap::new());
-        }
-    };
-
-    // Convert timestamps to time::OffsetDateTime
-    let start = time::OffsetDateTime::from_unix_timestamp(start_timestamp)
-        .unwrap_or(time::OffsetDateTime::now_utc());
-    let end = time::OffsetDateTime::from_unix_timestamp(end_timestamp)
-        .unwrap_or(time::OffsetDateTime::now_utc());
-
-    // Process in batches of 5 with 500ms delay between batches
-    for (batch_idx, chunk) in tickers.chunks(5).enumerate() {
-        if batch_idx > 0 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        }
-
-        for ticker in chunk {
-            match provider.get_quote_history(ticker, start, end).await {
-                Ok(response) => {
-                    // quotes() returns a Result, not an Option
-                    match response.quotes() {
-                        Ok(quotes) => {
-                            let currency = get_currency_from_ticker(ticker).to_string();
-                            let prices: Vec<HistoricalPrice> = quotes
-                                .iter()
-                                .map(|q| HistoricalPrice {
-                                    timestamp: q.timestamp,
-                                    price: q.close,
-                                    currency: currency.clone(),
-                                })
-                                .collect();
-
-                            if !prices.is_empty() {
-                                log::info!(
-                                    "[YAHOO HISTORICAL] {} - got {} historical prices",
-                                    ticker,
-                                    prices.len()
-                                );
-                                results.insert(ticker.clone(), prices);
-                            }
-                        }
-                        Err(e) => {
-                            log::warn!("[YAHOO HISTORICAL] {} - no quotes: {}", ticker, e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    log::warn!("[YAHOO HISTORICAL] {} - error: {}", ticker, e);
-                }
-            }
-        }
-    }
+    let requests: Vec<PriceRequest> = tickers.iter().map(|t| PriceRequest::stock(t)).collect();
+    let chain = db.with_conn(ProviderChain::load)?;
+    let results = chain
+        .history(&requests, start_timestamp, end_timestamp)
+        .await;
 
     log::info!(
-        "[YAHOO HISTORICAL] Fetched historical data for {} tickers",
+        "[HISTORICAL] Fetched historical data for {} tickers",
         results.len()
     );
 
@@ -776,9 +727,9 @@ pub async fn get_historical_crypto_price_coingecko(
 }
 
 /// Get historical crypto prices for multiple coins over a date range
-/// Uses CoinGecko's market_chart/range endpoint which is more efficient
-pub async fn get_historical_crypto_prices_coingecko(
-    api_key: Option<&str>,
+/// Takes coingecko_id -> ticker mapping; results are keyed by ticker
+pub async fn get_historical_crypto_prices(
+    db: &Database,
     id_to_ticker: &HashMap<String, String>,
     start_timestamp: i64,
     end_timestamp: i64,
@@ -787,106 +738,22 @@ pub async fn get_historical_crypto_prices_coingecko(
         return Ok(HashMap::new());
     }
 
-    let mut results: HashMap<String, Vec<HistoricalPrice>> = HashMap::new();
-
     log::info!(
-        "[COINGECKO HISTORICAL] Fetching historical prices for {} cryptos",
+        "[HISTORICAL] Fetching historical prices for {} cryptos",
         id_to_ticker.len()
     );
-    log::info!(
-        "[COINGECKO HISTORICAL] API key present: {}",
-        api_key.map(|k| !k.is_empty()).unwrap_or(false)
-    );
-
-    let client = reqwest::Client::new();
 
-    // Process each crypto with a delay to stay within CoinGecko free tier (~40 req/min)
-    for (idx, (coingecko_id, ticker)) in id_to_ticker.iter().enumerate() {
-        if idx > 0 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-        }
-
-        let url = format!(
-            "https://api.coingecko.com/api/v3/coins/{}/market_chart/range?vs_currency=usd&from={}&to={}",
-            coingecko_id, start_timestamp, end_timestamp
-        );
-
-        let mut request = client.get(&url);
-
-        if let Some(key) = api_key {
-            if !key.is_empty() {
-                request = request.header("x-cg-demo-api-key", key);
-            }
-        }
-
-        match request.send().await {
-            Ok(response) => {
-                if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                    log::warn!(
-                        "[COINGECKO HISTORICAL] Rate limit hit at {} ({}), stopping early with {} results",
-                        ticker,
-                        coingecko_id,
-                        results.len()
-                    );
-                    break;
-                }
-                if response.status().is_success() {
-                    #[derive(Deserialize)]
-                    struct RangeResponse {
-                        prices: Option<Vec<(f64, f64)>>, // [[timestamp_ms, price], ...]
-                    }
-
-                    if let Ok(data) = response.json::<RangeResponse>().await {
-                        if let Some(prices) = data.prices {
-                            // Convert to daily prices (CoinGecko returns hourly for short ranges)
-                            // Group by day and take the last price for each day
-                            let mut daily_prices: HashMap<i64, f64> = HashMap::new();
-                            for (ts_ms, price) in prices {
-                                let ts = (ts_ms / 1000.0) as i64;
-                                // Round to start of day (UTC)
-                                let day_start = (ts / 86400) * 86400;
-                                daily_prices.insert(day_start, price);
-                            }
-
-                            let historical: Vec<HistoricalPrice> = daily_prices
-                                .into_iter()
-                                .map(|(ts, price)| HistoricalPrice {
-                                    timestamp: ts,
-                                    price,
-                                    currency: "USD".to_string(),
-                                })
-                                .collect();
-
-                            if !historical.is_empty() {
-                                log::info!(
-                                    "[COINGECKO HISTORICAL] {} - got {} daily prices",
-                                    ticker,
-                                    historical.len()
-                                );
-                                results.insert(ticker.clone(), historical);
-                            }
-                        }
-                    }
-                } else {
-                    // Log the actual error response body
-                    let status = response.status();
-                    let error_body = response.text().await.unwrap_or_default();
-                    log::warn!(
-                        "[COINGECKO HISTORICAL] {} - HTTP error: {} - Body: {}",
-                        ticker,
-                        status,
-                        error_body
-                    );
-                }
-            }
-            Err(e) => {
-                log::warn!("[COINGECKO HISTORICAL] {} - error: {}", ticker, e);
-            }
-        }
-    }
+    let requests: Vec<PriceRequest> = id_to_ticker
+        .iter()
+        .map(|(coingecko_id, ticker)| PriceRequest::crypto(ticker, Some(coingecko_id)))
+        .collect();
+    let chain = db.with_conn(ProviderChain::load)?;
+    let results = chain
+        .history(&requests, start_timestamp, end_timestamp)
+        .await;
 
     log::info!(
-        "[COINGECKO HISTORICAL] Fetched historical data for {} cryptos",
+        "[HISTORICAL] Fetched historical data for {} cryptos",
         results.len()
     );
 
@@ -902,6 +769,7 @@ pub struct ApiKeys {
     pub marketstack: Option<String>,
     pub finnhub: Option<String>,
     pub coingecko: Option<String>,
+    pub alpha_vantage: Option<String>,
 }
 
 /// Get API keys from app_config table
@@ -931,10 +799,19 @@ pub fn get_api_keys(db: &Database) -> Result<ApiKeys> {
             )
             .ok();
 
+        let alpha_vantage: Option<String> = conn
+            .query_row(
+                "SELECT value FROM app_config WHERE key = 'api_key_alpha_vantage'",
+                [],
+                |row| row.get(0),
+            )
+            .ok();
+
         Ok(ApiKeys {
             marketstack,
             finnhub,
             coingecko,
+            alpha_vantage,
         })
     })
 }
@@ -966,6 +843,139 @@ pub fn set_api_keys(db: &Database, keys: &ApiKeys) -> Result<()> {
             )?;
         }
 
+        if let Some(ref key) = keys.alpha_vantage {
+            conn.execute(
+                "INSERT INTO app_config (key, value) VALUES ('api_key_alpha_vantage', ?1)
+                 ON CONFLICT(key) DO UPDATE SET value = ?1",
+                [key],
+            )?;
+        }
+
         Ok(())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PriceAssetKind, PriceProviderSettings};
+    use crate::services::price_providers::mock::MockProvider;
+    use crate::services::price_providers::PriceProvider;
+    use rusqlite::Connection;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE stock_data (
+                id TEXT PRIMARY KEY,
+                ticker TEXT NOT NULL UNIQUE,
+                original_price TEXT NOT NULL,
+                currency TEXT NOT NULL DEFAULT 'USD',
+                price_date INTEGER,
+                fetched_at INTEGER NOT NULL
+            );
+            CREATE TABLE stock_ticker_currencies (
+                ticker TEXT PRIMARY KEY,
+                currency TEXT NOT NULL,
+                source TEXT NOT NULL DEFAULT 'provider',
+                updated_at INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE crypto_prices (
+                id TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL UNIQUE,
+                coingecko_id TEXT,
+                price TEXT NOT NULL,
+                currency TEXT NOT NULL DEFAULT 'USD',
+                fetched_at INTEGER NOT NULL
+            );",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn chain(providers: Vec<MockProvider>) -> ProviderChain {
+        let order: Vec<PriceProviderId> = providers.iter().map(|p| p.id()).collect();
+        ProviderChain::new(
+            providers
+                .into_iter()
+                .map(|p| Box::new(p) as Box<dyn PriceProvider>)
+                .collect(),
+            PriceProviderSettings {
+                stock_order: order.clone(),
+                crypto_order: order,
+                local_file_path: None,
+            },
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_stock_refresh_keeps_stale_price_only_when_all_providers_fail() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO stock_data (id, ticker, original_price, currency, fetched_at)
+             VALUES ('1', 'AAPL', '150.00', 'USD', 0), ('2', 'GONE', '10.00', 'USD', 0)",
+            [],
+        )
+        .unwrap();
+        assert!(is_stock_price_stale(&conn, "aapl", 5 * 3600));
+        assert!(!is_stock_price_stale(&conn, "AAPL", 3600));
+
+        let chain = chain(vec![
+            MockProvider::new(PriceProviderId::Yahoo).failing(),
+            MockProvider::new(PriceProviderId::Stooq).with_quote("AAPL", 190.5234, "USD"),
+        ]);
+        let requests = vec![PriceRequest::stock("AAPL"), PriceRequest::stock("GONE")];
+        let fetched = futures::executor::block_on(chain.latest_quotes(&requests));
+        let (updated, failed) = store_stock_quotes(&conn, fetched, 1000);
+
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].provider, PriceProviderId::Stooq);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "GONE");
+
+        let price = |ticker: &str| -> (String, i64) {
+            conn.query_row(
+                "SELECT original_price, fetched_at FROM stock_data WHERE ticker = ?1",
+                [ticker],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+        // Stored at full precision, not truncated to cents
+        assert_eq!(price("AAPL"), ("190.5234".to_string(), 1000));
+        assert_eq!(price("GONE"), ("10.00".to_string(), 0));
+        assert_eq!(
+            pricing::stored_ticker_currency(&conn, "AAPL").as_deref(),
+            Some("USD")
+        );
+    }
+
+    #[test]
+    fn test_crypto_refresh_stores_fallback_quotes() {
+        let conn = setup_test_db();
+        let chain = chain(vec![
+            MockProvider::new(PriceProviderId::CoinGecko).with_quote("BTC", 60000.0, "USD"),
+            MockProvider::new(PriceProviderId::Yahoo).with_quote("ETH", 3000.0, "USD"),
+        ]);
+        let requests = vec![
+            PriceRequest::crypto("btc", Some("bitcoin")),
+            PriceRequest::crypto("eth", Some("ethereum")),
+        ];
+        assert_eq!(requests[0].kind, PriceAssetKind::Crypto);
+
+        let fetched = futures::executor::block_on(chain.latest_quotes(&requests));
+        let updated = store_crypto_quotes(&conn, &requests, fetched, 1000).unwrap();
+
+        assert_eq!(updated.len(), 2);
+        assert_eq!(updated[1].provider, PriceProviderId::Yahoo);
+        let coingecko_id: String = conn
+            .query_row(
+                "SELECT coingecko_id FROM crypto_prices WHERE symbol = 'ETH'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(coingecko_id, "ethereum");
+    }
+}
@@ -0,0 +1,193 @@
+//! Alpha Vantage provider for stock quotes and daily history (API key required)
+//!
+//! The free tier allows 5 requests per minute, so this provider is meant as a
+//! fallback rather than the primary source.
+
+use super::{
+    date_to_timestamp, fallback_currency_in_pence, PriceProvider, PriceQuote, PriceRequest,
+};
+use crate::error::{AppError, Result};
+use crate::models::{PriceAssetKind, PriceProviderId};
+use crate::services::price_api::HistoricalPrice;
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+pub struct AlphaVantageProvider {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+impl AlphaVantageProvider {
+    pub fn new(api_key: String) -> Self {
+        AlphaVantageProvider {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+
+    async fn query(&self, function: &str, symbol: &str, extra: &str) -> Result<Value> {
+        let url = format!(
+            "https://www.alphavantage.co/query?function={}&symbol={}{}&apikey={}",
+            function,
+            urlencoding::encode(symbol),
+            extra,
+            urlencoding::encode(&self.api_key)
+        );
+        let response =
+            self.client.get(&url).send().await.map_err(|e| {
+                AppError::ExternalApi(format!("Alpha Vantage request failed: {}", e))
+            })?;
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Alpha Vantage parse error: {}", e)))?;
+
+        // Rate limits and bad keys come back as 200 with a message field
+        for key in ["Note", "Information", "Error Message"] {
+            if let Some(message) = body.get(key).and_then(|v| v.as_str()) {
+                return Err(AppError::ExternalApi(format!("Alpha Vantage: {}", message)));
+            }
+        }
+        Ok(body)
+    }
+}
+
+/// Map a Yahoo ticker to an Alpha Vantage symbol
+pub fn alpha_vantage_symbol(ticker: &str) -> Option<String> {
+    if ticker.starts_with('^') {
+        return None;
+    }
+    let Some((base, suffix)) = ticker.rsplit_once('.') else {
+        return Some(ticker.to_string());
+    };
+    let exchange = match suffix.to_uppercase().as_str() {
+        "L" => "LON",
+        "DE" | "F" => "DEX",
+        "PA" => "PAR",
+        "AS" => "AMS",
+        "TO" => "TRT",
+        "SW" => "SWX",
+        _ => return None,
+    };
+    Some(format!("{}.{}", base, exchange))
+}
+
+fn symbol(request: &PriceRequest) -> Result<String> {
+    if request.kind != PriceAssetKind::Stock {
+        return Err(AppError::ExternalApi(
+            "Alpha Vantage only prices stocks".into(),
+        ));
+    }
+    alpha_vantage_symbol(&request.ticker).ok_or_else(|| {
+        AppError::ExternalApi(format!("{} is not listed on Alpha Vantage", request.ticker))
+    })
+}
+
+/// Price from a GLOBAL_QUOTE response
+pub fn parse_global_quote(body: &Value) -> Option<f64> {
+    body.get("Global Quote")?
+        .get("05. price")?
+        .as_str()?
+        .parse::<f64>()
+        .ok()
+        .filter(|&p| p > 0.0)
+}
+
+/// Daily closes from a TIME_SERIES_DAILY response, oldest first
+pub fn parse_daily_series(body: &Value) -> Vec<(i64, f64)> {
+    let Some(series) = body.get("Time Series (Daily)").and_then(|v| v.as_object()) else {
+        return vec![];
+    };
+    let mut prices: Vec<(i64, f64)> = series
+        .iter()
+        .filter_map(|(date, bar)| {
+            let ts = date_to_timestamp(date)?;
+            let close = bar.get("4. close")?.as_str()?.parse::<f64>().ok()?;
+            (close > 0.0).then_some((ts, close))
+        })
+        .collect();
+    prices.sort_by_key(|&(ts, _)| ts);
+    prices
+}
+
+impl PriceProvider for AlphaVantageProvider {
+    fn id(&self) -> PriceProviderId {
+        PriceProviderId::AlphaVantage
+    }
+
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            let body = self.query("GLOBAL_QUOTE", &symbol(request)?, "").await?;
+            let price = parse_global_quote(&body)
+                .ok_or_else(|| AppError::ExternalApi("price unavailable".into()))?;
+            Ok(PriceQuote {
+                price,
+                currency: fallback_currency_in_pence(request),
+                currency_reported: false,
+            })
+        })
+    }
+
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>> {
+        Box::pin(async move {
+            // The compact series only covers ~100 trading days
+            let output = if chrono::Utc::now().timestamp() - start > 140 * 86400 {
+                "&outputsize=full"
+            } else {
+                ""
+            };
+            let body = self
+                .query("TIME_SERIES_DAILY", &symbol(request)?, output)
+                .await?;
+            let currency = fallback_currency_in_pence(request);
+            Ok(parse_daily_series(&body)
+                .into_iter()
+                .filter(|&(ts, _)| ts >= start && ts <= end)
+                .map(|(timestamp, price)| HistoricalPrice {
+                    timestamp,
+                    price,
+                    currency: currency.clone(),
+                })
+                .collect())
+        })
+    }
+
+    fn request_delay_ms(&self) -> u64 {
+        12_000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alpha_vantage_responses() {
+        assert_eq!(alpha_vantage_symbol("IBM").as_deref(), Some("IBM"));
+        assert_eq!(alpha_vantage_symbol("VUSA.L").as_deref(), Some("VUSA.LON"));
+        assert_eq!(alpha_vantage_symbol("CEZ.PR"), None);
+
+        let quote: Value = serde_json::from_str(
+            r#"{"Global Quote": {"01. symbol": "IBM", "05. price": "185.2300"}}"#,
+        )
+        .unwrap();
+        assert_eq!(parse_global_quote(&quote), Some(185.23));
+
+        let series: Value = serde_json::from_str(
+            r#"{"Time Series (Daily)": {
+                "2024-01-03": {"4. close": "161.1000"},
+                "2024-01-02": {"4. close": "158.6000"}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_daily_series(&series),
+            vec![(1704153600, 158.6), (1704240000, 161.1)]
+        );
+    }
+}
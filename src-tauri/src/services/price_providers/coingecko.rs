@@ -0,0 +1,167 @@
+//! CoinGecko provider for cryptocurrency prices (USD)
+
+use super::{PriceProvider, PriceQuote, PriceRequest};
+use crate::error::{AppError, Result};
+use crate::models::PriceProviderId;
+use crate::services::price_api::HistoricalPrice;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub struct CoinGeckoProvider {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl CoinGeckoProvider {
+    pub fn new(api_key: Option<String>) -> Self {
+        CoinGeckoProvider {
+            client: reqwest::Client::new(),
+            api_key,
+        }
+    }
+
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match self.api_key {
+            Some(ref key) => request.header("x-cg-demo-api-key", key),
+            None => request,
+        }
+    }
+}
+
+fn coingecko_id(request: &PriceRequest) -> Result<&str> {
+    request
+        .coingecko_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| AppError::ExternalApi(format!("{} has no CoinGecko ID", request.ticker)))
+}
+
+/// Keep the last price of each UTC day (CoinGecko returns hourly points for short ranges)
+fn daily_prices(points: Vec<(f64, f64)>) -> Vec<HistoricalPrice> {
+    let mut daily: HashMap<i64, f64> = HashMap::new();
+    for (ts_ms, price) in points {
+        let ts = (ts_ms / 1000.0) as i64;
+        daily.insert((ts / 86400) * 86400, price);
+    }
+    let mut prices: Vec<HistoricalPrice> = daily
+        .into_iter()
+        .map(|(timestamp, price)| HistoricalPrice {
+            timestamp,
+            price,
+            currency: "USD".to_string(),
+        })
+        .collect();
+    prices.sort_by_key(|p| p.timestamp);
+    prices
+}
+
+impl PriceProvider for CoinGeckoProvider {
+    fn id(&self) -> PriceProviderId {
+        PriceProviderId::CoinGecko
+    }
+
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            self.latest_batch(std::slice::from_ref(request))
+                .await
+                .pop()
+                .unwrap_or_else(|| Err(AppError::ExternalApi("no response".into())))
+        })
+    }
+
+    /// One simple/price call for all coins
+    fn latest_batch<'a>(
+        &'a self,
+        requests: &'a [PriceRequest],
+    ) -> BoxFuture<'a, Vec<Result<PriceQuote>>> {
+        Box::pin(async move {
+            let ids: Vec<&str> = requests
+                .iter()
+                .filter_map(|r| coingecko_id(r).ok())
+                .collect();
+            let prices = if ids.is_empty() {
+                Ok(HashMap::new())
+            } else {
+                let url = format!(
+                    "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd",
+                    ids.join(",")
+                );
+                log::info!("[COINGECKO] Fetching crypto prices for: {}", ids.join(","));
+                // CoinGecko returns { "bitcoin": { "usd": 12345.67 }, ... }
+                match self.get(&url).send().await {
+                    Ok(response) => response
+                        .json::<HashMap<String, HashMap<String, f64>>>()
+                        .await
+                        .map_err(|e| format!("CoinGecko parse error: {}", e)),
+                    Err(e) => Err(format!("CoinGecko request failed: {}", e)),
+                }
+            };
+
+            requests
+                .iter()
+                .map(|request| {
+                    let id = coingecko_id(request)?;
+                    let prices = prices
+                        .as_ref()
+                        .map_err(|e| AppError::ExternalApi(e.clone()))?;
+                    prices
+                        .get(id)
+                        .and_then(|p| p.get("usd"))
+                        .map(|&price| PriceQuote {
+                            price,
+                            currency: "USD".to_string(),
+                            currency_reported: true,
+                        })
+                        .ok_or_else(|| AppError::ExternalApi(format!("no price for {}", id)))
+                })
+                .collect()
+        })
+    }
+
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>> {
+        Box::pin(async move {
+            let id = coingecko_id(request)?;
+            let url = format!(
+                "https://api.coingecko.com/api/v3/coins/{}/market_chart/range?vs_currency=usd&from={}&to={}",
+                id, start, end
+            );
+            let response = self.get(&url).send().await.map_err(|e| {
+                AppError::ExternalApi(format!("CoinGecko historical request failed: {}", e))
+            })?;
+
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(AppError::ExternalApi("CoinGecko rate limit hit".into()));
+            }
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(AppError::ExternalApi(format!(
+                    "CoinGecko HTTP error: {} - {}",
+                    status, body
+                )));
+            }
+
+            #[derive(Deserialize)]
+            struct RangeResponse {
+                prices: Option<Vec<(f64, f64)>>, // [[timestamp_ms, price], ...]
+            }
+
+            let data: RangeResponse = response.json().await.map_err(|e| {
+                AppError::ExternalApi(format!("CoinGecko historical parse error: {}", e))
+            })?;
+            Ok(daily_prices(data.prices.unwrap_or_default()))
+        })
+    }
+
+    /// Stay within the free tier (~40 requests/min)
+    fn request_delay_ms(&self) -> u64 {
+        1500
+    }
+}
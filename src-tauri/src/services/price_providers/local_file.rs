@@ -0,0 +1,214 @@
+//! Local price file provider for instruments no online source covers
+//!
+//! CSV with a header row (`ticker,price[,currency][,date]`) or a JSON array of
+//! `{"ticker", "price", "currency"?, "date"?}` objects. Dates are "YYYY-MM-DD";
+//! rows without a date count as the current price. Minor units ("GBp") are
+//! accepted. Without a currency column the stored ticker currency is used.
+
+use super::{date_to_timestamp, PriceProvider, PriceQuote, PriceRequest};
+use crate::error::{AppError, Result};
+use crate::models::PriceProviderId;
+use crate::services::price_api::HistoricalPrice;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct PriceRow {
+    ticker: String,
+    price: f64,
+    #[serde(default)]
+    currency: Option<String>,
+    #[serde(default)]
+    date: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct LocalPrice {
+    /// None = current price
+    date: Option<i64>,
+    price: f64,
+    currency: Option<String>,
+}
+
+pub struct LocalFileProvider {
+    prices: HashMap<String, Vec<LocalPrice>>,
+}
+
+impl LocalFileProvider {
+    /// Read and parse the price file; the format follows the extension
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AppError::Internal(format!("Cannot read {}: {}", path.display(), e)))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::from_json(&content)
+        } else {
+            Self::from_csv(&content)
+        }
+    }
+
+    pub fn from_csv(content: &str) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let mut rows = Vec::new();
+        for record in reader.deserialize::<PriceRow>() {
+            rows.push(
+                record.map_err(|e| AppError::Validation(format!("Invalid price file: {}", e)))?,
+            );
+        }
+        Self::from_rows(rows)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self> {
+        let rows: Vec<PriceRow> = serde_json::from_str(content)
+            .map_err(|e| AppError::Validation(format!("Invalid price file: {}", e)))?;
+        Self::from_rows(rows)
+    }
+
+    fn from_rows(rows: Vec<PriceRow>) -> Result<Self> {
+        let mut prices: HashMap<String, Vec<LocalPrice>> = HashMap::new();
+        for row in rows {
+            let date = match row.date.as_deref().filter(|d| !d.trim().is_empty()) {
+                Some(d) => Some(date_to_timestamp(d).ok_or_else(|| {
+                    AppError::Validation(format!("Invalid date in price file: {}", d))
+                })?),
+                None => None,
+            };
+            if row.price <= 0.0 {
+                continue;
+            }
+            prices
+                .entry(row.ticker.trim().to_uppercase())
+                .or_default()
+                .push(LocalPrice {
+                    date,
+                    price: row.price,
+                    currency: row
+                        .currency
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty()),
+                });
+        }
+        Ok(LocalFileProvider { prices })
+    }
+
+    fn currency(request: &PriceRequest, price: &LocalPrice) -> String {
+        price
+            .currency
+            .clone()
+            .unwrap_or_else(|| request.fallback_currency())
+    }
+}
+
+impl PriceProvider for LocalFileProvider {
+    fn id(&self) -> PriceProviderId {
+        PriceProviderId::LocalFile
+    }
+
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            // Undated rows sort last, i.e. win over any dated row
+            let latest = self
+                .prices
+                .get(&request.ticker)
+                .and_then(|prices| {
+                    prices
+                        .iter()
+                        .max_by_key(|p| p.date.map_or((1, 0), |d| (0, d)))
+                })
+                .ok_or_else(|| {
+                    AppError::ExternalApi(format!("{} not in price file", request.ticker))
+                })?;
+            Ok(PriceQuote {
+                price: latest.price,
+                currency: Self::currency(request, latest),
+                currency_reported: latest.currency.is_some(),
+            })
+        })
+    }
+
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>> {
+        Box::pin(async move {
+            let mut history: Vec<HistoricalPrice> = self
+                .prices
+                .get(&request.ticker)
+                .map(|prices| {
+                    prices
+                        .iter()
+                        .filter_map(|p| {
+                            let date = p.date?;
+                            (date >= start && date <= end).then(|| HistoricalPrice {
+                                timestamp: date,
+                                price: p.price,
+                                currency: Self::currency(request, p),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            history.sort_by_key(|p| p.timestamp);
+            Ok(history)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_local_csv_prices() {
+        let provider = LocalFileProvider::from_csv(
+            "ticker,price,currency,date\n\
+             cez.pr,1010,CZK,2024-01-02\n\
+             CEZ.PR,1020,CZK,2024-01-03\n\
+             FUND1,150.5,EUR,\n",
+        )
+        .unwrap();
+
+        let quote = block_on(provider.latest(&PriceRequest::stock("CEZ.PR"))).unwrap();
+        assert_eq!(quote.price, 1020.0);
+        assert_eq!(quote.currency, "CZK");
+
+        let fund = block_on(provider.latest(&PriceRequest::stock("FUND1"))).unwrap();
+        assert_eq!(fund.price, 150.5);
+        assert_eq!(fund.currency, "EUR");
+
+        let history =
+            block_on(provider.history(&PriceRequest::stock("CEZ.PR"), 1704153600, 1704153600))
+                .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].price, 1010.0);
+
+        assert!(block_on(provider.latest(&PriceRequest::stock("AAPL"))).is_err());
+    }
+
+    #[test]
+    fn test_local_json_prices() {
+        let provider = LocalFileProvider::from_json(
+            r#"[{"ticker": "PRIVATE", "price": 42.0}, {"ticker": "BTC", "price": 60000}]"#,
+        )
+        .unwrap();
+
+        let stock = block_on(provider.latest(&PriceRequest::stock("PRIVATE"))).unwrap();
+        assert_eq!(stock.currency, "USD");
+        let crypto = block_on(provider.latest(&PriceRequest::crypto("btc", None))).unwrap();
+        assert_eq!(crypto.price, 60000.0);
+
+        assert!(LocalFileProvider::from_json(
+            r#"[{"ticker": "X", "price": 1, "date": "01/02/2024"}]"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! In-memory provider for testing refresh and backfill logic without network
+
+use super::{PriceProvider, PriceQuote, PriceRequest};
+use crate::error::{AppError, Result};
+use crate::models::PriceProviderId;
+use crate::services::price_api::HistoricalPrice;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Serves fixed quotes and history, records every requested ticker
+pub struct MockProvider {
+    id: PriceProviderId,
+    quotes: HashMap<String, PriceQuote>,
+    history: HashMap<String, Vec<HistoricalPrice>>,
+    failing: bool,
+    pub calls: Mutex<Vec<String>>,
+}
+
+impl MockProvider {
+    pub fn new(id: PriceProviderId) -> Self {
+        MockProvider {
+            id,
+            quotes: HashMap::new(),
+            history: HashMap::new(),
+            failing: false,
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_quote(mut self, ticker: &str, price: f64, currency: &str) -> Self {
+        self.quotes.insert(
+            ticker.to_uppercase(),
+            PriceQuote {
+                price,
+                currency: currency.to_string(),
+                currency_reported: true,
+            },
+        );
+        self
+    }
+
+    pub fn with_history(mut self, ticker: &str, prices: &[(i64, f64)], currency: &str) -> Self {
+        self.history.insert(
+            ticker.to_uppercase(),
+            prices
+                .iter()
+                .map(|&(timestamp, price)| HistoricalPrice {
+                    timestamp,
+                    price,
+                    currency: currency.to_string(),
+                })
+                .collect(),
+        );
+        self
+    }
+
+    /// Every request fails, as if the provider were offline
+    pub fn failing(mut self) -> Self {
+        self.failing = true;
+        self
+    }
+
+    fn record(&self, request: &PriceRequest) -> Result<()> {
+        self.calls
+            .lock()
+            .expect("mock calls mutex poisoned")
+            .push(request.ticker.clone());
+        if self.failing {
+            return Err(AppError::ExternalApi("mock provider offline".into()));
+        }
+        Ok(())
+    }
+}
+
+impl PriceProvider for MockProvider {
+    fn id(&self) -> PriceProviderId {
+        self.id
+    }
+
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            self.record(request)?;
+            self.quotes
+                .get(&request.ticker)
+                .cloned()
+                .ok_or_else(|| AppError::ExternalApi(format!("no quote for {}", request.ticker)))
+        })
+    }
+
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>> {
+        Box::pin(async move {
+            self.record(request)?;
+            Ok(self
+                .history
+                .get(&request.ticker)
+                .map(|prices| {
+                    prices
+                        .iter()
+                        .filter(|p| p.timestamp >= start && p.timestamp <= end)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+}
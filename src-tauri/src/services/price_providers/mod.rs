@@ -0,0 +1,723 @@
+//! Pluggable market price providers
+//!
+//! Every price source implements [`PriceProvider`]. A [`ProviderChain`] tries the
+//! per-ticker preferred provider first and then the global fallback order for the
+//! asset kind, so one failing source no longer leaves prices stale:
+//! 1. Yahoo Finance - stocks, ETFs, indices and crypto (as "BTC-USD")
+//! 2. CoinGecko - crypto by CoinGecko ID
+//! 3. Stooq - stocks on US, UK, German, Japanese and Hong Kong exchanges
+//! 4. Alpha Vantage - stocks, needs an API key
+//! 5. Local file - user-maintained CSV/JSON price file
+
+pub mod alpha_vantage;
+pub mod coingecko;
+pub mod local_file;
+#[cfg(test)]
+pub mod mock;
+pub mod stooq;
+pub mod yahoo;
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    InsertPriceProviderPreference, PriceAssetKind, PriceProviderId, PriceProviderPreference,
+    PriceProviderSettings,
+};
+use crate::services::currency::normalize_minor_unit;
+use crate::services::price_api::{get_currency_from_ticker, HistoricalPrice};
+use futures::future::BoxFuture;
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
+
+/// Fallback order used until the user changes it
+pub const DEFAULT_STOCK_ORDER: [PriceProviderId; 4] = [
+    PriceProviderId::Yahoo,
+    PriceProviderId::Stooq,
+    PriceProviderId::AlphaVantage,
+    PriceProviderId::LocalFile,
+];
+pub const DEFAULT_CRYPTO_ORDER: [PriceProviderId; 3] = [
+    PriceProviderId::CoinGecko,
+    PriceProviderId::Yahoo,
+    PriceProviderId::LocalFile,
+];
+
+const STOCK_ORDER_KEY: &str = "price_provider_order_stock";
+const CRYPTO_ORDER_KEY: &str = "price_provider_order_crypto";
+const LOCAL_FILE_KEY: &str = "price_file_path";
+
+/// One instrument to price
+#[derive(Debug, Clone)]
+pub struct PriceRequest {
+    /// Ticker as stored in the portfolio (Yahoo notation for stocks)
+    pub ticker: String,
+    pub kind: PriceAssetKind,
+    /// Required by CoinGecko for crypto
+    pub coingecko_id: Option<String>,
+    /// Stored quote currency of the ticker, filled in by the chain
+    pub currency: Option<String>,
+}
+
+impl PriceRequest {
+    pub fn stock(ticker: &str) -> Self {
+        PriceRequest {
+            ticker: ticker.to_uppercase(),
+            kind: PriceAssetKind::Stock,
+            coingecko_id: None,
+            currency: None,
+        }
+    }
+
+    pub fn crypto(ticker: &str, coingecko_id: Option<&str>) -> Self {
+        PriceRequest {
+            ticker: ticker.to_uppercase(),
+            kind: PriceAssetKind::Crypto,
+            coingecko_id: coingecko_id.map(|s| s.to_string()),
+            currency: None,
+        }
+    }
+
+    /// Currency for providers that report none: the stored ticker currency,
+    /// else the ticker-suffix guess for stocks and USD for crypto
+    pub fn fallback_currency(&self) -> String {
+        match (&self.currency, self.kind) {
+            (Some(currency), _) => currency.clone(),
+            (None, PriceAssetKind::Stock) => get_currency_from_ticker(&self.ticker).to_string(),
+            (None, PriceAssetKind::Crypto) => "USD".to_string(),
+        }
+    }
+}
+
+/// Latest price reported by a provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceQuote {
+    pub price: f64,
+    /// May be a minor unit ("GBp"); the chain normalizes it
+    pub currency: String,
+    /// Whether the currency came from provider metadata rather than a fallback
+    pub currency_reported: bool,
+}
+
+impl PriceQuote {
+    fn normalized(self) -> Self {
+        let (price, currency) = normalize_minor_unit(self.price, &self.currency);
+        PriceQuote {
+            price,
+            currency,
+            currency_reported: self.currency_reported,
+        }
+    }
+}
+
+fn normalize_history(prices: Vec<HistoricalPrice>) -> Vec<HistoricalPrice> {
+    prices
+        .into_iter()
+        .map(|p| {
+            let (price, currency) = normalize_minor_unit(p.price, &p.currency);
+            HistoricalPrice {
+                timestamp: p.timestamp,
+                price,
+                currency,
+            }
+        })
+        .collect()
+}
+
+/// A source of latest and historical prices
+pub trait PriceProvider: Send + Sync {
+    fn id(&self) -> PriceProviderId;
+
+    /// Latest price for one instrument. Errors (including "not listed") make the
+    /// chain move on to the next provider.
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>>;
+
+    /// Daily prices between `start` and `end` (Unix timestamps)
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>>;
+
+    /// Latest prices for several instruments, one result per request in order.
+    /// Providers with a batch endpoint override this.
+    fn latest_batch<'a>(
+        &'a self,
+        requests: &'a [PriceRequest],
+    ) -> BoxFuture<'a, Vec<Result<PriceQuote>>> {
+        Box::pin(async move {
+            let mut results = Vec::with_capacity(requests.len());
+            for (i, request) in requests.iter().enumerate() {
+                if i > 0 {
+                    throttle(self.request_delay_ms()).await;
+                }
+                results.push(self.latest(request).await);
+            }
+            results
+        })
+    }
+
+    /// Pause between consecutive requests to stay within rate limits
+    fn request_delay_ms(&self) -> u64 {
+        0
+    }
+}
+
+async fn throttle(delay_ms: u64) {
+    if delay_ms > 0 {
+        tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+    }
+}
+
+/// Whether a provider can price the asset kind at all
+pub fn supports(provider: PriceProviderId, kind: PriceAssetKind) -> bool {
+    match provider {
+        PriceProviderId::Yahoo | PriceProviderId::LocalFile => true,
+        PriceProviderId::CoinGecko => kind == PriceAssetKind::Crypto,
+        PriceProviderId::Stooq | PriceProviderId::AlphaVantage => kind == PriceAssetKind::Stock,
+    }
+}
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+fn config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM app_config WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+fn parse_order(value: Option<String>, default: &[PriceProviderId]) -> Vec<PriceProviderId> {
+    let order: Vec<PriceProviderId> = value
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|s| PriceProviderId::parse(s.trim()))
+        .collect();
+    if order.is_empty() {
+        default.to_vec()
+    } else {
+        order
+    }
+}
+
+fn format_order(order: &[PriceProviderId]) -> String {
+    order
+        .iter()
+        .map(|p| p.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Get the fallback order and local file path
+pub fn get_settings(conn: &Connection) -> Result<PriceProviderSettings> {
+    Ok(PriceProviderSettings {
+        stock_order: parse_order(config_value(conn, STOCK_ORDER_KEY), &DEFAULT_STOCK_ORDER),
+        crypto_order: parse_order(config_value(conn, CRYPTO_ORDER_KEY), &DEFAULT_CRYPTO_ORDER),
+        local_file_path: config_value(conn, LOCAL_FILE_KEY).filter(|p| !p.is_empty()),
+    })
+}
+
+/// Save the fallback order and local file path
+pub fn set_settings(conn: &Connection, settings: &PriceProviderSettings) -> Result<()> {
+    let path = settings
+        .local_file_path
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string();
+    for (key, value) in [
+        (STOCK_ORDER_KEY, format_order(&settings.stock_order)),
+        (CRYPTO_ORDER_KEY, format_order(&settings.crypto_order)),
+        (LOCAL_FILE_KEY, path),
+    ] {
+        conn.execute(
+            "INSERT INTO app_config (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            rusqlite::params![key, value],
+        )?;
+    }
+    Ok(())
+}
+
+/// Get all per-ticker provider preferences
+pub fn get_preferences(conn: &Connection) -> Result<Vec<PriceProviderPreference>> {
+    let mut stmt = conn.prepare(
+        "SELECT ticker, asset_kind, provider, updated_at FROM price_provider_preferences
+         ORDER BY asset_kind, ticker",
+    )?;
+    let preferences = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .filter_map(|(ticker, kind, provider, updated_at)| {
+            Some(PriceProviderPreference {
+                ticker,
+                asset_kind: PriceAssetKind::parse(&kind)?,
+                provider: PriceProviderId::parse(&provider)?,
+                updated_at,
+            })
+        })
+        .collect();
+    Ok(preferences)
+}
+
+/// Set the preferred provider for one ticker (upsert)
+pub fn set_preference(
+    conn: &Connection,
+    data: &InsertPriceProviderPreference,
+) -> Result<PriceProviderPreference> {
+    let ticker = data.ticker.trim().to_uppercase();
+    let now = chrono::Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO price_provider_preferences (ticker, asset_kind, provider, updated_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(ticker, asset_kind) DO UPDATE SET
+           provider = excluded.provider, updated_at = excluded.updated_at",
+        rusqlite::params![
+            ticker,
+            data.asset_kind.as_str(),
+            data.provider.as_str(),
+            now
+        ],
+    )?;
+    Ok(PriceProviderPreference {
+        ticker,
+        asset_kind: data.asset_kind,
+        provider: data.provider,
+        updated_at: now,
+    })
+}
+
+/// Remove the preference so the ticker follows the fallback order again
+pub fn delete_preference(conn: &Connection, ticker: &str, kind: PriceAssetKind) -> Result<()> {
+    let deleted = conn.execute(
+        "DELETE FROM price_provider_preferences WHERE ticker = ?1 AND asset_kind = ?2",
+        rusqlite::params![ticker.trim().to_uppercase(), kind.as_str()],
+    )?;
+    if deleted == 0 {
+        return Err(AppError::NotFound(format!(
+            "Price provider preference for {} not found",
+            ticker
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Provider Chain
+// ============================================================================
+
+/// Result of fetching latest prices through the chain
+#[derive(Debug, Default)]
+pub struct ChainQuotes {
+    /// Ticker -> (provider that answered, quote)
+    pub quotes: HashMap<String, (PriceProviderId, PriceQuote)>,
+    /// Tickers no provider could price, with the last error
+    pub failed: Vec<(String, String)>,
+}
+
+/// Providers plus the order in which they are tried
+pub struct ProviderChain {
+    providers: Vec<Box<dyn PriceProvider>>,
+    settings: PriceProviderSettings,
+    preferences: HashMap<(String, PriceAssetKind), PriceProviderId>,
+    /// Stored quote currency per stock ticker
+    ticker_currencies: HashMap<String, String>,
+}
+
+impl ProviderChain {
+    pub fn new(
+        providers: Vec<Box<dyn PriceProvider>>,
+        settings: PriceProviderSettings,
+        preferences: Vec<PriceProviderPreference>,
+    ) -> Self {
+        let preferences = preferences
+            .into_iter()
+            .map(|p| ((p.ticker.to_uppercase(), p.asset_kind), p.provider))
+            .collect();
+        ProviderChain {
+            providers,
+            settings,
+            preferences,
+            ticker_currencies: HashMap::new(),
+        }
+    }
+
+    /// Use stored quote currencies for providers that report none
+    pub fn with_ticker_currencies(mut self, currencies: HashMap<String, String>) -> Self {
+        self.ticker_currencies = currencies;
+        self
+    }
+
+    /// Build the chain from the stored settings, preferences and API keys.
+    /// Providers that cannot be set up (no API key, unreadable price file,
+    /// Yahoo connector failure) are left out with a warning.
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let settings = get_settings(conn)?;
+        let preferences = get_preferences(conn)?;
+
+        let mut providers: Vec<Box<dyn PriceProvider>> = Vec::new();
+        match yahoo::YahooProvider::new() {
+            Ok(provider) => providers.push(Box::new(provider)),
+            Err(e) => log::warn!("[PRICE PROVIDERS] Yahoo unavailable: {}", e),
+        }
+        providers.push(Box::new(coingecko::CoinGeckoProvider::new(
+            config_value(conn, "api_key_coingecko").filter(|k| !k.is_empty()),
+        )));
+        providers.push(Box::new(stooq::StooqProvider::new()));
+        if let Some(key) = config_value(conn, "api_key_alpha_vantage").filter(|k| !k.is_empty()) {
+            providers.push(Box::new(alpha_vantage::AlphaVantageProvider::new(key)));
+        }
+        if let Some(ref path) = settings.local_file_path {
+            match local_file::LocalFileProvider::load(std::path::Path::new(path)) {
+                Ok(provider) => providers.push(Box::new(provider)),
+                Err(e) => log::warn!("[PRICE PROVIDERS] Price file unavailable: {}", e),
+            }
+        }
+
+        let ticker_currencies = crate::services::pricing::get_ticker_currencies(conn)?
+            .into_iter()
+            .map(|c| (c.ticker, c.currency))
+            .collect();
+
+        Ok(Self::new(providers, settings, preferences).with_ticker_currencies(ticker_currencies))
+    }
+
+    fn prepare(&self, request: &PriceRequest) -> PriceRequest {
+        let mut request = request.clone();
+        if request.currency.is_none() && request.kind == PriceAssetKind::Stock {
+            request.currency = self.ticker_currencies.get(&request.ticker).cloned();
+        }
+        request
+    }
+
+    fn provider(&self, id: PriceProviderId) -> Option<&dyn PriceProvider> {
+        self.providers
+            .iter()
+            .find(|p| p.id() == id)
+            .map(|p| p.as_ref())
+    }
+
+    /// Providers to try for a request: preference first, then the fallback order
+    pub fn order_for(&self, request: &PriceRequest) -> Vec<PriceProviderId> {
+        let fallback = match request.kind {
+            PriceAssetKind::Stock => &self.settings.stock_order,
+            PriceAssetKind::Crypto => &self.settings.crypto_order,
+        };
+        let preferred = self
+            .preferences
+            .get(&(request.ticker.to_uppercase(), request.kind))
+            .copied();
+
+        let mut order = Vec::new();
+        for id in preferred.into_iter().chain(fallback.iter().copied()) {
+            if !order.contains(&id) && supports(id, request.kind) && self.provider(id).is_some() {
+                order.push(id);
+            }
+        }
+        order
+    }
+
+    /// Fetch latest prices. Requests are grouped by the provider they try next,
+    /// so batch endpoints are used, and whatever fails moves down its own order.
+    pub async fn latest_quotes(&self, requests: &[PriceRequest]) -> ChainQuotes {
+        let mut result = ChainQuotes::default();
+        let mut pending: Vec<(PriceRequest, Vec<PriceProviderId>, String)> = requests
+            .iter()
+            .map(|r| {
+                (
+                    self.prepare(r),
+                    self.order_for(r),
+                    "no provider available".to_string(),
+                )
+            })
+            .collect();
+        let mut round = 0;
+
+        while !pending.is_empty() {
+            let mut groups: Vec<(PriceProviderId, Vec<usize>)> = Vec::new();
+            let mut exhausted = Vec::new();
+            for (i, (_, order, _)) in pending.iter().enumerate() {
+                match order.get(round) {
+                    Some(&id) => match groups.iter_mut().find(|(g, _)| *g == id) {
+                        Some((_, members)) => members.push(i),
+                        None => groups.push((id, vec![i])),
+                    },
+                    None => exhausted.push(i),
+                }
+            }
+
+            let mut answered = vec![false; pending.len()];
+            for (id, members) in groups {
+                let Some(provider) = self.provider(id) else {
+                    continue;
+                };
+                let batch: Vec<PriceRequest> =
+                    members.iter().map(|&i| pending[i].0.clone()).collect();
+                let outcomes = provider.latest_batch(&batch).await;
+                for (&i, outcome) in members.iter().zip(outcomes) {
+                    match outcome {
+                        Ok(quote) if quote.price > 0.0 => {
+                            result
+                                .quotes
+                                .insert(pending[i].0.ticker.clone(), (id, quote.normalized()));
+                            answered[i] = true;
+                        }
+                        Ok(_) => pending[i].2 = format!("{}: price unavailable", id.as_str()),
+                        Err(e) => pending[i].2 = format!("{}: {}", id.as_str(), e),
+                    }
+                }
+            }
+
+            for &i in &exhausted {
+                result
+                    .failed
+                    .push((pending[i].0.ticker.clone(), pending[i].2.clone()));
+                answered[i] = true;
+            }
+            let mut index = 0;
+            pending.retain(|_| {
+                let keep = !answered[index];
+                index += 1;
+                keep
+            });
+            round += 1;
+        }
+
+        result
+    }
+
+    /// Fetch daily history for each request from the first provider that has data
+    /// for the range. Tickers without any data are left out of the map.
+    pub async fn history(
+        &self,
+        requests: &[PriceRequest],
+        start: i64,
+        end: i64,
+    ) -> HashMap<String, Vec<HistoricalPrice>> {
+        let mut results = HashMap::new();
+        let mut called: HashSet<PriceProviderId> = HashSet::new();
+
+        for request in requests {
+            let request = &self.prepare(request);
+            for id in self.order_for(request) {
+                let Some(provider) = self.provider(id) else {
+                    continue;
+                };
+                if !called.insert(id) {
+                    throttle(provider.request_delay_ms()).await;
+                }
+                match provider.history(request, start, end).await {
+                    Ok(prices) if !prices.is_empty() => {
+                        log::info!(
+                            "[PRICE PROVIDERS] {} - {} historical prices from {}",
+                            request.ticker,
+                            prices.len(),
+                            id.as_str()
+                        );
+                        results.insert(request.ticker.clone(), normalize_history(prices));
+                        break;
+                    }
+                    Ok(_) => {
+                        log::warn!(
+                            "[PRICE PROVIDERS] {} - no history from {}",
+                            request.ticker,
+                            id.as_str()
+                        );
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "[PRICE PROVIDERS] {} - {} failed: {}",
+                            request.ticker,
+                            id.as_str(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        results
+    }
+}
+
+/// Fallback currency for sources that quote London listings in pence without saying so
+pub(crate) fn fallback_currency_in_pence(request: &PriceRequest) -> String {
+    let currency = request.fallback_currency();
+    let london = request
+        .ticker
+        .rsplit_once('.')
+        .is_some_and(|(_, suffix)| suffix.eq_ignore_ascii_case("L"));
+    if london && currency == "GBP" {
+        "GBp".to_string()
+    } else {
+        currency
+    }
+}
+
+/// Midnight UTC of a "YYYY-MM-DD" date
+pub(crate) fn date_to_timestamp(date: &str) -> Option<i64> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockProvider;
+    use super::*;
+
+    fn settings(stock: &[PriceProviderId], crypto: &[PriceProviderId]) -> PriceProviderSettings {
+        PriceProviderSettings {
+            stock_order: stock.to_vec(),
+            crypto_order: crypto.to_vec(),
+            local_file_path: None,
+        }
+    }
+
+    fn preference(ticker: &str, provider: PriceProviderId) -> PriceProviderPreference {
+        PriceProviderPreference {
+            ticker: ticker.to_string(),
+            asset_kind: PriceAssetKind::Stock,
+            provider,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_latest_falls_back_to_next_provider() {
+        let yahoo = MockProvider::new(PriceProviderId::Yahoo).with_quote("AAPL", 190.0, "USD");
+        let stooq = MockProvider::new(PriceProviderId::Stooq)
+            .with_quote("AAPL", 189.0, "USD")
+            .with_quote("VWCE.DE", 120.0, "EUR");
+        let chain = ProviderChain::new(
+            vec![Box::new(yahoo), Box::new(stooq)],
+            settings(
+                &[PriceProviderId::Yahoo, PriceProviderId::Stooq],
+                &[PriceProviderId::CoinGecko],
+            ),
+            vec![],
+        );
+
+        let requests = vec![
+            PriceRequest::stock("AAPL"),
+            PriceRequest::stock("VWCE.DE"),
+            PriceRequest::stock("MISSING"),
+        ];
+        let result = futures::executor::block_on(chain.latest_quotes(&requests));
+
+        assert_eq!(result.quotes["AAPL"].0, PriceProviderId::Yahoo);
+        assert_eq!(result.quotes["AAPL"].1.price, 190.0);
+        assert_eq!(result.quotes["VWCE.DE"].0, PriceProviderId::Stooq);
+        assert_eq!(result.quotes["VWCE.DE"].1.currency, "EUR");
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, "MISSING");
+        assert!(result.failed[0].1.starts_with("stooq"));
+    }
+
+    #[test]
+    fn test_preference_is_tried_first() {
+        let yahoo = MockProvider::new(PriceProviderId::Yahoo).with_quote("CEZ.PR", 1000.0, "CZK");
+        let local =
+            MockProvider::new(PriceProviderId::LocalFile).with_quote("CEZ.PR", 990.0, "CZK");
+        let chain = ProviderChain::new(
+            vec![Box::new(yahoo), Box::new(local)],
+            settings(
+                &[PriceProviderId::Yahoo, PriceProviderId::LocalFile],
+                &[PriceProviderId::CoinGecko],
+            ),
+            vec![preference("cez.pr", PriceProviderId::LocalFile)],
+        );
+
+        let request = PriceRequest::stock("CEZ.PR");
+        assert_eq!(
+            chain.order_for(&request),
+            vec![PriceProviderId::LocalFile, PriceProviderId::Yahoo]
+        );
+        let result = futures::executor::block_on(chain.latest_quotes(&[request]));
+        assert_eq!(result.quotes["CEZ.PR"].0, PriceProviderId::LocalFile);
+        assert_eq!(result.quotes["CEZ.PR"].1.price, 990.0);
+    }
+
+    #[test]
+    fn test_order_skips_unsupported_and_missing_providers() {
+        let chain = ProviderChain::new(
+            vec![Box::new(MockProvider::new(PriceProviderId::Yahoo))],
+            settings(
+                &[PriceProviderId::AlphaVantage, PriceProviderId::Yahoo],
+                &[PriceProviderId::CoinGecko, PriceProviderId::Yahoo],
+            ),
+            vec![preference("AAPL", PriceProviderId::CoinGecko)],
+        );
+
+        assert_eq!(
+            chain.order_for(&PriceRequest::stock("AAPL")),
+            vec![PriceProviderId::Yahoo]
+        );
+        assert_eq!(
+            chain.order_for(&PriceRequest::crypto("BTC", Some("bitcoin"))),
+            vec![PriceProviderId::Yahoo]
+        );
+    }
+
+    #[test]
+    fn test_minor_units_are_normalized_and_stored_currency_used() {
+        let yahoo = MockProvider::new(PriceProviderId::Yahoo).with_quote("VOD.L", 7250.0, "GBp");
+        let chain = ProviderChain::new(
+            vec![Box::new(yahoo)],
+            settings(&[PriceProviderId::Yahoo], &[PriceProviderId::CoinGecko]),
+            vec![],
+        )
+        .with_ticker_currencies(HashMap::from([("CSPX.L".to_string(), "USD".to_string())]));
+
+        let result =
+            futures::executor::block_on(chain.latest_quotes(&[PriceRequest::stock("VOD.L")]));
+        assert_eq!(result.quotes["VOD.L"].1.price, 72.5);
+        assert_eq!(result.quotes["VOD.L"].1.currency, "GBP");
+
+        // Sources without currency metadata fall back to the stored currency
+        let request = chain.prepare(&PriceRequest::stock("cspx.l"));
+        assert_eq!(request.fallback_currency(), "USD");
+        assert_eq!(fallback_currency_in_pence(&request), "USD");
+        assert_eq!(
+            fallback_currency_in_pence(&PriceRequest::stock("VOD.L")),
+            "GBp"
+        );
+    }
+
+    #[test]
+    fn test_history_uses_first_provider_with_data() {
+        let yahoo = MockProvider::new(PriceProviderId::Yahoo).failing();
+        let stooq = MockProvider::new(PriceProviderId::Stooq).with_history(
+            "AAPL",
+            &[(86400, 100.0), (2 * 86400, 101.0), (9 * 86400, 110.0)],
+            "USD",
+        );
+        let chain = ProviderChain::new(
+            vec![Box::new(yahoo), Box::new(stooq)],
+            settings(
+                &[PriceProviderId::Yahoo, PriceProviderId::Stooq],
+                &[PriceProviderId::CoinGecko],
+            ),
+            vec![],
+        );
+
+        let history = futures::executor::block_on(chain.history(
+            &[PriceRequest::stock("AAPL"), PriceRequest::stock("MSFT")],
+            0,
+            5 * 86400,
+        ));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history["AAPL"].len(), 2);
+    }
+}
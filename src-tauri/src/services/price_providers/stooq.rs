@@ -0,0 +1,210 @@
+//! Stooq provider: free CSV quotes and daily history, no API key
+//!
+//! Stooq uses lowercase symbols with a market suffix ("aapl.us", "vwce.de"),
+//! so Yahoo tickers are mapped first. Exchanges without a mapping are skipped.
+
+use super::{
+    date_to_timestamp, fallback_currency_in_pence, PriceProvider, PriceQuote, PriceRequest,
+};
+use crate::error::{AppError, Result};
+use crate::models::{PriceAssetKind, PriceProviderId};
+use crate::services::price_api::HistoricalPrice;
+use futures::future::BoxFuture;
+
+pub struct StooqProvider {
+    client: reqwest::Client,
+}
+
+impl StooqProvider {
+    pub fn new() -> Self {
+        StooqProvider {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn fetch_csv(&self, url: &str) -> Result<String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Stooq request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalApi(format!(
+                "Stooq HTTP error: {}",
+                response.status()
+            )));
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| AppError::ExternalApi(format!("Stooq read error: {}", e)))
+    }
+}
+
+impl Default for StooqProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Map a Yahoo ticker to a Stooq symbol
+pub fn stooq_symbol(ticker: &str) -> Option<String> {
+    if ticker.starts_with('^') {
+        return None;
+    }
+    let (base, suffix) = match ticker.rsplit_once('.') {
+        Some((base, suffix)) => (base, Some(suffix.to_uppercase())),
+        None => (ticker, None),
+    };
+    let market = match suffix.as_deref() {
+        None => "us",
+        Some("L") => "uk",
+        Some("DE") | Some("F") => "de",
+        Some("T") => "jp",
+        Some("HK") => "hk",
+        _ => return None,
+    };
+    Some(format!("{}.{}", base.to_lowercase(), market))
+}
+
+fn symbol(request: &PriceRequest) -> Result<String> {
+    if request.kind != PriceAssetKind::Stock {
+        return Err(AppError::ExternalApi("Stooq only prices stocks".into()));
+    }
+    stooq_symbol(&request.ticker)
+        .ok_or_else(|| AppError::ExternalApi(format!("{} is not listed on Stooq", request.ticker)))
+}
+
+/// Parse the last close from a quote CSV (Symbol,Date,Time,Open,High,Low,Close,Volume).
+/// Unknown symbols come back with "N/D" values.
+pub fn parse_quote_csv(body: &str) -> Option<f64> {
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let headers = reader.headers().ok()?.clone();
+    let close = headers
+        .iter()
+        .position(|h| h.eq_ignore_ascii_case("close"))?;
+    reader
+        .records()
+        .filter_map(|r| r.ok())
+        .filter_map(|r| r.get(close)?.trim().parse::<f64>().ok())
+        .find(|&p| p > 0.0)
+}
+
+/// Parse daily closes from a history CSV (Date,Open,High,Low,Close,Volume)
+pub fn parse_history_csv(body: &str) -> Vec<(i64, f64)> {
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let Ok(headers) = reader.headers().cloned() else {
+        return vec![];
+    };
+    let date = headers.iter().position(|h| h.eq_ignore_ascii_case("date"));
+    let close = headers.iter().position(|h| h.eq_ignore_ascii_case("close"));
+    let (Some(date), Some(close)) = (date, close) else {
+        return vec![];
+    };
+    reader
+        .records()
+        .filter_map(|r| r.ok())
+        .filter_map(|r| {
+            let ts = date_to_timestamp(r.get(date)?)?;
+            let price = r.get(close)?.trim().parse::<f64>().ok()?;
+            (price > 0.0).then_some((ts, price))
+        })
+        .collect()
+}
+
+fn stooq_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%d")
+        .to_string()
+}
+
+impl PriceProvider for StooqProvider {
+    fn id(&self) -> PriceProviderId {
+        PriceProviderId::Stooq
+    }
+
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            let symbol = symbol(request)?;
+            let url = format!(
+                "https://stooq.com/q/l/?s={}&f=sd2t2ohlcv&h&e=csv",
+                urlencoding::encode(&symbol)
+            );
+            let body = self.fetch_csv(&url).await?;
+            let price = parse_quote_csv(&body)
+                .ok_or_else(|| AppError::ExternalApi("price unavailable".into()))?;
+            Ok(PriceQuote {
+                price,
+                currency: fallback_currency_in_pence(request),
+                currency_reported: false,
+            })
+        })
+    }
+
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>> {
+        Box::pin(async move {
+            let symbol = symbol(request)?;
+            let url = format!(
+                "https://stooq.com/q/d/l/?s={}&d1={}&d2={}&i=d",
+                urlencoding::encode(&symbol),
+                stooq_date(start),
+                stooq_date(end)
+            );
+            let body = self.fetch_csv(&url).await?;
+            let currency = fallback_currency_in_pence(request);
+            Ok(parse_history_csv(&body)
+                .into_iter()
+                .map(|(timestamp, price)| HistoricalPrice {
+                    timestamp,
+                    price,
+                    currency: currency.clone(),
+                })
+                .collect())
+        })
+    }
+
+    fn request_delay_ms(&self) -> u64 {
+        200
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stooq_symbol_mapping() {
+        assert_eq!(stooq_symbol("AAPL").as_deref(), Some("aapl.us"));
+        assert_eq!(stooq_symbol("VWCE.DE").as_deref(), Some("vwce.de"));
+        assert_eq!(stooq_symbol("VUSA.L").as_deref(), Some("vusa.uk"));
+        assert_eq!(stooq_symbol("BRK-B").as_deref(), Some("brk-b.us"));
+        assert_eq!(stooq_symbol("CEZ.PR"), None);
+        assert_eq!(stooq_symbol("^GSPC"), None);
+    }
+
+    #[test]
+    fn test_parse_stooq_csv() {
+        let quote = "Symbol,Date,Time,Open,High,Low,Close,Volume\n\
+                     AAPL.US,2024-03-01,22:00:19,179.55,180.53,177.38,179.66,73488997\n";
+        assert_eq!(parse_quote_csv(quote), Some(179.66));
+
+        let missing =
+            "Symbol,Date,Time,Open,High,Low,Close,Volume\nXXX.US,N/D,N/D,N/D,N/D,N/D,N/D,N/D\n";
+        assert_eq!(parse_quote_csv(missing), None);
+
+        let history = "Date,Open,High,Low,Close,Volume\n\
+                       2024-01-02,187.15,188.44,183.89,185.64,82488700\n\
+                       2024-01-03,184.22,185.88,183.43,184.25,58414500\n";
+        let prices = parse_history_csv(history);
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0], (1704153600, 185.64));
+        assert_eq!(parse_history_csv("No data"), vec![]);
+    }
+}
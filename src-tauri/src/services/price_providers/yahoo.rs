@@ -0,0 +1,130 @@
+//! Yahoo Finance provider (via yahoo_finance_api crate)
+//!
+//! The crate handles the cookie/crumb authentication that Yahoo requires.
+//! Works for US, European (.DE, .PA, .MI, etc.) and HK (.HK) stocks, indices,
+//! and crypto quoted against USD ("BTC-USD").
+
+use super::{PriceProvider, PriceQuote, PriceRequest};
+use crate::error::{AppError, Result};
+use crate::models::{PriceAssetKind, PriceProviderId};
+use crate::services::price_api::HistoricalPrice;
+use futures::future::BoxFuture;
+use yahoo_finance_api as yahoo;
+
+pub struct YahooProvider {
+    connector: yahoo::YahooConnector,
+}
+
+impl YahooProvider {
+    pub fn new() -> Result<Self> {
+        let connector = yahoo::YahooConnector::new()
+            .map_err(|e| AppError::ExternalApi(format!("Yahoo connector failed: {}", e)))?;
+        Ok(YahooProvider { connector })
+    }
+}
+
+/// Yahoo symbol for a request
+fn symbol(request: &PriceRequest) -> String {
+    match request.kind {
+        PriceAssetKind::Stock => request.ticker.clone(),
+        PriceAssetKind::Crypto => format!("{}-USD", request.ticker),
+    }
+}
+
+/// Quote currency from the response metadata (may be a minor unit such as "GBp"),
+/// else the request fallback
+fn currency(response: &yahoo::YResponse, request: &PriceRequest) -> (String, bool) {
+    match response
+        .metadata()
+        .ok()
+        .and_then(|m| m.currency)
+        .filter(|c| !c.is_empty())
+    {
+        Some(currency) => (currency, true),
+        None => (request.fallback_currency(), false),
+    }
+}
+
+impl PriceProvider for YahooProvider {
+    fn id(&self) -> PriceProviderId {
+        PriceProviderId::Yahoo
+    }
+
+    fn latest<'a>(&'a self, request: &'a PriceRequest) -> BoxFuture<'a, Result<PriceQuote>> {
+        Box::pin(async move {
+            let symbol = symbol(request);
+            // Use get_latest_quotes and read regular_market_price from the response metadata.
+            // This field is always populated by Yahoo Finance with the most recent market price
+            // regardless of market hours (open, pre-market, after-hours, weekends).
+            // last_quote().close only returns completed candle closes — during open market
+            // hours today's bar has close=null and it falls back to yesterday's close.
+            let response = self
+                .connector
+                .get_latest_quotes(&symbol, "1d")
+                .await
+                .map_err(|e| AppError::ExternalApi(format!("{}", e)))?;
+
+            // Prefer regular_market_price from metadata (reflects current price),
+            // fall back to last_quote().close if metadata price is unavailable.
+            let price = response
+                .metadata()
+                .ok()
+                .and_then(|m| m.regular_market_price)
+                .filter(|&p| p > 0.0)
+                .or_else(|| {
+                    response
+                        .last_quote()
+                        .ok()
+                        .map(|q| q.close)
+                        .filter(|&p| p > 0.0)
+                })
+                .ok_or_else(|| AppError::ExternalApi("price unavailable".into()))?;
+            let (currency, currency_reported) = currency(&response, request);
+
+            Ok(PriceQuote {
+                price,
+                currency,
+                currency_reported,
+            })
+        })
+    }
+
+    fn history<'a>(
+        &'a self,
+        request: &'a PriceRequest,
+        start: i64,
+        end: i64,
+    ) -> BoxFuture<'a, Result<Vec<HistoricalPrice>>> {
+        Box::pin(async move {
+            let symbol = symbol(request);
+            let start = time::OffsetDateTime::from_unix_timestamp(start)
+                .unwrap_or(time::OffsetDateTime::now_utc());
+            let end = time::OffsetDateTime::from_unix_timestamp(end)
+                .unwrap_or(time::OffsetDateTime::now_utc());
+
+            let response = self
+                .connector
+                .get_quote_history(&symbol, start, end)
+                .await
+                .map_err(|e| AppError::ExternalApi(format!("{}", e)))?;
+            let quotes = response
+                .quotes()
+                .map_err(|e| AppError::ExternalApi(format!("no quotes: {}", e)))?;
+            let (currency, _) = currency(&response, request);
+
+            Ok(quotes
+                .iter()
+                .map(|q| HistoricalPrice {
+                    timestamp: q.timestamp,
+                    price: q.close,
+                    currency: currency.clone(),
+                })
+                .collect())
+        })
+    }
+
+    /// Matches the former batching of 5 tickers per 500ms
+    fn request_delay_ms(&self) -> u64 {
+        100
+    }
+}
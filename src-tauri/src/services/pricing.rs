@@ -2,7 +2,12 @@
 //!
 //! SINGLE SOURCE OF TRUTH for determining the active price of a stock or crypto asset.
 //! Logic: manual override > API price > None
+//!
+//! The currency of an API price is the stored per-ticker currency when there is one
+//! (see `stock_ticker_currencies`), otherwise the currency saved with the price.
 
+use crate::error::{AppError, Result};
+use crate::models::{TickerCurrency, TickerCurrencySource};
 use crate::services::currency::convert_to_czk;
 
 /// Resolved price for a stock or crypto asset
@@ -32,14 +37,19 @@ pub fn resolve_stock_price(conn: &rusqlite::Connection, ticker: &str) -> Option<
         )
         .ok();
 
-    // 2. Check global (API) price
+    // 2. Check global (API) price; rows saved before currencies were stored per
+    // ticker may carry a currency guessed from the ticker suffix
     let global_price: Option<(String, String, i64)> = conn
         .query_row(
             "SELECT original_price, currency, fetched_at FROM stock_data WHERE ticker = ?1",
             [ticker],
             |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )
-        .ok();
+        .ok()
+        .map(|(price, currency, fetched_at)| {
+            let currency = stored_ticker_currency(conn, ticker).unwrap_or(currency);
+            (price, currency, fetched_at)
+        });
 
     // 3. Resolve: prefer override if newer, otherwise global
     match (&override_price, &global_price) {
@@ -68,6 +78,108 @@ pub fn resolve_stock_price(conn: &rusqlite::Connection, ticker: &str) -> Option<
     }
 }
 
+// ============================================================================
+// Stored ticker currencies
+// ============================================================================
+
+/// Stored quote currency of a stock ticker, if known
+pub fn stored_ticker_currency(conn: &rusqlite::Connection, ticker: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT currency FROM stock_ticker_currencies WHERE ticker = ?1",
+        [ticker.to_uppercase()],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// Get all stored ticker currencies
+pub fn get_ticker_currencies(conn: &rusqlite::Connection) -> Result<Vec<TickerCurrency>> {
+    let mut stmt = conn.prepare(
+        "SELECT ticker, currency, source, updated_at FROM stock_ticker_currencies ORDER BY ticker",
+    )?;
+    let currencies = stmt
+        .query_map([], |row| {
+            let source: String = row.get(2)?;
+            Ok(TickerCurrency {
+                ticker: row.get(0)?,
+                currency: row.get(1)?,
+                source: if source == "manual" {
+                    TickerCurrencySource::Manual
+                } else {
+                    TickerCurrencySource::Provider
+                },
+                updated_at: row.get(3)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(currencies)
+}
+
+/// Remember the currency a provider reported for a ticker.
+/// Provider metadata describes the fetched numbers, so it replaces a manual value.
+pub fn record_provider_currency(
+    conn: &rusqlite::Connection,
+    ticker: &str,
+    currency: &str,
+    now: i64,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO stock_ticker_currencies (ticker, currency, source, updated_at)
+         VALUES (?1, ?2, 'provider', ?3)
+         ON CONFLICT(ticker) DO UPDATE SET
+           currency = excluded.currency, source = excluded.source, updated_at = excluded.updated_at
+         WHERE stock_ticker_currencies.currency != excluded.currency
+            OR stock_ticker_currencies.source != excluded.source",
+        rusqlite::params![ticker.to_uppercase(), currency, now],
+    )?;
+    Ok(())
+}
+
+/// Set the quote currency of a ticker by hand (for providers that report none).
+/// The stored API price is relabelled so every reader sees the same currency.
+pub fn set_manual_ticker_currency(
+    conn: &rusqlite::Connection,
+    ticker: &str,
+    currency: &str,
+) -> Result<TickerCurrency> {
+    let ticker = ticker.trim().to_uppercase();
+    let currency = currency.trim().to_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::Validation("validation.currencyInvalid".into()));
+    }
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO stock_ticker_currencies (ticker, currency, source, updated_at)
+         VALUES (?1, ?2, 'manual', ?3)
+         ON CONFLICT(ticker) DO UPDATE SET
+           currency = excluded.currency, source = excluded.source, updated_at = excluded.updated_at",
+        rusqlite::params![ticker, currency, now],
+    )?;
+    conn.execute(
+        "UPDATE stock_data SET currency = ?2 WHERE ticker = ?1",
+        rusqlite::params![ticker, currency],
+    )?;
+
+    Ok(TickerCurrency {
+        ticker,
+        currency,
+        source: TickerCurrencySource::Manual,
+        updated_at: now,
+    })
+}
+
+/// Forget the stored currency; the next refresh falls back to provider metadata
+/// or the ticker-suffix guess
+pub fn delete_ticker_currency(conn: &rusqlite::Connection, ticker: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM stock_ticker_currencies WHERE ticker = ?1",
+        [ticker.trim().to_uppercase()],
+    )?;
+    Ok(())
+}
+
 /// Resolve the current price for a crypto symbol.
 /// Priority: crypto_price_overrides > crypto_prices > None
 pub fn resolve_crypto_price(conn: &rusqlite::Connection, symbol: &str) -> Option<ResolvedPrice> {
@@ -132,6 +244,13 @@ mod tests {
                 fetched_at INTEGER NOT NULL DEFAULT 0
             );
 
+            CREATE TABLE stock_ticker_currencies (
+                ticker TEXT PRIMARY KEY,
+                currency TEXT NOT NULL,
+                source TEXT NOT NULL DEFAULT 'provider',
+                updated_at INTEGER NOT NULL DEFAULT 0
+            );
+
             CREATE TABLE stock_price_overrides (
                 ticker TEXT PRIMARY KEY,
                 price TEXT NOT NULL,
@@ -199,6 +318,37 @@ mod tests {
         assert!(result.is_manual);
     }
 
+    #[test]
+    fn test_resolve_stock_price_trusts_stored_ticker_currency() {
+        let conn = setup_test_db();
+        // Saved before currencies were stored: ".L" guessed as GBP, but CSPX trades in USD
+        conn.execute(
+            "INSERT INTO stock_data (ticker, original_price, currency, fetched_at) VALUES ('CSPX.L', '550.00', 'GBP', 100)",
+            [],
+        )
+        .unwrap();
+        record_provider_currency(&conn, "cspx.l", "USD", 100).unwrap();
+
+        let result = resolve_stock_price(&conn, "CSPX.L").expect("price");
+        assert_eq!(result.currency, "USD");
+
+        let manual = set_manual_ticker_currency(&conn, "CSPX.L", "eur").unwrap();
+        assert_eq!(manual.source, TickerCurrencySource::Manual);
+        assert_eq!(
+            resolve_stock_price(&conn, "CSPX.L").unwrap().currency,
+            "EUR"
+        );
+        assert!(set_manual_ticker_currency(&conn, "CSPX.L", "EURO").is_err());
+
+        delete_ticker_currency(&conn, "CSPX.L").unwrap();
+        assert_eq!(get_ticker_currencies(&conn).unwrap().len(), 0);
+        // stock_data was relabelled by the manual currency
+        assert_eq!(
+            resolve_stock_price(&conn, "CSPX.L").unwrap().currency,
+            "EUR"
+        );
+    }
+
     #[test]
     fn test_resolve_crypto_price_returns_none_when_no_data() {
         let conn = setup_test_db();
@@ -0,0 +1,1139 @@
+//! Real estate valuations
+//!
+//! Each property keeps dated valuations (appraisals, price index updates,
+//! listings of comparable properties or own estimates). The latest one sets
+//! `market_price`; on earlier days the property is worth its last valuation
+//! up to that day, else its purchase price once it has a purchase date, else
+//! the current market price. Nothing is counted before the purchase date.
+//!
+//! Equity is the value minus the outstanding principal of the linked loans.
+//! Amounts in other currencies are converted at the current rate.
+//!
+//! Rental performance covers the trailing year, and the cumulative cash flow
+//! the holding period. Linked credits are the rent of the months they were
+//! booked in, and the monthly rent fills the months without any. Operating
+//! costs are the recurring costs and linked insurance premiums plus the linked
+//! debits (repairs and other costs beyond them). Debt service comes from the
+//! loan schedules, which use matched bank payments.
+
+use crate::error::{AppError, Result};
+use crate::models::{
+    BankTransaction, InsertRealEstateValuation, Loan, LoanInstallment, PerformanceSource,
+    RealEstate, RealEstateValuation, RealEstateValueHistory, RealEstateValuePoint,
+    RentalPerformance,
+};
+use crate::services::loans as loan_service;
+use crate::services::money::{self, Decimal};
+use chrono::{Datelike, Months, NaiveDate};
+use rusqlite::{Connection, OptionalExtension, Row};
+use uuid::Uuid;
+
+/// Columns read by `real_estate_from_row`
+pub const REAL_ESTATE_COLUMNS: &str =
+    "id, name, address, type, purchase_price, purchase_price_currency,
+     market_price, market_price_currency, monthly_rent, monthly_rent_currency,
+     recurring_costs, photos, notes, created_at, updated_at, purchase_date";
+
+/// Columns read by `valuation_from_row`
+const VALUATION_COLUMNS: &str =
+    "id, real_estate_id, valuation_date, value, currency, source, notes, created_at";
+
+/// Columns read by `transaction_from_row`
+const TRANSACTION_COLUMNS: &str =
+    "id, bank_account_id, transaction_id, tx_type, amount, currency, description,
+     counterparty_name, counterparty_iban, booking_date, value_date, category_id,
+     merchant_category_code, remittance_info, variable_symbol, status, data_source, created_at";
+
+/// Length of the rental performance period
+const YEAR_SECONDS: i64 = 365 * 86400;
+
+/// Upper bound on monthly history points (fifty years)
+const MAX_MONTHS: u32 = 600;
+
+/// Holding period in years from which appreciation is annualized
+const MIN_ANNUALIZED_YEARS: f64 = 1.0;
+
+pub fn real_estate_from_row(row: &Row) -> rusqlite::Result<RealEstate> {
+    let rc_json: String = row.get(10)?;
+    let photos_json: String = row.get(11)?;
+
+    Ok(RealEstate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        address: row.get(2)?,
+        property_type: row.get(3)?,
+        purchase_price: row.get(4)?,
+        purchase_price_currency: row.get(5)?,
+        market_price: row.get(6)?,
+        market_price_currency: row.get(7)?,
+        monthly_rent: row.get(8)?,
+        monthly_rent_currency: row.get(9)?,
+        recurring_costs: serde_json::from_str(&rc_json).unwrap_or_default(),
+        photos: serde_json::from_str(&photos_json).unwrap_or_default(),
+        notes: row.get(12)?,
+        created_at: row.get(13)?,
+        updated_at: row.get(14)?,
+        purchase_date: row.get(15)?,
+    })
+}
+
+fn valuation_from_row(row: &Row) -> rusqlite::Result<RealEstateValuation> {
+    Ok(RealEstateValuation {
+        id: row.get(0)?,
+        real_estate_id: row.get(1)?,
+        valuation_date: row.get(2)?,
+        value: row.get(3)?,
+        currency: row.get(4)?,
+        source: row.get(5)?,
+        notes: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+fn transaction_from_row(row: &Row) -> rusqlite::Result<BankTransaction> {
+    Ok(BankTransaction {
+        id: row.get(0)?,
+        bank_account_id: row.get(1)?,
+        transaction_id: row.get(2)?,
+        tx_type: row.get(3)?,
+        amount: row.get(4)?,
+        currency: row.get(5)?,
+        description: row.get(6)?,
+        counterparty_name: row.get(7)?,
+        counterparty_iban: row.get(8)?,
+        booking_date: row.get(9)?,
+        value_date: row.get(10)?,
+        category_id: row.get(11)?,
+        merchant_category_code: row.get(12)?,
+        remittance_info: row.get(13)?,
+        variable_symbol: row.get(14)?,
+        status: row.get(15)?,
+        data_source: row.get(16)?,
+        created_at: row.get(17)?,
+    })
+}
+
+pub fn get_properties(conn: &Connection) -> Result<Vec<RealEstate>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM real_estate ORDER BY name",
+        REAL_ESTATE_COLUMNS
+    ))?;
+    let properties = stmt
+        .query_map([], real_estate_from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(properties)
+}
+
+pub fn get_property(conn: &Connection, id: &str) -> Result<Option<RealEstate>> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM real_estate WHERE id = ?1",
+                REAL_ESTATE_COLUMNS
+            ),
+            [id],
+            real_estate_from_row,
+        )
+        .optional()?)
+}
+
+/// Valuations of a property, oldest first
+pub fn get_valuations(conn: &Connection, real_estate_id: &str) -> Result<Vec<RealEstateValuation>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM real_estate_valuations WHERE real_estate_id = ?1
+         ORDER BY valuation_date, created_at",
+        VALUATION_COLUMNS
+    ))?;
+    let valuations = stmt
+        .query_map([real_estate_id], valuation_from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(valuations)
+}
+
+/// Set `market_price` to the latest valuation, if there is any
+fn sync_market_price(conn: &Connection, real_estate_id: &str) -> Result<()> {
+    if let Some(latest) = get_valuations(conn, real_estate_id)?.pop() {
+        conn.execute(
+            "UPDATE real_estate SET market_price = ?1, market_price_currency = ?2, updated_at = ?3
+             WHERE id = ?4",
+            rusqlite::params![
+                latest.value,
+                latest.currency,
+                chrono::Utc::now().timestamp(),
+                real_estate_id
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Add a valuation; the latest one becomes the market price
+pub fn add_valuation(
+    conn: &Connection,
+    data: &InsertRealEstateValuation,
+) -> Result<RealEstateValuation> {
+    let property = get_property(conn, &data.real_estate_id)?
+        .ok_or_else(|| AppError::NotFound("Real estate not found".into()))?;
+
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO real_estate_valuations
+             (id, real_estate_id, valuation_date, value, currency, source, notes, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            id,
+            data.real_estate_id,
+            data.valuation_date,
+            data.value.trim(),
+            data.currency
+                .as_deref()
+                .unwrap_or(&property.market_price_currency),
+            data.source.as_deref().unwrap_or("manual"),
+            data.notes,
+            chrono::Utc::now().timestamp(),
+        ],
+    )?;
+    sync_market_price(conn, &data.real_estate_id)?;
+
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM real_estate_valuations WHERE id = ?1",
+            VALUATION_COLUMNS
+        ),
+        [&id],
+        valuation_from_row,
+    )
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Delete a valuation; the market price falls back to the latest remaining one
+pub fn delete_valuation(conn: &Connection, id: &str) -> Result<()> {
+    let real_estate_id: String = conn
+        .query_row(
+            "SELECT real_estate_id FROM real_estate_valuations WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound("Valuation not found".into()))?;
+    conn.execute("DELETE FROM real_estate_valuations WHERE id = ?1", [id])?;
+    sync_market_price(conn, &real_estate_id)
+}
+
+/// Record an edited market price as an own estimate dated `now`
+///
+/// A property without a purchase date or valuations has only its market price
+/// to go by, so the price it had until now is first kept as of its creation.
+pub fn record_market_price(
+    conn: &Connection,
+    property: &RealEstate,
+    value: &str,
+    currency: &str,
+    now: i64,
+) -> Result<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO real_estate_valuations
+             (id, real_estate_id, valuation_date, value, currency, source, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'manual', ?6)",
+    )?;
+    if property.purchase_date.is_none()
+        && property.created_at < now
+        && money::parse(&property.market_price).is_some()
+        && get_valuations(conn, &property.id)?.is_empty()
+    {
+        stmt.execute(rusqlite::params![
+            Uuid::new_v4().to_string(),
+            property.id,
+            property.created_at,
+            property.market_price,
+            property.market_price_currency,
+            now
+        ])?;
+    }
+    stmt.execute(rusqlite::params![
+        Uuid::new_v4().to_string(),
+        property.id,
+        now,
+        value,
+        currency,
+        now
+    ])?;
+    Ok(())
+}
+
+/// Value of a property on a day in its market price currency, from its
+/// valuations (oldest first)
+pub fn value_on(property: &RealEstate, valuations: &[RealEstateValuation], day_ts: i64) -> Decimal {
+    let currency = property.market_price_currency.as_str();
+    if property
+        .purchase_date
+        .is_some_and(|purchased| day_ts < purchased)
+    {
+        return Decimal::ZERO;
+    }
+    if let Some(valuation) = valuations.iter().rev().find(|v| v.valuation_date <= day_ts) {
+        return money::convert_between(
+            money::parse_or_zero(&valuation.value),
+            &valuation.currency,
+            currency,
+        );
+    }
+    if property.purchase_date.is_some() {
+        return money::convert_between(
+            money::parse_or_zero(&property.purchase_price),
+            &property.purchase_price_currency,
+            currency,
+        );
+    }
+    money::parse_or_zero(&property.market_price)
+}
+
+/// Every property with its valuations (oldest first), for valuing many days
+/// with `value_on`
+pub fn properties_with_valuations(
+    conn: &Connection,
+) -> Result<Vec<(RealEstate, Vec<RealEstateValuation>)>> {
+    get_properties(conn)?
+        .into_iter()
+        .map(|property| {
+            let valuations = get_valuations(conn, &property.id)?;
+            Ok((property, valuations))
+        })
+        .collect()
+}
+
+fn date_of(ts: i64) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp(ts, 0).map(|dt| dt.date_naive())
+}
+
+fn timestamp_of(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+/// History days: the start, the first of every following month, each
+/// valuation date and `now`
+fn history_days(start: i64, valuations: &[RealEstateValuation], now: i64) -> Vec<i64> {
+    let mut days = vec![start, now];
+    days.extend(
+        valuations
+            .iter()
+            .map(|v| v.valuation_date)
+            .filter(|date| (start..=now).contains(date)),
+    );
+    if let Some(first) = date_of(start).and_then(|d| d.with_day(1)) {
+        let months = (1..=MAX_MONTHS)
+            .map_while(|k| first.checked_add_months(Months::new(k)))
+            .map(timestamp_of)
+            .take_while(|ts| *ts < now);
+        days.extend(months);
+    }
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+/// A linked loan with its payments found in bank transactions and its
+/// amortization schedule
+type LinkedLoan = (Loan, Vec<loan_service::Payment>, Vec<LoanInstallment>);
+
+/// Linked loans with their payments and amortization schedules as of `now`
+fn linked_loans(conn: &Connection, real_estate_id: &str, now: i64) -> Result<Vec<LinkedLoan>> {
+    let mut stmt =
+        conn.prepare("SELECT loan_id FROM real_estate_loans WHERE real_estate_id = ?1")?;
+    let loan_ids: Vec<String> = stmt
+        .query_map([real_estate_id], |row| row.get(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    let all_loans = loan_service::get_loans(conn)?;
+    let mut payments_by_loan = loan_service::payments_by_loan(conn, &all_loans)?;
+    let mut loans = Vec::new();
+    for loan in all_loans
+        .into_iter()
+        .filter(|loan| loan_ids.contains(&loan.id))
+    {
+        let payments = payments_by_loan.remove(&loan.id).unwrap_or_default();
+        let installments = loan_service::schedule(&loan, &payments, now).installments;
+        loans.push((loan, payments, installments));
+    }
+    Ok(loans)
+}
+
+/// Value, linked loan balance and equity over time, and appreciation since purchase
+pub fn value_history(
+    conn: &Connection,
+    real_estate_id: &str,
+    now: i64,
+) -> Result<RealEstateValueHistory> {
+    let property = get_property(conn, real_estate_id)?
+        .ok_or_else(|| AppError::NotFound("Real estate not found".into()))?;
+    let valuations = get_valuations(conn, real_estate_id)?;
+    let currency = property.market_price_currency.clone();
+
+    let loans = linked_loans(conn, real_estate_id, now)?;
+    let loan_balance_on = |day: i64| -> Decimal {
+        loans
+            .iter()
+            .map(|(loan, _, installments)| {
+                money::convert_between(
+                    loan_service::balance_on(loan, installments, day),
+                    &loan.currency,
+                    &currency,
+                )
+            })
+            .sum()
+    };
+
+    let start = property
+        .purchase_date
+        .or_else(|| valuations.first().map(|v| v.valuation_date))
+        .unwrap_or(property.created_at)
+        .min(now);
+    let points = history_days(start, &valuations, now)
+        .into_iter()
+        .map(|day| {
+            let value = value_on(&property, &valuations, day);
+            let loan_balance = loan_balance_on(day);
+            RealEstateValuePoint {
+                date: day,
+                value: money::to_f64(value),
+                loan_balance: money::to_f64(loan_balance),
+                equity: money::to_f64(value - loan_balance),
+            }
+        })
+        .collect();
+
+    let purchase_price = money::convert_between(
+        money::parse_or_zero(&property.purchase_price),
+        &property.purchase_price_currency,
+        &currency,
+    );
+    let current_value = money::parse_or_zero(&property.market_price);
+    let loan_balance = loan_balance_on(now);
+    let (appreciation_percent, annualized_appreciation_percent) = if purchase_price > Decimal::ZERO
+    {
+        let ratio = money::to_f64(current_value) / money::to_f64(purchase_price);
+        let annualized = property
+            .purchase_date
+            .map(|purchased| (now - purchased) as f64 / (365.25 * 86400.0))
+            .filter(|years| *years >= MIN_ANNUALIZED_YEARS)
+            .map(|years| (ratio.powf(1.0 / years) - 1.0) * 100.0);
+        (Some((ratio - 1.0) * 100.0), annualized)
+    } else {
+        (None, None)
+    };
+
+    Ok(RealEstateValueHistory {
+        real_estate_id: property.id,
+        purchase_price: money::format(purchase_price, &currency),
+        purchase_date: property.purchase_date,
+        current_value: money::format(current_value, &currency),
+        loan_balance: money::format(loan_balance, &currency),
+        equity: money::format(current_value - loan_balance, &currency),
+        appreciation: money::format(current_value - purchase_price, &currency),
+        appreciation_percent,
+        annualized_appreciation_percent,
+        points,
+        currency,
+    })
+}
+
+/// Bank transactions linked to a property, newest first
+pub fn get_linked_transactions(
+    conn: &Connection,
+    real_estate_id: &str,
+) -> Result<Vec<BankTransaction>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM bank_transactions
+         WHERE id IN (SELECT transaction_id FROM real_estate_transactions WHERE real_estate_id = ?1)
+         ORDER BY booking_date DESC",
+        TRANSACTION_COLUMNS
+    ))?;
+    let transactions = stmt
+        .query_map([real_estate_id], transaction_from_row)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(transactions)
+}
+
+/// Link transactions to a property; a transaction belongs to one property at most
+pub fn link_transactions(
+    conn: &Connection,
+    real_estate_id: &str,
+    transaction_ids: &[String],
+) -> Result<()> {
+    if get_property(conn, real_estate_id)?.is_none() {
+        return Err(AppError::NotFound("Real estate not found".into()));
+    }
+    for transaction_id in transaction_ids {
+        conn.execute(
+            "INSERT OR REPLACE INTO real_estate_transactions (transaction_id, real_estate_id)
+             VALUES (?1, ?2)",
+            rusqlite::params![transaction_id, real_estate_id],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn unlink_transaction(
+    conn: &Connection,
+    real_estate_id: &str,
+    transaction_id: &str,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM real_estate_transactions WHERE real_estate_id = ?1 AND transaction_id = ?2",
+        [real_estate_id, transaction_id],
+    )?;
+    Ok(())
+}
+
+/// Yearly amount of a payment made at the given frequency
+fn yearly_amount(amount: Decimal, frequency: &str) -> Decimal {
+    let per_year = match frequency.to_lowercase().as_str() {
+        "quarterly" => 4,
+        "semi-annually" | "semiannually" => 2,
+        "yearly" | "annually" | "annual" => 1,
+        "one-time" | "onetime" => 0,
+        _ => 12,
+    };
+    amount * Decimal::from(per_year)
+}
+
+/// Yearly premiums of the active insurance policies linked to a property
+fn insurance_premiums(conn: &Connection, real_estate_id: &str, currency: &str) -> Result<Decimal> {
+    let mut stmt = conn.prepare(
+        "SELECT regular_payment, regular_payment_currency, payment_frequency
+         FROM insurance_policies
+         WHERE status = 'active'
+           AND id IN (SELECT insurance_id FROM real_estate_insurances WHERE real_estate_id = ?1)",
+    )?;
+    let premiums = stmt
+        .query_map([real_estate_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .map(|(payment, payment_currency, frequency)| {
+            money::convert_between(
+                yearly_amount(money::parse_or_zero(&payment), &frequency),
+                &payment_currency,
+                currency,
+            )
+        })
+        .sum();
+    Ok(premiums)
+}
+
+/// One-time costs of a property
+fn one_time_costs(conn: &Connection, real_estate_id: &str, currency: &str) -> Result<Decimal> {
+    let mut stmt = conn.prepare(
+        "SELECT amount, currency FROM real_estate_one_time_costs WHERE real_estate_id = ?1",
+    )?;
+    let total = stmt
+        .query_map([real_estate_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .filter_map(|r| r.ok())
+        .map(|(amount, cost_currency)| {
+            money::convert_between(money::parse_or_zero(&amount), &cost_currency, currency)
+        })
+        .sum();
+    Ok(total)
+}
+
+/// `part` as a percentage of `whole`, if `whole` is positive
+fn percent_of(part: Decimal, whole: Decimal) -> Option<f64> {
+    (whole > Decimal::ZERO).then(|| money::to_f64(part) / money::to_f64(whole) * 100.0)
+}
+
+fn source(actual: bool) -> PerformanceSource {
+    if actual {
+        PerformanceSource::Actual
+    } else {
+        PerformanceSource::Estimated
+    }
+}
+
+/// Rent, operating costs and debt service of a property over a period
+struct Flows {
+    rent: Decimal,
+    rent_source: PerformanceSource,
+    operating_costs: Decimal,
+    costs_source: PerformanceSource,
+    debt_service: Decimal,
+    debt_source: PerformanceSource,
+}
+
+impl Flows {
+    fn cash_flow(&self) -> Decimal {
+        self.rent - self.operating_costs - self.debt_service
+    }
+}
+
+/// Month of a twelfth of a year since `start` that `date` falls in, capped at
+/// the last of `months`
+fn month_index(date: i64, start: i64, months: usize) -> usize {
+    (((date - start) * 12 / YEAR_SECONDS) as usize).min(months.saturating_sub(1))
+}
+
+/// Flows from `start` to `now`, in months of a twelfth of a year counted from
+/// `start`, given the `monthly_rent` and the `yearly_costs` estimated from the
+/// property terms
+///
+/// Linked credits and debits replace the estimates of the months they were
+/// booked in. Debits paying an installment of a linked loan count as debt
+/// service only.
+fn flows(
+    transactions: &[BankTransaction],
+    loans: &[LinkedLoan],
+    monthly_rent: Decimal,
+    yearly_costs: Decimal,
+    currency: &str,
+    start: i64,
+    now: i64,
+) -> Flows {
+    let months = ((now - start).max(0) * 12 / YEAR_SECONDS) as usize;
+    let mut rent_covered = vec![false; months];
+    let mut costs_covered = vec![false; months];
+    let mut linked_rent = Decimal::ZERO;
+    let mut linked_costs = Decimal::ZERO;
+    for transaction in transactions {
+        if transaction.status != "booked"
+            || transaction.booking_date <= start
+            || transaction.booking_date > now
+        {
+            continue;
+        }
+        let amount = money::parse_or_zero(&transaction.amount).abs();
+        let month = month_index(transaction.booking_date, start, months);
+        if transaction.tx_type.eq_ignore_ascii_case("credit") {
+            linked_rent += money::convert_between(amount, &transaction.currency, currency);
+            if let Some(slot) = rent_covered.get_mut(month) {
+                *slot = true;
+            }
+        } else {
+            let pays_loan = loans.iter().any(|(loan, payments, _)| {
+                let payment = (
+                    transaction.booking_date,
+                    money::convert_between(amount, &transaction.currency, &loan.currency),
+                );
+                payments.contains(&payment)
+            });
+            if pays_loan {
+                continue;
+            }
+            linked_costs += money::convert_between(amount, &transaction.currency, currency);
+            if let Some(slot) = costs_covered.get_mut(month) {
+                *slot = true;
+            }
+        }
+    }
+    let uncovered_rent_months = rent_covered.iter().filter(|c| !**c).count();
+    let uncovered_cost_months = costs_covered.iter().filter(|c| !**c).count();
+
+    let mut debt_service = Decimal::ZERO;
+    let mut debt_matched = true;
+    for (loan, _, installments) in loans {
+        for installment in installments
+            .iter()
+            .filter(|i| i.date > start && i.date <= now)
+        {
+            debt_service += money::convert_between(
+                money::parse_or_zero(&installment.payment),
+                &loan.currency,
+                currency,
+            );
+            debt_matched &= installment.status == "paid";
+        }
+    }
+
+    Flows {
+        rent: linked_rent + monthly_rent * Decimal::from(uncovered_rent_months),
+        rent_source: mixed_source(
+            linked_rent > Decimal::ZERO,
+            uncovered_rent_months > 0 && !monthly_rent.is_zero(),
+        ),
+        operating_costs: linked_costs
+            + yearly_costs * Decimal::from(uncovered_cost_months) / Decimal::from(12),
+        costs_source: mixed_source(
+            linked_costs > Decimal::ZERO,
+            uncovered_cost_months > 0 && !yearly_costs.is_zero(),
+        ),
+        debt_service,
+        debt_source: source(debt_matched),
+    }
+}
+
+/// Source of a figure made of linked transactions, estimates or both
+fn mixed_source(actual: bool, estimated: bool) -> PerformanceSource {
+    match (actual, estimated) {
+        (true, true) => PerformanceSource::Mixed,
+        (true, false) => PerformanceSource::Actual,
+        (false, _) => PerformanceSource::Estimated,
+    }
+}
+
+/// Yield, cash flow, return and break-even figures of a property over the year to `now`
+pub fn performance(
+    conn: &Connection,
+    property: &RealEstate,
+    now: i64,
+) -> Result<RentalPerformance> {
+    let currency = property.market_price_currency.as_str();
+    let convert = |amount: &str, from: &str| {
+        money::convert_between(money::parse_or_zero(amount), from, currency)
+    };
+    let transactions = get_linked_transactions(conn, &property.id)?;
+    let loans = linked_loans(conn, &property.id, now)?;
+
+    // Rent and costs estimated from the property terms
+    let monthly_rent = property
+        .monthly_rent
+        .as_deref()
+        .map(|rent| {
+            convert(
+                rent,
+                property.monthly_rent_currency.as_deref().unwrap_or("CZK"),
+            )
+        })
+        .unwrap_or(Decimal::ZERO);
+    let recurring: Decimal = property
+        .recurring_costs
+        .iter()
+        .map(|cost| {
+            money::convert_between(
+                yearly_amount(money::from_f64(cost.amount), &cost.frequency),
+                cost.currency.as_deref().unwrap_or("CZK"),
+                currency,
+            )
+        })
+        .sum();
+    let yearly_costs = recurring + insurance_premiums(conn, &property.id, currency)?;
+
+    let year = flows(
+        &transactions,
+        &loans,
+        monthly_rent,
+        yearly_costs,
+        currency,
+        now - YEAR_SECONDS,
+        now,
+    );
+    let annual_rent = year.rent;
+    let operating_costs = year.operating_costs;
+    let debt_service = year.debt_service;
+
+    let mut loan_principal = Decimal::ZERO;
+    let mut loan_balance = Decimal::ZERO;
+    for (loan, _, installments) in &loans {
+        loan_principal += convert(&loan.principal, &loan.currency);
+        loan_balance += money::convert_between(
+            loan_service::balance_on(loan, installments, now),
+            &loan.currency,
+            currency,
+        );
+    }
+
+    let net_operating_income = annual_rent - operating_costs;
+    let annual_cash_flow = net_operating_income - debt_service;
+    let total_cost = convert(&property.purchase_price, &property.purchase_price_currency)
+        + one_time_costs(conn, &property.id, currency)?;
+    let cash_invested = total_cost - loan_principal;
+    let current_value = money::parse_or_zero(&property.market_price);
+    let equity = current_value - loan_balance;
+
+    let held_since = property.purchase_date.unwrap_or(property.created_at);
+    let years_held = ((now - held_since).max(0) as f64) / (365.25 * 86400.0);
+    let cumulative_cash_flow = flows(
+        &transactions,
+        &loans,
+        monthly_rent,
+        yearly_costs,
+        currency,
+        held_since,
+        now,
+    )
+    .cash_flow();
+    let total_return = equity + cumulative_cash_flow - cash_invested;
+
+    let outgoings = operating_costs + debt_service;
+    let payback_years = (cash_invested > Decimal::ZERO && annual_cash_flow > Decimal::ZERO)
+        .then(|| money::to_f64(cash_invested) / money::to_f64(annual_cash_flow));
+
+    Ok(RentalPerformance {
+        real_estate_id: property.id.clone(),
+        name: property.name.clone(),
+        currency: currency.to_string(),
+        annual_rent: money::format(annual_rent, currency),
+        rent_source: year.rent_source,
+        operating_costs: money::format(operating_costs, currency),
+        costs_source: year.costs_source,
+        net_operating_income: money::format(net_operating_income, currency),
+        debt_service: money::format(debt_service, currency),
+        debt_source: year.debt_source,
+        annual_cash_flow: money::format(annual_cash_flow, currency),
+        total_cost: money::format(total_cost, currency),
+        cash_invested: money::format(cash_invested, currency),
+        current_value: money::format(current_value, currency),
+        equity: money::format(equity, currency),
+        cumulative_cash_flow: money::format(cumulative_cash_flow, currency),
+        years_held,
+        gross_yield_percent: percent_of(annual_rent, total_cost),
+        net_yield_percent: percent_of(net_operating_income, total_cost),
+        cap_rate_percent: percent_of(net_operating_income, current_value),
+        cash_on_cash_percent: percent_of(annual_cash_flow, cash_invested),
+        total_roi_percent: percent_of(total_return, cash_invested),
+        break_even_monthly_rent: money::format(outgoings / Decimal::from(12), currency),
+        break_even_occupancy_percent: percent_of(outgoings, annual_rent),
+        payback_years,
+    })
+}
+
+/// Rental performance of every property not used as a personal residence
+pub fn get_performance(conn: &Connection, now: i64) -> Result<Vec<RentalPerformance>> {
+    get_properties(conn)?
+        .iter()
+        .filter(|property| property.property_type != "personal")
+        .map(|property| performance(conn, property, now))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2020-01-01 00:00 UTC
+    const PURCHASED: i64 = 1_577_836_800;
+    /// 2022-01-01 00:00 UTC
+    const REVALUED: i64 = 1_640_995_200;
+    /// 2025-01-01 00:00 UTC
+    const NOW: i64 = 1_735_689_600;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE real_estate (
+                 id TEXT PRIMARY KEY,
+                 name TEXT NOT NULL,
+                 address TEXT NOT NULL,
+                 type TEXT NOT NULL,
+                 purchase_price TEXT NOT NULL DEFAULT '0',
+                 purchase_price_currency TEXT NOT NULL DEFAULT 'CZK',
+                 market_price TEXT NOT NULL DEFAULT '0',
+                 market_price_currency TEXT NOT NULL DEFAULT 'CZK',
+                 monthly_rent TEXT,
+                 monthly_rent_currency TEXT DEFAULT 'CZK',
+                 recurring_costs TEXT DEFAULT '[]',
+                 photos TEXT DEFAULT '[]',
+                 notes TEXT,
+                 created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+                 updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+                 purchase_date INTEGER
+             );
+             CREATE TABLE real_estate_valuations (
+                 id TEXT PRIMARY KEY,
+                 real_estate_id TEXT NOT NULL,
+                 valuation_date INTEGER NOT NULL,
+                 value TEXT NOT NULL,
+                 currency TEXT NOT NULL DEFAULT 'CZK',
+                 source TEXT NOT NULL DEFAULT 'manual',
+                 notes TEXT,
+                 created_at INTEGER NOT NULL DEFAULT (unixepoch())
+             );
+             CREATE TABLE real_estate_loans (real_estate_id TEXT NOT NULL, loan_id TEXT NOT NULL);
+             CREATE TABLE loans (
+                 id TEXT PRIMARY KEY, name TEXT NOT NULL, principal TEXT NOT NULL,
+                 currency TEXT NOT NULL DEFAULT 'CZK', interest_rate TEXT, interest_rate_validity_date INTEGER,
+                 monthly_payment TEXT, start_date INTEGER, end_date INTEGER,
+                 created_at INTEGER NOT NULL DEFAULT 0, updated_at INTEGER NOT NULL DEFAULT 0,
+                 repayment_type TEXT NOT NULL DEFAULT 'annuity', payment_iban TEXT, payment_variable_symbol TEXT
+             );
+             CREATE TABLE real_estate_one_time_costs (
+                 id TEXT PRIMARY KEY, real_estate_id TEXT NOT NULL, name TEXT NOT NULL,
+                 amount TEXT NOT NULL, currency TEXT NOT NULL DEFAULT 'CZK', date INTEGER NOT NULL
+             );
+             CREATE TABLE insurance_policies (
+                 id TEXT PRIMARY KEY, regular_payment TEXT NOT NULL DEFAULT '0',
+                 regular_payment_currency TEXT NOT NULL DEFAULT 'CZK',
+                 payment_frequency TEXT NOT NULL, status TEXT NOT NULL DEFAULT 'active'
+             );
+             CREATE TABLE real_estate_insurances (real_estate_id TEXT NOT NULL, insurance_id TEXT NOT NULL);
+             CREATE TABLE bank_transactions (
+                 id TEXT PRIMARY KEY, bank_account_id TEXT NOT NULL, transaction_id TEXT,
+                 tx_type TEXT NOT NULL, amount TEXT NOT NULL, currency TEXT NOT NULL,
+                 description TEXT, counterparty_name TEXT, counterparty_iban TEXT,
+                 booking_date INTEGER NOT NULL, value_date INTEGER, category_id TEXT,
+                 merchant_category_code TEXT, remittance_info TEXT, variable_symbol TEXT,
+                 status TEXT NOT NULL DEFAULT 'booked', data_source TEXT NOT NULL DEFAULT 'manual',
+                 created_at INTEGER NOT NULL DEFAULT 0
+             );
+             CREATE TABLE real_estate_transactions (
+                 transaction_id TEXT PRIMARY KEY, real_estate_id TEXT NOT NULL
+             );
+             INSERT INTO real_estate (id, name, address, type, purchase_price, market_price, purchase_date, created_at)
+             VALUES ('flat', 'Flat', 'Prague', 'investment', '4000000', '4000000', 1577836800, 1577836800);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn valuation(date: i64, value: &str, source: &str) -> InsertRealEstateValuation {
+        InsertRealEstateValuation {
+            real_estate_id: "flat".into(),
+            valuation_date: date,
+            value: value.into(),
+            currency: None,
+            source: Some(source.into()),
+            notes: None,
+        }
+    }
+
+    fn market_price(conn: &Connection) -> String {
+        get_property(conn, "flat").unwrap().unwrap().market_price
+    }
+
+    #[test]
+    fn test_latest_valuation_sets_market_price() {
+        let conn = setup_test_db();
+        let latest = add_valuation(&conn, &valuation(REVALUED, "5000000", "appraisal")).unwrap();
+        assert_eq!(market_price(&conn), "5000000");
+
+        // An older valuation does not override the latest one
+        add_valuation(&conn, &valuation(PURCHASED + 86400, "4100000", "index")).unwrap();
+        assert_eq!(market_price(&conn), "5000000");
+
+        delete_valuation(&conn, &latest.id).unwrap();
+        assert_eq!(market_price(&conn), "4100000");
+        assert_eq!(get_valuations(&conn, "flat").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_value_on_uses_purchase_price_then_valuations() {
+        let conn = setup_test_db();
+        add_valuation(&conn, &valuation(REVALUED, "5000000", "listing")).unwrap();
+        let property = get_property(&conn, "flat").unwrap().unwrap();
+        let valuations = get_valuations(&conn, "flat").unwrap();
+
+        assert_eq!(
+            value_on(&property, &valuations, PURCHASED - 1),
+            Decimal::ZERO
+        );
+        assert_eq!(
+            value_on(&property, &valuations, REVALUED - 1),
+            Decimal::from(4_000_000)
+        );
+        assert_eq!(
+            value_on(&property, &valuations, NOW),
+            Decimal::from(5_000_000)
+        );
+    }
+
+    #[test]
+    fn test_market_price_edit_keeps_previous_price() {
+        let conn = setup_test_db();
+        conn.execute("UPDATE real_estate SET purchase_date = NULL", [])
+            .unwrap();
+        let property = get_property(&conn, "flat").unwrap().unwrap();
+        record_market_price(&conn, &property, "4500000", "CZK", REVALUED).unwrap();
+
+        let property = get_property(&conn, "flat").unwrap().unwrap();
+        let valuations = get_valuations(&conn, "flat").unwrap();
+        assert_eq!(valuations.len(), 2);
+        assert_eq!(
+            value_on(&property, &valuations, REVALUED - 1),
+            Decimal::from(4_000_000)
+        );
+        assert_eq!(
+            value_on(&property, &valuations, REVALUED),
+            Decimal::from(4_500_000)
+        );
+
+        // Later edits only add the new price
+        record_market_price(&conn, &property, "4600000", "CZK", NOW).unwrap();
+        assert_eq!(get_valuations(&conn, "flat").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_value_history_equity_and_annualized_appreciation() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO loans (id, name, principal, interest_rate, monthly_payment, start_date, repayment_type)
+             VALUES ('mortgage', 'Mortgage', '3000000', '0', '10000', 1577836800, 'linear');
+             INSERT INTO real_estate_loans VALUES ('flat', 'mortgage');",
+        )
+        .unwrap();
+        // Bank transactions are not needed without a payment IBAN or variable symbol
+        add_valuation(&conn, &valuation(NOW, "5000000", "appraisal")).unwrap();
+
+        let history = value_history(&conn, "flat", NOW).unwrap();
+        assert_eq!(history.purchase_price, "4000000.00");
+        assert_eq!(history.current_value, "5000000.00");
+        assert_eq!(history.appreciation, "1000000.00");
+        assert_eq!(history.appreciation_percent, Some(25.0));
+        // 25 % over five years is about 4.56 % a year
+        let annualized = history.annualized_appreciation_percent.unwrap();
+        assert!((annualized - 4.56).abs() < 0.01, "{}", annualized);
+
+        // Monthly points from the purchase to now, first and last exact
+        assert_eq!(history.points.first().unwrap().date, PURCHASED);
+        assert_eq!(history.points.last().unwrap().date, NOW);
+        assert_eq!(history.points.len(), 61);
+        // Sixty linear installments of 10 000 repaid by now
+        let last = history.points.last().unwrap();
+        assert_eq!(last.value, 5_000_000.0);
+        assert_eq!(last.loan_balance, 2_400_000.0);
+        assert_eq!(last.equity, 2_600_000.0);
+        assert_eq!(history.equity, "2600000.00");
+        assert_eq!(history.points[0].equity, 1_000_000.0);
+    }
+
+    /// Rent of 20 000 a month, fees of 3 000 a month, a yearly premium of
+    /// 4 000, a renovation of 200 000 and a current value of 5 000 000
+    fn setup_rental(conn: &Connection) {
+        conn.execute_batch(
+            r#"UPDATE real_estate SET monthly_rent = '20000',
+                   recurring_costs = '[{"name":"Fees","amount":3000.0,"frequency":"monthly"}]'
+               WHERE id = 'flat';
+               INSERT INTO insurance_policies (id, regular_payment, payment_frequency)
+               VALUES ('home', '4000', 'yearly');
+               INSERT INTO real_estate_insurances VALUES ('flat', 'home');
+               INSERT INTO real_estate_one_time_costs (id, real_estate_id, name, amount, date)
+               VALUES ('kitchen', 'flat', 'Kitchen', '200000', 1600000000);"#,
+        )
+        .unwrap();
+        add_valuation(conn, &valuation(NOW, "5000000", "appraisal")).unwrap();
+    }
+
+    fn flat_performance(conn: &Connection) -> RentalPerformance {
+        let property = get_property(conn, "flat").unwrap().unwrap();
+        performance(conn, &property, NOW).unwrap()
+    }
+
+    fn assert_percent(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_performance_from_property_terms() {
+        let conn = setup_test_db();
+        setup_rental(&conn);
+
+        let perf = flat_performance(&conn);
+        assert_eq!(perf.annual_rent, "240000.00");
+        assert_eq!(perf.rent_source, PerformanceSource::Estimated);
+        assert_eq!(perf.operating_costs, "40000.00");
+        assert_eq!(perf.costs_source, PerformanceSource::Estimated);
+        assert_eq!(perf.net_operating_income, "200000.00");
+        assert_eq!(perf.debt_service, "0.00");
+        assert_eq!(perf.annual_cash_flow, "200000.00");
+        assert_eq!(perf.total_cost, "4200000.00");
+        assert_eq!(perf.cash_invested, "4200000.00");
+
+        assert_percent(perf.gross_yield_percent, 5.714);
+        assert_percent(perf.net_yield_percent, 4.762);
+        assert_percent(perf.cap_rate_percent, 4.0);
+        assert_percent(perf.cash_on_cash_percent, 4.762);
+        // Value 800 000 above the total cost and sixty months of 200 000 a year
+        assert_eq!(perf.cumulative_cash_flow, "1000000.00");
+        assert_percent(perf.total_roi_percent, 42.857);
+
+        assert_eq!(perf.break_even_monthly_rent, "3333.33");
+        assert_percent(perf.break_even_occupancy_percent, 16.667);
+        assert_percent(perf.payback_years, 21.0);
+    }
+
+    #[test]
+    fn test_performance_combines_linked_transactions_and_terms() {
+        let conn = setup_test_db();
+        setup_rental(&conn);
+        conn.execute_batch(
+            "INSERT INTO bank_transactions (id, bank_account_id, tx_type, amount, currency, booking_date) VALUES
+                 ('rent-jun', 'acc', 'credit', '25000', 'CZK', 1717200000),
+                 ('rent-jul', 'acc', 'credit', '25000', 'CZK', 1719792000),
+                 ('repair', 'acc', 'debit', '-5000', 'CZK', 1722470400),
+                 ('rent-2023', 'acc', 'credit', '25000', 'CZK', 1685577600);",
+        )
+        .unwrap();
+        let ids: Vec<String> = ["rent-jun", "rent-jul", "repair", "rent-2023"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        link_transactions(&conn, "flat", &ids).unwrap();
+        assert_eq!(get_linked_transactions(&conn, "flat").unwrap().len(), 4);
+
+        // Two months of the trailing year paid 25 000, the other ten 20 000 by
+        // the terms; the repair replaces the estimated costs of its month
+        let perf = flat_performance(&conn);
+        assert_eq!(perf.annual_rent, "250000.00");
+        assert_eq!(perf.rent_source, PerformanceSource::Mixed);
+        assert_eq!(perf.operating_costs, "41666.67");
+        assert_eq!(perf.costs_source, PerformanceSource::Mixed);
+        // Three of the sixty months held paid 25 000, one cost 5 000
+        assert_eq!(perf.cumulative_cash_flow, "1013333.33");
+
+        unlink_transaction(&conn, "flat", "repair").unwrap();
+        let perf = flat_performance(&conn);
+        assert_eq!(perf.operating_costs, "40000.00");
+        assert_eq!(perf.costs_source, PerformanceSource::Estimated);
+    }
+
+    #[test]
+    fn test_performance_counts_linked_payments_once() {
+        let conn = setup_test_db();
+        setup_rental(&conn);
+        conn.execute_batch(
+            "INSERT INTO loans (id, name, principal, interest_rate, monthly_payment, start_date, repayment_type, payment_iban)
+             VALUES ('mortgage', 'Mortgage', '3000000', '0', '10000', 1577836800, 'linear', 'CZ1100000000001234567890');
+             INSERT INTO real_estate_loans VALUES ('flat', 'mortgage');
+             INSERT INTO bank_transactions (id, bank_account_id, tx_type, amount, currency, booking_date, counterparty_iban) VALUES
+                 ('fees-sep', 'acc', 'debit', '-3000', 'CZK', 1725148800, NULL),
+                 ('installment-oct', 'acc', 'debit', '-10000', 'CZK', 1727740800, 'CZ1100000000001234567890');",
+        )
+        .unwrap();
+        let ids = vec!["fees-sep".to_string(), "installment-oct".to_string()];
+        link_transactions(&conn, "flat", &ids).unwrap();
+
+        // The fees paid in September stand in for that month's estimate, and the
+        // installment counts in the debt service only
+        let perf = flat_performance(&conn);
+        assert_eq!(perf.operating_costs, "39666.67");
+        assert_eq!(perf.costs_source, PerformanceSource::Mixed);
+        assert_eq!(perf.debt_service, "120000.00");
+        assert_eq!(perf.annual_cash_flow, "80333.33");
+    }
+
+    #[test]
+    fn test_performance_with_linked_loan() {
+        let conn = setup_test_db();
+        setup_rental(&conn);
+        conn.execute_batch(
+            "INSERT INTO loans (id, name, principal, interest_rate, monthly_payment, start_date, repayment_type)
+             VALUES ('mortgage', 'Mortgage', '3000000', '0', '10000', 1577836800, 'linear');
+             INSERT INTO real_estate_loans VALUES ('flat', 'mortgage');
+             INSERT INTO real_estate (id, name, address, type) VALUES ('home', 'Home', 'Brno', 'personal');",
+        )
+        .unwrap();
+
+        let perf = flat_performance(&conn);
+        // Twelve scheduled installments, none matched in bank transactions
+        assert_eq!(perf.debt_service, "120000.00");
+        assert_eq!(perf.debt_source, PerformanceSource::Estimated);
+        assert_eq!(perf.annual_cash_flow, "80000.00");
+        assert_eq!(perf.cash_invested, "1200000.00");
+        assert_eq!(perf.equity, "2600000.00");
+        assert_percent(perf.cash_on_cash_percent, 6.667);
+        assert_eq!(perf.break_even_monthly_rent, "13333.33");
+
+        // Personal residences are left out
+        let all = get_performance(&conn, NOW).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].real_estate_id, "flat");
+    }
+}
@@ -0,0 +1,201 @@
+//! Reporting currency
+//!
+//! Everything is computed and stored in the base currency (CZK); snapshots and
+//! value history stay in CZK so they do not depend on the display setting. The
+//! reporting currency is the profile currency. Current aggregates are expressed
+//! in it at the current rate, dated values at the rate of their own day, so
+//! switching the reporting currency re-expresses past values at the rates of
+//! that time instead of today's.
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{ExposureHistoryPoint, PortfolioMetricsHistory, TickerValueHistory};
+use crate::services::exchange_rate_history::{ensure_history, HistoricalRates};
+use crate::services::exchange_rates;
+use crate::services::money::{self, Decimal};
+use rusqlite::Connection;
+
+/// Currency all amounts are computed and stored in
+pub const BASE_CURRENCY: &str = "CZK";
+
+/// Reporting currency from the user profile (base currency if not set)
+pub fn reporting_currency(conn: &Connection) -> String {
+    conn.query_row("SELECT currency FROM user_profile LIMIT 1", [], |row| {
+        row.get::<_, String>(0)
+    })
+    .ok()
+    .map(|c| c.trim().to_uppercase())
+    .filter(|c| !c.is_empty())
+    .unwrap_or_else(|| BASE_CURRENCY.to_string())
+}
+
+/// Converts base currency amounts to the reporting currency
+#[derive(Debug)]
+pub struct Reporting {
+    pub currency: String,
+    rates: HistoricalRates,
+}
+
+impl Reporting {
+    /// Load the reporting currency and its stored daily rates from the valuation source
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let currency = reporting_currency(conn);
+        let rates = if currency == BASE_CURRENCY {
+            HistoricalRates::default()
+        } else {
+            let source = exchange_rates::get_settings(conn)?.valuation_source;
+            HistoricalRates::load_currency(conn, &currency, source)?
+        };
+        Ok(Self { currency, rates })
+    }
+
+    pub fn is_base(&self) -> bool {
+        self.currency == BASE_CURRENCY
+    }
+
+    /// Amount in the reporting currency at the current rate
+    pub fn amount(&self, czk: f64) -> f64 {
+        if self.is_base() {
+            return czk;
+        }
+        crate::services::currency::convert_from_czk(czk, &self.currency)
+    }
+
+    /// Amount in the reporting currency at the rate of a day
+    pub fn amount_on(&self, czk: f64, day: i64) -> f64 {
+        if self.is_base() {
+            return czk;
+        }
+        self.rates.from_czk(czk, &self.currency, day)
+    }
+
+    /// Decimal amount in the reporting currency at the rate of a day (unrounded)
+    pub fn amount_decimal_on(&self, czk: Decimal, day: i64) -> Decimal {
+        if self.is_base() {
+            return czk;
+        }
+        self.rates.from_czk_decimal(czk, &self.currency, day)
+    }
+
+    /// Decimal string amount converted at the rate of a day; unparsable values are kept
+    pub fn amount_str_on(&self, czk: &str, day: i64) -> String {
+        if self.is_base() {
+            return czk.to_string();
+        }
+        match money::parse(czk) {
+            Some(value) => money::format(self.amount_decimal_on(value, day), &self.currency),
+            None => czk.to_string(),
+        }
+    }
+
+    /// Express a portfolio snapshot in the reporting currency at the rate of its day
+    pub fn convert_portfolio_history(&self, entry: &mut PortfolioMetricsHistory) {
+        let day = entry.recorded_at;
+        for value in [
+            &mut entry.total_savings,
+            &mut entry.total_loans_principal,
+            &mut entry.total_investments,
+            &mut entry.total_crypto,
+            &mut entry.total_bonds,
+            &mut entry.total_real_estate_personal,
+            &mut entry.total_real_estate_investment,
+            &mut entry.total_other_assets,
+        ] {
+            *value = self.amount_str_on(value, day);
+        }
+    }
+
+    /// Express a per-ticker value in the reporting currency at the rate of its day
+    pub fn convert_ticker_history(&self, entry: &mut TickerValueHistory) {
+        entry.value_czk = self.amount_str_on(&entry.value_czk, entry.recorded_at);
+    }
+
+    /// Express an exposure history point in the reporting currency at the rate of its day
+    pub fn convert_exposure_history(&self, point: &mut ExposureHistoryPoint) {
+        point.total_value = self.amount_on(point.total_value, point.date);
+        for item in &mut point.items {
+            item.value = self.amount_on(item.value, point.date);
+        }
+    }
+}
+
+/// Download the daily rates needed to show the whole portfolio history in the
+/// reporting currency (e.g. after it was changed). Failures are logged; missing
+/// days then use the current rate.
+pub async fn ensure_reporting_history(db: &Database) -> Result<()> {
+    let (currency, first_snapshot, source) = db.with_conn(|conn| {
+        let first: Option<i64> = conn.query_row(
+            "SELECT MIN(recorded_at) FROM portfolio_metrics_history",
+            [],
+            |row| row.get(0),
+        )?;
+        let source = exchange_rates::get_settings(conn)?.valuation_source;
+        Ok((reporting_currency(conn), first, source))
+    })?;
+    let Some(start) = first_snapshot else {
+        return Ok(());
+    };
+    if currency == BASE_CURRENCY {
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = ensure_history(db, start, now, source).await {
+        log::warn!("[REPORTING] Rate history for {} failed: {}", currency, e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE user_profile (id TEXT PRIMARY KEY, currency TEXT);
+             CREATE TABLE app_config (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE exchange_rate_history (
+                currency TEXT NOT NULL,
+                rate_date INTEGER NOT NULL,
+                rate REAL NOT NULL,
+                source TEXT NOT NULL,
+                PRIMARY KEY (currency, rate_date, source)
+             );",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_base_currency_without_profile() {
+        let conn = setup_test_db();
+        let reporting = Reporting::load(&conn).unwrap();
+
+        assert_eq!(reporting.currency, "CZK");
+        assert_eq!(reporting.amount_on(1234.5, 0), 1234.5);
+        assert_eq!(reporting.amount_str_on("10.123", 0), "10.123");
+    }
+
+    #[test]
+    fn test_dated_amounts_use_the_rate_of_their_day() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO user_profile (id, currency) VALUES ('u', 'eur');
+             INSERT INTO exchange_rate_history VALUES ('EUR', 1704067200, 25.0, 'ecb');
+             INSERT INTO exchange_rate_history VALUES ('EUR', 1719792000, 25.0, 'cnb');
+             INSERT INTO exchange_rate_history VALUES ('EUR', 1719792000, 24.0, 'ecb');",
+        )
+        .unwrap();
+        let reporting = Reporting::load(&conn).unwrap();
+
+        assert_eq!(reporting.currency, "EUR");
+        // 2024-01-01 and the weekend after it
+        assert_eq!(reporting.amount_on(2500.0, 1_704_067_200), 100.0);
+        assert_eq!(reporting.amount_on(2500.0, 1_704_240_000), 100.0);
+        // 2024-07-01, ECB preferred by default
+        assert_eq!(reporting.amount_str_on("2400", 1_719_792_000), "100.00");
+        assert_eq!(reporting.amount_str_on("2400.12", 1_719_792_000), "100.01");
+        assert_eq!(reporting.amount_str_on("n/a", 1_719_792_000), "n/a");
+    }
+}
@@ -0,0 +1,553 @@
+//! Risk metrics service
+//!
+//! Daily returns are chain-linked from the per-ticker value history tables the
+//! same way as `investments::compute_twr_for_tickers`: quantity changes between
+//! two days are treated as cash flows, so buying or selling is not a gain or
+//! loss. Annualization uses the observed number of returns per year, because
+//! snapshots exist on calendar days including weekends. The portfolio covers
+//! stocks and crypto, the asset classes with per-ticker history. Values and
+//! benchmark prices are taken in the reporting currency at the rate of their
+//! day, so returns are the ones measured in that currency.
+
+use crate::error::{AppError, Result};
+use crate::models::{RiskMetrics, RiskScope};
+use crate::services::reporting::Reporting;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// app_config key of the annual risk-free rate in percent
+const RISK_FREE_RATE_KEY: &str = "risk_free_rate";
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 86400.0;
+
+/// Minimum number of returns for volatility-based metrics
+const MIN_OBSERVATIONS: usize = 3;
+
+/// Day -> (value in the reporting currency, quantity) of one ticker
+type Series = BTreeMap<i64, (f64, f64)>;
+
+/// One chain-linked return: (previous day, day, return as fraction)
+type DailyReturn = (i64, i64, f64);
+
+/// Get the annual risk-free rate in percent (defaults to 0)
+pub fn get_risk_free_rate(conn: &rusqlite::Connection) -> f64 {
+    conn.query_row(
+        "SELECT value FROM app_config WHERE key = ?1",
+        [RISK_FREE_RATE_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(0.0)
+}
+
+/// Save the annual risk-free rate in percent
+pub fn set_risk_free_rate(conn: &rusqlite::Connection, rate: f64) -> Result<()> {
+    if !rate.is_finite() || !(-10.0..=100.0).contains(&rate) {
+        return Err(AppError::Validation(
+            "validation.riskFreeRateInvalid".into(),
+        ));
+    }
+    conn.execute(
+        "INSERT INTO app_config (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [RISK_FREE_RATE_KEY, &rate.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Load per-ticker history from `stock_value_history` or `crypto_value_history`
+fn load_series(
+    conn: &rusqlite::Connection,
+    reporting: &Reporting,
+    table: &str,
+    from_ts: Option<i64>,
+    to_ts: i64,
+) -> Result<HashMap<String, Series>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT ticker, recorded_at, CAST(value_czk AS REAL), CAST(quantity AS REAL)
+         FROM {table}
+         WHERE (?1 IS NULL OR recorded_at >= ?1) AND recorded_at <= ?2
+         ORDER BY recorded_at"
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![from_ts, to_ts], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, f64>(2)?,
+            row.get::<_, f64>(3)?,
+        ))
+    })?;
+
+    let mut series: HashMap<String, Series> = HashMap::new();
+    for row in rows {
+        let (ticker, day, value, quantity) = row?;
+        series
+            .entry(ticker)
+            .or_default()
+            .insert(day, (reporting.amount_on(value, day), quantity));
+    }
+    Ok(series)
+}
+
+/// Chain-linked daily returns of a group of tickers; missing days carry the last value
+fn group_returns(members: &[&Series]) -> Vec<DailyReturn> {
+    let days: BTreeSet<i64> = members.iter().flat_map(|s| s.keys().copied()).collect();
+    let mut last: Vec<(f64, f64)> = vec![(0.0, 0.0); members.len()];
+    let mut returns = Vec::new();
+    let mut prev_day: Option<i64> = None;
+
+    for day in days {
+        let mut v_prev = 0.0;
+        let mut v_curr = 0.0;
+        let mut cash_flow = 0.0;
+
+        for (i, series) in members.iter().enumerate() {
+            let (val_prev, qty_prev) = last[i];
+            let (val_curr, qty_curr) = series.get(&day).copied().unwrap_or(last[i]);
+            v_prev += val_prev;
+            v_curr += val_curr;
+
+            let delta_qty = qty_curr - qty_prev;
+            if delta_qty.abs() > 1e-9 {
+                let price = if qty_curr > 1e-9 {
+                    val_curr / qty_curr
+                } else if qty_prev > 1e-9 {
+                    val_prev / qty_prev
+                } else {
+                    0.0
+                };
+                cash_flow += delta_qty * price;
+            }
+            last[i] = (val_curr, qty_curr);
+        }
+
+        if let Some(prev) = prev_day {
+            let denominator = v_prev + cash_flow;
+            if denominator > 1e-9 {
+                returns.push((prev, day, (v_curr - v_prev - cash_flow) / denominator));
+            }
+        }
+        prev_day = Some(day);
+    }
+
+    returns
+}
+
+/// Daily prices in the reporting currency of a benchmark ticker: backfilled
+/// benchmark prices, else the stock value history of a held ticker
+fn benchmark_prices(
+    conn: &rusqlite::Connection,
+    reporting: &Reporting,
+    ticker: &str,
+) -> Result<BTreeMap<i64, f64>> {
+    let prices = crate::services::benchmarks::load_prices_czk(conn, ticker)?;
+    if !prices.is_empty() {
+        return Ok(prices
+            .into_iter()
+            .map(|(day, price)| (day, reporting.amount_on(price, day)))
+            .collect());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT recorded_at, CAST(value_czk AS REAL), CAST(quantity AS REAL)
+         FROM stock_value_history WHERE ticker = ?1",
+    )?;
+    let prices = stmt
+        .query_map([ticker.to_uppercase()], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, f64>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .filter(|(_, _, qty)| *qty > 1e-9)
+        .map(|(day, value, qty)| (day, reporting.amount_on(value / qty, day)))
+        .collect();
+    Ok(prices)
+}
+
+/// Last known price at or before `day`
+fn price_at(prices: &BTreeMap<i64, f64>, day: i64) -> Option<f64> {
+    prices.range(..=day).next_back().map(|(_, p)| *p)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Sample standard deviation
+fn std_dev(values: &[f64]) -> f64 {
+    let m = mean(values);
+    let var = values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    var.sqrt()
+}
+
+/// Beta of `returns` against the benchmark price changes over the same intervals
+fn beta(returns: &[DailyReturn], prices: &BTreeMap<i64, f64>) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = returns
+        .iter()
+        .filter_map(|(prev, day, r)| {
+            let p0 = price_at(prices, *prev)?;
+            let p1 = price_at(prices, *day)?;
+            (p0 > 0.0).then(|| (*r, p1 / p0 - 1.0))
+        })
+        .collect();
+    if pairs.len() < MIN_OBSERVATIONS {
+        return None;
+    }
+
+    let mean_p = pairs.iter().map(|(p, _)| p).sum::<f64>() / pairs.len() as f64;
+    let mean_b = pairs.iter().map(|(_, b)| b).sum::<f64>() / pairs.len() as f64;
+    let covariance: f64 = pairs.iter().map(|(p, b)| (p - mean_p) * (b - mean_b)).sum();
+    let variance: f64 = pairs.iter().map(|(_, b)| (b - mean_b).powi(2)).sum();
+    (variance > 1e-18).then(|| covariance / variance)
+}
+
+/// Compute metrics from chain-linked returns. `risk_free_rate` is annual, in percent.
+pub fn compute_metrics(
+    scope: RiskScope,
+    id: &str,
+    name: &str,
+    asset_class: Option<&str>,
+    returns: &[DailyReturn],
+    risk_free_rate: f64,
+    benchmark: Option<(&str, &BTreeMap<i64, f64>)>,
+) -> RiskMetrics {
+    let mut metrics = RiskMetrics {
+        scope,
+        id: id.to_string(),
+        name: name.to_string(),
+        asset_class: asset_class.map(str::to_string),
+        observations: returns.len() as i32,
+        start_date: returns.first().map(|(prev, _, _)| *prev),
+        end_date: returns.last().map(|(_, day, _)| *day),
+        annualized_return: None,
+        volatility: None,
+        max_drawdown: None,
+        drawdown_peak_date: None,
+        drawdown_trough_date: None,
+        drawdown_recovery_date: None,
+        sharpe_ratio: None,
+        sortino_ratio: None,
+        beta: None,
+        benchmark: benchmark.map(|(ticker, _)| ticker.to_string()),
+        risk_free_rate,
+    };
+    let (Some(start), Some(end)) = (metrics.start_date, metrics.end_date) else {
+        return metrics;
+    };
+
+    // Drawdown on the cumulative index
+    let mut index = 1.0;
+    let mut peak = (1.0, start);
+    let mut worst: Option<(f64, i64, i64, f64)> = None; // (drawdown, peak date, trough date, peak index)
+    let mut recovery = None;
+    for (_, day, r) in returns {
+        index *= 1.0 + r;
+        if index >= peak.0 {
+            peak = (index, *day);
+            if recovery.is_none() && worst.is_some_and(|w| index >= w.3) {
+                recovery = Some(*day);
+            }
+        }
+        let drawdown = index / peak.0 - 1.0;
+        if worst.is_none_or(|w| drawdown < w.0) && drawdown < 0.0 {
+            worst = Some((drawdown, peak.1, *day, peak.0));
+            recovery = None;
+        }
+    }
+    match worst {
+        Some((drawdown, peak_date, trough_date, _)) => {
+            metrics.max_drawdown = Some(drawdown * 100.0);
+            metrics.drawdown_peak_date = Some(peak_date);
+            metrics.drawdown_trough_date = Some(trough_date);
+            metrics.drawdown_recovery_date = recovery;
+        }
+        None => metrics.max_drawdown = Some(0.0),
+    }
+
+    let years = (end - start) as f64 / SECONDS_PER_YEAR;
+    if years <= 0.0 || returns.len() < MIN_OBSERVATIONS {
+        return metrics;
+    }
+    let periods_per_year = returns.len() as f64 / years;
+    let annual_return = index.powf(1.0 / years) - 1.0;
+    metrics.annualized_return = Some(annual_return * 100.0);
+
+    let values: Vec<f64> = returns.iter().map(|(_, _, r)| *r).collect();
+    let volatility = std_dev(&values) * periods_per_year.sqrt();
+    metrics.volatility = Some(volatility * 100.0);
+
+    let rf = risk_free_rate / 100.0;
+    if volatility > 1e-12 {
+        metrics.sharpe_ratio = Some((annual_return - rf) / volatility);
+    }
+    let rf_period = (1.0 + rf).powf(1.0 / periods_per_year) - 1.0;
+    let downside = (values
+        .iter()
+        .map(|r| (r - rf_period).min(0.0).powi(2))
+        .sum::<f64>()
+        / values.len() as f64)
+        .sqrt()
+        * periods_per_year.sqrt();
+    if downside > 1e-12 {
+        metrics.sortino_ratio = Some((annual_return - rf) / downside);
+    }
+
+    if let Some((_, prices)) = benchmark {
+        metrics.beta = beta(returns, prices);
+    }
+
+    metrics
+}
+
+/// Compute risk metrics for the given scope over [from_ts, to_ts].
+///
+/// `from_ts` = None starts at the first recorded day; `to_ts` = None ends now.
+/// `risk_free_rate` = None uses the saved setting.
+pub fn compute_risk(
+    conn: &rusqlite::Connection,
+    scope: RiskScope,
+    benchmark: Option<&str>,
+    risk_free_rate: Option<f64>,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<RiskMetrics>> {
+    let to_ts = to_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    if from_ts.is_some_and(|from| from >= to_ts) {
+        return Err(AppError::Validation("validation.dateRangeInvalid".into()));
+    }
+    let risk_free_rate = risk_free_rate.unwrap_or_else(|| get_risk_free_rate(conn));
+    let reporting = Reporting::load(conn)?;
+
+    let benchmark = benchmark
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| benchmark_prices(conn, &reporting, t).map(|prices| (t.to_uppercase(), prices)))
+        .transpose()?;
+    let benchmark_ref = benchmark.as_ref().map(|(t, p)| (t.as_str(), p));
+
+    let stocks = load_series(conn, &reporting, "stock_value_history", from_ts, to_ts)?;
+    let crypto = load_series(conn, &reporting, "crypto_value_history", from_ts, to_ts)?;
+    let metrics = |id: &str, name: &str, class: Option<&str>, members: &[&Series]| {
+        compute_metrics(
+            scope,
+            id,
+            name,
+            class,
+            &group_returns(members),
+            risk_free_rate,
+            benchmark_ref,
+        )
+    };
+
+    let results = match scope {
+        RiskScope::Holding => {
+            let mut results = Vec::new();
+            let sources = [
+                (
+                    "stocks",
+                    &stocks,
+                    "SELECT ticker, company_name FROM stock_investments",
+                ),
+                (
+                    "crypto",
+                    &crypto,
+                    "SELECT ticker, name FROM crypto_investments",
+                ),
+            ];
+            for (class, series, sql) in sources {
+                let names: HashMap<String, String> = conn
+                    .prepare(sql)?
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .filter_map(|r| r.ok())
+                    .collect();
+                let mut tickers: Vec<&String> = series.keys().collect();
+                tickers.sort();
+                for ticker in tickers {
+                    let name = names.get(ticker).unwrap_or(ticker);
+                    results.push(metrics(ticker, name, Some(class), &[&series[ticker]]));
+                }
+            }
+            results
+        }
+        RiskScope::Tag => {
+            let mut tags: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+            let mut stmt = conn.prepare(
+                "SELECT st.id, st.name, si.ticker FROM stock_tags st
+                 LEFT JOIN stock_investment_tags sit ON sit.tag_id = st.id
+                 LEFT JOIN stock_investments si ON si.id = sit.investment_id
+                 ORDER BY st.name",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (tag_id, tag_name, ticker) = row?;
+                let entry = tags.entry(tag_id).or_insert((tag_name, Vec::new()));
+                entry.1.extend(ticker);
+            }
+
+            tags.iter()
+                .map(|(tag_id, (tag_name, tickers))| {
+                    let members: Vec<&Series> =
+                        tickers.iter().filter_map(|t| stocks.get(t)).collect();
+                    metrics(tag_id, tag_name, None, &members)
+                })
+                .collect()
+        }
+        RiskScope::AssetClass => vec![
+            metrics(
+                "stocks",
+                "Stocks",
+                None,
+                &stocks.values().collect::<Vec<_>>(),
+            ),
+            metrics(
+                "crypto",
+                "Crypto",
+                None,
+                &crypto.values().collect::<Vec<_>>(),
+            ),
+        ],
+        RiskScope::Portfolio => {
+            let all: Vec<&Series> = stocks.values().chain(crypto.values()).collect();
+            vec![metrics("portfolio", "Portfolio", None, &all)]
+        }
+    };
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    const DAY: i64 = 86400;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE app_config (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             CREATE TABLE stock_investments (id TEXT PRIMARY KEY, ticker TEXT, company_name TEXT);
+             CREATE TABLE crypto_investments (id TEXT PRIMARY KEY, ticker TEXT, name TEXT);
+             CREATE TABLE stock_tags (id TEXT PRIMARY KEY, name TEXT);
+             CREATE TABLE stock_investment_tags (investment_id TEXT, tag_id TEXT);
+             CREATE TABLE stock_value_history (ticker TEXT, recorded_at INTEGER, value_czk TEXT, quantity TEXT);
+             CREATE TABLE crypto_value_history (ticker TEXT, recorded_at INTEGER, value_czk TEXT, quantity TEXT);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn add_value(conn: &Connection, table: &str, ticker: &str, day: i64, value: f64, qty: f64) {
+        conn.execute(
+            &format!("INSERT INTO {table} VALUES (?1, ?2, ?3, ?4)"),
+            rusqlite::params![ticker, day * DAY, value.to_string(), qty.to_string()],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_buying_is_not_a_return() {
+        let mut series = Series::new();
+        series.insert(0, (100.0, 1.0));
+        series.insert(DAY, (220.0, 2.0)); // bought 1 more at 110, counted at the start of the day
+        series.insert(2 * DAY, (220.0, 2.0));
+        let returns = group_returns(&[&series]);
+
+        assert_eq!(returns.len(), 2);
+        assert!((returns[0].2 - 10.0 / 210.0).abs() < 1e-9);
+        assert!(returns[1].2.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drawdown_dates_and_recovery() {
+        let values = [100.0, 120.0, 90.0, 100.0, 130.0, 125.0];
+        let returns: Vec<DailyReturn> = values
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| (i as i64 * DAY, (i as i64 + 1) * DAY, w[1] / w[0] - 1.0))
+            .collect();
+        let m = compute_metrics(RiskScope::Portfolio, "p", "P", None, &returns, 0.0, None);
+
+        assert!((m.max_drawdown.unwrap() + 25.0).abs() < 1e-9);
+        assert_eq!(m.drawdown_peak_date, Some(DAY));
+        assert_eq!(m.drawdown_trough_date, Some(2 * DAY));
+        assert_eq!(m.drawdown_recovery_date, Some(4 * DAY));
+        assert!(m.volatility.unwrap() > 0.0);
+        assert!(m.sharpe_ratio.is_some() && m.sortino_ratio.is_some());
+    }
+
+    #[test]
+    fn test_beta_against_benchmark() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO stock_investments VALUES ('1', 'LEV', 'Leveraged'), ('2', 'IDX', 'Index');",
+        )
+        .unwrap();
+        let moves = [0.01, -0.02, 0.015, 0.005, -0.01, 0.02];
+        let (mut idx, mut lev) = (100.0, 100.0);
+        add_value(&conn, "stock_value_history", "IDX", 0, idx, 1.0);
+        add_value(&conn, "stock_value_history", "LEV", 0, lev, 1.0);
+        for (i, m) in moves.iter().enumerate() {
+            idx *= 1.0 + m;
+            lev *= 1.0 + 2.0 * m;
+            add_value(&conn, "stock_value_history", "IDX", i as i64 + 1, idx, 1.0);
+            add_value(&conn, "stock_value_history", "LEV", i as i64 + 1, lev, 1.0);
+        }
+
+        let results = compute_risk(
+            &conn,
+            RiskScope::Holding,
+            Some("idx"),
+            Some(2.0),
+            None,
+            Some(100 * DAY),
+        )
+        .unwrap();
+        let lev = results.iter().find(|r| r.id == "LEV").unwrap();
+        assert_eq!(lev.name, "Leveraged");
+        assert_eq!(lev.observations, 6);
+        assert!((lev.beta.unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(lev.benchmark.as_deref(), Some("IDX"));
+        assert!((lev.risk_free_rate - 2.0).abs() < 1e-9);
+        let idx = results.iter().find(|r| r.id == "IDX").unwrap();
+        assert!((idx.beta.unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_risk_free_rate_setting_and_scopes() {
+        let conn = setup_test_db();
+        assert_eq!(get_risk_free_rate(&conn), 0.0);
+        set_risk_free_rate(&conn, 3.5).unwrap();
+        assert!((get_risk_free_rate(&conn) - 3.5).abs() < 1e-9);
+        assert!(set_risk_free_rate(&conn, 500.0).is_err());
+
+        add_value(&conn, "crypto_value_history", "BTC", 0, 100.0, 1.0);
+        add_value(&conn, "crypto_value_history", "BTC", 1, 110.0, 1.0);
+        let results = compute_risk(
+            &conn,
+            RiskScope::AssetClass,
+            None,
+            None,
+            None,
+            Some(10 * DAY),
+        )
+        .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].observations, 0);
+        assert_eq!(results[1].observations, 1);
+        assert!((results[1].risk_free_rate - 3.5).abs() < 1e-9);
+        // Too few returns for volatility
+        assert!(results[1].volatility.is_none());
+
+        assert!(compute_risk(&conn, RiskScope::Portfolio, None, None, Some(5), Some(1)).is_err());
+    }
+}
@@ -0,0 +1,707 @@
+//! Money-weighted return (XIRR) service
+//!
+//! Complements the time-weighted return from `investments::compute_twr_for_tickers`.
+//! XIRR is solved from the actual cash flows (buys, sells, net dividends, broker fees),
+//! so it reflects the timing and size of contributions. When the range has a start,
+//! the value held on that day counts as the initial investment; the value at the end
+//! of the range counts as the final withdrawal. Values come from the per-ticker
+//! value history tables (zero once a holding is fully sold), cash flows are
+//! converted to CZK at the rate of their own day like the value history. Flows
+//! and values are then expressed in the reporting currency at the rate of their
+//! day, so the return is the one an investor in that currency earned.
+
+use crate::error::{AppError, Result};
+use crate::models::{XirrResult, XirrScope};
+use crate::services::exchange_rate_history::HistoricalRates;
+use crate::services::exchange_rates;
+use crate::services::money::{self, Decimal};
+use crate::services::reporting::Reporting;
+use std::collections::BTreeMap;
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 86400.0;
+
+/// Solve the annual rate at which the net present value of the cash flows is zero.
+///
+/// Flows are `(unix timestamp, amount)`: negative = money put in, positive = money taken out.
+/// Returns the rate as a fraction (0.1 = 10 %), or None when there is no solution.
+pub fn xirr(flows: &[(i64, f64)]) -> Option<f64> {
+    if !flows.iter().any(|(_, a)| *a < 0.0) || !flows.iter().any(|(_, a)| *a > 0.0) {
+        return None;
+    }
+    let t0 = flows.iter().map(|(t, _)| *t).min()?;
+    let flows: Vec<(f64, f64)> = flows
+        .iter()
+        .map(|(t, a)| ((t - t0) as f64 / SECONDS_PER_YEAR, *a))
+        .collect();
+    let npv = |rate: f64| -> f64 { flows.iter().map(|(y, a)| a / (1.0 + rate).powf(*y)).sum() };
+    let npv_derivative = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .map(|(y, a)| -y * a / (1.0 + rate).powf(y + 1.0))
+            .sum()
+    };
+
+    // Newton-Raphson converges in a few steps for typical portfolios
+    let mut rate = 0.1;
+    for _ in 0..100 {
+        let derivative = npv_derivative(rate);
+        if derivative.abs() < 1e-12 {
+            break;
+        }
+        let next = rate - npv(rate) / derivative;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+        if (next - rate).abs() < 1e-10 {
+            return Some(next);
+        }
+        rate = next;
+    }
+
+    // Fall back to bisection when Newton diverges (e.g. very short ranges)
+    let mut low = -0.999_999;
+    let mut high = 10.0;
+    let mut npv_low = npv(low);
+    while npv_low.signum() == npv(high).signum() {
+        high *= 10.0;
+        if high > 1e9 {
+            return None;
+        }
+    }
+    for _ in 0..300 {
+        let mid = (low + high) / 2.0;
+        let npv_mid = npv(mid);
+        if npv_mid.abs() < 1e-9 || high - low < 1e-12 {
+            return Some(mid);
+        }
+        if npv_mid.signum() == npv_low.signum() {
+            low = mid;
+            npv_low = npv_mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+/// Dated cash flows as (unix timestamp, amount in the reporting currency)
+type CashFlows = Vec<(i64, f64)>;
+
+/// Cash flows within the range and range-boundary values of one holding
+struct HoldingFlows {
+    asset_class: &'static str,
+    ticker: String,
+    name: String,
+    flows: CashFlows,
+    start_value: f64,
+    end_value: f64,
+}
+
+/// Units of a ticker held at or before `ts` (strictly before when `exclusive`)
+fn quantity_on(
+    conn: &rusqlite::Connection,
+    asset_class: &str,
+    ticker: &str,
+    ts: i64,
+    exclusive: bool,
+) -> Decimal {
+    let table = if asset_class == "crypto" {
+        "crypto_transactions"
+    } else {
+        "investment_transactions"
+    };
+    let op = if exclusive { "<" } else { "<=" };
+    let Ok(mut stmt) = conn.prepare(&format!(
+        "SELECT LOWER(type), quantity FROM {table} WHERE ticker = ?1 AND transaction_date {op} ?2"
+    )) else {
+        return Decimal::ZERO;
+    };
+    let rows = match stmt.query_map(rusqlite::params![ticker, ts], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    }) {
+        Ok(rows) => rows,
+        Err(_) => return Decimal::ZERO,
+    };
+    rows.filter_map(|r| r.ok())
+        .map(|(tx_type, quantity)| match tx_type.as_str() {
+            "sell" => -money::parse_or_zero(&quantity),
+            "transfer_in" | "transfer_out" => Decimal::ZERO,
+            _ => money::parse_or_zero(&quantity),
+        })
+        .sum()
+}
+
+/// Value of a holding from its value history table at or before `ts` (strictly
+/// before when `exclusive`). No history row is written once a holding is sold,
+/// so a holding without units at that time is worth nothing.
+fn history_value(
+    conn: &rusqlite::Connection,
+    asset_class: &str,
+    ticker: &str,
+    ts: i64,
+    exclusive: bool,
+) -> f64 {
+    if quantity_on(conn, asset_class, ticker, ts, exclusive) <= Decimal::ZERO {
+        return 0.0;
+    }
+    let table = if asset_class == "crypto" {
+        "crypto_value_history"
+    } else {
+        "stock_value_history"
+    };
+    let op = if exclusive { "<" } else { "<=" };
+    conn.query_row(
+        &format!(
+            "SELECT value_czk FROM {table} WHERE ticker = ?1 AND recorded_at {op} ?2
+             ORDER BY recorded_at DESC LIMIT 1"
+        ),
+        rusqlite::params![ticker, ts],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|v| money::to_f64(money::parse_or_zero(&v)))
+    .unwrap_or(0.0)
+}
+
+/// Load cash flows of all stock and crypto holdings within [from_ts, to_ts].
+/// Also returns broker fees not tied to a ticker (attributed to stocks as a whole).
+fn load_holdings(
+    conn: &rusqlite::Connection,
+    reporting: &Reporting,
+    from_ts: Option<i64>,
+    to_ts: i64,
+) -> Result<(Vec<HoldingFlows>, CashFlows)> {
+    let from = from_ts.unwrap_or(i64::MIN);
+    let source = exchange_rates::get_settings(conn)?.valuation_source;
+    let first_flow: Option<i64> = conn
+        .query_row(
+            "SELECT MIN(d) FROM (
+                 SELECT MIN(transaction_date) AS d FROM investment_transactions
+                 UNION ALL SELECT MIN(transaction_date) FROM crypto_transactions
+                 UNION ALL SELECT MIN(payment_date) FROM stock_dividends
+                 UNION ALL SELECT MIN(occurred_at) FROM broker_cash_movements
+             )",
+            [],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+    let fx = HistoricalRates::load(conn, from_ts.or(first_flow).unwrap_or(to_ts), to_ts, source)?;
+    let convert = |amount: Decimal, currency: &str, date: i64| {
+        reporting.amount_on(
+            money::to_f64(fx.to_czk_decimal(amount, currency, date)),
+            date,
+        )
+    };
+    let mut holdings: BTreeMap<(&'static str, String), HoldingFlows> = BTreeMap::new();
+
+    // Holdings that still exist plus ones only present in the transaction history
+    let sources = [
+        (
+            "stocks",
+            "SELECT ticker, company_name FROM stock_investments
+             UNION SELECT ticker, MAX(company_name) FROM investment_transactions GROUP BY ticker",
+        ),
+        (
+            "crypto",
+            "SELECT ticker, name FROM crypto_investments
+             UNION SELECT ticker, MAX(name) FROM crypto_transactions GROUP BY ticker",
+        ),
+    ];
+    for (asset_class, sql) in sources {
+        let rows: Vec<(String, String)> = conn
+            .prepare(sql)?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        for (ticker, name) in rows {
+            holdings
+                .entry((asset_class, ticker.clone()))
+                .or_insert_with(|| HoldingFlows {
+                    asset_class,
+                    ticker,
+                    name,
+                    flows: Vec::new(),
+                    start_value: 0.0,
+                    end_value: 0.0,
+                });
+        }
+    }
+
+    // Trades: buys (and received gifts) put money in, sells take it out
+    let trades = [
+        (
+            "stocks",
+            "SELECT ticker, LOWER(type), quantity, price_per_unit, currency, transaction_date
+             FROM investment_transactions WHERE transaction_date >= ?1 AND transaction_date <= ?2",
+        ),
+        (
+            "crypto",
+            "SELECT ticker, LOWER(type), quantity, price_per_unit, currency, transaction_date
+             FROM crypto_transactions WHERE transaction_date >= ?1 AND transaction_date <= ?2",
+        ),
+    ];
+    for (asset_class, sql) in trades {
+        let rows: Vec<(String, String, String, String, String, i64)> = conn
+            .prepare(sql)?
+            .query_map([from, to_ts], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        for (ticker, tx_type, qty, price, currency, date) in rows {
+            let sign = match tx_type.as_str() {
+                "buy" | "gift" => -1.0,
+                "sell" => 1.0,
+                // Income and transfers are not external cash flows
+                _ => continue,
+            };
+            let amount = money::parse_or_zero(&qty) * money::parse_or_zero(&price);
+            if let Some(holding) = holdings.get_mut(&(asset_class, ticker)) {
+                holding
+                    .flows
+                    .push((date, sign * convert(amount, &currency, date)));
+            }
+        }
+    }
+
+    // Dividends net of the tax withheld at source
+    let dividends: Vec<(String, i64, String, String, String)> = conn
+        .prepare(
+            "SELECT ticker, payment_date, gross_amount, withholding_tax, currency
+             FROM stock_dividends WHERE payment_date >= ?1 AND payment_date <= ?2",
+        )?
+        .query_map([from, to_ts], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (ticker, date, gross, withholding, currency) in dividends {
+        let net = money::parse_or_zero(&gross) - money::parse_or_zero(&withholding);
+        if let Some(holding) = holdings.get_mut(&("stocks", ticker)) {
+            holding.flows.push((date, convert(net, &currency, date)));
+        }
+    }
+
+    // Broker fees are always money put in, whatever sign the statement used
+    let mut unattributed = Vec::new();
+    let fees: Vec<(Option<String>, String, String, i64)> = conn
+        .prepare(
+            "SELECT ticker, amount, currency, occurred_at FROM broker_cash_movements
+             WHERE kind = 'fee' AND occurred_at >= ?1 AND occurred_at <= ?2",
+        )?
+        .query_map([from, to_ts], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    for (ticker, amount, currency, date) in fees {
+        let flow = (
+            date,
+            -convert(money::parse_or_zero(&amount).abs(), &currency, date),
+        );
+        match ticker.and_then(|t| holdings.get_mut(&("stocks", t))) {
+            Some(holding) => holding.flows.push(flow),
+            None => unattributed.push(flow),
+        }
+    }
+
+    for holding in holdings.values_mut() {
+        let (class, ticker) = (holding.asset_class, holding.ticker.as_str());
+        if let Some(from) = from_ts {
+            holding.start_value =
+                reporting.amount_on(history_value(conn, class, ticker, from, true), from);
+        }
+        holding.end_value =
+            reporting.amount_on(history_value(conn, class, ticker, to_ts, false), to_ts);
+    }
+
+    Ok((holdings.into_values().collect(), unattributed))
+}
+
+/// Combine holdings into one XIRR result
+#[allow(clippy::too_many_arguments)]
+fn summarize(
+    scope: XirrScope,
+    id: &str,
+    name: &str,
+    asset_class: Option<&str>,
+    holdings: &[&HoldingFlows],
+    extra_flows: &[(i64, f64)],
+    from_ts: Option<i64>,
+    to_ts: i64,
+) -> XirrResult {
+    let mut flows: Vec<(i64, f64)> = holdings
+        .iter()
+        .flat_map(|h| h.flows.iter().copied())
+        .chain(extra_flows.iter().copied())
+        .collect();
+    let cash_flow_count = flows.len() as i32;
+    let invested: f64 = -flows.iter().map(|(_, a)| a.min(0.0)).sum::<f64>();
+    let withdrawn: f64 = flows.iter().map(|(_, a)| a.max(0.0)).sum();
+    let start_value: f64 = holdings.iter().map(|h| h.start_value).sum();
+    let end_value: f64 = holdings.iter().map(|h| h.end_value).sum();
+
+    if let Some(from) = from_ts.filter(|_| start_value > 0.0) {
+        flows.push((from, -start_value));
+    }
+    if end_value > 0.0 {
+        flows.push((to_ts, end_value));
+    }
+
+    XirrResult {
+        scope,
+        currency: String::new(),
+        id: id.to_string(),
+        name: name.to_string(),
+        asset_class: asset_class.map(|c| c.to_string()),
+        xirr: xirr(&flows).map(|r| r * 100.0),
+        start_value,
+        end_value,
+        invested,
+        withdrawn,
+        gain: end_value + withdrawn - start_value - invested,
+        cash_flow_count,
+    }
+}
+
+/// Compute money-weighted returns for the given scope over [from_ts, to_ts].
+///
+/// `from_ts` = None starts at the first cash flow; `to_ts` = None ends now.
+/// The portfolio covers stocks and crypto, the asset classes with dated cash flows.
+pub fn compute_xirr(
+    conn: &rusqlite::Connection,
+    scope: XirrScope,
+    from_ts: Option<i64>,
+    to_ts: Option<i64>,
+) -> Result<Vec<XirrResult>> {
+    let to_ts = to_ts.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    if from_ts.is_some_and(|from| from >= to_ts) {
+        return Err(AppError::Validation("validation.dateRangeInvalid".into()));
+    }
+
+    let reporting = Reporting::load(conn)?;
+    let (holdings, unattributed) = load_holdings(conn, &reporting, from_ts, to_ts)?;
+    let of_class = |class: &str| -> Vec<&HoldingFlows> {
+        holdings.iter().filter(|h| h.asset_class == class).collect()
+    };
+
+    let mut results: Vec<XirrResult> = match scope {
+        XirrScope::Holding => holdings
+            .iter()
+            .filter(|h| !h.flows.is_empty() || h.start_value > 0.0 || h.end_value > 0.0)
+            .map(|h| {
+                summarize(
+                    scope,
+                    &h.ticker,
+                    &h.name,
+                    Some(h.asset_class),
+                    &[h],
+                    &[],
+                    from_ts,
+                    to_ts,
+                )
+            })
+            .collect(),
+        XirrScope::Tag => {
+            let mut tags: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+            let mut stmt = conn.prepare(
+                "SELECT st.id, st.name, si.ticker FROM stock_tags st
+                 LEFT JOIN stock_investment_tags sit ON sit.tag_id = st.id
+                 LEFT JOIN stock_investments si ON si.id = sit.investment_id
+                 ORDER BY st.name",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?;
+            for row in rows {
+                let (tag_id, tag_name, ticker) = row?;
+                let entry = tags.entry(tag_id).or_insert((tag_name, Vec::new()));
+                entry.1.extend(ticker);
+            }
+
+            let stocks = of_class("stocks");
+            tags.iter()
+                .map(|(tag_id, (tag_name, tickers))| {
+                    let members: Vec<&HoldingFlows> = stocks
+                        .iter()
+                        .copied()
+                        .filter(|h| tickers.contains(&h.ticker))
+                        .collect();
+                    summarize(scope, tag_id, tag_name, None, &members, &[], from_ts, to_ts)
+                })
+                .collect()
+        }
+        XirrScope::AssetClass => vec![
+            summarize(
+                scope,
+                "stocks",
+                "Stocks",
+                None,
+                &of_class("stocks"),
+                &unattributed,
+                from_ts,
+                to_ts,
+            ),
+            summarize(
+                scope,
+                "crypto",
+                "Crypto",
+                None,
+                &of_class("crypto"),
+                &[],
+                from_ts,
+                to_ts,
+            ),
+        ],
+        XirrScope::Portfolio => {
+            let all: Vec<&HoldingFlows> = holdings.iter().collect();
+            vec![summarize(
+                scope,
+                "portfolio",
+                "Portfolio",
+                None,
+                &all,
+                &unattributed,
+                from_ts,
+                to_ts,
+            )]
+        }
+    };
+
+    for result in &mut results {
+        result.currency = reporting.currency.clone();
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    const DAY: i64 = 86400;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE stock_investments (id TEXT PRIMARY KEY, ticker TEXT, company_name TEXT);
+             CREATE TABLE investment_transactions (
+                 ticker TEXT, company_name TEXT, type TEXT, quantity TEXT,
+                 price_per_unit TEXT, currency TEXT, transaction_date INTEGER
+             );
+             CREATE TABLE crypto_investments (id TEXT PRIMARY KEY, ticker TEXT, name TEXT);
+             CREATE TABLE crypto_transactions (
+                 ticker TEXT, name TEXT, type TEXT, quantity TEXT,
+                 price_per_unit TEXT, currency TEXT, transaction_date INTEGER
+             );
+             CREATE TABLE stock_dividends (
+                 ticker TEXT, payment_date INTEGER, gross_amount TEXT,
+                 withholding_tax TEXT, currency TEXT
+             );
+             CREATE TABLE broker_cash_movements (
+                 kind TEXT, ticker TEXT, amount TEXT, currency TEXT, occurred_at INTEGER
+             );
+             CREATE TABLE stock_value_history (ticker TEXT, recorded_at INTEGER, value_czk TEXT);
+             CREATE TABLE crypto_value_history (ticker TEXT, recorded_at INTEGER, value_czk TEXT);
+             CREATE TABLE stock_tags (id TEXT PRIMARY KEY, name TEXT);
+             CREATE TABLE stock_investment_tags (investment_id TEXT, tag_id TEXT);
+             CREATE TABLE app_config (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE exchange_rate_history (
+                 currency TEXT, rate_date INTEGER, rate REAL, source TEXT
+             );",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn add_stock_tx(conn: &Connection, ticker: &str, tx_type: &str, amount: &str, date: i64) {
+        conn.execute(
+            "INSERT INTO investment_transactions VALUES (?1, ?1, ?2, '1', ?3, 'CZK', ?4)",
+            rusqlite::params![ticker, tx_type, amount, date],
+        )
+        .unwrap();
+    }
+
+    fn add_value(conn: &Connection, ticker: &str, date: i64, value: &str) {
+        conn.execute(
+            "INSERT INTO stock_value_history VALUES (?1, ?2, ?3)",
+            rusqlite::params![ticker, date, value],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_xirr_single_year_doubling() {
+        let flows = [(0, -1000.0), (365 * DAY, 2000.0)];
+        let rate = xirr(&flows).unwrap();
+        assert!((rate - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_xirr_requires_both_directions() {
+        assert!(xirr(&[(0, -1000.0), (DAY, -500.0)]).is_none());
+        assert!(xirr(&[]).is_none());
+    }
+
+    #[test]
+    fn test_xirr_negative_return() {
+        let flows = [(0, -1000.0), (365 * DAY, 800.0)];
+        let rate = xirr(&flows).unwrap();
+        assert!((rate + 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_contribution_timing_affects_xirr() {
+        // 1000 in at t0, another 1000 after half a year, 2300 at year end.
+        // A late contribution earns for a shorter time, so XIRR is above the simple 15 %.
+        let conn = setup_test_db();
+        add_stock_tx(&conn, "AAPL", "buy", "1000", 0);
+        add_stock_tx(&conn, "AAPL", "buy", "1000", 182 * DAY);
+        add_value(&conn, "AAPL", 365 * DAY, "2300");
+
+        let result = compute_xirr(&conn, XirrScope::Portfolio, None, Some(365 * DAY)).unwrap();
+        assert_eq!(result.len(), 1);
+        let portfolio = &result[0];
+        assert!((portfolio.invested - 2000.0).abs() < 1e-9);
+        assert!((portfolio.gain - 300.0).abs() < 1e-9);
+        let rate = portfolio.xirr.unwrap();
+        assert!(rate > 15.0 && rate < 21.0, "xirr was {rate}");
+    }
+
+    #[test]
+    fn test_range_start_value_and_dividends() {
+        let conn = setup_test_db();
+        add_stock_tx(&conn, "MSFT", "buy", "5000", 0);
+        add_value(&conn, "MSFT", 99 * DAY, "1000");
+        // Dividend of 120 with 20 withheld inside the range
+        conn.execute(
+            "INSERT INTO stock_dividends VALUES ('MSFT', ?1, '120', '20', 'CZK')",
+            [200 * DAY],
+        )
+        .unwrap();
+        add_value(&conn, "MSFT", 465 * DAY, "1000");
+
+        let result =
+            compute_xirr(&conn, XirrScope::Holding, Some(100 * DAY), Some(465 * DAY)).unwrap();
+        assert_eq!(result.len(), 1);
+        let msft = &result[0];
+        // The purchase before the range is replaced by the starting value
+        assert!((msft.start_value - 1000.0).abs() < 1e-9);
+        assert!((msft.invested - 0.0).abs() < 1e-9);
+        assert!((msft.withdrawn - 100.0).abs() < 1e-9);
+        let rate = msft.xirr.unwrap();
+        assert!(rate > 10.0 && rate < 11.0, "xirr was {rate}");
+    }
+
+    #[test]
+    fn test_tag_and_asset_class_scopes() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO stock_investments VALUES ('1', 'AAPL', 'Apple'), ('2', 'MSFT', 'Microsoft');
+             INSERT INTO stock_tags VALUES ('t1', 'Growth'), ('t2', 'Empty');
+             INSERT INTO stock_investment_tags VALUES ('1', 't1');
+             INSERT INTO crypto_transactions VALUES ('BTC', 'Bitcoin', 'buy', '1', '1000', 'CZK', 0);
+             INSERT INTO crypto_transactions VALUES ('BTC', 'Bitcoin', 'staking', '1', '1000', 'CZK', 10);
+             INSERT INTO crypto_value_history VALUES ('BTC', 31536000, '3000');
+             INSERT INTO broker_cash_movements VALUES ('fee', NULL, '-10', 'CZK', 5);",
+        )
+        .unwrap();
+        add_stock_tx(&conn, "AAPL", "buy", "1000", 0);
+        add_stock_tx(&conn, "MSFT", "buy", "1000", 0);
+        add_value(&conn, "AAPL", 365 * DAY, "1100");
+        add_value(&conn, "MSFT", 365 * DAY, "900");
+
+        let tags = compute_xirr(&conn, XirrScope::Tag, None, Some(365 * DAY)).unwrap();
+        assert_eq!(tags.len(), 2);
+        let growth = tags.iter().find(|t| t.id == "t1").unwrap();
+        assert!((growth.xirr.unwrap() - 10.0).abs() < 1e-4);
+        assert!(tags.iter().find(|t| t.id == "t2").unwrap().xirr.is_none());
+
+        let classes = compute_xirr(&conn, XirrScope::AssetClass, None, Some(365 * DAY)).unwrap();
+        let stocks = classes.iter().find(|c| c.id == "stocks").unwrap();
+        // Unattributed broker fee counts against stocks
+        assert!((stocks.invested - 2010.0).abs() < 1e-9);
+        let crypto = classes.iter().find(|c| c.id == "crypto").unwrap();
+        // Staking income is not a contribution
+        assert!((crypto.invested - 1000.0).abs() < 1e-9);
+        assert!((crypto.xirr.unwrap() - 200.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_sold_holding_has_no_end_value() {
+        let conn = setup_test_db();
+        add_stock_tx(&conn, "AAPL", "buy", "1000", 0);
+        add_value(&conn, "AAPL", 100 * DAY, "1200");
+        add_stock_tx(&conn, "AAPL", "sell", "1100", 200 * DAY);
+
+        let result = compute_xirr(&conn, XirrScope::Holding, None, Some(365 * DAY)).unwrap();
+        let aapl = &result[0];
+        // The last history row before the sale is not a value still held
+        assert_eq!(aapl.end_value, 0.0);
+        assert!((aapl.gain - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_flows_use_rate_of_their_day() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "INSERT INTO exchange_rate_history VALUES ('USD', 0, 20.0, 'ecb');
+             INSERT INTO exchange_rate_history VALUES ('USD', 31536000, 25.0, 'ecb');
+             INSERT INTO investment_transactions
+                 VALUES ('SPY', 'SPY', 'buy', '1', '100', 'USD', 0);",
+        )
+        .unwrap();
+        add_value(&conn, "SPY", 365 * DAY, "2500");
+
+        let result = compute_xirr(&conn, XirrScope::Portfolio, None, Some(365 * DAY)).unwrap();
+        let portfolio = &result[0];
+        // Bought at 20 CZK/USD, valued at 25 CZK/USD: the FX gain shows in the return
+        assert!((portfolio.invested - 2000.0).abs() < 1e-9);
+        assert!((portfolio.xirr.unwrap() - 25.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_amounts_in_reporting_currency() {
+        let conn = setup_test_db();
+        conn.execute_batch(
+            "CREATE TABLE user_profile (currency TEXT);
+             INSERT INTO user_profile VALUES ('EUR');
+             INSERT INTO exchange_rate_history VALUES ('EUR', 0, 25.0, 'ecb');
+             INSERT INTO exchange_rate_history VALUES ('EUR', 31536000, 20.0, 'ecb');",
+        )
+        .unwrap();
+        add_stock_tx(&conn, "CEZ", "buy", "2500", 0);
+        add_value(&conn, "CEZ", 365 * DAY, "2500");
+
+        let result = compute_xirr(&conn, XirrScope::Portfolio, None, Some(365 * DAY)).unwrap();
+        let portfolio = &result[0];
+        // Flat in CZK, but 100 EUR became 125 EUR as the koruna strengthened
+        assert_eq!(portfolio.currency, "EUR");
+        assert!((portfolio.invested - 100.0).abs() < 1e-9);
+        assert!((portfolio.end_value - 125.0).abs() < 1e-9);
+        assert!((portfolio.xirr.unwrap() - 25.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_invalid_range_rejected() {
+        let conn = setup_test_db();
+        assert!(compute_xirr(&conn, XirrScope::Portfolio, Some(10), Some(5)).is_err());
+    }
+}
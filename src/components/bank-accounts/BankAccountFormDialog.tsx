@@ -19,7 +19,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 
-import type { BankAccountWithInstitution, InsertBankAccount, AccountType } from "@shared/schema";
+import type { BankAccountWithInstitution, InsertBankAccount, AccountType, InterestCapitalization } from "@shared/schema";
 import { useCurrency } from "@/lib/currency";
 import { currencies } from "@/lib/currency";
 import { CurrencyCode } from "@shared/currencies";
@@ -37,8 +37,16 @@ type UpdateBankAccountData = {
   bban?: string;
   interestRate?: string;
   hasZoneDesignation?: boolean;
+  terminationDate?: number | null;
+  depositStartDate?: number | null;
+  autoRenew?: boolean;
+  capitalization?: InterestCapitalization;
+  interestTaxRate?: string;
 };
 
+const toDateInput = (ts: number | null) => ts ? new Date(ts * 1000).toISOString().split('T')[0] : "";
+const fromDateInput = (value: string) => value ? Math.floor(new Date(value).getTime() / 1000) : null;
+
 interface ZoneData {
   id?: string;
   fromAmount: string;
@@ -78,6 +86,12 @@ export function BankAccountFormDialog({
   const [interestRate, setInterestRate] = useState("0");
   const [hasZoneDesignation, setHasZoneDesignation] = useState(false);
   const [zones, setZones] = useState<ZoneData[]>([]);
+  const [terminationDate, setTerminationDate] = useState("");
+  const [depositStartDate, setDepositStartDate] = useState("");
+  const [autoRenew, setAutoRenew] = useState(false);
+  const [capitalization, setCapitalization] = useState<InterestCapitalization>("monthly");
+  const [interestTaxRate, setInterestTaxRate] = useState("15");
+  const isTermDeposit = accountType === "term_deposit";
 
 
   // BBAN ↔ IBAN conversion functions for Czech accounts
@@ -165,6 +179,11 @@ export function BankAccountFormDialog({
         setBban(account.bban || "");
         setInterestRate(account.interestRate?.toString() || "0");
         setHasZoneDesignation(account.hasZoneDesignation || false);
+        setTerminationDate(toDateInput(account.terminationDate));
+        setDepositStartDate(toDateInput(account.depositStartDate));
+        setAutoRenew(account.autoRenew || false);
+        setCapitalization(account.capitalization || "monthly");
+        setInterestTaxRate(account.interestTaxRate || "15");
         
         // Use initial zones if provided (for edit mode)
         if (initialZones && initialZones.length > 0) {
@@ -183,11 +202,24 @@ export function BankAccountFormDialog({
         setInterestRate("0");
         setHasZoneDesignation(false);
         setZones([]);
+        setTerminationDate("");
+        setDepositStartDate("");
+        setAutoRenew(false);
+        setCapitalization("monthly");
+        setInterestTaxRate("15");
       }
     }
   }, [account, open, userCurrency, initialZones]);
 
   const handleSubmit = () => {
+    // Maturity capitalization only applies to term deposits
+    const depositTerms = {
+      terminationDate: fromDateInput(terminationDate),
+      depositStartDate: isTermDeposit ? fromDateInput(depositStartDate) : null,
+      autoRenew: isTermDeposit && autoRenew,
+      capitalization: !isTermDeposit && capitalization === "maturity" ? "monthly" : capitalization,
+      interestTaxRate,
+    };
     if (isEditMode && account) {
       onSubmit({
         id: account.id,
@@ -199,6 +231,7 @@ export function BankAccountFormDialog({
         bban: bban || undefined,
         interestRate: hasZoneDesignation ? "0" : interestRate,
         hasZoneDesignation,
+        ...depositTerms,
       }, hasZoneDesignation ? zones : undefined);
     } else {
       onSubmit({
@@ -210,6 +243,7 @@ export function BankAccountFormDialog({
         bban: bban || undefined,
         interestRate: hasZoneDesignation ? "0" : interestRate,
         hasZoneDesignation,
+        ...depositTerms,
       } as InsertBankAccount, hasZoneDesignation ? zones : undefined);
     }
   };
@@ -255,6 +289,7 @@ export function BankAccountFormDialog({
                     <SelectItem value="savings">{t("accountTypes.savings")}</SelectItem>
                     <SelectItem value="credit_card">{t("accountTypes.credit_card")}</SelectItem>
                     <SelectItem value="investment">{t("accountTypes.investment")}</SelectItem>
+                    <SelectItem value="term_deposit">{t("accountTypes.term_deposit")}</SelectItem>
                   </SelectContent>
                 </Select>
               </div>
@@ -359,6 +394,85 @@ export function BankAccountFormDialog({
                   <SavingsAccountZoneManager zones={zones} onChange={setZones} />
                 </div>
               )}
+
+              {isTermDeposit && (
+                <div className="grid grid-cols-2 gap-4">
+                  <div className="grid gap-2">
+                    <Label htmlFor="depositStartDate">{t("fields.depositStartDate")}</Label>
+                    <Input
+                      id="depositStartDate"
+                      type="date"
+                      value={depositStartDate}
+                      onChange={(e) => setDepositStartDate(e.target.value)}
+                    />
+                  </div>
+                  <div className="grid gap-2">
+                    <Label htmlFor="terminationDate">{t("fields.maturityDate")} *</Label>
+                    <Input
+                      id="terminationDate"
+                      type="date"
+                      value={terminationDate}
+                      onChange={(e) => setTerminationDate(e.target.value)}
+                    />
+                  </div>
+                </div>
+              )}
+
+              {(accountType === "savings" || isTermDeposit) && (
+                <div className="grid grid-cols-2 gap-4">
+                  <div className="grid gap-2">
+                    <Label htmlFor="capitalization">{t("fields.capitalization")}</Label>
+                    <Select
+                      value={capitalization}
+                      onValueChange={(v) => setCapitalization(v as InterestCapitalization)}
+                    >
+                      <SelectTrigger>
+                        <SelectValue />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="monthly">{t("capitalization.monthly")}</SelectItem>
+                        <SelectItem value="annual">{t("capitalization.annual")}</SelectItem>
+                        {isTermDeposit && (
+                          <SelectItem value="maturity">{t("capitalization.maturity")}</SelectItem>
+                        )}
+                      </SelectContent>
+                    </Select>
+                  </div>
+                  <div className="grid gap-2">
+                    <Label htmlFor="interestTaxRate">{t("fields.interestTaxRate")}</Label>
+                    <Input
+                      id="interestTaxRate"
+                      type="number"
+                      step="0.01"
+                      value={interestTaxRate}
+                      onChange={(e) => setInterestTaxRate(e.target.value)}
+                      placeholder="15"
+                    />
+                  </div>
+                </div>
+              )}
+
+              {isTermDeposit && (
+                <div className="flex items-start space-x-3 p-3 rounded-lg border">
+                  <Checkbox
+                    id="autoRenew"
+                    checked={autoRenew}
+                    onCheckedChange={(checked) => setAutoRenew(checked as boolean)}
+                    className="mt-1"
+                  />
+                  <div className="space-y-1">
+                    <Label
+                      htmlFor="autoRenew"
+                      className="text-sm font-medium leading-none cursor-pointer"
+                    >
+                      {t("form.autoRenew")}
+                    </Label>
+                    <p className="text-xs text-muted-foreground">
+                      {t("form.autoRenewHelp")}
+                    </p>
+                  </div>
+                </div>
+              )}
         </div>
         <DialogFooter>
           <Button variant="outline" onClick={handleClose}>
@@ -370,6 +484,7 @@ export function BankAccountFormDialog({
               isLoading ||
               !name ||
               !balance ||
+              (isTermDeposit && !terminationDate) ||
               (isEditMode && !account)
             }
           >
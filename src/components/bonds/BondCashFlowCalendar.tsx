@@ -0,0 +1,74 @@
+import { useQuery } from "@tanstack/react-query";
+import { Card } from "@/components/ui/card";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Badge } from "@/components/ui/badge";
+import { bondsApi } from "@/lib/tauri-api";
+import { useCurrency } from "@/lib/currency";
+import { useTranslation } from "react-i18next";
+import { useLanguage } from "@/i18n/I18nProvider";
+import { BondCashFlowAmount } from "./BondScheduleDialog";
+
+/** Coupons and principal repayments of all bonds due in the next 12 months */
+export function BondCashFlowCalendar() {
+  const { t } = useTranslation('bonds');
+  const { formatCurrency } = useCurrency();
+  const { formatDate } = useLanguage();
+
+  const { data: flows = [] } = useQuery({
+    queryKey: ["bond-cash-flows"],
+    queryFn: () => bondsApi.getCashFlowCalendar(),
+  });
+
+  if (flows.length === 0) return null;
+
+  const total = flows.reduce((sum, flow) => sum + flow.amountCzk, 0);
+
+  return (
+    <Card className="border card-hover">
+      <div className="p-6">
+        <div className="flex items-baseline justify-between mb-6">
+          <h2 className="text-xl font-semibold">{t('calendar.title')}</h2>
+          <span className="text-sm text-muted-foreground">
+            {t('calendar.total', { amount: formatCurrency(total) })}
+          </span>
+        </div>
+
+        <div className="rounded-lg border">
+          <Table>
+            <TableHeader className="[&_th]:bg-muted/50">
+              <TableRow>
+                <TableHead>{t('schedule.date')}</TableHead>
+                <TableHead>{t('table.bondName')}</TableHead>
+                <TableHead>{t('schedule.kind')}</TableHead>
+                <TableHead className="text-right">{t('schedule.amount')}</TableHead>
+              </TableRow>
+            </TableHeader>
+            <TableBody>
+              {flows.map((flow) => (
+                <TableRow key={`${flow.bondId}-${flow.kind}-${flow.date}`}>
+                  <TableCell>{formatDate(new Date(flow.date * 1000))}</TableCell>
+                  <TableCell className="font-medium">{flow.bondName}</TableCell>
+                  <TableCell>
+                    <Badge variant={flow.kind === "principal" ? "default" : "secondary"}>
+                      {t(`schedule.kinds.${flow.kind}`)}
+                    </Badge>
+                  </TableCell>
+                  <TableCell className="text-right">
+                    <BondCashFlowAmount flow={flow} />
+                  </TableCell>
+                </TableRow>
+              ))}
+            </TableBody>
+          </Table>
+        </div>
+      </div>
+    </Card>
+  );
+}
@@ -0,0 +1,123 @@
+import { useQuery } from "@tanstack/react-query";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import {
+  Table,
+  TableBody,
+  TableCell,
+  TableHead,
+  TableHeader,
+  TableRow,
+} from "@/components/ui/table";
+import { Badge } from "@/components/ui/badge";
+import { bondsApi } from "@/lib/tauri-api";
+import { useCurrency } from "@/lib/currency";
+import { convertToCzK, type CurrencyCode } from "@shared/currencies";
+import type { Bond, BondCashFlow } from "@shared/schema";
+import { useTranslation } from "react-i18next";
+import { useLanguage } from "@/i18n/I18nProvider";
+
+interface BondScheduleDialogProps {
+  bond: Bond | null;
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+/** Amount in the user currency, with the bond currency amount when it differs */
+export function BondCashFlowAmount({ flow }: { flow: BondCashFlow }) {
+  const { formatCurrency, currencyCode } = useCurrency();
+  return (
+    <>
+      {formatCurrency(flow.amountCzk)}
+      {flow.currency !== currencyCode && (
+        <span className="block text-xs text-muted-foreground">
+          {Number(flow.amount).toLocaleString(undefined, { minimumFractionDigits: 2 })} {flow.currency}
+        </span>
+      )}
+    </>
+  );
+}
+
+export function BondScheduleDialog({ bond, open, onOpenChange }: BondScheduleDialogProps) {
+  const { t } = useTranslation('bonds');
+  const { formatCurrency } = useCurrency();
+  const { formatDate } = useLanguage();
+
+  const { data: schedule, isLoading } = useQuery({
+    queryKey: ["bond-schedule", bond?.id],
+    queryFn: () => bondsApi.getSchedule(bond!.id),
+    enabled: open && !!bond,
+  });
+
+  const now = Date.now() / 1000;
+  const accrued = Number(schedule?.accruedInterest ?? 0);
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-2xl max-h-[90vh] overflow-y-auto">
+        <DialogHeader>
+          <DialogTitle>{t('schedule.title', { name: bond?.name ?? "" })}</DialogTitle>
+          <DialogDescription>{t('schedule.description')}</DialogDescription>
+        </DialogHeader>
+
+        {isLoading || !schedule ? (
+          <p className="text-sm text-muted-foreground">{t('loading')}</p>
+        ) : schedule.cashFlows.length === 0 ? (
+          <p className="text-sm text-muted-foreground">{t('schedule.noMaturity')}</p>
+        ) : (
+          <div className="space-y-4">
+            <div className="grid grid-cols-2 gap-4 text-sm">
+              <div>
+                <p className="text-muted-foreground">{t('schedule.accruedInterest')}</p>
+                <p className="font-semibold">
+                  {formatCurrency(convertToCzK(accrued, (bond?.currency || "CZK") as CurrencyCode))}
+                </p>
+              </div>
+              <div>
+                <p className="text-muted-foreground">{t('schedule.nextCoupon')}</p>
+                <p className="font-semibold">
+                  {schedule.nextCouponDate ? formatDate(new Date(schedule.nextCouponDate * 1000)) : "—"}
+                </p>
+              </div>
+            </div>
+
+            <div className="rounded-lg border">
+              <Table>
+                <TableHeader className="[&_th]:bg-muted/50">
+                  <TableRow>
+                    <TableHead>{t('schedule.date')}</TableHead>
+                    <TableHead>{t('schedule.kind')}</TableHead>
+                    <TableHead className="text-right">{t('schedule.amount')}</TableHead>
+                  </TableRow>
+                </TableHeader>
+                <TableBody>
+                  {schedule.cashFlows.map((flow) => (
+                    <TableRow
+                      key={`${flow.kind}-${flow.date}`}
+                      className={flow.date < now ? "text-muted-foreground" : undefined}
+                    >
+                      <TableCell>{formatDate(new Date(flow.date * 1000))}</TableCell>
+                      <TableCell>
+                        <Badge variant={flow.kind === "principal" ? "default" : "secondary"}>
+                          {t(`schedule.kinds.${flow.kind}`)}
+                        </Badge>
+                      </TableCell>
+                      <TableCell className="text-right">
+                        <BondCashFlowAmount flow={flow} />
+                      </TableCell>
+                    </TableRow>
+                  ))}
+                </TableBody>
+              </Table>
+            </div>
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -18,7 +18,7 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
-import type { Bond, InsertBond } from "@shared/schema";
+import type { Bond, BondCouponFrequency, BondDayCount, InsertBond } from "@shared/schema";
 import { useCurrency } from "@/lib/currency";
 import { currencies } from "@/lib/currency";
 import { CurrencyCode } from "@shared/currencies";
@@ -31,8 +31,22 @@ type UpdateBondData = {
   couponValue?: string;
   interestRate?: string;
   maturityDate?: Date | null | undefined;
+  couponFrequency?: BondCouponFrequency;
+  dayCount?: BondDayCount;
+  issueDate?: number | null;
+  firstCouponDate?: number | null;
+  purchasePrice?: string | null;
+  purchaseDate?: number | null;
+  marketPrice?: string | null;
+  priceTicker?: string | null;
 };
 
+const COUPON_FREQUENCIES: BondCouponFrequency[] = ['annual', 'semiannual', 'quarterly', 'monthly'];
+const DAY_COUNTS: BondDayCount[] = ['30/360', 'act/360', 'act/365', 'act/act'];
+
+const toDateInput = (ts: number | null) => ts ? new Date(ts * 1000).toISOString().split('T')[0] : "";
+const fromDateInput = (value: string) => value ? Math.floor(new Date(value).getTime() / 1000) : null;
+
 interface BondsFormDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
@@ -53,6 +67,14 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
   const [quantity, setQuantity] = useState("1");
   const [interestRate, setInterestRate] = useState("0");
   const [maturityDate, setMaturityDate] = useState<string>("");
+  const [couponFrequency, setCouponFrequency] = useState<BondCouponFrequency>("annual");
+  const [dayCount, setDayCount] = useState<BondDayCount>("30/360");
+  const [issueDate, setIssueDate] = useState<string>("");
+  const [firstCouponDate, setFirstCouponDate] = useState<string>("");
+  const [purchasePrice, setPurchasePrice] = useState("");
+  const [purchaseDate, setPurchaseDate] = useState<string>("");
+  const [marketPrice, setMarketPrice] = useState("");
+  const [priceTicker, setPriceTicker] = useState("");
   const [selectedCurrency, setSelectedCurrency] = useState<CurrencyCode>(userCurrency);
 
   useEffect(() => {
@@ -64,7 +86,16 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
       setCouponValue(bond.couponValue);
       setQuantity(bond.quantity || "1");
       setInterestRate(bond.interestRate.toString());
-      setMaturityDate(bond.maturityDate ? new Date(bond.maturityDate * 1000).toISOString().split('T')[0] : "");
+      setMaturityDate(toDateInput(bond.maturityDate));
+      setCouponFrequency(bond.couponFrequency || "annual");
+      setDayCount(bond.dayCount || "30/360");
+      setIssueDate(toDateInput(bond.issueDate));
+      setFirstCouponDate(toDateInput(bond.firstCouponDate));
+      setPurchasePrice(bond.purchasePrice || "");
+      setPurchaseDate(toDateInput(bond.purchaseDate));
+      // Only a manual price is editable; a fetched one is shown as the placeholder
+      setMarketPrice(bond.marketPriceSource === "manual" ? bond.marketPrice || "" : "");
+      setPriceTicker(bond.priceTicker || "");
       setSelectedCurrency((bond.currency as CurrencyCode) || "CZK");
     } else {
       setName("");
@@ -73,6 +104,14 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
       setQuantity("1");
       setInterestRate("0");
       setMaturityDate("");
+      setCouponFrequency("annual");
+      setDayCount("30/360");
+      setIssueDate("");
+      setFirstCouponDate("");
+      setPurchasePrice("");
+      setPurchaseDate("");
+      setMarketPrice("");
+      setPriceTicker("");
       setSelectedCurrency(userCurrency);
     }
   }, [open, bond, userCurrency]);
@@ -85,7 +124,15 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
       quantity,
       currency: selectedCurrency,
       interestRate,
-      maturityDate: maturityDate ? Math.floor(new Date(maturityDate).getTime() / 1000) : undefined
+      maturityDate: maturityDate ? Math.floor(new Date(maturityDate).getTime() / 1000) : undefined,
+      couponFrequency,
+      dayCount,
+      issueDate: fromDateInput(issueDate),
+      firstCouponDate: fromDateInput(firstCouponDate),
+      purchasePrice: purchasePrice || null,
+      purchaseDate: fromDateInput(purchaseDate),
+      marketPrice: marketPrice || null,
+      priceTicker: priceTicker.trim() || null,
     };
 
     if (isEditMode && bond) {
@@ -107,6 +154,14 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
       setQuantity("1");
       setInterestRate("0");
       setMaturityDate("");
+      setCouponFrequency("annual");
+      setDayCount("30/360");
+      setIssueDate("");
+      setFirstCouponDate("");
+      setPurchasePrice("");
+      setPurchaseDate("");
+      setMarketPrice("");
+      setPriceTicker("");
     }
   };
 
@@ -201,6 +256,65 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
                 />
               </div>
 
+              <div className="grid grid-cols-2 gap-4">
+                <div className="grid gap-2">
+                  <Label htmlFor="issueDate">{t('form.issueDate')}</Label>
+                  <Input
+                    id="issueDate"
+                    type="date"
+                    value={issueDate}
+                    onChange={(e) => setIssueDate(e.target.value)}
+                  />
+                </div>
+                <div className="grid gap-2">
+                  <Label htmlFor="firstCouponDate">{t('form.firstCouponDate')}</Label>
+                  <Input
+                    id="firstCouponDate"
+                    type="date"
+                    value={firstCouponDate}
+                    onChange={(e) => setFirstCouponDate(e.target.value)}
+                  />
+                </div>
+              </div>
+
+              <div className="grid grid-cols-2 gap-4">
+                <div className="grid gap-2">
+                  <Label htmlFor="couponFrequency">{t('form.couponFrequency')}</Label>
+                  <Select
+                    value={couponFrequency}
+                    onValueChange={(v) => setCouponFrequency(v as BondCouponFrequency)}
+                  >
+                    <SelectTrigger id="couponFrequency">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {COUPON_FREQUENCIES.map((f) => (
+                        <SelectItem key={f} value={f}>{t(`frequencies.${f}`)}</SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+                <div className="grid gap-2">
+                  <Label htmlFor="dayCount">{t('form.dayCount')}</Label>
+                  <Select
+                    value={dayCount}
+                    onValueChange={(v) => setDayCount(v as BondDayCount)}
+                  >
+                    <SelectTrigger id="dayCount">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {DAY_COUNTS.map((d) => (
+                        <SelectItem key={d} value={d}>{d.toUpperCase()}</SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+              </div>
+              <p className="text-xs text-muted-foreground -mt-2">
+                {t('modal.scheduleHelp')}
+              </p>
+
               <div className="grid gap-2">
                 <Label htmlFor="interestRate">{t('form.interestRate')} *</Label>
                 <Input
@@ -216,6 +330,58 @@ export function BondsFormDialog({ open, onOpenChange, onSubmit, bond, isLoading
                   {t('modal.apyHelp')}
                 </p>
               </div>
+
+              <div className="grid grid-cols-2 gap-4">
+                <div className="grid gap-2">
+                  <Label htmlFor="purchasePrice">{t('form.purchasePrice')} (%)</Label>
+                  <Input
+                    id="purchasePrice"
+                    type="number"
+                    step="any"
+                    value={purchasePrice}
+                    onChange={(e) => setPurchasePrice(e.target.value)}
+                    placeholder="100.00"
+                  />
+                </div>
+                <div className="grid gap-2">
+                  <Label htmlFor="purchaseDate">{t('form.purchaseDate')}</Label>
+                  <Input
+                    id="purchaseDate"
+                    type="date"
+                    value={purchaseDate}
+                    onChange={(e) => setPurchaseDate(e.target.value)}
+                  />
+                </div>
+              </div>
+              <p className="text-xs text-muted-foreground -mt-2">
+                {t('modal.priceHelp')}
+              </p>
+
+              <div className="grid grid-cols-2 gap-4">
+                <div className="grid gap-2">
+                  <Label htmlFor="marketPrice">{t('form.marketPrice')} (%)</Label>
+                  <Input
+                    id="marketPrice"
+                    type="number"
+                    step="any"
+                    value={marketPrice}
+                    onChange={(e) => setMarketPrice(e.target.value)}
+                    placeholder={bond?.marketPriceSource !== "manual" && bond?.marketPrice ? bond.marketPrice : purchasePrice || "100.00"}
+                  />
+                </div>
+                <div className="grid gap-2">
+                  <Label htmlFor="priceTicker">{t('form.priceTicker')}</Label>
+                  <Input
+                    id="priceTicker"
+                    value={priceTicker}
+                    onChange={(e) => setPriceTicker(e.target.value)}
+                    placeholder={t('modal.priceTickerPlaceholder')}
+                  />
+                </div>
+              </div>
+              <p className="text-xs text-muted-foreground -mt-2">
+                {t('modal.marketPriceHelp')} {t('modal.priceTickerHelp')}
+              </p>
         </div>
 
         <DialogFooter>
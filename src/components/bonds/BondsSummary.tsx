@@ -1,5 +1,6 @@
 import { SummaryCard } from "@/components/common/SummaryCard";
 import type { BondsMetrics } from "@/hooks/use-bonds";
+import { useSavingsAccounts } from "@/hooks/use-savings-accounts";
 import { useCurrency } from "@/lib/currency";
 import { FileText, Percent, Banknote } from "lucide-react";
 import { useTranslation } from "react-i18next";
@@ -11,6 +12,7 @@ interface BondsSummaryProps {
 export function BondsSummary({ metrics }: BondsSummaryProps) {
   const { formatCurrency } = useCurrency();
   const { t } = useTranslation('bonds');
+  const { accounts, metrics: savingsMetrics } = useSavingsAccounts();
 
   return (
     <div className="grid grid-cols-1 sm:grid-cols-3 gap-6 mb-6">
@@ -24,6 +26,9 @@ export function BondsSummary({ metrics }: BondsSummaryProps) {
         title={t('summary.avgYield')}
         value={`${metrics.averageInterestRate.toFixed(2)}%`}
         icon={<Percent className="h-4 w-4" />}
+        subtitle={accounts.length > 0
+          ? t('summary.savingsComparison', { rate: savingsMetrics.averageInterestRate.toFixed(2) })
+          : undefined}
       />
 
       <SummaryCard
@@ -15,8 +15,9 @@ import {
   DropdownMenuItem,
   DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
-import { MoreVertical, Pencil, Trash2, ArrowUpDown, ArrowUp, ArrowDown } from "lucide-react";
-import { Bond } from "@shared/schema";
+import { MoreVertical, Pencil, Trash2, CalendarDays, ArrowUpDown, ArrowUp, ArrowDown } from "lucide-react";
+import { Bond, BondAnalytics } from "@shared/schema";
+import { bondYield } from "@/hooks/use-bonds";
 import { useCurrency } from "@/lib/currency";
 import { convertToCzK, type CurrencyCode } from "@shared/currencies";
 import { useTranslation } from "react-i18next";
@@ -24,18 +25,20 @@ import { useLanguage } from "@/i18n/I18nProvider";
 
 interface BondsTableProps {
   bonds: Bond[];
+  analytics: Map<string, BondAnalytics>;
   onEdit: (bond: Bond) => void;
   onDelete: (bond: Bond) => void;
+  onShowSchedule: (bond: Bond) => void;
 }
 
-export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
+export function BondsTable({ bonds, analytics, onEdit, onDelete, onShowSchedule }: BondsTableProps) {
   const { t } = useTranslation('bonds');
   const { t: tc } = useTranslation('common');
   const { formatCurrency } = useCurrency();
   const { formatDate } = useLanguage();
 
   // Sorting state
-  type SortColumn = 'name' | 'isin' | 'couponValue' | 'quantity' | 'totalValue' | 'interestRate' | 'maturityDate';
+  type SortColumn = 'name' | 'isin' | 'couponValue' | 'quantity' | 'price' | 'totalValue' | 'interestRate' | 'ytm' | 'maturityDate';
   const [sortColumn, setSortColumn] = useState<SortColumn>('name');
   const [sortDirection, setSortDirection] = useState<'asc' | 'desc'>('asc');
 
@@ -57,6 +60,11 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
       : <ArrowDown className="h-3 w-3 ml-1" />;
   };
 
+  const priceOf = (bond: Bond) => Number(analytics.get(bond.id)?.cleanPrice ?? 100);
+  const valueCzkOf = (bond: Bond) =>
+    analytics.get(bond.id)?.marketValueCzk
+    ?? convertToCzK(Number(bond.couponValue) * Number(bond.quantity || "1"), bond.currency as CurrencyCode);
+
   const sortedBonds = useMemo(() => {
     return [...bonds].sort((a, b) => {
       let comparison = 0;
@@ -78,8 +86,14 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
         case 'quantity':
           comparison = quantityA - quantityB;
           break;
+        case 'price':
+          comparison = priceOf(a) - priceOf(b);
+          break;
         case 'totalValue':
-          comparison = (couponValueA * quantityA) - (couponValueB * quantityB);
+          comparison = valueCzkOf(a) - valueCzkOf(b);
+          break;
+        case 'ytm':
+          comparison = bondYield(a, analytics.get(a.id)) - bondYield(b, analytics.get(b.id));
           break;
         case 'interestRate':
           comparison = Number(a.interestRate) - Number(b.interestRate);
@@ -90,7 +104,8 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
       }
       return sortDirection === 'asc' ? comparison : -comparison;
     });
-  }, [bonds, sortColumn, sortDirection]);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [bonds, analytics, sortColumn, sortDirection]);
 
   return (
     <Card className="border card-hover">
@@ -113,12 +128,18 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
                 <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('quantity')}>
                   <span className="flex items-center justify-end">{t('table.quantity')}<SortIcon column="quantity" /></span>
                 </TableHead>
+                <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('price')}>
+                  <span className="flex items-center justify-end">{t('table.price')}<SortIcon column="price" /></span>
+                </TableHead>
                 <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('totalValue')}>
                   <span className="flex items-center justify-end">{t('table.totalValue')}<SortIcon column="totalValue" /></span>
                 </TableHead>
                 <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('interestRate')}>
                   <span className="flex items-center justify-end">{t('table.interestRate')}<SortIcon column="interestRate" /></span>
                 </TableHead>
+                <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('ytm')}>
+                  <span className="flex items-center justify-end">{t('table.ytm')}<SortIcon column="ytm" /></span>
+                </TableHead>
                 <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('maturityDate')}>
                   <span className="flex items-center justify-end">{t('table.maturityDate')}<SortIcon column="maturityDate" /></span>
                 </TableHead>
@@ -129,7 +150,7 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
               {sortedBonds.length === 0 ? (
                 <TableRow>
                   <TableCell
-                    colSpan={8}
+                    colSpan={10}
                     className="text-center text-muted-foreground py-8"
                   >
                     {t('table.noBonds')}
@@ -139,7 +160,7 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
                 sortedBonds.map((bond) => {
                   const quantity = Number(bond.quantity || "1");
                   const couponValue = Number(bond.couponValue);
-                  const totalValue = couponValue * quantity;
+                  const bondAnalytics = analytics.get(bond.id);
                   return (
                   <TableRow key={bond.id}>
                     <TableCell className="font-medium">{bond.name}</TableCell>
@@ -150,12 +171,23 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
                     <TableCell className="text-right">
                       {quantity}
                     </TableCell>
+                    <TableCell className="text-right">
+                      {priceOf(bond).toFixed(2)}%
+                    </TableCell>
                     <TableCell className="text-right font-medium">
-                      {formatCurrency(convertToCzK(totalValue, bond.currency as CurrencyCode))}
+                      {formatCurrency(valueCzkOf(bond))}
                     </TableCell>
                     <TableCell className="text-right">
                       {Number(bond.interestRate).toFixed(2)}%
                     </TableCell>
+                    <TableCell className="text-right">
+                      {bondAnalytics?.yieldToMaturity != null ? `${bondAnalytics.yieldToMaturity.toFixed(2)}%` : "—"}
+                      {bondAnalytics?.modifiedDuration != null && (
+                        <span className="block text-xs text-muted-foreground">
+                          {t('table.duration', { years: bondAnalytics.modifiedDuration.toFixed(2) })}
+                        </span>
+                      )}
+                    </TableCell>
                     <TableCell className="text-right">
                       {bond.maturityDate ? formatDate(new Date(bond.maturityDate * 1000)) : "—"}
                     </TableCell>
@@ -167,6 +199,10 @@ export function BondsTable({ bonds, onEdit, onDelete }: BondsTableProps) {
                           </Button>
                         </DropdownMenuTrigger>
                         <DropdownMenuContent align="end">
+                          <DropdownMenuItem onClick={() => onShowSchedule(bond)}>
+                            <CalendarDays className="mr-2 h-4 w-4" />
+                            {t('actions.schedule')}
+                          </DropdownMenuItem>
                           <DropdownMenuItem onClick={() => onEdit(bond)}>
                             <Pencil className="mr-2 h-4 w-4" />
                             {tc('buttons.edit')}
@@ -201,7 +201,7 @@ export default function PortfolioValueTrendChart({
 
   // Fetch portfolio history
   const { data: portfolioHistory } = useQuery<PortfolioMetricsHistory[]>({
-    queryKey: ["portfolio-history", dateRange.start?.toISOString(), dateRange.end.toISOString()],
+    queryKey: ["portfolio-history", dateRange.start?.toISOString(), dateRange.end.toISOString(), currencyCode],
     queryFn: async () => {
       const startDate = dateRange.start ? Math.floor(dateRange.start.getTime() / 1000) : undefined;
       const endDate = Math.floor(dateRange.end.getTime() / 1000);
@@ -241,15 +241,10 @@ export default function PortfolioValueTrendChart({
     // Reverse history to get chronological order (Oldest -> Newest)
     // portfolioHistory is DESC (Newest -> Oldest)
     const historyData: TrendData[] = [...(portfolioHistory || [])].reverse().map(h => {
-      let valueInCzk: number;
-      if (type === 'investments') {
-        valueInCzk = Number(h.totalInvestments);
-      } else {
-        valueInCzk = Number(h.totalCrypto || 0);
-      }
-      
-      // Convert historical value from CZK to display currency
-      const value = convert(valueInCzk, "CZK", currencyCode);
+      // History is already in the display currency at the rate of its day
+      const value = type === 'investments'
+        ? Number(h.totalInvestments)
+        : Number(h.totalCrypto || 0);
       
       // Include year in date format for multi-year periods
       const includeYear = selectedPeriod === '1Y' || selectedPeriod === '5Y' || selectedPeriod === 'All';
@@ -184,7 +184,7 @@ export default function TickerValueTrendChart({
 
   // Fetch ticker history
   const { data: tickerHistory } = useQuery<TickerValueHistory[]>({
-    queryKey: ["ticker-history", type, ticker, dateRange.start?.toISOString(), dateRange.end.toISOString()],
+    queryKey: ["ticker-history", type, ticker, dateRange.start?.toISOString(), dateRange.end.toISOString(), currencyCode],
     queryFn: async () => {
       const startDate = dateRange.start ? Math.floor(dateRange.start.getTime() / 1000) : undefined;
       const endDate = Math.floor(dateRange.end.getTime() / 1000);
@@ -219,9 +219,8 @@ export default function TickerValueTrendChart({
     // Reverse history to get chronological order (Oldest -> Newest)
     // tickerHistory is DESC (Newest -> Oldest)
     const historyData: TrendData[] = [...(tickerHistory || [])].reverse().map(h => {
-      const valueInCzk = Number(h.valueCzk) || 0;
-      // Convert to preferred currency
-      const value = convert(valueInCzk, "CZK", currencyCode);
+      // History is already in the display currency at the rate of its day
+      const value = Number(h.valueCzk) || 0;
       
       // Include year in date format for multi-year periods
       const includeYear = selectedPeriod === '1Y' || selectedPeriod === '5Y' || selectedPeriod === 'All';
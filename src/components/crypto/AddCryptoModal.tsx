@@ -229,15 +229,8 @@ export function AddCryptoModal() {
                                                 <FormControl>
                                                     <Input 
                                                         type="number" 
-                                                        step="0.01" 
-                                                        {...field} 
-                                                        onBlur={(e) => {
-                                                            const value = parseFloat(e.target.value);
-                                                            if (!isNaN(value)) {
-                                                                field.onChange(value.toFixed(2));
-                                                            }
-                                                            field.onBlur();
-                                                        }}
+                                                        step="any" 
+                                                        {...field}
                                                     />
                                                 </FormControl>
                                                 <FormMessage />
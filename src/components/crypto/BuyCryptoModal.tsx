@@ -70,7 +70,7 @@ export function BuyCryptoModal({ crypto, open, onOpenChange }: BuyCryptoModalPro
             form.setValue("currency", (crypto.currency as "USD" | "EUR" | "CZK") || "USD");
             // Use originalPrice (in source currency) for prefill, not currentPrice (which is converted to CZK)
             const priceToShow = Number(crypto.originalPrice ?? crypto.currentPrice);
-            form.setValue("pricePerUnit", priceToShow ? Number(priceToShow.toFixed(2)) : 0);
+            form.setValue("pricePerUnit", priceToShow || 0);
         }
     }, [crypto, form]);
 
@@ -146,15 +146,8 @@ export function BuyCryptoModal({ crypto, open, onOpenChange }: BuyCryptoModalPro
                                         <FormControl>
                                             <Input 
                                                 type="number" 
-                                                step="0.01" 
-                                                {...field} 
-                                                onBlur={(e) => {
-                                                    const value = parseFloat(e.target.value);
-                                                    if (!isNaN(value)) {
-                                                        field.onChange(value.toFixed(2));
-                                                    }
-                                                    field.onBlur();
-                                                }}
+                                                step="any" 
+                                                {...field}
                                             />
                                         </FormControl>
                                         <FormMessage />
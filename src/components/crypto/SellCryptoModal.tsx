@@ -124,15 +124,8 @@ export function SellCryptoModal({ investment, open, onOpenChange }: SellCryptoMo
                                                 <FormControl>
                                                     <Input 
                                                         type="number" 
-                                                        step="0.01" 
-                                                        {...field} 
-                                                        onBlur={(e) => {
-                                                            const value = parseFloat(e.target.value);
-                                                            if (!isNaN(value)) {
-                                                                field.onChange(value.toFixed(2));
-                                                            }
-                                                            field.onBlur();
-                                                        }}
+                                                        step="any" 
+                                                        {...field}
                                                     />
                                                 </FormControl>
                                                 <FormMessage />
@@ -1,6 +1,6 @@
 import { useForm, DefaultValues } from "react-hook-form";
 import { zodResolver } from "@hookform/resolvers/zod";
-import { insertLoanSchema, InsertLoan, Loan } from "@shared/schema";
+import { insertLoanSchema, InsertLoan, Loan, LoanRepaymentType } from "@shared/schema";
 import {
     Dialog,
     DialogContent,
@@ -31,6 +31,7 @@ import { format } from "date-fns";
 import { useCurrency, currencies } from "@/lib/currency";
 import { useTranslation } from "react-i18next";
 
+const REPAYMENT_TYPES: LoanRepaymentType[] = ['annuity', 'linear', 'interest_only'];
 
 interface LoanFormDialogProps {
     open: boolean;
@@ -62,6 +63,9 @@ export function LoanFormDialog({
             interestRateValidityDate: undefined,
             startDate: new Date(),
             endDate: undefined,
+            repaymentType: "annuity",
+            paymentIban: "",
+            paymentVariableSymbol: "",
         } as DefaultValues<InsertLoan>,
     });
 
@@ -80,6 +84,9 @@ export function LoanFormDialog({
                     interestRateValidityDate: loan.interestRateValidityDate ? new Date(loan.interestRateValidityDate * 1000) : undefined,
                     startDate: loan.startDate ? new Date(loan.startDate * 1000) : new Date(),
                     endDate: loan.endDate ? new Date(loan.endDate * 1000) : undefined,
+                    repaymentType: loan.repaymentType || "annuity",
+                    paymentIban: loan.paymentIban || "",
+                    paymentVariableSymbol: loan.paymentVariableSymbol || "",
                 } as DefaultValues<InsertLoan>);
             } else {
                 form.reset({
@@ -91,6 +98,9 @@ export function LoanFormDialog({
                     interestRateValidityDate: undefined,
                     startDate: new Date(),
                     endDate: undefined,
+                    repaymentType: "annuity",
+                    paymentIban: "",
+                    paymentVariableSymbol: "",
                 } as DefaultValues<InsertLoan>);
             }
         }
@@ -119,6 +129,8 @@ export function LoanFormDialog({
             startDate: convertToTimestamp(data.startDate),
             endDate: convertToTimestamp(data.endDate),
             interestRateValidityDate: convertToTimestamp(data.interestRateValidityDate),
+            paymentIban: data.paymentIban?.trim() || null,
+            paymentVariableSymbol: data.paymentVariableSymbol?.trim() || null,
         };
 
         if (loan) {
@@ -243,6 +255,64 @@ export function LoanFormDialog({
                                     />
                                 </div>
 
+                                <div className="grid grid-cols-2 gap-4">
+                                    <FormField
+                                        control={form.control}
+                                        name="startDate"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{t('form.startDate')}</FormLabel>
+                                                <FormControl>
+                                                    <Input
+                                                        type="date"
+                                                        value={
+                                                            field.value
+                                                                ? format(new Date(field.value), "yyyy-MM-dd")
+                                                                : ""
+                                                        }
+                                                        onChange={(e) =>
+                                                            field.onChange(
+                                                                e.target.value ? new Date(e.target.value) : undefined
+                                                            )
+                                                        }
+                                                    />
+                                                </FormControl>
+                                                <p className="text-xs text-muted-foreground">
+                                                    {t('modal.startDateHelp')}
+                                                </p>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                    <FormField
+                                        control={form.control}
+                                        name="repaymentType"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{t('form.repaymentType')}</FormLabel>
+                                                <Select
+                                                    value={field.value}
+                                                    onValueChange={field.onChange}
+                                                >
+                                                    <FormControl>
+                                                        <SelectTrigger>
+                                                            <SelectValue />
+                                                        </SelectTrigger>
+                                                    </FormControl>
+                                                    <SelectContent>
+                                                        {REPAYMENT_TYPES.map((type) => (
+                                                            <SelectItem key={type} value={type}>
+                                                                {t(`repaymentTypes.${type}`)}
+                                                            </SelectItem>
+                                                        ))}
+                                                    </SelectContent>
+                                                </Select>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                </div>
+
                                 <div className="grid grid-cols-2 gap-4">
                                     <FormField
                                         control={form.control}
@@ -301,9 +371,43 @@ export function LoanFormDialog({
                                             </FormItem>
                                         )}
                                     />
+                                </div>
 
-
+                                <div className="grid grid-cols-2 gap-4">
+                                    <FormField
+                                        control={form.control}
+                                        name="paymentIban"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{t('form.paymentIban')}</FormLabel>
+                                                <FormControl>
+                                                    <Input
+                                                        placeholder={t('form.paymentIbanPlaceholder')}
+                                                        {...field}
+                                                        value={field.value ?? ""}
+                                                    />
+                                                </FormControl>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                    <FormField
+                                        control={form.control}
+                                        name="paymentVariableSymbol"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{t('form.paymentVariableSymbol')}</FormLabel>
+                                                <FormControl>
+                                                    <Input {...field} value={field.value ?? ""} />
+                                                </FormControl>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
                                 </div>
+                                <p className="text-xs text-muted-foreground -mt-2">
+                                    {t('modal.paymentMatchHelp')}
+                                </p>
                             </div>
 
                         <DialogFooter>
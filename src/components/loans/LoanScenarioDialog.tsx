@@ -0,0 +1,232 @@
+import { useState } from "react";
+import { useMutation } from "@tanstack/react-query";
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogHeader,
+    DialogTitle,
+} from "@/components/ui/dialog";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { loansApi } from "@/lib/tauri-api";
+import { useCurrency } from "@/lib/currency";
+import { convertToCzK, type CurrencyCode } from "@shared/currencies";
+import type { Loan, LoanPrepaymentStrategy, LoanScenarioResult } from "@shared/schema";
+import { toast } from "sonner";
+import { useTranslation } from "react-i18next";
+import { useLanguage } from "@/i18n/I18nProvider";
+import { translateApiError } from "@/lib/translate-api-error";
+
+interface LoanScenarioDialogProps {
+    loan: Loan | null;
+    open: boolean;
+    onOpenChange: (open: boolean) => void;
+}
+
+export function LoanScenarioDialog({ loan, open, onOpenChange }: LoanScenarioDialogProps) {
+    const { t } = useTranslation('loans');
+    const { t: tc } = useTranslation('common');
+    const { formatCurrency } = useCurrency();
+    const { formatDate } = useLanguage();
+
+    const [prepaymentAmount, setPrepaymentAmount] = useState("");
+    const [prepaymentDate, setPrepaymentDate] = useState("");
+    const [monthlyPrepayment, setMonthlyPrepayment] = useState("");
+    const [strategy, setStrategy] = useState<LoanPrepaymentStrategy>("shorten_term");
+    const [refixationRate, setRefixationRate] = useState("");
+    const [feeFreePercent, setFeeFreePercent] = useState("");
+    const [prepaymentFeePercent, setPrepaymentFeePercent] = useState("");
+
+    const simulation = useMutation({
+        mutationFn: () =>
+            loansApi.simulate(loan!.id, [
+                {
+                    name: t('scenarios.scenario'),
+                    oneOffPrepayments:
+                        prepaymentAmount && prepaymentDate
+                            ? [{ date: Math.floor(new Date(prepaymentDate).getTime() / 1000), amount: prepaymentAmount }]
+                            : [],
+                    monthlyPrepayment: monthlyPrepayment || null,
+                    strategy,
+                    refixationRate: refixationRate || null,
+                    feeFreePercent: feeFreePercent || null,
+                    prepaymentFeePercent: prepaymentFeePercent || null,
+                },
+            ]),
+        onError: (error: Error) => {
+            toast.error(tc('status.error'), { description: translateApiError(error, tc) });
+        },
+    });
+
+    const currency = (loan?.currency || "CZK") as CurrencyCode;
+    const money = (amount: string) => formatCurrency(convertToCzK(Number(amount), currency));
+    const result = simulation.data;
+    const rows: LoanScenarioResult[] = result ? [result.baseline, ...result.scenarios] : [];
+
+    return (
+        <Dialog
+            open={open}
+            onOpenChange={(next) => {
+                if (!next) simulation.reset();
+                onOpenChange(next);
+            }}
+        >
+            <DialogContent className="max-w-3xl max-h-[90vh] overflow-y-auto">
+                <DialogHeader>
+                    <DialogTitle>{t('scenarios.title', { name: loan?.name ?? "" })}</DialogTitle>
+                    <DialogDescription>{t('scenarios.description')}</DialogDescription>
+                </DialogHeader>
+
+                <div className="space-y-4">
+                    <div className="grid grid-cols-2 sm:grid-cols-3 gap-4">
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.prepaymentAmount')}</Label>
+                            <Input
+                                type="number"
+                                min="0"
+                                value={prepaymentAmount}
+                                onChange={(e) => setPrepaymentAmount(e.target.value)}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.prepaymentDate')}</Label>
+                            <Input
+                                type="date"
+                                value={prepaymentDate}
+                                onChange={(e) => setPrepaymentDate(e.target.value)}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.monthlyPrepayment')}</Label>
+                            <Input
+                                type="number"
+                                min="0"
+                                value={monthlyPrepayment}
+                                onChange={(e) => setMonthlyPrepayment(e.target.value)}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.strategy')}</Label>
+                            <Select
+                                value={strategy}
+                                onValueChange={(value) => setStrategy(value as LoanPrepaymentStrategy)}
+                            >
+                                <SelectTrigger>
+                                    <SelectValue />
+                                </SelectTrigger>
+                                <SelectContent>
+                                    <SelectItem value="shorten_term">{t('scenarios.strategies.shorten_term')}</SelectItem>
+                                    <SelectItem value="lower_payment">{t('scenarios.strategies.lower_payment')}</SelectItem>
+                                </SelectContent>
+                            </Select>
+                        </div>
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.refixationRate')}</Label>
+                            <Input
+                                type="number"
+                                min="0"
+                                step="0.01"
+                                value={refixationRate}
+                                onChange={(e) => setRefixationRate(e.target.value)}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.feeFreePercent')}</Label>
+                            <Input
+                                type="number"
+                                min="0"
+                                value={feeFreePercent}
+                                onChange={(e) => setFeeFreePercent(e.target.value)}
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <Label>{t('scenarios.prepaymentFeePercent')}</Label>
+                            <Input
+                                type="number"
+                                min="0"
+                                step="0.01"
+                                value={prepaymentFeePercent}
+                                onChange={(e) => setPrepaymentFeePercent(e.target.value)}
+                            />
+                        </div>
+                    </div>
+
+                    <p className="text-xs text-muted-foreground">
+                        {loan?.interestRateValidityDate
+                            ? t('scenarios.refixationHelp', {
+                                date: formatDate(new Date(Number(loan.interestRateValidityDate) * 1000)),
+                            })
+                            : t('scenarios.noRefixation')}
+                    </p>
+
+                    <div className="flex justify-end">
+                        <Button onClick={() => simulation.mutate()} disabled={!loan || simulation.isPending}>
+                            {t('scenarios.simulate')}
+                        </Button>
+                    </div>
+
+                    {result && (
+                        <div className="rounded-lg border">
+                            <Table>
+                                <TableHeader className="[&_th]:bg-muted/50">
+                                    <TableRow>
+                                        <TableHead />
+                                        <TableHead className="text-right">{t('scenarios.monthlyPayment')}</TableHead>
+                                        <TableHead className="text-right">{t('scenarios.totalInterest')}</TableHead>
+                                        <TableHead className="text-right">{t('scenarios.interestSaved')}</TableHead>
+                                        <TableHead className="text-right">{t('scenarios.fees')}</TableHead>
+                                        <TableHead className="text-right">{t('scenarios.payoffDate')}</TableHead>
+                                    </TableRow>
+                                </TableHeader>
+                                <TableBody>
+                                    {rows.map((row, index) => (
+                                        <TableRow key={index}>
+                                            <TableCell className="font-medium">
+                                                {index === 0 ? t('scenarios.baseline') : row.name}
+                                            </TableCell>
+                                            <TableCell className="text-right">
+                                                {money(row.monthlyPayment)}
+                                                {row.paymentAfterRefixation && (
+                                                    <span className="block text-xs text-muted-foreground">
+                                                        {t('scenarios.afterRefixation', {
+                                                            amount: money(row.paymentAfterRefixation),
+                                                        })}
+                                                    </span>
+                                                )}
+                                            </TableCell>
+                                            <TableCell className="text-right">{money(row.totalInterest)}</TableCell>
+                                            <TableCell className="text-right">
+                                                {index === 0 ? "—" : money(row.interestSaved)}
+                                            </TableCell>
+                                            <TableCell className="text-right">{money(row.fees)}</TableCell>
+                                            <TableCell className="text-right">
+                                                {row.payoffDate ? formatDate(new Date(row.payoffDate * 1000)) : "—"}
+                                            </TableCell>
+                                        </TableRow>
+                                    ))}
+                                </TableBody>
+                            </Table>
+                        </div>
+                    )}
+                </div>
+            </DialogContent>
+        </Dialog>
+    );
+}
@@ -0,0 +1,120 @@
+import { useQuery } from "@tanstack/react-query";
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogHeader,
+    DialogTitle,
+} from "@/components/ui/dialog";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Badge } from "@/components/ui/badge";
+import { loansApi } from "@/lib/tauri-api";
+import { useCurrency } from "@/lib/currency";
+import { convertToCzK, type CurrencyCode } from "@shared/currencies";
+import type { Loan } from "@shared/schema";
+import { useTranslation } from "react-i18next";
+import { useLanguage } from "@/i18n/I18nProvider";
+
+interface LoanScheduleDialogProps {
+    loan: Loan | null;
+    open: boolean;
+    onOpenChange: (open: boolean) => void;
+}
+
+export function LoanScheduleDialog({ loan, open, onOpenChange }: LoanScheduleDialogProps) {
+    const { t } = useTranslation('loans');
+    const { formatCurrency } = useCurrency();
+    const { formatDate } = useLanguage();
+
+    const { data: schedule, isLoading } = useQuery({
+        queryKey: ["loan-schedule", loan?.id],
+        queryFn: () => loansApi.getSchedule(loan!.id),
+        enabled: open && !!loan,
+    });
+
+    const currency = (loan?.currency || "CZK") as CurrencyCode;
+    const money = (amount: string) => formatCurrency(convertToCzK(Number(amount), currency));
+
+    return (
+        <Dialog open={open} onOpenChange={onOpenChange}>
+            <DialogContent className="max-w-3xl max-h-[90vh] overflow-y-auto">
+                <DialogHeader>
+                    <DialogTitle>{t('schedule.title', { name: loan?.name ?? "" })}</DialogTitle>
+                    <DialogDescription>
+                        {schedule && schedule.matchedPayments > 0
+                            ? t('schedule.matched', { count: schedule.matchedPayments })
+                            : t('schedule.description')}
+                    </DialogDescription>
+                </DialogHeader>
+
+                {isLoading || !schedule ? (
+                    <p className="text-sm text-muted-foreground">{t('loading')}</p>
+                ) : (
+                    <div className="space-y-4">
+                        <div className="grid grid-cols-2 sm:grid-cols-4 gap-4 text-sm">
+                            <div>
+                                <p className="text-muted-foreground">{t('schedule.outstanding')}</p>
+                                <p className="font-semibold">{formatCurrency(schedule.outstandingPrincipalCzk)}</p>
+                            </div>
+                            <div>
+                                <p className="text-muted-foreground">{t('schedule.paidInterest')}</p>
+                                <p className="font-semibold">{money(schedule.paidInterest)}</p>
+                            </div>
+                            <div>
+                                <p className="text-muted-foreground">{t('schedule.remainingInterest')}</p>
+                                <p className="font-semibold">{money(schedule.remainingInterest)}</p>
+                            </div>
+                            <div>
+                                <p className="text-muted-foreground">{t('schedule.payoffDate')}</p>
+                                <p className="font-semibold">
+                                    {schedule.payoffDate ? formatDate(new Date(schedule.payoffDate * 1000)) : "—"}
+                                </p>
+                            </div>
+                        </div>
+
+                        <div className="rounded-lg border">
+                            <Table>
+                                <TableHeader className="[&_th]:bg-muted/50">
+                                    <TableRow>
+                                        <TableHead>{t('schedule.date')}</TableHead>
+                                        <TableHead className="text-right">{t('schedule.payment')}</TableHead>
+                                        <TableHead className="text-right">{t('schedule.interest')}</TableHead>
+                                        <TableHead className="text-right">{t('schedule.principal')}</TableHead>
+                                        <TableHead className="text-right">{t('schedule.balance')}</TableHead>
+                                        <TableHead>{t('schedule.status')}</TableHead>
+                                    </TableRow>
+                                </TableHeader>
+                                <TableBody>
+                                    {schedule.installments.map((installment) => (
+                                        <TableRow
+                                            key={installment.date}
+                                            className={installment.status === "upcoming" ? undefined : "text-muted-foreground"}
+                                        >
+                                            <TableCell>{formatDate(new Date(installment.date * 1000))}</TableCell>
+                                            <TableCell className="text-right">{money(installment.payment)}</TableCell>
+                                            <TableCell className="text-right">{money(installment.interest)}</TableCell>
+                                            <TableCell className="text-right">{money(installment.principal)}</TableCell>
+                                            <TableCell className="text-right">{money(installment.balance)}</TableCell>
+                                            <TableCell>
+                                                <Badge variant={installment.status === "paid" ? "default" : "secondary"}>
+                                                    {t(`schedule.statuses.${installment.status}`)}
+                                                </Badge>
+                                            </TableCell>
+                                        </TableRow>
+                                    ))}
+                                </TableBody>
+                            </Table>
+                        </div>
+                    </div>
+                )}
+            </DialogContent>
+        </Dialog>
+    );
+}
@@ -15,8 +15,9 @@ import {
     DropdownMenuItem,
     DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
-import { MoreVertical, Pencil, Trash2, ArrowUpDown, ArrowUp, ArrowDown } from "lucide-react";
-import { Loan } from "@shared/schema";
+import { MoreVertical, Pencil, Trash2, ArrowUpDown, ArrowUp, ArrowDown, CalendarDays, Calculator } from "lucide-react";
+import { Loan, LoanSchedule } from "@shared/schema";
+import { outstandingCzk } from "@/hooks/use-loans";
 import { isAfter } from "date-fns";
 import { useCurrency } from "@/lib/currency";
 import { convertToCzK, type CurrencyCode } from "@shared/currencies";
@@ -25,11 +26,14 @@ import { useLanguage } from "@/i18n/I18nProvider";
 
 interface LoansTableProps {
     loans: Loan[];
+    schedules: Map<string, LoanSchedule>;
     onEdit: (loan: Loan) => void;
     onDelete: (loan: Loan) => void;
+    onShowSchedule: (loan: Loan) => void;
+    onSimulate: (loan: Loan) => void;
 }
 
-export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
+export function LoansTable({ loans, schedules, onEdit, onDelete, onShowSchedule, onSimulate }: LoansTableProps) {
     const { t } = useTranslation('loans');
     const { t: tc } = useTranslation('common');
     const { formatCurrency } = useCurrency();
@@ -37,7 +41,7 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
     const today = new Date();
 
     // Sorting state
-    type SortColumn = 'name' | 'principal' | 'interestRate' | 'monthlyPayment' | 'rateValidity' | 'endDate';
+    type SortColumn = 'name' | 'principal' | 'outstanding' | 'interestRate' | 'monthlyPayment' | 'rateValidity' | 'endDate';
     const [sortColumn, setSortColumn] = useState<SortColumn>('name');
     const [sortDirection, setSortDirection] = useState<'asc' | 'desc'>('asc');
 
@@ -69,6 +73,9 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
                 case 'principal':
                     comparison = Number(a.principal) - Number(b.principal);
                     break;
+                case 'outstanding':
+                    comparison = outstandingCzk(a, schedules.get(a.id)) - outstandingCzk(b, schedules.get(b.id));
+                    break;
                 case 'interestRate':
                     comparison = Number(a.interestRate) - Number(b.interestRate);
                     break;
@@ -84,7 +91,7 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
             }
             return sortDirection === 'asc' ? comparison : -comparison;
         });
-    }, [loans, sortColumn, sortDirection]);
+    }, [loans, schedules, sortColumn, sortDirection]);
 
 
     return (
@@ -102,6 +109,9 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
                                 <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('principal')}>
                                     <span className="flex items-center justify-end">{t('table.principal')}<SortIcon column="principal" /></span>
                                 </TableHead>
+                                <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('outstanding')}>
+                                    <span className="flex items-center justify-end">{t('table.outstanding')}<SortIcon column="outstanding" /></span>
+                                </TableHead>
                                 <TableHead className="text-right cursor-pointer select-none hover:bg-muted/50" onClick={() => handleSort('interestRate')}>
                                     <span className="flex items-center justify-end">{t('table.interestRate')}<SortIcon column="interestRate" /></span>
                                 </TableHead>
@@ -121,7 +131,7 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
                             {sortedLoans.length === 0 ? (
                                 <TableRow>
                                     <TableCell
-                                        colSpan={7}
+                                        colSpan={8}
                                         className="text-center text-muted-foreground py-8"
                                     >
                                         {t('table.noLoans')}
@@ -139,6 +149,9 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
                                             <TableCell className="text-right">
                                                 {formatCurrency(convertToCzK(Number(loan.principal), loan.currency as CurrencyCode))}
                                             </TableCell>
+                                            <TableCell className="text-right font-medium">
+                                                {formatCurrency(outstandingCzk(loan, schedules.get(loan.id)))}
+                                            </TableCell>
                                             <TableCell className="text-right">
                                                 {Number(loan.interestRate).toFixed(2)}%
                                             </TableCell>
@@ -163,6 +176,14 @@ export function LoansTable({ loans, onEdit, onDelete }: LoansTableProps) {
                                                         </Button>
                                                     </DropdownMenuTrigger>
                                                     <DropdownMenuContent align="end">
+                                                        <DropdownMenuItem onClick={() => onShowSchedule(loan)}>
+                                                            <CalendarDays className="mr-2 h-4 w-4" />
+                                                            {t('actions.schedule')}
+                                                        </DropdownMenuItem>
+                                                        <DropdownMenuItem onClick={() => onSimulate(loan)}>
+                                                            <Calculator className="mr-2 h-4 w-4" />
+                                                            {t('actions.simulate')}
+                                                        </DropdownMenuItem>
                                                         <DropdownMenuItem onClick={() => onEdit(loan)}>
                                                             <Pencil className="mr-2 h-4 w-4" />
                                                             {tc('buttons.edit')}
@@ -60,6 +60,7 @@ export function AddRealEstateModal({ realEstate, trigger }: AddRealEstateModalPr
             type: realEstate?.type || "personal",
             purchasePrice: realEstate?.purchasePrice?.toString() || "0",
             purchasePriceCurrency: (realEstate as any)?.purchasePriceCurrency || userCurrency,
+            purchaseDate: realEstate?.purchaseDate ?? null,
             marketPrice: realEstate?.marketPrice?.toString() || "0",
             marketPriceCurrency: (realEstate as any)?.marketPriceCurrency || userCurrency,
             monthlyRent: realEstate?.monthlyRent?.toString() || "0",
@@ -82,6 +83,7 @@ export function AddRealEstateModal({ realEstate, trigger }: AddRealEstateModalPr
                 type: realEstate.type,
                 purchasePrice: realEstate.purchasePrice.toString(),
                 purchasePriceCurrency: (realEstate as any).purchasePriceCurrency || userCurrency,
+                purchaseDate: realEstate.purchaseDate ?? null,
                 marketPrice: realEstate.marketPrice.toString(),
                 marketPriceCurrency: (realEstate as any).marketPriceCurrency || userCurrency,
                 monthlyRent: realEstate.monthlyRent?.toString() || "0",
@@ -100,6 +102,7 @@ export function AddRealEstateModal({ realEstate, trigger }: AddRealEstateModalPr
                 type: "personal",
                 purchasePrice: "0",
                 purchasePriceCurrency: userCurrency,
+                purchaseDate: null,
                 marketPrice: "0",
                 marketPriceCurrency: userCurrency,
                 monthlyRent: "0",
@@ -223,6 +226,8 @@ export function AddRealEstateModal({ realEstate, trigger }: AddRealEstateModalPr
                 queryClient.invalidateQueries({ queryKey: ["real-estate", realEstate.id] });
                 queryClient.invalidateQueries({ queryKey: ["real-estate-loans", realEstate.id] });
                 queryClient.invalidateQueries({ queryKey: ["real-estate-insurances", realEstate.id] });
+                queryClient.invalidateQueries({ queryKey: ["real-estate-valuations", realEstate.id] });
+                queryClient.invalidateQueries({ queryKey: ["real-estate-value-history", realEstate.id] });
             }
             queryClient.invalidateQueries({ queryKey: ["available-insurances"] });
             setOpen(false);
@@ -367,6 +372,24 @@ export function AddRealEstateModal({ realEstate, trigger }: AddRealEstateModalPr
                                     />
                                 </div>
 
+                                <FormField
+                                    control={form.control}
+                                    name="purchaseDate"
+                                    render={({ field }) => (
+                                        <FormItem>
+                                            <FormLabel>{t('form.purchaseDate')}</FormLabel>
+                                            <FormControl>
+                                                <Input
+                                                    type="date"
+                                                    value={field.value ? new Date(field.value * 1000).toISOString().split('T')[0] : ''}
+                                                    onChange={(e) => field.onChange(e.target.valueAsDate ? Math.floor(e.target.valueAsDate.getTime() / 1000) : null)}
+                                                />
+                                            </FormControl>
+                                            <FormMessage />
+                                        </FormItem>
+                                    )}
+                                />
+
                                 <div className="grid grid-cols-3 gap-4">
                                     <FormField
                                         control={form.control}
@@ -0,0 +1,152 @@
+import { useState } from "react";
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import type { TransactionQueryResult } from "@shared/schema";
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle,
+    DialogTrigger,
+} from "@/components/ui/dialog";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import { Link2, Loader2 } from "lucide-react";
+import { toast } from "sonner";
+import { bankAccountsApi, realEstateApi } from "@/lib/tauri-api";
+import { useTranslation } from "react-i18next";
+import { useLanguage } from "@/i18n/I18nProvider";
+import { useBankAccounts } from "@/hooks/use-bank-accounts";
+
+interface LinkTransactionsModalProps {
+    realEstateId: string;
+    linkedIds: string[];
+}
+
+export function LinkTransactionsModal({ realEstateId, linkedIds }: LinkTransactionsModalProps) {
+    const { t } = useTranslation('realEstate');
+    const { t: tc } = useTranslation('common');
+    const { formatDate } = useLanguage();
+    const queryClient = useQueryClient();
+    const [open, setOpen] = useState(false);
+    const [accountId, setAccountId] = useState<string>("");
+    const [search, setSearch] = useState("");
+    const [selectedIds, setSelectedIds] = useState<string[]>([]);
+
+    const { accounts } = useBankAccounts();
+
+    const { data: result, isLoading } = useQuery<TransactionQueryResult>({
+        queryKey: ["bank-transactions", accountId, { search, limit: 50 }],
+        queryFn: () => bankAccountsApi.getTransactions(accountId, { search: search || undefined, limit: 50 }),
+        enabled: open && !!accountId,
+    });
+
+    const transactions = (result?.transactions || []).filter((tx) => !linkedIds.includes(tx.id));
+
+    const linkMutation = useMutation({
+        mutationFn: () => realEstateApi.linkTransactions(realEstateId, selectedIds),
+        onSuccess: () => {
+            queryClient.invalidateQueries({ queryKey: ["real-estate-transactions", realEstateId] });
+            queryClient.invalidateQueries({ queryKey: ["real-estate-performance", realEstateId] });
+            setOpen(false);
+            setSelectedIds([]);
+            toast(tc('status.success'), { description: t('performance.transactionsLinked') });
+        },
+        onError: (error) => {
+            toast.error(tc('status.error'), { description: error.message });
+        },
+    });
+
+    const toggle = (id: string, checked: boolean) => {
+        setSelectedIds((ids) => checked ? [...ids, id] : ids.filter((selected) => selected !== id));
+    };
+
+    return (
+        <Dialog open={open} onOpenChange={setOpen}>
+            <DialogTrigger asChild>
+                <Button size="sm">
+                    <Link2 className="mr-2 h-4 w-4" /> {t('performance.linkTransactions')}
+                </Button>
+            </DialogTrigger>
+            <DialogContent className="max-w-2xl max-h-[90vh] overflow-y-auto">
+                <DialogHeader>
+                    <DialogTitle>{t('performance.linkTransactions')}</DialogTitle>
+                    <DialogDescription>{t('performance.linkDescription')}</DialogDescription>
+                </DialogHeader>
+                <div className="grid grid-cols-2 gap-4">
+                    <div className="space-y-2">
+                        <Label>{t('performance.account')}</Label>
+                        <Select value={accountId} onValueChange={setAccountId}>
+                            <SelectTrigger>
+                                <SelectValue placeholder={t('performance.selectAccount')} />
+                            </SelectTrigger>
+                            <SelectContent>
+                                {accounts.map((account) => (
+                                    <SelectItem key={account.id} value={account.id}>
+                                        {account.name}
+                                    </SelectItem>
+                                ))}
+                            </SelectContent>
+                        </Select>
+                    </div>
+                    <div className="space-y-2">
+                        <Label>{tc('labels.description')}</Label>
+                        <Input
+                            value={search}
+                            onChange={(e) => setSearch(e.target.value)}
+                            placeholder={t('performance.searchPlaceholder')}
+                        />
+                    </div>
+                </div>
+                <div className="space-y-1 max-h-[320px] overflow-y-auto">
+                    {isLoading && accountId ? (
+                        <p className="text-sm text-muted-foreground">{t('loading')}</p>
+                    ) : transactions.length > 0 ? (
+                        transactions.map((tx) => (
+                            <label
+                                key={tx.id}
+                                className="flex items-center gap-3 rounded-md border p-2 text-sm hover:bg-muted/50 cursor-pointer"
+                            >
+                                <Checkbox
+                                    checked={selectedIds.includes(tx.id)}
+                                    onCheckedChange={(checked) => toggle(tx.id, checked === true)}
+                                />
+                                <span className="w-24 text-muted-foreground">{formatDate(new Date(tx.bookingDate * 1000))}</span>
+                                <span className="flex-1 truncate">{tx.counterpartyName || tx.description}</span>
+                                <span className={tx.type === 'credit' ? 'text-green-600' : 'text-red-600'}>
+                                    {tx.type === 'credit' ? '+' : '-'}{Math.abs(Number(tx.amount)).toLocaleString()} {tx.currency}
+                                </span>
+                            </label>
+                        ))
+                    ) : (
+                        <p className="text-sm text-muted-foreground text-center py-6">
+                            {accountId ? t('performance.noTransactionsFound') : t('performance.selectAccount')}
+                        </p>
+                    )}
+                </div>
+                <DialogFooter>
+                    <Button type="button" variant="outline" onClick={() => setOpen(false)}>
+                        {tc('buttons.cancel')}
+                    </Button>
+                    <Button
+                        onClick={() => linkMutation.mutate()}
+                        disabled={selectedIds.length === 0 || linkMutation.isPending}
+                    >
+                        {linkMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                        {t('performance.linkSelected', { count: selectedIds.length })}
+                    </Button>
+                </DialogFooter>
+            </DialogContent>
+        </Dialog>
+    );
+}
@@ -0,0 +1,239 @@
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { realEstateApi } from "@/lib/tauri-api";
+import type { BankTransaction, PerformanceSource, RentalPerformance } from "@shared/schema";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Badge } from "@/components/ui/badge";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Unlink } from "lucide-react";
+import { toast } from "sonner";
+import { useTranslation } from "react-i18next";
+import { convertToCzK, type CurrencyCode } from "@shared/currencies";
+import { useCurrency } from "@/lib/currency";
+import { useLanguage } from "@/i18n/I18nProvider";
+import { LinkTransactionsModal } from "./LinkTransactionsModal";
+
+interface RealEstatePerformanceProps {
+    realEstateId: string;
+}
+
+export function RealEstatePerformance({ realEstateId }: RealEstatePerformanceProps) {
+    const { t } = useTranslation('realEstate');
+    const { t: tc } = useTranslation('common');
+    const queryClient = useQueryClient();
+    const { formatCurrency } = useCurrency();
+    const { formatDate } = useLanguage();
+
+    const { data: performance } = useQuery<RentalPerformance>({
+        queryKey: ["real-estate-performance", realEstateId],
+        queryFn: () => realEstateApi.getPerformance(realEstateId),
+    });
+
+    const { data: transactions } = useQuery<BankTransaction[]>({
+        queryKey: ["real-estate-transactions", realEstateId],
+        queryFn: () => realEstateApi.getTransactions(realEstateId),
+    });
+
+    const unlinkMutation = useMutation({
+        mutationFn: (transactionId: string) => realEstateApi.unlinkTransaction(realEstateId, transactionId),
+        onSuccess: () => {
+            queryClient.invalidateQueries({ queryKey: ["real-estate-transactions", realEstateId] });
+            queryClient.invalidateQueries({ queryKey: ["real-estate-performance", realEstateId] });
+            toast(tc('status.success'), { description: t('performance.transactionsUnlinked') });
+        },
+        onError: (error) => {
+            toast.error(tc('status.error'), { description: error.message });
+        },
+    });
+
+    // Performance amounts are in the property currency; show them in CZK like the rest of the page
+    const currency = (performance?.currency || "CZK") as CurrencyCode;
+    const money = (amount: string | undefined) =>
+        amount === undefined ? "—" : formatCurrency(convertToCzK(Number(amount), currency));
+
+    const formatPercent = (value: number | null | undefined) =>
+        value === null || value === undefined ? "—" : `${value.toFixed(2)}%`;
+
+    const sourceBadge = (source: PerformanceSource | undefined) =>
+        source && (
+            <Badge
+                variant={source === 'actual' ? 'default' : source === 'mixed' ? 'secondary' : 'outline'}
+                className="ml-2"
+            >
+                {t(`performance.sources.${source}`)}
+            </Badge>
+        );
+
+    const ratios = [
+        { label: t('performance.grossYield'), value: performance?.grossYieldPercent, hint: t('performance.grossYieldHint') },
+        { label: t('performance.netYield'), value: performance?.netYieldPercent, hint: t('performance.netYieldHint') },
+        { label: t('performance.capRate'), value: performance?.capRatePercent, hint: t('performance.capRateHint') },
+        { label: t('performance.cashOnCash'), value: performance?.cashOnCashPercent, hint: t('performance.cashOnCashHint') },
+        { label: t('performance.totalRoi'), value: performance?.totalRoiPercent, hint: t('performance.totalRoiHint') },
+    ];
+
+    const cashFlow = performance ? Number(performance.annualCashFlow) : 0;
+
+    return (
+        <div className="space-y-4">
+            <div className="grid gap-4 md:grid-cols-5">
+                {ratios.map((ratio) => (
+                    <Card key={ratio.label} className="card-hover">
+                        <CardHeader className="pb-2">
+                            <CardTitle className="text-sm font-medium">{ratio.label}</CardTitle>
+                        </CardHeader>
+                        <CardContent>
+                            <div className={`text-2xl font-bold ${(ratio.value ?? 0) < 0 ? 'text-red-600' : ''}`}>
+                                {formatPercent(ratio.value)}
+                            </div>
+                            <p className="text-xs text-muted-foreground">{ratio.hint}</p>
+                        </CardContent>
+                    </Card>
+                ))}
+            </div>
+
+            <div className="grid gap-4 md:grid-cols-2">
+                <Card className="card-hover">
+                    <CardHeader>
+                        <CardTitle>{t('performance.incomeTitle')}</CardTitle>
+                        <CardDescription>{t('performance.incomeDescription')}</CardDescription>
+                    </CardHeader>
+                    <CardContent className="space-y-2 text-sm">
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">
+                                {t('performance.annualRent')}{sourceBadge(performance?.rentSource)}
+                            </span>
+                            <span className="font-medium text-green-600">{money(performance?.annualRent)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">
+                                {t('performance.operatingCosts')}{sourceBadge(performance?.costsSource)}
+                            </span>
+                            <span className="font-medium text-red-600">-{money(performance?.operatingCosts)}</span>
+                        </div>
+                        <div className="flex justify-between border-t pt-2">
+                            <span className="text-muted-foreground">{t('performance.noi')}</span>
+                            <span className="font-medium">{money(performance?.netOperatingIncome)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">
+                                {t('performance.debtService')}{sourceBadge(performance?.debtSource)}
+                            </span>
+                            <span className="font-medium text-red-600">-{money(performance?.debtService)}</span>
+                        </div>
+                        <div className="flex justify-between border-t pt-2">
+                            <span className="font-medium">{t('performance.cashFlow')}</span>
+                            <span className={`font-bold ${cashFlow >= 0 ? 'text-green-600' : 'text-red-600'}`}>
+                                {money(performance?.annualCashFlow)}
+                            </span>
+                        </div>
+                    </CardContent>
+                </Card>
+
+                <Card className="card-hover">
+                    <CardHeader>
+                        <CardTitle>{t('performance.investmentTitle')}</CardTitle>
+                        <CardDescription>{t('performance.investmentDescription')}</CardDescription>
+                    </CardHeader>
+                    <CardContent className="space-y-2 text-sm">
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">{t('performance.totalCost')}</span>
+                            <span className="font-medium">{money(performance?.totalCost)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">{t('performance.cashInvested')}</span>
+                            <span className="font-medium">{money(performance?.cashInvested)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">{t('detail.equity')}</span>
+                            <span className="font-medium">{money(performance?.equity)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">
+                                {t('performance.cumulativeCashFlow', { years: (performance?.yearsHeld ?? 0).toFixed(1) })}
+                            </span>
+                            <span className="font-medium">{money(performance?.cumulativeCashFlow)}</span>
+                        </div>
+                        <div className="flex justify-between border-t pt-2">
+                            <span className="text-muted-foreground">{t('performance.breakEvenRent')}</span>
+                            <span className="font-medium">{money(performance?.breakEvenMonthlyRent)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">{t('performance.breakEvenOccupancy')}</span>
+                            <span className="font-medium">{formatPercent(performance?.breakEvenOccupancyPercent)}</span>
+                        </div>
+                        <div className="flex justify-between">
+                            <span className="text-muted-foreground">{t('performance.payback')}</span>
+                            <span className="font-medium">
+                                {performance?.paybackYears == null
+                                    ? "—"
+                                    : t('performance.years', { count: Number(performance.paybackYears.toFixed(1)) })}
+                            </span>
+                        </div>
+                    </CardContent>
+                </Card>
+            </div>
+
+            <Card className="card-hover">
+                <CardHeader className="flex flex-row items-center justify-between">
+                    <div>
+                        <CardTitle>{t('performance.transactionsTitle')}</CardTitle>
+                        <CardDescription>{t('performance.transactionsDescription')}</CardDescription>
+                    </div>
+                    <LinkTransactionsModal
+                        realEstateId={realEstateId}
+                        linkedIds={(transactions || []).map((tx) => tx.id)}
+                    />
+                </CardHeader>
+                <CardContent>
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHead>{tc('labels.date')}</TableHead>
+                                <TableHead>{tc('labels.description')}</TableHead>
+                                <TableHead className="text-right">{tc('labels.amount')}</TableHead>
+                                <TableHead className="w-[50px]"></TableHead>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            {transactions?.map((tx) => (
+                                <TableRow key={tx.id}>
+                                    <TableCell>{formatDate(new Date(tx.bookingDate * 1000))}</TableCell>
+                                    <TableCell>{tx.counterpartyName || tx.description}</TableCell>
+                                    <TableCell className={`text-right ${tx.type === 'credit' ? 'text-green-600' : 'text-red-600'}`}>
+                                        {tx.type === 'credit' ? '+' : '-'}
+                                        {formatCurrency(convertToCzK(Math.abs(Number(tx.amount)), tx.currency as CurrencyCode))}
+                                    </TableCell>
+                                    <TableCell>
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            className="h-8 w-8 text-destructive"
+                                            onClick={() => unlinkMutation.mutate(tx.id)}
+                                        >
+                                            <Unlink className="h-4 w-4" />
+                                        </Button>
+                                    </TableCell>
+                                </TableRow>
+                            ))}
+                            {(!transactions || transactions.length === 0) && (
+                                <TableRow>
+                                    <TableCell colSpan={4} className="text-center text-muted-foreground h-24">
+                                        {t('performance.noLinkedTransactions')}
+                                    </TableCell>
+                                </TableRow>
+                            )}
+                        </TableBody>
+                    </Table>
+                </CardContent>
+            </Card>
+        </div>
+    );
+}
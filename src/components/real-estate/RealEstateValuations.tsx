@@ -0,0 +1,228 @@
+import { useMemo } from "react";
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { ComposedChart, Area, Line, XAxis, YAxis, CartesianGrid, Tooltip, Legend, ResponsiveContainer } from "recharts";
+import { realEstateApi } from "@/lib/tauri-api";
+import type { RealEstateValuation, RealEstateValueHistory } from "@shared/schema";
+import { Card, CardContent, CardHeader, CardTitle, CardDescription } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import {
+    Table,
+    TableBody,
+    TableCell,
+    TableHead,
+    TableHeader,
+    TableRow,
+} from "@/components/ui/table";
+import { Trash2 } from "lucide-react";
+import { toast } from "sonner";
+import { useTranslation } from "react-i18next";
+import { convertToCzK, type CurrencyCode } from "@shared/currencies";
+import { useCurrency } from "@/lib/currency";
+import { useLanguage } from "@/i18n/I18nProvider";
+import { ValuationModal } from "./ValuationModal";
+
+interface RealEstateValuationsProps {
+    realEstateId: string;
+    currency: string;
+}
+
+export function RealEstateValuations({ realEstateId, currency }: RealEstateValuationsProps) {
+    const { t } = useTranslation('realEstate');
+    const { t: tc } = useTranslation('common');
+    const queryClient = useQueryClient();
+    const { formatCurrency, formatCurrencyShort } = useCurrency();
+    const { formatDate } = useLanguage();
+
+    const { data: history } = useQuery<RealEstateValueHistory>({
+        queryKey: ["real-estate-value-history", realEstateId],
+        queryFn: () => realEstateApi.getValueHistory(realEstateId),
+    });
+
+    const { data: valuations } = useQuery<RealEstateValuation[]>({
+        queryKey: ["real-estate-valuations", realEstateId],
+        queryFn: () => realEstateApi.getValuations(realEstateId),
+    });
+
+    const deleteMutation = useMutation({
+        mutationFn: (valuationId: string) => realEstateApi.deleteValuation(valuationId),
+        onSuccess: () => {
+            queryClient.invalidateQueries({ queryKey: ["real-estate-valuations", realEstateId] });
+            queryClient.invalidateQueries({ queryKey: ["real-estate-value-history", realEstateId] });
+            queryClient.invalidateQueries({ queryKey: ["real-estate"] });
+            queryClient.invalidateQueries({ queryKey: ["portfolio-metrics"] });
+            toast(tc('status.success'), { description: t('toast.valuationDeleted') });
+        },
+        onError: (error) => {
+            toast.error(tc('status.error'), { description: error.message });
+        },
+    });
+
+    // History amounts are in the property currency; chart them in CZK like the rest of the page
+    const historyCurrency = (history?.currency || currency) as CurrencyCode;
+    const toCzk = (amount: number | string) => convertToCzK(Number(amount), historyCurrency);
+
+    const chartData = useMemo(() => (history?.points || []).map((point) => ({
+        date: formatDate(new Date(point.date * 1000), { month: 'short', year: '2-digit' }),
+        value: convertToCzK(point.value, historyCurrency),
+        equity: convertToCzK(point.equity, historyCurrency),
+    })), [history, historyCurrency, formatDate]);
+
+    const formatPercent = (value: number | null | undefined) =>
+        value === null || value === undefined ? "—" : `${value >= 0 ? '+' : ''}${value.toFixed(2)}%`;
+
+    const appreciation = history ? Number(history.appreciation) : 0;
+
+    return (
+        <div className="space-y-4">
+            <div className="grid gap-4 md:grid-cols-3">
+                <Card className="card-hover">
+                    <CardHeader className="pb-2">
+                        <CardTitle className="text-sm font-medium">{t('valuations.appreciation')}</CardTitle>
+                    </CardHeader>
+                    <CardContent>
+                        <div className={`text-2xl font-bold ${appreciation >= 0 ? 'text-green-600' : 'text-red-600'}`}>
+                            {history ? formatCurrency(toCzk(history.appreciation)) : "—"}
+                        </div>
+                        <p className="text-xs text-muted-foreground">
+                            {formatPercent(history?.appreciationPercent)} {t('valuations.sincePurchase')}
+                        </p>
+                    </CardContent>
+                </Card>
+                <Card className="card-hover">
+                    <CardHeader className="pb-2">
+                        <CardTitle className="text-sm font-medium">{t('valuations.annualized')}</CardTitle>
+                    </CardHeader>
+                    <CardContent>
+                        <div className="text-2xl font-bold">{formatPercent(history?.annualizedAppreciationPercent)}</div>
+                        <p className="text-xs text-muted-foreground">
+                            {history?.purchaseDate
+                                ? t('valuations.purchasedOn', { date: formatDate(new Date(history.purchaseDate * 1000)) })
+                                : t('valuations.noPurchaseDate')}
+                        </p>
+                    </CardContent>
+                </Card>
+                <Card className="card-hover">
+                    <CardHeader className="pb-2">
+                        <CardTitle className="text-sm font-medium">{t('detail.equity')}</CardTitle>
+                    </CardHeader>
+                    <CardContent>
+                        <div className="text-2xl font-bold">{history ? formatCurrency(toCzk(history.equity)) : "—"}</div>
+                        <p className="text-xs text-muted-foreground">
+                            {t('valuations.loanBalance')}: {history ? formatCurrency(toCzk(history.loanBalance)) : "—"}
+                        </p>
+                    </CardContent>
+                </Card>
+            </div>
+
+            <Card className="card-hover">
+                <CardHeader>
+                    <CardTitle>{t('valuations.chartTitle')}</CardTitle>
+                    <CardDescription>{t('valuations.chartDescription')}</CardDescription>
+                </CardHeader>
+                <CardContent>
+                    {chartData.length > 1 ? (
+                        <ResponsiveContainer width="100%" height={300}>
+                            <ComposedChart data={chartData} margin={{ top: 10, right: 10, left: 10, bottom: 20 }}>
+                                <defs>
+                                    <linearGradient id="colorValue-real-estate" x1="0" y1="0" x2="0" y2="1">
+                                        <stop offset="5%" stopColor="hsl(var(--chart-1))" stopOpacity={0.2} />
+                                        <stop offset="95%" stopColor="hsl(var(--chart-1))" stopOpacity={0} />
+                                    </linearGradient>
+                                </defs>
+                                <CartesianGrid strokeDasharray="3 3" stroke="hsl(var(--border))" vertical={false} />
+                                <XAxis
+                                    dataKey="date"
+                                    stroke="hsl(var(--muted-foreground))"
+                                    tick={{ fill: 'hsl(var(--muted-foreground))', fontSize: 11, fontWeight: 600 }}
+                                    tickLine={false}
+                                    axisLine={false}
+                                    minTickGap={24}
+                                    tickMargin={8}
+                                />
+                                <YAxis
+                                    tickFormatter={(value: number) => formatCurrencyShort(value)}
+                                    tick={{ fill: 'hsl(var(--muted-foreground))', fontSize: 11 }}
+                                    tickLine={false}
+                                    axisLine={false}
+                                    width={70}
+                                />
+                                <Tooltip formatter={(value: number) => formatCurrency(value)} />
+                                <Legend />
+                                <Area
+                                    type="stepAfter"
+                                    dataKey="value"
+                                    name={t('valuations.value')}
+                                    stroke="hsl(var(--chart-1))"
+                                    strokeWidth={2}
+                                    fill="url(#colorValue-real-estate)"
+                                />
+                                <Line
+                                    type="monotone"
+                                    dataKey="equity"
+                                    name={t('detail.equity')}
+                                    stroke="hsl(var(--chart-2))"
+                                    strokeWidth={2}
+                                    dot={false}
+                                />
+                            </ComposedChart>
+                        </ResponsiveContainer>
+                    ) : (
+                        <p className="text-sm text-muted-foreground text-center py-8">{t('valuations.noHistory')}</p>
+                    )}
+                </CardContent>
+            </Card>
+
+            <Card className="card-hover">
+                <CardHeader className="flex flex-row items-center justify-between">
+                    <div>
+                        <CardTitle>{t('valuations.title')}</CardTitle>
+                        <CardDescription>{t('valuations.description')}</CardDescription>
+                    </div>
+                    <ValuationModal realEstateId={realEstateId} currency={currency} />
+                </CardHeader>
+                <CardContent>
+                    <Table>
+                        <TableHeader>
+                            <TableRow>
+                                <TableHead>{tc('labels.date')}</TableHead>
+                                <TableHead>{t('valuations.source')}</TableHead>
+                                <TableHead>{t('modal.valuation.notes')}</TableHead>
+                                <TableHead className="text-right">{t('valuations.value')}</TableHead>
+                                <TableHead className="w-[50px]"></TableHead>
+                            </TableRow>
+                        </TableHeader>
+                        <TableBody>
+                            {[...(valuations || [])].reverse().map((valuation) => (
+                                <TableRow key={valuation.id}>
+                                    <TableCell>{formatDate(new Date(valuation.valuationDate * 1000))}</TableCell>
+                                    <TableCell>{t(`valuations.sources.${valuation.source}`)}</TableCell>
+                                    <TableCell>{valuation.notes}</TableCell>
+                                    <TableCell className="text-right">
+                                        {formatCurrency(convertToCzK(Number(valuation.value), valuation.currency as CurrencyCode))}
+                                    </TableCell>
+                                    <TableCell>
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            className="h-8 w-8 text-destructive"
+                                            onClick={() => deleteMutation.mutate(valuation.id)}
+                                        >
+                                            <Trash2 className="h-4 w-4" />
+                                        </Button>
+                                    </TableCell>
+                                </TableRow>
+                            ))}
+                            {(!valuations || valuations.length === 0) && (
+                                <TableRow>
+                                    <TableCell colSpan={5} className="text-center text-muted-foreground h-24">
+                                        {t('valuations.noValuations')}
+                                    </TableCell>
+                                </TableRow>
+                            )}
+                        </TableBody>
+                    </Table>
+                </CardContent>
+            </Card>
+        </div>
+    );
+}
@@ -0,0 +1,217 @@
+import { useState } from "react";
+import { useForm } from "react-hook-form";
+import { zodResolver } from "@hookform/resolvers/zod";
+import { useMutation, useQueryClient } from "@tanstack/react-query";
+import { insertRealEstateValuationSchema, type InsertRealEstateValuation } from "@shared/schema";
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogFooter,
+    DialogHeader,
+    DialogTitle,
+    DialogTrigger,
+} from "@/components/ui/dialog";
+import {
+    Form,
+    FormControl,
+    FormField,
+    FormItem,
+    FormLabel,
+    FormMessage,
+} from "@/components/ui/form";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Plus, Loader2 } from "lucide-react";
+import { FormSection } from "@/components/ui/form-section";
+import { toast } from "sonner";
+import { Textarea } from "@/components/ui/textarea";
+import {
+    Select,
+    SelectContent,
+    SelectItem,
+    SelectTrigger,
+    SelectValue,
+} from "@/components/ui/select";
+import { currencies } from "@/lib/currency";
+import { realEstateApi } from "@/lib/tauri-api";
+import { useTranslation } from "react-i18next";
+
+const VALUATION_SOURCES = ['appraisal', 'index', 'listing', 'manual'] as const;
+
+interface ValuationModalProps {
+    realEstateId: string;
+    currency: string;
+}
+
+export function ValuationModal({ realEstateId, currency }: ValuationModalProps) {
+    const { t } = useTranslation('realEstate');
+    const { t: tc } = useTranslation('common');
+    const [open, setOpen] = useState(false);
+    const queryClient = useQueryClient();
+
+    const defaultValues: InsertRealEstateValuation = {
+        realEstateId,
+        valuationDate: new Date(),
+        value: "",
+        currency,
+        source: "appraisal",
+        notes: "",
+    };
+
+    const form = useForm<InsertRealEstateValuation>({
+        resolver: zodResolver(insertRealEstateValuationSchema),
+        defaultValues,
+    });
+
+    const addMutation = useMutation({
+        mutationFn: (data: InsertRealEstateValuation) =>
+            realEstateApi.addValuation({
+                ...data,
+                realEstateId,
+                valuationDate: Math.floor(new Date(data.valuationDate).getTime() / 1000),
+            }),
+        onSuccess: () => {
+            queryClient.invalidateQueries({ queryKey: ["real-estate-valuations", realEstateId] });
+            queryClient.invalidateQueries({ queryKey: ["real-estate-value-history", realEstateId] });
+            queryClient.invalidateQueries({ queryKey: ["real-estate"] });
+            queryClient.invalidateQueries({ queryKey: ["portfolio-metrics"] });
+            setOpen(false);
+            form.reset(defaultValues);
+            toast(tc('status.success'), { description: t('toast.valuationAdded') });
+        },
+        onError: (error) => {
+            toast.error(tc('status.error'), { description: error.message });
+        },
+    });
+
+    return (
+        <Dialog open={open} onOpenChange={setOpen}>
+            <DialogTrigger asChild>
+                <Button size="sm">
+                    <Plus className="mr-2 h-4 w-4" /> {t('modal.valuation.add')}
+                </Button>
+            </DialogTrigger>
+            <DialogContent className="max-w-xl max-h-[90vh] overflow-y-auto">
+                <DialogHeader>
+                    <DialogTitle>{t('modal.valuation.title')}</DialogTitle>
+                    <DialogDescription>{t('modal.valuation.description')}</DialogDescription>
+                </DialogHeader>
+                <Form {...form}>
+                    <form id="valuation-form" onSubmit={form.handleSubmit((data) => addMutation.mutate(data))} className="space-y-6">
+                        <FormSection title={t('modal.valuation.details')} first>
+                            <div className="grid gap-4">
+                                <div className="grid grid-cols-3 gap-4">
+                                    <FormField
+                                        control={form.control}
+                                        name="value"
+                                        render={({ field }) => (
+                                            <FormItem className="col-span-2">
+                                                <FormLabel>{t('modal.valuation.value')}</FormLabel>
+                                                <FormControl>
+                                                    <Input type="number" step="0.01" {...field} />
+                                                </FormControl>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                    <FormField
+                                        control={form.control}
+                                        name="currency"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{tc('labels.currency')}</FormLabel>
+                                                <Select onValueChange={field.onChange} value={field.value}>
+                                                    <FormControl>
+                                                        <SelectTrigger>
+                                                            <SelectValue />
+                                                        </SelectTrigger>
+                                                    </FormControl>
+                                                    <SelectContent>
+                                                        {currencies.map((c: { code: string }) => (
+                                                            <SelectItem key={c.code} value={c.code}>
+                                                                {c.code}
+                                                            </SelectItem>
+                                                        ))}
+                                                    </SelectContent>
+                                                </Select>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                </div>
+
+                                <div className="grid grid-cols-2 gap-4">
+                                    <FormField
+                                        control={form.control}
+                                        name="valuationDate"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{t('modal.valuation.date')}</FormLabel>
+                                                <FormControl>
+                                                    <Input
+                                                        type="date"
+                                                        value={field.value ? new Date(field.value).toISOString().split('T')[0] : ''}
+                                                        onChange={(e) => field.onChange(e.target.valueAsDate)}
+                                                    />
+                                                </FormControl>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                    <FormField
+                                        control={form.control}
+                                        name="source"
+                                        render={({ field }) => (
+                                            <FormItem>
+                                                <FormLabel>{t('modal.valuation.source')}</FormLabel>
+                                                <Select onValueChange={field.onChange} value={field.value}>
+                                                    <FormControl>
+                                                        <SelectTrigger>
+                                                            <SelectValue />
+                                                        </SelectTrigger>
+                                                    </FormControl>
+                                                    <SelectContent>
+                                                        {VALUATION_SOURCES.map((source) => (
+                                                            <SelectItem key={source} value={source}>
+                                                                {t(`valuations.sources.${source}`)}
+                                                            </SelectItem>
+                                                        ))}
+                                                    </SelectContent>
+                                                </Select>
+                                                <FormMessage />
+                                            </FormItem>
+                                        )}
+                                    />
+                                </div>
+                            </div>
+
+                            <FormField
+                                control={form.control}
+                                name="notes"
+                                render={({ field }) => (
+                                    <FormItem>
+                                        <FormLabel>{t('modal.valuation.notes')}</FormLabel>
+                                        <FormControl>
+                                            <Textarea placeholder={t('modal.valuation.notesPlaceholder')} {...field} value={field.value || ""} />
+                                        </FormControl>
+                                        <FormMessage />
+                                    </FormItem>
+                                )}
+                            />
+                        </FormSection>
+                    </form>
+                </Form>
+                <DialogFooter>
+                    <Button type="button" variant="outline" onClick={() => setOpen(false)}>
+                        {tc('buttons.cancel')}
+                    </Button>
+                    <Button type="submit" form="valuation-form" disabled={addMutation.isPending}>
+                        {addMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                        {t('modal.valuation.add')}
+                    </Button>
+                </DialogFooter>
+            </DialogContent>
+        </Dialog>
+    );
+}
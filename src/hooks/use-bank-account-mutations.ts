@@ -87,6 +87,8 @@ export function useBankAccountMutations() {
       queryClient.invalidateQueries({ queryKey: ["bank-accounts"] });
       queryClient.invalidateQueries({ queryKey: ["portfolio-metrics"] });
       queryClient.invalidateQueries({ queryKey: ["cashflow-report"] });
+      queryClient.invalidateQueries({ queryKey: ["interest-accruals"] });
+      queryClient.invalidateQueries({ queryKey: ["interest-calendar"] });
       // Record new portfolio snapshot to update dashboard history
       await portfolioApi.recordSnapshot();
       queryClient.invalidateQueries({ queryKey: ["portfolio-history"] });
@@ -35,7 +35,7 @@ export function useBankAccounts() {
   }, 0);
 
   const savingsBalance = includedAccounts
-    .filter((item: BankAccountWithInstitution) => item.accountType === 'savings')
+    .filter((item: BankAccountWithInstitution) => item.accountType === 'savings' || item.accountType === 'term_deposit')
     .reduce((sum, item: BankAccountWithInstitution) => {
       const balance = parseFloat(item.balance || '0');
       const currency = item.currency || 'CZK';
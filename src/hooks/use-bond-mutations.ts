@@ -12,10 +12,19 @@ export interface UpdateBondData {
   couponValue?: string;
   interestRate?: string;
   maturityDate?: number | Date;
+  couponFrequency?: InsertBond['couponFrequency'];
+  dayCount?: InsertBond['dayCount'];
+  issueDate?: number | null;
+  firstCouponDate?: number | null;
+  purchasePrice?: string | null;
+  purchaseDate?: number | null;
+  marketPrice?: string | null;
+  priceTicker?: string | null;
 }
 
 export function useBondMutations() {
   const { t } = useTranslation('common');
+  const { t: tb } = useTranslation('bonds');
 
   const createMutation = useMutation({
     mutationFn: async (data: InsertBond & { maturityDate?: Date | number | null }) => {
@@ -31,6 +40,9 @@ export function useBondMutations() {
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['bonds'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-schedule'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-cash-flows'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-analytics'] });
       queryClient.invalidateQueries({ queryKey: ['portfolio-metrics'] });
       queryClient.invalidateQueries({ queryKey: ['projection'] });
       queryClient.invalidateQueries({ queryKey: ['cashflow-report'] });
@@ -55,6 +67,9 @@ export function useBondMutations() {
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['bonds'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-schedule'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-cash-flows'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-analytics'] });
       queryClient.invalidateQueries({ queryKey: ['portfolio-metrics'] });
       queryClient.invalidateQueries({ queryKey: ['projection'] });
       queryClient.invalidateQueries({ queryKey: ['cashflow-report'] });
@@ -69,6 +84,9 @@ export function useBondMutations() {
     mutationFn: (id: string) => bondsApi.delete(id),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['bonds'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-schedule'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-cash-flows'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-analytics'] });
       queryClient.invalidateQueries({ queryKey: ['portfolio-metrics'] });
       queryClient.invalidateQueries({ queryKey: ['projection'] });
       queryClient.invalidateQueries({ queryKey: ['cashflow-report'] });
@@ -79,9 +97,24 @@ export function useBondMutations() {
     },
   });
 
+  const refreshPricesMutation = useMutation({
+    mutationFn: () => bondsApi.refreshPrices(),
+    onSuccess: (count: number) => {
+      queryClient.invalidateQueries({ queryKey: ['bonds'] });
+      queryClient.invalidateQueries({ queryKey: ['bond-analytics'] });
+      queryClient.invalidateQueries({ queryKey: ['portfolio-metrics'] });
+      queryClient.invalidateQueries({ queryKey: ['projection'] });
+      toast(tb('toast.pricesRefreshed', { count }));
+    },
+    onError: (error: Error) => {
+      toast.error(t('status.error'), { description: translateApiError(error, t) });
+    },
+  });
+
   return {
     createMutation,
     updateMutation,
     deleteMutation,
+    refreshPricesMutation,
   };
 }
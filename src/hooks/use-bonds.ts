@@ -1,7 +1,7 @@
 import { useQuery } from "@tanstack/react-query";
 import { bondsApi } from "@/lib/tauri-api";
 import { convertToCzK, type CurrencyCode } from "@shared/currencies";
-import type { Bond } from "@shared/schema";
+import type { Bond, BondAnalytics } from "@shared/schema";
 
 export interface BondsMetrics {
   totalCouponValue: number;
@@ -12,34 +12,43 @@ export interface BondsMetrics {
   projectedYearlyIncome: number;
 }
 
+/** Yield to maturity where known, else the coupon rate */
+export function bondYield(bond: Bond, analytics?: BondAnalytics): number {
+  return analytics?.yieldToMaturity ?? parseFloat(bond.interestRate || "0");
+}
+
 export function useBonds() {
   const { data: bonds = [], isLoading } = useQuery({
     queryKey: ["bonds"],
     queryFn: () => bondsApi.getAll(),
   });
 
+  const { data: analyticsList = [] } = useQuery({
+    queryKey: ["bond-analytics"],
+    queryFn: () => bondsApi.getAnalytics(),
+  });
+  const analytics = new Map(analyticsList.map((a) => [a.bondId, a]));
+
+  // Market value (clean price plus accrued interest), par until analytics load
+  const valueCzk = (bond: Bond) => {
+    const marketValue = analytics.get(bond.id)?.marketValueCzk;
+    if (marketValue !== undefined) return marketValue;
+    const value = parseFloat(bond.couponValue || "0");
+    const quantity = parseFloat(bond.quantity || "1");
+    return convertToCzK(value * quantity, (bond.currency || "CZK") as CurrencyCode);
+  };
+
   // Calculate metrics in CZK
-  const totalValue = bonds.reduce(
-    (sum, bond: Bond) => {
-      const value = parseFloat(bond.couponValue || "0");
-      const quantity = parseFloat(bond.quantity || "1");
-      const currency = bond.currency || "CZK";
-      return sum + convertToCzK(value * quantity, currency as CurrencyCode);
-    },
-    0
-  );
+  const totalValue = bonds.reduce((sum, bond: Bond) => sum + valueCzk(bond), 0);
 
-  // Calculate weighted average yield (weighted by total value in CZK)
+  // Calculate weighted average yield to maturity (weighted by market value in CZK)
   const averageYield = (() => {
     if (bonds.length === 0) return 0;
 
     const { weightedSum, totalWeight } = bonds.reduce(
       (acc, bond: Bond) => {
-        const value = parseFloat(bond.couponValue || "0");
-        const quantity = parseFloat(bond.quantity || "1");
-        const currency = bond.currency || "CZK";
-        const totalValueCzk = convertToCzK(value * quantity, currency as CurrencyCode);
-        const rate = parseFloat(bond.interestRate || "0");
+        const totalValueCzk = valueCzk(bond);
+        const rate = bondYield(bond, analytics.get(bond.id));
 
         return {
           weightedSum: acc.weightedSum + (totalValueCzk * rate),
@@ -78,6 +87,7 @@ export function useBonds() {
 
   return {
     bonds,
+    analytics,
     isLoading,
     metrics,
   };
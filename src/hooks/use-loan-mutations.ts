@@ -13,6 +13,8 @@ export function useLoanMutations() {
         mutationFn: (data: InsertLoan) => loansApi.create(data),
         onSuccess: () => {
             queryClient.invalidateQueries({ queryKey: ["loans"] });
+            queryClient.invalidateQueries({ queryKey: ["loan-schedules"] });
+            queryClient.invalidateQueries({ queryKey: ["loan-schedule"] });
             queryClient.invalidateQueries({ queryKey: ["portfolio-metrics"] });
             toast(t('status.success'));
         },
@@ -27,6 +29,8 @@ export function useLoanMutations() {
         },
         onSuccess: () => {
             queryClient.invalidateQueries({ queryKey: ["loans"] });
+            queryClient.invalidateQueries({ queryKey: ["loan-schedules"] });
+            queryClient.invalidateQueries({ queryKey: ["loan-schedule"] });
             queryClient.invalidateQueries({ queryKey: ["portfolio-metrics"] });
             toast(t('status.success'));
         },
@@ -39,6 +43,8 @@ export function useLoanMutations() {
         mutationFn: (id: string) => loansApi.delete(id),
         onSuccess: () => {
             queryClient.invalidateQueries({ queryKey: ["loans"] });
+            queryClient.invalidateQueries({ queryKey: ["loan-schedules"] });
+            queryClient.invalidateQueries({ queryKey: ["loan-schedule"] });
             queryClient.invalidateQueries({ queryKey: ["portfolio-metrics"] });
             toast(t('status.success'));
         },
@@ -1,7 +1,13 @@
 import { useQuery } from "@tanstack/react-query";
 import { loansApi } from "@/lib/tauri-api";
 import { convertToCzK, type CurrencyCode } from "@shared/currencies";
-import type { Loan } from "@shared/schema";
+import type { Loan, LoanSchedule } from "@shared/schema";
+
+/** Outstanding principal in CZK from the amortization schedule, else the original principal */
+export function outstandingCzk(loan: Loan, schedule?: LoanSchedule): number {
+    if (schedule) return schedule.outstandingPrincipalCzk;
+    return convertToCzK(parseFloat(loan.principal || "0"), (loan.currency || "CZK") as CurrencyCode);
+}
 
 export function useLoans() {
     const { data: loans = [], isLoading } = useQuery({
@@ -9,13 +15,15 @@ export function useLoans() {
         queryFn: () => loansApi.getAll(),
     });
 
+    const { data: scheduleList = [] } = useQuery({
+        queryKey: ["loan-schedules"],
+        queryFn: () => loansApi.getSchedules(),
+    });
+    const schedules = new Map(scheduleList.map((schedule) => [schedule.loanId, schedule]));
+
     // Calculate metrics in CZK
     const totalPrincipal = loans.reduce(
-        (sum, loan: Loan) => {
-            const principal = parseFloat(loan.principal || "0");
-            const currency = loan.currency || "CZK";
-            return sum + convertToCzK(principal, currency as CurrencyCode);
-        },
+        (sum, loan: Loan) => sum + outstandingCzk(loan, schedules.get(loan.id)),
         0
     );
 
@@ -28,15 +36,13 @@ export function useLoans() {
         0
     );
 
-    // Calculate weighted average interest rate (weighted by principal in CZK)
+    // Calculate weighted average interest rate (weighted by outstanding principal in CZK)
     const averageInterestRate = (() => {
         if (loans.length === 0) return 0;
 
         const { weightedSum, totalWeight } = loans.reduce(
             (acc, loan: Loan) => {
-                const principal = parseFloat(loan.principal || "0");
-                const currency = loan.currency || "CZK";
-                const principalInCzk = convertToCzK(principal, currency as CurrencyCode);
+                const principalInCzk = outstandingCzk(loan, schedules.get(loan.id));
                 const rate = parseFloat(loan.interestRate || "0");
 
                 return {
@@ -52,6 +58,7 @@ export function useLoans() {
 
     return {
         loans,
+        schedules,
         isLoading,
         metrics: {
             totalPrincipal,
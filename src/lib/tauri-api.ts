@@ -11,12 +11,22 @@ import type {
   SavingsAccount,
   SavingsAccountZone,
   InsertSavingsAccount,
+  InterestAccrual,
+  InterestPayment,
   InvestmentTransaction,
   InsertInvestmentTransaction,
   CryptoTransaction,
+  CryptoWalletHolding,
+  CryptoIncomeCostBasis,
   Bond,
   InsertBond,
+  BondAnalytics,
+  BondCashFlow,
+  BondSchedule,
   Loan,
+  LoanSchedule,
+  LoanScenario,
+  LoanSimulation,
   InsertLoan,
   RealEstate,
   InsertRealEstate,
@@ -25,6 +35,10 @@ import type {
   RealEstatePhotoBatch,
   RealEstatePhoto,
   RealEstateDocument,
+  RealEstateValuation,
+  InsertRealEstateValuation,
+  RealEstateValueHistory,
+  RentalPerformance,
   InsurancePolicy,
   InsertInsurancePolicy,
   InsuranceDocument,
@@ -34,6 +48,10 @@ import type {
   InsertOtherAssetTransaction,
   PortfolioMetrics,
   PortfolioMetricsHistory,
+  ExposureDimension,
+  ExposureReport,
+  ExposureHistoryPoint,
+  UnderlyingCurrency,
   CashflowReport,
   CashflowItem,
   ProjectionSettings,
@@ -64,6 +82,50 @@ import type {
   StockInvestmentWithTags,
   TagMetrics,
   TwrSeries,
+  XirrScope,
+  XirrResult,
+  RiskScope,
+  RiskMetrics,
+  // Benchmark types
+  Benchmark,
+  InsertBenchmark,
+  BenchmarkComparison,
+  // Price provider types
+  PriceProviderId,
+  PriceAssetKind,
+  PriceProviderSettings,
+  PriceProviderPreference,
+  InsertPriceProviderPreference,
+  TickerCurrency,
+  // Allocation types
+  AllocationKind,
+  AllocationTarget,
+  InsertAllocationTarget,
+  AllocationReport,
+  RebalancePlan,
+  // Savings goal types
+  SavingsGoal,
+  InsertSavingsGoal,
+  SavingsGoalProgress,
+  // Dividend types
+  ReceivedDividend,
+  InsertReceivedDividend,
+  DividendTaxReport,
+  // Broker import types
+  Broker,
+  BrokerImportResult,
+  // Brokerage account types
+  BrokerageAccount,
+  InsertBrokerageAccount,
+  AccountPosition,
+  AccountValue,
+  // Crypto exchange import types
+  Exchange,
+  CryptoImportResult,
+  // Exchange rate types
+  FxSettings,
+  ManualExchangeRate,
+  InsertManualExchangeRate,
 } from '../../shared/schema';
 import type {
   StockInvestmentWithPrice,
@@ -78,7 +140,8 @@ interface ImportResult {
   errors: string[];
 }
 
-// Per-ticker value history record
+// Per-ticker value history record. valueCzk is stored in CZK; the history
+// commands return it in the reporting currency at the rate of recordedAt.
 export interface TickerValueHistory {
   ticker: string;
   recordedAt: number;
@@ -190,6 +253,13 @@ export const savingsApi = {
     tauriInvoke<SavingsAccountZone>('create_account_zone', { data }),
 
   deleteZone: (zoneId: string) => tauriInvoke<void>('delete_account_zone', { zoneId }),
+
+  /** Interest accrued today on savings accounts and term deposits */
+  getInterestAccruals: () => tauriInvoke<InterestAccrual[]>('get_interest_accruals'),
+
+  /** Interest credits and term deposit payouts, by default over the next year */
+  getInterestCalendar: (fromTs?: number, toTs?: number) =>
+    tauriInvoke<InterestPayment[]>('get_interest_calendar', { fromTs, toTs }),
 };
 
 // ============================================================================
@@ -240,9 +310,14 @@ export const investmentsApi = {
 
   importTransactions: (
     transactions: Record<string, string | number | boolean | null | undefined>[],
-    defaultCurrency: string
+    defaultCurrency: string,
+    accountId?: string
   ) =>
-    tauriInvoke<ImportResult>('import_investment_transactions', { transactions, defaultCurrency }),
+    tauriInvoke<ImportResult>('import_investment_transactions', {
+      transactions,
+      defaultCurrency,
+      accountId,
+    }),
 
   refreshMetadata: (ticker: string) => tauriInvoke<boolean>('refresh_stock_metadata', { ticker }),
 
@@ -260,6 +335,87 @@ export const investmentsApi = {
     toTs: number,
   ) =>
     tauriInvoke<TwrSeries[]>('get_stock_twr', { tagIds, includePortfolio, includeUntagged, fromTs, toTs }),
+
+  getXirr: (scope: XirrScope, fromTs?: number, toTs?: number) =>
+    tauriInvoke<XirrResult[]>('get_xirr', { scope, fromTs, toTs }),
+
+  getRiskMetrics: (
+    scope: RiskScope,
+    benchmark?: string,
+    riskFreeRate?: number,
+    fromTs?: number,
+    toTs?: number
+  ) =>
+    tauriInvoke<RiskMetrics[]>('get_risk_metrics', {
+      scope,
+      benchmark,
+      riskFreeRate,
+      fromTs,
+      toTs,
+    }),
+
+  getRiskFreeRate: () => tauriInvoke<number>('get_risk_free_rate'),
+
+  setRiskFreeRate: (rate: number) => tauriInvoke<void>('set_risk_free_rate', { rate }),
+};
+
+// ============================================================================
+// Dividends API
+// ============================================================================
+
+export const dividendsApi = {
+  getAll: (ticker?: string) => tauriInvoke<ReceivedDividend[]>('get_received_dividends', { ticker }),
+
+  create: (data: InsertReceivedDividend) =>
+    tauriInvoke<ReceivedDividend>('create_received_dividend', { data }),
+
+  delete: (id: string) => tauriInvoke<void>('delete_received_dividend', { id }),
+
+  getTaxReport: (year: number, annualRates?: Record<string, number>) =>
+    tauriInvoke<DividendTaxReport>('get_dividend_tax_report', { year, annualRates }),
+};
+
+// ============================================================================
+// Broker Import API
+// ============================================================================
+
+export const brokerImportApi = {
+  importStatement: (
+    filePath: string,
+    broker: Broker,
+    accountCurrency?: string,
+    accountId?: string
+  ) =>
+    tauriInvoke<BrokerImportResult>('import_broker_statement', {
+      filePath,
+      broker,
+      accountCurrency,
+      accountId,
+    }),
+};
+
+// ============================================================================
+// Brokerage Accounts API
+// ============================================================================
+
+export const brokerageAccountsApi = {
+  getAll: () => tauriInvoke<BrokerageAccount[]>('get_brokerage_accounts'),
+
+  create: (data: InsertBrokerageAccount) =>
+    tauriInvoke<BrokerageAccount>('create_brokerage_account', { data }),
+
+  update: (id: string, data: InsertBrokerageAccount) =>
+    tauriInvoke<BrokerageAccount>('update_brokerage_account', { id, data }),
+
+  delete: (id: string) => tauriInvoke<void>('delete_brokerage_account', { id }),
+
+  getPositions: (accountId?: string) =>
+    tauriInvoke<AccountPosition[]>('get_account_positions', { accountId }),
+
+  getValues: () => tauriInvoke<AccountValue[]>('get_account_values'),
+
+  setTransactionAccount: (txId: string, accountId: string | null) =>
+    tauriInvoke<void>('set_transaction_account', { txId, accountId }),
 };
 
 // ============================================================================
@@ -299,6 +455,23 @@ export const cryptoApi = {
 
   backfillHistory: (ticker: string) =>
     tauriInvoke<BackfillResult>('backfill_crypto_ticker_history', { ticker }),
+
+  getWalletHoldings: (ticker?: string) =>
+    tauriInvoke<CryptoWalletHolding[]>('get_crypto_wallet_holdings', { ticker }),
+
+  getIncomeCostBasis: () => tauriInvoke<CryptoIncomeCostBasis>('get_crypto_income_cost_basis'),
+
+  setIncomeCostBasis: (basis: CryptoIncomeCostBasis) =>
+    tauriInvoke<void>('set_crypto_income_cost_basis', { basis }),
+};
+
+// ============================================================================
+// Crypto Exchange Import API
+// ============================================================================
+
+export const cryptoExchangeImportApi = {
+  importExport: (filePath: string, exchange: Exchange) =>
+    tauriInvoke<CryptoImportResult>('import_crypto_exchange', { filePath, exchange }),
 };
 
 // ============================================================================
@@ -313,6 +486,17 @@ export const bondsApi = {
   update: (id: string, data: Partial<InsertBond>) => tauriInvoke<Bond>('update_bond', { id, data }),
 
   delete: (id: string) => tauriInvoke<void>('delete_bond', { id }),
+
+  getSchedule: (id: string) => tauriInvoke<BondSchedule>('get_bond_schedule', { id }),
+
+  /** Coupons and repayments of all bonds, by default over the next year */
+  getCashFlowCalendar: (fromTs?: number, toTs?: number) =>
+    tauriInvoke<BondCashFlow[]>('get_bond_cash_flow_calendar', { fromTs, toTs }),
+
+  getAnalytics: () => tauriInvoke<BondAnalytics[]>('get_bond_analytics'),
+
+  /** Fetch market prices for bonds with a price ticker; returns the number updated */
+  refreshPrices: () => tauriInvoke<number>('refresh_bond_prices'),
 };
 
 // ============================================================================
@@ -328,6 +512,13 @@ export const loansApi = {
 
   delete: (id: string) => tauriInvoke<void>('delete_loan', { id }),
 
+  getSchedule: (id: string) => tauriInvoke<LoanSchedule>('get_loan_schedule', { id }),
+
+  getSchedules: () => tauriInvoke<LoanSchedule[]>('get_loan_schedules'),
+
+  simulate: (id: string, scenarios: LoanScenario[]) =>
+    tauriInvoke<LoanSimulation>('simulate_loan_scenarios', { id, scenarios }),
+
   // Real estate linking
   getRealEstate: (loanId: string) =>
     tauriInvoke<RealEstate | null>('get_loan_real_estate', { loanId }),
@@ -381,6 +572,34 @@ export const realEstateApi = {
   unlinkInsurance: (realEstateId: string, insuranceId: string) =>
     tauriInvoke<void>('unlink_insurance_from_real_estate', { realEstateId, insuranceId }),
 
+  // Valuations
+  getValuations: (realEstateId: string) =>
+    tauriInvoke<RealEstateValuation[]>('get_real_estate_valuations', { realEstateId }),
+
+  addValuation: (data: InsertRealEstateValuation) =>
+    tauriInvoke<RealEstateValuation>('add_real_estate_valuation', { data }),
+
+  deleteValuation: (valuationId: string) =>
+    tauriInvoke<void>('delete_real_estate_valuation', { valuationId }),
+
+  getValueHistory: (realEstateId: string) =>
+    tauriInvoke<RealEstateValueHistory>('get_real_estate_value_history', { realEstateId }),
+
+  // Linked transactions and rental performance
+  getTransactions: (realEstateId: string) =>
+    tauriInvoke<BankTransaction[]>('get_real_estate_transactions', { realEstateId }),
+
+  linkTransactions: (realEstateId: string, transactionIds: string[]) =>
+    tauriInvoke<void>('link_transactions_to_real_estate', { realEstateId, transactionIds }),
+
+  unlinkTransaction: (realEstateId: string, transactionId: string) =>
+    tauriInvoke<void>('unlink_transaction_from_real_estate', { realEstateId, transactionId }),
+
+  getAllPerformance: () => tauriInvoke<RentalPerformance[]>('get_rental_performance'),
+
+  getPerformance: (realEstateId: string) =>
+    tauriInvoke<RentalPerformance>('get_real_estate_performance', { realEstateId }),
+
   // Photo batches
   getPhotoBatches: (realEstateId: string) =>
     tauriInvoke<RealEstatePhotoBatch[]>('get_real_estate_photo_batches', { realEstateId }),
@@ -510,12 +729,47 @@ export const portfolioApi = {
   getHistory: (startDate?: number, endDate?: number) =>
     tauriInvoke<PortfolioMetricsHistory[]>('get_portfolio_history', { startDate, endDate }),
 
+  getExposure: (
+    dimension: ExposureDimension,
+    stocksOnly: boolean = false,
+    excludePersonalRealEstate: boolean = false
+  ) =>
+    tauriInvoke<ExposureReport>('get_portfolio_exposure', {
+      dimension,
+      stocksOnly,
+      excludePersonalRealEstate,
+    }),
+
+  getExposureHistory: (dimension: ExposureDimension, fromTs?: number, toTs?: number) =>
+    tauriInvoke<ExposureHistoryPoint[]>('get_exposure_history', { dimension, fromTs, toTs }),
+
+  getUnderlyingCurrencies: () => tauriInvoke<UnderlyingCurrency[]>('get_underlying_currencies'),
+
+  setUnderlyingCurrency: (ticker: string, currency: string) =>
+    tauriInvoke<UnderlyingCurrency>('set_underlying_currency', { ticker, currency }),
+
+  deleteUnderlyingCurrency: (ticker: string) =>
+    tauriInvoke<void>('delete_underlying_currency', { ticker }),
+
   recordSnapshot: () => tauriInvoke<void>('record_portfolio_snapshot'),
 
   refreshExchangeRates: () => tauriInvoke<Record<string, number>>('refresh_exchange_rates'),
 
   getExchangeRates: () => tauriInvoke<Record<string, number>>('get_exchange_rates'),
 
+  getFxSettings: () => tauriInvoke<FxSettings>('get_fx_settings'),
+
+  setFxSettings: (settings: FxSettings) =>
+    tauriInvoke<FxSettings>('set_fx_settings', { settings }),
+
+  getManualExchangeRates: () => tauriInvoke<ManualExchangeRate[]>('get_manual_exchange_rates'),
+
+  setManualExchangeRate: (data: InsertManualExchangeRate) =>
+    tauriInvoke<ManualExchangeRate>('set_manual_exchange_rate', { data }),
+
+  deleteManualExchangeRate: (currency: string) =>
+    tauriInvoke<void>('delete_manual_exchange_rate', { currency }),
+
   getPriceStatus: () => tauriInvoke<PriceStatus>('get_price_status'),
 
   startBackfill: () => tauriInvoke<BackfillResult>('start_snapshot_backfill'),
@@ -525,32 +779,36 @@ export const portfolioApi = {
 };
 
 // ============================================================================
-// Price API (Yahoo Finance / Finnhub / Marketstack for stocks, CoinGecko for crypto)
+// Price API (provider chain: Yahoo, CoinGecko, Stooq, Alpha Vantage, local file)
 // ============================================================================
 
 export interface ApiKeys {
   marketstack?: string;
   finnhub?: string;
   coingecko?: string;
+  alpha_vantage?: string;
 }
 
 export interface StockPriceResult {
   ticker: string;
   price: number;
   currency: string;
+  provider: PriceProviderId;
 }
 
-// Result from Finnhub stock price refresh with rate limit info
+// Result from stock price refresh; failed tickers keep their previous price
 export interface StockPriceRefreshResult {
   updated: StockPriceResult[];
   remaining_tickers: string[];
   rate_limit_hit: boolean;
+  failed_tickers: string[];
 }
 
 export interface CryptoPriceResult {
   ticker: string;
   price: number;
   currency: string;
+  provider: PriceProviderId;
 }
 
 export interface CoinGeckoSearchResult {
@@ -588,6 +846,29 @@ export const priceApi = {
 
   searchStockTickers: (query: string) =>
     tauriInvoke<StockSearchResult[]>('search_stock_tickers', { query }),
+
+  getProviderSettings: () =>
+    tauriInvoke<PriceProviderSettings>('get_price_provider_settings'),
+
+  setProviderSettings: (settings: PriceProviderSettings) =>
+    tauriInvoke<PriceProviderSettings>('set_price_provider_settings', { settings }),
+
+  getProviderPreferences: () =>
+    tauriInvoke<PriceProviderPreference[]>('get_price_provider_preferences'),
+
+  setProviderPreference: (data: InsertPriceProviderPreference) =>
+    tauriInvoke<PriceProviderPreference>('set_price_provider_preference', { data }),
+
+  deleteProviderPreference: (ticker: string, assetKind: PriceAssetKind) =>
+    tauriInvoke<void>('delete_price_provider_preference', { ticker, assetKind }),
+
+  getTickerCurrencies: () => tauriInvoke<TickerCurrency[]>('get_ticker_currencies'),
+
+  setTickerCurrency: (ticker: string, currency: string) =>
+    tauriInvoke<TickerCurrency>('set_ticker_currency', { ticker, currency }),
+
+  deleteTickerCurrency: (ticker: string) =>
+    tauriInvoke<void>('delete_ticker_currency', { ticker }),
 };
 
 // ============================================================================
@@ -760,6 +1041,76 @@ export const stockTagsApi = {
   deleteGroup: (id: string) => tauriInvoke<void>('delete_stock_tag_group', { id }),
 };
 
+// ============================================================================
+// Benchmarks API
+// ============================================================================
+
+export const benchmarksApi = {
+  getAll: () => tauriInvoke<Benchmark[]>('get_benchmarks'),
+
+  add: (data: InsertBenchmark) => tauriInvoke<Benchmark>('add_benchmark', { data }),
+
+  delete: (ticker: string) => tauriInvoke<void>('delete_benchmark', { ticker }),
+
+  backfillHistory: (ticker: string, fromTs?: number) =>
+    tauriInvoke<number>('backfill_benchmark_history', { ticker, fromTs }),
+
+  compare: (benchmark: string, fromTs: number, toTs: number, tagId?: string) =>
+    tauriInvoke<BenchmarkComparison>('compare_with_benchmark', {
+      benchmark,
+      tagId,
+      fromTs,
+      toTs,
+    }),
+};
+
+// ============================================================================
+// Allocation API
+// ============================================================================
+
+export const allocationApi = {
+  getTargets: (kind?: AllocationKind) =>
+    tauriInvoke<AllocationTarget[]>('get_allocation_targets', { kind }),
+
+  setTarget: (data: InsertAllocationTarget) =>
+    tauriInvoke<AllocationTarget>('set_allocation_target', { data }),
+
+  deleteTarget: (id: string) => tauriInvoke<void>('delete_allocation_target', { id }),
+
+  getDrift: (kind: AllocationKind, excludePersonalRealEstate = false) =>
+    tauriInvoke<AllocationReport>('get_allocation_drift', { kind, excludePersonalRealEstate }),
+
+  getRebalancingPlan: (
+    kind: AllocationKind,
+    contribution?: number,
+    cashOnly = false,
+    excludePersonalRealEstate = false
+  ) =>
+    tauriInvoke<RebalancePlan>('get_rebalancing_plan', {
+      kind,
+      contribution,
+      cashOnly,
+      excludePersonalRealEstate,
+    }),
+};
+
+// ============================================================================
+// Savings Goals API
+// ============================================================================
+
+export const goalsApi = {
+  getAll: () => tauriInvoke<SavingsGoal[]>('get_savings_goals'),
+
+  create: (data: InsertSavingsGoal) => tauriInvoke<SavingsGoal>('create_savings_goal', { data }),
+
+  update: (id: string, data: InsertSavingsGoal) =>
+    tauriInvoke<SavingsGoal>('update_savings_goal', { id, data }),
+
+  delete: (id: string) => tauriInvoke<void>('delete_savings_goal', { id }),
+
+  getProgress: () => tauriInvoke<SavingsGoalProgress[]>('get_savings_goal_progress'),
+};
+
 // ============================================================================
 // Categorization API
 // ============================================================================
@@ -1055,7 +1406,11 @@ export const api = {
   auth: authApi,
   savings: savingsApi,
   investments: investmentsApi,
+  dividends: dividendsApi,
+  brokerImport: brokerImportApi,
+  brokerageAccounts: brokerageAccountsApi,
   crypto: cryptoApi,
+  cryptoExchangeImport: cryptoExchangeImportApi,
   bonds: bondsApi,
   loans: loansApi,
   realEstate: realEstateApi,
@@ -1068,6 +1423,8 @@ export const api = {
   export: exportApi,
   bankAccounts: bankAccountsApi,
   stockTags: stockTagsApi,
+  allocation: allocationApi,
+  benchmarks: benchmarksApi,
   categorization: categorizationApi,
   budgeting: budgetingApi,
 };
@@ -215,6 +215,22 @@ export default function BankAccountDetail() {
     enabled: !!accountId && !!account?.hasZoneDesignation,
   });
 
+  // Fetch accrued interest for savings accounts and term deposits
+  const { data: accrual } = useQuery({
+    queryKey: ['interest-accruals'],
+    queryFn: () => savingsApi.getInterestAccruals(),
+    enabled: account?.accountType === 'savings' || account?.accountType === 'term_deposit',
+    select: (accruals) => accruals.find((a) => a.accountId === accountId),
+  });
+
+  // Fetch interest credits and maturity payouts of the next year
+  const { data: interestCalendar = [] } = useQuery({
+    queryKey: ['interest-calendar'],
+    queryFn: () => savingsApi.getInterestCalendar(),
+    enabled: account?.accountType === 'savings' || account?.accountType === 'term_deposit',
+    select: (payments) => payments.filter((p) => p.accountId === accountId),
+  });
+
   // Fetch import batches
   const { data: importBatches } = useQuery({
     queryKey: ['import-batches', accountId],
@@ -710,10 +726,66 @@ export default function BankAccountDetail() {
                 ? t('detail.perYear', 'per year')
                 : t('detail.noInterest', 'No interest')}
             </div>
+            {accrual && parseFloat(accrual.accruedNet) > 0 && (
+              <div className="text-sm text-muted-foreground">
+                {t('detail.accrued', 'Accrued (net)')}:{' '}
+                {formatCurrency(convertToCzK(parseFloat(accrual.accruedNet), currency as CurrencyCode))}
+              </div>
+            )}
+            {accrual?.maturityDate && (
+              <div className="text-sm text-muted-foreground">
+                {t('detail.maturesOn', 'Matures on')} {formatDate(accrual.maturityDate)}
+                {accrual.maturityPayout &&
+                  ` · ${formatCurrency(convertToCzK(parseFloat(accrual.maturityPayout), currency as CurrencyCode))}`}
+              </div>
+            )}
           </CardContent>
         </Card>
       </div>
 
+      {/* Interest Calendar */}
+      {interestCalendar.length > 0 && (
+        <Card className="card-hover">
+          <CardHeader>
+            <CardTitle>{t('detail.interestCalendar', 'Upcoming Interest')}</CardTitle>
+          </CardHeader>
+          <CardContent>
+            <Table>
+              <TableHeader>
+                <TableRow>
+                  <TableHead>{tCommon('labels.date', 'Date')}</TableHead>
+                  <TableHead>{tCommon('labels.type', 'Type')}</TableHead>
+                  <TableHead className="text-right">{t('detail.grossInterest', 'Gross interest')}</TableHead>
+                  <TableHead className="text-right">{t('detail.tax', 'Tax')}</TableHead>
+                  <TableHead className="text-right">{tCommon('labels.amount', 'Amount')}</TableHead>
+                </TableRow>
+              </TableHeader>
+              <TableBody>
+                {interestCalendar.map((payment) => (
+                  <TableRow key={`${payment.date}-${payment.kind}`}>
+                    <TableCell>{formatDate(payment.date)}</TableCell>
+                    <TableCell>
+                      <Badge variant={payment.kind === 'maturity' ? 'default' : 'outline'}>
+                        {t(`detail.paymentKinds.${payment.kind}`)}
+                      </Badge>
+                    </TableCell>
+                    <TableCell className="text-right">
+                      {formatCurrency(convertToCzK(parseFloat(payment.grossInterest), payment.currency as CurrencyCode))}
+                    </TableCell>
+                    <TableCell className="text-right text-muted-foreground">
+                      -{formatCurrency(convertToCzK(parseFloat(payment.tax), payment.currency as CurrencyCode))}
+                    </TableCell>
+                    <TableCell className="text-right font-medium text-positive">
+                      {formatCurrency(convertToCzK(parseFloat(payment.amount), payment.currency as CurrencyCode))}
+                    </TableCell>
+                  </TableRow>
+                ))}
+              </TableBody>
+            </Table>
+          </CardContent>
+        </Card>
+      )}
+
       {/* Import History */}
       {importHistoryOpen && (
         <Card className="card-hover">
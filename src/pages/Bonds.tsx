@@ -1,11 +1,13 @@
 import { useState } from "react";
 import { Button } from "@/components/ui/button";
-import { Plus, FileText } from "lucide-react";
+import { Plus, FileText, RefreshCw } from "lucide-react";
 import { useBonds } from "@/hooks/use-bonds";
 import { BondsSummary } from "@/components/bonds/BondsSummary";
 import { BondsTable } from "@/components/bonds/BondsTable";
 import { BondsFormDialog } from "@/components/bonds/BondsFormDialog";
 import { DeleteBondDialog } from "@/components/bonds/DeleteBondDialog";
+import { BondScheduleDialog } from "@/components/bonds/BondScheduleDialog";
+import { BondCashFlowCalendar } from "@/components/bonds/BondCashFlowCalendar";
 import { useBondMutations } from "@/hooks/use-bond-mutations";
 import type { Bond, InsertBond } from "@shared/schema";
 import { useTranslation } from "react-i18next";
@@ -15,12 +17,14 @@ import { exportApi } from "@/lib/tauri-api";
 
 export default function Bonds() {
   const { t } = useTranslation('bonds');
-  const { bonds, metrics, isLoading } = useBonds();
-  const { createMutation, updateMutation, deleteMutation } = useBondMutations();
+  const { bonds, metrics, analytics, isLoading } = useBonds();
+  const { createMutation, updateMutation, deleteMutation, refreshPricesMutation } = useBondMutations();
+  const hasPriceTickers = bonds.some((bond) => !!bond.priceTicker);
 
   const [addDialogOpen, setAddDialogOpen] = useState(false);
   const [editDialogOpen, setEditDialogOpen] = useState(false);
   const [deleteDialogOpen, setDeleteDialogOpen] = useState(false);
+  const [scheduleDialogOpen, setScheduleDialogOpen] = useState(false);
   const [selectedBond, setSelectedBond] = useState<Bond | null>(null);
 
   const handleAddClick = () => setAddDialogOpen(true);
@@ -40,6 +44,11 @@ export default function Bonds() {
     updateMutation.mutate(data, { onSuccess: () => { setEditDialogOpen(false); setSelectedBond(null); } });
   };
 
+  const handleScheduleClick = (bond: Bond) => {
+    setSelectedBond(bond);
+    setScheduleDialogOpen(true);
+  };
+
   const handleDeleteClick = (bond: Bond) => {
     setSelectedBond(bond);
     setDeleteDialogOpen(true);
@@ -69,6 +78,17 @@ export default function Bonds() {
           <p className="page-subtitle">{t('subtitle')}</p>
         </div>
         <div className="flex gap-2">
+          {hasPriceTickers && (
+            <Button
+              variant="outline"
+              size="icon"
+              onClick={() => refreshPricesMutation.mutate()}
+              disabled={refreshPricesMutation.isPending}
+              title={t('refreshPrices')}
+            >
+              <RefreshCw className={`h-4 w-4 ${refreshPricesMutation.isPending ? 'animate-spin' : ''}`} />
+            </Button>
+          )}
           <ExportButton exportFn={exportApi.bonds} />
           <Button onClick={handleAddClick} className="transition-all duration-200">
             <Plus className="mr-2 h-4 w-4" />
@@ -92,13 +112,17 @@ export default function Bonds() {
           }
         />
       ) : (
-        <BondsTable bonds={bonds} onEdit={handleEditClick} onDelete={handleDeleteClick} />
+        <BondsTable bonds={bonds} analytics={analytics} onEdit={handleEditClick} onDelete={handleDeleteClick} onShowSchedule={handleScheduleClick} />
       )}
 
+      <BondCashFlowCalendar />
+
       <BondsFormDialog open={addDialogOpen} onOpenChange={setAddDialogOpen} onSubmit={handleAddSubmit} isLoading={createMutation.isPending} />
 
       <BondsFormDialog open={editDialogOpen} onOpenChange={setEditDialogOpen} onSubmit={handleEditSubmit} bond={selectedBond} isLoading={updateMutation.isPending} />
 
+      <BondScheduleDialog open={scheduleDialogOpen} onOpenChange={setScheduleDialogOpen} bond={selectedBond} />
+
       <DeleteBondDialog open={deleteDialogOpen} onOpenChange={setDeleteDialogOpen} bond={selectedBond} onConfirm={handleDeleteConfirm} isLoading={deleteMutation.isPending} />
     </div>
   );
@@ -347,6 +347,14 @@ export default function Cashflow() {
                                     ))}
                                 </div>
                             )}
+
+                            {/* Maturing term deposits return savings, so they stay out of the totals */}
+                            {!isLoading && report && report.termDepositMaturities.items.length > 0 && (
+                                <div className="space-y-2">
+                                    <p className="text-xs text-muted-foreground">{t('sections.maturitiesNote')}</p>
+                                    <CashflowCategory category={report.termDepositMaturities} />
+                                </div>
+                            )}
                         </div>
 
                         {/* Expenses Column */}
@@ -11,7 +11,7 @@ import { TrendingUp, Wallet, CreditCard, Landmark, Bitcoin, FileText, Home, Gem
 import { useQuery } from "@tanstack/react-query";
 import { portfolioApi } from "@/lib/tauri-api";
 import type { PortfolioMetricsHistory } from "@shared/schema";
-import { useState, useMemo } from "react";
+import { useState, useMemo, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { useLanguage } from "@/i18n/I18nProvider";
 import { useCurrency } from "@/lib/currency";
@@ -20,7 +20,7 @@ export default function Dashboard() {
   const { t } = useTranslation('dashboard');
   const { user } = useAuth();
   const { formatDate } = useLanguage();
-  const { ratesTimestamp } = useCurrency();
+  const { ratesTimestamp, convert, currencyCode } = useCurrency();
   const [selectedPeriod, setSelectedPeriod] = useState<Period>('30D');
 
   // Calculate date range based on selected period
@@ -46,7 +46,7 @@ export default function Dashboard() {
 
   // Fetch portfolio history using Tauri API
   const { data: portfolioHistory } = useQuery<PortfolioMetricsHistory[]>({
-    queryKey: ["portfolio-history", dateRange.start?.toISOString(), dateRange.end.toISOString()],
+    queryKey: ["portfolio-history", dateRange.start?.toISOString(), dateRange.end.toISOString(), currencyCode],
     queryFn: async () => {
       const startDate = dateRange.start ? Math.floor(dateRange.start.getTime() / 1000) : undefined;
       const endDate = Math.floor(dateRange.end.getTime() / 1000);
@@ -70,6 +70,13 @@ export default function Dashboard() {
   });
 
 
+  // History comes in the display currency at each day's rate. Express it in CZK at the
+  // current rate so it formats (and compares) like the live metrics.
+  const historyValue = useCallback(
+    (value: string | number | null | undefined) => convert(Number(value ?? 0), currencyCode, "CZK"),
+    [convert, currencyCode],
+  );
+
   // Use metrics from API (with fallbacks for loading state)
   const totalSavings = portfolioMetrics?.totalSavings || 0;
   const totalInvestments = portfolioMetrics?.totalInvestments || 0;
@@ -89,44 +96,44 @@ export default function Dashboard() {
     if (!oldestSnapshot) return 0;
 
     // Calculate oldest snapshot's net worth
-    const oldAssets = Number(oldestSnapshot.totalSavings) + Number(oldestSnapshot.totalInvestments) +
-      Number(oldestSnapshot.totalBonds) +
-      (user?.excludePersonalRealEstate ? 0 : Number(oldestSnapshot.totalRealEstatePersonal)) +
-      Number(oldestSnapshot.totalRealEstateInvestment) +
-      Number(oldestSnapshot.totalCrypto || 0) +
-      Number(oldestSnapshot.totalOtherAssets || 0);
-    const oldLiabilities = Number(oldestSnapshot.totalLoansPrincipal);
+    const oldAssets = historyValue(oldestSnapshot.totalSavings) + historyValue(oldestSnapshot.totalInvestments) +
+      historyValue(oldestSnapshot.totalBonds) +
+      (user?.excludePersonalRealEstate ? 0 : historyValue(oldestSnapshot.totalRealEstatePersonal)) +
+      historyValue(oldestSnapshot.totalRealEstateInvestment) +
+      historyValue(oldestSnapshot.totalCrypto || 0) +
+      historyValue(oldestSnapshot.totalOtherAssets || 0);
+    const oldLiabilities = historyValue(oldestSnapshot.totalLoansPrincipal);
     const oldNetWorth = oldAssets - oldLiabilities;
 
     // Compare with current net worth
     if (oldNetWorth === 0) return 0;
     const change = ((netWorth - oldNetWorth) / Math.abs(oldNetWorth)) * 100;
     return change;
-  }, [oldestSnapshot, netWorth, user?.excludePersonalRealEstate]);
+  }, [oldestSnapshot, netWorth, user?.excludePersonalRealEstate, historyValue]);
 
   const assetsChange = useMemo(() => {
     if (!oldestSnapshot) return 0;
 
-    const oldAssets = Number(oldestSnapshot.totalSavings) + Number(oldestSnapshot.totalInvestments) +
-      Number(oldestSnapshot.totalBonds) +
-      (user?.excludePersonalRealEstate ? 0 : Number(oldestSnapshot.totalRealEstatePersonal)) +
-      Number(oldestSnapshot.totalRealEstateInvestment) +
-      Number(oldestSnapshot.totalCrypto || 0) +
-      Number(oldestSnapshot.totalOtherAssets || 0);
+    const oldAssets = historyValue(oldestSnapshot.totalSavings) + historyValue(oldestSnapshot.totalInvestments) +
+      historyValue(oldestSnapshot.totalBonds) +
+      (user?.excludePersonalRealEstate ? 0 : historyValue(oldestSnapshot.totalRealEstatePersonal)) +
+      historyValue(oldestSnapshot.totalRealEstateInvestment) +
+      historyValue(oldestSnapshot.totalCrypto || 0) +
+      historyValue(oldestSnapshot.totalOtherAssets || 0);
 
     if (oldAssets === 0) return 0;
     const change = ((totalAssets - oldAssets) / Math.abs(oldAssets)) * 100;
     return change;
-  }, [oldestSnapshot, totalAssets, user?.excludePersonalRealEstate]);
+  }, [oldestSnapshot, totalAssets, user?.excludePersonalRealEstate, historyValue]);
 
   const liabilitiesChange = useMemo(() => {
     if (!oldestSnapshot) return 0;
 
-    const oldLiabilities = Number(oldestSnapshot.totalLoansPrincipal);
+    const oldLiabilities = historyValue(oldestSnapshot.totalLoansPrincipal);
     if (oldLiabilities === 0) return 0;
     const change = ((totalLiabilities - oldLiabilities) / Math.abs(oldLiabilities)) * 100;
     return change;
-  }, [oldestSnapshot, totalLiabilities]);
+  }, [oldestSnapshot, totalLiabilities, historyValue]);
 
   // Using shadcn chart tokens for consistent theming
   const allocationColors = {
@@ -170,12 +177,12 @@ export default function Dashboard() {
             : { month: 'short' as const, day: 'numeric' as const };
           
           const historyData = [...(portfolioHistory || [])].reverse().map(h => {
-            const assets = Number(h.totalSavings) + Number(h.totalInvestments) + Number(h.totalBonds) +
-              (user?.excludePersonalRealEstate ? 0 : Number(h.totalRealEstatePersonal)) +
-              Number(h.totalRealEstateInvestment) +
-              Number(h.totalCrypto || 0) +
-              Number(h.totalOtherAssets || 0);
-            const liabilities = Number(h.totalLoansPrincipal);
+            const assets = historyValue(h.totalSavings) + historyValue(h.totalInvestments) + historyValue(h.totalBonds) +
+              (user?.excludePersonalRealEstate ? 0 : historyValue(h.totalRealEstatePersonal)) +
+              historyValue(h.totalRealEstateInvestment) +
+              historyValue(h.totalCrypto || 0) +
+              historyValue(h.totalOtherAssets || 0);
+            const liabilities = historyValue(h.totalLoansPrincipal);
             return {
               date: formatDate(new Date(h.recordedAt * 1000), dateOptions),
               value: assets - liabilities
@@ -224,12 +231,12 @@ export default function Dashboard() {
                 : { month: 'short' as const, day: 'numeric' as const };
               
               const chartData = [...(portfolioHistory || [])].reverse().map(h => {
-                const assets = Number(h.totalSavings) + Number(h.totalInvestments) + Number(h.totalBonds) +
-                  (user?.excludePersonalRealEstate ? 0 : Number(h.totalRealEstatePersonal)) +
-                  Number(h.totalRealEstateInvestment) +
-                  Number(h.totalCrypto || 0) +
-                  Number(h.totalOtherAssets || 0);
-                const liabilities = Number(h.totalLoansPrincipal);
+                const assets = historyValue(h.totalSavings) + historyValue(h.totalInvestments) + historyValue(h.totalBonds) +
+                  (user?.excludePersonalRealEstate ? 0 : historyValue(h.totalRealEstatePersonal)) +
+                  historyValue(h.totalRealEstateInvestment) +
+                  historyValue(h.totalCrypto || 0) +
+                  historyValue(h.totalOtherAssets || 0);
+                const liabilities = historyValue(h.totalLoansPrincipal);
                 return {
                   date: formatDate(new Date(h.recordedAt * 1000), dateOptions),
                   assets,
@@ -313,13 +320,13 @@ export default function Dashboard() {
 
           const chartData = [...(portfolioHistory || [])].reverse().map(h => ({
             date: formatDate(new Date(h.recordedAt * 1000), dateOptions),
-            investments: Number(h.totalInvestments),
-            savings: Number(h.totalSavings),
-            bonds: Number(h.totalBonds),
-            realEstate: (user?.excludePersonalRealEstate ? 0 : Number(h.totalRealEstatePersonal)) +
-              Number(h.totalRealEstateInvestment),
-            crypto: Number(h.totalCrypto || 0),
-            otherAssets: Number(h.totalOtherAssets || 0),
+            investments: historyValue(h.totalInvestments),
+            savings: historyValue(h.totalSavings),
+            bonds: historyValue(h.totalBonds),
+            realEstate: (user?.excludePersonalRealEstate ? 0 : historyValue(h.totalRealEstatePersonal)) +
+              historyValue(h.totalRealEstateInvestment),
+            crypto: historyValue(h.totalCrypto || 0),
+            otherAssets: historyValue(h.totalOtherAssets || 0),
           }));
 
           // Append or update with current live values
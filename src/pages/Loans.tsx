@@ -9,6 +9,8 @@ import { LoanFormDialog } from "@/components/loans/LoanFormDialog";
 import { DeleteLoanDialog } from "@/components/loans/DeleteLoanDialog";
 import { LoansSummary } from "@/components/loans/LoansSummary";
 import { LoansTable } from "@/components/loans/LoansTable";
+import { LoanScheduleDialog } from "@/components/loans/LoanScheduleDialog";
+import { LoanScenarioDialog } from "@/components/loans/LoanScenarioDialog";
 import { EmptyState } from "@/components/common/EmptyState";
 import type { Loan, InsertLoan } from "@shared/schema";
 import { useTranslation } from "react-i18next";
@@ -17,13 +19,15 @@ import { exportApi } from "@/lib/tauri-api";
 
 export default function Loans() {
   const { t } = useTranslation('loans');
-  const { loans, metrics, isLoading } = useLoans();
+  const { loans, schedules, metrics, isLoading } = useLoans();
   const { createMutation, updateMutation, deleteMutation } = useLoanMutations();
   const queryClient = useQueryClient();
 
   const [addDialogOpen, setAddDialogOpen] = useState(false);
   const [editDialogOpen, setEditDialogOpen] = useState(false);
   const [deleteDialogOpen, setDeleteDialogOpen] = useState(false);
+  const [scheduleDialogOpen, setScheduleDialogOpen] = useState(false);
+  const [scenarioDialogOpen, setScenarioDialogOpen] = useState(false);
   const [selectedLoan, setSelectedLoan] = useState<Loan | null>(null);
 
   const handleAddClick = () => {
@@ -62,6 +66,16 @@ export default function Loans() {
     });
   };
 
+  const handleScheduleClick = (loan: Loan) => {
+    setSelectedLoan(loan);
+    setScheduleDialogOpen(true);
+  };
+
+  const handleSimulateClick = (loan: Loan) => {
+    setSelectedLoan(loan);
+    setScenarioDialogOpen(true);
+  };
+
   const handleDeleteClick = (loan: Loan) => {
     setSelectedLoan(loan);
     setDeleteDialogOpen(true);
@@ -121,8 +135,11 @@ export default function Loans() {
       ) : (
         <LoansTable
           loans={loans}
+          schedules={schedules}
           onEdit={handleEditClick}
           onDelete={handleDeleteClick}
+          onShowSchedule={handleScheduleClick}
+          onSimulate={handleSimulateClick}
         />
       )}
 
@@ -141,6 +158,18 @@ export default function Loans() {
         isLoading={updateMutation.isPending}
       />
 
+      <LoanScheduleDialog
+        open={scheduleDialogOpen}
+        onOpenChange={setScheduleDialogOpen}
+        loan={selectedLoan}
+      />
+
+      <LoanScenarioDialog
+        open={scenarioDialogOpen}
+        onOpenChange={setScenarioDialogOpen}
+        loan={selectedLoan}
+      />
+
       <DeleteLoanDialog
         open={deleteDialogOpen}
         onOpenChange={setDeleteDialogOpen}
@@ -30,6 +30,8 @@ import { AddRealEstateModal } from "@/components/real-estate/AddRealEstateModal"
 import { OneTimeCostModal } from "@/components/real-estate/OneTimeCostModal";
 import { PhotoTimelineGallery } from "@/components/real-estate/PhotoTimelineGallery";
 import { RealEstateDocuments } from "@/components/real-estate/RealEstateDocuments";
+import { RealEstateValuations } from "@/components/real-estate/RealEstateValuations";
+import { RealEstatePerformance } from "@/components/real-estate/RealEstatePerformance";
 import type { RealEstate, RealEstateOneTimeCost, Loan, InsurancePolicy } from "@shared/schema";
 import { toast } from "sonner";
 import { realEstateApi } from "@/lib/tauri-api";
@@ -282,6 +284,10 @@ export default function RealEstateDetail() {
             <Tabs defaultValue="financials" className="space-y-4">
                 <TabsList>
                     <TabsTrigger value="financials">{t('detail.financials')}</TabsTrigger>
+                    <TabsTrigger value="valuations">{t('detail.valuations')}</TabsTrigger>
+                    {realEstate.type !== 'personal' && (
+                        <TabsTrigger value="performance">{t('detail.performance')}</TabsTrigger>
+                    )}
                     <TabsTrigger value="costs">{t('detail.recurringCosts')}</TabsTrigger>
                     <TabsTrigger value="history">{t('detail.history')}</TabsTrigger>
                     <TabsTrigger value="gallery">{t('detail.gallery')}</TabsTrigger>
@@ -381,6 +387,16 @@ export default function RealEstateDetail() {
                     </div>
                 </TabsContent>
 
+                <TabsContent value="valuations">
+                    <RealEstateValuations realEstateId={id!} currency={marketCurrency} />
+                </TabsContent>
+
+                {realEstate.type !== 'personal' && (
+                    <TabsContent value="performance">
+                        <RealEstatePerformance realEstateId={id!} />
+                    </TabsContent>
+                )}
+
                 <TabsContent value="costs" className="space-y-4">
                     <Card className="card-hover">
                         <CardHeader>